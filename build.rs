@@ -0,0 +1,51 @@
+//! Extracts the `///` docs of `CommitConfig`'s fields from `src/config.rs`, so
+//! `--init-config` can comment each key with the same text as the struct.
+
+use std::{env, fmt::Write as _, fs, path::Path};
+
+fn main() {
+   println!("cargo:rerun-if-changed=src/config.rs");
+
+   let source = fs::read_to_string("src/config.rs").expect("read src/config.rs");
+   let docs = commit_config_field_docs(&source);
+
+   let mut out = String::from("/// `(field, doc)` for each documented `CommitConfig` field\n");
+   out.push_str("pub const CONFIG_FIELD_DOCS: &[(&str, &str)] = &[\n");
+   for (field, doc) in docs {
+      writeln!(out, "   ({field:?}, {doc:?}),").unwrap();
+   }
+   out.push_str("];\n");
+
+   let dest = Path::new(&env::var("OUT_DIR").unwrap()).join("config_docs.rs");
+   fs::write(dest, out).expect("write config_docs.rs");
+}
+
+/// Field name and joined doc lines, in declaration order
+fn commit_config_field_docs(source: &str) -> Vec<(String, String)> {
+   let lines = source
+      .lines()
+      .skip_while(|line| !line.starts_with("pub struct CommitConfig"))
+      .skip(1)
+      .take_while(|line| !line.starts_with('}'));
+
+   let mut fields = Vec::new();
+   let mut doc: Vec<String> = Vec::new();
+   for line in lines {
+      let line = line.trim();
+      if let Some(text) = line.strip_prefix("///") {
+         doc.push(text.trim().replace("[`", "`").replace("`]", "`"));
+      } else if line.starts_with("#[") {
+         // Attributes sit between a field's docs and the field itself
+      } else if let Some(rest) = line.strip_prefix("pub ")
+         && let Some((name, _)) = rest.split_once(':')
+      {
+         if !doc.is_empty() {
+            fields.push((name.trim().to_string(), doc.join("\n")));
+         }
+         doc.clear();
+      } else {
+         doc.clear();
+      }
+   }
+   fields
+}
@@ -4,10 +4,11 @@ use std::{
 };
 
 /// Scope analysis functionality for git diff numstat parsing
-use crate::config::CommitConfig;
+use crate::config::{CommitConfig, ScopeDepthPreference};
 use crate::{
+   diff::parse_diff,
    error::{CommitGenError, Result},
-   types::{Mode, ScopeCandidate},
+   types::{ChangelogCategory, CommitType, ConventionalAnalysis, Mode, ScopeCandidate},
 };
 
 /// Placeholder dirs to skip when building two-segment scopes
@@ -26,9 +27,23 @@ const PLACEHOLDER_DIRS: &[&str] = &[
 const SKIP_DIRS: &[&str] =
    &["test", "tests", "benches", "examples", "target", "build", "node_modules", ".github"];
 
+/// Divisor applied to test file line counts when
+/// `deprioritize_test_files_for_type` is enabled
+const TEST_FILE_WEIGHT_DIVISOR: usize = 4;
+
+/// Fewest deleted lines for a change to count as a removal; small cleanups
+/// are left to the model
+const DELETION_HEAVY_MIN_LINES: usize = 50;
+
 pub struct ScopeAnalyzer {
    component_lines: HashMap<String, usize>,
+   /// Components rooted at a `force_scope_dirs` entry, exempt from the
+   /// placeholder filter
+   forced:          HashSet<String>,
    total_lines:     usize,
+   /// Raw added/deleted lines of non-excluded files (no test down-weighting)
+   added:           usize,
+   deleted:         usize,
 }
 
 impl Default for ScopeAnalyzer {
@@ -39,7 +54,13 @@ impl Default for ScopeAnalyzer {
 
 impl ScopeAnalyzer {
    pub fn new() -> Self {
-      Self { component_lines: HashMap::new(), total_lines: 0 }
+      Self {
+         component_lines: HashMap::new(),
+         forced:          HashSet::new(),
+         total_lines:     0,
+         added:           0,
+         deleted:         0,
+      }
    }
 
    /// Process single numstat line: "added\tdeleted\tpath"
@@ -52,33 +73,55 @@ impl ScopeAnalyzer {
       let (added_str, deleted_str, path_part) = (parts[0], parts[1], parts[2]);
 
       // Parse line counts (skip binary files marked with "-")
-      let added = added_str.parse::<usize>().unwrap_or(0);
-      let deleted = deleted_str.parse::<usize>().unwrap_or(0);
-      let lines_changed = added + deleted;
-
-      if lines_changed == 0 {
+      let (Ok(added), Ok(deleted)) = (added_str.parse::<usize>(), deleted_str.parse::<usize>())
+      else {
          return;
-      }
+      };
+      // Mode-only changes and pure renames have no changed lines; count them
+      // as one so they still register
+      let lines_changed = (added + deleted).max(1);
 
       // Extract actual path from rename syntax
       let path = Self::extract_path_from_rename(path_part);
 
       // Skip excluded files
-      if config.excluded_files.iter().any(|ex| path.ends_with(ex)) {
+      if config.is_excluded_file(&path) {
          return;
       }
 
+      self.added += added;
+      self.deleted += deleted;
+
+      // Down-weight test files so accompanying feature code dominates
+      let lines_changed = if config.deprioritize_test_files_for_type && Self::is_test_path(&path) {
+         (lines_changed / TEST_FILE_WEIGHT_DIVISOR).max(1)
+      } else {
+         lines_changed
+      };
+
       self.total_lines += lines_changed;
 
       // Extract component candidates from path
-      let component_candidates = Self::extract_components_from_path(&path);
+      let component_candidates =
+         Self::extract_components_from_path(&path, &config.force_scope_dirs);
 
       for comp in component_candidates {
-         // Final sanity check: no segments should contain dots
-         if comp.split('/').any(|s| s.contains('.')) {
+         // Final sanity check: no segments should contain dots (forced
+         // directories like `.github` excepted)
+         if comp
+            .split('/')
+            .any(|s| s.contains('.') && !config.force_scope_dirs.iter().any(|dir| dir == s))
+         {
             continue;
          }
 
+         if comp
+            .split('/')
+            .next()
+            .is_some_and(|root| config.force_scope_dirs.iter().any(|dir| dir == root))
+         {
+            self.forced.insert(comp.clone());
+         }
          *self.component_lines.entry(comp).or_insert(0) += lines_changed;
       }
    }
@@ -110,7 +153,10 @@ impl ScopeAnalyzer {
    }
 
    /// Extract meaningful component paths from file path
-   fn extract_components_from_path(path: &str) -> Vec<String> {
+   ///
+   /// A directory in `force_scope_dirs` is never skipped, and leads the
+   /// components wherever it sits in the path.
+   fn extract_components_from_path(path: &str, force_scope_dirs: &[String]) -> Vec<String> {
       let segments: Vec<&str> = path.split('/').collect();
       let mut component_candidates = Vec::new();
       let mut meaningful_segments = Vec::new();
@@ -131,6 +177,12 @@ impl ScopeAnalyzer {
 
       // Build candidates by walking path and extracting meaningful directory segments
       for (seg_idx, seg) in segments.iter().enumerate() {
+         if segments.len() > seg_idx + 1 && force_scope_dirs.iter().any(|dir| dir == seg) {
+            meaningful_segments.clear();
+            // `.github` is scoped as `github`, which `Scope` accepts
+            meaningful_segments.push(seg.trim_start_matches('.').to_string());
+            continue;
+         }
          // Skip placeholder dirs when any deeper segments exist
          if PLACEHOLDER_DIRS.contains(seg) {
             // If this is a placeholder and we have more segments after it, skip it
@@ -168,11 +220,31 @@ impl ScopeAnalyzer {
    }
 
    /// Build sorted `ScopeCandidate` list from accumulated data
-   pub fn build_scope_candidates(&self) -> Vec<ScopeCandidate> {
+   ///
+   /// With [`ScopeDepthPreference::Deep`] a two-segment candidate is judged by
+   /// its share of its parent rather than of the whole change, and a parent
+   /// whose lines mostly sit in one child yields to it.
+   pub fn build_scope_candidates(&self, depth: ScopeDepthPreference) -> Vec<ScopeCandidate> {
+      // Share (0-100) of each parent's lines held by its dominant child
+      let dominant_child_share = |parent: &str| -> f32 {
+         let Some(&parent_lines) = self.component_lines.get(parent) else {
+            return 0.0;
+         };
+         self
+            .component_lines
+            .iter()
+            .filter(|(path, _)| path.split_once('/').is_some_and(|(root, _)| root == parent))
+            .map(|(_, &lines)| lines as f32 / parent_lines as f32 * 100.0)
+            .fold(0.0, f32::max)
+      };
+
       let mut candidates: Vec<ScopeCandidate> = self
          .component_lines
          .iter()
          .filter(|(path, _)| {
+            if self.forced.contains(path.as_str()) {
+               return true;
+            }
             // Filter out pure placeholder single-segment scopes
             if !path.contains('/') && PLACEHOLDER_DIRS.contains(&path.as_str()) {
                return false;
@@ -187,29 +259,69 @@ impl ScopeAnalyzer {
          })
          .map(|(path, &lines)| {
             let percentage = (lines as f32 / self.total_lines as f32) * 100.0;
-            let is_two_segment = path.contains('/');
 
             // Confidence calculation:
-            // - Single-segment: percentage as-is
-            // - Two-segment: percentage * 1.2 if >60%, else * 0.8
-            let confidence = if is_two_segment {
-               if percentage > 60.0 {
-                  percentage * 1.2
-               } else {
+            // - Single-segment: percentage as-is (deep: * 0.8 if a child holds >60% of it)
+            // - Two-segment: percentage * 1.2 if >60% (deep: of its parent), else * 0.8
+            let confidence = match (depth, path.split_once('/')) {
+               (ScopeDepthPreference::Shallow, Some(_)) => {
+                  if percentage > 60.0 {
+                     percentage * 1.2
+                  } else {
+                     percentage * 0.8
+                  }
+               },
+               (ScopeDepthPreference::Deep, Some((parent, _))) => {
+                  let parent_lines = self.component_lines.get(parent).copied().unwrap_or(lines);
+                  if lines as f32 / parent_lines as f32 > 0.6 {
+                     percentage * 1.2
+                  } else {
+                     percentage * 0.8
+                  }
+               },
+               (ScopeDepthPreference::Deep, None) if dominant_child_share(path) > 60.0 => {
                   percentage * 0.8
-               }
-            } else {
-               percentage
+               },
+               (_, None) => percentage,
             };
 
             ScopeCandidate { percentage, path: path.clone(), confidence }
          })
          .collect();
 
-      candidates.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
+      // Ties go to the preferred depth
+      let segments = |c: &ScopeCandidate| c.path.matches('/').count();
+      candidates.sort_by(|a, b| {
+         b.confidence
+            .partial_cmp(&a.confidence)
+            .unwrap()
+            .then_with(|| match depth {
+               ScopeDepthPreference::Shallow => segments(a).cmp(&segments(b)),
+               ScopeDepthPreference::Deep => segments(b).cmp(&segments(a)),
+            })
+      });
       candidates
    }
 
+   /// Compact top-level weight string for prompts, e.g. `80% api, 20% db`
+   ///
+   /// Only single-segment candidates are used so the shares don't overlap;
+   /// components under 5% are dropped as noise.
+   pub fn format_change_weights(candidates: &[ScopeCandidate]) -> Option<String> {
+      let mut top_level: Vec<&ScopeCandidate> = candidates
+         .iter()
+         .filter(|c| !c.path.contains('/') && c.percentage >= 5.0)
+         .collect();
+      top_level.sort_by(|a, b| b.percentage.total_cmp(&a.percentage));
+
+      let parts: Vec<String> = top_level
+         .iter()
+         .take(4)
+         .map(|c| format!("{:.0}% {}", c.percentage, c.path))
+         .collect();
+      (!parts.is_empty()).then(|| parts.join(", "))
+   }
+
    /// Check if change spans multiple components (wide change)
    pub fn is_wide_change(candidates: &[ScopeCandidate], config: &CommitConfig) -> bool {
       // Check if top component is below threshold
@@ -236,10 +348,206 @@ impl ScopeAnalyzer {
          analyzer.process_numstat_line(line, config);
       }
 
-      let candidates = analyzer.build_scope_candidates();
+      let candidates = analyzer.build_scope_candidates(config.scope_depth_preference);
       (candidates, analyzer.total_lines)
    }
 
+   /// Prompt note for a change that mostly deletes code (removing a feature or
+   /// dead code), which models tend to label `feat`/`fix`.
+   ///
+   /// Applies when at least [`DELETION_HEAVY_MIN_LINES`] lines are deleted and
+   /// deletions are `deletion_heavy_ratio` times the additions or more.
+   pub fn deletion_heavy_note(numstat: &str, config: &CommitConfig) -> Option<String> {
+      if config.deletion_heavy_ratio == 0 {
+         return None;
+      }
+
+      let mut analyzer = Self::new();
+      for line in numstat.lines() {
+         analyzer.process_numstat_line(line, config);
+      }
+
+      let (added, deleted) = (analyzer.added, analyzer.deleted);
+      (deleted >= DELETION_HEAVY_MIN_LINES
+         && deleted >= added.max(1).saturating_mul(config.deletion_heavy_ratio))
+      .then(|| {
+         let mut verb = "removed".to_string();
+         crate::normalization::normalize_summary_verb(&mut verb, "refactor", config.summary_tense);
+         format!(
+            "\nMostly deletions (-{deleted}/+{added} lines): this removes code, so prefer \
+             `refactor` (or `chore` for non-code cleanup) over `feat`/`fix`, and start the \
+             summary with \"{verb}\""
+         )
+      })
+   }
+
+   /// Check if a path matches the test file patterns: under a `test`/`tests`
+   /// directory, or named `*_test.*`
+   pub fn is_test_path(path: &str) -> bool {
+      path.starts_with("test/")
+         || path.starts_with("tests/")
+         || path.contains("/test/")
+         || path.contains("/tests/")
+         || path.contains("_test.")
+   }
+
+   /// Whether a path is documentation: a markup file (`.md`, `.rst`,
+   /// `.adoc`, `.org`, `.tex`, `.pod`, ...), a README, or anything under a
+   /// `docs`/`doc` directory at any depth
+   pub fn is_doc_path(path: &str) -> bool {
+      let path = std::path::Path::new(path);
+      path
+         .extension()
+         .and_then(|ext| ext.to_str())
+         .is_some_and(|ext| {
+            ["md", "mdx", "rst", "adoc", "asciidoc", "org", "tex", "pod"]
+               .iter()
+               .any(|doc| ext.eq_ignore_ascii_case(doc))
+         })
+         || path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.to_lowercase().starts_with("readme"))
+         || path.parent().is_some_and(|dir| {
+            dir.components().any(|c| {
+               c.as_os_str().to_str().is_some_and(|name| {
+                  name.eq_ignore_ascii_case("docs") || name.eq_ignore_ascii_case("doc")
+               })
+            })
+         })
+   }
+
+   /// Whether every file in numstat is documentation (see
+   /// [`ScopeAnalyzer::is_doc_path`]) other than a changelog, which release
+   /// and chore commits update too
+   pub fn is_docs_only(numstat: &str) -> bool {
+      let mut paths = numstat
+         .lines()
+         .filter_map(|line| line.splitn(3, '\t').nth(2))
+         .peekable();
+      paths.peek().is_some() && paths.all(|path| Self::is_doc_path(path) && !is_changelog(path))
+   }
+
+   /// Changed files of a numstat with their added plus deleted lines, leaving
+   /// out excluded files and files without changed lines (binary, mode-only)
+   fn changed_lines(numstat: &str, config: &CommitConfig) -> Vec<(String, usize)> {
+      numstat
+         .lines()
+         .filter_map(|line| {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() < 3 {
+               return None;
+            }
+            let added = parts[0].parse::<usize>().unwrap_or(0);
+            let deleted = parts[1].parse::<usize>().unwrap_or(0);
+            let path = Self::extract_path_from_rename(parts[2]);
+            (added + deleted > 0 && !config.is_excluded_file(&path))
+               .then_some((path, added + deleted))
+         })
+         .collect()
+   }
+
+   /// Sum changed lines from numstat into (source, test) buckets, applying the
+   /// test down-weighting when enabled
+   pub fn split_test_lines(numstat: &str, config: &CommitConfig) -> (usize, usize) {
+      let mut source_lines = 0;
+      let mut test_lines = 0;
+
+      for (path, lines_changed) in Self::changed_lines(numstat, config) {
+         if Self::is_test_path(&path) {
+            test_lines += if config.deprioritize_test_files_for_type {
+               (lines_changed / TEST_FILE_WEIGHT_DIVISOR).max(1)
+            } else {
+               lines_changed
+            };
+         } else {
+            source_lines += lines_changed;
+         }
+      }
+
+      (source_lines, test_lines)
+   }
+
+   /// Reclassify a `test` commit type when non-test code dominates the change
+   /// and the analysis says what that code does: its first changelog detail
+   /// under `Added` makes it `feat`, under `Fixed` or `Security` `fix`.
+   ///
+   /// Without such a detail `test` stands, so a refactor or fix that brought
+   /// its regression test isn't promoted to a feature. Only active with
+   /// `deprioritize_test_files_for_type`. Returns the replacement type, or
+   /// `None` when the original type should stand.
+   pub fn reconcile_test_type(
+      analysis: &ConventionalAnalysis,
+      numstat: &str,
+      config: &CommitConfig,
+   ) -> Option<CommitType> {
+      if !config.deprioritize_test_files_for_type || analysis.commit_type.as_str() != "test" {
+         return None;
+      }
+
+      let (source_lines, test_lines) = Self::split_test_lines(numstat, config);
+      if source_lines <= test_lines {
+         return None;
+      }
+      let replacement = match analysis
+         .details
+         .iter()
+         .find_map(|detail| detail.changelog_category)?
+      {
+         ChangelogCategory::Added => "feat",
+         ChangelogCategory::Fixed | ChangelogCategory::Security => "fix",
+         _ => return None,
+      };
+      CommitType::new(replacement).ok()
+   }
+
+   /// Switch the model's type to `docs` when every changed file is
+   /// documentation, wherever it lives (`src/foo/README.md`).
+   ///
+   /// Only active with `docs_only_as_docs`. Returns the replacement type, or
+   /// `None` when the original type should stand.
+   pub fn reconcile_docs_type(
+      commit_type: &CommitType,
+      numstat: &str,
+      config: &CommitConfig,
+   ) -> Option<CommitType> {
+      (config.docs_only_as_docs && commit_type.as_str() != "docs" && Self::is_docs_only(numstat))
+         .then(|| CommitType::new("docs").ok())
+         .flatten()
+   }
+
+   /// Switch the model's type to the `tooling_types` type of the
+   /// [`tooling_kind`] that makes up the change (CI pipelines -> `ci`,
+   /// `Dockerfile` -> `build`, `.editorconfig` -> `chore`).
+   ///
+   /// Returns the replacement type when one type's files hold
+   /// `tooling_dominance_percent` of the changed lines, or `None` when the
+   /// original type should stand.
+   pub fn reconcile_tooling_type(
+      commit_type: &CommitType,
+      numstat: &str,
+      config: &CommitConfig,
+   ) -> Option<CommitType> {
+      if config.tooling_types.is_empty() || config.tooling_dominance_percent == 0 {
+         return None;
+      }
+
+      let mut total_lines = 0;
+      let mut type_lines: HashMap<&str, usize> = HashMap::new();
+      for (path, lines) in Self::changed_lines(numstat, config) {
+         total_lines += lines;
+         if let Some(tooling_type) = tooling_kind(&path).and_then(|k| config.tooling_types.get(k)) {
+            *type_lines.entry(tooling_type).or_default() += lines;
+         }
+      }
+
+      let (tooling_type, lines) = type_lines.into_iter().max_by_key(|(_, lines)| *lines)?;
+      (lines * 100 >= total_lines * config.tooling_dominance_percent
+         && commit_type.as_str() != tooling_type)
+         .then(|| CommitType::new(tooling_type).ok())
+         .flatten()
+   }
+
    /// Analyze wide changes to detect cross-cutting patterns
    pub fn analyze_wide_change(numstat: &str) -> Option<String> {
       let lines: Vec<&str> = numstat.lines().collect();
@@ -284,15 +592,10 @@ impl ScopeAnalyzer {
          {
             md_count += 1;
          }
-         if path.contains("/test") || path.contains("_test.") || path.ends_with("_test.rs") {
+         if Self::is_test_path(path) {
             test_count += 1;
          }
-         if std::path::Path::new(path).extension().is_some_and(|ext| {
-            ext.eq_ignore_ascii_case("toml")
-               || ext.eq_ignore_ascii_case("yaml")
-               || ext.eq_ignore_ascii_case("yml")
-               || ext.eq_ignore_ascii_case("json")
-         }) {
+         if tooling_kind(path).is_some() {
             config_count += 1;
          }
 
@@ -355,17 +658,313 @@ impl ScopeAnalyzer {
       // No clear pattern detected
       None
    }
+
+   /// Commit type a diff most likely has, judged only by its file types
+   pub fn likely_commit_type(numstat: &str) -> Option<&'static str> {
+      if Self::is_docs_only(numstat) {
+         return Some("docs");
+      }
+      match Self::analyze_wide_change(numstat)?.as_str() {
+         "docs" => Some("docs"),
+         "tests" => Some("test"),
+         "deps" => Some("build"),
+         _ => None,
+      }
+   }
+
+   /// Analysis prompt variant for a diff: the `analysis_prompt_variants` entry
+   /// for its likely type, else `analysis_prompt_variant`
+   pub fn select_analysis_prompt_variant<'a>(numstat: &str, config: &'a CommitConfig) -> &'a str {
+      if config.analysis_prompt_variants.is_empty() {
+         return &config.analysis_prompt_variant;
+      }
+      Self::likely_commit_type(numstat)
+         .and_then(|commit_type| config.analysis_prompt_variants.get(commit_type))
+         .unwrap_or(&config.analysis_prompt_variant)
+   }
 }
 
-/// Extract candidate scopes from git diff --numstat output
-/// Returns (`scope_string`, `is_wide_change`)
-pub fn extract_scope_candidates(
-   mode: &Mode,
-   target: Option<&str>,
-   dir: &str,
-   config: &CommitConfig,
-) -> Result<(String, bool)> {
-   // Get numstat output
+/// Kind of change a file's lines count toward in a [`ChangeProfile`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChangeBucket {
+   Code,
+   Tests,
+   Docs,
+   Config,
+   Deps,
+}
+
+/// Lockfiles that aren't named `*.lock`/`*.lockb`
+const LOCKFILES: &[&str] =
+   &["package-lock.json", "npm-shrinkwrap.json", "pnpm-lock.yaml", "go.sum"];
+
+/// Manifests declaring a project's dependencies
+const DEPENDENCY_MANIFESTS: &[&str] = &[
+   "Cargo.toml",
+   "package.json",
+   "go.mod",
+   "requirements.txt",
+   "Pipfile",
+   "pyproject.toml",
+   "Gemfile",
+   "composer.json",
+   "build.gradle",
+   "build.gradle.kts",
+   "gradle.properties",
+   "pom.xml",
+];
+
+fn file_name(path: &str) -> &str {
+   std::path::Path::new(path)
+      .file_name()
+      .and_then(|name| name.to_str())
+      .unwrap_or(path)
+}
+
+/// Whether `path` is a changelog (`CHANGELOG.md`, `changelog.txt`, ...)
+pub fn is_changelog(path: &str) -> bool {
+   file_name(path).to_lowercase().starts_with("changelog")
+}
+
+/// Whether `path` is a lockfile (`Cargo.lock`, `package-lock.json`, ...)
+pub fn is_lockfile(path: &str) -> bool {
+   let name = file_name(path);
+   LOCKFILES.contains(&name)
+      || std::path::Path::new(name)
+         .extension()
+         .is_some_and(|ext| ext.eq_ignore_ascii_case("lock") || ext.eq_ignore_ascii_case("lockb"))
+}
+
+/// Whether changes to `path` are dependency updates: a lockfile or a
+/// dependency manifest
+pub fn is_dependency_file(path: &str) -> bool {
+   is_lockfile(path) || DEPENDENCY_MANIFESTS.contains(&file_name(path))
+}
+
+/// CI pipeline definitions outside `.github/workflows/` and `.circleci/`
+const CI_FILES: &[&str] = &[".gitlab-ci.yml", "Jenkinsfile", "azure-pipelines.yml"];
+
+/// Container and build scripts
+const BUILD_FILES: &[&str] = &["Dockerfile", "docker-compose.yml", "Makefile", "CMakeLists.txt"];
+
+/// Editor and git settings
+const SETTINGS_FILES: &[&str] =
+   &[".editorconfig", ".gitignore", ".gitattributes", ".pre-commit-config.yaml"];
+
+/// Kind of tooling file `path` is: `ci` for CI pipelines, `build` for
+/// container and build scripts, `settings` for editor and git settings, and
+/// `config` for any other TOML/YAML/JSON file
+pub fn tooling_kind(path: &str) -> Option<&'static str> {
+   let name = file_name(path);
+   if path.starts_with(".github/workflows/")
+      || path.starts_with(".circleci/")
+      || CI_FILES.contains(&name)
+   {
+      Some("ci")
+   } else if BUILD_FILES.contains(&name) {
+      Some("build")
+   } else if SETTINGS_FILES.contains(&name) {
+      Some("settings")
+   } else {
+      std::path::Path::new(name)
+         .extension()
+         .is_some_and(|ext| {
+            ["toml", "yaml", "yml", "json"]
+               .iter()
+               .any(|config| ext.eq_ignore_ascii_case(config))
+         })
+         .then_some("config")
+   }
+}
+
+/// Line-comment markers per source extension, used to spot comment-only hunks
+const COMMENT_MARKERS: &[(&[&str], &[&str])] = &[
+   (
+      &[
+         "rs", "c", "h", "cc", "cpp", "cxx", "hpp", "hxx", "java", "kt", "kts", "scala", "groovy",
+         "cs", "js", "jsx", "ts", "tsx", "mjs", "cjs", "go", "swift", "dart", "zig", "php", "sol",
+      ],
+      // `* ` continues a block comment; a bare `*` could be a dereference
+      &["//", "/*", "* ", "*/"],
+   ),
+   (&["py", "pyi", "rb", "sh", "bash", "zsh", "fish", "pl", "pm", "r", "nix", "ex", "exs"], &["#"]),
+   (&["lua", "sql", "hs", "elm", "ada", "adb", "ads"], &["--"]),
+];
+
+/// Share of changed lines per kind of change, each in `0.0..=1.0`
+///
+/// Built by [`classify_change_profile`] from a diff, or by
+/// [`ChangeProfile::from_stat`] from file paths alone. All zero when nothing
+/// changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChangeProfile {
+   pub code:   f32,
+   pub tests:  f32,
+   /// Documentation files, `examples/`, and comment-only edits to source
+   pub docs:   f32,
+   pub config: f32,
+   pub deps:   f32,
+}
+
+impl ChangeProfile {
+   #[allow(clippy::cast_precision_loss, reason = "line counts are far below f32 precision")]
+   fn from_line_counts(counts: impl IntoIterator<Item = (ChangeBucket, usize)>) -> Self {
+      let mut lines = [0usize; 5];
+      for (bucket, count) in counts {
+         lines[bucket as usize] += count;
+      }
+      let total: usize = lines.iter().sum();
+      if total == 0 {
+         return Self::default();
+      }
+      let share = |bucket: ChangeBucket| lines[bucket as usize] as f32 / total as f32;
+      Self {
+         code:   share(ChangeBucket::Code),
+         tests:  share(ChangeBucket::Tests),
+         docs:   share(ChangeBucket::Docs),
+         config: share(ChangeBucket::Config),
+         deps:   share(ChangeBucket::Deps),
+      }
+   }
+
+   /// Profile from `git diff --stat` output, judged by paths only (no
+   /// comment detection)
+   pub fn from_stat(stat: &str) -> Self {
+      Self::from_line_counts(stat.lines().filter_map(|line| {
+         let (path, changes) = line.split_once('|')?;
+         let path = ScopeAnalyzer::extract_path_from_rename(path);
+         // Binary files ("Bin 0 -> 10 bytes") count as one line
+         let lines = changes
+            .split_whitespace()
+            .next()
+            .and_then(|count| count.parse::<usize>().ok())
+            .unwrap_or(1);
+         Some((path_bucket(&path), lines.max(1)))
+      }))
+   }
+
+   /// Whether nothing was classified
+   pub fn is_empty(&self) -> bool {
+      self.code + self.tests + self.docs + self.config + self.deps == 0.0
+   }
+
+   /// Prompt note with the share of each kind of change, largest first; `None`
+   /// for empty or code-only changes, where it adds nothing
+   pub fn format_for_prompt(&self) -> Option<String> {
+      if self.is_empty() || self.code >= 1.0 {
+         return None;
+      }
+      let mut shares = [
+         ("code", self.code),
+         ("tests", self.tests),
+         ("docs", self.docs),
+         ("config", self.config),
+         ("deps", self.deps),
+      ];
+      shares.sort_by(|a, b| b.1.total_cmp(&a.1));
+      let parts: Vec<String> = shares
+         .iter()
+         .filter(|(_, share)| (share * 100.0).round() >= 1.0)
+         .map(|(kind, share)| format!("{kind} {:.0}%", share * 100.0))
+         .collect();
+      Some(format!(
+         "Changed lines by kind: {} (comment-only edits to source count as docs)",
+         parts.join(", ")
+      ))
+   }
+}
+
+/// Whether a path lies under an `examples`/`example` directory
+fn is_example_path(path: &str) -> bool {
+   std::path::Path::new(path).parent().is_some_and(|dir| {
+      dir.components().any(|c| {
+         c.as_os_str().to_str().is_some_and(|name| {
+            name.eq_ignore_ascii_case("examples") || name.eq_ignore_ascii_case("example")
+         })
+      })
+   })
+}
+
+/// Bucket of a file judged by its path alone
+fn path_bucket(path: &str) -> ChangeBucket {
+   let file = std::path::Path::new(path);
+   if is_dependency_file(path) {
+      ChangeBucket::Deps
+   } else if ScopeAnalyzer::is_doc_path(path) || is_example_path(path) {
+      ChangeBucket::Docs
+   } else if ScopeAnalyzer::is_test_path(path) {
+      ChangeBucket::Tests
+   } else if path.starts_with(".github/")
+      || file
+         .extension()
+         .and_then(|ext| ext.to_str())
+         .is_some_and(|ext| {
+            ["toml", "yaml", "yml", "json", "ini", "cfg", "conf"]
+               .iter()
+               .any(|config| ext.eq_ignore_ascii_case(config))
+         })
+   {
+      ChangeBucket::Config
+   } else {
+      ChangeBucket::Code
+   }
+}
+
+/// Whether every changed line in a file's diff content is a comment (or
+/// blank) in the file's language, with at least one comment among them
+fn is_comment_only(path: &str, content: &str) -> bool {
+   let Some(ext) = std::path::Path::new(path)
+      .extension()
+      .and_then(|ext| ext.to_str())
+   else {
+      return false;
+   };
+   let Some((_, markers)) = COMMENT_MARKERS
+      .iter()
+      .find(|(exts, _)| exts.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+   else {
+      return false;
+   };
+
+   let mut has_comment = false;
+   for line in content.lines() {
+      let Some(changed) = line.strip_prefix('+').or_else(|| line.strip_prefix('-')) else {
+         continue;
+      };
+      let changed = changed.trim();
+      if changed.is_empty() {
+         continue;
+      }
+      if changed != "*" && !markers.iter().any(|marker| changed.starts_with(marker)) {
+         return false;
+      }
+      has_comment = true;
+   }
+   has_comment
+}
+
+/// Share of changed lines per kind of change in a unified diff
+///
+/// Files are bucketed by path; paths under `examples/` and `.mdx`/`.rst`/
+/// `.adoc` files count as docs, and so do code or test files whose changed
+/// lines are all comments (doc-comment-only edits).
+pub fn classify_change_profile(diff: &str) -> ChangeProfile {
+   ChangeProfile::from_line_counts(parse_diff(diff).iter().map(|file| {
+      let bucket = match path_bucket(&file.filename) {
+         ChangeBucket::Code | ChangeBucket::Tests
+            if is_comment_only(&file.filename, &file.content) =>
+         {
+            ChangeBucket::Docs
+         },
+         bucket => bucket,
+      };
+      // Binary and mode-only changes still register
+      (bucket, (file.additions + file.deletions).max(1))
+   }))
+}
+
+/// Get git diff --numstat output for the given mode
+pub fn get_numstat(mode: &Mode, target: Option<&str>, dir: &str) -> Result<String> {
    let output = match mode {
       Mode::Staged => Command::new("git")
          .args(["diff", "--cached", "--numstat"])
@@ -378,8 +977,13 @@ pub fn extract_scope_candidates(
          let target = target.ok_or_else(|| {
             CommitGenError::ValidationError("--target required for commit mode".to_string())
          })?;
+         let subcommand = if crate::git::is_commit_range(target) {
+            "diff"
+         } else {
+            "show"
+         };
          Command::new("git")
-            .args(["show", "--numstat", target])
+            .args([subcommand, "--numstat", target])
             .current_dir(dir)
             .output()
             .map_err(|e| {
@@ -391,6 +995,18 @@ pub fn extract_scope_candidates(
          .current_dir(dir)
          .output()
          .map_err(|e| CommitGenError::GitError(format!("Failed to run git diff --numstat: {e}")))?,
+      Mode::Merge => {
+         let base = crate::git::merge_diff_base(dir)?;
+         Command::new("git")
+            .args(["diff", "--cached", "--numstat", base])
+            .current_dir(dir)
+            .output()
+            .map_err(|e| {
+               CommitGenError::GitError(format!(
+                  "Failed to run git diff --cached --numstat {base}: {e}"
+               ))
+            })?
+      },
       Mode::Compose => unreachable!("compose mode handled separately"),
    };
 
@@ -398,20 +1014,53 @@ pub fn extract_scope_candidates(
       return Err(CommitGenError::GitError("git diff --numstat failed".to_string()));
    }
 
-   let numstat = String::from_utf8_lossy(&output.stdout);
+   Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Extract candidate scopes from git diff --numstat output
+/// Returns (`scope_string`, `is_wide_change`)
+pub fn extract_scope_candidates(
+   mode: &Mode,
+   target: Option<&str>,
+   dir: &str,
+   config: &CommitConfig,
+) -> Result<(String, bool)> {
+   let numstat = get_numstat(mode, target, dir)?;
+   Ok(scope_candidates_prompt(&numstat, config))
+}
 
-   let (candidates, total_lines) = ScopeAnalyzer::extract_scope(&numstat, config);
+/// Scope suggestions for the prompt from numstat output: at most
+/// `max_scope_candidates`, each holding at least `min_scope_percentage` of
+/// the change. Returns (`scope_string`, `is_wide_change`)
+pub fn scope_candidates_prompt(numstat: &str, config: &CommitConfig) -> (String, bool) {
+   let (candidates, total_lines) = ScopeAnalyzer::extract_scope(numstat, config);
 
    if total_lines == 0 {
-      return Ok(("(none - no measurable changes)".to_string(), false));
+      return ("(none - no measurable changes)".to_string(), false);
    }
 
    let is_wide = ScopeAnalyzer::is_wide_change(&candidates, config);
 
+   // Nudge the model away from `test` when tests merely accompany source changes
+   let test_note = if config.deprioritize_test_files_for_type {
+      let (source_lines, test_lines) = ScopeAnalyzer::split_test_lines(numstat, config);
+      (source_lines > 0 && test_lines > 0).then_some(
+         "\nTest files accompany source changes: classify the type by the source changes (not \
+          `test`) and mention the tests in the details",
+      )
+   } else {
+      None
+   };
+   let notes = format!(
+      "{}{}",
+      test_note.unwrap_or_default(),
+      ScopeAnalyzer::deletion_heavy_note(numstat, config).unwrap_or_default()
+   );
+
    if is_wide {
       // Try to detect a pattern if wide_change_abstract is enabled
       let scope_str = if config.wide_change_abstract {
-         if let Some(pattern) = ScopeAnalyzer::analyze_wide_change(&numstat) {
+         if let Some(pattern) = ScopeAnalyzer::analyze_wide_change(numstat) {
             format!("(cross-cutting: {pattern})")
          } else {
             "(none - multi-component change)".to_string()
@@ -420,17 +1069,18 @@ pub fn extract_scope_candidates(
          "(none - multi-component change)".to_string()
       };
 
-      return Ok((scope_str, true));
+      return (format!("{scope_str}{notes}"), true);
    }
 
-   // Format suggested scopes with weights for prompt (keep top 5, prefer 2-segment
-   // when >60%)
+   // Format suggested scopes with weights for prompt (keep the top
+   // `max_scope_candidates`, prefer 2-segment when >60%)
    let mut suggestion_parts = Vec::new();
-   for cand in candidates.iter().take(5) {
-      // Only suggest if ≥10% to avoid noise
-      if cand.percentage >= 10.0 {
+   for cand in candidates.iter().take(config.max_scope_candidates) {
+      // Only suggest if ≥`min_scope_percentage` to avoid noise
+      if cand.percentage >= config.min_scope_percentage {
          let confidence_label = if cand.path.contains('/') {
-            if cand.percentage > 60.0 {
+            // Boosted above its raw share by the depth heuristic
+            if cand.confidence > cand.percentage {
                "high confidence"
             } else {
                "moderate confidence"
@@ -450,12 +1100,13 @@ pub fn extract_scope_candidates(
       format!("{}\nPrefer 2-segment scopes marked 'high confidence'", suggestion_parts.join(", "))
    };
 
-   Ok((scope_str, is_wide))
+   (format!("{scope_str}{notes}"), is_wide)
 }
 
 #[cfg(test)]
 mod tests {
    use super::*;
+   use crate::config::Tense;
 
    fn default_config() -> CommitConfig {
       CommitConfig {
@@ -519,34 +1170,70 @@ mod tests {
    #[test]
    fn test_extract_components_simple() {
       // "src" is placeholder and skipped, only "api" remains
-      let comps = ScopeAnalyzer::extract_components_from_path("src/api/client.rs");
+      let comps = ScopeAnalyzer::extract_components_from_path("src/api/client.rs", &[]);
       assert_eq!(comps, vec!["api"]);
    }
 
    #[test]
    fn test_extract_components_with_placeholder() {
       // "lib" is placeholder and skipped, "foo" and "bar" remain
-      let comps = ScopeAnalyzer::extract_components_from_path("lib/foo/bar/baz.tsx");
+      let comps = ScopeAnalyzer::extract_components_from_path("lib/foo/bar/baz.tsx", &[]);
       assert_eq!(comps, vec!["foo", "foo/bar"]);
    }
 
    #[test]
    fn test_extract_components_skip_tests() {
       // "tests" is in SKIP_DIRS, so skipped, only "api" remains
-      let comps = ScopeAnalyzer::extract_components_from_path("tests/api/client_test.rs");
+      let comps = ScopeAnalyzer::extract_components_from_path("tests/api/client_test.rs", &[]);
       assert_eq!(comps, vec!["api"]);
    }
 
+   #[test]
+   fn test_extract_components_force_scope_dirs() {
+      let force = vec!["tests".to_string(), ".github".to_string(), "infra".to_string()];
+      let comps = ScopeAnalyzer::extract_components_from_path("tests/api/client_test.rs", &force);
+      assert_eq!(comps, vec!["tests", "tests/api"]);
+
+      let comps = ScopeAnalyzer::extract_components_from_path(".github/workflows/ci.yml", &force);
+      assert_eq!(comps, vec!["github", "github/workflows"]);
+      assert!(
+         comps
+            .iter()
+            .all(|comp| crate::types::Scope::new(comp.as_str()).is_ok())
+      );
+
+      // A forced directory leads even when nested
+      let comps = ScopeAnalyzer::extract_components_from_path("deploy/infra/main.tf", &force);
+      assert_eq!(comps, vec!["infra"]);
+   }
+
+   #[test]
+   fn test_extract_scope_force_scope_dir() {
+      let config = CommitConfig { force_scope_dirs: vec!["tests".to_string()], ..default_config() };
+      let numstat = "50\t0\ttests/api/client_test.rs\n10\t2\tsrc/db/pool.rs";
+      let (candidates, _) = ScopeAnalyzer::extract_scope(numstat, &config);
+      assert_eq!(candidates[0].path, "tests/api");
+      assert!(candidates.iter().any(|cand| cand.path == "tests"));
+
+      // Without the setting `tests` is skipped
+      let (candidates, _) = ScopeAnalyzer::extract_scope(numstat, &default_config());
+      assert!(
+         candidates
+            .iter()
+            .all(|cand| !cand.path.starts_with("tests"))
+      );
+   }
+
    #[test]
    fn test_extract_components_skip_node_modules() {
       // "node_modules" is in SKIP_DIRS, only "foo" remains
-      let comps = ScopeAnalyzer::extract_components_from_path("node_modules/foo/bar.js");
+      let comps = ScopeAnalyzer::extract_components_from_path("node_modules/foo/bar.js", &[]);
       assert_eq!(comps, vec!["foo"]);
    }
 
    #[test]
    fn test_extract_components_single_segment() {
-      let comps = ScopeAnalyzer::extract_components_from_path("src/main.rs");
+      let comps = ScopeAnalyzer::extract_components_from_path("src/main.rs", &[]);
       // "src" is a placeholder and is stripped, leaving no components
       assert_eq!(comps, Vec::<String>::new());
    }
@@ -554,13 +1241,13 @@ mod tests {
    #[test]
    fn test_extract_components_dotfile_skipped() {
       // ".git" gets stripped to "" and filtered out, "config" is kept
-      let comps = ScopeAnalyzer::extract_components_from_path("lib/.git/config");
+      let comps = ScopeAnalyzer::extract_components_from_path("lib/.git/config", &[]);
       assert_eq!(comps, vec!["config"]);
    }
 
    #[test]
    fn test_extract_components_strips_extension() {
-      let comps = ScopeAnalyzer::extract_components_from_path("src/api/client.rs");
+      let comps = ScopeAnalyzer::extract_components_from_path("src/api/client.rs", &[]);
       // "client.rs" is a file, so skipped; "api" and "src" are dirs
       assert!(comps.contains(&"api".to_string()));
    }
@@ -568,28 +1255,29 @@ mod tests {
    #[test]
    fn test_extract_components_go_internal() {
       // Go projects: internal/ is placeholder, extract actual module
-      let comps = ScopeAnalyzer::extract_components_from_path("internal/agent/worker.go");
+      let comps = ScopeAnalyzer::extract_components_from_path("internal/agent/worker.go", &[]);
       assert_eq!(comps, vec!["agent"]);
    }
 
    #[test]
    fn test_extract_components_go_internal_nested() {
       // Go projects: internal/foo/bar/baz.go → extract "foo" and "foo/bar"
-      let comps = ScopeAnalyzer::extract_components_from_path("internal/config/parser/json.go");
+      let comps =
+         ScopeAnalyzer::extract_components_from_path("internal/config/parser/json.go", &[]);
       assert_eq!(comps, vec!["config", "config/parser"]);
    }
 
    #[test]
    fn test_extract_components_go_pkg() {
       // Go projects: pkg/ is also a placeholder
-      let comps = ScopeAnalyzer::extract_components_from_path("pkg/util/strings.go");
+      let comps = ScopeAnalyzer::extract_components_from_path("pkg/util/strings.go", &[]);
       assert_eq!(comps, vec!["util"]);
    }
 
    #[test]
    fn test_extract_components_monorepo_packages() {
       // Monorepos: packages/ is placeholder
-      let comps = ScopeAnalyzer::extract_components_from_path("packages/core/index.ts");
+      let comps = ScopeAnalyzer::extract_components_from_path("packages/core/index.ts", &[]);
       assert_eq!(comps, vec!["core"]);
    }
 
@@ -623,6 +1311,16 @@ mod tests {
       assert_eq!(analyzer.total_lines, 0);
    }
 
+   #[test]
+   fn test_process_numstat_line_mode_only_counts_as_one_line() {
+      let mut analyzer = ScopeAnalyzer::new();
+      let config = default_config();
+      analyzer.process_numstat_line("0\t0\tscripts/deploy.sh", &config);
+
+      assert_eq!(analyzer.total_lines, 1);
+      assert_eq!(analyzer.component_lines.get("scripts"), Some(&1));
+   }
+
    #[test]
    fn test_process_numstat_line_invalid() {
       let mut analyzer = ScopeAnalyzer::new();
@@ -774,6 +1472,22 @@ mod tests {
       assert_eq!(candidates[0].path, "api");
    }
 
+   #[test]
+   fn test_extract_scope_skips_generated_files() {
+      let config = CommitConfig {
+         generated_files: crate::git::parse_generated_attributes(
+            "src/proto/*.pb.rs linguist-generated\n",
+         ),
+         ..default_config()
+      };
+      let numstat = "400\t120\tsrc/proto/api.pb.rs\n10\t5\tsrc/db/pool.rs";
+      let (candidates, total_lines) = ScopeAnalyzer::extract_scope(numstat, &config);
+
+      assert_eq!(total_lines, 15);
+      assert_eq!(candidates.len(), 1);
+      assert_eq!(candidates[0].path, "db");
+   }
+
    #[test]
    fn test_extract_scope_no_changes() {
       let config = default_config();
@@ -802,7 +1516,7 @@ mod tests {
       analyzer.component_lines.insert("db".to_string(), 70);
       analyzer.total_lines = 100;
 
-      let candidates = analyzer.build_scope_candidates();
+      let candidates = analyzer.build_scope_candidates(ScopeDepthPreference::Shallow);
 
       assert_eq!(candidates.len(), 2);
       assert_eq!(candidates[0].path, "db");
@@ -822,7 +1536,7 @@ mod tests {
       analyzer.component_lines.insert("other".to_string(), 30);
       analyzer.total_lines = 100;
 
-      let candidates = analyzer.build_scope_candidates();
+      let candidates = analyzer.build_scope_candidates(ScopeDepthPreference::Shallow);
 
       // api/client at 70% gets confidence = 70 * 1.2 = 84
       // api at 70% gets confidence = 70
@@ -844,7 +1558,7 @@ mod tests {
       analyzer.component_lines.insert("other".to_string(), 55);
       analyzer.total_lines = 100;
 
-      let candidates = analyzer.build_scope_candidates();
+      let candidates = analyzer.build_scope_candidates(ScopeDepthPreference::Shallow);
 
       // other at 55% gets confidence = 55
       // api at 45% gets confidence = 45
@@ -856,6 +1570,44 @@ mod tests {
       assert!((candidates[2].confidence - 36.0).abs() < 0.001);
    }
 
+   // Depth preference: 40 of api's 50 lines are in api/client (80% of the change)
+   const NESTED_NUMSTAT: &str = "30\t10\tsrc/api/client/handler.rs\n10\t0\tsrc/api/routes.rs";
+
+   #[test]
+   fn test_scope_depth_shallow_prefers_component() {
+      let config = default_config();
+      let (candidates, _) = ScopeAnalyzer::extract_scope(NESTED_NUMSTAT, &config);
+
+      // api 100; api/client 80 * 1.2 = 96
+      assert_eq!(candidates[0].path, "api");
+      assert_eq!(candidates[1].path, "api/client");
+      assert!((candidates[1].confidence - 96.0).abs() < 0.001);
+   }
+
+   #[test]
+   fn test_scope_depth_deep_prefers_subdirectory() {
+      let config =
+         CommitConfig { scope_depth_preference: ScopeDepthPreference::Deep, ..default_config() };
+      let (candidates, _) = ScopeAnalyzer::extract_scope(NESTED_NUMSTAT, &config);
+
+      // api/client 80 * 1.2 = 96; api yields to its child: 100 * 0.8 = 80
+      assert_eq!(candidates[0].path, "api/client");
+      assert!((candidates[0].confidence - 96.0).abs() < 0.001);
+      assert_eq!(candidates[1].path, "api");
+      assert!((candidates[1].confidence - 80.0).abs() < 0.001);
+   }
+
+   #[test]
+   fn test_scope_depth_deep_keeps_component_when_split() {
+      let config =
+         CommitConfig { scope_depth_preference: ScopeDepthPreference::Deep, ..default_config() };
+      let numstat = "10\t10\tsrc/api/client/handler.rs\n10\t10\tsrc/api/server/handler.rs";
+      let (candidates, _) = ScopeAnalyzer::extract_scope(numstat, &config);
+
+      // Neither child holds >60% of api
+      assert_eq!(candidates[0].path, "api");
+   }
+
    // Tests for analyze_wide_change()
    #[test]
    fn test_analyze_wide_change_dependency_updates() {
@@ -924,4 +1676,316 @@ mod tests {
       let result = ScopeAnalyzer::analyze_wide_change(numstat);
       assert_eq!(result, Some("deps".to_string()));
    }
+
+   #[test]
+   fn test_select_analysis_prompt_variant() {
+      let docs_heavy =
+         "50\t20\tREADME.md\n30\t10\tdocs/guide.md\n20\t5\tdocs/api.md\n5\t2\tsrc/lib.rs";
+      let code_heavy = "50\t20\tsrc/api.rs\n30\t10\tsrc/client.rs\n2\t1\tREADME.md";
+
+      let mut config = CommitConfig::default();
+      assert_eq!(ScopeAnalyzer::select_analysis_prompt_variant(docs_heavy, &config), "default");
+
+      config
+         .analysis_prompt_variants
+         .insert("docs".to_string(), "docs".to_string());
+      assert_eq!(ScopeAnalyzer::select_analysis_prompt_variant(docs_heavy, &config), "docs");
+      assert_eq!(ScopeAnalyzer::select_analysis_prompt_variant(code_heavy, &config), "default");
+   }
+
+   // Tests for deprioritize_test_files_for_type
+   #[test]
+   fn test_format_change_weights() {
+      let config = CommitConfig::default();
+      let numstat = "70\t10\tsrc/api/client.rs\n15\t5\tsrc/db/pool.rs\n1\t0\tsrc/util/misc.rs";
+      let (candidates, _) = ScopeAnalyzer::extract_scope(numstat, &config);
+      assert_eq!(
+         ScopeAnalyzer::format_change_weights(&candidates).as_deref(),
+         Some("79% api, 20% db")
+      );
+      assert_eq!(ScopeAnalyzer::format_change_weights(&[]), None);
+   }
+
+   #[test]
+   fn test_is_test_path() {
+      assert!(ScopeAnalyzer::is_test_path("tests/client_test.rs"));
+      assert!(ScopeAnalyzer::is_test_path("src/api/tests/mod.rs"));
+      assert!(ScopeAnalyzer::is_test_path("pkg/util/strings_test.go"));
+      assert!(!ScopeAnalyzer::is_test_path("src/api/client.rs"));
+      assert!(!ScopeAnalyzer::is_test_path("src/testing_utils.rs"));
+   }
+
+   #[test]
+   fn test_deprioritized_tests_weight_scope() {
+      let config = CommitConfig { deprioritize_test_files_for_type: true, ..default_config() };
+      let mut analyzer = ScopeAnalyzer::new();
+      analyzer.process_numstat_line("40\t0\ttests/api/client_test.rs", &config);
+
+      assert_eq!(analyzer.total_lines, 10);
+   }
+
+   #[test]
+   fn test_reconcile_test_type_feature_with_tests_yields_feat() {
+      let config = CommitConfig { deprioritize_test_files_for_type: true, ..default_config() };
+      // Tests outweigh the feature code by raw line count
+      let numstat = "40\t0\tsrc/api/client.rs\n80\t0\ttests/client_test.rs";
+      let analysis = test_analysis(&[Some(ChangelogCategory::Added), None]);
+
+      let reconciled = ScopeAnalyzer::reconcile_test_type(&analysis, numstat, &config);
+      assert_eq!(reconciled.map(|t| t.to_string()), Some("feat".to_string()));
+   }
+
+   #[test]
+   fn test_reconcile_test_type_follows_analysis_details() {
+      let config = CommitConfig { deprioritize_test_files_for_type: true, ..default_config() };
+      let numstat = "40\t0\tsrc/api/client.rs\n80\t0\ttests/client_test.rs";
+      let reconcile = |categories: &[Option<ChangelogCategory>]| {
+         ScopeAnalyzer::reconcile_test_type(&test_analysis(categories), numstat, &config)
+            .map(|t| t.to_string())
+      };
+
+      // A fix with its regression test
+      assert_eq!(
+         reconcile(&[None, Some(ChangelogCategory::Fixed), Some(ChangelogCategory::Added)]),
+         Some("fix".to_string())
+      );
+      // A refactor with tests says nothing changelog-worthy: `test` stands
+      assert_eq!(reconcile(&[None, None]), None);
+      assert_eq!(reconcile(&[Some(ChangelogCategory::Changed)]), None);
+   }
+
+   /// `test`-typed analysis with one detail per entry of `categories`
+   fn test_analysis(categories: &[Option<ChangelogCategory>]) -> ConventionalAnalysis {
+      ConventionalAnalysis {
+         commit_type: CommitType::new("test").unwrap(),
+         scope:       None,
+         details:     categories
+            .iter()
+            .map(|category| crate::types::AnalysisDetail {
+               text:               "Updated the client.".to_string(),
+               changelog_category: *category,
+               user_visible:       category.is_some(),
+            })
+            .collect(),
+         issue_refs:  Vec::new(),
+         rationale:   None,
+      }
+   }
+
+   #[test]
+   fn test_reconcile_tooling_type_maps_dominant_tooling_files() {
+      let config = default_config();
+      let feat = CommitType::new("feat").unwrap();
+      let reconcile = |numstat: &str| {
+         ScopeAnalyzer::reconcile_tooling_type(&feat, numstat, &config).map(|t| t.to_string())
+      };
+
+      assert_eq!(
+         reconcile("12\t3\t.github/workflows/release.yml\n4\t1\t.github/workflows/ci.yml"),
+         Some("ci".to_string())
+      );
+      assert_eq!(
+         reconcile("20\t8\tDockerfile\n6\t0\tservices/api/Dockerfile\n2\t0\tsrc/main.rs"),
+         Some("build".to_string())
+      );
+      assert_eq!(reconcile("3\t1\t.editorconfig"), Some("chore".to_string()));
+
+      // Tooling beside a real code change leaves the model's type alone
+      assert_eq!(reconcile("4\t0\t.github/workflows/ci.yml\n30\t2\tsrc/lib.rs"), None);
+      // Already the tooling type
+      let ci = CommitType::new("ci").unwrap();
+      assert!(
+         ScopeAnalyzer::reconcile_tooling_type(&ci, "1\t1\t.gitlab-ci.yml", &config).is_none()
+      );
+      let config = CommitConfig { tooling_types: Default::default(), ..default_config() };
+      assert!(
+         ScopeAnalyzer::reconcile_tooling_type(&feat, "3\t1\t.editorconfig", &config).is_none()
+      );
+
+      // A lower bar lets CI config beside a small code change win
+      let config = CommitConfig { tooling_dominance_percent: 50, ..default_config() };
+      let numstat = "12\t0\t.github/workflows/ci.yml\n6\t2\tsrc/lib.rs";
+      assert_eq!(
+         ScopeAnalyzer::reconcile_tooling_type(&feat, numstat, &config).map(|t| t.to_string()),
+         Some("ci".to_string())
+      );
+   }
+
+   #[test]
+   fn test_reconcile_docs_type_outside_docs_dir() {
+      let config = default_config();
+      let feat = CommitType::new("feat").unwrap();
+      let numstat = "12\t3\tsrc/foo/README.md";
+
+      assert!(ScopeAnalyzer::is_docs_only(numstat));
+      assert_eq!(ScopeAnalyzer::likely_commit_type(numstat), Some("docs"));
+      let reconciled = ScopeAnalyzer::reconcile_docs_type(&feat, numstat, &config);
+      assert_eq!(reconciled.map(|t| t.to_string()), Some("docs".to_string()));
+
+      // Any depth of docs/doc dir, and reStructuredText/AsciiDoc
+      let numstat = "4\t0\tcrates/core/doc/diagram.svg\n2\t2\tsrc/guide.rst\n1\t0\tapi.ADOC";
+      assert!(ScopeAnalyzer::reconcile_docs_type(&feat, numstat, &config).is_some());
+
+      // One code file is enough to leave the type alone
+      let numstat = "12\t3\tsrc/foo/README.md\n1\t1\tsrc/foo/lib.rs";
+      assert!(!ScopeAnalyzer::is_docs_only(numstat));
+      assert!(ScopeAnalyzer::reconcile_docs_type(&feat, numstat, &config).is_none());
+
+      let config = CommitConfig { docs_only_as_docs: false, ..default_config() };
+      assert!(
+         ScopeAnalyzer::reconcile_docs_type(&feat, "1\t0\tsrc/foo/README.md", &config).is_none()
+      );
+      assert!(!ScopeAnalyzer::is_doc_path("src/docsite/main.rs"));
+
+      // Changelogs are documentation, but a changelog update isn't forced to `docs`
+      assert!(ScopeAnalyzer::is_doc_path("CHANGELOG.md"));
+      assert!(!ScopeAnalyzer::is_docs_only("8\t0\tCHANGELOG.md"));
+      assert!(ScopeAnalyzer::is_docs_only("8\t0\tdocs/guide.md"));
+      assert!(ScopeAnalyzer::is_doc_path("crates/core/README"));
+   }
+
+   #[test]
+   fn test_scope_candidate_cap_and_threshold() {
+      let numstat = ["auth", "billing", "users", "orders", "search", "export", "import"]
+         .iter()
+         .map(|module| format!("10\t0\tsrc/api/{module}/mod.rs"))
+         .collect::<Vec<_>>()
+         .join("\n");
+      let suggestions = |config: &CommitConfig| {
+         let (scopes, is_wide) = scope_candidates_prompt(&numstat, config);
+         assert!(!is_wide, "{scopes}");
+         scopes.matches("confidence)").count()
+      };
+
+      assert_eq!(suggestions(&default_config()), 5);
+      assert_eq!(suggestions(&CommitConfig { max_scope_candidates: 8, ..default_config() }), 8);
+      assert_eq!(suggestions(&CommitConfig { max_scope_candidates: 2, ..default_config() }), 2);
+      // Each module holds ~14% of the change; only `api` clears 20%
+      assert_eq!(suggestions(&CommitConfig { min_scope_percentage: 20.0, ..default_config() }), 1);
+   }
+
+   #[test]
+   fn test_deletion_heavy_note() {
+      let config = default_config();
+      let numstat = "0\t420\tsrc/legacy/export.rs\n3\t18\tsrc/lib.rs\n0\t60\tCargo.lock";
+
+      let note = ScopeAnalyzer::deletion_heavy_note(numstat, &config).unwrap();
+      assert!(note.contains("-438/+3"), "{note}");
+      assert!(note.contains("`refactor`") && note.contains("\"removed\""), "{note}");
+
+      // Balanced rewrites, small cleanups, and the disabled setting get no note
+      let rewrite = "300\t420\tsrc/legacy/export.rs";
+      assert!(ScopeAnalyzer::deletion_heavy_note(rewrite, &config).is_none());
+      assert!(ScopeAnalyzer::deletion_heavy_note("0\t12\tsrc/lib.rs", &config).is_none());
+      // The suggested verb follows `summary_tense`
+      let imperative = CommitConfig { summary_tense: Tense::Imperative, ..default_config() };
+      let note = ScopeAnalyzer::deletion_heavy_note(numstat, &imperative).unwrap();
+      assert!(note.contains("\"remove\"") && !note.contains("\"removed\""), "{note}");
+
+      let config = CommitConfig { deletion_heavy_ratio: 0, ..default_config() };
+      assert!(ScopeAnalyzer::deletion_heavy_note(numstat, &config).is_none());
+   }
+
+   #[test]
+   fn test_reconcile_test_type_disabled() {
+      let config = default_config();
+      let numstat = "40\t0\tsrc/api/client.rs\n80\t0\ttests/client_test.rs";
+      let analysis = test_analysis(&[Some(ChangelogCategory::Added)]);
+
+      assert!(ScopeAnalyzer::reconcile_test_type(&analysis, numstat, &config).is_none());
+   }
+
+   #[test]
+   fn test_reconcile_test_type_tests_only() {
+      let config = CommitConfig { deprioritize_test_files_for_type: true, ..default_config() };
+      let numstat = "80\t0\ttests/client_test.rs\n10\t2\tsrc/api_test.rs";
+      let analysis = test_analysis(&[Some(ChangelogCategory::Added)]);
+
+      assert!(ScopeAnalyzer::reconcile_test_type(&analysis, numstat, &config).is_none());
+   }
+
+   const DOC_COMMENT_DIFF: &str = "diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -10,3 +10,4 @@ pub struct Parser {
+-/// Parse the input
++/// Parse the input into tokens
++///
++/// Returns an error on unterminated strings
+ pub fn parse(input: &str) -> Result<Vec<Token>> {
+";
+
+   #[test]
+   fn test_change_profile_doc_comment_only_hunk() {
+      let profile = classify_change_profile(DOC_COMMENT_DIFF);
+      assert!((profile.docs - 1.0).abs() < f32::EPSILON, "{profile:?}");
+      assert_eq!(profile.code, 0.0);
+   }
+
+   #[test]
+   fn test_change_profile_mixed() {
+      let code = "diff --git a/src/parse.rs b/src/parse.rs
+--- a/src/parse.rs
++++ b/src/parse.rs
+@@ -1,2 +1,2 @@
+-let x = *ptr;
++let x = 1; // no deref
+@@ -20,1 +20,1 @@
+-// old note
++// new note
+";
+      let example = "diff --git a/examples/basic.rs b/examples/basic.rs
+--- a/examples/basic.rs
++++ b/examples/basic.rs
+@@ -1,1 +1,2 @@
++fn main() {}
++
+";
+      let diff = format!("{DOC_COMMENT_DIFF}{code}{example}");
+      let profile = classify_change_profile(&diff);
+
+      // 4 doc-comment lines and 2 example lines against 4 code lines
+      assert!((profile.docs - 0.6).abs() < 0.001, "{profile:?}");
+      assert!((profile.code - 0.4).abs() < 0.001, "{profile:?}");
+      let note = profile.format_for_prompt().unwrap();
+      assert!(note.contains("docs 60%, code 40%"), "{note}");
+   }
+
+   #[test]
+   fn test_is_dependency_file() {
+      for path in ["Cargo.lock", "web/bun.lock", "bun.lockb", "npm-shrinkwrap.json", "go.sum"] {
+         assert!(is_lockfile(path) && is_dependency_file(path), "{path}");
+      }
+      for path in ["pyproject.toml", "Pipfile", "crates/core/Cargo.toml", "pom.xml"] {
+         assert!(!is_lockfile(path) && is_dependency_file(path), "{path}");
+      }
+      assert!(!is_dependency_file("src/lock.rs"));
+      assert!(!is_dependency_file("config.toml"));
+   }
+
+   #[test]
+   fn test_tooling_kind() {
+      assert_eq!(tooling_kind(".github/workflows/ci.yml"), Some("ci"));
+      assert_eq!(tooling_kind("Jenkinsfile"), Some("ci"));
+      assert_eq!(tooling_kind("services/api/Dockerfile"), Some("build"));
+      assert_eq!(tooling_kind(".gitattributes"), Some("settings"));
+      assert_eq!(tooling_kind("config/settings.YAML"), Some("config"));
+      assert_eq!(tooling_kind("src/workflows.rs"), None);
+   }
+
+   #[test]
+   fn test_change_profile_from_stat() {
+      let stat = " docs/guide.mdx | 6 +++---\n README.rst | 2 +-\n Cargo.toml | 2 +-\n src/lib.rs \
+                  | 10 +++++-----\n 4 files changed, 10 insertions(+), 10 deletions(-)";
+      let profile = ChangeProfile::from_stat(stat);
+      assert!((profile.docs - 0.4).abs() < 0.001, "{profile:?}");
+      assert!((profile.deps - 0.1).abs() < 0.001, "{profile:?}");
+      assert!((profile.code - 0.5).abs() < 0.001, "{profile:?}");
+
+      // Code-only changes need no prompt note
+      let code_only = ChangeProfile::from_stat(" src/lib.rs | 3 ++-");
+      assert!(code_only.format_for_prompt().is_none());
+      assert!(ChangeProfile::from_stat("").is_empty());
+   }
 }
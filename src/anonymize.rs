@@ -0,0 +1,193 @@
+//! Diff anonymization for sharing bug reports
+//!
+//! Replaces identifiers with `ident_N` and double-quoted string literals with
+//! `"str_N"`, consistently across the whole diff, so a misclassified change
+//! can be shared without its code. Diff structure (file headers, hunk ranges,
+//! `+`/`-`/context markers), keywords, numbers and punctuation are kept, as
+//! are file paths, which scope and type detection depend on.
+
+use std::collections::HashMap;
+
+use crate::diff::normalize_line_endings;
+
+/// Words left as-is: keywords and builtin names common across languages, so
+/// the anonymized diff still reads as code of the same shape
+const KEYWORDS: &str =
+   "abstract as async await bool break case catch char class const continue crate def default \
+    defer del delete do dyn elif else enum except export extends extern false final finally float \
+    fn for from func function go if impl implements import in int interface is lambda let loop \
+    match mod move mut new nil None not null or and package pass private protected pub public \
+    raise ref return self Self static str string struct super switch this throw trait true True \
+    False try type typeof undefined unsafe use var void where while with yield i8 i16 i32 i64 \
+    i128 isize u8 u16 u32 u64 u128 usize f32 f64";
+
+/// Consistent placeholder assignment for one diff
+#[derive(Debug, Default)]
+struct Anonymizer {
+   idents:  HashMap<String, usize>,
+   strings: HashMap<String, usize>,
+}
+
+impl Anonymizer {
+   fn ident(&mut self, word: &str) -> String {
+      if KEYWORDS.split_whitespace().any(|keyword| keyword == word) {
+         return word.to_string();
+      }
+      let next = self.idents.len() + 1;
+      let n = *self.idents.entry(word.to_string()).or_insert(next);
+      format!("ident_{n}")
+   }
+
+   fn string(&mut self, literal: &str) -> String {
+      let next = self.strings.len() + 1;
+      let n = *self.strings.entry(literal.to_string()).or_insert(next);
+      format!("\"str_{n}\"")
+   }
+
+   /// Anonymize one line of code (without its diff marker)
+   fn code(&mut self, code: &str) -> String {
+      let mut out = String::with_capacity(code.len());
+      let mut chars = code.char_indices().peekable();
+
+      while let Some((start, c)) = chars.next() {
+         if c == '"' {
+            // String literal, honoring backslash escapes; an unterminated one
+            // runs to the end of the line
+            let mut end = code.len();
+            let mut escaped = false;
+            for (i, c) in chars.by_ref() {
+               if escaped {
+                  escaped = false;
+               } else if c == '\\' {
+                  escaped = true;
+               } else if c == '"' {
+                  end = i + 1;
+                  break;
+               }
+            }
+            out.push_str(&self.string(&code[start..end]));
+         } else if c.is_alphabetic() || c == '_' {
+            let mut end = start + c.len_utf8();
+            while let Some(&(i, c)) = chars.peek() {
+               if !(c.is_alphanumeric() || c == '_') {
+                  break;
+               }
+               end = i + c.len_utf8();
+               chars.next();
+            }
+            out.push_str(&self.ident(&code[start..end]));
+         } else if c.is_ascii_digit() {
+            // Keep numbers whole so suffixes like `10u32` aren't split into
+            // an identifier
+            out.push(c);
+            while let Some(&(_, c)) = chars.peek() {
+               if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                  break;
+               }
+               out.push(c);
+               chars.next();
+            }
+         } else {
+            out.push(c);
+         }
+      }
+      out
+   }
+
+   /// Hunk header: keep the ranges, anonymize the trailing section heading
+   fn hunk_header(&mut self, line: &str) -> String {
+      let Some(close) = line[2..].find("@@").map(|i| i + 4) else {
+         return line.to_string();
+      };
+      format!("{}{}", &line[..close], self.code(&line[close..]))
+   }
+}
+
+/// Anonymize a unified diff; see the module docs for what is kept
+pub fn anonymize_diff(diff: &str) -> String {
+   let mut anonymizer = Anonymizer::default();
+   let mut out = String::with_capacity(diff.len());
+   let mut in_hunk = false;
+
+   for line in normalize_line_endings(diff).lines() {
+      if line.starts_with("diff --git ") {
+         in_hunk = false;
+         out.push_str(line);
+      } else if line.starts_with("@@") {
+         in_hunk = true;
+         out.push_str(&anonymizer.hunk_header(line));
+      } else if !in_hunk || line.starts_with('\\') {
+         // File headers and `\ No newline at end of file`
+         out.push_str(line);
+      } else if let Some(marker) = line.chars().next().filter(|c| matches!(c, '+' | '-' | ' ')) {
+         out.push(marker);
+         out.push_str(&anonymizer.code(&line[1..]));
+      } else {
+         out.push_str(&anonymizer.code(line));
+      }
+      out.push('\n');
+   }
+
+   if !diff.ends_with('\n') {
+      out.pop();
+   }
+   out
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+   use crate::diff::parse_diff;
+
+   const DIFF: &str = "\
+diff --git a/src/auth.rs b/src/auth.rs
+index 1111111..2222222 100644
+--- a/src/auth.rs
++++ b/src/auth.rs
+@@ -1,4 +1,5 @@ fn check_token(token: &str) -> bool {
+ fn check_token(token: &str) -> bool {
+-    token == \"secret\"
++    let expected = load_secret(\"AUTH_KEY\");
++    token == expected && token.len() > 10u32
+ }
+@@ -20,2 +21,2 @@ impl Session {
+-    log(\"secret\");
++    log(\"say \\\"hi\\\"\");
+\\ No newline at end of file
+";
+
+   #[test]
+   fn test_anonymize_preserves_diff_structure() {
+      let out = anonymize_diff(DIFF);
+
+      assert_eq!(out.lines().count(), DIFF.lines().count());
+      for (original, anonymized) in DIFF.lines().zip(out.lines()) {
+         assert_eq!(original.chars().next(), anonymized.chars().next(), "{anonymized}");
+      }
+      assert!(out.starts_with(
+         "diff --git a/src/auth.rs b/src/auth.rs\nindex 1111111..2222222 100644\n--- \
+          a/src/auth.rs\n+++ b/src/auth.rs\n@@ -1,4 +1,5 @@ fn ident_1(ident_2: &str) -> bool {\n"
+      ));
+      assert!(out.contains("\n@@ -20,2 +21,2 @@ impl ident_"));
+      assert!(out.contains("\n\\ No newline at end of file\n"));
+      assert_eq!(parse_diff(&out)[0].additions, 3);
+      assert_eq!(parse_diff(&out)[0].deletions, 2);
+   }
+
+   #[test]
+   fn test_anonymize_is_consistent() {
+      let out = anonymize_diff(DIFF);
+      let lines: Vec<&str> = out.lines().collect();
+
+      // Same identifier, same placeholder: `check_token` and `token`
+      assert_eq!(lines[5], " fn ident_1(ident_2: &str) -> bool {");
+      assert_eq!(lines[6], "-    ident_2 == \"str_1\"");
+      assert_eq!(lines[7], "+    let ident_3 = ident_4(\"str_2\");");
+      assert_eq!(lines[8], "+    ident_2 == ident_3 && ident_2.ident_5() > 10u32");
+      // Same literal, same placeholder; escapes stay inside one literal
+      assert_eq!(lines[11], "-    ident_7(\"str_1\");");
+      assert_eq!(lines[12], "+    ident_7(\"str_3\");");
+
+      assert!(!out.contains("secret") && !out.contains("AUTH_KEY") && !out.contains("Session"));
+   }
+}
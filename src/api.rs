@@ -3,11 +3,13 @@ use std::{path::Path, thread, time::Duration};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-   config::{CommitConfig, ResolvedApiMode},
+   analysis::ChangeProfile,
+   config::{BodyStyle, CommitConfig, LengthMetric, ResolvedApiMode, Tense},
    error::{CommitGenError, Result},
+   rate_limit::{RateLimiter, parse_retry_after, rate_limit_error},
    templates,
    tokens::TokenCounter,
-   types::{CommitSummary, ConventionalAnalysis},
+   types::{AnalysisDetail, CommitSummary, CommitType, ConventionalAnalysis},
 };
 
 // Prompts now loaded from config instead of compile-time constants
@@ -23,19 +25,39 @@ pub struct AnalysisContext<'a> {
    pub common_scopes:   Option<&'a str>,
    /// Project context (language, framework) for terminology
    pub project_context: Option<&'a str>,
+   /// Share of each kind of change, from [`ChangeProfile::format_for_prompt`]
+   pub change_profile:  Option<&'a str>,
    /// Debug output directory for saving raw I/O
    pub debug_output:    Option<&'a Path>,
    /// Prefix for debug output files to avoid collisions
    pub debug_prefix:    Option<&'a str>,
 }
 
+/// Whether `LLM_GIT_NO_NETWORK` is set to a truthy value
+pub(crate) fn network_disabled_by_env() -> bool {
+   std::env::var("LLM_GIT_NO_NETWORK")
+      .is_ok_and(|v| !matches!(v.trim().to_lowercase().as_str(), "" | "0" | "false" | "no"))
+}
+
+/// Fail fast when network access is disabled by config or environment
+///
+/// Checked before any HTTP client is built, so sandboxed runs get a clear
+/// error instead of hanging on a connect timeout.
+pub fn ensure_network_allowed(config: &CommitConfig) -> Result<()> {
+   if config.offline_strict || network_disabled_by_env() {
+      return Err(CommitGenError::NetworkDisabled(config.api_base_url.clone()));
+   }
+   Ok(())
+}
+
 /// Build HTTP client with timeouts from config
-fn build_client(config: &CommitConfig) -> reqwest::blocking::Client {
-   reqwest::blocking::Client::builder()
+pub fn build_client(config: &CommitConfig) -> Result<reqwest::blocking::Client> {
+   ensure_network_allowed(config)?;
+   Ok(reqwest::blocking::Client::builder()
       .timeout(Duration::from_secs(config.request_timeout_secs))
       .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
       .build()
-      .expect("Failed to build HTTP client")
+      .expect("Failed to build HTTP client"))
 }
 
 fn debug_filename(prefix: Option<&str>, name: &str) -> String {
@@ -49,14 +71,125 @@ fn response_snippet(body: &str, limit: usize) -> String {
    if body.is_empty() {
       return "<empty response body>".to_string();
    }
-   let mut snippet = body.trim().to_string();
+   let mut snippet = if ResponseBodyKind::of(body) == ResponseBodyKind::Html {
+      strip_html_tags(body)
+   } else {
+      body.trim().to_string()
+   };
    if snippet.len() > limit {
-      snippet.truncate(limit);
+      snippet.truncate(snippet.floor_char_boundary(limit));
       snippet.push_str("...");
    }
    snippet
 }
 
+/// Visible text of an HTML page: tags, scripts and styles dropped, whitespace
+/// collapsed
+fn strip_html_tags(html: &str) -> String {
+   let mut text = String::with_capacity(html.len());
+   let mut rest = html;
+   while let Some(open) = rest.find('<') {
+      text.push_str(&rest[..open]);
+      text.push(' ');
+      let tag = &rest[open..];
+      let lower = tag.get(..7).unwrap_or(tag).to_ascii_lowercase();
+      let skip_to = if lower.starts_with("<script") {
+         "</script>"
+      } else if lower.starts_with("<style") {
+         "</style>"
+      } else {
+         ">"
+      };
+      rest = match tag.to_ascii_lowercase().find(skip_to) {
+         Some(end) => &tag[end + skip_to.len()..],
+         None => "",
+      };
+   }
+   text.push_str(rest);
+   text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// What an API response body that should be JSON looks like
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseBodyKind {
+   Json,
+   /// Starts like JSON but doesn't parse (cut off or malformed)
+   MalformedJson,
+   /// An HTML page, e.g. a proxy's login or error page
+   Html,
+   Empty,
+   Text,
+}
+
+impl ResponseBodyKind {
+   pub fn of(body: &str) -> Self {
+      let trimmed = body.trim_start();
+      let head = trimmed
+         .get(..trimmed.floor_char_boundary(512))
+         .unwrap_or(trimmed)
+         .to_ascii_lowercase();
+      if trimmed.is_empty() {
+         Self::Empty
+      } else if serde_json::from_str::<serde::de::IgnoredAny>(body).is_ok() {
+         Self::Json
+      } else if head.starts_with("<!doctype html") || head.contains("<html") {
+         Self::Html
+      } else if trimmed.starts_with(['{', '[']) {
+         Self::MalformedJson
+      } else {
+         Self::Text
+      }
+   }
+
+   pub const fn describe(self) -> &'static str {
+      match self {
+         Self::Json => "JSON",
+         Self::MalformedJson => "malformed JSON",
+         Self::Html => "an HTML page",
+         Self::Empty => "an empty body",
+         Self::Text => "plain text",
+      }
+   }
+}
+
+/// `Content-Type` of a response, for error reports
+pub fn response_content_type(headers: &reqwest::header::HeaderMap) -> String {
+   headers
+      .get(reqwest::header::CONTENT_TYPE)
+      .and_then(|value| value.to_str().ok())
+      .unwrap_or("none")
+      .to_string()
+}
+
+/// [`CommitGenError::NonJsonResponse`] for a non-empty body that isn't JSON,
+/// e.g. a misrouted proxy's HTML login page; empty bodies are left to the
+/// caller's retry
+pub fn non_json_error(
+   status: reqwest::StatusCode,
+   content_type: &str,
+   body: &str,
+) -> Option<CommitGenError> {
+   let kind = ResponseBodyKind::of(body);
+   matches!(kind, ResponseBodyKind::Html | ResponseBodyKind::MalformedJson | ResponseBodyKind::Text)
+      .then(|| CommitGenError::NonJsonResponse {
+         status:       status.as_u16(),
+         content_type: content_type.to_string(),
+         kind:         kind.describe(),
+         snippet:      response_snippet(body, 200),
+      })
+}
+
+/// Whether a failed attempt is worth repeating. Not when the network is off
+/// by policy, nor for a non-JSON body outside 5xx: a misrouted proxy won't
+/// start answering JSON.
+const fn should_retry(err: &CommitGenError) -> bool {
+   match err {
+      CommitGenError::NetworkDisabled(_) | CommitGenError::ToolCallUnsupported { .. } => false,
+      CommitGenError::NonJsonResponse { status, .. } => *status >= 500,
+      _ => true,
+   }
+}
+
 fn save_debug_output(debug_dir: Option<&Path>, filename: &str, content: &str) -> Result<()> {
    let Some(dir) = debug_dir else {
       return Ok(());
@@ -148,7 +281,8 @@ struct Tool {
 struct ApiRequest {
    model:       String,
    max_tokens:  u32,
-   temperature: f32,
+   #[serde(skip_serializing_if = "Option::is_none")]
+   temperature: Option<f32>,
    tools:       Vec<Tool>,
    #[serde(skip_serializing_if = "Option::is_none")]
    tool_choice: Option<serde_json::Value>,
@@ -159,7 +293,8 @@ struct ApiRequest {
 struct AnthropicRequest {
    model:       String,
    max_tokens:  u32,
-   temperature: f32,
+   #[serde(skip_serializing_if = "Option::is_none")]
+   temperature: Option<f32>,
    #[serde(skip_serializing_if = "Option::is_none")]
    system:      Option<String>,
    tools:       Vec<AnthropicTool>,
@@ -219,6 +354,17 @@ struct ResponseMessage {
    content:    Option<String>,
 }
 
+impl ResponseMessage {
+   /// First tool call whose name matches `name`; models may emit several
+   /// calls, or put the wanted one after others
+   fn find_tool_call(&self, name: &str) -> Option<&ToolCall> {
+      self
+         .tool_calls
+         .iter()
+         .find(|call| call.function.name.ends_with(name))
+   }
+}
+
 #[derive(Debug, Deserialize)]
 struct ApiResponse {
    choices: Vec<Choice>,
@@ -229,8 +375,32 @@ struct SummaryOutput {
    summary: String,
 }
 
+/// Arguments of the combined `create_commit_message` tool used by fast mode
+#[derive(Debug, Deserialize)]
+struct FastCommitOutput {
+   summary:  String,
+   #[serde(flatten)]
+   analysis: ConventionalAnalysis,
+}
+
 /// Retry an API call with exponential backoff
-pub fn retry_api_call<F, T>(config: &CommitConfig, mut f: F) -> Result<T>
+pub fn retry_api_call<F, T>(config: &CommitConfig, f: F) -> Result<T>
+where
+   F: FnMut() -> Result<(bool, Option<T>)>,
+{
+   retry_api_call_with(config, &RateLimiter::unbounded(), f)
+}
+
+/// Retry an API call, coordinating backoff through a shared [`RateLimiter`]
+///
+/// Each attempt waits for any global pause and claims an in-flight slot first.
+/// Rate-limit errors pause every worker sharing `limiter` instead of only the
+/// one that hit the limit.
+pub fn retry_api_call_with<F, T>(
+   config: &CommitConfig,
+   limiter: &RateLimiter,
+   mut f: F,
+) -> Result<T>
 where
    F: FnMut() -> Result<(bool, Option<T>)>,
 {
@@ -238,8 +408,13 @@ where
 
    loop {
       attempt += 1;
+      config.api_counter.record_attempt(attempt);
 
-      match f() {
+      let permit = limiter.acquire();
+      let outcome = f();
+      drop(permit);
+
+      match outcome {
          Ok((false, Some(result))) => return Ok(result),
          Ok((false, None)) => {
             return Err(CommitGenError::Other("API call failed without result".to_string()));
@@ -261,6 +436,25 @@ where
                source:  Box::new(CommitGenError::Other("Max retries exceeded".to_string())),
             });
          },
+         Err(e) if !should_retry(&e) => return Err(e),
+         Err(CommitGenError::RateLimited { status, retry_after, .. })
+            if attempt < config.max_retries =>
+         {
+            // The pause is enforced by the limiter on the next acquire()
+            let delay =
+               limiter.record_rate_limit(retry_after, attempt - 1, config.initial_backoff_ms);
+            eprintln!(
+               "{}",
+               crate::style::warning(&format!(
+                  "Rate limited (HTTP {status}) - pausing all requests for {}ms, concurrency now \
+                   {} (retry {}/{})",
+                  delay.as_millis(),
+                  limiter.concurrency(),
+                  attempt,
+                  config.max_retries
+               ))
+            );
+         },
          Err(e) => {
             if attempt < config.max_retries {
                let backoff_ms = config.initial_backoff_ms * (1 << (attempt - 1));
@@ -310,7 +504,93 @@ pub fn format_types_description(config: &CommitConfig) -> String {
    out
 }
 
+/// Analysis prompt as sent to the model
+pub fn render_analysis_request(
+   stat: &str,
+   diff: &str,
+   scope_candidates: &str,
+   ctx: &AnalysisContext<'_>,
+   config: &CommitConfig,
+) -> Result<templates::PromptParts> {
+   let types_desc = format_types_description(config);
+   let parts = templates::render_analysis_prompt(&templates::AnalysisParams {
+      variant: &config.analysis_prompt_variant,
+      stat,
+      diff,
+      scope_candidates,
+      recent_commits: ctx.recent_commits,
+      common_scopes: ctx.common_scopes,
+      types_description: Some(&types_desc),
+      project_context: ctx.project_context,
+      change_profile: ctx.change_profile,
+      body_tense: config.body_tense,
+      body_paragraphs: config.body_style == BodyStyle::Paragraphs,
+   })?;
+   Ok(templates::PromptParts {
+      system: parts.system,
+      user:   analysis_user_content(ctx, diff, parts.user),
+   })
+}
+
+/// Prefix the rendered analysis prompt with user context and explicit
+/// deleted/renamed file lines from the parsed diff
+fn analysis_user_content(ctx: &AnalysisContext<'_>, diff: &str, prompt: String) -> String {
+   use std::fmt::Write;
+   let mut out = String::new();
+   if let Some(user_ctx) = ctx.user_context {
+      let _ = write!(out, "ADDITIONAL CONTEXT FROM USER:\n{user_ctx}\n\n");
+   }
+   if let Some(file_ctx) = crate::diff::structural_change_context(&crate::diff::parse_diff(diff)) {
+      let _ = write!(out, "{file_ctx}\n\n");
+   }
+   out.push_str(&prompt);
+   out
+}
+
+/// `user_context` with a note that a previous attempt `went_wrong` and what
+/// to do instead, for re-asking the model
+pub fn retry_context(user_context: Option<&str>, went_wrong: &str, instead: &str) -> String {
+   let instruction = format!("A previous attempt {went_wrong}. {instead}");
+   match user_context {
+      Some(user_context) => format!("{user_context}\n\n{instruction}"),
+      None => instruction,
+   }
+}
+
+/// Whether an analysis without details should be re-requested: the diff
+/// changes at least `min_diff_for_body_lines` lines (0 disables the check)
+fn needs_details_retry(analysis: &ConventionalAnalysis, diff: &str, config: &CommitConfig) -> bool {
+   if !analysis.details.is_empty() || config.min_diff_for_body_lines == 0 {
+      return false;
+   }
+   let changed_lines: usize = crate::diff::parse_diff(diff)
+      .iter()
+      .map(|file| file.additions + file.deletions)
+      .sum();
+   changed_lines >= config.min_diff_for_body_lines
+}
+
+/// Add the optional `rationale` property to an analysis tool's properties
+/// when `capture_rationale` is on (`--explain`)
+pub fn add_rationale_property(properties: &mut serde_json::Value, config: &CommitConfig) {
+   if config.capture_rationale
+      && let Some(properties) = properties.as_object_mut()
+   {
+      properties.insert(
+         "rationale".to_string(),
+         serde_json::json!({
+            "type": "string",
+            "description": "One or two sentences on why this type and scope fit the change"
+         }),
+      );
+   }
+}
+
 /// Generate conventional commit analysis using OpenAI-compatible API
+///
+/// An analysis with an empty `details` array for a diff of at least
+/// `min_diff_for_body_lines` changed lines is requested once more, asking for
+/// 2-4 detail items; small diffs may keep an empty body.
 pub fn generate_conventional_analysis<'a>(
    stat: &'a str,
    diff: &'a str,
@@ -318,15 +598,53 @@ pub fn generate_conventional_analysis<'a>(
    scope_candidates_str: &'a str,
    ctx: &AnalysisContext<'a>,
    config: &'a CommitConfig,
+) -> Result<ConventionalAnalysis> {
+   let analysis =
+      request_conventional_analysis(stat, diff, model_name, scope_candidates_str, ctx, config)?;
+   if !needs_details_retry(&analysis, diff, config) {
+      return Ok(analysis);
+   }
+
+   crate::style::warn("Analysis returned no details for a sizeable diff; asking again for 2-4");
+   let user_context = retry_context(
+      ctx.user_context,
+      "returned no detail items for this change",
+      "Return 2-4 detail items describing what changed and why.",
+   );
+   let retry_ctx = AnalysisContext { user_context: Some(&user_context), ..*ctx };
+   let retried = request_conventional_analysis(
+      stat,
+      diff,
+      model_name,
+      scope_candidates_str,
+      &retry_ctx,
+      config,
+   )?;
+   // Keep the first answer if the retry had nothing more to say
+   Ok(if retried.details.is_empty() {
+      analysis
+   } else {
+      retried
+   })
+}
+
+/// One analysis request, with the usual API retries
+fn request_conventional_analysis<'a>(
+   stat: &'a str,
+   diff: &'a str,
+   model_name: &'a str,
+   scope_candidates_str: &'a str,
+   ctx: &AnalysisContext<'a>,
+   config: &'a CommitConfig,
 ) -> Result<ConventionalAnalysis> {
    retry_api_call(config, move || {
-      let client = build_client(config);
+      let client = build_client(config)?;
 
       // Build type enum from config
       let type_enum: Vec<&str> = config.types.keys().map(|s| s.as_str()).collect();
 
       // Define the conventional analysis tool
-      let tool = Tool {
+      let mut tool = Tool {
          tool_type: "function".to_string(),
          function:  Function {
             name:        "create_conventional_analysis".to_string(),
@@ -347,13 +665,13 @@ pub fn generate_conventional_analysis<'a>(
                   },
                   "details": {
                      "type": "array",
-                     "description": "Array of 0-6 detail items with changelog metadata.",
+                     "description": "Array of 0-6 detail items with changelog metadata. Any deleted or moved file over 100 lines must be mentioned by name.",
                      "items": {
                         "type": "object",
                         "properties": {
                            "text": {
                               "type": "string",
-                              "description": "Detail about change, starting with past-tense verb, ending with period"
+                              "description": format!("Detail about change, starting with {} verb, ending with period", config.body_tense.verb_form())
                            },
                            "changelog_category": {
                               "type": "string",
@@ -384,6 +702,7 @@ pub fn generate_conventional_analysis<'a>(
             },
          },
       };
+      add_rationale_property(&mut tool.function.parameters.properties, config);
 
       let debug_dir = ctx.debug_output;
       let debug_prefix = ctx.debug_prefix;
@@ -391,34 +710,19 @@ pub fn generate_conventional_analysis<'a>(
 
       let response_text = match mode {
          ResolvedApiMode::ChatCompletions => {
-            let types_desc = format_types_description(config);
-            let parts = templates::render_analysis_prompt(&templates::AnalysisParams {
-               variant: &config.analysis_prompt_variant,
-               stat,
-               diff,
-               scope_candidates: scope_candidates_str,
-               recent_commits: ctx.recent_commits,
-               common_scopes: ctx.common_scopes,
-               types_description: Some(&types_desc),
-               project_context: ctx.project_context,
-            })?;
-
-            let user_content = if let Some(user_ctx) = ctx.user_context {
-               format!("ADDITIONAL CONTEXT FROM USER:\n{user_ctx}\n\n{}", parts.user)
-            } else {
-               parts.user
-            };
+            let templates::PromptParts { system, user: user_content } =
+               render_analysis_request(stat, diff, scope_candidates_str, ctx, config)?;
 
             let request = ApiRequest {
                model:       model_name.to_string(),
                max_tokens:  1000,
-               temperature: config.temperature,
+               temperature: config.temperature_for(model_name),
                tools:       vec![tool],
                tool_choice: Some(
                   serde_json::json!({ "type": "function", "function": { "name": "create_conventional_analysis" } }),
                ),
                messages:    vec![
-                  Message { role: "system".to_string(), content: parts.system },
+                  Message { role: "system".to_string(), content: system },
                   Message { role: "user".to_string(), content: user_content },
                ],
             };
@@ -448,6 +752,8 @@ pub fn generate_conventional_analysis<'a>(
                .map_err(CommitGenError::HttpError)?;
 
             let status = response.status();
+            let retry_after = parse_retry_after(response.headers());
+            let content_type = response_content_type(response.headers());
             let response_text = response.text().map_err(CommitGenError::HttpError)?;
             if debug_dir.is_some() {
                save_debug_output(
@@ -457,6 +763,13 @@ pub fn generate_conventional_analysis<'a>(
                )?;
             }
 
+            if let Some(err) = rate_limit_error(status, retry_after, &response_text) {
+               return Err(err);
+            }
+            if let Some(err) = non_json_error(status, &content_type, &response_text) {
+               return Err(err);
+            }
+
             // Retry on 5xx errors
             if status.is_server_error() {
                eprintln!(
@@ -476,29 +789,14 @@ pub fn generate_conventional_analysis<'a>(
             response_text
          },
          ResolvedApiMode::AnthropicMessages => {
-            let types_desc = format_types_description(config);
-            let parts = templates::render_analysis_prompt(&templates::AnalysisParams {
-               variant: &config.analysis_prompt_variant,
-               stat,
-               diff,
-               scope_candidates: scope_candidates_str,
-               recent_commits: ctx.recent_commits,
-               common_scopes: ctx.common_scopes,
-               types_description: Some(&types_desc),
-               project_context: ctx.project_context,
-            })?;
-
-            let user_content = if let Some(user_ctx) = ctx.user_context {
-               format!("ADDITIONAL CONTEXT FROM USER:\n{user_ctx}\n\n{}", parts.user)
-            } else {
-               parts.user
-            };
+            let templates::PromptParts { system, user: user_content } =
+               render_analysis_request(stat, diff, scope_candidates_str, ctx, config)?;
 
-            let request = AnthropicRequest {
+            let mut request = AnthropicRequest {
                model:       model_name.to_string(),
                max_tokens:  1000,
-               temperature: config.temperature,
-               system:      Some(parts.system).filter(|s| !s.is_empty()),
+               temperature: config.temperature_for(model_name),
+               system:      Some(system).filter(|s| !s.is_empty()),
                tools:       vec![AnthropicTool {
                   name:         "create_conventional_analysis".to_string(),
                   description:  "Analyze changes and classify as conventional commit with type, \
@@ -518,13 +816,13 @@ pub fn generate_conventional_analysis<'a>(
                         },
                         "details": {
                            "type": "array",
-                           "description": "Array of 0-6 detail items with changelog metadata.",
+                           "description": "Array of 0-6 detail items with changelog metadata. Any deleted or moved file over 100 lines must be mentioned by name.",
                            "items": {
                               "type": "object",
                               "properties": {
                                  "text": {
                                     "type": "string",
-                                    "description": "Detail about change, starting with past-tense verb, ending with period"
+                                    "description": format!("Detail about change, starting with {} verb, ending with period", config.body_tense.verb_form())
                                  },
                                  "changelog_category": {
                                     "type": "string",
@@ -562,6 +860,7 @@ pub fn generate_conventional_analysis<'a>(
                   }],
                }],
             };
+            add_rationale_property(&mut request.tools[0].input_schema["properties"], config);
 
             if debug_dir.is_some() {
                let request_json = serde_json::to_string_pretty(&request)?;
@@ -587,6 +886,8 @@ pub fn generate_conventional_analysis<'a>(
                .map_err(CommitGenError::HttpError)?;
 
             let status = response.status();
+            let retry_after = parse_retry_after(response.headers());
+            let content_type = response_content_type(response.headers());
             let response_text = response.text().map_err(CommitGenError::HttpError)?;
             if debug_dir.is_some() {
                save_debug_output(
@@ -596,6 +897,13 @@ pub fn generate_conventional_analysis<'a>(
                )?;
             }
 
+            if let Some(err) = rate_limit_error(status, retry_after, &response_text) {
+               return Err(err);
+            }
+            if let Some(err) = non_json_error(status, &content_type, &response_text) {
+               return Err(err);
+            }
+
             if status.is_server_error() {
                eprintln!(
                   "{}",
@@ -638,34 +946,27 @@ pub fn generate_conventional_analysis<'a>(
             let message = &api_response.choices[0].message;
 
             // Find the tool call in the response
-            if !message.tool_calls.is_empty() {
-               let tool_call = &message.tool_calls[0];
-               if tool_call
-                  .function
-                  .name
-                  .ends_with("create_conventional_analysis")
-               {
-                  let args = &tool_call.function.arguments;
-                  if args.is_empty() {
-                     crate::style::warn(
-                        "Model returned empty function arguments. Model may not support function \
-                         calling properly.",
-                     );
-                     return Err(CommitGenError::Other(
-                        "Model returned empty function arguments - try using a Claude model \
-                         (sonnet/opus/haiku)"
-                           .to_string(),
-                     ));
-                  }
-                  let analysis: ConventionalAnalysis = serde_json::from_str(args).map_err(|e| {
-                     CommitGenError::Other(format!(
-                        "Failed to parse model response: {}. Response was: {}",
-                        e,
-                        args.chars().take(200).collect::<String>()
-                     ))
-                  })?;
-                  return Ok((false, Some(analysis)));
+            if let Some(tool_call) = message.find_tool_call("create_conventional_analysis") {
+               let args = &tool_call.function.arguments;
+               if args.is_empty() {
+                  crate::style::warn(
+                     "Model returned empty function arguments. Model may not support function \
+                      calling properly.",
+                  );
+                  return Err(CommitGenError::Other(
+                     "Model returned empty function arguments - try using a Claude model \
+                      (sonnet/opus/haiku)"
+                        .to_string(),
+                  ));
                }
+               let analysis: ConventionalAnalysis = serde_json::from_str(args).map_err(|e| {
+                  CommitGenError::Other(format!(
+                     "Failed to parse model response: {}. Response was: {}",
+                     e,
+                     args.chars().take(200).collect::<String>()
+                  ))
+               })?;
+               return Ok((false, Some(analysis)));
             }
 
             // Fallback: try to parse content as text
@@ -674,13 +975,12 @@ pub fn generate_conventional_analysis<'a>(
                   crate::style::warn("Model returned empty content for analysis; retrying.");
                   return Ok((true, None));
                }
-               let analysis: ConventionalAnalysis =
-                  serde_json::from_str(content.trim()).map_err(|e| {
-                     CommitGenError::Other(format!(
-                        "Failed to parse analysis content JSON: {e}. Content: {}",
-                        response_snippet(content, 500)
-                     ))
-                  })?;
+               let analysis: ConventionalAnalysis = parse_json_content(content).map_err(|e| {
+                  CommitGenError::Other(format!(
+                     "Failed to parse analysis content JSON: {e}. Content: {}",
+                     response_snippet(content, 500)
+                  ))
+               })?;
                return Ok((false, Some(analysis)));
             }
 
@@ -705,8 +1005,8 @@ pub fn generate_conventional_analysis<'a>(
                return Ok((true, None));
             }
 
-            let analysis: ConventionalAnalysis = serde_json::from_str(text_content.trim())
-               .map_err(|e| {
+            let analysis: ConventionalAnalysis =
+               parse_json_content(&text_content).map_err(|e| {
                   CommitGenError::Other(format!(
                      "Failed to parse analysis content JSON: {e}. Content: {}",
                      response_snippet(&text_content, 500)
@@ -737,13 +1037,73 @@ fn strip_type_prefix(summary: &str, commit_type: &str, scope: Option<&str>) -> S
       .to_string()
 }
 
-/// Validate summary against requirements
+/// Candidate JSON texts in a model's plain-text reply, most literal first:
+/// the whole reply, the contents of each markdown code fence (without a
+/// language tag line), then the span from the first `{` to the last `}`
+fn json_candidates(content: &str) -> Vec<&str> {
+   let trimmed = content.trim();
+   let mut candidates = vec![trimmed];
+
+   for block in trimmed.split("```").skip(1).step_by(2) {
+      let block = block.trim();
+      let (first_line, rest) = block.split_once('\n').unwrap_or((block, ""));
+      let rest = rest.trim();
+      if first_line.trim_start().starts_with(['{', '[']) || rest.is_empty() {
+         candidates.push(block);
+      } else {
+         candidates.push(rest);
+      }
+   }
+
+   if let (Some(start), Some(end)) = (trimmed.find('{'), trimmed.rfind('}'))
+      && end > start
+   {
+      candidates.push(&trimmed[start..=end]);
+   }
+   candidates
+}
+
+/// Parse a model's plain-text reply as `T`, tolerating markdown code fences and
+/// prose around the JSON. The error is the one for the reply as a whole.
+pub fn parse_json_content<T: serde::de::DeserializeOwned>(content: &str) -> serde_json::Result<T> {
+   let mut first_err = None;
+   for candidate in json_candidates(content) {
+      match serde_json::from_str(candidate) {
+         Ok(value) => return Ok(value),
+         Err(err) => {
+            first_err.get_or_insert(err);
+         },
+      }
+   }
+   Err(first_err.expect("json_candidates always includes the whole reply"))
+}
+
+/// Summary text from a reply without a tool call: its JSON `summary` (fenced
+/// or not), else the reply itself for models without function calling
+fn summary_from_content(content: &str) -> Result<String> {
+   let trimmed = content.trim();
+   match parse_json_content::<SummaryOutput>(trimmed) {
+      Ok(summary) => Ok(summary.summary),
+      // Only use plain text if it doesn't look like JSON
+      Err(e) if trimmed.starts_with('{') || trimmed.starts_with("```") => {
+         Err(CommitGenError::Other(format!(
+            "Failed to parse summary JSON: {e}. Content: {}",
+            response_snippet(trimmed, 500)
+         )))
+      },
+      Err(_) => Ok(trimmed.to_string()),
+   }
+}
+
+/// Validate summary against requirements; `profile` is only used to warn
+/// about a type the changed lines contradict
 fn validate_summary_quality(
    summary: &str,
    commit_type: &str,
-   stat: &str,
+   profile: &ChangeProfile,
+   tense: Option<Tense>,
 ) -> std::result::Result<(), String> {
-   use crate::validation::is_past_tense_verb;
+   use crate::validation::is_verb_in_tense;
 
    let first_word = summary
       .split_whitespace()
@@ -752,11 +1112,18 @@ fn validate_summary_quality(
 
    let first_word_lower = first_word.to_lowercase();
 
-   // Check past-tense verb
-   if !is_past_tense_verb(&first_word_lower) {
-      return Err(format!(
-         "must start with past-tense verb (ending in -ed/-d or irregular), got '{first_word}'"
-      ));
+   // Check verb form (unless `enforce_tense` is off)
+   if let Some(tense) = tense
+      && !is_verb_in_tense(&first_word_lower, tense)
+   {
+      return Err(match tense {
+         Tense::Past => format!(
+            "must start with past-tense verb (ending in -ed/-d or irregular), got '{first_word}'"
+         ),
+         Tense::Imperative => {
+            format!("must start with imperative verb (base form, not -ed/-ing), got '{first_word}'")
+         },
+      });
    }
 
    // Check type repetition
@@ -765,93 +1132,194 @@ fn validate_summary_quality(
    }
 
    // Type-file mismatch heuristic
-   let file_exts: Vec<&str> = stat
-      .lines()
-      .filter_map(|line| {
-         let path = line.split('|').next()?.trim();
-         std::path::Path::new(path).extension()?.to_str()
-      })
-      .collect();
+   if let Some(warning) = crate::validation::profile_type_warning(commit_type, profile) {
+      crate::style::warn(&warning);
+   }
 
-   if !file_exts.is_empty() {
-      let total = file_exts.len();
-      let md_count = file_exts.iter().filter(|&&e| e == "md").count();
+   Ok(())
+}
 
-      // If >80% markdown but not docs type, suggest docs
-      if md_count * 100 / total > 80 && commit_type != "docs" {
-         crate::style::warn(&format!(
-            "Type mismatch: {}% .md files but type is '{}' (consider docs type)",
-            md_count * 100 / total,
-            commit_type
-         ));
-      }
+/// Inputs shared by every model of the summary cascade
+#[derive(Clone, Copy)]
+struct SummaryInputs<'a> {
+   stat:           &'a str,
+   profile:        &'a ChangeProfile,
+   commit_type:    &'a str,
+   scope:          Option<&'a str>,
+   details:        &'a [String],
+   user_context:   Option<&'a str>,
+   change_weights: Option<&'a str>,
+   debug_dir:      Option<&'a Path>,
+   debug_prefix:   Option<&'a str>,
+}
 
-      // If no code files and type=feat/fix, warn
-      let code_exts = [
-         // Systems programming
-         "rs", "c", "cpp", "cc", "cxx", "h", "hpp", "hxx", "zig", "nim", "v",
-         // JVM languages
-         "java", "kt", "kts", "scala", "groovy", "clj", "cljs", // .NET languages
-         "cs", "fs", "vb", // Web/scripting
-         "js", "ts", "jsx", "tsx", "mjs", "cjs", "vue", "svelte", // Python ecosystem
-         "py", "pyx", "pxd", "pyi", // Ruby
-         "rb", "rake", "gemspec", // PHP
-         "php",     // Go
-         "go",      // Swift/Objective-C
-         "swift", "m", "mm",  // Lua
-         "lua", // Shell
-         "sh", "bash", "zsh", "fish", // Perl
-         "pl", "pm", // Haskell/ML family
-         "hs", "lhs", "ml", "mli", "fs", "fsi", "elm", "ex", "exs", "erl", "hrl",
-         // Lisp family
-         "lisp", "cl", "el", "scm", "rkt", // Julia
-         "jl",  // R
-         "r", "R",    // Dart/Flutter
-         "dart", // Crystal
-         "cr",   // D
-         "d",    // Fortran
-         "f", "f90", "f95", "f03", "f08", // Ada
-         "ada", "adb", "ads", // Cobol
-         "cob", "cbl", // Assembly
-         "asm", "s", "S", // SQL (stored procs)
-         "sql", "plsql", // Prolog
-         "pl", "pro", // OCaml/ReasonML
-         "re", "rei", // Nix
-         "nix", // Terraform/HCL
-         "tf", "hcl",  // Solidity
-         "sol",  // Move
-         "move", // Cairo
-         "cairo",
-      ];
-      let code_count = file_exts
-         .iter()
-         .filter(|&&e| code_exts.contains(&e))
-         .count();
-      if code_count == 0 && (commit_type == "feat" || commit_type == "fix") {
-         crate::style::warn(&format!(
-            "Type mismatch: no code files changed but type is '{commit_type}'"
-         ));
-      }
-   }
+/// Outcome of one model's summary attempts
+enum SummaryAttempt {
+   Accepted(CommitSummary),
+   /// Every attempt failed `validate_summary_quality`; holds the last one
+   Rejected {
+      summary: CommitSummary,
+      reason:  String,
+   },
+}
 
-   Ok(())
+/// Validation retries per summary model, after its first attempt
+const SUMMARY_VALIDATION_RETRIES: usize = 1;
+
+/// Summary from [`generate_summary_from_analysis`]
+#[derive(Debug, Clone)]
+pub struct GeneratedSummary {
+   pub summary:       CommitSummary,
+   /// Whether a deterministic fallback replaced the model's summary
+   pub fallback_used: bool,
 }
 
-/// Create commit summary using a smaller model focused on detail retention
+/// Create commit summary using a smaller model focused on detail retention.
+///
+/// The models of `summary_model_cascade` are tried in order: the next one
+/// gets the same prompt plus the last failure reason once a model's summary
+/// fails the quality check on every attempt, or when the model can't make
+/// tool calls. Only the last model's rejected summary goes to the fallback.
 #[allow(clippy::too_many_arguments, reason = "summary generation needs debug hooks and context")]
 pub fn generate_summary_from_analysis<'a>(
    stat: &'a str,
+   profile: &'a ChangeProfile,
    commit_type: &'a str,
    scope: Option<&'a str>,
    details: &'a [String],
    user_context: Option<&'a str>,
+   change_weights: Option<&'a str>,
    config: &'a CommitConfig,
    debug_dir: Option<&'a Path>,
    debug_prefix: Option<&'a str>,
-) -> Result<CommitSummary> {
+) -> Result<GeneratedSummary> {
+   let inputs = SummaryInputs {
+      stat,
+      profile,
+      commit_type,
+      scope,
+      details,
+      user_context,
+      change_weights,
+      debug_dir,
+      debug_prefix,
+   };
+   let models = config.summary_models();
+   let report = |summary: CommitSummary, model: &str| {
+      if models.len() > 1 && !crate::style::is_quiet() {
+         crate::style::print_info(&format!("summary model: {model}"));
+      }
+      GeneratedSummary { summary, fallback_used: false }
+   };
+
+   let (last, cheaper) = models
+      .split_last()
+      .expect("summary_models always includes a model");
+   let mut carried_reason = None;
+   for (idx, model) in cheaper.iter().enumerate() {
+      let next = &models[idx + 1];
+      match summarize_with_model(&inputs, model, carried_reason.take(), config) {
+         Ok(SummaryAttempt::Accepted(summary)) => return Ok(report(summary, model)),
+         Ok(SummaryAttempt::Rejected { reason, .. }) => {
+            crate::style::warn(&format!(
+               "Summary from {model} failed validation {} times ({reason}); escalating to {next}",
+               SUMMARY_VALIDATION_RETRIES + 1
+            ));
+            carried_reason = Some(reason);
+         },
+         Err(err @ CommitGenError::ToolCallUnsupported { .. }) => {
+            crate::style::warn(&format!("{err}; escalating to {next}"));
+         },
+         Err(err) => return Err(err),
+      }
+   }
+
+   match summarize_with_model(&inputs, last, carried_reason, config)? {
+      SummaryAttempt::Accepted(summary) => Ok(report(summary, last)),
+      SummaryAttempt::Rejected { summary, reason } => {
+         crate::style::warn(&format!(
+            "Validation failed after {} retries: {}. Using fallback.",
+            SUMMARY_VALIDATION_RETRIES + 1,
+            reason
+         ));
+         // Fallback: use first detail or heuristic
+         Ok(GeneratedSummary {
+            summary:       fallback_from_details_or_summary(
+               details,
+               summary.as_str(),
+               commit_type,
+               config,
+            ),
+            fallback_used: true,
+         })
+      },
+   }
+}
+
+/// Summary prompt as sent to the model, before any retry constraint is added
+pub fn render_summary_request(
+   stat: &str,
+   commit_type: &str,
+   scope: Option<&str>,
+   details: &[String],
+   user_context: Option<&str>,
+   change_weights: Option<&str>,
+   config: &CommitConfig,
+) -> Result<templates::PromptParts> {
+   // Pass details as plain sentences (no numbering - prevents model parroting)
+   let bullet_points = details.join("\n");
+   let details_str = if bullet_points.is_empty() {
+      "None (no supporting detail points were generated)."
+   } else {
+      bullet_points.as_str()
+   };
+
+   // Calculate guideline summary length accounting for "type(scope): " prefix
+   let scope_str = scope.unwrap_or("");
+   let prefix = if scope_str.is_empty() {
+      format!("{commit_type}: ")
+   } else {
+      format!("{commit_type}({scope_str}): ")
+   };
+   let max_summary_len = config
+      .summary_guideline
+      .saturating_sub(config.summary_length_metric.measure(&prefix));
+
+   templates::render_summary_prompt(
+      &config.summary_prompt_variant,
+      commit_type,
+      scope_str,
+      &max_summary_len.to_string(),
+      details_str,
+      stat.trim(),
+      user_context,
+      change_weights.filter(|_| config.summary_include_weights),
+      config.summary_rules(),
+   )
+}
+
+/// Summary from one model, retried with the failure reason while it fails
+/// `validate_summary_quality`; `first_reason` carries a cheaper model's
+/// failure into the first prompt
+fn summarize_with_model(
+   inputs: &SummaryInputs<'_>,
+   model: &str,
+   first_reason: Option<String>,
+   config: &CommitConfig,
+) -> Result<SummaryAttempt> {
+   let SummaryInputs {
+      stat,
+      profile,
+      commit_type,
+      scope,
+      details,
+      user_context,
+      change_weights,
+      debug_dir,
+      debug_prefix,
+   } = *inputs;
    let mut validation_attempt = 0;
-   let max_validation_retries = 1;
-   let mut last_failure_reason: Option<String> = None;
+   let mut last_failure_reason = first_reason;
 
    loop {
       let additional_constraint = if let Some(reason) = &last_failure_reason {
@@ -861,10 +1329,7 @@ pub fn generate_summary_from_analysis<'a>(
       };
 
       let result = retry_api_call(config, move || {
-         // Pass details as plain sentences (no numbering - prevents model parroting)
-         let bullet_points = details.join("\n");
-
-         let client = build_client(config);
+         let client = build_client(config)?;
 
          let tool = Tool {
             tool_type: "function".to_string(),
@@ -876,7 +1341,7 @@ pub fn generate_summary_from_analysis<'a>(
                   properties: serde_json::json!({
                      "summary": {
                         "type": "string",
-                        "description": format!("Single line summary, target {} chars (hard limit {}), past tense verb first.", config.summary_guideline, config.summary_hard_limit),
+                        "description": format!("Single line summary, target {} chars (hard limit {}), {} first.", config.summary_guideline, config.summary_hard_limit, config.summary_verb()),
                         "maxLength": config.summary_hard_limit
                      }
                   }),
@@ -885,38 +1350,26 @@ pub fn generate_summary_from_analysis<'a>(
             },
          };
 
-         // Calculate guideline summary length accounting for "type(scope): " prefix
-         let scope_str = scope.unwrap_or("");
-         let prefix_len =
-            commit_type.len() + 2 + scope_str.len() + if scope_str.is_empty() { 0 } else { 2 }; // "type: " or "type(scope): "
-         let max_summary_len = config.summary_guideline.saturating_sub(prefix_len);
-
-         let mode = config.resolved_api_mode(&config.model);
+         let mode = config.resolved_api_mode(model);
 
          let response_text = match mode {
             ResolvedApiMode::ChatCompletions => {
-               let details_str = if bullet_points.is_empty() {
-                  "None (no supporting detail points were generated)."
-               } else {
-                  bullet_points.as_str()
-               };
-
-               let parts = templates::render_summary_prompt(
-                  &config.summary_prompt_variant,
+               let parts = render_summary_request(
+                  stat,
                   commit_type,
-                  scope_str,
-                  &max_summary_len.to_string(),
-                  details_str,
-                  stat.trim(),
+                  scope,
+                  details,
                   user_context,
+                  change_weights,
+                  config,
                )?;
 
                let user_content = format!("{}{additional_constraint}", parts.user);
 
                let request = ApiRequest {
-                  model:       config.model.clone(),
+                  model:       model.to_string(),
                   max_tokens:  200,
-                  temperature: config.temperature,
+                  temperature: config.temperature_for(model),
                   tools:       vec![tool],
                   tool_choice: Some(serde_json::json!({
                      "type": "function",
@@ -953,6 +1406,8 @@ pub fn generate_summary_from_analysis<'a>(
                   .map_err(CommitGenError::HttpError)?;
 
                let status = response.status();
+               let retry_after = parse_retry_after(response.headers());
+               let content_type = response_content_type(response.headers());
                let response_text = response.text().map_err(CommitGenError::HttpError)?;
                if debug_dir.is_some() {
                   save_debug_output(
@@ -962,6 +1417,13 @@ pub fn generate_summary_from_analysis<'a>(
                   )?;
                }
 
+               if let Some(err) = rate_limit_error(status, retry_after, &response_text) {
+                  return Err(err);
+               }
+               if let Some(err) = non_json_error(status, &content_type, &response_text) {
+                  return Err(err);
+               }
+
                // Retry on 5xx errors
                if status.is_server_error() {
                   eprintln!(
@@ -981,28 +1443,22 @@ pub fn generate_summary_from_analysis<'a>(
                response_text
             },
             ResolvedApiMode::AnthropicMessages => {
-               let details_str = if bullet_points.is_empty() {
-                  "None (no supporting detail points were generated)."
-               } else {
-                  bullet_points.as_str()
-               };
-
-               let parts = templates::render_summary_prompt(
-                  &config.summary_prompt_variant,
+               let parts = render_summary_request(
+                  stat,
                   commit_type,
-                  scope_str,
-                  &max_summary_len.to_string(),
-                  details_str,
-                  stat.trim(),
+                  scope,
+                  details,
                   user_context,
+                  change_weights,
+                  config,
                )?;
 
                let user_content = format!("{}{additional_constraint}", parts.user);
 
                let request = AnthropicRequest {
-                  model:       config.model.clone(),
+                  model:       model.to_string(),
                   max_tokens:  200,
-                  temperature: config.temperature,
+                  temperature: config.temperature_for(model),
                   system:      Some(parts.system).filter(|s| !s.is_empty()),
                   tools:       vec![AnthropicTool {
                      name:         "create_commit_summary".to_string(),
@@ -1013,7 +1469,7 @@ pub fn generate_summary_from_analysis<'a>(
                         "properties": {
                            "summary": {
                               "type": "string",
-                              "description": format!("Single line summary, target {} chars (hard limit {}), past tense verb first.", config.summary_guideline, config.summary_hard_limit),
+                              "description": format!("Single line summary, target {} chars (hard limit {}), {} first.", config.summary_guideline, config.summary_hard_limit, config.summary_verb()),
                               "maxLength": config.summary_hard_limit
                            }
                         },
@@ -1057,6 +1513,8 @@ pub fn generate_summary_from_analysis<'a>(
                   .map_err(CommitGenError::HttpError)?;
 
                let status = response.status();
+               let retry_after = parse_retry_after(response.headers());
+               let content_type = response_content_type(response.headers());
                let response_text = response.text().map_err(CommitGenError::HttpError)?;
                if debug_dir.is_some() {
                   save_debug_output(
@@ -1066,6 +1524,13 @@ pub fn generate_summary_from_analysis<'a>(
                   )?;
                }
 
+               if let Some(err) = rate_limit_error(status, retry_after, &response_text) {
+                  return Err(err);
+               }
+               if let Some(err) = non_json_error(status, &content_type, &response_text) {
+                  return Err(err);
+               }
+
                // Retry on 5xx errors
                if status.is_server_error() {
                   eprintln!(
@@ -1109,36 +1574,22 @@ pub fn generate_summary_from_analysis<'a>(
 
                let message_choice = &api_response.choices[0].message;
 
-               if !message_choice.tool_calls.is_empty() {
-                  let tool_call = &message_choice.tool_calls[0];
-                  if tool_call.function.name.ends_with("create_commit_summary") {
-                     let args = &tool_call.function.arguments;
-                     if args.is_empty() {
-                        crate::style::warn(
-                           "Model returned empty function arguments for summary. Model may not \
-                            support function calling.",
-                        );
-                        return Err(CommitGenError::Other(
-                           "Model returned empty summary arguments - try using a Claude model \
-                            (sonnet/opus/haiku)"
-                              .to_string(),
-                        ));
-                     }
-                     let summary: SummaryOutput = serde_json::from_str(args).map_err(|e| {
-                        CommitGenError::Other(format!(
-                           "Failed to parse summary response: {}. Response was: {}",
-                           e,
-                           args.chars().take(200).collect::<String>()
-                        ))
-                     })?;
-                     // Strip type prefix if LLM included it (e.g., "feat(scope): summary" ->
-                     // "summary")
-                     let cleaned = strip_type_prefix(&summary.summary, commit_type, scope);
-                     return Ok((
-                        false,
-                        Some(CommitSummary::new(cleaned, config.summary_hard_limit)?),
-                     ));
+               if let Some(tool_call) = message_choice.find_tool_call("create_commit_summary") {
+                  let args = &tool_call.function.arguments;
+                  if args.is_empty() {
+                     return Err(CommitGenError::ToolCallUnsupported { model: model.to_string() });
                   }
+                  let summary: SummaryOutput = serde_json::from_str(args).map_err(|e| {
+                     CommitGenError::Other(format!(
+                        "Failed to parse summary response: {}. Response was: {}",
+                        e,
+                        args.chars().take(200).collect::<String>()
+                     ))
+                  })?;
+                  // Strip type prefix if LLM included it (e.g., "feat(scope): summary" ->
+                  // "summary")
+                  let cleaned = strip_type_prefix(&summary.summary, commit_type, scope);
+                  return Ok((false, Some(CommitSummary::for_config(cleaned, config)?)));
                }
 
                if let Some(content) = &message_choice.content {
@@ -1146,28 +1597,10 @@ pub fn generate_summary_from_analysis<'a>(
                      crate::style::warn("Model returned empty content for summary; retrying.");
                      return Ok((true, None));
                   }
-                  // Try JSON first, fall back to plain text (for models without function calling)
-                  let trimmed = content.trim();
-                  let summary_text = match serde_json::from_str::<SummaryOutput>(trimmed) {
-                     Ok(summary) => summary.summary,
-                     Err(e) => {
-                        // Only use plain text if it doesn't look like JSON
-                        if trimmed.starts_with('{') {
-                           return Err(CommitGenError::Other(format!(
-                              "Failed to parse summary JSON: {e}. Content: {}",
-                              response_snippet(trimmed, 500)
-                           )));
-                        }
-                        // Model returned plain text instead of JSON - use it directly
-                        trimmed.to_string()
-                     },
-                  };
+                  let summary_text = summary_from_content(content)?;
                   // Strip type prefix if LLM included it
                   let cleaned = strip_type_prefix(&summary_text, commit_type, scope);
-                  return Ok((
-                     false,
-                     Some(CommitSummary::new(cleaned, config.summary_hard_limit)?),
-                  ));
+                  return Ok((false, Some(CommitSummary::for_config(cleaned, config)?)));
                }
 
                Err(CommitGenError::Other(
@@ -1186,10 +1619,7 @@ pub fn generate_summary_from_analysis<'a>(
                      ))
                   })?;
                   let cleaned = strip_type_prefix(&summary.summary, commit_type, scope);
-                  return Ok((
-                     false,
-                     Some(CommitSummary::new(cleaned, config.summary_hard_limit)?),
-                  ));
+                  return Ok((false, Some(CommitSummary::for_config(cleaned, config)?)));
                }
 
                if text_content.trim().is_empty() {
@@ -1197,90 +1627,377 @@ pub fn generate_summary_from_analysis<'a>(
                   return Ok((true, None));
                }
 
-               // Try JSON first, fall back to plain text (for models without function calling)
-               let trimmed = text_content.trim();
-               let summary_text = match serde_json::from_str::<SummaryOutput>(trimmed) {
-                  Ok(summary) => summary.summary,
-                  Err(e) => {
-                     // Only use plain text if it doesn't look like JSON
-                     if trimmed.starts_with('{') {
-                        return Err(CommitGenError::Other(format!(
-                           "Failed to parse summary JSON: {e}. Content: {}",
-                           response_snippet(trimmed, 500)
-                        )));
-                     }
-                     // Model returned plain text instead of JSON - use it directly
-                     trimmed.to_string()
-                  },
-               };
+               let summary_text = summary_from_content(&text_content)?;
                let cleaned = strip_type_prefix(&summary_text, commit_type, scope);
-               Ok((false, Some(CommitSummary::new(cleaned, config.summary_hard_limit)?)))
+               Ok((false, Some(CommitSummary::for_config(cleaned, config)?)))
             },
          }
       });
 
-      match result {
-         Ok(summary) => {
-            // Validate quality
-            match validate_summary_quality(summary.as_str(), commit_type, stat) {
-               Ok(()) => return Ok(summary),
-               Err(reason) if validation_attempt < max_validation_retries => {
-                  crate::style::warn(&format!(
-                     "Validation failed (attempt {}/{}): {}",
-                     validation_attempt + 1,
-                     max_validation_retries + 1,
-                     reason
-                  ));
-                  last_failure_reason = Some(reason);
-                  validation_attempt += 1;
-                  // Retry with constraint
-               },
-               Err(reason) => {
-                  crate::style::warn(&format!(
-                     "Validation failed after {} retries: {}. Using fallback.",
-                     max_validation_retries + 1,
-                     reason
-                  ));
-                  // Fallback: use first detail or heuristic
-                  return Ok(fallback_from_details_or_summary(
-                     details,
-                     summary.as_str(),
-                     commit_type,
-                     config,
-                  ));
-               },
-            }
+      let summary = result?;
+      match validate_summary_quality(
+         summary.as_str(),
+         commit_type,
+         profile,
+         config.enforced_tense(),
+      ) {
+         Ok(()) => return Ok(SummaryAttempt::Accepted(summary)),
+         Err(reason) if validation_attempt < SUMMARY_VALIDATION_RETRIES => {
+            crate::style::warn(&format!(
+               "Validation failed (attempt {}/{}): {}",
+               validation_attempt + 1,
+               SUMMARY_VALIDATION_RETRIES + 1,
+               reason
+            ));
+            last_failure_reason = Some(reason);
+            validation_attempt += 1;
+            // Retry with constraint
          },
-         Err(e) => return Err(e),
+         Err(reason) => return Ok(SummaryAttempt::Rejected { summary, reason }),
       }
    }
 }
 
-/// Fallback when validation fails: use first detail, strip type word if present
-fn fallback_from_details_or_summary(
-   details: &[String],
-   invalid_summary: &str,
-   commit_type: &str,
-   config: &CommitConfig,
-) -> CommitSummary {
-   let candidate = if let Some(first_detail) = details.first() {
-      // Use first detail line, strip type word
-      let mut cleaned = first_detail.trim().trim_end_matches('.').to_string();
-
-      // Remove type word if present at start
-      let type_word_variants =
-         [commit_type, &format!("{commit_type}ed"), &format!("{commit_type}d")];
-      for variant in &type_word_variants {
-         if cleaned
-            .to_lowercase()
-            .starts_with(&format!("{} ", variant.to_lowercase()))
-         {
-            cleaned = cleaned[variant.len()..].trim().to_string();
-            break;
-         }
-      }
+/// A single forced tool call whose arguments deserialize straight into the
+/// output type
+struct ToolCallRequest<'a> {
+   model:       &'a str,
+   name:        &'static str,
+   description: &'static str,
+   /// JSON schema object with `properties` and `required`
+   schema:      &'a serde_json::Value,
+   system:      &'a str,
+   user:        &'a str,
+   max_tokens:  u32,
+   /// Debug files are saved as `<label>_request.json`/`<label>_response.json`
+   debug_label: &'static str,
+}
 
-      cleaned
+/// Make a forced tool call (with retries) and parse its arguments as `T`
+fn call_tool<T: serde::de::DeserializeOwned>(
+   req: &ToolCallRequest<'_>,
+   ctx: &AnalysisContext<'_>,
+   config: &CommitConfig,
+) -> Result<T> {
+   let debug_dir = ctx.debug_output;
+   let debug_prefix = ctx.debug_prefix;
+   let mode = config.resolved_api_mode(req.model);
+   let tool_name = req.name;
+   let request_file = format!("{}_request.json", req.debug_label);
+   let response_file = format!("{}_response.json", req.debug_label);
+
+   retry_api_call(config, || {
+      let client = build_client(config)?;
+
+      let request_builder = match mode {
+         ResolvedApiMode::ChatCompletions => {
+            let required = req.schema["required"]
+               .as_array()
+               .into_iter()
+               .flatten()
+               .filter_map(|field| field.as_str().map(str::to_string))
+               .collect();
+            let request = ApiRequest {
+               model:       req.model.to_string(),
+               max_tokens:  req.max_tokens,
+               temperature: config.temperature_for(req.model),
+               tools:       vec![Tool {
+                  tool_type: "function".to_string(),
+                  function:  Function {
+                     name:        tool_name.to_string(),
+                     description: req.description.to_string(),
+                     parameters:  FunctionParameters {
+                        param_type: "object".to_string(),
+                        properties: req.schema["properties"].clone(),
+                        required,
+                     },
+                  },
+               }],
+               tool_choice: Some(
+                  serde_json::json!({ "type": "function", "function": { "name": tool_name } }),
+               ),
+               messages:    vec![
+                  Message { role: "system".to_string(), content: req.system.to_string() },
+                  Message { role: "user".to_string(), content: req.user.to_string() },
+               ],
+            };
+            if debug_dir.is_some() {
+               save_debug_output(
+                  debug_dir,
+                  &debug_filename(debug_prefix, &request_file),
+                  &serde_json::to_string_pretty(&request)?,
+               )?;
+            }
+
+            let mut request_builder = client
+               .post(format!("{}/chat/completions", config.api_base_url))
+               .header("content-type", "application/json");
+            if let Some(api_key) = &config.api_key {
+               request_builder =
+                  request_builder.header("Authorization", format!("Bearer {api_key}"));
+            }
+            request_builder.json(&request)
+         },
+         ResolvedApiMode::AnthropicMessages => {
+            let request = AnthropicRequest {
+               model:       req.model.to_string(),
+               max_tokens:  req.max_tokens,
+               temperature: config.temperature_for(req.model),
+               system:      Some(req.system.to_string()).filter(|s| !s.is_empty()),
+               tools:       vec![AnthropicTool {
+                  name:         tool_name.to_string(),
+                  description:  req.description.to_string(),
+                  input_schema: req.schema.clone(),
+               }],
+               tool_choice: Some(AnthropicToolChoice {
+                  choice_type: "tool".to_string(),
+                  name:        tool_name.to_string(),
+               }),
+               messages:    vec![AnthropicMessage {
+                  role:    "user".to_string(),
+                  content: vec![AnthropicContent {
+                     content_type: "text".to_string(),
+                     text:         req.user.to_string(),
+                  }],
+               }],
+            };
+            if debug_dir.is_some() {
+               save_debug_output(
+                  debug_dir,
+                  &debug_filename(debug_prefix, &request_file),
+                  &serde_json::to_string_pretty(&request)?,
+               )?;
+            }
+
+            let mut request_builder = client
+               .post(anthropic_messages_url(&config.api_base_url))
+               .header("content-type", "application/json")
+               .header("anthropic-version", "2023-06-01");
+            if let Some(api_key) = &config.api_key {
+               request_builder = request_builder.header("x-api-key", api_key);
+            }
+            request_builder.json(&request)
+         },
+      };
+
+      let response = request_builder.send().map_err(CommitGenError::HttpError)?;
+      let status = response.status();
+      let retry_after = parse_retry_after(response.headers());
+      let content_type = response_content_type(response.headers());
+      let response_text = response.text().map_err(CommitGenError::HttpError)?;
+      if debug_dir.is_some() {
+         save_debug_output(
+            debug_dir,
+            &debug_filename(debug_prefix, &response_file),
+            &response_text,
+         )?;
+      }
+
+      if let Some(err) = rate_limit_error(status, retry_after, &response_text) {
+         return Err(err);
+      }
+      if let Some(err) = non_json_error(status, &content_type, &response_text) {
+         return Err(err);
+      }
+      if status.is_server_error() {
+         eprintln!("{}", crate::style::error(&format!("Server error {status}: {response_text}")));
+         return Ok((true, None));
+      }
+      if !status.is_success() {
+         return Err(CommitGenError::ApiError { status: status.as_u16(), body: response_text });
+      }
+      if response_text.trim().is_empty() {
+         crate::style::warn(&format!(
+            "Model returned empty response body for {tool_name}; retrying."
+         ));
+         return Ok((true, None));
+      }
+
+      let parse_err = |e: serde_json::Error| {
+         CommitGenError::Other(format!(
+            "Failed to parse {tool_name} response: {e}. Response body: {}",
+            response_snippet(&response_text, 500)
+         ))
+      };
+      let output: T = match mode {
+         ResolvedApiMode::ChatCompletions => {
+            let api_response: ApiResponse =
+               serde_json::from_str(&response_text).map_err(parse_err)?;
+            let arguments = api_response
+               .choices
+               .first()
+               .and_then(|choice| choice.message.find_tool_call(tool_name))
+               .map(|call| call.function.arguments.as_str())
+               .ok_or_else(|| {
+                  CommitGenError::Other(format!("No {tool_name} tool call in API response"))
+               })?;
+            serde_json::from_str(arguments).map_err(parse_err)?
+         },
+         ResolvedApiMode::AnthropicMessages => {
+            let (tool_input, _) = extract_anthropic_content(&response_text, tool_name)?;
+            let input = tool_input.ok_or_else(|| {
+               CommitGenError::Other(format!("No {tool_name} tool use in API response"))
+            })?;
+            serde_json::from_value(input).map_err(parse_err)?
+         },
+      };
+      Ok((false, Some(output)))
+   })
+}
+
+/// Whether `diff` is small enough for the single-call fast path
+pub const fn should_use_fast_mode(diff: &str, config: &CommitConfig) -> bool {
+   diff.len() < config.fast_mode_threshold_chars
+}
+
+/// Generate type, scope, details and summary with one combined tool call
+/// (fast mode for small diffs).
+///
+/// The summary must pass the same quality check as
+/// [`generate_summary_from_analysis`]; there is no fallback summary here, so
+/// callers escalate to the two-call path on any error.
+pub fn generate_fast_commit<'a>(
+   stat: &'a str,
+   diff: &'a str,
+   scope_candidates_str: &'a str,
+   ctx: &AnalysisContext<'a>,
+   config: &'a CommitConfig,
+) -> Result<(ConventionalAnalysis, CommitSummary)> {
+   const TOOL_NAME: &str = "create_commit_message";
+   const TOOL_DESCRIPTION: &str =
+      "Classify the change and write its conventional commit: type, scope, summary and details";
+
+   let type_enum: Vec<&str> = config.types.keys().map(|s| s.as_str()).collect();
+   let mut schema = serde_json::json!({
+      "type": "object",
+      "properties": {
+         "type": {
+            "type": "string",
+            "enum": type_enum,
+            "description": "Commit type based on change classification"
+         },
+         "scope": {
+            "type": "string",
+            "description": "Optional scope (module/component). Omit if unclear or multi-component."
+         },
+         "summary": {
+            "type": "string",
+            "description": format!("Single line summary without type prefix, target {} chars (hard limit {}), {} first.", config.summary_guideline, config.summary_hard_limit, config.summary_verb()),
+            "maxLength": config.summary_hard_limit
+         },
+         "details": {
+            "type": "array",
+            "description": "Array of 0-3 detail items with changelog metadata",
+            "items": {
+               "type": "object",
+               "properties": {
+                  "text": {
+                     "type": "string",
+                     "description": format!("Detail about change, starting with {} verb", config.body_tense.verb_form())
+                  },
+                  "changelog_category": {
+                     "type": "string",
+                     "enum": ["Added", "Changed", "Fixed", "Deprecated", "Removed", "Security"],
+                     "description": "Changelog category if user-visible. Omit for internal changes."
+                  },
+                  "user_visible": {
+                     "type": "boolean",
+                     "description": "True if this change affects users/API and should appear in changelog"
+                  }
+               },
+               "required": ["text", "user_visible"]
+            }
+         },
+         "issue_refs": {
+            "type": "array",
+            "description": "Issue numbers from context (e.g., ['#123', '#456']). Empty if none.",
+            "items": { "type": "string" }
+         }
+      },
+      "required": ["type", "summary", "details", "issue_refs"]
+   });
+   add_rationale_property(&mut schema["properties"], config);
+
+   let types_desc = format_types_description(config);
+   let params = templates::AnalysisParams {
+      variant: "default",
+      stat,
+      diff,
+      scope_candidates: scope_candidates_str,
+      recent_commits: ctx.recent_commits,
+      common_scopes: ctx.common_scopes,
+      types_description: Some(&types_desc),
+      project_context: ctx.project_context,
+      change_profile: ctx.change_profile,
+      body_tense: config.body_tense,
+      body_paragraphs: config.body_style == BodyStyle::Paragraphs,
+   };
+   let parts = templates::render_fast_prompt(
+      &params,
+      &config.summary_guideline.to_string(),
+      config.summary_rules(),
+   )?;
+   let user_content = analysis_user_content(ctx, diff, parts.user);
+   let output: FastCommitOutput = call_tool(
+      &ToolCallRequest {
+         model:       &config.model,
+         name:        TOOL_NAME,
+         description: TOOL_DESCRIPTION,
+         schema:      &schema,
+         system:      &parts.system,
+         user:        &user_content,
+         max_tokens:  1000,
+         debug_label: "fast",
+      },
+      ctx,
+      config,
+   )?;
+
+   let analysis = output.analysis;
+   let cleaned = strip_type_prefix(
+      &output.summary,
+      analysis.commit_type.as_str(),
+      analysis.scope.as_ref().map(|s| s.as_str()),
+   );
+   let profile = crate::analysis::classify_change_profile(diff);
+   validate_summary_quality(
+      &cleaned,
+      analysis.commit_type.as_str(),
+      &profile,
+      config.enforced_tense(),
+   )
+   .map_err(|reason| CommitGenError::ValidationError(format!("fast-mode summary {reason}")))?;
+   let summary = CommitSummary::for_config(cleaned, config)?;
+
+   Ok((analysis, summary))
+}
+
+/// Fallback when validation fails: use first detail, strip type word if present
+fn fallback_from_details_or_summary(
+   details: &[String],
+   invalid_summary: &str,
+   commit_type: &str,
+   config: &CommitConfig,
+) -> CommitSummary {
+   crate::confidence::record_fallback();
+   let candidate = if let Some(first_detail) = details.first() {
+      // Use first detail line, strip type word
+      let mut cleaned = first_detail.trim().trim_end_matches('.').to_string();
+
+      // Remove type word if present at start
+      let type_word_variants =
+         [commit_type, &format!("{commit_type}ed"), &format!("{commit_type}d")];
+      for variant in &type_word_variants {
+         if cleaned
+            .to_lowercase()
+            .starts_with(&format!("{} ", variant.to_lowercase()))
+         {
+            cleaned = cleaned[variant.len()..].trim().to_string();
+            break;
+         }
+      }
+
+      cleaned
    } else {
       // No details, try to fix invalid summary
       let mut cleaned = invalid_summary
@@ -1298,33 +2015,86 @@ fn fallback_from_details_or_summary(
       cleaned
    };
 
-   // Ensure valid past-tense verb prefix
-   let with_verb = if candidate
-      .split_whitespace()
-      .next()
-      .is_some_and(|w| crate::validation::is_past_tense_verb(&w.to_lowercase()))
-   {
-      candidate
-   } else {
-      let verb = match commit_type {
-         "feat" => "added",
-         "fix" => "fixed",
-         "refactor" => "restructured",
-         "docs" => "documented",
-         "test" => "tested",
-         "perf" => "optimized",
-         "build" | "ci" | "chore" => "updated",
-         "style" => "formatted",
-         "revert" => "reverted",
-         _ => "changed",
+   // Details are written in the body tense; convert the leading verb first
+   let mut candidate = candidate;
+   crate::normalization::normalize_summary_verb(&mut candidate, commit_type, config.summary_tense);
+
+   // Ensure valid verb prefix in the configured tense
+   let with_verb =
+      if candidate.split_whitespace().next().is_some_and(|w| {
+         crate::validation::is_verb_in_tense(&w.to_lowercase(), config.summary_tense)
+      }) {
+         candidate
+      } else {
+         format!("{} {candidate}", fallback_verb(commit_type, config.summary_tense))
       };
-      format!("{verb} {candidate}")
-   };
 
-   CommitSummary::new(with_verb, config.summary_hard_limit)
+   CommitSummary::for_config(with_verb, config)
       .unwrap_or_else(|_| fallback_summary("", details, commit_type, config))
 }
 
+/// Safe leading verb for a commit type that never repeats the type itself
+const fn fallback_verb(commit_type: &str, tense: Tense) -> &'static str {
+   match (tense, commit_type.as_bytes()) {
+      (Tense::Past, b"feat") => "added",
+      (Tense::Past, b"fix") => "fixed",
+      (Tense::Past, b"refactor") => "restructured",
+      (Tense::Past, b"docs") => "documented",
+      (Tense::Past, b"test") => "tested",
+      (Tense::Past, b"perf") => "optimized",
+      (Tense::Past, b"build" | b"ci" | b"chore") => "updated",
+      (Tense::Past, b"style") => "formatted",
+      (Tense::Past, b"revert") => "reverted",
+      (Tense::Past, _) => "changed",
+      (Tense::Imperative, b"feat") => "add",
+      (Tense::Imperative, b"fix") => "correct",
+      (Tense::Imperative, b"refactor") => "restructure",
+      (Tense::Imperative, b"docs") => "document",
+      (Tense::Imperative, b"test") => "cover",
+      (Tense::Imperative, b"perf") => "optimize",
+      (Tense::Imperative, b"build" | b"ci" | b"chore") => "update",
+      (Tense::Imperative, b"style") => "format",
+      (Tense::Imperative, b"revert") => "undo",
+      (Tense::Imperative, _) => "change",
+   }
+}
+
+/// Shortest target `fallback_summary` shortens to, whatever the guideline
+const MIN_FALLBACK_LEN: usize = 20;
+
+/// Shorten `text` to at most `max_len` as measured by `metric`.
+///
+/// Drops trailing clauses (after the last `, ` or ` and `) first, so the verb
+/// and primary object survive; only falls back to cutting at a word boundary,
+/// and finally mid-word, when no clause boundary is left.
+pub fn shorten_summary(text: &str, max_len: usize, metric: LengthMetric) -> String {
+   let mut text = text.trim().to_string();
+   while metric.measure(&text) > max_len {
+      let boundary = [", ", " and "]
+         .iter()
+         .filter_map(|sep| text.rfind(sep))
+         .max()
+         // Keep at least the verb and one word of its object
+         .filter(|&pos| text[..pos].trim().contains(' '));
+      if let Some(pos) = boundary {
+         text.truncate(pos);
+      } else if let Some(pos) = text.rfind(' ') {
+         text.truncate(pos);
+      } else {
+         // Longest prefix of whole chars that still fits
+         let cut = text
+            .char_indices()
+            .map(|(i, c)| i + c.len_utf8())
+            .take_while(|&end| metric.measure(&text[..end]) <= max_len)
+            .last()
+            .unwrap_or(0);
+         text.truncate(cut);
+      }
+      text = text.trim_end_matches([',', ' ']).to_string();
+   }
+   text
+}
+
 /// Provide a deterministic fallback summary if model generation fails
 pub fn fallback_summary(
    stat: &str,
@@ -1332,6 +2102,7 @@ pub fn fallback_summary(
    commit_type: &str,
    config: &CommitConfig,
 ) -> CommitSummary {
+   crate::confidence::record_fallback();
    let mut candidate = if let Some(first) = details.first() {
       first.trim().trim_end_matches('.').to_string()
    } else {
@@ -1348,10 +2119,14 @@ pub fn fallback_summary(
          .filter(|s| !s.is_empty())
          .unwrap_or("files");
 
+      let verb = match config.summary_tense {
+         Tense::Past => "Updated",
+         Tense::Imperative => "Update",
+      };
       if subject.eq_ignore_ascii_case("files") {
-         "Updated files".to_string()
+         format!("{verb} files")
       } else {
-         format!("Updated {subject}")
+         format!("{verb} {subject}")
       }
    };
 
@@ -1367,21 +2142,16 @@ pub fn fallback_summary(
       .to_string();
 
    if candidate.is_empty() {
-      candidate = "Updated files".to_string();
+      candidate = format!("{} files", fallback_verb("chore", config.summary_tense));
    }
 
-   // Truncate to conservative length (50 chars) since we don't know the scope yet
-   // post_process_commit_message will truncate further if needed
-   const CONSERVATIVE_MAX: usize = 50;
-   while candidate.len() > CONSERVATIVE_MAX {
-      if let Some(pos) = candidate.rfind(' ') {
-         candidate.truncate(pos);
-         candidate = candidate.trim_end_matches(',').trim().to_string();
-      } else {
-         candidate.truncate(CONSERVATIVE_MAX);
-         break;
-      }
-   }
+   // Aim for the guideline less the `type: ` prefix; the scope isn't known yet,
+   // so post_process_commit_message may shorten further
+   let target = config
+      .summary_guideline
+      .saturating_sub(commit_type.len() + 2)
+      .max(MIN_FALLBACK_LEN);
+   candidate = shorten_summary(&candidate, target, config.summary_length_metric);
 
    // Ensure no trailing period (conventional commits style)
    candidate = candidate.trim_end_matches('.').to_string();
@@ -1393,23 +2163,62 @@ pub fn fallback_summary(
       .next()
       .is_some_and(|word| word.eq_ignore_ascii_case(commit_type))
    {
-      candidate = match commit_type {
-         "refactor" => "restructured change".to_string(),
-         "feat" => "added functionality".to_string(),
-         "fix" => "fixed issue".to_string(),
-         "docs" => "documented updates".to_string(),
-         "test" => "tested changes".to_string(),
-         "chore" | "build" | "ci" | "style" => "updated tooling".to_string(),
-         "perf" => "optimized performance".to_string(),
-         "revert" => "reverted previous commit".to_string(),
-         _ => "updated files".to_string(),
+      let (verb_type, object) = match commit_type {
+         "refactor" => ("refactor", "change"),
+         "feat" => ("feat", "functionality"),
+         "fix" => ("fix", "issue"),
+         "docs" => ("docs", "updates"),
+         "test" => ("test", "changes"),
+         "perf" => ("perf", "performance"),
+         "revert" => ("revert", "previous commit"),
+         "chore" | "build" | "ci" | "style" => ("chore", "tooling"),
+         _ => ("chore", "files"),
       };
+      let verb = config
+         .types
+         .get(commit_type)
+         .and_then(|ty| {
+            ty.leading_verbs.iter().find(|verb| {
+               crate::validation::is_verb_in_tense(&verb.to_lowercase(), config.summary_tense)
+            })
+         })
+         .map_or_else(|| fallback_verb(verb_type, config.summary_tense), String::as_str);
+      candidate = format!("{verb} {object}");
    }
 
-   // Unwrap is safe: fallback_summary guarantees non-empty string ≤50 chars (<
-   // config limit)
-   CommitSummary::new(candidate, config.summary_hard_limit)
-      .expect("fallback summary should always be valid")
+   // Unwrap is safe: the candidate is non-empty and within the guideline, which
+   // is below the hard limit
+   CommitSummary::for_config(candidate, config).expect("fallback summary should always be valid")
+}
+
+/// Deterministic analysis for diffs that only rename files
+///
+/// A pure `git mv` has no content for the model to describe, so it tends to
+/// invent details. Returns `None` unless every file is a rename-only change.
+fn rename_only_analysis(diff: &str, config: &CommitConfig) -> Option<ConventionalAnalysis> {
+   let files = crate::diff::parse_diff(diff);
+   if files.is_empty() || !files.iter().all(|f| f.is_rename_only()) {
+      return None;
+   }
+
+   // Moving source code is a refactor; moving anything else is housekeeping
+   let commit_type = if files.iter().any(|f| f.priority(config) >= 100) {
+      "refactor"
+   } else {
+      "chore"
+   };
+
+   Some(ConventionalAnalysis {
+      commit_type: CommitType::new(commit_type).ok()?,
+      scope:       None,
+      details:     files
+         .iter()
+         .filter_map(|f| f.rename_observation())
+         .map(AnalysisDetail::simple)
+         .collect(),
+      issue_refs:  vec![],
+      rationale:   None,
+   })
 }
 
 /// Generate conventional commit analysis, using map-reduce for large diffs
@@ -1427,34 +2236,344 @@ pub fn generate_analysis_with_map_reduce<'a>(
 ) -> Result<ConventionalAnalysis> {
    use crate::map_reduce::{run_map_reduce, should_use_map_reduce};
 
+   if let Some(analysis) = rename_only_analysis(diff, config) {
+      return Ok(analysis);
+   }
+
    if should_use_map_reduce(diff, config, counter) {
       crate::style::print_info(&format!(
          "Large diff detected ({} tokens), using map-reduce...",
          counter.count_sync(diff)
       ));
-      run_map_reduce(diff, stat, scope_candidates_str, model_name, config, counter)
+      run_map_reduce(diff, stat, scope_candidates_str, ctx, model_name, config, counter)
    } else {
       generate_conventional_analysis(stat, diff, model_name, scope_candidates_str, ctx, config)
    }
 }
 
+/// Classify type and scope from the diff stat alone, on `classify_model`.
+///
+/// Returns an analysis with no details; see [`generate_split_analysis`].
+pub fn classify_type_and_scope<'a>(
+   stat: &'a str,
+   scope_candidates_str: &'a str,
+   ctx: &AnalysisContext<'a>,
+   config: &'a CommitConfig,
+) -> Result<ConventionalAnalysis> {
+   let type_enum: Vec<&str> = config.types.keys().map(|s| s.as_str()).collect();
+   let mut schema = serde_json::json!({
+      "type": "object",
+      "properties": {
+         "type": {
+            "type": "string",
+            "enum": type_enum,
+            "description": "Commit type based on change classification"
+         },
+         "scope": {
+            "type": "string",
+            "description": "Optional scope (module/component). Omit if unclear or multi-component."
+         }
+      },
+      "required": ["type"]
+   });
+   add_rationale_property(&mut schema["properties"], config);
+
+   let types_desc = format_types_description(config);
+   let params = templates::AnalysisParams {
+      variant: "default",
+      stat,
+      diff: "",
+      scope_candidates: scope_candidates_str,
+      recent_commits: ctx.recent_commits,
+      common_scopes: ctx.common_scopes,
+      types_description: Some(&types_desc),
+      project_context: ctx.project_context,
+      change_profile: ctx.change_profile,
+      body_tense: config.body_tense,
+      body_paragraphs: config.body_style == BodyStyle::Paragraphs,
+   };
+   let parts = templates::render_classify_prompt(&params)?;
+   let user_content = analysis_user_content(ctx, "", parts.user);
+
+   call_tool(
+      &ToolCallRequest {
+         model:       config.classify_model.as_deref().unwrap_or(&config.model),
+         name:        "create_type_classification",
+         description: "Classify the change's conventional commit type and scope from its stat",
+         schema:      &schema,
+         system:      &parts.system,
+         user:        &user_content,
+         max_tokens:  200,
+         debug_label: "classify",
+      },
+      ctx,
+      config,
+   )
+}
+
+/// Type and scope from the stat-only classification, details and issue refs
+/// from the diff-reading analysis
+pub fn merge_split_analysis(
+   classification: ConventionalAnalysis,
+   body: ConventionalAnalysis,
+) -> ConventionalAnalysis {
+   ConventionalAnalysis {
+      commit_type: classification.commit_type,
+      scope:       classification.scope,
+      details:     body.details,
+      issue_refs:  body.issue_refs,
+      rationale:   classification.rationale,
+   }
+}
+
+/// Analysis for `split_type_and_body` mode.
+///
+/// A stat-only classification call runs alongside the (possibly map-reduced)
+/// analysis of the diff, which then only contributes the body. If
+/// classification fails, the diff analysis' own type and scope are kept.
+pub fn generate_split_analysis<'a>(
+   stat: &'a str,
+   diff: &'a str,
+   model_name: &'a str,
+   scope_candidates_str: &'a str,
+   ctx: &AnalysisContext<'a>,
+   config: &'a CommitConfig,
+   counter: &TokenCounter,
+) -> Result<ConventionalAnalysis> {
+   if let Some(analysis) = rename_only_analysis(diff, config) {
+      return Ok(analysis);
+   }
+
+   let (classification, body) = thread::scope(|scope| {
+      let classify =
+         scope.spawn(|| classify_type_and_scope(stat, scope_candidates_str, ctx, config));
+      let body = generate_analysis_with_map_reduce(
+         stat,
+         diff,
+         model_name,
+         scope_candidates_str,
+         ctx,
+         config,
+         counter,
+      );
+      let classification = classify.join().unwrap_or_else(|_| {
+         Err(CommitGenError::Other("type classification thread panicked".to_string()))
+      });
+      (classification, body)
+   });
+
+   let body = body?;
+   match classification {
+      Ok(classification) => Ok(merge_split_analysis(classification, body)),
+      Err(err) => {
+         crate::style::warn(&format!(
+            "Type classification failed ({err}); using the diff analysis' type and scope"
+         ));
+         Ok(body)
+      },
+   }
+}
+
 #[cfg(test)]
 mod tests {
    use super::*;
    use crate::config::CommitConfig;
 
+   #[test]
+   fn test_non_json_bodies_are_classified() {
+      let ok = reqwest::StatusCode::OK;
+      let html = "<!DOCTYPE html>\n<html><head><title>Sign in</title><style>body { color: red \
+                  }</style></head>\n<body><h1>Corporate   SSO</h1><p>Please log \
+                  in.</p></body></html>";
+
+      assert_eq!(ResponseBodyKind::of(html), ResponseBodyKind::Html);
+      assert_eq!(response_snippet(html, 200), "Sign in Corporate SSO Please log in.");
+      let err = non_json_error(ok, "text/html; charset=utf-8", html).unwrap();
+      assert!(matches!(&err, CommitGenError::NonJsonResponse {
+         status: 200,
+         kind: "an HTML page",
+         ..
+      }));
+      let message = err.to_string();
+      assert!(message.contains("content-type: text/html; charset=utf-8"), "{message}");
+      assert!(!message.contains("<body>"), "{message}");
+
+      let truncated = r#"{"choices":[{"message":{"content":"fix: trunc"#;
+      assert_eq!(ResponseBodyKind::of(truncated), ResponseBodyKind::MalformedJson);
+      assert!(matches!(
+         non_json_error(ok, "application/json", truncated),
+         Some(CommitGenError::NonJsonResponse { kind: "malformed JSON", .. })
+      ));
+
+      // Empty bodies keep their own retry; JSON passes through
+      assert_eq!(ResponseBodyKind::of("  \n"), ResponseBodyKind::Empty);
+      assert!(non_json_error(ok, "none", "").is_none());
+      assert!(non_json_error(ok, "application/json", r#"{"choices":[]}"#).is_none());
+      assert_eq!(ResponseBodyKind::of("Bad Gateway"), ResponseBodyKind::Text);
+   }
+
+   #[test]
+   fn test_non_json_responses_retry_only_on_5xx() {
+      let html = "<html><body>Login</body></html>";
+      let login = non_json_error(reqwest::StatusCode::OK, "text/html", html).unwrap();
+      let gateway = non_json_error(reqwest::StatusCode::BAD_GATEWAY, "text/html", html).unwrap();
+      assert!(!should_retry(&login));
+      assert!(should_retry(&gateway));
+
+      let config = CommitConfig { max_retries: 3, initial_backoff_ms: 1, ..Default::default() };
+      let mut attempts = 0;
+      let result: Result<()> = retry_api_call(&config, || {
+         attempts += 1;
+         Err(non_json_error(reqwest::StatusCode::OK, "text/html", html).unwrap())
+      });
+      assert!(matches!(result, Err(CommitGenError::NonJsonResponse { .. })));
+      assert_eq!(attempts, 1);
+
+      let mut attempts = 0;
+      let result: Result<()> = retry_api_call(&config, || {
+         attempts += 1;
+         Err(non_json_error(reqwest::StatusCode::BAD_GATEWAY, "text/html", html).unwrap())
+      });
+      assert!(matches!(result, Err(CommitGenError::NonJsonResponse { status: 502, .. })));
+      assert_eq!(attempts, 3);
+
+      // Every attempt lands in the config's counter, retries separately
+      let counters = config.api_counter.counters();
+      assert_eq!((counters.api_calls, counters.retries), (4, 2));
+   }
+
+   #[test]
+   fn test_find_tool_call_skips_other_calls() {
+      let response: ApiResponse = serde_json::from_str(
+         r#"{"choices":[{"message":{"tool_calls":[
+            {"function":{"name":"think","arguments":"{}"}},
+            {"function":{"name":"functions.create_conventional_analysis","arguments":"{\"type\":\"fix\",\"details\":[],\"issue_refs\":[]}"}}
+         ]}}]}"#,
+      )
+      .unwrap();
+      let message = &response.choices[0].message;
+
+      let call = message
+         .find_tool_call("create_conventional_analysis")
+         .unwrap();
+      let analysis: ConventionalAnalysis = serde_json::from_str(&call.function.arguments).unwrap();
+      assert_eq!(analysis.commit_type.as_str(), "fix");
+      assert!(message.find_tool_call("create_commit_summary").is_none());
+   }
+
+   #[test]
+   fn test_summary_from_content_fenced_and_unfenced() {
+      assert_eq!(
+         summary_from_content(r#"{"summary": "added retry jitter"}"#).unwrap(),
+         "added retry jitter"
+      );
+      assert_eq!(
+         summary_from_content("```json\n{\"summary\": \"added retry jitter\"}\n```").unwrap(),
+         "added retry jitter"
+      );
+      assert_eq!(
+         summary_from_content("Here you go:\n```\n{\"summary\": \"added retry jitter\"}\n```\n")
+            .unwrap(),
+         "added retry jitter"
+      );
+      // Plain text from models without function calling is used as is
+      assert_eq!(summary_from_content("  added retry jitter\n").unwrap(), "added retry jitter");
+      assert!(summary_from_content("```json\n{\"summary\": \n```").is_err());
+      assert!(summary_from_content(r#"{"summary": }"#).is_err());
+   }
+
+   #[test]
+   fn test_parse_json_content_fenced_analysis() {
+      let analysis: ConventionalAnalysis =
+         parse_json_content("```json\n{\"type\":\"fix\",\"details\":[],\"issue_refs\":[]}\n```")
+            .unwrap();
+      assert_eq!(analysis.commit_type.as_str(), "fix");
+
+      let err = parse_json_content::<ConventionalAnalysis>("not json").unwrap_err();
+      assert!(err.is_syntax(), "{err}");
+   }
+
+   #[test]
+   fn test_rationale_property_only_when_captured() {
+      let mut config = CommitConfig::default();
+      let mut properties = serde_json::json!({ "type": { "type": "string" } });
+      add_rationale_property(&mut properties, &config);
+      assert!(properties.get("rationale").is_none());
+
+      config.capture_rationale = true;
+      add_rationale_property(&mut properties, &config);
+      assert_eq!(properties["rationale"]["type"], "string");
+   }
+
+   #[test]
+   fn test_merge_split_analysis_takes_type_and_scope_from_classification() {
+      let classification: ConventionalAnalysis =
+         serde_json::from_str(r#"{"type":"fix","scope":"api","details":[],"issue_refs":[]}"#)
+            .unwrap();
+      let body: ConventionalAnalysis = serde_json::from_str(
+         r##"{"type":"feat","scope":"core","details":[{"text":"Fixed retry loop.","user_visible":false}],"issue_refs":["#4"]}"##,
+      )
+      .unwrap();
+
+      let merged = merge_split_analysis(classification, body);
+      assert_eq!(merged.commit_type.as_str(), "fix");
+      assert_eq!(merged.scope.as_ref().map(|s| s.as_str()), Some("api"));
+      assert_eq!(merged.details.len(), 1);
+      assert_eq!(merged.issue_refs, vec!["#4".to_string()]);
+   }
+
+   #[test]
+   fn test_should_use_fast_mode_threshold() {
+      let config = CommitConfig { fast_mode_threshold_chars: 10, ..CommitConfig::default() };
+      assert!(should_use_fast_mode("short", &config));
+      assert!(!should_use_fast_mode("exactly 10", &config));
+      assert!(!should_use_fast_mode("well over the threshold", &config));
+
+      let disabled = CommitConfig { fast_mode_threshold_chars: 0, ..CommitConfig::default() };
+      assert!(!should_use_fast_mode("", &disabled));
+   }
+
    #[test]
    fn test_validate_summary_quality_valid() {
       let stat = "src/main.rs | 10 +++++++---\n";
-      assert!(validate_summary_quality("added new feature", "feat", stat).is_ok());
-      assert!(validate_summary_quality("fixed critical bug", "fix", stat).is_ok());
-      assert!(validate_summary_quality("restructured module layout", "refactor", stat).is_ok());
+      assert!(
+         validate_summary_quality(
+            "added new feature",
+            "feat",
+            &ChangeProfile::from_stat(stat),
+            Some(Tense::Past)
+         )
+         .is_ok()
+      );
+      assert!(
+         validate_summary_quality(
+            "fixed critical bug",
+            "fix",
+            &ChangeProfile::from_stat(stat),
+            Some(Tense::Past)
+         )
+         .is_ok()
+      );
+      assert!(
+         validate_summary_quality(
+            "restructured module layout",
+            "refactor",
+            &ChangeProfile::from_stat(stat),
+            Some(Tense::Past)
+         )
+         .is_ok()
+      );
    }
 
    #[test]
    fn test_validate_summary_quality_invalid_verb() {
       let stat = "src/main.rs | 10 +++++++---\n";
-      let result = validate_summary_quality("adding new feature", "feat", stat);
+      let result = validate_summary_quality(
+         "adding new feature",
+         "feat",
+         &ChangeProfile::from_stat(stat),
+         Some(Tense::Past),
+      );
       assert!(result.is_err());
       assert!(result.unwrap_err().contains("past-tense verb"));
    }
@@ -1463,12 +2582,22 @@ mod tests {
    fn test_validate_summary_quality_type_repetition() {
       let stat = "src/main.rs | 10 +++++++---\n";
       // "feat" is not a past-tense verb so it should fail on verb check first
-      let result = validate_summary_quality("feat new feature", "feat", stat);
+      let result = validate_summary_quality(
+         "feat new feature",
+         "feat",
+         &ChangeProfile::from_stat(stat),
+         Some(Tense::Past),
+      );
       assert!(result.is_err());
       assert!(result.unwrap_err().contains("past-tense verb"));
 
       // "fixed" is past-tense but repeats "fix" type
-      let result = validate_summary_quality("fix bug", "fix", stat);
+      let result = validate_summary_quality(
+         "fix bug",
+         "fix",
+         &ChangeProfile::from_stat(stat),
+         Some(Tense::Past),
+      );
       assert!(result.is_err());
       // "fix" is not in PAST_TENSE_VERBS, so fails on verb check
       assert!(result.unwrap_err().contains("past-tense verb"));
@@ -1477,23 +2606,68 @@ mod tests {
    #[test]
    fn test_validate_summary_quality_empty() {
       let stat = "src/main.rs | 10 +++++++---\n";
-      let result = validate_summary_quality("", "feat", stat);
+      let result =
+         validate_summary_quality("", "feat", &ChangeProfile::from_stat(stat), Some(Tense::Past));
       assert!(result.is_err());
       assert!(result.unwrap_err().contains("empty"));
    }
 
+   #[test]
+   fn test_validate_summary_quality_tense_not_enforced() {
+      let stat = "src/main.rs | 10 +++++++---\n";
+      assert!(
+         validate_summary_quality("Add new feature", "feat", &ChangeProfile::from_stat(stat), None)
+            .is_ok()
+      );
+      assert!(
+         validate_summary_quality(
+            "adding new feature",
+            "feat",
+            &ChangeProfile::from_stat(stat),
+            None
+         )
+         .is_ok()
+      );
+      // Type repetition is still rejected
+      assert!(
+         validate_summary_quality(
+            "feat new feature",
+            "feat",
+            &ChangeProfile::from_stat(stat),
+            None
+         )
+         .is_err()
+      );
+   }
+
    #[test]
    fn test_validate_summary_quality_markdown_type_mismatch() {
       let stat = "README.md | 10 +++++++---\nDOCS.md | 5 +++++\n";
       // Should warn but not fail
-      assert!(validate_summary_quality("added documentation", "feat", stat).is_ok());
+      assert!(
+         validate_summary_quality(
+            "added documentation",
+            "feat",
+            &ChangeProfile::from_stat(stat),
+            Some(Tense::Past)
+         )
+         .is_ok()
+      );
    }
 
    #[test]
    fn test_validate_summary_quality_no_code_files() {
       let stat = "config.toml | 2 +-\nREADME.md | 1 +\n";
       // Should warn but not fail
-      assert!(validate_summary_quality("added config option", "feat", stat).is_ok());
+      assert!(
+         validate_summary_quality(
+            "added config option",
+            "feat",
+            &ChangeProfile::from_stat(stat),
+            Some(Tense::Past)
+         )
+         .is_ok()
+      );
    }
 
    #[test]
@@ -1561,6 +2735,14 @@ mod tests {
       assert_eq!(result.as_str(), "optimized module structure");
    }
 
+   #[test]
+   fn test_fallback_summary_records_fallback() {
+      let config = CommitConfig::default();
+      let before = crate::confidence::fallback_count();
+      let _ = fallback_summary("", &[], "feat", &config);
+      assert!(crate::confidence::fallback_count() > before);
+   }
+
    #[test]
    fn test_fallback_summary_with_stat() {
       let config = CommitConfig::default();
@@ -1597,13 +2779,367 @@ mod tests {
       assert_eq!(result.as_str(), "restructured change");
    }
 
+   #[test]
+   fn test_fallback_summary_uses_type_leading_verbs() {
+      let details = vec!["ci pipeline tweaks".to_string()];
+      let config = CommitConfig::default();
+      assert_eq!(fallback_summary("", &details, "ci", &config).as_str(), "configured tooling");
+
+      let config = CommitConfig {
+         summary_tense: Tense::Imperative,
+         body_tense: Tense::Imperative,
+         ..Default::default()
+      };
+      assert_eq!(fallback_summary("", &details, "ci", &config).as_str(), "configure tooling");
+
+      // Types without leading verbs keep the built-in choice
+      let mut config = CommitConfig::default();
+      config.types.get_mut("ci").unwrap().leading_verbs.clear();
+      assert_eq!(fallback_summary("", &details, "ci", &config).as_str(), "updated tooling");
+   }
+
    #[test]
    fn test_fallback_summary_length_limit() {
       let config = CommitConfig::default();
       let long_detail = "a ".repeat(100); // 200 chars
       let details = vec![long_detail.trim().to_string()];
       let result = fallback_summary("", &details, "feat", &config);
-      // Should truncate to conservative max (50 chars)
-      assert!(result.len() <= 50);
+      // Should fit the guideline alongside the `feat: ` prefix
+      assert!(result.len() <= config.summary_guideline - "feat: ".len());
+   }
+
+   #[test]
+   fn test_shorten_summary_drops_trailing_clauses() {
+      let long = "added retry logic to the http client, exposed backoff settings in config and \
+                  documented defaults";
+      let chars = LengthMetric::Chars;
+      assert_eq!(shorten_summary(long, 60, chars), "added retry logic to the http client");
+      assert_eq!(
+         shorten_summary(long, 80, chars),
+         "added retry logic to the http client, exposed backoff settings in config"
+      );
+      assert_eq!(shorten_summary("added parser", 60, chars), "added parser");
+   }
+
+   #[test]
+   fn test_shorten_summary_cuts_at_word_boundary_without_clauses() {
+      let result = shorten_summary(
+         "restructured configuration loading pipeline internals",
+         30,
+         LengthMetric::Chars,
+      );
+      assert_eq!(result, "restructured configuration");
+      assert_eq!(shorten_summary("supercalifragilistic", 5, LengthMetric::Chars), "super");
+   }
+
+   #[test]
+   fn test_shorten_summary_measures_with_metric() {
+      // 11 chars but 14 bytes: fits by chars, not by bytes
+      let accented = "café résumé";
+      assert_eq!(shorten_summary(accented, 11, LengthMetric::Chars), accented);
+      assert_eq!(shorten_summary(accented, 11, LengthMetric::Bytes), "café");
+
+      // Each of these counts 1 char but 2 columns
+      let wide = "修复解析器错误";
+      assert_eq!(shorten_summary(wide, 5, LengthMetric::Chars), "修复解析器");
+      assert_eq!(shorten_summary(wide, 5, LengthMetric::Width), "修复");
+      assert_eq!(shorten_summary(wide, 5, LengthMetric::Bytes), "修");
+   }
+
+   #[test]
+   fn test_fallback_summary_shortens_at_clause_boundary() {
+      let config = CommitConfig { summary_guideline: 52, ..CommitConfig::default() };
+      let details = vec![
+         "Added streaming support to the export command, with progress output and resumable \
+          downloads."
+            .to_string(),
+      ];
+      let result = fallback_summary("", &details, "feat", &config);
+      assert_eq!(result.as_str(), "Added streaming support to the export command");
+   }
+
+   fn imperative_config() -> CommitConfig {
+      CommitConfig { summary_tense: Tense::Imperative, ..Default::default() }
+   }
+
+   #[test]
+   fn test_validate_summary_quality_imperative_valid() {
+      let stat = "src/main.rs | 10 +++++++---\n";
+      let tense = Tense::Imperative;
+      assert!(
+         validate_summary_quality(
+            "add new feature",
+            "feat",
+            &ChangeProfile::from_stat(stat),
+            Some(tense)
+         )
+         .is_ok()
+      );
+      assert!(
+         validate_summary_quality(
+            "correct critical bug",
+            "fix",
+            &ChangeProfile::from_stat(stat),
+            Some(tense)
+         )
+         .is_ok()
+      );
+      assert!(
+         validate_summary_quality(
+            "restructure module layout",
+            "refactor",
+            &ChangeProfile::from_stat(stat),
+            Some(tense)
+         )
+         .is_ok()
+      );
+   }
+
+   #[test]
+   fn test_validate_summary_quality_imperative_invalid_verb() {
+      let stat = "src/main.rs | 10 +++++++---\n";
+      for summary in ["adding new feature", "added new feature", "adds new feature"] {
+         let result = validate_summary_quality(
+            summary,
+            "feat",
+            &ChangeProfile::from_stat(stat),
+            Some(Tense::Imperative),
+         );
+         assert!(result.unwrap_err().contains("imperative verb"), "{summary}");
+      }
+   }
+
+   #[test]
+   fn test_validate_summary_quality_imperative_type_repetition() {
+      let stat = "src/main.rs | 10 +++++++---\n";
+      // "fix" is a valid imperative verb, so repetition is what fails
+      let result = validate_summary_quality(
+         "fix bug",
+         "fix",
+         &ChangeProfile::from_stat(stat),
+         Some(Tense::Imperative),
+      );
+      assert!(result.unwrap_err().contains("repeats commit type"));
+   }
+
+   #[test]
+   fn test_fallback_from_details_imperative_converts_detail_verb() {
+      let config = imperative_config();
+      let details = vec!["Added authentication middleware.".to_string()];
+      let result = fallback_from_details_or_summary(&details, "invalid verb", "feat", &config);
+      assert_eq!(result.as_str(), "add authentication middleware");
+   }
+
+   #[test]
+   fn test_fallback_from_details_imperative_type_specific_verbs() {
+      let config = imperative_config();
+      let details = vec!["module structure".to_string()];
+
+      let cases = [
+         ("feat", "add module structure"),
+         ("fix", "correct module structure"),
+         ("refactor", "restructure module structure"),
+         ("docs", "document module structure"),
+         ("test", "cover module structure"),
+         ("perf", "optimize module structure"),
+         ("revert", "undo module structure"),
+      ];
+      for (commit_type, expected) in cases {
+         let result = fallback_from_details_or_summary(&details, "invalid", commit_type, &config);
+         assert_eq!(result.as_str(), expected);
+         // Fallback output must itself pass validation
+         assert!(
+            validate_summary_quality(
+               result.as_str(),
+               commit_type,
+               &ChangeProfile::default(),
+               Some(Tense::Imperative)
+            )
+            .is_ok()
+         );
+      }
+   }
+
+   #[test]
+   fn test_fallback_summary_imperative() {
+      let config = imperative_config();
+      assert_eq!(fallback_summary("", &[], "feat", &config).as_str(), "Update files");
+
+      let stat = "src/main.rs | 10 +++++++---\n";
+      assert_eq!(fallback_summary(stat, &[], "feat", &config).as_str(), "Update src/main.rs");
+
+      let details = vec!["refactor was performed".to_string()];
+      let result = fallback_summary("", &details, "refactor", &config);
+      assert_eq!(result.as_str(), "restructure change");
+   }
+
+   #[test]
+   fn test_rename_only_analysis_is_deterministic() {
+      let config = CommitConfig::default();
+      let diff = "diff --git a/src/old.rs b/src/new.rs
+similarity index 100%
+rename from src/old.rs
+rename to src/new.rs
+diff --git a/docs/a.md b/docs/b.md
+similarity index 100%
+rename from docs/a.md
+rename to docs/b.md";
+      let analysis = rename_only_analysis(diff, &config).unwrap();
+      assert_eq!(analysis.commit_type.as_str(), "refactor");
+      assert_eq!(analysis.body_texts(), vec![
+         "Renamed src/old.rs to src/new.rs.".to_string(),
+         "Renamed docs/a.md to docs/b.md.".to_string(),
+      ]);
+   }
+
+   #[test]
+   fn test_rename_only_analysis_non_source_is_chore() {
+      let config = CommitConfig::default();
+      let diff = "diff --git a/docs/a.md b/docs/b.md
+similarity index 100%
+rename from docs/a.md
+rename to docs/b.md";
+      let analysis = rename_only_analysis(diff, &config).unwrap();
+      assert_eq!(analysis.commit_type.as_str(), "chore");
+   }
+
+   #[test]
+   fn test_rename_only_analysis_skips_mixed_diffs() {
+      let config = CommitConfig::default();
+      let diff = "diff --git a/src/old.rs b/src/new.rs
+similarity index 100%
+rename from src/old.rs
+rename to src/new.rs
+diff --git a/src/lib.rs b/src/lib.rs
+index abc..def 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1 @@
+-mod old;
++mod new;";
+      assert!(rename_only_analysis(diff, &config).is_none());
+   }
+
+   #[test]
+   fn test_analysis_request_has_change_profile_section() {
+      let ctx = AnalysisContext {
+         change_profile: Some("Changed lines by kind: docs 60%, code 40%"),
+         ..Default::default()
+      };
+      let parts = render_analysis_request(
+         " src/lib.rs | 2 +-",
+         "+/// Docs",
+         "lib (100%)",
+         &ctx,
+         &CommitConfig::default(),
+      )
+      .unwrap();
+      assert!(
+         parts.user.contains(
+            "<change_profile>\nChanged lines by kind: docs 60%, code 40%\n</change_profile>"
+         ),
+         "{}",
+         parts.user
+      );
+      assert!(
+         parts
+            .user
+            .contains("<scope_candidates>\nlib (100%)\n</scope_candidates>")
+      );
+   }
+
+   #[test]
+   fn test_summary_request_includes_change_weights() {
+      let debug_dir =
+         std::env::temp_dir().join(format!("llm-git-summary-weights-{}", std::process::id()));
+      // Unroutable endpoint: the request is saved to the debug dir before sending
+      let config = CommitConfig {
+         api_base_url: "http://127.0.0.1:9".to_string(),
+         api_mode: crate::config::ApiMode::ChatCompletions,
+         max_retries: 1,
+         connect_timeout_secs: 1,
+         summary_include_weights: true,
+         ..Default::default()
+      };
+
+      let _ = generate_summary_from_analysis(
+         " src/api/client.rs | 80 ++++\n src/db/pool.rs | 20 ++",
+         &ChangeProfile::default(),
+         "feat",
+         None,
+         &["Added request batching to the API client.".to_string()],
+         None,
+         Some("80% api, 20% db"),
+         &config,
+         Some(&debug_dir),
+         None,
+      );
+
+      let request = std::fs::read_to_string(debug_dir.join("summary_request.json")).unwrap();
+      std::fs::remove_dir_all(&debug_dir).ok();
+      assert!(request.contains("80% api, 20% db"));
+   }
+
+   #[test]
+   fn test_offline_strict_analysis_never_builds_client() {
+      // A blackhole address would hang until the connect timeout if a client
+      // were ever built and used
+      let config = CommitConfig {
+         api_base_url: "http://10.255.255.1:4000".to_string(),
+         offline_strict: true,
+         max_retries: 3,
+         initial_backoff_ms: 5000,
+         ..Default::default()
+      };
+
+      let started = std::time::Instant::now();
+      let result = generate_conventional_analysis(
+         " src/lib.rs | 2 +-",
+         "diff --git a/src/lib.rs b/src/lib.rs",
+         &config.model,
+         "",
+         &AnalysisContext::default(),
+         &config,
+      );
+
+      assert!(matches!(result, Err(CommitGenError::NetworkDisabled(_))));
+      // No retries or backoff: the error is returned before any client exists
+      assert!(started.elapsed() < std::time::Duration::from_secs(1));
+      assert!(matches!(build_client(&config), Err(CommitGenError::NetworkDisabled(_))));
+   }
+
+   #[test]
+   fn test_analysis_user_content_names_deletions_and_renames() {
+      let diff = "diff --git a/src/legacy/auth.rs b/src/legacy/auth.rs
+deleted file mode 100644
+index abc..000 100644
+--- a/src/legacy/auth.rs
++++ /dev/null
+@@ -1,412 +0,0 @@
+-pub fn login() {}
+diff --git a/src/io.rs b/src/io/mod.rs
+similarity index 90%
+rename from src/io.rs
+rename to src/io/mod.rs
+index abc..def 100644
+--- a/src/io.rs
++++ b/src/io/mod.rs
+@@ -1 +1 @@
+-pub fn read() {}
++pub fn read_all() {}";
+      let ctx = AnalysisContext { user_context: Some("Dropped old auth"), ..Default::default() };
+
+      let content = analysis_user_content(&ctx, diff, "<diff/>".to_string());
+      assert_eq!(
+         content,
+         "ADDITIONAL CONTEXT FROM USER:\nDropped old auth\n\nDELETED FILES: src/legacy/auth.rs \
+          (412 lines)\nRENAMED: src/io.rs -> src/io/mod.rs\n\n<diff/>"
+      );
+
+      let plain = "diff --git a/src/lib.rs b/src/lib.rs\n@@ -1 +1 @@\n-a\n+b";
+      assert_eq!(
+         analysis_user_content(&AnalysisContext::default(), plain, "<diff/>".to_string()),
+         "<diff/>"
+      );
    }
 }
@@ -10,13 +10,12 @@ use std::{
    collections::HashMap,
    path::{Path, PathBuf},
    process::Command,
-   thread,
-   time::Duration,
 };
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
+   api::retry_api_call,
    config::CommitConfig,
    diff::smart_truncate_diff,
    error::{CommitGenError, Result},
@@ -39,7 +38,8 @@ struct ChangelogResponse {
 struct ApiRequest {
    model:       String,
    max_tokens:  u32,
-   temperature: f32,
+   #[serde(skip_serializing_if = "Option::is_none")]
+   temperature: Option<f32>,
    tools:       Vec<Tool>,
    #[serde(skip_serializing_if = "Option::is_none")]
    tool_choice: Option<serde_json::Value>,
@@ -271,11 +271,7 @@ fn call_changelog_api(
    parts: &templates::PromptParts,
    config: &CommitConfig,
 ) -> Result<ChangelogResponse> {
-   let client = reqwest::blocking::Client::builder()
-      .timeout(Duration::from_secs(config.request_timeout_secs))
-      .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
-      .build()
-      .expect("Failed to build HTTP client");
+   let client = crate::api::build_client(config)?;
 
    let model = config.model.clone();
 
@@ -336,10 +332,7 @@ fn call_changelog_api(
       },
    };
 
-   let mut attempt = 0;
-   loop {
-      attempt += 1;
-
+   retry_api_call(config, || {
       let mut messages = Vec::new();
       if !parts.system.is_empty() {
          messages.push(Message { role: "system".to_string(), content: parts.system.clone() });
@@ -349,7 +342,7 @@ fn call_changelog_api(
       let request = ApiRequest {
          model: model.clone(),
          max_tokens: 2000,
-         temperature: config.temperature,
+         temperature: config.temperature_for(&model),
          tools: vec![tool.clone()],
          tool_choice: Some(
             serde_json::json!({ "type": "function", "function": { "name": "create_changelog_entries" } }),
@@ -372,23 +365,10 @@ fn call_changelog_api(
 
       let status = response.status();
 
+      // Retry on 5xx errors
       if status.is_server_error() {
-         if attempt < config.max_retries {
-            let backoff_ms = config.initial_backoff_ms * (1 << (attempt - 1));
-            eprintln!(
-               "{}",
-               crate::style::warning(&format!(
-                  "Server error {status}, retry {attempt}/{} after {backoff_ms}ms...",
-                  config.max_retries
-               ))
-            );
-            thread::sleep(Duration::from_millis(backoff_ms));
-            continue;
-         }
-         let error_text = response
-            .text()
-            .unwrap_or_else(|_| "Unknown error".to_string());
-         return Err(CommitGenError::ApiError { status: status.as_u16(), body: error_text });
+         eprintln!("{}", crate::style::warning(&format!("Server error {status}")));
+         return Ok((true, None));
       }
 
       if !status.is_success() {
@@ -424,7 +404,7 @@ fn call_changelog_api(
                            .collect::<String>()
                      ))
                   })?;
-               return Ok(changelog_response);
+               return Ok((false, Some(changelog_response)));
             }
          }
 
@@ -439,7 +419,7 @@ fn call_changelog_api(
                         json_str.chars().take(500).collect::<String>()
                      ))
                   })?;
-               return Ok(changelog_response);
+               return Ok((false, Some(changelog_response)));
             }
          }
       }
@@ -459,8 +439,8 @@ fn call_changelog_api(
          ))
       })?;
 
-      return Ok(changelog_response);
-   }
+      Ok((false, Some(changelog_response)))
+   })
 }
 
 /// Extract JSON from content that may be wrapped in markdown code blocks
@@ -1,19 +1,28 @@
-use std::{path::Path, sync::OnceLock, time::Duration};
+use std::{
+   io::{BufRead, IsTerminal, Write},
+   sync::OnceLock,
+   time::Duration,
+};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
-   api::{AnalysisContext, generate_conventional_analysis},
-   config::CommitConfig,
+   analysis::{classify_change_profile, is_dependency_file},
+   api::{AnalysisContext, generate_conventional_analysis, parse_json_content, retry_api_call},
+   config::{ApplyErrorStrategy, CommitConfig, ComposeScopeSource},
+   diagnostics,
    diff::smart_truncate_diff,
    error::{CommitGenError, Result},
-   git::{get_git_diff, get_git_stat, get_head_hash, git_commit},
+   git::{self, CommitDates, get_git_diff, get_git_stat, get_head_hash, git_commit},
    normalization::{format_commit_message, post_process_commit_message},
-   patch::{reset_staging, stage_group_changes},
+   patch::{check_group_changes, reset_staging, selectors_claim_hunks, stage_group_changes},
+   stats::{RunStats, RunTimer},
    style,
    tokens::create_token_counter,
-   types::{Args, ChangeGroup, CommitType, ComposeAnalysis, ConventionalCommit, Mode},
-   validation::validate_commit_message,
+   types::{
+      Args, ChangeGroup, CommitType, ComposeAnalysis, ConventionalCommit, HunkSelector, Mode, Scope,
+   },
+   validation::{get_repository_name, is_project_name_scope, validate_commit_message},
 };
 
 static CLIENT: OnceLock<reqwest::blocking::Client> = OnceLock::new();
@@ -60,7 +69,8 @@ struct Tool {
 struct ApiRequest {
    model:       String,
    max_tokens:  u32,
-   temperature: f32,
+   #[serde(skip_serializing_if = "Option::is_none")]
+   temperature: Option<f32>,
    tools:       Vec<Tool>,
    #[serde(skip_serializing_if = "Option::is_none")]
    tool_choice: Option<serde_json::Value>,
@@ -105,7 +115,7 @@ const COMPOSE_PROMPT: &str = r#"Split this git diff into 1-{MAX_COMMITS} logical
 
 ## Git Diff
 {DIFF}
-
+{PREVIOUS_COMMITS}
 ## Rules (CRITICAL)
 1. **EXHAUSTIVENESS**: You MUST account for 100% of changes. Every file and hunk in the diff above must appear in exactly one group.
 2. **Atomicity**: Each group represents ONE logical change (feat/fix/refactor/etc.) that leaves codebase working.
@@ -114,6 +124,7 @@ const COMPOSE_PROMPT: &str = r#"Split this git diff into 1-{MAX_COMMITS} logical
 5. **Dependencies**: Use indices. Group 2 depending on Group 1 means: dependencies: [0].
 6. **Hunk selection** (IMPORTANT - Use line numbers, NOT hunk headers):
    - If entire file → hunks: ["ALL"]
+   - New files ("new file mode") → always hunks: ["ALL"]
    - If partial → specify line ranges: hunks: [{start: 10, end: 25}, {start: 50, end: 60}]
    - Line numbers are 1-indexed from the ORIGINAL file (look at "-" lines in diff)
    - You can specify multiple ranges for discontinuous changes in one file
@@ -152,65 +163,17 @@ struct ComposeResult {
 }
 
 fn parse_compose_groups_from_content(content: &str) -> Result<Vec<ChangeGroup>> {
-   fn try_parse(input: &str) -> Option<Vec<ChangeGroup>> {
-      let trimmed = input.trim();
-      if trimmed.is_empty() {
-         return None;
-      }
-
-      serde_json::from_str::<ComposeResult>(trimmed)
-         .map(|r| r.groups)
-         .ok()
-   }
-
-   let trimmed = content.trim();
-   if trimmed.is_empty() {
+   if content.trim().is_empty() {
       return Err(CommitGenError::Other(
          "Model returned an empty compose analysis response".to_string(),
       ));
    }
 
-   if let Some(groups) = try_parse(trimmed) {
-      return Ok(groups);
-   }
-
-   if let (Some(start), Some(end)) = (trimmed.find('{'), trimmed.rfind('}'))
-      && end >= start
-   {
-      let candidate = &trimmed[start..=end];
-      if let Some(groups) = try_parse(candidate) {
-         return Ok(groups);
-      }
-   }
-
-   let segments: Vec<&str> = trimmed.split("```").collect();
-   for (idx, segment) in segments.iter().enumerate() {
-      if idx % 2 == 1 {
-         let block = segment.trim();
-         let mut lines = block.lines();
-         let first_line = lines.next().unwrap_or_default();
-
-         let mut owned_candidate: Option<String> = None;
-         let json_candidate = if first_line.trim_start().starts_with('{') {
-            block
-         } else {
-            let rest: String = lines.collect::<Vec<_>>().join("\n");
-            let trimmed_rest = rest.trim();
-            if trimmed_rest.is_empty() {
-               block
-            } else {
-               owned_candidate = Some(trimmed_rest.to_string());
-               owned_candidate.as_deref().unwrap()
-            }
-         };
-
-         if let Some(groups) = try_parse(json_candidate) {
-            return Ok(groups);
-         }
-      }
-   }
-
-   Err(CommitGenError::Other("Failed to parse compose analysis from model response".to_string()))
+   parse_json_content::<ComposeResult>(content)
+      .map(|r| r.groups)
+      .map_err(|_| {
+         CommitGenError::Other("Failed to parse compose analysis from model response".to_string())
+      })
 }
 
 fn parse_compose_groups_from_json(
@@ -234,51 +197,91 @@ fn debug_failed_payload(source: &str, payload: &str, err: &serde_json::Error) {
    eprintln!("Compose debug: failed to parse {source} payload ({err}); preview: {preview}");
 }
 
+/// Where a compose commit's scope came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeOrigin {
+   Group,
+   Analysis,
+   /// Neither source offered a usable scope
+   None,
+}
+
+impl std::fmt::Display for ScopeOrigin {
+   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      f.write_str(match self {
+         Self::Group => "group",
+         Self::Analysis => "analysis",
+         Self::None => "none",
+      })
+   }
+}
+
+/// Pick a compose commit's scope from the grouping pass' scope and the
+/// per-group message analysis' scope.
+///
+/// A scope naming the project (`repo_name`) is never used. Under
+/// `Reconcile` the group scope wins, since it saw the whole change set, and
+/// the analysis scope fills in when the group has none or names the project.
+pub fn reconcile_compose_scope(
+   group: Option<&Scope>,
+   analysis: Option<&Scope>,
+   policy: ComposeScopeSource,
+   repo_name: Option<&str>,
+) -> (Option<Scope>, ScopeOrigin) {
+   let is_project =
+      |s: &&Scope| repo_name.is_some_and(|repo| is_project_name_scope(s.as_str(), repo));
+   let group = group
+      .filter(|s| !is_project(s))
+      .map(|s| (s.clone(), ScopeOrigin::Group));
+   let analysis = analysis
+      .filter(|s| !is_project(s))
+      .map(|s| (s.clone(), ScopeOrigin::Analysis));
+
+   let chosen = match policy {
+      ComposeScopeSource::Group => group,
+      ComposeScopeSource::Analysis => analysis,
+      ComposeScopeSource::Reconcile => group.or(analysis),
+   };
+   match chosen {
+      Some((scope, origin)) => (Some(scope), origin),
+      None => (None, ScopeOrigin::None),
+   }
+}
+
 fn group_affects_only_dependency_files(group: &ChangeGroup) -> bool {
    group
       .changes
       .iter()
-      .all(|change| is_dependency_manifest(&change.path))
+      .all(|change| is_dependency_file(&change.path))
 }
 
-fn is_dependency_manifest(path: &str) -> bool {
-   const DEP_MANIFESTS: &[&str] = &[
-      "Cargo.toml",
-      "Cargo.lock",
-      "package.json",
-      "package-lock.json",
-      "pnpm-lock.yaml",
-      "yarn.lock",
-      "bun.lock",
-      "bun.lockb",
-      "go.mod",
-      "go.sum",
-      "requirements.txt",
-      "Pipfile",
-      "Pipfile.lock",
-      "pyproject.toml",
-      "Gemfile",
-      "Gemfile.lock",
-      "composer.json",
-      "composer.lock",
-      "build.gradle",
-      "build.gradle.kts",
-      "gradle.properties",
-      "pom.xml",
-   ];
-
-   let path = Path::new(path);
-   let Some(file_name) = path.file_name().and_then(|s| s.to_str()) else {
-      return false;
-   };
-
-   if DEP_MANIFESTS.contains(&file_name) {
-      return true;
+/// Compose prompt section listing the commits earlier rounds created, so a
+/// later round doesn't propose them again; empty in the first round
+fn previous_commits_section(previous_commits: &[String]) -> String {
+   if previous_commits.is_empty() {
+      return String::new();
    }
+   let list: Vec<String> = previous_commits
+      .iter()
+      .map(|subject| format!("- {subject}"))
+      .collect();
+   format!(
+      "\n## COMMITS ALREADY CREATED THIS SESSION\nThese are committed; the diff above is what \
+       remains. Don't repeat their rationale or split the rest to mirror them.\n{}\n",
+      list.join("\n")
+   )
+}
 
-   Path::new(file_name)
-      .extension()
-      .is_some_and(|ext| ext.eq_ignore_ascii_case("lock") || ext.eq_ignore_ascii_case("lockb"))
+/// Analysis context for a group's message: commits created so far this
+/// session, so the message doesn't restate them
+fn session_commits_context(subjects: &[String]) -> Option<String> {
+   (!subjects.is_empty()).then(|| {
+      let list: Vec<String> = subjects
+         .iter()
+         .map(|subject| format!("- {subject}"))
+         .collect();
+      format!("Commits already created this session:\n{}", list.join("\n"))
+   })
 }
 
 /// Call AI to analyze and group changes for compose mode
@@ -287,7 +290,9 @@ pub fn analyze_for_compose(
    stat: &str,
    config: &CommitConfig,
    max_commits: usize,
+   previous_commits: &[String],
 ) -> Result<ComposeAnalysis> {
+   crate::api::ensure_network_allowed(config)?;
    let client = get_client();
 
    let tool = Tool {
@@ -366,12 +371,13 @@ pub fn analyze_for_compose(
    let prompt = COMPOSE_PROMPT
       .replace("{STAT}", stat)
       .replace("{DIFF}", diff)
-      .replace("{MAX_COMMITS}", &max_commits.to_string());
+      .replace("{MAX_COMMITS}", &max_commits.to_string())
+      .replace("{PREVIOUS_COMMITS}", &previous_commits_section(previous_commits));
 
    let request = ApiRequest {
       model:       config.model.clone(),
       max_tokens:  8000,
-      temperature: config.temperature,
+      temperature: config.temperature_for(&config.model),
       tools:       vec![tool],
       tool_choice: Some(
          serde_json::json!({ "type": "function", "function": { "name": "create_compose_analysis" } }),
@@ -379,22 +385,24 @@ pub fn analyze_for_compose(
       messages:    vec![Message { role: "user".to_string(), content: prompt }],
    };
 
-   let response = client
-      .post(format!("{}/chat/completions", config.api_base_url))
-      .header("content-type", "application/json")
-      .json(&request)
-      .send()
-      .map_err(CommitGenError::HttpError)?;
-
-   let status = response.status();
-   if !status.is_success() {
-      let error_text = response
-         .text()
-         .unwrap_or_else(|_| "Unknown error".to_string());
-      return Err(CommitGenError::ApiError { status: status.as_u16(), body: error_text });
-   }
+   let api_response: ApiResponse = retry_api_call(config, || {
+      let response = client
+         .post(format!("{}/chat/completions", config.api_base_url))
+         .header("content-type", "application/json")
+         .json(&request)
+         .send()
+         .map_err(CommitGenError::HttpError)?;
+
+      let status = response.status();
+      if !status.is_success() {
+         let error_text = response
+            .text()
+            .unwrap_or_else(|_| "Unknown error".to_string());
+         return Err(CommitGenError::ApiError { status: status.as_u16(), body: error_text });
+      }
 
-   let api_response: ApiResponse = response.json().map_err(CommitGenError::HttpError)?;
+      Ok((false, Some(response.json().map_err(CommitGenError::HttpError)?)))
+   })?;
 
    if api_response.choices.is_empty() {
       return Err(CommitGenError::Other(
@@ -685,64 +693,330 @@ fn validate_compose_groups(groups: &[ChangeGroup], full_diff: &str) -> Result<()
    Ok(())
 }
 
-/// Execute compose: stage groups, generate messages, create commits
+/// `println!` gated on a `verbose` flag, so library callers of [`compose`] get
+/// the structured [`ComposeOutcome`] instead of console output
+macro_rules! progress {
+   ($verbose:expr, $($arg:tt)*) => {
+      if $verbose {
+         println!($($arg)*);
+      }
+   };
+}
+
+/// One compose round: the proposed plan and what was committed from it
+#[derive(Debug, Clone)]
+pub struct ComposeRound {
+   pub analysis:      ComposeAnalysis,
+   /// Created commits in order (empty in preview mode)
+   pub commit_hashes: Vec<String>,
+   /// Files skipped after hunk apply failures, left for a later round
+   pub skipped_files: Vec<String>,
+}
+
+/// Structured result of [`compose`]
+#[derive(Debug, Clone, Default)]
+pub struct ComposeOutcome {
+   pub rounds:          Vec<ComposeRound>,
+   /// Files still uncommitted when compose stopped (empty in preview mode)
+   pub uncovered_files: Vec<String>,
+   /// Stage timings (summed over groups and rounds) and API call counts
+   pub stats:           RunStats,
+}
+
+/// `type(scope)` for a group as it will be committed; dependency-only groups
+/// become `build`
+fn group_label(group: &ChangeGroup) -> String {
+   let commit_type = if group_affects_only_dependency_files(group) {
+      "build"
+   } else {
+      group.commit_type.as_str()
+   };
+   match &group.scope {
+      Some(scope) => format!("{commit_type}({scope})"),
+      None => commit_type.to_string(),
+   }
+}
+
+/// One-line overview of a compose plan, groups in commit order:
+/// `splitting into 3 commits: feat(api), build(db), docs`
+pub fn plan_summary(analysis: &ComposeAnalysis) -> String {
+   let labels: Vec<String> = analysis
+      .dependency_order
+      .iter()
+      .filter_map(|&idx| analysis.groups.get(idx))
+      .map(group_label)
+      .collect();
+   let noun = if labels.len() == 1 {
+      "commit"
+   } else {
+      "commits"
+   };
+   format!("splitting into {} {noun}: {}", labels.len(), labels.join(", "))
+}
+
+/// Ask on the terminal whether to go ahead with the plan; EOF declines
+fn confirm_plan() -> Result<bool> {
+   eprint!("  Proceed? [Y/n] ");
+   std::io::stderr().flush()?;
+
+   let mut answer = String::new();
+   if std::io::stdin().lock().read_line(&mut answer)? == 0 {
+      return Ok(false);
+   }
+   Ok(matches!(answer.trim().to_ascii_lowercase().as_str(), "" | "y" | "yes"))
+}
+
+/// Ask on the terminal how to recover from a failed hunk application
+fn prompt_apply_error_strategy() -> Result<ApplyErrorStrategy> {
+   let stdin = std::io::stdin();
+   loop {
+      eprint!("  Retry with the whole file, skip it for a later round, or abort? [w/s/a] ");
+      std::io::stderr().flush()?;
+
+      let mut answer = String::new();
+      if stdin.lock().read_line(&mut answer)? == 0 {
+         return Ok(ApplyErrorStrategy::Abort);
+      }
+      match answer.trim().to_ascii_lowercase().as_str() {
+         "w" | "widen" => return Ok(ApplyErrorStrategy::Widen),
+         "s" | "skip" => return Ok(ApplyErrorStrategy::Skip),
+         "a" | "abort" => return Ok(ApplyErrorStrategy::Abort),
+         _ => {},
+      }
+   }
+}
+
+/// Stage a group, recovering from [`CommitGenError::HunkApplyFailed`] with
+/// `strategy` (or by asking on a TTY when unset).
+///
+/// Widening replaces the failed file's selectors with `All`, but only when
+/// none of `later_groups` (index, group) claim any of that file's hunks;
+/// otherwise the file is skipped. Skipped files are removed from `group` and
+/// returned so the caller can leave them for a later round.
+fn stage_group_with_recovery(
+   group: &mut ChangeGroup,
+   group_idx: usize,
+   later_groups: &[(usize, &ChangeGroup)],
+   dir: &str,
+   baseline_diff: &str,
+   strategy: Option<ApplyErrorStrategy>,
+) -> Result<Vec<String>> {
+   let mut uncovered = Vec::new();
+
+   loop {
+      let err = match stage_group_changes(group, group_idx, dir, baseline_diff) {
+         Ok(()) => return Ok(uncovered),
+         Err(e) => e,
+      };
+      let CommitGenError::HunkApplyFailed { file, .. } = &err else {
+         return Err(err);
+      };
+      let file = file.clone();
+
+      eprintln!("  {}", style::warning(&format!("{} {err}", style::icons::WARNING)));
+
+      let action = match strategy {
+         Some(strategy) => strategy,
+         None if std::io::stdin().is_terminal() => prompt_apply_error_strategy()?,
+         None => ApplyErrorStrategy::Abort,
+      };
+
+      let conflicts: Vec<usize> = later_groups
+         .iter()
+         .filter(|(_, other)| {
+            other
+               .changes
+               .iter()
+               .any(|c| c.path == file && selectors_claim_hunks(baseline_diff, &file, &c.hunks))
+         })
+         .map(|(idx, _)| *idx)
+         .collect();
+
+      match action {
+         ApplyErrorStrategy::Abort => return Err(err),
+         ApplyErrorStrategy::Widen if conflicts.is_empty() => {
+            eprintln!("  {}", style::info(&format!("Staging all changes in {file}")));
+            for change in group.changes.iter_mut().filter(|c| c.path == file) {
+               change.hunks = vec![HunkSelector::All];
+            }
+         },
+         ApplyErrorStrategy::Widen | ApplyErrorStrategy::Skip => {
+            if !conflicts.is_empty() {
+               eprintln!(
+                  "  {}",
+                  style::warning(&format!(
+                     "Cannot widen {file}: group(s) {conflicts:?} also select its hunks"
+                  ))
+               );
+            }
+            eprintln!("  {}", style::info(&format!("Skipping {file} for a later round")));
+            group.changes.retain(|c| c.path != file);
+            uncovered.push(file);
+         },
+      }
+
+      // Earlier files of the group may already be staged; start over cleanly
+      reset_staging(dir)?;
+   }
+}
+
+/// Full diff against HEAD, which hunk selectors refer to, followed by add-file
+/// diffs for untracked files (stubs past `untracked_max_bytes`)
+fn baseline_diff(dir: &str, untracked_max_bytes: u64) -> Result<String> {
+   let output = std::process::Command::new("git")
+      .args(["diff", "HEAD"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to get baseline diff: {e}")))?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git diff HEAD failed: {stderr}")));
+   }
+
+   let mut diff = String::from_utf8_lossy(&output.stdout).to_string();
+   for file in git::get_untracked_files(dir)? {
+      diff.push_str(&git::untracked_file_diff(dir, &file, Some(untracked_max_bytes)));
+   }
+   Ok(diff)
+}
+
+/// `git diff HEAD --stat` plus lines for untracked files
+fn baseline_stat(dir: &str) -> Result<String> {
+   let output = std::process::Command::new("git")
+      .args(["diff", "HEAD", "--stat"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to get baseline stat: {e}")))?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git diff HEAD --stat failed: {stderr}")));
+   }
+
+   let mut stat = String::from_utf8_lossy(&output.stdout).to_string();
+   stat.push_str(&git::untracked_stat(dir, &git::get_untracked_files(dir)?));
+   Ok(stat)
+}
+
+/// Dry-run staging every group (`--compose-validate`), reporting each
+/// selection that wouldn't stage; the index is left as it was
+pub fn check_compose_staging(
+   analysis: &ComposeAnalysis,
+   dir: &str,
+   config: &CommitConfig,
+) -> Result<()> {
+   let baseline_diff = baseline_diff(dir, config.compose_untracked_max_bytes)?;
+   let mut failures = Vec::new();
+   for &group_idx in &analysis.dependency_order {
+      if let Err(err) =
+         check_group_changes(&analysis.groups[group_idx], group_idx, dir, &baseline_diff)
+      {
+         eprintln!("{}", style::warning(&format!("{} {err}", style::icons::WARNING)));
+         failures.push(err.to_string());
+      }
+   }
+
+   if failures.is_empty() {
+      Ok(())
+   } else {
+      Err(CommitGenError::ValidationError(format!(
+         "{} group(s) have hunk selections that won't stage: {}",
+         failures.len(),
+         failures.join("; ")
+      )))
+   }
+}
+
+/// Whether compose stops after showing the plan (`--compose-preview`, or
+/// `--compose-validate` after its staging dry run)
+const fn is_dry_run(args: &Args) -> bool {
+   args.compose_preview || args.compose_validate
+}
+
+/// Execute compose: stage groups, generate messages, create commits.
+///
+/// Progress goes to stdout only when `verbose` is set; each group's stages are
+/// timed into `timer`. `previous_commits` are the subjects earlier rounds
+/// committed, shown to each group's message generation with those created
+/// here.
 pub fn execute_compose(
    analysis: &ComposeAnalysis,
    config: &CommitConfig,
    args: &Args,
-) -> Result<Vec<String>> {
+   verbose: bool,
+   timer: &RunTimer,
+   previous_commits: &[String],
+) -> Result<ComposeRound> {
    let dir = &args.dir;
    let token_counter = create_token_counter(config);
 
    // Reset staging area
-   println!("{}", style::info("Resetting staging area..."));
+   progress!(verbose, "{}", style::info("Resetting staging area..."));
    reset_staging(dir)?;
 
    // Capture the full diff against the original HEAD once so we can reuse the same
    // hunk metadata even after earlier groups move HEAD forward.
-   let baseline_diff_output = std::process::Command::new("git")
-      .args(["diff", "HEAD"])
-      .current_dir(dir)
-      .output()
-      .map_err(|e| CommitGenError::GitError(format!("Failed to get baseline diff: {e}")))?;
-
-   if !baseline_diff_output.status.success() {
-      let stderr = String::from_utf8_lossy(&baseline_diff_output.stderr);
-      return Err(CommitGenError::GitError(format!("git diff HEAD failed: {stderr}")));
-   }
-
-   let baseline_diff = String::from_utf8_lossy(&baseline_diff_output.stdout).to_string();
+   let baseline_diff = baseline_diff(dir, config.compose_untracked_max_bytes)?;
 
    let mut commit_hashes = Vec::new();
+   let mut uncovered_files = Vec::new();
+   let mut session_subjects = previous_commits.to_vec();
 
    for (idx, &group_idx) in analysis.dependency_order.iter().enumerate() {
       let mut group = analysis.groups[group_idx].clone();
+      diagnostics::clear();
       let dependency_only = group_affects_only_dependency_files(&group);
 
       if dependency_only && group.commit_type.as_str() != "build" {
          group.commit_type = CommitType::new("build")?;
       }
 
-      println!(
+      progress!(
+         verbose,
          "\n[{}/{}] Creating commit for group: {}",
          idx + 1,
          analysis.dependency_order.len(),
          group.rationale
       );
-      println!("  Type: {}", style::commit_type(&group.commit_type.to_string()));
-      if let Some(scope) = &group.scope {
-         println!("  Scope: {}", style::scope(&scope.to_string()));
-      }
+      progress!(verbose, "  Type: {}", style::commit_type(&group.commit_type.to_string()));
       let files: Vec<String> = group.changes.iter().map(|c| c.path.clone()).collect();
-      println!("  Files: {}", files.join(", "));
+      progress!(verbose, "  Files: {}", files.join(", "));
 
       // Stage changes for this group (with hunk awareness)
-      stage_group_changes(&group, dir, &baseline_diff)?;
+      let later_groups: Vec<(usize, &ChangeGroup)> = analysis.dependency_order[idx + 1..]
+         .iter()
+         .map(|&later| (later, &analysis.groups[later]))
+         .collect();
+      let skipped = timer.stage("staging", || {
+         stage_group_with_recovery(
+            &mut group,
+            group_idx,
+            &later_groups,
+            dir,
+            &baseline_diff,
+            config.compose_on_apply_error,
+         )
+      })?;
+      uncovered_files.extend(skipped);
+
+      if group.changes.is_empty() {
+         eprintln!(
+            "  {}",
+            style::warning(&format!(
+               "{} Nothing left to stage for this group; no commit created",
+               style::icons::WARNING
+            ))
+         );
+         continue;
+      }
 
       // Get diff and stat for this specific group
       let diff = get_git_diff(&Mode::Staged, None, dir, config)?;
       let stat = get_git_stat(&Mode::Staged, None, dir, config)?;
 
+      let profile = classify_change_profile(&diff);
+
       // Truncate if needed
       let diff = if diff.len() > config.max_diff_length {
          smart_truncate_diff(&diff, config.max_diff_length, config, &token_counter)
@@ -751,31 +1025,67 @@ pub fn execute_compose(
       };
 
       // Generate commit message using existing infrastructure
-      println!("  {}", style::info("Generating commit message..."));
+      progress!(verbose, "  {}", style::info("Generating commit message..."));
       let debug_prefix = format!("compose-{}", idx + 1);
+      let session_context = session_commits_context(&session_subjects);
       let ctx = AnalysisContext {
          user_context:    Some(&group.rationale),
-         recent_commits:  None, // No recent commits for compose mode
+         recent_commits:  session_context.as_deref(),
          common_scopes:   None, // No common scopes for compose mode
          project_context: None, // No project context for compose mode
+         change_profile:  None,
          debug_output:    args.debug_output.as_deref(),
          debug_prefix:    Some(&debug_prefix),
       };
-      let message_analysis =
-         generate_conventional_analysis(&stat, &diff, &config.model, "", &ctx, config)?;
+      let message_analysis = timer.stage("analysis", || {
+         generate_conventional_analysis(&stat, &diff, &config.model, "", &ctx, config)
+      })?;
 
       let analysis_body = message_analysis.body_texts();
 
-      let summary = crate::api::generate_summary_from_analysis(
-         &stat,
-         group.commit_type.as_str(),
-         group.scope.as_ref().map(|s| s.as_str()),
-         &analysis_body,
-         Some(&group.rationale),
-         config,
-         args.debug_output.as_deref(),
-         Some(&debug_prefix),
-      )?;
+      if let (Some(group_scope), Some(analysis_scope)) = (&group.scope, &message_analysis.scope)
+         && group_scope != analysis_scope
+      {
+         progress!(
+            verbose,
+            "  {}",
+            style::dim(&format!(
+               "Scope disagreement: group chose '{group_scope}', analysis chose '{analysis_scope}'"
+            ))
+         );
+      }
+      let repo_name = get_repository_name().ok();
+      let (scope, scope_origin) = reconcile_compose_scope(
+         group.scope.as_ref(),
+         message_analysis.scope.as_ref(),
+         config.compose_scope_source,
+         repo_name.as_deref(),
+      );
+      match &scope {
+         Some(scope) => progress!(
+            verbose,
+            "  Scope: {} (from {scope_origin})",
+            style::scope(&scope.to_string())
+         ),
+         None => progress!(verbose, "  Scope: (none)"),
+      }
+
+      let summary = timer
+         .stage("summary", || {
+            crate::api::generate_summary_from_analysis(
+               &stat,
+               &profile,
+               group.commit_type.as_str(),
+               scope.as_ref().map(|s| s.as_str()),
+               &analysis_body,
+               Some(&group.rationale),
+               None,
+               config,
+               args.debug_output.as_deref(),
+               Some(&debug_prefix),
+            )
+         })?
+         .summary;
 
       let final_commit_type = if dependency_only {
          CommitType::new("build")?
@@ -785,7 +1095,7 @@ pub fn execute_compose(
 
       let mut commit = ConventionalCommit {
          commit_type: final_commit_type,
-         scope: message_analysis.scope,
+         scope,
          summary,
          body: analysis_body,
          footers: vec![],
@@ -800,9 +1110,10 @@ pub fn execute_compose(
          );
       }
 
-      let formatted_message = format_commit_message(&commit);
+      let formatted_message = format_commit_message(&commit, config.body_style);
 
-      println!(
+      progress!(
+         verbose,
          "  Message:\n{}",
          formatted_message
             .lines()
@@ -812,16 +1123,33 @@ pub fn execute_compose(
       );
 
       // Create commit (unless preview mode)
-      if !args.compose_preview {
+      if !is_dry_run(args) {
+         diagnostics::check_before_commit(config, args.yes)?;
          let sign = args.sign || config.gpg_sign;
          let signoff = args.signoff || config.signoff;
-         git_commit(&formatted_message, false, dir, sign, signoff, args.skip_hooks)?;
+         let dates =
+            CommitDates::from_flags(args.commit_date.as_deref(), args.author_date.as_deref())?;
+         timer.stage("commit", || {
+            git_commit(
+               &formatted_message,
+               false,
+               dir,
+               sign,
+               signoff,
+               args.skip_hooks,
+               false,
+               &dates,
+            )
+         })?;
          let hash = get_head_hash(dir)?;
          commit_hashes.push(hash);
+         if let Some(subject) = formatted_message.lines().next() {
+            session_subjects.push(subject.to_string());
+         }
 
          // Run tests if requested
          if args.compose_test_after_each {
-            println!("  {}", style::info("Running tests..."));
+            progress!(verbose, "  {}", style::info("Running tests..."));
             let test_result = std::process::Command::new("cargo")
                .arg("test")
                .current_dir(dir)
@@ -833,50 +1161,136 @@ pub fn execute_compose(
                      "Tests failed after commit {idx}. Aborting."
                   )));
                }
-               println!("  {}", style::success(&format!("{} Tests passed", style::icons::SUCCESS)));
+               progress!(
+                  verbose,
+                  "  {}",
+                  style::success(&format!("{} Tests passed", style::icons::SUCCESS))
+               );
             }
          }
       }
    }
 
-   Ok(commit_hashes)
+   if !uncovered_files.is_empty() {
+      eprintln!(
+         "\n{}",
+         style::warning(&format!(
+            "{} Left unstaged after apply failures: {}",
+            style::icons::WARNING,
+            uncovered_files.join(", ")
+         ))
+      );
+   }
+
+   Ok(ComposeRound { analysis: analysis.clone(), commit_hashes, skipped_files: uncovered_files })
+}
+
+/// Main entry point for compose mode (CLI): runs [`compose`] with console
+/// output, ending with the run's stats line
+pub fn run_compose_mode(args: &Args, config: &CommitConfig, timer: &RunTimer) -> Result<()> {
+   let outcome = compose_with_output(args, config, true, timer)?;
+   progress!(
+      !style::is_quiet(),
+      "\n{} {}",
+      style::dim("›"),
+      style::dim(&outcome.stats.to_string())
+   );
+   Ok(())
 }
 
-/// Main entry point for compose mode
-pub fn run_compose_mode(args: &Args, config: &CommitConfig) -> Result<()> {
+/// Run compose and return the structured plan and results without printing
+/// progress (warnings still go to stderr)
+pub fn compose(args: &Args, config: &CommitConfig) -> Result<ComposeOutcome> {
+   compose_with_output(args, config, false, &RunTimer::start(&config.api_counter))
+}
+
+/// Files with changes relative to HEAD (staged or unstaged) and untracked
+/// files
+fn remaining_changed_files(dir: &str) -> Result<Vec<String>> {
+   let output = std::process::Command::new("git")
+      .args(["diff", "HEAD", "--name-only"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to check remaining diff: {e}")))?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git diff HEAD failed: {stderr}")));
+   }
+
+   let mut files: Vec<String> = String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .map(str::to_string)
+      .collect();
+   files.extend(git::get_untracked_files(dir)?);
+   Ok(files)
+}
+
+/// Subjects of `hashes`, in order
+fn commit_subjects(hashes: &[String], dir: &str) -> Result<Vec<String>> {
+   if hashes.is_empty() {
+      return Ok(Vec::new());
+   }
+   let output = std::process::Command::new("git")
+      .args(["log", "--no-walk=unsorted", "--format=%s"])
+      .args(hashes)
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to read commit subjects: {e}")))?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git log failed: {stderr}")));
+   }
+
+   Ok(String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .map(str::to_string)
+      .collect())
+}
+
+fn compose_with_output(
+   args: &Args,
+   config: &CommitConfig,
+   verbose: bool,
+   timer: &RunTimer,
+) -> Result<ComposeOutcome> {
    let max_rounds = config.compose_max_rounds;
+   let mut outcome = ComposeOutcome::default();
+   // Subjects committed so far, carried into later rounds
+   let mut previous_commits: Vec<String> = Vec::new();
 
    for round in 1..=max_rounds {
       if round > 1 {
-         println!(
+         progress!(
+            verbose,
             "\n{}",
             style::section_header(&format!("Compose Round {round}/{max_rounds}"), 80)
          );
       } else {
-         println!("{}", style::section_header("Compose Mode", 80));
+         progress!(verbose, "{}", style::section_header("Compose Mode", 80));
       }
-      println!("{}\n", style::info("Analyzing all changes for intelligent splitting..."));
+      progress!(verbose, "{}\n", style::info("Analyzing all changes for intelligent splitting..."));
 
-      run_compose_round(args, config, round)?;
+      let executed = run_compose_round(args, config, round, verbose, timer, &previous_commits)?;
 
       // Check if there are remaining changes
-      if args.compose_preview {
+      if is_dry_run(args) {
+         outcome.rounds.push(executed);
          break;
       }
 
-      let remaining_diff_output = std::process::Command::new("git")
-         .args(["diff", "HEAD"])
-         .current_dir(&args.dir)
-         .output()
-         .map_err(|e| CommitGenError::GitError(format!("Failed to check remaining diff: {e}")))?;
-
-      if !remaining_diff_output.status.success() {
+      previous_commits
+         .extend(commit_subjects(&executed.commit_hashes, &args.dir).unwrap_or_default());
+      outcome.rounds.push(executed);
+      let Ok(remaining) = remaining_changed_files(&args.dir) else {
          continue;
-      }
+      };
+      outcome.uncovered_files = remaining;
 
-      let remaining_diff = String::from_utf8_lossy(&remaining_diff_output.stdout);
-      if remaining_diff.trim().is_empty() {
-         println!(
+      if outcome.uncovered_files.is_empty() {
+         progress!(
+            verbose,
             "\n{}",
             style::success(&format!(
                "{} All changes committed successfully",
@@ -886,6 +1300,10 @@ pub fn run_compose_mode(args: &Args, config: &CommitConfig) -> Result<()> {
          break;
       }
 
+      if !verbose {
+         continue;
+      }
+
       eprintln!(
          "\n{}",
          style::warning(&format!(
@@ -919,46 +1337,46 @@ pub fn run_compose_mode(args: &Args, config: &CommitConfig) -> Result<()> {
       );
    }
 
-   Ok(())
+   outcome.stats = timer.finish();
+   Ok(outcome)
+}
+
+/// Why compose stopped when a round's plan is declined; earlier rounds'
+/// commits stay
+fn cancelled_message(earlier_commits: usize) -> String {
+   if earlier_commits == 0 {
+      "Compose cancelled; nothing was committed".to_string()
+   } else {
+      format!("Compose cancelled; kept the {earlier_commits} commit(s) from earlier rounds")
+   }
 }
 
 /// Run a single round of compose
-fn run_compose_round(args: &Args, config: &CommitConfig, round: usize) -> Result<()> {
+fn run_compose_round(
+   args: &Args,
+   config: &CommitConfig,
+   round: usize,
+   verbose: bool,
+   timer: &RunTimer,
+   previous_commits: &[String],
+) -> Result<ComposeRound> {
    let token_counter = create_token_counter(config);
 
-   // Get combined diff (staged + unstaged)
-   let diff_staged = get_git_diff(&Mode::Staged, None, &args.dir, config).unwrap_or_default();
-   let diff_unstaged = get_git_diff(&Mode::Unstaged, None, &args.dir, config).unwrap_or_default();
-
-   let combined_diff = if diff_staged.is_empty() {
-      diff_unstaged
-   } else if diff_unstaged.is_empty() {
-      diff_staged
-   } else {
-      format!("{diff_staged}\n{diff_unstaged}")
-   };
-
-   if combined_diff.is_empty() {
+   // Everything since HEAD, staged or not, plus untracked files
+   let combined_diff = baseline_diff(&args.dir, config.compose_untracked_max_bytes)?;
+   if combined_diff.trim().is_empty() {
       return Err(CommitGenError::NoChanges { mode: "working directory".to_string() });
    }
-
-   let stat_staged = get_git_stat(&Mode::Staged, None, &args.dir, config).unwrap_or_default();
-   let stat_unstaged = get_git_stat(&Mode::Unstaged, None, &args.dir, config).unwrap_or_default();
-
-   let combined_stat = if stat_staged.is_empty() {
-      stat_unstaged
-   } else if stat_unstaged.is_empty() {
-      stat_staged
-   } else {
-      format!("{stat_staged}\n{stat_unstaged}")
-   };
+   let combined_stat = baseline_stat(&args.dir)?;
 
    // Save original diff for validation (before possible truncation)
    let original_diff = combined_diff.clone();
 
    // Truncate if needed
-   let diff = if combined_diff.len() > config.max_diff_length {
-      println!(
+   let max_diff_length = config.max_diff_length_for(&Mode::Compose);
+   let diff = if combined_diff.len() > max_diff_length {
+      progress!(
+         verbose,
          "{}",
          style::warning(&format!(
             "{} Applying smart truncation (diff size: {} characters)",
@@ -966,27 +1384,38 @@ fn run_compose_round(args: &Args, config: &CommitConfig, round: usize) -> Result
             combined_diff.len()
          ))
       );
-      smart_truncate_diff(&combined_diff, config.max_diff_length, config, &token_counter)
+      smart_truncate_diff(&combined_diff, max_diff_length, config, &token_counter)
    } else {
       combined_diff
    };
 
    let max_commits = args.compose_max_commits.unwrap_or(3);
 
-   println!("{}", style::info(&format!("Analyzing changes (max {max_commits} commits)...")));
-   let analysis = analyze_for_compose(&diff, &combined_stat, config, max_commits)?;
+   progress!(
+      verbose,
+      "{}",
+      style::info(&format!("Analyzing changes (max {max_commits} commits)..."))
+   );
+   let analysis = timer.stage("grouping", || {
+      analyze_for_compose(&diff, &combined_stat, config, max_commits, previous_commits)
+   })?;
 
    // Validate groups for exhaustiveness and correctness
-   println!("{}", style::info("Validating groups..."));
+   progress!(verbose, "{}", style::info("Validating groups..."));
    validate_compose_groups(&analysis.groups, &original_diff)?;
+   if args.compose_validate {
+      progress!(verbose, "{}", style::info("Dry-running staging..."));
+      check_compose_staging(&analysis, &args.dir, config)?;
+   }
 
-   println!("\n{}", style::section_header("Proposed Commit Groups", 80));
+   progress!(verbose, "\n{}", style::section_header("Proposed Commit Groups", 80));
    for (idx, &group_idx) in analysis.dependency_order.iter().enumerate() {
       let mut group = analysis.groups[group_idx].clone();
       if group_affects_only_dependency_files(&group) && group.commit_type.as_str() != "build" {
          group.commit_type = CommitType::new("build")?;
       }
-      println!(
+      progress!(
+         verbose,
          "\n{}. [{}{}] {}",
          idx + 1,
          style::commit_type(&group.commit_type.to_string()),
@@ -997,13 +1426,13 @@ fn run_compose_round(args: &Args, config: &CommitConfig, round: usize) -> Result
             .unwrap_or_default(),
          group.rationale
       );
-      println!("   Changes:");
+      progress!(verbose, "   Changes:");
       for change in &group.changes {
          let is_all =
             change.hunks.len() == 1 && matches!(&change.hunks[0], crate::types::HunkSelector::All);
 
          if is_all {
-            println!("     - {} (all changes)", change.path);
+            progress!(verbose, "     - {} (all changes)", change.path);
          } else {
             // Display summary of selectors
             let summary: Vec<String> = change
@@ -1023,35 +1452,350 @@ fn run_compose_round(args: &Args, config: &CommitConfig, round: usize) -> Result
                   },
                })
                .collect();
-            println!("     - {} ({})", change.path, summary.join(", "));
+            progress!(verbose, "     - {} ({})", change.path, summary.join(", "));
          }
       }
       if !group.dependencies.is_empty() {
-         println!("   Depends on: {:?}", group.dependencies);
+         progress!(verbose, "   Depends on: {:?}", group.dependencies);
       }
    }
 
-   if args.compose_preview {
-      println!(
+   progress!(verbose, "\n{}", style::info(&plan_summary(&analysis)));
+
+   if is_dry_run(args) {
+      progress!(
+         verbose,
          "\n{}",
          style::success(&format!(
-            "{} Preview complete (use --compose without --compose-preview to execute)",
+            "{} Preview complete (use --compose without --compose-preview/--compose-validate to \
+             execute)",
             style::icons::SUCCESS
          ))
       );
-      return Ok(());
+      return Ok(ComposeRound { analysis, commit_hashes: vec![], skipped_files: vec![] });
    }
 
-   println!("\n{}", style::info(&format!("Executing compose (round {round})...")));
-   let hashes = execute_compose(&analysis, config, args)?;
+   if verbose && !args.yes && std::io::stdin().is_terminal() && !confirm_plan()? {
+      return Err(CommitGenError::Other(cancelled_message(previous_commits.len())));
+   }
+
+   progress!(verbose, "\n{}", style::info(&format!("Executing compose (round {round})...")));
+   let executed = execute_compose(&analysis, config, args, verbose, timer, previous_commits)?;
 
-   println!(
+   progress!(
+      verbose,
       "{}",
       style::success(&format!(
          "{} Round {round}: Created {} commit(s)",
          style::icons::SUCCESS,
-         hashes.len()
+         executed.commit_hashes.len()
       ))
    );
-   Ok(())
+   Ok(executed)
+}
+
+#[cfg(test)]
+mod tests {
+   use std::{path::Path, process::Command};
+
+   use super::*;
+   use crate::types::FileChange;
+
+   fn scope(s: &str) -> Scope {
+      Scope::new(s).unwrap()
+   }
+
+   #[test]
+   fn test_reconcile_compose_scope_all_combinations() {
+      use ComposeScopeSource::{Analysis, Group, Reconcile};
+
+      /// Group scope, analysis scope, policy, expected scope, expected origin
+      type Case<'a> =
+         (Option<&'a Scope>, Option<&'a Scope>, ComposeScopeSource, Option<&'a str>, ScopeOrigin);
+
+      let (api, cli, repo) = (scope("api"), scope("cli"), scope("llm-git"));
+      let cases: &[Case] = &[
+         // Both present and different
+         (Some(&api), Some(&cli), Reconcile, Some("api"), ScopeOrigin::Group),
+         (Some(&api), Some(&cli), Group, Some("api"), ScopeOrigin::Group),
+         (Some(&api), Some(&cli), Analysis, Some("cli"), ScopeOrigin::Analysis),
+         // Only one present
+         (Some(&api), None, Reconcile, Some("api"), ScopeOrigin::Group),
+         (None, Some(&cli), Reconcile, Some("cli"), ScopeOrigin::Analysis),
+         (Some(&api), None, Analysis, None, ScopeOrigin::None),
+         (None, Some(&cli), Group, None, ScopeOrigin::None),
+         // Neither
+         (None, None, Reconcile, None, ScopeOrigin::None),
+         // Group names the project: analysis fills in, else dropped
+         (Some(&repo), Some(&cli), Reconcile, Some("cli"), ScopeOrigin::Analysis),
+         (Some(&repo), None, Reconcile, None, ScopeOrigin::None),
+         (Some(&repo), Some(&cli), Group, None, ScopeOrigin::None),
+         (Some(&api), Some(&repo), Analysis, None, ScopeOrigin::None),
+      ];
+
+      for &(group, analysis, policy, expected, origin) in cases {
+         let (chosen, chosen_origin) =
+            reconcile_compose_scope(group, analysis, policy, Some("llm_git"));
+         assert_eq!(
+            chosen.as_ref().map(Scope::as_str),
+            expected,
+            "{group:?} / {analysis:?} under {policy:?}"
+         );
+         assert_eq!(chosen_origin, origin, "{group:?} / {analysis:?} under {policy:?}");
+      }
+   }
+
+   #[test]
+   fn test_reconcile_compose_scope_without_repo_name() {
+      let repo = scope("llm-git");
+      let (chosen, origin) =
+         reconcile_compose_scope(Some(&repo), None, ComposeScopeSource::Reconcile, None);
+      assert_eq!(chosen, Some(repo));
+      assert_eq!(origin, ScopeOrigin::Group);
+   }
+
+   fn git(dir: &Path, args: &[&str]) -> String {
+      let output = Command::new("git")
+         .args(args)
+         .current_dir(dir)
+         .output()
+         .unwrap();
+      assert!(output.status.success(), "git {args:?}: {}", String::from_utf8_lossy(&output.stderr));
+      String::from_utf8_lossy(&output.stdout).into_owned()
+   }
+
+   /// Repo with `a.txt` modified at line 1 and line 20
+   fn setup_repo(name: &str) -> (std::path::PathBuf, String) {
+      let dir = std::env::temp_dir().join(format!("lgit-compose-{name}-{}", std::process::id()));
+      let _ = std::fs::remove_dir_all(&dir);
+      std::fs::create_dir_all(&dir).unwrap();
+      git(&dir, &["init", "-q"]);
+      git(&dir, &["config", "user.email", "test@example.com"]);
+      git(&dir, &["config", "user.name", "Test"]);
+
+      let original: Vec<String> = (1..=20).map(|n| format!("line {n}")).collect();
+      std::fs::write(dir.join("a.txt"), original.join("\n") + "\n").unwrap();
+      git(&dir, &["add", "-A"]);
+      git(&dir, &["commit", "-qm", "init"]);
+
+      let mut modified = original;
+      modified[0] = "line one".to_string();
+      modified[19] = "line twenty".to_string();
+      std::fs::write(dir.join("a.txt"), modified.join("\n") + "\n").unwrap();
+
+      let diff = git(&dir, &["diff", "HEAD"]);
+      (dir, diff)
+   }
+
+   fn group(hunks: Vec<HunkSelector>) -> ChangeGroup {
+      ChangeGroup {
+         changes:      vec![FileChange { path: "a.txt".to_string(), hunks }],
+         commit_type:  CommitType::new("fix").unwrap(),
+         scope:        None,
+         rationale:    "test".to_string(),
+         dependencies: vec![],
+      }
+   }
+
+   #[test]
+   fn test_cancelled_message_counts_earlier_commits() {
+      assert_eq!(cancelled_message(0), "Compose cancelled; nothing was committed");
+      assert_eq!(
+         cancelled_message(2),
+         "Compose cancelled; kept the 2 commit(s) from earlier rounds"
+      );
+   }
+
+   #[test]
+   fn test_plan_summary_lists_groups_in_commit_order() {
+      let with = |commit_type: &str, scope_name: Option<&str>, path: &str| ChangeGroup {
+         changes: vec![FileChange { path: path.to_string(), hunks: vec![HunkSelector::All] }],
+         commit_type: CommitType::new(commit_type).unwrap(),
+         scope: scope_name.map(scope),
+         ..group(vec![])
+      };
+      let analysis = ComposeAnalysis {
+         groups:           vec![
+            with("docs", None, "README.md"),
+            with("feat", Some("api"), "src/api.rs"),
+            with("chore", Some("deps"), "Cargo.lock"),
+         ],
+         dependency_order: vec![1, 2, 0],
+      };
+      assert_eq!(plan_summary(&analysis), "splitting into 3 commits: feat(api), build(deps), docs");
+
+      let single =
+         ComposeAnalysis { groups: vec![group(vec![])], dependency_order: vec![0] };
+      assert_eq!(plan_summary(&single), "splitting into 1 commit: fix");
+   }
+
+   /// Selector pointing past the end of the file, so it resolves to no hunks
+   fn misaligned() -> Vec<HunkSelector> {
+      vec![HunkSelector::Lines { start: 200, end: 210 }]
+   }
+
+   #[test]
+   fn test_stage_group_reports_apply_failure_context() {
+      let (dir, diff) = setup_repo("fail");
+      let err =
+         stage_group_changes(&group(misaligned()), 2, dir.to_str().unwrap(), &diff).unwrap_err();
+
+      match err {
+         CommitGenError::HunkApplyFailed { group, file, selector, .. } => {
+            assert_eq!(group, 2);
+            assert_eq!(file, "a.txt");
+            assert_eq!(selector, "lines 200-210");
+         },
+         other => panic!("unexpected error: {other}"),
+      }
+      let _ = std::fs::remove_dir_all(&dir);
+   }
+
+   #[test]
+   fn test_untracked_file_stages_whole_only() {
+      let (dir, _) = setup_repo("untracked");
+      let dir_str = dir.to_str().unwrap();
+      std::fs::write(dir.join("new.txt"), "first\nsecond\n").unwrap();
+      let diff = baseline_diff(dir_str, 1024).unwrap();
+      assert!(diff.contains("diff --git a/new.txt b/new.txt\nnew file mode 100644\n"));
+      assert!(diff.contains("@@ -0,0 +1,2 @@\n+first\n+second\n"));
+      let untracked = |hunks| ChangeGroup {
+         changes: vec![FileChange { path: "new.txt".to_string(), hunks }],
+         ..group(vec![])
+      };
+
+      let err = stage_group_changes(
+         &untracked(vec![HunkSelector::Lines { start: 1, end: 1 }]),
+         0,
+         dir_str,
+         &diff,
+      )
+      .unwrap_err();
+      assert!(
+         matches!(&err, CommitGenError::HunkApplyFailed { file, stderr, .. } if file == "new.txt" && stderr.contains("untracked")),
+         "{err}"
+      );
+      assert!(git(&dir, &["diff", "--cached"]).is_empty());
+
+      stage_group_changes(&untracked(vec![HunkSelector::All]), 0, dir_str, &diff).unwrap();
+      assert_eq!(git(&dir, &["diff", "--cached", "--name-only"]).trim(), "new.txt");
+      let _ = std::fs::remove_dir_all(&dir);
+   }
+
+   #[test]
+   fn test_recovery_widen_stages_whole_file() {
+      let (dir, diff) = setup_repo("widen");
+      let dir_str = dir.to_str().unwrap();
+      let mut group = group(misaligned());
+
+      let uncovered = stage_group_with_recovery(
+         &mut group,
+         0,
+         &[],
+         dir_str,
+         &diff,
+         Some(ApplyErrorStrategy::Widen),
+      )
+      .unwrap();
+
+      assert!(uncovered.is_empty());
+      assert!(matches!(group.changes[0].hunks.as_slice(), [HunkSelector::All]));
+      assert_eq!(git(&dir, &["diff", "--cached", "--name-only"]).trim(), "a.txt");
+      assert!(git(&dir, &["diff"]).is_empty());
+      let _ = std::fs::remove_dir_all(&dir);
+   }
+
+   #[test]
+   fn test_recovery_widen_refuses_to_steal_hunks() {
+      let (dir, diff) = setup_repo("steal");
+      let dir_str = dir.to_str().unwrap();
+      let mut first = group(misaligned());
+      let later = group(vec![HunkSelector::Lines { start: 20, end: 20 }]);
+
+      let uncovered = stage_group_with_recovery(
+         &mut first,
+         0,
+         &[(1, &later)],
+         dir_str,
+         &diff,
+         Some(ApplyErrorStrategy::Widen),
+      )
+      .unwrap();
+
+      assert_eq!(uncovered, vec!["a.txt".to_string()]);
+      assert!(first.changes.is_empty());
+      assert!(git(&dir, &["diff", "--cached"]).is_empty());
+      let _ = std::fs::remove_dir_all(&dir);
+   }
+
+   #[test]
+   fn test_recovery_skip_leaves_file_uncovered() {
+      let (dir, diff) = setup_repo("skip");
+      let dir_str = dir.to_str().unwrap();
+      let mut group = group(misaligned());
+
+      let uncovered = stage_group_with_recovery(
+         &mut group,
+         0,
+         &[],
+         dir_str,
+         &diff,
+         Some(ApplyErrorStrategy::Skip),
+      )
+      .unwrap();
+
+      assert_eq!(uncovered, vec!["a.txt".to_string()]);
+      assert!(group.changes.is_empty());
+      assert!(git(&dir, &["diff", "--cached"]).is_empty());
+      assert!(!git(&dir, &["diff"]).is_empty());
+      let _ = std::fs::remove_dir_all(&dir);
+   }
+
+   #[test]
+   fn test_staging_dry_run_flags_out_of_range_selector() {
+      let (dir, _) = setup_repo("dry-run");
+      let dir_str = dir.to_str().unwrap();
+      let config = CommitConfig::default();
+      let analysis = |hunks| ComposeAnalysis {
+         groups:           vec![group(hunks)],
+         dependency_order: vec![0],
+      };
+
+      check_compose_staging(
+         &analysis(vec![HunkSelector::Lines { start: 1, end: 1 }]),
+         dir_str,
+         &config,
+      )
+      .unwrap();
+      check_compose_staging(&analysis(vec![HunkSelector::All]), dir_str, &config).unwrap();
+
+      let err = check_compose_staging(&analysis(misaligned()), dir_str, &config).unwrap_err();
+      assert!(
+         matches!(&err, CommitGenError::ValidationError(msg) if msg.contains("a.txt") && msg.contains("lines 200-210")),
+         "{err}"
+      );
+
+      // Nothing was staged along the way
+      assert_eq!(git(&dir, &["diff", "--cached", "--name-only"]), "");
+      let _ = std::fs::remove_dir_all(&dir);
+   }
+
+   #[test]
+   fn test_recovery_abort_returns_error() {
+      let (dir, diff) = setup_repo("abort");
+      let mut group = group(misaligned());
+
+      let err = stage_group_with_recovery(
+         &mut group,
+         0,
+         &[],
+         dir.to_str().unwrap(),
+         &diff,
+         Some(ApplyErrorStrategy::Abort),
+      )
+      .unwrap_err();
+
+      assert!(matches!(err, CommitGenError::HunkApplyFailed { .. }));
+      let _ = std::fs::remove_dir_all(&dir);
+   }
 }
@@ -0,0 +1,134 @@
+//! Confidence score for a generated commit message
+//!
+//! Signals that the pipeline already produces in different places (how well
+//! the scope is backed by the diff, whether validation passed, whether a
+//! deterministic fallback replaced the model's summary) are gathered here
+//! into one score, which gates auto-committing via
+//! `auto_commit_min_confidence`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::types::ScopeCandidate;
+
+/// Number of times a fallback summary was used in this process
+static FALLBACKS: AtomicUsize = AtomicUsize::new(0);
+
+/// Note that a deterministic fallback summary replaced the model's output
+pub fn record_fallback() {
+   FALLBACKS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Fallbacks recorded so far in this process, for the run statistics; a
+/// single run learns whether it used one from the summary it got back
+pub fn fallback_count() -> usize {
+   FALLBACKS.load(Ordering::Relaxed)
+}
+
+/// Share of changed lines (0-100) behind `scope`, from the scope candidates.
+/// A scope no candidate backs (e.g. taken from history) counts as 0.
+pub fn scope_share(candidates: &[ScopeCandidate], scope: &str) -> f32 {
+   candidates
+      .iter()
+      .filter(|c| c.path == scope || c.path.ends_with(&format!("/{scope}")))
+      .map(|c| c.percentage)
+      .fold(0.0, f32::max)
+}
+
+/// Signals from one generation run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfidenceSignals {
+   /// Share of changed lines behind the chosen scope; `None` when unscoped
+   pub scope_percentage:  Option<f32>,
+   /// Whether the final message passed validation
+   pub validation_passed: bool,
+   /// Whether a fallback summary replaced the model's output
+   pub fallback_used:     bool,
+}
+
+impl ConfidenceSignals {
+   /// Scope share below which the scope is treated as a guess
+   const STRONG_SCOPE_PERCENTAGE: f32 = 60.0;
+
+   /// Score in `0.0..=1.0`; 1.0 means every signal looks healthy
+   pub fn score(&self) -> f32 {
+      let mut score = 1.0;
+      if self.fallback_used {
+         score -= 0.5;
+      }
+      if !self.validation_passed {
+         score -= 0.4;
+      }
+      if let Some(pct) = self.scope_percentage
+         && pct < Self::STRONG_SCOPE_PERCENTAGE
+      {
+         score -= 0.2 * (Self::STRONG_SCOPE_PERCENTAGE - pct) / Self::STRONG_SCOPE_PERCENTAGE;
+      }
+      f32::clamp(score, 0.0, 1.0)
+   }
+
+   /// What lowered the score, for the refusal message
+   pub fn reasons(&self) -> Vec<String> {
+      let mut reasons = Vec::new();
+      if self.fallback_used {
+         reasons.push("fallback summary used".to_string());
+      }
+      if !self.validation_passed {
+         reasons.push("validation failed".to_string());
+      }
+      if let Some(pct) = self.scope_percentage
+         && pct < Self::STRONG_SCOPE_PERCENTAGE
+      {
+         reasons.push(format!("scope covers only {pct:.0}% of changes"));
+      }
+      reasons
+   }
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   const HEALTHY: ConfidenceSignals = ConfidenceSignals {
+      scope_percentage:  Some(85.0),
+      validation_passed: true,
+      fallback_used:     false,
+   };
+
+   #[test]
+   fn test_healthy_run_is_fully_confident() {
+      assert!((HEALTHY.score() - 1.0).abs() < f32::EPSILON);
+      assert!(HEALTHY.reasons().is_empty());
+
+      let unscoped = ConfidenceSignals { scope_percentage: None, ..HEALTHY };
+      assert!((unscoped.score() - 1.0).abs() < f32::EPSILON);
+   }
+
+   #[test]
+   fn test_fallback_summary_run_is_low_confidence() {
+      let signals = ConfidenceSignals { fallback_used: true, ..HEALTHY };
+      assert!(signals.score() <= 0.5);
+      assert_eq!(signals.reasons(), vec!["fallback summary used".to_string()]);
+   }
+
+   #[test]
+   fn test_weak_scope_lowers_confidence() {
+      let weak = ConfidenceSignals { scope_percentage: Some(30.0), ..HEALTHY };
+      assert!(weak.score() < 1.0 && weak.score() > 0.8);
+      assert_eq!(weak.reasons(), vec!["scope covers only 30% of changes".to_string()]);
+   }
+
+   #[test]
+   fn test_scope_share_matches_nested_candidates() {
+      let candidates = vec![
+         ScopeCandidate { path: "api".to_string(), percentage: 40.0, confidence: 40.0 },
+         ScopeCandidate {
+            path:       "core/parser".to_string(),
+            percentage: 55.0,
+            confidence: 44.0,
+         },
+      ];
+      assert!((scope_share(&candidates, "parser") - 55.0).abs() < f32::EPSILON);
+      assert!((scope_share(&candidates, "api") - 40.0).abs() < f32::EPSILON);
+      assert!(scope_share(&candidates, "deps").abs() < f32::EPSILON);
+   }
+}
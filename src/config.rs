@@ -1,16 +1,20 @@
 use std::path::{Path, PathBuf};
 
 use indexmap::IndexMap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
+   diagnostics::WarningCode,
    error::{CommitGenError, Result},
+   exclude::{self, ExcludedFiles},
+   stats::ApiCounter,
    types::{
-      CategoryConfig, TypeConfig, default_categories, default_classifier_hint, default_types,
+      CategoryConfig, Mode, TypeConfig, default_categories, default_classifier_hint, default_types,
+      resolve_model_name,
    },
 };
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum ApiMode {
    Auto,
@@ -18,15 +22,237 @@ pub enum ApiMode {
    AnthropicMessages,
 }
 
+/// Verb form required at the start of generated summaries and body items
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tense {
+   /// "added oauth support"
+   #[default]
+   Past,
+   /// "add oauth support"
+   Imperative,
+}
+
+impl Tense {
+   /// Wording used in prompts and validation messages
+   pub const fn verb_form(self) -> &'static str {
+      match self {
+         Self::Past => "past-tense",
+         Self::Imperative => "imperative",
+      }
+   }
+}
+
+/// Summary style rules a prompt states, from the `enforce_*` settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SummaryRules {
+   pub tense:              Tense,
+   /// Ask for a `tense` verb; otherwise any opening verb
+   pub enforce_tense:      bool,
+   pub lowercase_start:    bool,
+   pub no_trailing_period: bool,
+}
+
+/// How the commit body is laid out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyStyle {
+   /// One `- ` bullet per detail, each ending with a period
+   #[default]
+   Bullets,
+   /// Flowing prose, one paragraph per detail, separated by blank lines
+   Paragraphs,
+}
+
+/// How issue-reference footers separate key and value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FooterFormat {
+   /// `Fixes #123`
+   #[default]
+   Spaced,
+   /// `Fixes: #123`
+   Colon,
+}
+
+/// Which scope a compose commit uses when the group and the per-group message
+/// analysis disagree
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComposeScopeSource {
+   /// The grouping pass' scope only
+   Group,
+   /// The per-group message analysis' scope only
+   Analysis,
+   /// The group's scope, falling back to the analysis' when the group has none
+   /// or names the project
+   #[default]
+   Reconcile,
+}
+
+/// What to do when the commit type doesn't match the changed files (e.g.
+/// `docs` with no documentation touched)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TypeScopePolicy {
+   /// Print a warning and keep the type
+   #[default]
+   Warn,
+   /// Switch to the type the changed files suggest, warning when none does
+   Correct,
+   /// Fail generation when the changed files suggest another type, warning
+   /// otherwise
+   Reject,
+}
+
+/// Which directory level scope suggestions favor
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScopeDepthPreference {
+   /// Top component (`api` for `src/api/client/handler.rs`) unless one
+   /// subdirectory holds most of all changes
+   #[default]
+   Shallow,
+   /// Two-segment form (`api/client`) whenever it holds most of its parent's
+   /// changes
+   Deep,
+}
+
+/// Subject of a `--mode merge` commit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeSubject {
+   /// Keep git's `Merge branch ...` subject; the generated message follows
+   /// in the body
+   #[default]
+   Keep,
+   /// Use the generated message alone
+   Replace,
+}
+
+/// Diff algorithm git uses for the diffs sent to the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffAlgorithm {
+   Myers,
+   Minimal,
+   Patience,
+   Histogram,
+}
+
+impl DiffAlgorithm {
+   /// Value for `git diff --diff-algorithm=`
+   pub const fn as_str(self) -> &'static str {
+      match self {
+         Self::Myers => "myers",
+         Self::Minimal => "minimal",
+         Self::Patience => "patience",
+         Self::Histogram => "histogram",
+      }
+   }
+}
+
+/// How summary and first-line lengths are counted against the limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthMetric {
+   /// UTF-8 bytes, so `é` counts 2 and `中` counts 3
+   Bytes,
+   /// Unicode scalar values, so `é` and `中` each count 1
+   #[default]
+   Chars,
+   /// Terminal display columns, so `é` counts 1 and `中` counts 2
+   Width,
+}
+
+impl LengthMetric {
+   /// Length of `text` under this metric
+   pub fn measure(self, text: &str) -> usize {
+      match self {
+         Self::Bytes => text.len(),
+         Self::Chars => text.chars().count(),
+         Self::Width => unicode_width::UnicodeWidthStr::width(text),
+      }
+   }
+}
+
+/// Prompt template text supplied in config (`[templates.<category>.<name>]`)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TemplateConfig {
+   /// Tera template source, same syntax as the files in `prompts/`
+   pub text: String,
+}
+
+/// What compose does when a group's hunks fail to apply to the index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApplyErrorStrategy {
+   /// Stage the whole file, unless a later group claims some of its hunks
+   Widen,
+   /// Leave the file unstaged for a later compose round
+   Skip,
+   /// Stop compose with the error
+   Abort,
+}
+
+/// Character categories `normalize_unicode` rewrites to ASCII
+/// (`[normalization]`); a category turned off keeps its characters as written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NormalizationConfig {
+   /// Curly and angle quotes to `'` and `"`
+   pub smart_quotes: bool,
+   /// `→` to `->`, `⇒` to `=>` and the like
+   pub arrows:       bool,
+   /// `≠ ≤ ≥ ≈ ≡ × ÷`, vulgar fractions, superscripts and subscripts
+   pub math:         bool,
+   /// Greek letters (`λ` to `lambda`)
+   pub greek:        bool,
+   /// Bullet glyphs (`•`, `▪`, `◆`, ...) to `-`
+   pub bullets:      bool,
+}
+
+impl Default for NormalizationConfig {
+   fn default() -> Self {
+      Self {
+         smart_quotes: true,
+         arrows:       true,
+         math:         true,
+         greek:        true,
+         bullets:      true,
+      }
+   }
+}
+
+/// Defaults for command-line flags (`[cli_defaults]`). Unset entries leave
+/// the flag's own default; a flag passed on the command line always wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CliDefaults {
+   pub model:        Option<String>,
+   pub copy:         Option<bool>,
+   pub dry_run:      Option<bool>,
+   pub push:         Option<bool>,
+   pub quiet:        Option<bool>,
+   pub sign:         Option<bool>,
+   pub signoff:      Option<bool>,
+   pub skip_hooks:   Option<bool>,
+   pub no_stage:     Option<bool>,
+   pub no_changelog: Option<bool>,
+   pub yes:          Option<bool>,
+   pub compose:      Option<bool>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ResolvedApiMode {
    ChatCompletions,
    AnthropicMessages,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct CommitConfig {
+   /// OpenAI-compatible or Anthropic API root (overridden by `LLM_GIT_API_URL`)
    pub api_base_url: String,
 
    /// API mode for model endpoints (auto/chat-completions/anthropic-messages)
@@ -37,6 +263,14 @@ pub struct CommitConfig {
    /// var)
    pub api_key: Option<String>,
 
+   /// GitHub token for looking up `--fixes` issues (overridden by
+   /// `LLM_GIT_GITHUB_TOKEN`); without one, issues are never fetched
+   pub github_token: Option<String>,
+
+   /// GitHub REST API root, for GitHub Enterprise (default: `https://api.github.com`)
+   #[serde(default = "default_github_api_url")]
+   pub github_api_url: String,
+
    /// HTTP request timeout in seconds
    pub request_timeout_secs: u64,
 
@@ -46,27 +280,128 @@ pub struct CommitConfig {
    /// Maximum rounds for compose mode multi-commit generation
    pub compose_max_rounds: usize,
 
+   /// Recovery when a compose group's hunks fail to apply: "widen", "skip"
+   /// or "abort". Unset asks on a TTY and aborts otherwise.
+   #[serde(default = "default_compose_on_apply_error")]
+   pub compose_on_apply_error: Option<ApplyErrorStrategy>,
+
+   /// Scope for compose commits: "group", "analysis" or "reconcile"
+   #[serde(default = "default_compose_scope_source")]
+   pub compose_scope_source: ComposeScopeSource,
+
+   /// Untracked files larger than this many bytes are shown to the compose
+   /// planner as a stub without their contents (default: 64 KiB)
+   #[serde(default = "default_compose_untracked_max_bytes")]
+   pub compose_untracked_max_bytes: u64,
+
+   /// Target length of the first line
    pub summary_guideline:       usize,
+   /// First-line length above which validation warns
    pub summary_soft_limit:      usize,
+   /// First-line length above which the summary is rejected
    pub summary_hard_limit:      usize,
+   /// Attempts per API call before giving up
    pub max_retries:             u32,
+   /// Delay before the first retry, doubled on each further one
    pub initial_backoff_ms:      u64,
+   /// Characters of diff sent to the model; longer diffs are truncated
    pub max_diff_length:         usize,
+   /// Token budget for the diff; a diff over it is cut to about 4 characters
+   /// per token, and map-reduce reduces observations in groups of this size
    pub max_diff_tokens:         usize,
+   /// Share of changed lines (0.0-1.0) under which the largest component
+   /// makes a change count as wide
    pub wide_change_threshold:   f32,
+   /// Sampling temperature for API calls
    pub temperature:             f32,
+   /// Model used for analysis and summary
    pub model:                   String,
+   /// Gitignore-style patterns, see [`crate::exclude`]
    pub excluded_files:          Vec<String>,
+   /// Extensions whose files are the first cut when the diff is truncated
    pub low_priority_extensions: Vec<String>,
 
+   /// How `summary_guideline` and the limits count length: "chars" (Unicode
+   /// scalar values), "width" (display columns, CJK counts 2) or "bytes"
+   /// (default: chars)
+   #[serde(default)]
+   pub summary_length_metric: LengthMetric,
+
    /// Maximum token budget for commit message detail points (approx 4
    /// chars/token)
    pub max_detail_tokens: usize,
 
+   /// `max_diff_length` for `--mode=staged` (default: `max_diff_length`)
+   pub max_diff_length_staged: Option<usize>,
+
+   /// `max_diff_length` for the compose planning call, which needs the whole
+   /// picture (default: `max_diff_length`)
+   pub max_diff_length_compose: Option<usize>,
+
    /// Prompt variant for analysis phase (e.g., "default")
    #[serde(default = "default_analysis_prompt_variant")]
    pub analysis_prompt_variant: String,
 
+   /// Analysis prompt variant per likely commit type, chosen by a pre-pass
+   /// over file types (e.g. `docs = "docs"` for mostly-Markdown diffs).
+   /// Types without an entry use `analysis_prompt_variant`.
+   #[serde(default)]
+   pub analysis_prompt_variants: IndexMap<String, String>,
+
+   /// Reusable context snippets by name, added to the user context with
+   /// `--context-preset <name>`
+   #[serde(default)]
+   pub context_presets: IndexMap<String, String>,
+
+   /// Flag values used when the command line doesn't pass the flag
+   /// (`[cli_defaults]`); `--ignore-cli-defaults` skips them
+   #[serde(default)]
+   pub cli_defaults: CliDefaults,
+
+   /// Commits of history counted for the common-scopes table (default: 100)
+   #[serde(default = "default_scope_history_depth")]
+   pub scope_history_depth: usize,
+
+   /// Scopes used fewer times than this are left out of the common-scopes
+   /// table (default: 1)
+   #[serde(default = "default_scope_min_count")]
+   pub scope_min_count: usize,
+
+   /// Historical scope spellings mapped to the canonical one (`auth-service =
+   /// "auth"`); plurals fold into a singular seen in history on their own
+   #[serde(default)]
+   pub scope_aliases: IndexMap<String, String>,
+
+   /// Issue labels mapped to a scope (`"area: parser" = "parser"`); the
+   /// first entry labeling a `--fixes` issue is suggested to the model, and
+   /// used when it picks no scope. Needs `github_token`
+   #[serde(default)]
+   pub issue_scope_from_labels: IndexMap<String, String>,
+
+   /// Scope candidate ranking: "shallow" or "deep" (default: shallow)
+   #[serde(default = "default_scope_depth_preference")]
+   pub scope_depth_preference: ScopeDepthPreference,
+
+   /// Most scope candidates suggested to the model (default: 5)
+   #[serde(default = "default_max_scope_candidates")]
+   pub max_scope_candidates: usize,
+
+   /// Smallest share of changed lines (percent) for a suggested scope
+   /// candidate (default: 10)
+   #[serde(default = "default_min_scope_percentage")]
+   pub min_scope_percentage: f32,
+
+   /// Directories that are always the scope when touched (`infra`, `ci`,
+   /// `tests`), even ones otherwise skipped as placeholders or non-scopes; a
+   /// leading dot is dropped from the scope (`.github` -> `github`)
+   #[serde(default)]
+   pub force_scope_dirs: Vec<String>,
+
+   /// Custom prompt variants by category then name, e.g.
+   /// `[templates.analysis.terse]`; selectable like built-in variants
+   #[serde(default)]
+   pub templates: IndexMap<String, IndexMap<String, TemplateConfig>>,
+
    /// Prompt variant for summary phase (e.g., "default")
    #[serde(default = "default_summary_prompt_variant")]
    pub summary_prompt_variant: String,
@@ -80,6 +415,17 @@ pub struct CommitConfig {
    #[serde(default = "default_exclude_old_message")]
    pub exclude_old_message: bool,
 
+   /// Map authors and committers through the repository's `.mailmap` when
+   /// rewriting history, so recreated commits carry canonical identities
+   #[serde(default = "default_rewrite_apply_mailmap")]
+   pub rewrite_apply_mailmap: bool,
+
+   /// Extra identity mappings applied when rewriting history, as `(from, to)`
+   /// pairs of `email` or `Name <email>`, e.g. `[["me@old", "me@new"]]`.
+   /// Applied after (and taking precedence over) `.mailmap`.
+   #[serde(default)]
+   pub rewrite_mailmap: Vec<(String, String)>,
+
    /// GPG sign commits by default (can be overridden by --sign CLI flag)
    #[serde(default = "default_gpg_sign")]
    pub gpg_sign: bool,
@@ -109,10 +455,260 @@ pub struct CommitConfig {
    #[serde(default = "default_map_reduce_enabled")]
    pub map_reduce_enabled: bool,
 
+   /// Files analyzed at most in map-reduce; larger commits are analyzed from
+   /// a representative sample (0 = no limit, default: 200)
+   #[serde(default = "default_max_analyzed_files")]
+   pub max_analyzed_files: usize,
+
    /// Token threshold for triggering map-reduce (default: 30000 tokens)
    #[serde(default = "default_map_reduce_threshold")]
    pub map_reduce_threshold: usize,
 
+   /// Refuse all network access (HTTP client construction errors out) so
+   /// sandboxed runs fail fast; also enabled by `LLM_GIT_NO_NETWORK=1`
+   /// (default: false)
+   #[serde(default = "default_offline_strict")]
+   pub offline_strict: bool,
+
+   /// Tell the summary model how changed lines split across components (e.g.
+   /// "80% api, 20% db") so it leads with the bulk of the change (default:
+   /// false)
+   #[serde(default = "default_summary_include_weights")]
+   pub summary_include_weights: bool,
+
+   /// Verb form for summaries: "past" ("added x") or "imperative" ("add x")
+   /// (default: past)
+   #[serde(default = "default_summary_tense")]
+   pub summary_tense: Tense,
+
+   /// Require the `summary_tense` verb form: validation rejects other
+   /// openers and post-processing rewrites them (default: true)
+   #[serde(default = "default_enforce_tense")]
+   pub enforce_tense: bool,
+
+   /// Lowercase the summary's first letter and warn about capitals (default:
+   /// true)
+   #[serde(default = "default_enforce_lowercase_start")]
+   pub enforce_lowercase_start: bool,
+
+   /// Strip and reject a trailing period on the summary (default: true)
+   #[serde(default = "default_enforce_no_trailing_period")]
+   pub enforce_no_trailing_period: bool,
+
+   /// Verb form for body items, independent of `summary_tense` (default: past)
+   #[serde(default = "default_body_tense")]
+   pub body_tense: Tense,
+
+   /// Body layout: "bullets" or "paragraphs" (default: bullets)
+   #[serde(default = "default_body_style")]
+   pub body_style: BodyStyle,
+
+   /// Issue footers as `Fixes #123` ("spaced") or `Fixes: #123` ("colon");
+   /// `BREAKING CHANGE:` always takes a colon (default: spaced)
+   #[serde(default = "default_footer_format")]
+   pub footer_format: FooterFormat,
+
+   /// Which characters are rewritten to ASCII in messages (`[normalization]`)
+   #[serde(default)]
+   pub normalization: NormalizationConfig,
+
+   /// Column at which paragraph bodies are wrapped; 0 disables (default: 72)
+   #[serde(default = "default_body_wrap_width")]
+   pub body_wrap_width: usize,
+
+   /// Drop body items that restate the summary nearly word for word (default:
+   /// true)
+   #[serde(default = "default_dedupe_summary_body")]
+   pub dedupe_summary_body: bool,
+
+   /// Summaries that say nothing about the change ("updated files"); a
+   /// body-less message whose summary is one of these, for a diff of at
+   /// least `vacuous_min_diff_chars`, is regenerated once and then rejected
+   #[serde(default = "default_vacuous_summary_patterns")]
+   pub vacuous_summary_patterns: Vec<String>,
+
+   /// Diff size (characters) from which the vacuous-message check applies
+   /// (default: 2000)
+   #[serde(default = "default_vacuous_min_diff_chars")]
+   pub vacuous_min_diff_chars: usize,
+
+   /// An analysis with no detail items for a diff changing at least this many
+   /// lines is requested once more, asking for 2-4 (0 = off; default: 20)
+   #[serde(default = "default_min_diff_for_body_lines")]
+   pub min_diff_for_body_lines: usize,
+
+   /// Diffs shorter than this many characters get type, scope, summary and
+   /// body from one combined API call instead of analysis + summary; 0
+   /// disables (default: 1500)
+   #[serde(default = "default_fast_mode_threshold_chars")]
+   pub fast_mode_threshold_chars: usize,
+
+   /// Decide type and scope in a separate stat-only call (on
+   /// `classify_model`), leaving the diff-reading call to the body details.
+   /// Costs one extra call per commit; it is small, so a cheap
+   /// `classify_model` keeps the overhead low (default: false)
+   #[serde(default = "default_split_type_and_body")]
+   pub split_type_and_body: bool,
+
+   /// Model for the stat-only classification in `split_type_and_body` mode
+   /// (default: `model`)
+   #[serde(default = "default_classify_model")]
+   pub classify_model: Option<String>,
+
+   /// Hunks longer than this many lines have their middle elided before
+   /// analysis; 0 disables (default: 400)
+   #[serde(default = "default_max_hunk_lines")]
+   pub max_hunk_lines: usize,
+
+   /// Lines kept at each end of an elided hunk, at most
+   /// `(max_hunk_lines - 1) / 2` (default: 40)
+   #[serde(default = "default_hunk_keep_lines")]
+   pub hunk_keep_lines: usize,
+
+   /// Down-weight test files when choosing the commit type, so feature code
+   /// dominates over its accompanying tests (default: false)
+   #[serde(default = "default_deprioritize_test_files_for_type")]
+   pub deprioritize_test_files_for_type: bool,
+
+   /// Classify a change as `docs` when every changed file is documentation
+   /// (`.md`/`.rst`/`.adoc`, or under a `docs`/`doc` directory), wherever it
+   /// lives (default: true)
+   #[serde(default = "default_docs_only_as_docs")]
+   pub docs_only_as_docs: bool,
+
+   /// Commit type for a change dominated by one kind of tooling file, keyed
+   /// by kind: `ci`, `build`, `settings` (editor and git settings) or
+   /// `config` (other TOML/YAML/JSON files) (empty = off; default: `ci` ->
+   /// `ci`, `build` -> `build`, `settings` -> `chore`)
+   #[serde(default = "default_tooling_types")]
+   pub tooling_types: IndexMap<String, String>,
+
+   /// Share of the changed lines (percent) files of one `tooling_types` type
+   /// need before that type replaces the model's (0 = off; default: 80)
+   #[serde(default = "default_tooling_dominance_percent")]
+   pub tooling_dominance_percent: usize,
+
+   /// Treat a change as a removal when deletions are at least this many times
+   /// the additions, hinting `refactor`/`chore` and a "removed" summary (0 =
+   /// off; default: 5)
+   #[serde(default = "default_deletion_heavy_ratio")]
+   pub deletion_heavy_ratio: usize,
+
+   /// Ask before committing when validation warned about one of
+   /// `confirm_warning_codes` (default: when stdin is a terminal)
+   #[serde(default = "default_confirm_on_warnings")]
+   pub confirm_on_warnings: Option<bool>,
+
+   /// Fail instead of committing on those warnings when not asking (default:
+   /// false)
+   #[serde(default = "default_warnings_block_commit")]
+   pub warnings_block_commit: bool,
+
+   /// Warnings that `confirm_on_warnings` and `warnings_block_commit` act on
+   /// (default: soft-limit, type-scope-mismatch, filler-word)
+   #[serde(default = "default_confirm_warning_codes")]
+   pub confirm_warning_codes: Vec<WarningCode>,
+
+   /// Type/file mismatch handling: "warn", "correct" or "reject" (default:
+   /// warn)
+   #[serde(default = "default_on_type_scope_inconsistency")]
+   pub on_type_scope_inconsistency: TypeScopePolicy,
+
+   /// Run `git add -A` when nothing is staged; when false, an empty index is
+   /// an error instead (default: true)
+   #[serde(default = "default_auto_stage")]
+   pub auto_stage: bool,
+
+   /// Branches staged mode won't commit on without `--allow-protected` or
+   /// `--force`; the message is still generated and printed (default: main,
+   /// master)
+   #[serde(default = "default_protected_branches")]
+   pub protected_branches: Vec<String>,
+
+   /// Warn when a staged file is larger than this many bytes (0 disables;
+   /// default: 10 MiB)
+   #[serde(default = "default_large_file_warn_bytes")]
+   pub large_file_warn_bytes: u64,
+
+   /// Refuse to commit in staged mode when a staged file is larger than this
+   /// many bytes (default: unset)
+   #[serde(default = "default_max_committed_file_bytes")]
+   pub max_committed_file_bytes: Option<u64>,
+
+   /// Minimum confidence (0.0-1.0) needed to auto-commit in staged mode; below
+   /// it the message is only printed unless `--yes` is given (default: 0.0)
+   #[serde(default = "default_auto_commit_min_confidence")]
+   pub auto_commit_min_confidence: f32,
+
+   /// Poll interval for `--watch` mode in milliseconds; staged changes must be
+   /// stable for one interval before regenerating (default: 1000)
+   #[serde(default = "default_watch_poll_interval_ms")]
+   pub watch_poll_interval_ms: u64,
+
+   /// Largest random offset added to `temperature` on `--regenerate`, so a
+   /// re-run explores alternatives; clamped to 0.0-1.0, 0 disables (default:
+   /// 0.15)
+   #[serde(default = "default_temperature_jitter")]
+   pub temperature_jitter: f32,
+
+   /// Ask the analysis for a short rationale of its type and scope and print
+   /// it to stderr (`--explain`)
+   #[serde(default)]
+   pub capture_rationale: bool,
+
+   /// Models tried in order for the summary (e.g. `["haiku", "sonnet"]`);
+   /// the next takes over when one fails the summary quality check on every
+   /// attempt or can't make tool calls. Empty: `model` alone
+   #[serde(default)]
+   pub summary_model_cascade: Vec<String>,
+
+   /// Check before generating that the endpoint's model listing offers
+   /// `model`, `classify_model` and every `summary_model_cascade` entry
+   /// (default: false)
+   #[serde(default)]
+   pub validate_models: bool,
+
+   /// Temperature per model name, overriding `temperature` for that model
+   #[serde(default)]
+   pub model_temperatures: IndexMap<String, f32>,
+
+   /// Models that reject a temperature (reasoning models); requests to them
+   /// leave it out. `o3` also covers `o3-mini` and `openai/o3`
+   #[serde(default = "default_models_without_temperature")]
+   pub models_without_temperature: Vec<String>,
+
+   /// Diff algorithm for the diff and stat sent to the model: "myers",
+   /// "minimal", "patience" or "histogram" (default: git's own setting).
+   /// `histogram` and `patience` tend to produce cleaner hunks.
+   #[serde(default = "default_diff_algorithm")]
+   pub diff_algorithm: Option<DiffAlgorithm>,
+
+   /// `--mode merge` subject: "keep" git's `Merge branch ...` line above the
+   /// generated message, or "replace" it (default: keep)
+   #[serde(default = "default_merge_subject")]
+   pub merge_subject: MergeSubject,
+
+   /// Print `[k/N] analyzed <file>` to stderr as map-reduce finishes each
+   /// file (default: when stderr is a terminal)
+   #[serde(default = "default_show_progress")]
+   pub show_progress: Option<bool>,
+
+   /// Append a `Generated-by: llm-git (<model>)` trailer naming the analysis
+   /// model, as the last footer (default: false)
+   #[serde(default = "default_add_generator_trailer")]
+   pub add_generator_trailer: bool,
+
+   /// Create an annotated tag after committing a detected release (a version
+   /// bump with its changelog); `--no-tag` skips it and `--push` pushes it
+   /// (default: false)
+   #[serde(default = "default_release_tag")]
+   pub release_tag: bool,
+
+   /// Release tag name; `{version}` is the new version without a `v`
+   /// (default: `v{version}`)
+   #[serde(default = "default_release_tag_format")]
+   pub release_tag_format: String,
+
    /// Loaded analysis prompt (not in config file)
    #[serde(skip)]
    pub analysis_prompt: String,
@@ -120,6 +716,15 @@ pub struct CommitConfig {
    /// Loaded summary prompt (not in config file)
    #[serde(skip)]
    pub summary_prompt: String,
+
+   /// Files the repository's `.gitattributes` marks as generated, as
+   /// gitignore-style lines (not in config file)
+   #[serde(skip)]
+   pub generated_files: Vec<String>,
+
+   /// API requests made with this config and its clones (not in config file)
+   #[serde(skip)]
+   pub api_counter: ApiCounter,
 }
 
 fn default_analysis_prompt_variant() -> String {
@@ -130,6 +735,10 @@ const fn default_api_mode() -> ApiMode {
    ApiMode::Auto
 }
 
+fn default_github_api_url() -> String {
+   "https://api.github.com".to_string()
+}
+
 fn default_summary_prompt_variant() -> String {
    "default".to_string()
 }
@@ -142,6 +751,10 @@ const fn default_exclude_old_message() -> bool {
    true
 }
 
+const fn default_rewrite_apply_mailmap() -> bool {
+   false
+}
+
 const fn default_gpg_sign() -> bool {
    false
 }
@@ -158,10 +771,232 @@ const fn default_map_reduce_enabled() -> bool {
    true
 }
 
+const fn default_max_analyzed_files() -> usize {
+   200
+}
+
 const fn default_map_reduce_threshold() -> usize {
    30000 // ~30k tokens, roughly 120k characters
 }
 
+const fn default_offline_strict() -> bool {
+   false
+}
+
+const fn default_summary_include_weights() -> bool {
+   false
+}
+
+const fn default_summary_tense() -> Tense {
+   Tense::Past
+}
+
+const fn default_enforce_tense() -> bool {
+   true
+}
+
+const fn default_enforce_lowercase_start() -> bool {
+   true
+}
+
+const fn default_enforce_no_trailing_period() -> bool {
+   true
+}
+
+const fn default_body_tense() -> Tense {
+   Tense::Past
+}
+
+const fn default_scope_depth_preference() -> ScopeDepthPreference {
+   ScopeDepthPreference::Shallow
+}
+
+const fn default_max_scope_candidates() -> usize {
+   5
+}
+
+const fn default_min_scope_percentage() -> f32 {
+   10.0
+}
+
+const fn default_fast_mode_threshold_chars() -> usize {
+   1500
+}
+
+const fn default_split_type_and_body() -> bool {
+   false
+}
+
+const fn default_classify_model() -> Option<String> {
+   None
+}
+
+const fn default_scope_history_depth() -> usize {
+   100
+}
+
+const fn default_scope_min_count() -> usize {
+   1
+}
+
+const fn default_footer_format() -> FooterFormat {
+   FooterFormat::Spaced
+}
+
+const fn default_body_style() -> BodyStyle {
+   BodyStyle::Bullets
+}
+
+const fn default_body_wrap_width() -> usize {
+   72
+}
+
+const fn default_dedupe_summary_body() -> bool {
+   true
+}
+
+fn default_vacuous_summary_patterns() -> Vec<String> {
+   [
+      "updated files",
+      "update files",
+      "made changes",
+      "make changes",
+      "modified code",
+      "modify code",
+      "updated code",
+      "update code",
+      "minor changes",
+      "various changes",
+      "various updates",
+      "misc changes",
+   ]
+   .map(String::from)
+   .to_vec()
+}
+
+const fn default_min_diff_for_body_lines() -> usize {
+   20
+}
+
+const fn default_vacuous_min_diff_chars() -> usize {
+   2000
+}
+
+const fn default_compose_on_apply_error() -> Option<ApplyErrorStrategy> {
+   None
+}
+
+const fn default_compose_scope_source() -> ComposeScopeSource {
+   ComposeScopeSource::Reconcile
+}
+
+const fn default_auto_stage() -> bool {
+   true
+}
+
+fn default_protected_branches() -> Vec<String> {
+   vec!["main".to_string(), "master".to_string()]
+}
+
+const fn default_compose_untracked_max_bytes() -> u64 {
+   64 * 1024
+}
+
+const fn default_large_file_warn_bytes() -> u64 {
+   10 * 1024 * 1024
+}
+
+const fn default_max_committed_file_bytes() -> Option<u64> {
+   None
+}
+
+const fn default_auto_commit_min_confidence() -> f32 {
+   0.0
+}
+
+const fn default_max_hunk_lines() -> usize {
+   400
+}
+
+const fn default_hunk_keep_lines() -> usize {
+   40
+}
+
+const fn default_deprioritize_test_files_for_type() -> bool {
+   false
+}
+
+const fn default_deletion_heavy_ratio() -> usize {
+   5
+}
+
+const fn default_confirm_on_warnings() -> Option<bool> {
+   None
+}
+
+const fn default_warnings_block_commit() -> bool {
+   false
+}
+
+fn default_confirm_warning_codes() -> Vec<WarningCode> {
+   vec![WarningCode::SoftLimit, WarningCode::TypeScopeMismatch, WarningCode::FillerWord]
+}
+
+const fn default_docs_only_as_docs() -> bool {
+   true
+}
+
+fn default_tooling_types() -> IndexMap<String, String> {
+   [("ci", "ci"), ("build", "build"), ("settings", "chore")]
+      .into_iter()
+      .map(|(kind, commit_type)| (kind.to_string(), commit_type.to_string()))
+      .collect()
+}
+
+const fn default_tooling_dominance_percent() -> usize {
+   80
+}
+
+const fn default_on_type_scope_inconsistency() -> TypeScopePolicy {
+   TypeScopePolicy::Warn
+}
+
+const fn default_watch_poll_interval_ms() -> u64 {
+   1000
+}
+
+fn default_models_without_temperature() -> Vec<String> {
+   vec!["o1".to_string(), "o3".to_string(), "o4-mini".to_string()]
+}
+
+const fn default_temperature_jitter() -> f32 {
+   0.15
+}
+
+const fn default_diff_algorithm() -> Option<DiffAlgorithm> {
+   None
+}
+
+const fn default_merge_subject() -> MergeSubject {
+   MergeSubject::Keep
+}
+
+const fn default_show_progress() -> Option<bool> {
+   None
+}
+
+const fn default_add_generator_trailer() -> bool {
+   false
+}
+
+const fn default_release_tag() -> bool {
+   false
+}
+
+fn default_release_tag_format() -> String {
+   "v{version}".to_string()
+}
+
 fn parse_api_mode(value: &str) -> ApiMode {
    match value.trim().to_lowercase().as_str() {
       "auto" => ApiMode::Auto,
@@ -176,23 +1011,30 @@ fn parse_api_mode(value: &str) -> ApiMode {
 impl Default for CommitConfig {
    fn default() -> Self {
       Self {
-         api_base_url:            "http://localhost:4000".to_string(),
-         api_mode:                default_api_mode(),
-         api_key:                 None,
-         request_timeout_secs:    120,
-         connect_timeout_secs:    30,
-         compose_max_rounds:      5,
-         summary_guideline:       72,
-         summary_soft_limit:      96,
-         summary_hard_limit:      128,
-         max_retries:             3,
-         initial_backoff_ms:      1000,
-         max_diff_length:         100000, // Increased to handle larger refactors better
-         max_diff_tokens:         25000,  // ~100K chars = 25K tokens (4 chars/token estimate)
-         wide_change_threshold:   0.50,
-         temperature:             0.2, // Low temperature for consistent structured output
-         model:                   "claude-opus-4.5".to_string(),
-         excluded_files:          vec![
+         api_base_url: "http://localhost:4000".to_string(),
+         api_mode: default_api_mode(),
+         api_key: None,
+         github_token: None,
+         github_api_url: default_github_api_url(),
+         request_timeout_secs: 120,
+         connect_timeout_secs: 30,
+         compose_max_rounds: 5,
+         compose_on_apply_error: default_compose_on_apply_error(),
+         compose_scope_source: default_compose_scope_source(),
+         compose_untracked_max_bytes: default_compose_untracked_max_bytes(),
+         summary_guideline: 72,
+         summary_soft_limit: 96,
+         summary_hard_limit: 128,
+         max_retries: 3,
+         initial_backoff_ms: 1000,
+         max_diff_length: 100000, // Increased to handle larger refactors better
+         max_diff_tokens: 25000,  // ~100K chars = 25K tokens (4 chars/token estimate)
+         max_diff_length_staged: None,
+         max_diff_length_compose: None,
+         wide_change_threshold: 0.50,
+         temperature: 0.2, // Low temperature for consistent structured output
+         model: "claude-opus-4.5".to_string(),
+         excluded_files: vec![
             // Rust
             "Cargo.lock".to_string(),
             // JavaScript/Node
@@ -242,26 +1084,173 @@ impl Default for CommitConfig {
             ".tmp".to_string(),
             ".bak".to_string(),
          ],
-         max_detail_tokens:       200,
+         summary_length_metric: LengthMetric::default(),
+         max_detail_tokens: 200,
          analysis_prompt_variant: default_analysis_prompt_variant(),
-         summary_prompt_variant:  default_summary_prompt_variant(),
-         wide_change_abstract:    default_wide_change_abstract(),
-         exclude_old_message:     default_exclude_old_message(),
-         gpg_sign:                default_gpg_sign(),
-         signoff:                 default_signoff(),
-         types:                   default_types(),
-         classifier_hint:         default_classifier_hint(),
-         categories:              default_categories(),
-         changelog_enabled:       default_changelog_enabled(),
-         map_reduce_enabled:      default_map_reduce_enabled(),
-         map_reduce_threshold:    default_map_reduce_threshold(),
-         analysis_prompt:         String::new(),
-         summary_prompt:          String::new(),
+         analysis_prompt_variants: IndexMap::new(),
+         context_presets: IndexMap::new(),
+         cli_defaults: CliDefaults::default(),
+         scope_history_depth: default_scope_history_depth(),
+         scope_min_count: default_scope_min_count(),
+         scope_aliases: IndexMap::new(),
+         issue_scope_from_labels: IndexMap::new(),
+         scope_depth_preference: default_scope_depth_preference(),
+         max_scope_candidates: default_max_scope_candidates(),
+         min_scope_percentage: default_min_scope_percentage(),
+         force_scope_dirs: Vec::new(),
+         templates: IndexMap::new(),
+         summary_prompt_variant: default_summary_prompt_variant(),
+         wide_change_abstract: default_wide_change_abstract(),
+         exclude_old_message: default_exclude_old_message(),
+         rewrite_apply_mailmap: default_rewrite_apply_mailmap(),
+         rewrite_mailmap: Vec::new(),
+         gpg_sign: default_gpg_sign(),
+         signoff: default_signoff(),
+         types: default_types(),
+         classifier_hint: default_classifier_hint(),
+         categories: default_categories(),
+         changelog_enabled: default_changelog_enabled(),
+         map_reduce_enabled: default_map_reduce_enabled(),
+         max_analyzed_files: default_max_analyzed_files(),
+         map_reduce_threshold: default_map_reduce_threshold(),
+         offline_strict: default_offline_strict(),
+         summary_include_weights: default_summary_include_weights(),
+         summary_tense: default_summary_tense(),
+         enforce_tense: default_enforce_tense(),
+         enforce_lowercase_start: default_enforce_lowercase_start(),
+         enforce_no_trailing_period: default_enforce_no_trailing_period(),
+         body_tense: default_body_tense(),
+         fast_mode_threshold_chars: default_fast_mode_threshold_chars(),
+         split_type_and_body: default_split_type_and_body(),
+         classify_model: default_classify_model(),
+         body_style: default_body_style(),
+         footer_format: default_footer_format(),
+         normalization: NormalizationConfig::default(),
+         body_wrap_width: default_body_wrap_width(),
+         dedupe_summary_body: default_dedupe_summary_body(),
+         vacuous_summary_patterns: default_vacuous_summary_patterns(),
+         vacuous_min_diff_chars: default_vacuous_min_diff_chars(),
+         min_diff_for_body_lines: default_min_diff_for_body_lines(),
+         max_hunk_lines: default_max_hunk_lines(),
+         hunk_keep_lines: default_hunk_keep_lines(),
+         deprioritize_test_files_for_type: default_deprioritize_test_files_for_type(),
+         docs_only_as_docs: default_docs_only_as_docs(),
+         tooling_types: default_tooling_types(),
+         tooling_dominance_percent: default_tooling_dominance_percent(),
+         deletion_heavy_ratio: default_deletion_heavy_ratio(),
+         confirm_on_warnings: default_confirm_on_warnings(),
+         warnings_block_commit: default_warnings_block_commit(),
+         confirm_warning_codes: default_confirm_warning_codes(),
+         on_type_scope_inconsistency: default_on_type_scope_inconsistency(),
+         auto_stage: default_auto_stage(),
+         protected_branches: default_protected_branches(),
+         large_file_warn_bytes: default_large_file_warn_bytes(),
+         max_committed_file_bytes: default_max_committed_file_bytes(),
+         auto_commit_min_confidence: default_auto_commit_min_confidence(),
+         watch_poll_interval_ms: default_watch_poll_interval_ms(),
+         temperature_jitter: default_temperature_jitter(),
+         capture_rationale: false,
+         summary_model_cascade: vec![],
+         validate_models: false,
+         model_temperatures: IndexMap::new(),
+         models_without_temperature: default_models_without_temperature(),
+         diff_algorithm: default_diff_algorithm(),
+         merge_subject: default_merge_subject(),
+         show_progress: default_show_progress(),
+         add_generator_trailer: default_add_generator_trailer(),
+         release_tag: default_release_tag(),
+         release_tag_format: default_release_tag_format(),
+         analysis_prompt: String::new(),
+         summary_prompt: String::new(),
+         generated_files: vec![],
+         api_counter: ApiCounter::default(),
       }
    }
 }
 
+/// Read a config file, dropping the UTF-8 BOM some Windows editors prepend
+fn read_config_file(path: &Path) -> Result<String> {
+   let contents = std::fs::read_to_string(path)
+      .map_err(|e| CommitGenError::Other(format!("Failed to read config: {e}")))?;
+   Ok(match contents.strip_prefix('\u{feff}') {
+      Some(stripped) => stripped.to_string(),
+      None => contents,
+   })
+}
+
 impl CommitConfig {
+   /// Diff length allowed before smart truncation in `mode`
+   pub fn max_diff_length_for(&self, mode: &Mode) -> usize {
+      match mode {
+         Mode::Staged => self.max_diff_length_staged,
+         Mode::Compose => self.max_diff_length_compose,
+         Mode::Commit | Mode::Unstaged | Mode::Merge => None,
+      }
+      .unwrap_or(self.max_diff_length)
+   }
+
+   /// `summary_tense` when `enforce_tense` is on
+   pub const fn enforced_tense(&self) -> Option<Tense> {
+      if self.enforce_tense {
+         Some(self.summary_tense)
+      } else {
+         None
+      }
+   }
+
+   /// How prompts and tool schemas name the summary's opening word
+   pub fn summary_verb(&self) -> String {
+      self
+         .enforced_tense()
+         .map_or_else(|| "verb".to_string(), |tense| format!("{} verb", tense.verb_form()))
+   }
+
+   /// Summary style rules for the summary and fast prompts
+   pub const fn summary_rules(&self) -> SummaryRules {
+      SummaryRules {
+         tense:              self.summary_tense,
+         enforce_tense:      self.enforce_tense,
+         lowercase_start:    self.enforce_lowercase_start,
+         no_trailing_period: self.enforce_no_trailing_period,
+      }
+   }
+
+   /// Summary models in the order they're tried: `summary_model_cascade`
+   /// (short names resolved), or `model` alone
+   pub fn summary_models(&self) -> Vec<String> {
+      if self.summary_model_cascade.is_empty() {
+         vec![self.model.clone()]
+      } else {
+         self
+            .summary_model_cascade
+            .iter()
+            .map(|name| resolve_model_name(name))
+            .collect()
+      }
+   }
+
+   /// Temperature to send to `model`: none for `models_without_temperature`,
+   /// otherwise its `model_temperatures` entry or `temperature`
+   pub fn temperature_for(&self, model: &str) -> Option<f32> {
+      let name = model.rsplit('/').next().unwrap_or(model);
+      let unsupported = self.models_without_temperature.iter().any(|entry| {
+         name == entry
+            || name
+               .strip_prefix(entry.as_str())
+               .is_some_and(|rest| rest.starts_with('-'))
+      });
+      if unsupported {
+         return None;
+      }
+      Some(
+         self
+            .model_temperatures
+            .get(model)
+            .copied()
+            .unwrap_or(self.temperature),
+      )
+   }
+
    pub fn resolved_api_mode(&self, _model_name: &str) -> ResolvedApiMode {
       match self.api_mode {
          ApiMode::ChatCompletions => ResolvedApiMode::ChatCompletions,
@@ -277,12 +1266,19 @@ impl CommitConfig {
       }
    }
 
+   /// Whether `path` is left out of analysis, by `excluded_files` or as a
+   /// generated file
+   pub fn is_excluded_file(&self, path: &str) -> bool {
+      ExcludedFiles::cached(&self.generated_files, &self.excluded_files).is_excluded(path)
+   }
+
    /// Load config from default location (~/.config/llm-git/config.toml)
    /// Falls back to Default if file doesn't exist or can't determine home
    /// directory Environment variables override config file values:
    /// - `LLM_GIT_API_URL` overrides `api_base_url`
    /// - `LLM_GIT_API_KEY` overrides `api_key`
    /// - `LLM_GIT_API_MODE` overrides `api_mode`
+   /// - `LLM_GIT_GITHUB_TOKEN` overrides `github_token`
    pub fn load() -> Result<Self> {
       let config_path = if let Ok(custom_path) = std::env::var("LLM_GIT_CONFIG") {
          PathBuf::from(custom_path)
@@ -313,17 +1309,42 @@ impl CommitConfig {
          config.api_key = Some(api_key);
       }
 
+      if let Ok(token) = std::env::var("LLM_GIT_GITHUB_TOKEN") {
+         config.github_token = Some(token);
+      }
+
       if let Ok(api_mode) = std::env::var("LLM_GIT_API_MODE") {
          config.api_mode = parse_api_mode(&api_mode);
       }
    }
 
    /// Load config from specific file
+   ///
+   /// Files listed under `include` (relative to the including file) are
+   /// loaded first, and the file's own keys override theirs.
    pub fn from_file(path: &Path) -> Result<Self> {
-      let contents = std::fs::read_to_string(path)
-         .map_err(|e| CommitGenError::Other(format!("Failed to read config: {e}")))?;
-      let mut config: Self = toml::from_str(&contents)
+      let (table, deprecated) = load_config_table(path, &mut Vec::new())?;
+      for (file, key) in deprecated {
+         eprintln!(
+            "Warning: config key `{}` in {} is deprecated, use `{}` instead",
+            key.old,
+            file.display(),
+            key.new
+         );
+      }
+      let mut config: Self = table
+         .try_into()
          .map_err(|e| CommitGenError::Other(format!("Failed to parse config: {e}")))?;
+      ExcludedFiles::check(&config.excluded_files)?;
+      for (entry, suggestion) in exclude::legacy_entries(&config.excluded_files) {
+         eprintln!(
+            "Note: excluded_files entry `{entry}` in {} matches by suffix; gitignore-style \
+             patterns like `{suggestion}` (with `!` negation and `dir/`) are also supported",
+            path.display()
+         );
+      }
+      // A fraction: a jitter past 1.0 would only ever land on the clamped ends
+      config.temperature_jitter = config.temperature_jitter.clamp(0.0, 1.0);
 
       // Apply environment variable overrides
       Self::apply_env_overrides(&mut config);
@@ -332,11 +1353,120 @@ impl CommitConfig {
       Ok(config)
    }
 
+   /// Parse config TOML, rewriting deprecated keys to their replacements
+   ///
+   /// Returns the config along with the deprecated keys that were used. Fails
+   /// if a deprecated key and its replacement are both set to different
+   /// values. Migrates [`TEST_DEPRECATED_KEYS`], and `include` isn't resolved
+   /// here; see [`Self::from_file`].
+   #[cfg(test)]
+   fn parse_with_migrations(contents: &str) -> Result<(Self, Vec<&'static DeprecatedKey>)> {
+      let mut table: toml::Table = toml::from_str(contents)
+         .map_err(|e| CommitGenError::Other(format!("Failed to parse config: {e}")))?;
+      let deprecated = migrate_deprecated_keys(&mut table, TEST_DEPRECATED_KEYS)?;
+      let config = table
+         .try_into()
+         .map_err(|e| CommitGenError::Other(format!("Failed to parse config: {e}")))?;
+      Ok((config, deprecated))
+   }
+
+   /// Strictly check a config file without loading prompts or env overrides
+   ///
+   /// Reports deprecated keys (with replacements) and keys that match neither a
+   /// config field nor a deprecation, then verifies the migrated file parses.
+   /// Included files are checked as part of the file including them.
+   pub fn check_file(path: &Path) -> Result<ConfigCheck> {
+      let (table, deprecated) = load_config_table(path, &mut Vec::new())?;
+      Self::check_table(table, deprecated.into_iter().map(|(_, key)| key).collect())
+   }
+
+   /// [`Self::check_file`] for a string, migrating [`TEST_DEPRECATED_KEYS`]
+   #[cfg(test)]
+   fn check_str(contents: &str) -> Result<ConfigCheck> {
+      let mut table: toml::Table = toml::from_str(contents)
+         .map_err(|e| CommitGenError::Other(format!("Failed to parse config: {e}")))?;
+      let deprecated = migrate_deprecated_keys(&mut table, TEST_DEPRECATED_KEYS)?;
+      Self::check_table(table, deprecated)
+   }
+
+   fn check_table(
+      table: toml::Table,
+      deprecated: Vec<&'static DeprecatedKey>,
+   ) -> Result<ConfigCheck> {
+      let known = config_field_names();
+      let unknown = table
+         .keys()
+         .filter(|key| !known.contains(&key.as_str()))
+         .cloned()
+         .collect();
+
+      let _: Self = table
+         .try_into()
+         .map_err(|e| CommitGenError::Other(format!("Failed to parse config: {e}")))?;
+
+      Ok(ConfigCheck { deprecated, unknown })
+   }
+
+   /// Default config as TOML for `--init-config`, each key set to its default
+   /// and preceded by its field docs as comments
+   ///
+   /// Plain keys come first and tables (`[normalization]`, `[types.*]`, ...)
+   /// after them, as TOML requires; unset optional keys are only mentioned.
+   pub fn default_config_toml() -> Result<String> {
+      use std::fmt::Write as _;
+
+      let to_toml_error = |e: toml::ser::Error| {
+         CommitGenError::Other(format!("Failed to serialize default config: {e}"))
+      };
+      let mut table = toml::Table::try_from(Self::default()).map_err(to_toml_error)?;
+      table.iter_mut().for_each(|(_, value)| shorten_float(value));
+
+      let mut plain = String::new();
+      let mut sections = String::new();
+      for &field in config_field_names() {
+         let value = table.remove(field);
+         let is_section = match &value {
+            Some(toml::Value::Table(_)) => true,
+            Some(toml::Value::Array(items)) => {
+               !items.is_empty() && items.iter().all(toml::Value::is_table)
+            },
+            _ => false,
+         };
+         let out = if is_section {
+            &mut sections
+         } else {
+            &mut plain
+         };
+
+         out.push('\n');
+         let doc = CONFIG_FIELD_DOCS
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map_or("", |(_, doc)| doc);
+         for line in doc.lines() {
+            writeln!(out, "# {line}").unwrap();
+         }
+         match value {
+            Some(value) => {
+               let entry = toml::Table::from_iter([(field.to_string(), value)]);
+               out.push_str(&toml::to_string_pretty(&entry).map_err(to_toml_error)?);
+            },
+            None => writeln!(out, "# {field} is unset by default").unwrap(),
+         }
+      }
+
+      Ok(format!(
+         "# llm-git configuration, written by `lgit --init-config`\n# Every key holds its \
+          default; remove the ones you don't want to pin.\n{plain}{sections}"
+      ))
+   }
+
    /// Load prompts - templates are now loaded dynamically via Tera
    /// This method ensures prompts are initialized
    fn load_prompts(&mut self) -> Result<()> {
       // Ensure prompts directory exists and embedded templates are unpacked
       crate::templates::ensure_prompts_dir()?;
+      crate::templates::register_config_templates(&self.templates)?;
 
       // Templates loaded dynamically at render time
       self.analysis_prompt = String::new();
@@ -361,6 +1491,220 @@ impl CommitConfig {
    }
 }
 
+/// A renamed config key that is still accepted under its old name
+#[derive(Debug)]
+pub struct DeprecatedKey {
+   /// Key as written in older configs
+   pub old:   &'static str,
+   /// Current key name
+   pub new:   &'static str,
+   /// Converts the old value into the new key's representation
+   transform: Option<fn(toml::Value) -> toml::Value>,
+}
+
+/// Old key → new key. Append here whenever a config key is renamed; none has
+/// been yet.
+pub const DEPRECATED_KEYS: &[DeprecatedKey] = &[];
+
+/// Renames exercising the migration in tests, including a value transform
+#[cfg(test)]
+const TEST_DEPRECATED_KEYS: &[DeprecatedKey] = &[
+   DeprecatedKey { old: "api_url", new: "api_base_url", transform: None },
+   DeprecatedKey { old: "timeout_secs", new: "request_timeout_secs", transform: None },
+   DeprecatedKey {
+      old:       "disable_changelog",
+      new:       "changelog_enabled",
+      transform: Some(invert_bool),
+   },
+];
+
+#[cfg(test)]
+fn invert_bool(value: toml::Value) -> toml::Value {
+   match value {
+      toml::Value::Boolean(b) => toml::Value::Boolean(!b),
+      other => other,
+   }
+}
+
+/// Result of a strict config check (`--check-config`)
+#[derive(Debug, Default)]
+pub struct ConfigCheck {
+   /// Deprecated keys present in the file
+   pub deprecated: Vec<&'static DeprecatedKey>,
+   /// Keys that are neither config fields nor known deprecations
+   pub unknown:    Vec<String>,
+}
+
+/// Key listing config files to load underneath the file that names it
+const INCLUDE_KEY: &str = "include";
+
+/// Config file at `path` as a TOML table, with deprecated keys migrated and
+/// its `include` files merged in first (in order), so its own keys win
+///
+/// `chain` holds the files currently being included, to detect cycles. The
+/// deprecated keys come back with the file that used them.
+fn load_config_table(
+   path: &Path,
+   chain: &mut Vec<PathBuf>,
+) -> Result<(toml::Table, Vec<(PathBuf, &'static DeprecatedKey)>)> {
+   let canonical = path.canonicalize().map_err(|e| {
+      CommitGenError::Other(format!("Failed to read config {}: {e}", path.display()))
+   })?;
+   if let Some(start) = chain.iter().position(|seen| *seen == canonical) {
+      let cycle: Vec<String> = chain[start..]
+         .iter()
+         .chain([&canonical])
+         .map(|file| file.display().to_string())
+         .collect();
+      return Err(CommitGenError::Other(format!("Config include cycle: {}", cycle.join(" -> "))));
+   }
+
+   let contents = read_config_file(path)?;
+   let mut table: toml::Table = toml::from_str(&contents).map_err(|e| {
+      CommitGenError::Other(format!("Failed to parse config {}: {e}", path.display()))
+   })?;
+   let includes = match table.remove(INCLUDE_KEY) {
+      None => Vec::new(),
+      Some(toml::Value::String(include)) => vec![include],
+      Some(toml::Value::Array(items)) => items
+         .into_iter()
+         .map(|item| match item {
+            toml::Value::String(include) => Ok(include),
+            other => Err(include_type_error(path, &other)),
+         })
+         .collect::<Result<_>>()?,
+      Some(other) => return Err(include_type_error(path, &other)),
+   };
+
+   let mut merged = toml::Table::new();
+   let mut deprecated = Vec::new();
+   let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+   chain.push(canonical);
+   for include in includes {
+      let (included, included_deprecated) = load_config_table(&base_dir.join(include), chain)?;
+      merge_config_tables(&mut merged, included);
+      deprecated.extend(included_deprecated);
+   }
+   chain.pop();
+
+   deprecated.extend(
+      migrate_deprecated_keys(&mut table, DEPRECATED_KEYS)?
+         .into_iter()
+         .map(|key| (path.to_path_buf(), key)),
+   );
+   merge_config_tables(&mut merged, table);
+   Ok((merged, deprecated))
+}
+
+fn include_type_error(path: &Path, value: &toml::Value) -> CommitGenError {
+   CommitGenError::Other(format!(
+      "Config `{INCLUDE_KEY}` in {} must be a path or an array of paths, found {}",
+      path.display(),
+      value.type_str()
+   ))
+}
+
+/// Merge `overlay` onto `base`: tables (`[normalization]`, `[types.feat]`,
+/// ...) merge key by key, any other value replaces the one in `base`
+fn merge_config_tables(base: &mut toml::Table, overlay: toml::Table) {
+   for (key, value) in overlay {
+      match (base.get_mut(&key), value) {
+         (Some(toml::Value::Table(base_table)), toml::Value::Table(table)) => {
+            merge_config_tables(base_table, table);
+         },
+         (_, value) => {
+            base.insert(key, value);
+         },
+      }
+   }
+}
+
+/// Rewrite the deprecated `keys` in place, returning the ones that were
+/// present
+fn migrate_deprecated_keys(
+   table: &mut toml::Table,
+   keys: &'static [DeprecatedKey],
+) -> Result<Vec<&'static DeprecatedKey>> {
+   let mut used = Vec::new();
+   for key in keys {
+      let Some(value) = table.remove(key.old) else {
+         continue;
+      };
+      let value = match key.transform {
+         Some(transform) => transform(value),
+         None => value,
+      };
+
+      match table.get(key.new) {
+         Some(existing) if *existing != value => {
+            return Err(CommitGenError::Other(format!(
+               "Config sets both deprecated `{}` and `{}` with conflicting values; remove `{}`",
+               key.old, key.new, key.old
+            )));
+         },
+         Some(_) => {},
+         None => {
+            table.insert(key.new.to_string(), value);
+         },
+      }
+      used.push(key);
+   }
+   Ok(used)
+}
+
+// Generated by build.rs from the `CommitConfig` field docs
+include!(concat!(env!("OUT_DIR"), "/config_docs.rs"));
+
+/// Rewrite floats (serialized through `f64`) to the shortest `f32` spelling,
+/// so the generated config reads `0.15` rather than `0.15000000596046448`
+fn shorten_float(value: &mut toml::Value) {
+   match value {
+      toml::Value::Float(f) => *f = (*f as f32).to_string().parse().unwrap_or(*f),
+      toml::Value::Array(items) => items.iter_mut().for_each(shorten_float),
+      toml::Value::Table(table) => table.iter_mut().for_each(|(_, value)| shorten_float(value)),
+      _ => {},
+   }
+}
+
+/// Field names accepted by `CommitConfig`, read from its derived `Deserialize`
+/// so the strict check never drifts from the struct
+fn config_field_names() -> &'static [&'static str] {
+   use serde::de::{self, Visitor};
+
+   struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+   impl<'de> de::Deserializer<'de> for FieldNames<'_> {
+      type Error = de::value::Error;
+
+      serde::forward_to_deserialize_any! {
+         bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+         option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+         ignored_any
+      }
+
+      fn deserialize_any<V: Visitor<'de>>(
+         self,
+         _: V,
+      ) -> std::result::Result<V::Value, Self::Error> {
+         Err(de::Error::custom("field name probe"))
+      }
+
+      fn deserialize_struct<V: Visitor<'de>>(
+         self,
+         _: &'static str,
+         fields: &'static [&'static str],
+         _: V,
+      ) -> std::result::Result<V::Value, Self::Error> {
+         *self.0 = fields;
+         Err(de::Error::custom("field name probe"))
+      }
+   }
+
+   let mut fields: &'static [&'static str] = &[];
+   let _ = CommitConfig::deserialize(FieldNames(&mut fields));
+   fields
+}
+
 /// Valid past-tense verbs for commit messages
 pub const PAST_TENSE_VERBS: &[&str] = &[
    "added",
@@ -566,3 +1910,310 @@ BEFORE RESPONDING:
 ✓ Aligns with detail points and diff stat
 ✓ Specific (names subsystem/artifact)
 "#;
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   #[test]
+   fn test_bom_prefixed_config_loads() {
+      let dir = std::env::temp_dir().join(format!("lgit-config-bom-{}", std::process::id()));
+      std::fs::create_dir_all(&dir).unwrap();
+      let path = dir.join("config.toml");
+      std::fs::write(&path, "\u{feff}summary_guideline = 60\nchangelog_enabled = false\n").unwrap();
+
+      let config = CommitConfig::from_file(&path).unwrap();
+      assert_eq!(config.summary_guideline, 60);
+      assert!(!config.changelog_enabled);
+      assert!(CommitConfig::check_file(&path).is_ok());
+      let _ = std::fs::remove_dir_all(&dir);
+   }
+
+   #[test]
+   fn test_include_merges_two_levels_with_local_overrides() {
+      let dir = std::env::temp_dir().join(format!("lgit-config-include-{}", std::process::id()));
+      std::fs::create_dir_all(dir.join("team")).unwrap();
+      std::fs::create_dir_all(dir.join("me")).unwrap();
+      std::fs::write(
+         dir.join("team/base.toml"),
+         "summary_guideline = 60\nmax_retries = 5\napi_base_url = \"http://team.test\"\n\n\
+          [normalization]\narrows = false\n",
+      )
+      .unwrap();
+      std::fs::write(
+         dir.join("team/team.toml"),
+         "include = [\"base.toml\"]\nsummary_guideline = 65\n\n[normalization]\ngreek = false\n",
+      )
+      .unwrap();
+      let path = dir.join("me/config.toml");
+      std::fs::write(
+         &path,
+         "include = [\"../team/team.toml\"]\nsummary_guideline = 70\nmax_dif_length = 1\n",
+      )
+      .unwrap();
+
+      let config = CommitConfig::from_file(&path).unwrap();
+      let check = CommitConfig::check_file(&path).unwrap();
+      let _ = std::fs::remove_dir_all(&dir);
+
+      assert_eq!(config.summary_guideline, 70);
+      assert_eq!(config.max_retries, 5);
+      assert_eq!(config.api_base_url, "http://team.test");
+      assert!(!config.normalization.arrows);
+      assert!(!config.normalization.greek);
+      assert!(config.normalization.math);
+      assert!(check.deprecated.is_empty());
+      assert_eq!(check.unknown, vec!["max_dif_length".to_string()]);
+   }
+
+   #[test]
+   fn test_temperature_jitter_is_clamped_on_load() {
+      let dir = std::env::temp_dir().join(format!("lgit-config-jitter-{}", std::process::id()));
+      std::fs::create_dir_all(&dir).unwrap();
+      std::fs::write(dir.join("high.toml"), "temperature_jitter = 3.5\n").unwrap();
+      std::fs::write(dir.join("low.toml"), "temperature_jitter = -0.2\n").unwrap();
+
+      let high = CommitConfig::from_file(&dir.join("high.toml")).unwrap();
+      let low = CommitConfig::from_file(&dir.join("low.toml")).unwrap();
+      std::fs::remove_dir_all(&dir).ok();
+      assert!((high.temperature_jitter - 1.0).abs() < f32::EPSILON);
+      assert!(low.temperature_jitter.abs() < f32::EPSILON);
+   }
+
+   #[test]
+   fn test_include_cycle_is_error() {
+      let dir = std::env::temp_dir().join(format!("lgit-config-cycle-{}", std::process::id()));
+      std::fs::create_dir_all(&dir).unwrap();
+      std::fs::write(dir.join("a.toml"), "include = \"b.toml\"\n").unwrap();
+      std::fs::write(dir.join("b.toml"), "include = [\"./a.toml\"]\nmodel = \"x\"\n").unwrap();
+      std::fs::write(dir.join("c.toml"), "include = [1]\n").unwrap();
+
+      let err = CommitConfig::from_file(&dir.join("a.toml"))
+         .unwrap_err()
+         .to_string();
+      let type_err = CommitConfig::from_file(&dir.join("c.toml"))
+         .unwrap_err()
+         .to_string();
+      let canonical = dir.canonicalize().unwrap();
+      let _ = std::fs::remove_dir_all(&dir);
+
+      let (a, b) = (canonical.join("a.toml"), canonical.join("b.toml"));
+      assert_eq!(
+         err,
+         format!("Config include cycle: {} -> {} -> {}", a.display(), b.display(), a.display())
+      );
+      assert!(
+         type_err.contains("must be a path or an array of paths, found integer"),
+         "{type_err}"
+      );
+   }
+
+   #[test]
+   fn test_deprecated_keys_are_migrated() {
+      let (config, deprecated) = CommitConfig::parse_with_migrations(
+         "api_url = \"http://example.test\"\ndisable_changelog = true\n",
+      )
+      .unwrap();
+      assert_eq!(config.api_base_url, "http://example.test");
+      assert!(!config.changelog_enabled);
+      let names: Vec<_> = deprecated.iter().map(|k| k.old).collect();
+      assert_eq!(names, vec!["api_url", "disable_changelog"]);
+   }
+
+   #[test]
+   fn test_deprecated_key_matching_new_value_is_accepted() {
+      let (config, deprecated) =
+         CommitConfig::parse_with_migrations("timeout_secs = 30\nrequest_timeout_secs = 30\n")
+            .unwrap();
+      assert_eq!(config.request_timeout_secs, 30);
+      assert_eq!(deprecated.len(), 1);
+   }
+
+   #[test]
+   fn test_deprecated_key_conflict_is_error() {
+      let err = CommitConfig::parse_with_migrations(
+         "api_url = \"http://old.test\"\napi_base_url = \"http://new.test\"\n",
+      )
+      .unwrap_err();
+      assert!(err.to_string().contains("conflicting values"));
+   }
+
+   #[test]
+   fn test_check_reports_deprecated_and_unknown_keys() {
+      let check = CommitConfig::check_str(
+         "api_url = \"http://example.test\"\nmodel = \"x\"\nmax_dif_length = 10\n",
+      )
+      .unwrap();
+      assert_eq!(check.deprecated.len(), 1);
+      assert_eq!(check.deprecated[0].new, "api_base_url");
+      assert_eq!(check.unknown, vec!["max_dif_length".to_string()]);
+   }
+
+   #[test]
+   fn test_config_field_names_cover_struct() {
+      let fields = config_field_names();
+      assert!(fields.contains(&"api_base_url"));
+      assert!(fields.contains(&"changelog_enabled"));
+      assert!(!fields.contains(&"analysis_prompt"));
+   }
+
+   #[test]
+   fn test_compose_on_apply_error_parsing() {
+      assert_eq!(CommitConfig::default().compose_on_apply_error, None);
+
+      let (config, _) =
+         CommitConfig::parse_with_migrations("compose_on_apply_error = \"widen\"\n").unwrap();
+      assert_eq!(config.compose_on_apply_error, Some(ApplyErrorStrategy::Widen));
+      assert!(CommitConfig::parse_with_migrations("compose_on_apply_error = \"retry\"\n").is_err());
+   }
+
+   #[test]
+   fn test_on_type_scope_inconsistency_parsing() {
+      assert_eq!(CommitConfig::default().on_type_scope_inconsistency, TypeScopePolicy::Warn);
+
+      let (config, _) =
+         CommitConfig::parse_with_migrations("on_type_scope_inconsistency = \"correct\"\n")
+            .unwrap();
+      assert_eq!(config.on_type_scope_inconsistency, TypeScopePolicy::Correct);
+      assert!(
+         CommitConfig::parse_with_migrations("on_type_scope_inconsistency = \"ignore\"\n").is_err()
+      );
+   }
+
+   #[test]
+   fn test_rewrite_mailmap_parsing() {
+      assert!(CommitConfig::default().rewrite_mailmap.is_empty());
+
+      let (config, _) = CommitConfig::parse_with_migrations(
+         "rewrite_mailmap = [[\"me@old\", \"me@new\"], [\"a@b\", \"A <a@c>\"]]\n",
+      )
+      .unwrap();
+      assert_eq!(config.rewrite_mailmap, vec![
+         ("me@old".to_string(), "me@new".to_string()),
+         ("a@b".to_string(), "A <a@c>".to_string()),
+      ]);
+   }
+
+   #[test]
+   fn test_tense_defaults_and_parsing() {
+      let config = CommitConfig::default();
+      assert_eq!(config.summary_tense, Tense::Past);
+      assert_eq!(config.body_tense, Tense::Past);
+
+      let (config, _) =
+         CommitConfig::parse_with_migrations("summary_tense = \"imperative\"\n").unwrap();
+      assert_eq!(config.summary_tense, Tense::Imperative);
+      assert_eq!(config.body_tense, Tense::Past);
+   }
+
+   #[test]
+   fn test_normalization_section() {
+      assert_eq!(CommitConfig::default().normalization, NormalizationConfig::default());
+
+      let (config, _) =
+         CommitConfig::parse_with_migrations("[normalization]\narrows = false\ngreek = false\n")
+            .unwrap();
+      assert_eq!(config.normalization, NormalizationConfig {
+         arrows: false,
+         greek: false,
+         ..NormalizationConfig::default()
+      });
+      assert!(CommitConfig::parse_with_migrations("[normalization]\nemoji = false\n").is_err());
+   }
+
+   #[test]
+   fn test_summary_rule_toggles() {
+      let config = CommitConfig::default();
+      assert_eq!(config.enforced_tense(), Some(Tense::Past));
+      assert_eq!(config.summary_verb(), "past-tense verb");
+
+      let (config, _) = CommitConfig::parse_with_migrations(
+         "enforce_tense = false\nenforce_lowercase_start = false\n",
+      )
+      .unwrap();
+      assert_eq!(config.enforced_tense(), None);
+      assert_eq!(config.summary_verb(), "verb");
+      assert_eq!(config.summary_rules(), SummaryRules {
+         tense:              Tense::Past,
+         enforce_tense:      false,
+         lowercase_start:    false,
+         no_trailing_period: true,
+      });
+   }
+
+   #[test]
+   fn test_cli_defaults_table() {
+      let (config, _) = CommitConfig::parse_with_migrations(
+         "[cli_defaults]\ncopy = true\npush = false\nmodel = \"opus\"\n",
+      )
+      .unwrap();
+      assert_eq!(config.cli_defaults, CliDefaults {
+         model: Some("opus".to_string()),
+         copy: Some(true),
+         push: Some(false),
+         ..Default::default()
+      });
+
+      assert!(CommitConfig::parse_with_migrations("[cli_defaults]\nrewrite = true\n").is_err());
+   }
+
+   #[test]
+   fn test_max_diff_length_per_mode() {
+      let config = CommitConfig::default();
+      assert_eq!(config.max_diff_length_for(&Mode::Staged), config.max_diff_length);
+      assert_eq!(config.max_diff_length_for(&Mode::Compose), config.max_diff_length);
+
+      let (config, _) = CommitConfig::parse_with_migrations(
+         "max_diff_length = 50000\nmax_diff_length_staged = 20000\nmax_diff_length_compose = \
+          200000\n",
+      )
+      .unwrap();
+      assert_eq!(config.max_diff_length_for(&Mode::Staged), 20000);
+      assert_eq!(config.max_diff_length_for(&Mode::Compose), 200000);
+      assert_eq!(config.max_diff_length_for(&Mode::Commit), 50000);
+      assert_eq!(config.max_diff_length_for(&Mode::Unstaged), 50000);
+   }
+
+   #[test]
+   fn test_temperature_for_model() {
+      let (config, _) = CommitConfig::parse_with_migrations(
+         "temperature = 0.2\n[model_temperatures]\n\"claude-sonnet-4.5\" = 0.6\n",
+      )
+      .unwrap();
+      assert_eq!(config.temperature_for("claude-sonnet-4.5"), Some(0.6));
+      assert_eq!(config.temperature_for("claude-haiku-4-5"), Some(0.2));
+
+      // Reasoning models get none, including dated and provider-prefixed names
+      assert_eq!(config.temperature_for("o3"), None);
+      assert_eq!(config.temperature_for("o3-mini"), None);
+      assert_eq!(config.temperature_for("openai/o4-mini"), None);
+      assert_eq!(config.temperature_for("o1-2024-12-17"), None);
+      assert_eq!(config.temperature_for("o30"), Some(0.2));
+   }
+
+   #[test]
+   fn test_summary_length_metric() {
+      assert_eq!(CommitConfig::default().summary_length_metric, LengthMetric::Chars);
+      let (config, _) =
+         CommitConfig::parse_with_migrations("summary_length_metric = \"width\"\n").unwrap();
+      assert_eq!(config.summary_length_metric, LengthMetric::Width);
+
+      assert_eq!(LengthMetric::Bytes.measure("café 中文"), 12);
+      assert_eq!(LengthMetric::Chars.measure("café 中文"), 7);
+      assert_eq!(LengthMetric::Width.measure("café 中文"), 9);
+   }
+
+   #[test]
+   fn test_default_config_toml_round_trips() {
+      let text = CommitConfig::default_config_toml().unwrap();
+      assert!(text.contains("# Sampling temperature for API calls\ntemperature = 0.2\n"));
+      assert!(text.contains("# api_key is unset by default\n"));
+
+      let check = CommitConfig::check_str(&text).unwrap();
+      assert!(check.deprecated.is_empty() && check.unknown.is_empty());
+
+      // Debug covers every field, including the order of `types` and `categories`
+      let (parsed, _) = CommitConfig::parse_with_migrations(&text).unwrap();
+      assert_eq!(format!("{parsed:?}"), format!("{:?}", CommitConfig::default()));
+   }
+}
@@ -0,0 +1,233 @@
+//! Validation warnings collected for the commit step
+//!
+//! Warnings are printed where they occur, as before; [`warn`] also records
+//! them under a [`WarningCode`]. Before committing, staged mode and compose
+//! hand what was recorded to [`check_before_commit`], which asks first
+//! (`confirm_on_warnings`) or refuses (`warnings_block_commit`) when any of
+//! the `confirm_warning_codes` came up.
+
+use std::io::{BufRead, IsTerminal, Write};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+   config::CommitConfig,
+   error::{CommitGenError, Result},
+   style,
+};
+
+/// Kind of validation warning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WarningCode {
+   /// First line longer than `summary_soft_limit`
+   SoftLimit,
+   /// Commit type contradicted by the changed files
+   TypeScopeMismatch,
+   /// Filler word in the summary ("various", "several", ...)
+   FillerWord,
+   /// Meta-phrase in the summary ("this commit", "updated the", ...)
+   MetaPhrase,
+   /// Body item in the wrong tense or without a period
+   BodyStyle,
+   /// Scope naming a file rather than a module
+   FilenameScope,
+   /// Summary naming the scope again ("fix(parser): fixed parser panic")
+   ScopeInSummary,
+   /// Footer whose first line is longer than `summary_soft_limit`
+   LongFooter,
+}
+
+/// A recorded warning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+   pub code:    WarningCode,
+   pub message: String,
+}
+
+/// Warnings recorded since the last [`take`] or [`clear`]
+static RECORDED: Mutex<Vec<Diagnostic>> = Mutex::new(Vec::new());
+
+/// Print a warning and record it under `code`
+pub fn warn(code: WarningCode, message: &str) {
+   style::warn(message);
+   RECORDED
+      .lock()
+      .push(Diagnostic { code, message: message.to_string() });
+}
+
+/// Forget recorded warnings, e.g. before validating a regenerated message
+pub fn clear() {
+   RECORDED.lock().clear();
+}
+
+/// Recorded warnings, clearing the record
+pub fn take() -> Vec<Diagnostic> {
+   std::mem::take(&mut *RECORDED.lock())
+}
+
+/// What to do about the warnings before a commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitGate {
+   Proceed,
+   /// Ask, showing these warnings again
+   Confirm(Vec<Diagnostic>),
+   /// Refuse, listing these warnings
+   Block(Vec<Diagnostic>),
+}
+
+/// Decide on `diagnostics` per config.
+///
+/// `confirm` is whether asking is an option (`confirm_on_warnings`, defaulting
+/// to stdin being a terminal, and no `--yes`); otherwise warnings only stop the
+/// commit with `warnings_block_commit`.
+pub fn commit_gate(
+   diagnostics: Vec<Diagnostic>,
+   config: &CommitConfig,
+   confirm: bool,
+) -> CommitGate {
+   let mut relevant: Vec<Diagnostic> = Vec::new();
+   for diagnostic in diagnostics {
+      if config.confirm_warning_codes.contains(&diagnostic.code) && !relevant.contains(&diagnostic)
+      {
+         relevant.push(diagnostic);
+      }
+   }
+
+   if relevant.is_empty() {
+      CommitGate::Proceed
+   } else if confirm {
+      CommitGate::Confirm(relevant)
+   } else if config.warnings_block_commit {
+      CommitGate::Block(relevant)
+   } else {
+      CommitGate::Proceed
+   }
+}
+
+/// Show `warnings` again and ask whether to commit anyway; anything but
+/// `y`/`yes` (including end of input) is a no
+pub fn confirm_commit(warnings: &[Diagnostic], input: &mut impl BufRead) -> Result<bool> {
+   eprintln!("\n{}", style::warning("Validation warnings:"));
+   for warning in warnings {
+      eprintln!("  {} {}", style::warning(style::icons::WARNING), warning.message);
+   }
+   eprint!("Commit anyway? [y/N] ");
+   std::io::stderr().flush().ok();
+
+   let mut answer = String::new();
+   input.read_line(&mut answer)?;
+   Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn list(warnings: &[Diagnostic]) -> String {
+   warnings
+      .iter()
+      .map(|warning| warning.message.as_str())
+      .collect::<Vec<_>>()
+      .join("; ")
+}
+
+/// Apply the warnings policy to `diagnostics`, reading the answer from
+/// `input` when asking
+pub fn check_warnings(
+   diagnostics: Vec<Diagnostic>,
+   config: &CommitConfig,
+   confirm: bool,
+   input: &mut impl BufRead,
+) -> Result<()> {
+   match commit_gate(diagnostics, config, confirm) {
+      CommitGate::Proceed => Ok(()),
+      CommitGate::Confirm(warnings) => {
+         if confirm_commit(&warnings, input)? {
+            Ok(())
+         } else {
+            Err(CommitGenError::CommitDeclined(list(&warnings)))
+         }
+      },
+      CommitGate::Block(warnings) => Err(CommitGenError::WarningsBlockCommit(list(&warnings))),
+   }
+}
+
+/// [`check_warnings`] on the warnings recorded so far (clearing them),
+/// asking on stdin; `--yes` skips the question
+pub fn check_before_commit(config: &CommitConfig, assume_yes: bool) -> Result<()> {
+   let stdin = std::io::stdin();
+   let confirm = !assume_yes
+      && config
+         .confirm_on_warnings
+         .unwrap_or_else(|| stdin.is_terminal());
+   check_warnings(take(), config, confirm, &mut stdin.lock())
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   fn diagnostic(code: WarningCode, message: &str) -> Diagnostic {
+      Diagnostic { code, message: message.to_string() }
+   }
+
+   #[test]
+   fn test_commit_gate_filters_codes_and_applies_policy() {
+      let soft = diagnostic(WarningCode::SoftLimit, "Summary exceeds soft limit: 90 > 72");
+      let body = diagnostic(WarningCode::BodyStyle, "Body item missing period: 'x'");
+      let config = CommitConfig::default();
+
+      // Body style isn't gated by default
+      assert_eq!(commit_gate(vec![body.clone()], &config, true), CommitGate::Proceed);
+      assert_eq!(
+         commit_gate(vec![soft.clone(), body.clone(), soft.clone()], &config, true),
+         CommitGate::Confirm(vec![soft.clone()])
+      );
+      // Without a terminal nothing changes unless warnings block
+      assert_eq!(commit_gate(vec![soft.clone()], &config, false), CommitGate::Proceed);
+      let config = CommitConfig { warnings_block_commit: true, ..Default::default() };
+      assert_eq!(
+         commit_gate(vec![soft.clone()], &config, false),
+         CommitGate::Block(vec![soft.clone()])
+      );
+
+      let config =
+         CommitConfig { confirm_warning_codes: vec![WarningCode::BodyStyle], ..Default::default() };
+      assert_eq!(commit_gate(vec![soft.clone()], &config, true), CommitGate::Proceed);
+      assert_eq!(
+         commit_gate(vec![soft, body.clone()], &config, true),
+         CommitGate::Confirm(vec![body])
+      );
+   }
+
+   #[test]
+   fn test_confirm_commit_reads_answer() {
+      let warnings =
+         [diagnostic(WarningCode::FillerWord, "Summary contains filler word 'various'")];
+      assert!(confirm_commit(&warnings, &mut &b"y\n"[..]).unwrap());
+      assert!(confirm_commit(&warnings, &mut &b" YES \n"[..]).unwrap());
+      assert!(!confirm_commit(&warnings, &mut &b"n\n"[..]).unwrap());
+      assert!(!confirm_commit(&warnings, &mut &b"\n"[..]).unwrap());
+      assert!(!confirm_commit(&warnings, &mut &b""[..]).unwrap());
+   }
+
+   #[test]
+   fn test_check_warnings_scripted_answers() {
+      let warnings = vec![diagnostic(
+         WarningCode::TypeScopeMismatch,
+         "Commit type 'docs' but no documentation files changed",
+      )];
+      let config = CommitConfig::default();
+
+      let err = check_warnings(warnings.clone(), &config, true, &mut &b"n\n"[..]).unwrap_err();
+      assert!(
+         matches!(&err, CommitGenError::CommitDeclined(list) if list.contains("'docs'")),
+         "{err}"
+      );
+      check_warnings(warnings.clone(), &config, true, &mut &b"y\n"[..]).unwrap();
+
+      // Not asking: the input is never read
+      check_warnings(warnings.clone(), &config, false, &mut &b"n\n"[..]).unwrap();
+      let config = CommitConfig { warnings_block_commit: true, ..Default::default() };
+      let err = check_warnings(warnings, &config, false, &mut &b"y\n"[..]).unwrap_err();
+      assert!(matches!(err, CommitGenError::WarningsBlockCommit(_)));
+   }
+}
@@ -1,6 +1,34 @@
 /// Diff parsing and smart truncation logic
+use std::borrow::Cow;
+
 use crate::{config::CommitConfig, tokens::TokenCounter};
 
+/// Git's file mode for symbolic links
+const SYMLINK_MODE: &str = "120000";
+
+/// What a file entry changes, read from its extended header lines
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ChangeKind {
+   /// Text hunks, possibly alongside a mode change
+   #[default]
+   Content,
+   /// Mode change without content change (`old mode`/`new mode`, no hunks)
+   ModeOnly {
+      old: String,
+      new: String,
+   },
+   /// Symlink target change; a side is `None` when the link is created or
+   /// removed. `content` keeps the one-line target diff.
+   SymlinkRetarget {
+      old: Option<String>,
+      new: Option<String>,
+   },
+   Binary,
+   /// Git LFS pointer change; `content` holds a synthetic note instead of the
+   /// pointer lines
+   LfsPointer,
+}
+
 #[derive(Debug, Clone)]
 pub struct FileDiff {
    pub filename:  String,
@@ -8,10 +36,18 @@ pub struct FileDiff {
    pub content:   String, // The actual diff content
    pub additions: usize,
    pub deletions: usize,
-   pub is_binary: bool,
+   pub kind:      ChangeKind,
 }
 
 impl FileDiff {
+   pub const fn is_binary(&self) -> bool {
+      matches!(self.kind, ChangeKind::Binary)
+   }
+
+   pub const fn is_lfs_pointer(&self) -> bool {
+      matches!(self.kind, ChangeKind::LfsPointer)
+   }
+
    pub const fn size(&self) -> usize {
       self.header.len() + self.content.len()
    }
@@ -22,9 +58,96 @@ impl FileDiff {
       counter.count_sync(&self.header) + counter.count_sync(&self.content)
    }
 
+   /// Original path when the header records a rename (`rename from <old>`)
+   pub fn renamed_from(&self) -> Option<&str> {
+      self
+         .header
+         .lines()
+         .find_map(|line| line.strip_prefix("rename from "))
+   }
+
+   /// Whether this is a pure rename (e.g. `git mv`) with no content change
+   pub fn is_rename_only(&self) -> bool {
+      !self.is_binary()
+         && self.additions == 0
+         && self.deletions == 0
+         && self.renamed_from().is_some()
+   }
+
+   /// Deterministic observation for a rename-only file, so the model is never
+   /// asked to describe a change that has no content
+   pub fn rename_observation(&self) -> Option<String> {
+      if !self.is_rename_only() {
+         return None;
+      }
+      self
+         .renamed_from()
+         .map(|old| format!("Renamed {old} to {}.", self.filename))
+   }
+
+   /// Whether the file is removed entirely (`+++ /dev/null`)
+   pub fn is_deleted_file(&self) -> bool {
+      !self.is_binary()
+         && !self.is_lfs_pointer()
+         && self
+            .header
+            .lines()
+            .any(|line| line.starts_with("deleted file mode") || line == "+++ /dev/null")
+   }
+
+   /// Number of lines removed from a deleted file
+   ///
+   /// Read from the hunk header when present, so the count survives
+   /// truncation and hunk elision of the content.
+   pub fn deleted_line_count(&self) -> usize {
+      let from_hunk = self
+         .header
+         .lines()
+         .find_map(|line| line.strip_prefix("@@ -"))
+         .and_then(|rest| rest.split_whitespace().next())
+         .and_then(|range| {
+            range
+               .split_once(',')
+               .map_or(Some("1"), |(_, len)| Some(len))
+         })
+         .and_then(|len| len.parse::<usize>().ok())
+         .unwrap_or(0);
+      from_hunk.max(self.deletions)
+   }
+
+   /// Deterministic observation for a fully deleted file, naming what was
+   /// removed instead of leaving the model to say "removed unused code"
+   pub fn deletion_observation(&self) -> Option<String> {
+      self
+         .is_deleted_file()
+         .then(|| format!("Deleted {} ({}).", self.filename, line_count(self.deleted_line_count())))
+   }
+
+   /// Synthetic note for an LFS pointer change, used as its observation
+   /// without asking the model about oids
+   pub fn lfs_observation(&self) -> Option<&str> {
+      self.is_lfs_pointer().then_some(self.content.as_str())
+   }
+
+   /// Deterministic observation for a mode-only or symlink change, which has
+   /// no hunks worth asking the model about
+   pub fn kind_observation(&self) -> Option<String> {
+      let name = &self.filename;
+      match &self.kind {
+         ChangeKind::ModeOnly { new, .. } => Some(format!("Changed file mode of {name} to {new}.")),
+         ChangeKind::SymlinkRetarget { old, new } => Some(match (old, new) {
+            (Some(old), Some(new)) => format!("Retargeted symlink {name} from {old} to {new}."),
+            (None, Some(new)) => format!("Added symlink {name} pointing to {new}."),
+            (Some(old), None) => format!("Removed symlink {name} (pointed to {old})."),
+            (None, None) => format!("Changed symlink {name}."),
+         }),
+         _ => None,
+      }
+   }
+
    pub fn priority(&self, config: &CommitConfig) -> i32 {
       // Higher number = higher priority
-      if self.is_binary {
+      if self.is_binary() || self.is_lfs_pointer() {
          return -100; // Lowest priority
       }
 
@@ -112,8 +235,30 @@ impl FileDiff {
    }
 }
 
+/// Convert CRLF (and stray CRs before a newline) to plain LF
+///
+/// Diffs from Windows checkouts carry `\r\n`, sometimes mixed with `\n`.
+/// Carriage returns in the middle of a line are content and left alone.
+pub fn normalize_line_endings(text: &str) -> Cow<'_, str> {
+   if !text.contains('\r') {
+      return Cow::Borrowed(text);
+   }
+
+   let mut out = String::with_capacity(text.len());
+   let mut segments = text.split('\n').peekable();
+   while let Some(segment) = segments.next() {
+      // Trailing CRs with no newline after them are a line ending too
+      out.push_str(segment.trim_end_matches('\r'));
+      if segments.peek().is_some() {
+         out.push('\n');
+      }
+   }
+   Cow::Owned(out)
+}
+
 /// Parse a git diff into individual file diffs
 pub fn parse_diff(diff: &str) -> Vec<FileDiff> {
+   let diff = normalize_line_endings(diff);
    let mut file_diffs = Vec::new();
    let mut current_file: Option<FileDiff> = None;
    let mut in_diff_header = false;
@@ -138,12 +283,12 @@ pub fn parse_diff(diff: &str) -> Vec<FileDiff> {
             content: String::new(),
             additions: 0,
             deletions: 0,
-            is_binary: false,
+            kind: ChangeKind::Content,
          });
          in_diff_header = true;
       } else if let Some(file) = &mut current_file {
          if line.starts_with("Binary files") {
-            file.is_binary = true;
+            file.kind = ChangeKind::Binary;
             file.header.reserve(line.len() + 1);
             file.header.push('\n');
             file.header.push_str(line);
@@ -191,9 +336,235 @@ pub fn parse_diff(diff: &str) -> Vec<FileDiff> {
       file_diffs.push(file);
    }
 
+   for file in &mut file_diffs {
+      classify_change(file);
+      mark_lfs_pointer(file);
+   }
+
    file_diffs
 }
 
+/// Files of a diff made only of mode changes (`chmod +x`); `None` when any
+/// file changes content
+pub fn mode_only_files(diff: &str) -> Option<Vec<FileDiff>> {
+   let files = parse_diff(diff);
+   (!files.is_empty()
+      && files
+         .iter()
+         .all(|f| matches!(f.kind, ChangeKind::ModeOnly { .. })))
+   .then_some(files)
+}
+
+/// Set [`ChangeKind::ModeOnly`] or [`ChangeKind::SymlinkRetarget`] from the
+/// extended header lines of a parsed text entry
+fn classify_change(file: &mut FileDiff) {
+   if file.kind != ChangeKind::Content {
+      return;
+   }
+   let header_value = |prefix: &str| {
+      file
+         .header
+         .lines()
+         .find_map(|line| line.strip_prefix(prefix))
+   };
+
+   let is_symlink = [
+      header_value("new file mode "),
+      header_value("deleted file mode "),
+      header_value("index ").and_then(|index| index.split_whitespace().nth(1)),
+   ]
+   .contains(&Some(SYMLINK_MODE));
+   if is_symlink {
+      let target = |marker: char| {
+         file
+            .content
+            .lines()
+            .find_map(|line| line.strip_prefix(marker))
+            .map(str::to_string)
+      };
+      file.kind = ChangeKind::SymlinkRetarget { old: target('-'), new: target('+') };
+      return;
+   }
+
+   if file.content.is_empty()
+      && let (Some(old), Some(new)) = (header_value("old mode "), header_value("new mode "))
+   {
+      file.kind = ChangeKind::ModeOnly { old: old.to_string(), new: new.to_string() };
+   }
+}
+
+/// First line of every Git LFS pointer file
+const LFS_POINTER_VERSION: &str = "version https://git-lfs.github.com/spec/v1";
+
+/// Old and new object sizes from LFS pointer diff lines, or `None` when any
+/// changed line isn't part of a pointer (`version`, `oid`, `size`)
+fn lfs_pointer_sizes(content: &str) -> Option<(Option<u64>, Option<u64>)> {
+   let mut saw_version = false;
+   let mut changed = false;
+   let (mut old, mut new) = (None, None);
+
+   for line in content.lines() {
+      let (marker, text) = line.split_at_checked(1)?;
+      if !matches!(marker, "+" | "-" | " ") {
+         return None;
+      }
+      if text == LFS_POINTER_VERSION {
+         saw_version = true;
+      } else if let Some(size) = text.strip_prefix("size ") {
+         let size = size.parse::<u64>().ok()?;
+         match marker {
+            "+" => new = Some(size),
+            "-" => old = Some(size),
+            _ => {
+               old = Some(size);
+               new = Some(size);
+            },
+         }
+      } else if !text.starts_with("oid sha256:") {
+         return None;
+      }
+      changed |= marker != " ";
+   }
+
+   (saw_version && changed).then_some((old, new))
+}
+
+/// Flag a Git LFS pointer change and replace its pointer lines with a note
+/// like `LFS-tracked binary model.onnx changed, 140.0 MiB -> 152.0 MiB`.
+///
+/// Scope analysis reads numstat instead, where the pointer's few changed lines
+/// keep the file in the count at a small weight.
+fn mark_lfs_pointer(file: &mut FileDiff) {
+   use crate::git::format_size;
+
+   if file.kind != ChangeKind::Content {
+      return;
+   }
+   let Some(sizes) = lfs_pointer_sizes(&file.content) else {
+      return;
+   };
+   let name = &file.filename;
+   file.content = match sizes {
+      (Some(old), Some(new)) if old != new => {
+         format!("LFS-tracked binary {name} changed, {} -> {}", format_size(old), format_size(new))
+      },
+      (None, Some(new)) => format!("LFS-tracked binary {name} added ({})", format_size(new)),
+      (Some(old), None) => format!("LFS-tracked binary {name} removed ({})", format_size(old)),
+      _ => format!("LFS-tracked binary {name} changed"),
+   };
+   file.kind = ChangeKind::LfsPointer;
+   file.additions = 0;
+   file.deletions = 0;
+}
+
+/// Explicit prompt lines for deleted and renamed files
+///
+/// Returns e.g. `DELETED FILES: src/legacy/auth.rs (412 lines)` and one
+/// `RENAMED: old -> new` line per rename, or `None` when there are neither.
+pub fn structural_change_context(files: &[FileDiff]) -> Option<String> {
+   use std::fmt::Write;
+
+   let mut out = String::new();
+
+   let deleted: Vec<String> = files
+      .iter()
+      .filter(|f| f.is_deleted_file())
+      .map(|f| format!("{} ({})", f.filename, line_count(f.deleted_line_count())))
+      .collect();
+   if !deleted.is_empty() {
+      writeln!(out, "DELETED FILES: {}", deleted.join(", ")).unwrap();
+   }
+
+   for file in files {
+      if let Some(old) = file.renamed_from() {
+         writeln!(out, "RENAMED: {old} -> {}", file.filename).unwrap();
+      }
+   }
+
+   (!out.is_empty()).then(|| out.trim_end().to_string())
+}
+
+/// Top-level directory of a path (`.` for files at the repository root)
+fn top_level_dir(path: &str) -> &str {
+   path.split_once('/').map_or(".", |(dir, _)| dir)
+}
+
+/// Pick at most `max` files to stand in for a commit too large to analyze in
+/// full: the largest file (by churn) of each top-level directory, then the
+/// largest remaining files overall.
+///
+/// Deterministic: ties keep diff order, and the result is in diff order.
+/// When there are more directories than `max`, the directories with the most
+/// churn win.
+pub fn sample_representative_files(files: &[FileDiff], max: usize) -> Vec<FileDiff> {
+   use std::collections::HashMap;
+
+   if max == 0 || files.len() <= max {
+      return files.to_vec();
+   }
+
+   let churn = |idx: usize| files[idx].additions + files[idx].deletions;
+
+   // Largest file per directory, and each directory's total churn
+   let mut dirs: Vec<(&str, usize, usize)> = Vec::new(); // (dir, best idx, total churn)
+   let mut dir_slot: HashMap<&str, usize> = HashMap::new();
+   for (idx, file) in files.iter().enumerate() {
+      let dir = top_level_dir(&file.filename);
+      if let Some(&slot) = dir_slot.get(dir) {
+         let entry = &mut dirs[slot];
+         if churn(idx) > churn(entry.1) {
+            entry.1 = idx;
+         }
+         entry.2 += churn(idx);
+      } else {
+         dir_slot.insert(dir, dirs.len());
+         dirs.push((dir, idx, churn(idx)));
+      }
+   }
+   dirs.sort_by_key(|&(_, _, total)| std::cmp::Reverse(total));
+
+   let mut selected = vec![false; files.len()];
+   for &(_, idx, _) in dirs.iter().take(max) {
+      selected[idx] = true;
+   }
+
+   let mut by_churn: Vec<usize> = (0..files.len()).collect();
+   by_churn.sort_by_key(|&idx| std::cmp::Reverse(churn(idx)));
+   let mut remaining = max.saturating_sub(dirs.len());
+   for idx in by_churn {
+      if remaining == 0 {
+         break;
+      }
+      if !selected[idx] {
+         selected[idx] = true;
+         remaining -= 1;
+      }
+   }
+
+   files
+      .iter()
+      .zip(selected)
+      .filter(|&(_, keep)| keep)
+      .map(|(file, _)| file.clone())
+      .collect()
+}
+
+/// Prompt note telling the model it only sees a sample, listing the files
+pub fn sample_note(total_files: usize, sampled: &[FileDiff]) -> String {
+   use std::fmt::Write;
+
+   let mut note = format!(
+      "Commit touches {total_files} files; analyzed a representative sample of {} (largest by \
+       churn plus at least one per top-level directory). Describe the change broadly.\nSampled \
+       files:",
+      sampled.len()
+   );
+   for file in sampled {
+      write!(note, "\n- {}", file.filename).unwrap();
+   }
+   note
+}
+
 /// Smart truncation of git diff with token-aware budgeting
 pub fn smart_truncate_diff(
    diff: &str,
@@ -204,12 +575,7 @@ pub fn smart_truncate_diff(
    let mut file_diffs = parse_diff(diff);
 
    // Filter out excluded files
-   file_diffs.retain(|f| {
-      !config
-         .excluded_files
-         .iter()
-         .any(|excluded| f.filename.ends_with(excluded))
-   });
+   file_diffs.retain(|f| !config.is_excluded_file(&f.filename));
 
    if file_diffs.is_empty() {
       return "No relevant files to analyze (only lock files or excluded files were changed)"
@@ -219,9 +585,11 @@ pub fn smart_truncate_diff(
    // Sort by priority (highest first)
    file_diffs.sort_by_key(|f| -f.priority(config));
 
-   // Calculate total size and token estimate
-   let total_size: usize = file_diffs.iter().map(|f| f.size()).sum();
-   let total_tokens: usize = file_diffs.iter().map(|f| f.token_estimate(counter)).sum();
+   // Calculate total size and token estimate; LFS notes are always kept
+   // whole, so they don't count against the budget
+   let budgeted = || file_diffs.iter().filter(|f| !f.is_lfs_pointer());
+   let total_size: usize = budgeted().map(FileDiff::size).sum();
+   let total_tokens: usize = budgeted().map(|f| f.token_estimate(counter)).sum();
 
    // Use token budget if it's more restrictive than character budget
    // Estimate 4 chars per token for the size conversion
@@ -257,16 +625,11 @@ pub fn smart_truncate_diff(
 
       included_files.reserve(file_diffs.len());
       for file in file_diffs {
-         if file.is_binary {
+         if file.is_binary() {
             // Include binary files with just header
-            included_files.push(FileDiff {
-               filename:  file.filename,
-               header:    file.header,
-               content:   String::new(),
-               additions: file.additions,
-               deletions: file.deletions,
-               is_binary: true,
-            });
+            included_files.push(FileDiff { content: String::new(), ..file });
+         } else if file.is_lfs_pointer() {
+            included_files.push(file);
          } else {
             let mut truncated = file;
             let target_size = truncated.header.len() + space_per_file;
@@ -279,7 +642,7 @@ pub fn smart_truncate_diff(
    } else {
       // Even headers don't fit, fall back to including top priority files
       for mut file in file_diffs {
-         if file.is_binary {
+         if file.is_binary() {
             continue; // Skip binary files when severely constrained
          }
 
@@ -314,6 +677,136 @@ pub fn smart_truncate_diff(
    result
 }
 
+/// Marker that excludes a hunk from analysis when it appears on a changed line,
+/// e.g. `dbg!(x); // llm-git:ignore`
+pub const IGNORE_HUNK_MARKER: &str = "llm-git:ignore";
+
+/// Drop hunks whose added or removed lines carry [`IGNORE_HUNK_MARKER`]
+///
+/// Only the analyzed diff changes; the hunks stay staged and are committed.
+/// A file whose hunks are all dropped is removed entirely.
+pub fn drop_ignored_hunks(diff: &str) -> String {
+   if !diff.contains(IGNORE_HUNK_MARKER) {
+      return diff.to_string();
+   }
+
+   let is_marked = |hunk: &[&str]| {
+      hunk[1..].iter().any(|line| {
+         (line.starts_with('+') || line.starts_with('-')) && line.contains(IGNORE_HUNK_MARKER)
+      })
+   };
+
+   let mut result = String::with_capacity(diff.len());
+   let mut header: Vec<&str> = Vec::new();
+   let mut hunks: Vec<Vec<&str>> = Vec::new();
+
+   let flush_file = |header: &mut Vec<&str>, hunks: &mut Vec<Vec<&str>>, result: &mut String| {
+      let had_hunks = !hunks.is_empty();
+      hunks.retain(|hunk| !is_marked(hunk));
+      if !had_hunks || !hunks.is_empty() {
+         for line in header.iter().chain(hunks.iter().flatten()) {
+            result.push_str(line);
+            result.push('\n');
+         }
+      }
+      header.clear();
+      hunks.clear();
+   };
+
+   for line in diff.lines() {
+      if line.starts_with("diff --git ") {
+         flush_file(&mut header, &mut hunks, &mut result);
+         header.push(line);
+      } else if line.starts_with("@@") {
+         hunks.push(vec![line]);
+      } else if let Some(hunk) = hunks.last_mut() {
+         hunk.push(line);
+      } else {
+         header.push(line);
+      }
+   }
+   flush_file(&mut header, &mut hunks, &mut result);
+
+   if !diff.ends_with('\n') {
+      result.pop();
+   }
+   result
+}
+
+/// `1 line`, `2 lines`
+fn line_count(count: usize) -> String {
+   format!("{count} {}", if count == 1 { "line" } else { "lines" })
+}
+
+/// Elide the middle of very long hunks
+///
+/// Hunks with more than `config.max_hunk_lines` body lines keep their `@@`
+/// header plus the first and last `config.hunk_keep_lines` lines; the middle
+/// is replaced by a marker carrying the elided `+`/`-` counts so the change
+/// stays classifiable. A keep count too large for the threshold is clamped to
+/// `(max_hunk_lines - 1) / 2`, so a long hunk always loses at least one line.
+/// A threshold of 0 disables elision. Line endings are normalized to LF either
+/// way.
+pub fn elide_long_hunks(diff: &str, config: &CommitConfig) -> String {
+   let diff = normalize_line_endings(diff);
+   let diff = diff.as_ref();
+   let threshold = config.max_hunk_lines;
+   if threshold == 0 {
+      return diff.to_string();
+   }
+   let keep = config.hunk_keep_lines.min((threshold - 1) / 2);
+
+   let mut result = String::with_capacity(diff.len());
+   let mut hunk: Vec<&str> = Vec::new();
+   let mut in_hunk = false;
+
+   let flush = |hunk: &mut Vec<&str>, result: &mut String| {
+      if hunk.len() > threshold {
+         let elided = &hunk[keep..hunk.len() - keep];
+         let added = elided.iter().filter(|l| l.starts_with('+')).count();
+         let removed = elided.iter().filter(|l| l.starts_with('-')).count();
+         for line in &hunk[..keep] {
+            result.push_str(line);
+            result.push('\n');
+         }
+         use std::fmt::Write;
+         writeln!(result, "... {} elided (+{added} -{removed}) ...", line_count(elided.len()))
+            .unwrap();
+         for line in &hunk[hunk.len() - keep..] {
+            result.push_str(line);
+            result.push('\n');
+         }
+      } else {
+         for line in hunk.iter() {
+            result.push_str(line);
+            result.push('\n');
+         }
+      }
+      hunk.clear();
+   };
+
+   for line in diff.lines() {
+      let is_boundary = line.starts_with("@@") || line.starts_with("diff --git ");
+      if is_boundary {
+         flush(&mut hunk, &mut result);
+         in_hunk = line.starts_with("@@");
+         result.push_str(line);
+         result.push('\n');
+      } else if in_hunk {
+         hunk.push(line);
+      } else {
+         result.push_str(line);
+         result.push('\n');
+      }
+   }
+   flush(&mut hunk, &mut result);
+
+   if !diff.ends_with('\n') {
+      result.pop();
+   }
+   result
+}
+
 /// Reconstruct a diff from `FileDiff` objects
 pub fn reconstruct_diff(files: &[FileDiff]) -> String {
    // Pre-allocate capacity based on file sizes
@@ -324,10 +817,10 @@ pub fn reconstruct_diff(files: &[FileDiff]) -> String {
       if i > 0 {
          result.push('\n');
       }
-      result.push_str(&file.header);
+      result.push_str(&normalize_line_endings(&file.header));
       if !file.content.is_empty() {
          result.push('\n');
-         result.push_str(&file.content);
+         result.push_str(&normalize_line_endings(&file.content));
       }
    }
 
@@ -336,8 +829,113 @@ pub fn reconstruct_diff(files: &[FileDiff]) -> String {
 
 #[cfg(test)]
 mod tests {
+   use std::fmt::Write;
+
    use super::*;
 
+   fn file(filename: &str, additions: usize) -> FileDiff {
+      FileDiff {
+         filename: filename.to_string(),
+         header: String::new(),
+         content: String::new(),
+         additions,
+         deletions: 0,
+         kind: ChangeKind::Content,
+      }
+   }
+
+   #[test]
+   fn test_drop_ignored_hunks() {
+      let diff = "diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,4 @@
+ fn parse() {
++    validate_input();
+ }
+@@ -10,3 +11,4 @@
+ fn run() {
++    dbg!(state); // llm-git:ignore
+ }
+diff --git a/src/debug.rs b/src/debug.rs
+--- a/src/debug.rs
++++ b/src/debug.rs
+@@ -1,2 +1,3 @@
+ fn trace() {
++    eprintln!(\"here\"); // llm-git:ignore
+";
+      let filtered = drop_ignored_hunks(diff);
+
+      assert!(filtered.contains("validate_input();"));
+      assert!(!filtered.contains("dbg!(state)"));
+      assert!(!filtered.contains("@@ -10,3 +11,4 @@"));
+      // Every hunk of src/debug.rs was marked, so the file is gone
+      assert!(!filtered.contains("src/debug.rs"));
+      assert_eq!(parse_diff(&filtered).len(), 1);
+
+      // A marker on a context line doesn't count
+      let context_only = "diff --git a/a.rs b/a.rs
+--- a/a.rs
++++ b/a.rs
+@@ -1,2 +1,3 @@
+ // llm-git:ignore
++let x = 1;
+";
+      assert_eq!(drop_ignored_hunks(context_only), context_only);
+   }
+
+   fn names(files: &[FileDiff]) -> Vec<&str> {
+      files.iter().map(|f| f.filename.as_str()).collect()
+   }
+
+   #[test]
+   fn test_sample_representative_files_is_deterministic() {
+      let files: Vec<FileDiff> = (0..50)
+         .map(|i| file(&format!("vendor/pkg{i}/lib.rs"), i % 5))
+         .collect();
+
+      let first = sample_representative_files(&files, 10);
+      let second = sample_representative_files(&files, 10);
+      assert_eq!(names(&first), names(&second));
+      assert_eq!(first.len(), 10);
+      // Ties on churn keep diff order: the first ten files with churn 4
+      assert_eq!(names(&first)[..2], ["vendor/pkg4/lib.rs", "vendor/pkg9/lib.rs"]);
+      assert!(first.iter().all(|f| f.additions == 4));
+   }
+
+   #[test]
+   fn test_sample_representative_files_covers_each_directory() {
+      let mut files: Vec<FileDiff> = (0..20)
+         .map(|i| file(&format!("vendor/crate{i}.rs"), 1000 + i))
+         .collect();
+      files.push(file("src/main.rs", 3));
+      files.push(file("docs/guide.md", 1));
+      files.push(file("docs/small.md", 0));
+      files.push(file("Cargo.toml", 2));
+
+      let sample = sample_representative_files(&files, 5);
+      let sampled = names(&sample);
+      assert_eq!(sampled, vec![
+         "vendor/crate18.rs",
+         "vendor/crate19.rs",
+         "src/main.rs",
+         "docs/guide.md",
+         "Cargo.toml"
+      ]);
+
+      // Under the limit nothing is dropped
+      assert_eq!(sample_representative_files(&files, 100).len(), files.len());
+      assert_eq!(sample_representative_files(&files, 0).len(), files.len());
+   }
+
+   #[test]
+   fn test_sample_note_lists_selection() {
+      let sample = vec![file("src/a.rs", 1), file("vendor/b.rs", 2)];
+      let note = sample_note(5000, &sample);
+      assert!(note.starts_with("Commit touches 5000 files; analyzed a representative sample of 2"));
+      assert!(note.ends_with("Sampled files:\n- src/a.rs\n- vendor/b.rs"));
+   }
+
    fn test_config() -> CommitConfig {
       CommitConfig::default()
    }
@@ -362,7 +960,7 @@ index 123..456 100644
       assert_eq!(files[0].filename, "src/main.rs");
       assert_eq!(files[0].additions, 1);
       assert_eq!(files[0].deletions, 0);
-      assert!(!files[0].is_binary);
+      assert!(!files[0].is_binary());
       assert!(files[0].header.contains("diff --git"));
       assert!(files[0].content.contains("use std::collections::HashMap"));
    }
@@ -419,10 +1017,81 @@ Binary files a/image.png and b/image.png differ";
       let files = parse_diff(diff);
       assert_eq!(files.len(), 1);
       assert_eq!(files[0].filename, "image.png");
-      assert!(files[0].is_binary);
+      assert!(files[0].is_binary());
       assert!(files[0].header.contains("Binary files"));
    }
 
+   const LFS_DIFF: &str = r"diff --git a/models/model.onnx b/models/model.onnx
+index 1111111..2222222 100644
+--- a/models/model.onnx
++++ b/models/model.onnx
+@@ -1,3 +1,3 @@
+ version https://git-lfs.github.com/spec/v1
+-oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393
+-size 146800640
++oid sha256:8f434346648f6b96df89dda901c5176b10a6d83961dd3c1ac88b59b2dc327aa4
++size 159383552
+diff --git a/assets/logo.bin b/assets/logo.bin
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/assets/logo.bin
+@@ -0,0 +1,3 @@
++version https://git-lfs.github.com/spec/v1
++oid sha256:b5bb9d8014a0f9b1d61e21e796d78dccdf1352f23cd32812f4850b878ae4944c
++size 2048
+diff --git a/src/config.rs b/src/config.rs
+--- a/src/config.rs
++++ b/src/config.rs
+@@ -1,2 +1,2 @@
+ version https://git-lfs.github.com/spec/v1
+-size 10
++size = 20
+";
+
+   #[test]
+   fn test_parse_diff_lfs_pointer() {
+      let files = parse_diff(LFS_DIFF);
+      assert_eq!(files.len(), 3);
+
+      assert!(files[0].is_lfs_pointer());
+      assert_eq!(
+         files[0].content,
+         "LFS-tracked binary models/model.onnx changed, 140.0 MiB -> 152.0 MiB"
+      );
+      assert_eq!(files[0].additions + files[0].deletions, 0);
+      assert_eq!(files[0].lfs_observation(), Some(files[0].content.as_str()));
+
+      assert!(files[1].is_lfs_pointer());
+      assert_eq!(files[1].content, "LFS-tracked binary assets/logo.bin added (2.0 KiB)");
+      assert!(files[1].deletion_observation().is_none());
+
+      // Lines that only resemble a pointer are left alone
+      assert!(!files[2].is_lfs_pointer());
+      assert!(files[2].content.contains("+size = 20"));
+      assert!(files[2].lfs_observation().is_none());
+   }
+
+   #[test]
+   fn test_smart_truncate_diff_keeps_lfs_notes_outside_budget() {
+      let config = test_config();
+      let counter = test_counter();
+      let result = smart_truncate_diff(LFS_DIFF, 10000, &config, &counter);
+      assert!(result.contains("LFS-tracked binary models/model.onnx changed"));
+      assert!(!result.contains("oid sha256:"));
+
+      let big = format!(
+         "{LFS_DIFF}diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ \
+          -1,1 +1,200 @@\n{}",
+         "+let value = compute_something_long();\n".repeat(200)
+      );
+      let result = smart_truncate_diff(&big, 2000, &config, &counter);
+      assert!(
+         result.contains("LFS-tracked binary models/model.onnx changed, 140.0 MiB -> 152.0 MiB")
+      );
+      assert!(result.contains("LFS-tracked binary assets/logo.bin added (2.0 KiB)"));
+   }
+
    #[test]
    fn test_parse_diff_empty() {
       let diff = "";
@@ -476,6 +1145,143 @@ index 123..000 100644
       assert_eq!(files[0].deletions, 2);
    }
 
+   #[test]
+   fn test_deletion_observation_uses_hunk_line_count() {
+      let diff = r"diff --git a/src/legacy/auth.rs b/src/legacy/auth.rs
+deleted file mode 100644
+index 123..000 100644
+--- a/src/legacy/auth.rs
++++ /dev/null
+@@ -1,412 +0,0 @@
+-pub fn login() {}
+-... (truncated)";
+      let files = parse_diff(diff);
+      assert!(files[0].is_deleted_file());
+      assert_eq!(files[0].deleted_line_count(), 412);
+      assert_eq!(
+         files[0].deletion_observation().as_deref(),
+         Some("Deleted src/legacy/auth.rs (412 lines).")
+      );
+   }
+
+   #[test]
+   fn test_deletion_observation_skips_modified_files() {
+      let diff = r"diff --git a/src/lib.rs b/src/lib.rs
+index 123..456 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1 @@
+-fn old() {}
+ fn keep() {}";
+      let files = parse_diff(diff);
+      assert!(!files[0].is_deleted_file());
+      assert!(files[0].deletion_observation().is_none());
+   }
+
+   #[test]
+   fn test_structural_change_context() {
+      let diff = r"diff --git a/src/legacy/auth.rs b/src/legacy/auth.rs
+deleted file mode 100644
+index 123..000 100644
+--- a/src/legacy/auth.rs
++++ /dev/null
+@@ -1,3 +0,0 @@
+-a
+-b
+-c
+diff --git a/docs/old.md b/docs/old.md
+deleted file mode 100644
+index 123..000 100644
+--- a/docs/old.md
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+diff --git a/src/io.rs b/src/io/mod.rs
+similarity index 100%
+rename from src/io.rs
+rename to src/io/mod.rs
+diff --git a/src/lib.rs b/src/lib.rs
+index 123..456 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1 @@
+-mod io;
++pub mod io;";
+      let context = structural_change_context(&parse_diff(diff)).unwrap();
+      assert_eq!(
+         context,
+         "DELETED FILES: src/legacy/auth.rs (3 lines), docs/old.md (1 line)\nRENAMED: src/io.rs \
+          -> src/io/mod.rs"
+      );
+
+      let modified_only = r"diff --git a/src/lib.rs b/src/lib.rs
+@@ -1 +1 @@
+-a
++b";
+      assert!(structural_change_context(&parse_diff(modified_only)).is_none());
+   }
+
+   #[test]
+   fn test_normalize_line_endings() {
+      assert!(matches!(normalize_line_endings("a\nb\n"), Cow::Borrowed(_)));
+      assert_eq!(normalize_line_endings("a\r\nb\nc\r\r\n"), "a\nb\nc\n");
+      assert_eq!(normalize_line_endings("a\r\nb\r"), "a\nb");
+      // A CR inside a line is content, not a line ending
+      assert_eq!(normalize_line_endings("a\rb\r\n"), "a\rb\n");
+   }
+
+   #[test]
+   fn test_parse_diff_crlf() {
+      let diff = "diff --git a/src/old.rs b/src/old.rs\r\ndeleted file mode 100644\r\nindex \
+                  123..000 100644\r\n--- a/src/old.rs\r\n+++ /dev/null\r\n@@ -1,2 +0,0 @@\r\n-fn \
+                  a() {}\r\n-fn b() {}\r\ndiff --git a/src/io.rs b/src/io/mod.rs\nsimilarity \
+                  index 90%\r\nrename from src/io.rs\r\nrename to src/io/mod.rs\r\n--- \
+                  a/src/io.rs\r\n+++ b/src/io/mod.rs\r\n@@ -1 +1 @@\r\n-old\r\r\n+new\r\n";
+      let files = parse_diff(diff);
+      assert_eq!(files.len(), 2);
+
+      assert_eq!(files[0].filename, "src/old.rs");
+      assert!(files[0].is_deleted_file());
+      assert_eq!(files[0].deletions, 2);
+      assert_eq!(files[0].deletion_observation().as_deref(), Some("Deleted src/old.rs (2 lines)."));
+      assert_eq!(line_count(1), "1 line");
+
+      assert_eq!(files[1].filename, "src/io/mod.rs");
+      assert_eq!(files[1].renamed_from(), Some("src/io.rs"));
+      assert_eq!((files[1].additions, files[1].deletions), (1, 1));
+      assert_eq!(files[1].content, "-old\n+new");
+
+      let rebuilt = reconstruct_diff(&files);
+      assert!(!rebuilt.contains('\r'));
+      assert!(rebuilt.contains("@@ -1 +1 @@\n-old\n+new"));
+   }
+
+   #[test]
+   fn test_reconstruct_diff_strips_crlf_from_handbuilt_files() {
+      let file = FileDiff {
+         filename:  "a.rs".to_string(),
+         header:    "diff --git a/a.rs b/a.rs\r\n@@ -1 +1 @@".to_string(),
+         content:   "-a\r\n+b\r".to_string(),
+         additions: 1,
+         deletions: 1,
+         kind:      ChangeKind::Content,
+      };
+      assert_eq!(reconstruct_diff(&[file]), "diff --git a/a.rs b/a.rs\n@@ -1 +1 @@\n-a\n+b");
+   }
+
+   #[test]
+   fn test_elide_long_hunks_crlf() {
+      let config = CommitConfig { max_hunk_lines: 10, hunk_keep_lines: 2, ..Default::default() };
+      let mut diff = String::from("diff --git a/a b/a\r\n@@ -1 +1,20 @@\r\n");
+      for i in 0..20 {
+         write!(diff, "+line {i}\r\n").unwrap();
+      }
+      let elided = elide_long_hunks(&diff, &config);
+      assert!(!elided.contains('\r'));
+      assert!(elided.contains("... 16 lines elided (+16 -0) ..."));
+      assert!(elided.ends_with("+line 19\n"));
+   }
+
    #[test]
    fn test_file_diff_size() {
       let file = FileDiff {
@@ -484,7 +1290,7 @@ index 123..000 100644
          content:   "content".to_string(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       assert_eq!(file.size(), 6 + 7); // "header" + "content"
    }
@@ -498,7 +1304,7 @@ index 123..000 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       assert_eq!(rs_file.priority(&config), 100);
 
@@ -508,7 +1314,7 @@ index 123..000 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       assert_eq!(py_file.priority(&config), 100);
 
@@ -518,7 +1324,7 @@ index 123..000 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       assert_eq!(js_file.priority(&config), 100);
    }
@@ -532,7 +1338,7 @@ index 123..000 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: true,
+         kind:      ChangeKind::Binary,
       };
       assert_eq!(binary.priority(&config), -100);
    }
@@ -546,7 +1352,7 @@ index 123..000 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       assert_eq!(test_file.priority(&config), 10);
 
@@ -556,7 +1362,7 @@ index 123..000 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       assert_eq!(test_dir.priority(&config), 10);
    }
@@ -570,7 +1376,7 @@ index 123..000 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       assert_eq!(md_file.priority(&config), 20);
 
@@ -580,7 +1386,7 @@ index 123..000 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       assert_eq!(toml_file.priority(&config), 20);
    }
@@ -595,7 +1401,7 @@ index 123..000 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       assert_eq!(cargo_toml.priority(&config), 70);
 
@@ -605,7 +1411,7 @@ index 123..000 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       assert_eq!(package_json.priority(&config), 70);
 
@@ -615,7 +1421,7 @@ index 123..000 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       assert_eq!(go_mod.priority(&config), 70);
    }
@@ -629,7 +1435,7 @@ index 123..000 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       assert_eq!(other.priority(&config), 50);
    }
@@ -642,7 +1448,7 @@ index 123..000 100644
          content:   "short content".to_string(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       let original_size = file.size();
       file.truncate(1000);
@@ -660,7 +1466,7 @@ index 123..000 100644
          content,
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind: ChangeKind::Content,
       };
       file.truncate(500);
       assert!(file.content.contains("... (truncated"));
@@ -679,7 +1485,7 @@ index 123..000 100644
          content,
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind: ChangeKind::Content,
       };
       // Use a size that will definitely trigger truncation
       file.truncate(300);
@@ -702,7 +1508,7 @@ index 123..000 100644
          content:   "lots of content that needs to be truncated".to_string(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       };
       file.truncate(30);
       assert_eq!(file.content, "... (truncated)");
@@ -846,7 +1652,7 @@ index 123..456 100644
          content:   "+new line".to_string(),
          additions: 1,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       }];
       let result = reconstruct_diff(&files);
       assert_eq!(result, "diff --git a/test.rs b/test.rs\n+new line");
@@ -861,7 +1667,7 @@ index 123..456 100644
             content:   "+line a".to_string(),
             additions: 1,
             deletions: 0,
-            is_binary: false,
+            kind:      ChangeKind::Content,
          },
          FileDiff {
             filename:  "b.rs".to_string(),
@@ -869,7 +1675,7 @@ index 123..456 100644
             content:   "+line b".to_string(),
             additions: 1,
             deletions: 0,
-            is_binary: false,
+            kind:      ChangeKind::Content,
          },
       ];
       let result = reconstruct_diff(&files);
@@ -887,16 +1693,224 @@ index 123..456 100644
          content:   String::new(),
          additions: 0,
          deletions: 0,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       }];
       let result = reconstruct_diff(&files);
       assert_eq!(result, "diff --git a/test.rs b/test.rs");
    }
 
+   const MODE_ONLY_DIFF: &str = r"diff --git a/scripts/deploy.sh b/scripts/deploy.sh
+old mode 100644
+new mode 100755";
+
+   const SYMLINK_DIFF: &str = r"diff --git a/current b/current
+index 1111111..2222222 120000
+--- a/current
++++ b/current
+@@ -1 +1 @@
+-releases/v1
+\ No newline at end of file
++releases/v2
+\ No newline at end of file
+diff --git a/latest b/latest
+new file mode 120000
+index 0000000..3333333
+--- /dev/null
++++ b/latest
+@@ -0,0 +1 @@
++releases/v2
+\ No newline at end of file";
+
+   const COMBINED_DIFF: &str = r"diff --git a/bin/run b/bin/run
+old mode 100644
+new mode 100755
+index 1111111..2222222
+--- a/bin/run
++++ b/bin/run
+@@ -1,2 +1,2 @@
+ #!/bin/sh
+-exec ./app
++exec ./app --release
+diff --git a/tools/lint b/tools/lint
+old mode 100755
+new mode 100644
+diff --git a/docs/current b/docs/current
+deleted file mode 120000
+index 4444444..0000000
+--- a/docs/current
++++ /dev/null
+@@ -1 +0,0 @@
+-../site
+\ No newline at end of file";
+
+   #[test]
+   fn test_parse_diff_mode_only() {
+      let files = parse_diff(MODE_ONLY_DIFF);
+      assert_eq!(files.len(), 1);
+      assert_eq!(files[0].kind, ChangeKind::ModeOnly {
+         old: "100644".to_string(),
+         new: "100755".to_string(),
+      });
+      assert_eq!(
+         files[0].kind_observation().as_deref(),
+         Some("Changed file mode of scripts/deploy.sh to 100755.")
+      );
+      assert_eq!(reconstruct_diff(&files), MODE_ONLY_DIFF);
+
+      let mode_only = mode_only_files(MODE_ONLY_DIFF).unwrap();
+      assert_eq!(mode_only[0].filename, "scripts/deploy.sh");
+      assert!(mode_only_files(&format!("{MODE_ONLY_DIFF}\n{SYMLINK_DIFF}")).is_none());
+      assert!(mode_only_files("").is_none());
+   }
+
+   #[test]
+   fn test_parse_diff_symlink() {
+      let files = parse_diff(SYMLINK_DIFF);
+      assert_eq!(files[0].kind, ChangeKind::SymlinkRetarget {
+         old: Some("releases/v1".to_string()),
+         new: Some("releases/v2".to_string()),
+      });
+      assert_eq!((files[0].additions, files[0].deletions), (1, 1));
+      assert_eq!(files[1].kind, ChangeKind::SymlinkRetarget {
+         old: None,
+         new: Some("releases/v2".to_string()),
+      });
+      assert_eq!(
+         files[1].kind_observation().as_deref(),
+         Some("Added symlink latest pointing to releases/v2.")
+      );
+      assert_eq!(reconstruct_diff(&files), SYMLINK_DIFF);
+   }
+
+   #[test]
+   fn test_parse_diff_combined_mode_and_content() {
+      let files = parse_diff(COMBINED_DIFF);
+      assert_eq!(files.len(), 3);
+
+      // A mode change alongside hunks stays a content change
+      assert_eq!(files[0].kind, ChangeKind::Content);
+      assert_eq!((files[0].additions, files[0].deletions), (1, 1));
+      assert!(files[0].kind_observation().is_none());
+      assert!(matches!(files[1].kind, ChangeKind::ModeOnly { .. }));
+      assert_eq!(files[2].kind, ChangeKind::SymlinkRetarget {
+         old: Some("../site".to_string()),
+         new: None,
+      });
+
+      assert_eq!(reconstruct_diff(&files), COMBINED_DIFF);
+      // Round-trips again through parse
+      let reparsed = parse_diff(&reconstruct_diff(&files));
+      let kinds = |files: &[FileDiff]| files.iter().map(|f| f.kind.clone()).collect::<Vec<_>>();
+      assert_eq!(kinds(&reparsed), kinds(&files));
+   }
+
    #[test]
    fn test_reconstruct_diff_empty_vec() {
       let files: Vec<FileDiff> = vec![];
       let result = reconstruct_diff(&files);
       assert_eq!(result, "");
    }
+
+   #[test]
+   fn test_elide_long_hunks_giant_hunk() {
+      let config = CommitConfig { max_hunk_lines: 100, hunk_keep_lines: 10, ..Default::default() };
+      let mut diff = String::from(
+         "diff --git a/big.rs b/big.rs\nindex 123..456 100644\n--- a/big.rs\n+++ b/big.rs\n@@ \
+          -1,0 +1,500 @@\n",
+      );
+      for i in 0..500 {
+         writeln!(diff, "+line {i}").unwrap();
+      }
+
+      let result = elide_long_hunks(&diff, &config);
+      assert!(result.contains("@@ -1,0 +1,500 @@"));
+      assert!(result.contains("+line 0\n"));
+      assert!(result.contains("+line 9\n"));
+      assert!(!result.contains("+line 10\n"));
+      assert!(!result.contains("+line 489\n"));
+      assert!(result.contains("+line 490\n"));
+      assert!(result.contains("+line 499\n"));
+      assert!(result.contains("... 480 lines elided (+480 -0) ..."));
+      assert_eq!(parse_diff(&result).len(), 1);
+   }
+
+   #[test]
+   fn test_elide_long_hunks_clamps_keep_to_threshold() {
+      // Keeping 40 lines at each end would leave nothing of a 12-line hunk
+      let config = CommitConfig { max_hunk_lines: 10, hunk_keep_lines: 40, ..Default::default() };
+      let mut diff = String::from("diff --git a/a b/a\n@@ -1 +1,12 @@\n");
+      for i in 0..12 {
+         writeln!(diff, "+line {i}").unwrap();
+      }
+
+      let result = elide_long_hunks(&diff, &config);
+      assert!(result.contains("+line 3\n"));
+      assert!(!result.contains("+line 4\n"));
+      assert!(result.contains("... 4 lines elided (+4 -0) ..."));
+      assert!(!result.contains("+line 7\n"));
+      assert!(result.contains("+line 8\n"));
+   }
+
+   #[test]
+   fn test_elide_long_hunks_small_hunk_untouched() {
+      let config = CommitConfig { max_hunk_lines: 100, hunk_keep_lines: 10, ..Default::default() };
+      let diff = "diff --git a/a.rs b/a.rs
+index 123..456 100644
+--- a/a.rs
++++ b/a.rs
+@@ -1,3 +1,4 @@
+ fn main() {
+-    old();
++    new();
++    more();
+ }
+@@ -20,2 +21,2 @@
+-a
++b";
+      assert_eq!(elide_long_hunks(diff, &config), diff);
+   }
+
+   #[test]
+   fn test_elide_long_hunks_disabled() {
+      let config = CommitConfig { max_hunk_lines: 0, ..Default::default() };
+      let mut diff = String::from("diff --git a/a b/a\n@@ -1 +1,300 @@\n");
+      for i in 0..300 {
+         writeln!(diff, "+{i}").unwrap();
+      }
+      assert_eq!(elide_long_hunks(&diff, &config), diff);
+   }
+
+   #[test]
+   fn test_parse_diff_rename_only() {
+      let diff = "diff --git a/src/old.rs b/src/new.rs
+similarity index 100%
+rename from src/old.rs
+rename to src/new.rs";
+      let files = parse_diff(diff);
+      assert_eq!(files.len(), 1);
+      assert_eq!(files[0].filename, "src/new.rs");
+      assert_eq!(files[0].renamed_from(), Some("src/old.rs"));
+      assert!(files[0].is_rename_only());
+      assert_eq!(
+         files[0].rename_observation().as_deref(),
+         Some("Renamed src/old.rs to src/new.rs.")
+      );
+   }
+
+   #[test]
+   fn test_parse_diff_rename_with_edits_not_rename_only() {
+      let diff = "diff --git a/src/old.rs b/src/new.rs
+similarity index 90%
+rename from src/old.rs
+rename to src/new.rs
+index abc..def 100644
+--- a/src/old.rs
++++ b/src/new.rs
+@@ -1 +1 @@
+-fn old() {}
++fn new() {}";
+      let files = parse_diff(diff);
+      assert!(!files[0].is_rename_only());
+      assert_eq!(files[0].rename_observation(), None);
+   }
 }
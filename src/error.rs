@@ -5,9 +5,51 @@ pub enum CommitGenError {
    #[error("Git command failed: {0}")]
    GitError(String),
 
+   #[error("Git is unavailable: {0}")]
+   GitUnavailable(String),
+
    #[error("API request failed (HTTP {status}): {body}")]
    ApiError { status: u16, body: String },
 
+   #[error(
+      "API returned {kind} instead of JSON (HTTP {status}, content-type: {content_type}); check \
+       api_base_url and any proxy in between: {snippet}"
+   )]
+   NonJsonResponse {
+      status:       u16,
+      content_type: String,
+      kind:         &'static str,
+      /// Start of the body, with HTML tags stripped
+      snippet:      String,
+   },
+
+   #[error("API rate limited (HTTP {status}): {body}")]
+   RateLimited { status: u16, retry_after: Option<std::time::Duration>, body: String },
+
+   #[error(
+      "Network access is disabled (offline_strict / LLM_GIT_NO_NETWORK); refusing to contact {0}"
+   )]
+   NetworkDisabled(String),
+
+   #[error(
+      "Model {model} returned empty tool call arguments; it may not support function calling (try \
+       a Claude model, or add another to summary_model_cascade)"
+   )]
+   ToolCallUnsupported { model: String },
+
+   #[error(
+      "Model {model} ({field}) is not offered by {endpoint}{suggestion}; run `lgit --model-list` \
+       to see the available models"
+   )]
+   ModelNotFound {
+      model:      String,
+      /// Config key the model comes from
+      field:      String,
+      endpoint:   String,
+      /// ` (did you mean ...?)` for a near miss, or empty
+      suggestion: String,
+   },
+
    #[error("API call failed after {retries} retries: {source}")]
    ApiRetryExhausted {
       retries: u32,
@@ -15,9 +57,44 @@ pub enum CommitGenError {
       source:  Box<Self>,
    },
 
+   #[error(
+      "Failed to apply hunks for {file} in compose group {group} (selectors: {selector}): {stderr}"
+   )]
+   HunkApplyFailed {
+      group:    usize,
+      file:     String,
+      selector: String,
+      /// `git apply` stderr, or why the selectors matched no hunks
+      stderr:   String,
+   },
+
    #[error("Validation failed: {0}")]
    ValidationError(String),
 
+   #[error(
+      "Generated message \"{message}\" is too generic for a {diff_chars}-character diff; add \
+       context describing the change or pass --allow-generic"
+   )]
+   VacuousMessage { message: String, diff_chars: usize },
+
+   #[error(
+      "Commit message still contains the template placeholder `{placeholder}`; fill it in or pass \
+       --allow-placeholders"
+   )]
+   TemplatePlaceholder { placeholder: String },
+
+   #[error("Commit declined after validation warnings: {0}")]
+   CommitDeclined(String),
+
+   #[error("Commit blocked by validation warnings (warnings_block_commit = true): {0}")]
+   WarningsBlockCommit(String),
+
+   #[error(
+      "Not committing on protected branch {branch} (protected_branches); pass --allow-protected \
+       or --force to commit anyway"
+   )]
+   ProtectedBranch { branch: String },
+
    #[error("No changes found in {mode} mode")]
    NoChanges { mode: String },
 
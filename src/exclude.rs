@@ -0,0 +1,206 @@
+//! `excluded_files` matching
+//!
+//! Entries are gitignore-style patterns, applied in order so a later `!`
+//! pattern re-includes what an earlier one excluded (`*.json` then
+//! `!schema/*.json`); `dir/` patterns exclude everything under a directory.
+//! Matching is case-insensitive on Windows.
+//!
+//! Plain entries without glob syntax keep their original suffix meaning
+//! (`.json` and `Cargo.lock` match any path ending in them), so older configs
+//! behave as before.
+//!
+//! Files the repository's `.gitattributes` marks as generated are matched
+//! first, so an `excluded_files` negation can still bring one back.
+
+use std::sync::Arc;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use parking_lot::Mutex;
+
+use crate::error::{CommitGenError, Result};
+
+/// Characters that make an entry a pattern rather than a plain suffix
+const PATTERN_CHARS: &[char] = &['*', '?', '[', ']', '{', '}', '\\'];
+
+/// Compiled `excluded_files` patterns
+#[derive(Debug)]
+pub struct ExcludedFiles {
+   matcher: Gitignore,
+}
+
+/// Generated-file lines and `excluded_files` a matcher was built from
+type CacheKey = (Vec<String>, Vec<String>);
+
+/// Matcher for the most recently used pattern lists; configs rarely change
+/// within a run, and exclusion is checked per file
+static CACHE: Mutex<Option<(CacheKey, Arc<ExcludedFiles>)>> = Mutex::new(None);
+
+/// Whether an entry is a plain suffix in the pre-pattern syntax
+fn is_plain(entry: &str) -> bool {
+   !entry.is_empty()
+      && !entry.starts_with(['!', '#'])
+      && !entry.ends_with('/')
+      && !entry.contains(PATTERN_CHARS)
+}
+
+/// Gitignore line for an entry; plain entries become a glob matching paths
+/// that end with them
+fn to_line(entry: &str) -> String {
+   if is_plain(entry) {
+      format!("**/*{entry}")
+   } else {
+      entry.to_string()
+   }
+}
+
+impl ExcludedFiles {
+   fn builder() -> GitignoreBuilder {
+      let mut builder = GitignoreBuilder::new("");
+      builder.case_insensitive(cfg!(windows)).ok();
+      builder
+   }
+
+   /// Compile `patterns`, skipping any that don't parse (see
+   /// [`ExcludedFiles::check`])
+   pub fn new(patterns: &[String]) -> Self {
+      Self::with_generated(&[], patterns)
+   }
+
+   /// Compile gitignore-style `generated` lines (from
+   /// [`generated_file_patterns`](crate::git::generated_file_patterns)),
+   /// followed by `patterns`
+   pub fn with_generated(generated: &[String], patterns: &[String]) -> Self {
+      let mut builder = Self::builder();
+      for line in generated {
+         builder.add_line(None, line).ok();
+      }
+      for entry in patterns {
+         builder.add_line(None, &to_line(entry)).ok();
+      }
+      Self { matcher: builder.build().unwrap_or_else(|_| Gitignore::empty()) }
+   }
+
+   /// Fail on the first pattern that doesn't parse
+   pub fn check(patterns: &[String]) -> Result<()> {
+      let mut builder = Self::builder();
+      for entry in patterns {
+         builder.add_line(None, &to_line(entry)).map_err(|e| {
+            CommitGenError::ValidationError(format!(
+               "Invalid excluded_files pattern `{entry}`: {e}"
+            ))
+         })?;
+      }
+      Ok(())
+   }
+
+   /// Matcher for `generated` and `patterns`, reusing the last one built for
+   /// the same lists
+   pub fn cached(generated: &[String], patterns: &[String]) -> Arc<Self> {
+      let mut cache = CACHE.lock();
+      if let Some(((cached_generated, cached), matcher)) = cache.as_ref()
+         && cached_generated.as_slice() == generated
+         && cached.as_slice() == patterns
+      {
+         return Arc::clone(matcher);
+      }
+      let matcher = Arc::new(Self::with_generated(generated, patterns));
+      *cache = Some(((generated.to_vec(), patterns.to_vec()), Arc::clone(&matcher)));
+      matcher
+   }
+
+   /// Whether `path` (repository-relative) is excluded; the last matching
+   /// pattern wins, and a match on a parent directory counts
+   pub fn is_excluded(&self, path: &str) -> bool {
+      let path = path.trim_start_matches('/');
+      !path.is_empty()
+         && self
+            .matcher
+            .matched_path_or_any_parents(path, false)
+            .is_ignore()
+   }
+}
+
+/// Plain entries that only make sense as suffixes, with a pattern suggestion.
+///
+/// Partial names (`.json`) and paths (`gen/out.rs`) would mean something else
+/// as patterns. Whole file names like `Cargo.lock` mean the same either way
+/// and aren't reported.
+pub fn legacy_entries(patterns: &[String]) -> Vec<(&str, String)> {
+   patterns
+      .iter()
+      .map(String::as_str)
+      .filter(|entry| is_plain(entry) && (entry.starts_with('.') || entry.contains('/')))
+      .map(|entry| {
+         let suggestion = if entry.contains('/') {
+            format!("**/{}", entry.trim_start_matches('/'))
+         } else {
+            format!("*{entry}")
+         };
+         (entry, suggestion)
+      })
+      .collect()
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   fn excluded(patterns: &[&str]) -> ExcludedFiles {
+      let patterns: Vec<String> = patterns.iter().map(|p| (*p).to_string()).collect();
+      ExcludedFiles::new(&patterns)
+   }
+
+   #[test]
+   fn test_negation_reincludes_later_matches() {
+      let ex = excluded(&["*.json", "!schema/*.json"]);
+      assert!(ex.is_excluded("package.json"));
+      assert!(ex.is_excluded("web/data/fixtures.json"));
+      assert!(!ex.is_excluded("schema/config.json"));
+      assert!(!ex.is_excluded("src/main.rs"));
+
+      // Order matters: a later exclusion wins again
+      let ex = excluded(&["*.json", "!schema/*.json", "schema/generated.json"]);
+      assert!(ex.is_excluded("schema/generated.json"));
+      assert!(!ex.is_excluded("schema/config.json"));
+   }
+
+   #[test]
+   fn test_directory_patterns() {
+      let ex = excluded(&["vendor/", "/build/", "!vendor/patches/"]);
+      assert!(ex.is_excluded("vendor/lib.c"));
+      assert!(ex.is_excluded("third_party/vendor/a/b.rs"));
+      assert!(!ex.is_excluded("vendor/patches/fix.diff"));
+      assert!(ex.is_excluded("build/out.o"));
+      assert!(!ex.is_excluded("src/build/mod.rs"));
+      assert!(!ex.is_excluded("vendor.rs"));
+   }
+
+   #[test]
+   fn test_plain_entries_keep_suffix_semantics() {
+      let ex = excluded(&["Cargo.lock", ".min.js", "gen/out.rs"]);
+      assert!(ex.is_excluded("Cargo.lock"));
+      assert!(ex.is_excluded("crates/core/Cargo.lock"));
+      assert!(ex.is_excluded("static/app.min.js"));
+      assert!(!ex.is_excluded("static/app.js"));
+      assert!(ex.is_excluded("gen/out.rs"));
+      assert!(ex.is_excluded("src/gen/out.rs"));
+      assert!(ex.is_excluded("src/regen/out.rs"));
+      assert!(!ex.is_excluded("gen/out.rs.bak"));
+   }
+
+   #[test]
+   fn test_legacy_entries_and_invalid_patterns() {
+      let patterns: Vec<String> = ["Cargo.lock", ".json", "gen/out.rs", "*.snap", "!keep.json"]
+         .iter()
+         .map(|p| (*p).to_string())
+         .collect();
+      assert_eq!(legacy_entries(&patterns), vec![
+         (".json", "*.json".to_string()),
+         ("gen/out.rs", "**/gen/out.rs".to_string()),
+      ]);
+
+      assert!(ExcludedFiles::check(&patterns).is_ok());
+      let err = ExcludedFiles::check(&["*.{rs,toml".to_string()]).unwrap_err();
+      assert!(err.to_string().contains("*.{rs,toml"), "{err}");
+   }
+}
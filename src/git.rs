@@ -1,4 +1,6 @@
-use std::{collections::HashMap, process::Command};
+use std::{collections::HashMap, process::Command, sync::OnceLock};
+
+use indexmap::IndexMap;
 
 pub use self::git_push as push;
 use crate::{
@@ -8,7 +10,308 @@ use crate::{
    types::{CommitMetadata, Mode},
 };
 
+/// Oldest git release whose CLI behavior lgit relies on
+pub const MIN_GIT_VERSION: (u32, u32) = (2, 23);
+
+static GIT_CHECK: OnceLock<std::result::Result<(), String>> = OnceLock::new();
+
+/// Parse `git --version` output into (major, minor, patch)
+///
+/// Accepts vendor suffixes such as `2.39.3 (Apple Git-146)` or
+/// `2.45.1.windows.1`; a missing patch component reads as 0.
+pub fn parse_git_version(output: &str) -> Option<(u32, u32, u32)> {
+   let version = output.trim().strip_prefix("git version ")?;
+   let mut parts = version
+      .split(|c: char| !c.is_ascii_digit())
+      .take_while(|part| !part.is_empty());
+   let major = parts.next()?.parse().ok()?;
+   let minor = parts.next()?.parse().ok()?;
+   let patch = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+   Some((major, minor, patch))
+}
+
+/// Check `git --version` output against [`MIN_GIT_VERSION`]
+fn check_git_version(output: &str) -> std::result::Result<(), String> {
+   let (major, minor, patch) = parse_git_version(output)
+      .ok_or_else(|| format!("could not parse `git --version` output: {}", output.trim()))?;
+   if (major, minor) < MIN_GIT_VERSION {
+      let (min_major, min_minor) = MIN_GIT_VERSION;
+      return Err(format!(
+         "git {major}.{minor}.{patch} is too old; lgit requires git {min_major}.{min_minor} or \
+          newer"
+      ));
+   }
+   Ok(())
+}
+
+/// Verify once per process that `git` is on PATH and recent enough
+///
+/// Turns the cryptic "Failed to run git ..." errors of minimal containers
+/// into a single clear message before any other git operation runs.
+pub fn ensure_git_available() -> Result<()> {
+   GIT_CHECK
+      .get_or_init(|| {
+         let output = Command::new("git").arg("--version").output().map_err(|e| {
+            format!("could not run `git --version` ({e}); is git installed and on PATH?")
+         })?;
+         if !output.status.success() {
+            return Err(format!(
+               "`git --version` failed: {}",
+               String::from_utf8_lossy(&output.stderr).trim()
+            ));
+         }
+         check_git_version(&String::from_utf8_lossy(&output.stdout))
+      })
+      .clone()
+      .map_err(CommitGenError::GitUnavailable)
+}
+
+/// Whether a `--target` names a commit range (`A..B` or `A...B`) rather than
+/// a single commit
+pub fn is_commit_range(target: &str) -> bool {
+   target.contains("..")
+}
+
+/// Get the subjects of every commit in a range, oldest first
+pub fn get_range_subjects(range: &str, dir: &str) -> Result<Vec<String>> {
+   let output = Command::new("git")
+      .args(["log", "--reverse", "--pretty=format:%s", range])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to run git log: {e}")))?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git log failed: {stderr}")));
+   }
+
+   let stdout = String::from_utf8_lossy(&output.stdout);
+   Ok(stdout
+      .lines()
+      .filter(|s| !s.trim().is_empty())
+      .map(|s| s.to_string())
+      .collect())
+}
+
+/// Whether `rev` resolves in the repository at `dir`
+fn rev_exists(dir: &str, rev: &str) -> bool {
+   Command::new("git")
+      .args(["rev-parse", "-q", "--verify", rev])
+      .current_dir(dir)
+      .output()
+      .is_ok_and(|o| o.status.success())
+}
+
+/// Paths the index still holds as unmerged (conflicts not yet `git add`ed)
+pub fn get_unmerged_files(dir: &str) -> Result<Vec<String>> {
+   let output = Command::new("git")
+      .args(["diff", "--name-only", "--diff-filter=U"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to list unmerged files: {e}")))?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git diff --diff-filter=U failed: {stderr}")));
+   }
+
+   Ok(String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .filter(|s| !s.is_empty())
+      .map(|s| s.to_string())
+      .collect())
+}
+
+/// Revision `--mode merge` diffs the index against.
+///
+/// `AUTO_MERGE` (git's own merge result, conflict markers included) when git
+/// recorded one and the resolution differs from it, so the diff shows the
+/// resolution decisions; otherwise `HEAD`. Fails unless a merge is in
+/// progress with every conflict resolved.
+pub fn merge_diff_base(dir: &str) -> Result<&'static str> {
+   if !rev_exists(dir, "MERGE_HEAD") {
+      return Err(CommitGenError::ValidationError(
+         "--mode merge requires a merge in progress (no MERGE_HEAD)".to_string(),
+      ));
+   }
+   let unmerged = get_unmerged_files(dir)?;
+   if !unmerged.is_empty() {
+      return Err(CommitGenError::ValidationError(format!(
+         "Resolve and `git add` the remaining conflicts first: {}",
+         unmerged.join(", ")
+      )));
+   }
+
+   let resolution_differs = rev_exists(dir, "AUTO_MERGE")
+      && Command::new("git")
+         .args(["diff", "--cached", "--quiet", "AUTO_MERGE"])
+         .current_dir(dir)
+         .status()
+         .is_ok_and(|status| status.code() == Some(1));
+   Ok(if resolution_differs {
+      "AUTO_MERGE"
+   } else {
+      "HEAD"
+   })
+}
+
+/// Contents of `MERGE_MSG` in the repository at `dir`, if any
+fn read_merge_msg(dir: &str) -> Option<String> {
+   let output = Command::new("git")
+      .args(["rev-parse", "--git-path", "MERGE_MSG"])
+      .current_dir(dir)
+      .output()
+      .ok()
+      .filter(|o| o.status.success())?;
+   let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+   std::fs::read_to_string(std::path::Path::new(dir).join(path)).ok()
+}
+
+/// Files listed under the `Conflicts:` section git writes to `MERGE_MSG`,
+/// commented (`# Conflicts:` / `#\tpath`) or not
+pub fn parse_merge_msg_conflicts(contents: &str) -> Vec<String> {
+   let uncomment = |line: &str| line.strip_prefix('#').unwrap_or(line).to_string();
+   contents
+      .lines()
+      .map(uncomment)
+      .skip_while(|line| line.trim() != "Conflicts:")
+      .skip(1)
+      .skip_while(|line| line.trim().is_empty())
+      .take_while(|line| line.starts_with('\t'))
+      .map(|line| line.trim().to_string())
+      .filter(|path| !path.is_empty())
+      .collect()
+}
+
+/// Subject git prepared for the merge commit (`Merge branch 'x'`): the first
+/// non-comment line of `MERGE_MSG`
+pub fn parse_merge_msg_subject(contents: &str) -> Option<String> {
+   contents
+      .lines()
+      .map(str::trim)
+      .find(|line| !line.is_empty() && !line.starts_with('#'))
+      .map(|line| line.to_string())
+}
+
+/// Files the in-progress merge left conflicted: those recorded in `MERGE_MSG`,
+/// then any still unmerged
+pub fn get_merge_conflicted_files(dir: &str) -> Result<Vec<String>> {
+   let mut files = read_merge_msg(dir)
+      .map(|contents| parse_merge_msg_conflicts(&contents))
+      .unwrap_or_default();
+   for file in get_unmerged_files(dir)? {
+      if !files.contains(&file) {
+         files.push(file);
+      }
+   }
+   Ok(files)
+}
+
+/// Subject git prepared for the in-progress merge commit, if any
+pub fn get_merge_subject(dir: &str) -> Option<String> {
+   read_merge_msg(dir).and_then(|contents| parse_merge_msg_subject(&contents))
+}
+
+/// Untracked files in `dir` that aren't ignored
+pub fn get_untracked_files(dir: &str) -> Result<Vec<String>> {
+   let output = Command::new("git")
+      .args(["ls-files", "--others", "--exclude-standard"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to list untracked files: {e}")))?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git ls-files failed: {stderr}")));
+   }
+
+   Ok(String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .filter(|line| !line.is_empty())
+      .map(str::to_string)
+      .collect())
+}
+
+/// Diff adding the untracked `path`, as `git diff --cached` would show it.
+///
+/// Binary files, and files over `max_bytes` when set, get a stub with no
+/// content lines; unreadable paths give an empty string.
+pub fn untracked_file_diff(dir: &str, path: &str, max_bytes: Option<u64>) -> String {
+   use std::fmt::Write;
+
+   let full_path = std::path::Path::new(dir).join(path);
+   let Ok(metadata) = std::fs::metadata(&full_path) else {
+      return String::new();
+   };
+   if !metadata.is_file() {
+      return String::new();
+   }
+
+   let mut diff = format!("diff --git a/{path} b/{path}\nnew file mode 100644\n");
+   if let Some(max) = max_bytes
+      && metadata.len() > max
+   {
+      writeln!(diff, "Untracked file of {} not shown", format_size(metadata.len())).unwrap();
+      return diff;
+   }
+   let Ok(bytes) = std::fs::read(&full_path) else {
+      return String::new();
+   };
+   let content = match String::from_utf8(bytes) {
+      Ok(content) if !content.contains('\0') => content,
+      _ => {
+         writeln!(diff, "Binary files /dev/null and b/{path} differ").unwrap();
+         return diff;
+      },
+   };
+   if content.is_empty() {
+      return diff;
+   }
+
+   let lines: Vec<&str> = content.lines().collect();
+   writeln!(diff, "--- /dev/null\n+++ b/{path}\n@@ -0,0 +1,{} @@", lines.len()).unwrap();
+   for line in &lines {
+      writeln!(diff, "+{line}").unwrap();
+   }
+   if !content.ends_with('\n') {
+      diff.push_str("\\ No newline at end of file\n");
+   }
+   diff
+}
+
+/// `--stat`-style lines for untracked `files`, counting each file's lines
+pub fn untracked_stat(dir: &str, files: &[String]) -> String {
+   use std::fmt::Write;
+
+   let mut stat = String::new();
+   for file in files {
+      let full_path = std::path::Path::new(dir).join(file);
+      if let Ok(metadata) = std::fs::metadata(&full_path) {
+         let lines = if metadata.is_file() {
+            std::fs::read_to_string(&full_path)
+               .map(|content| content.lines().count())
+               .unwrap_or(0)
+         } else {
+            0
+         };
+         writeln!(stat, " {file} | {lines} {}", "+".repeat(lines.min(50))).unwrap();
+      }
+   }
+   stat
+}
+
 /// Get git diff based on the specified mode
+/// `git <subcommand>` in `dir`, with `--diff-algorithm` when
+/// `diff_algorithm` is set
+fn diff_command(subcommand: &str, dir: &str, config: &CommitConfig) -> Command {
+   let mut cmd = Command::new("git");
+   cmd.arg(subcommand).current_dir(dir);
+   if let Some(algorithm) = config.diff_algorithm {
+      cmd.arg(format!("--diff-algorithm={}", algorithm.as_str()));
+   }
+   cmd
+}
+
 pub fn get_git_diff(
    mode: &Mode,
    target: Option<&str>,
@@ -16,30 +319,33 @@ pub fn get_git_diff(
    config: &CommitConfig,
 ) -> Result<String> {
    let output = match mode {
-      Mode::Staged => Command::new("git")
-         .args(["diff", "--cached"])
-         .current_dir(dir)
+      Mode::Staged => diff_command("diff", dir, config)
+         .arg("--cached")
          .output()
          .map_err(|e| CommitGenError::GitError(format!("Failed to run git diff --cached: {e}")))?,
       Mode::Commit => {
          let target = target.ok_or_else(|| {
             CommitGenError::ValidationError("--target required for commit mode".to_string())
          })?;
-         let mut cmd = Command::new("git");
-         cmd.arg("show");
-         if config.exclude_old_message {
-            cmd.arg("--format=");
+         if is_commit_range(target) {
+            // Combined diff across the range (squash), not one diff per commit
+            diff_command("diff", dir, config)
+               .arg(target)
+               .output()
+               .map_err(|e| CommitGenError::GitError(format!("Failed to run git diff: {e}")))?
+         } else {
+            let mut cmd = diff_command("show", dir, config);
+            if config.exclude_old_message {
+               cmd.arg("--format=");
+            }
+            cmd.arg(target)
+               .output()
+               .map_err(|e| CommitGenError::GitError(format!("Failed to run git show: {e}")))?
          }
-         cmd.arg(target)
-            .current_dir(dir)
-            .output()
-            .map_err(|e| CommitGenError::GitError(format!("Failed to run git show: {e}")))?
       },
       Mode::Unstaged => {
          // Get diff for tracked files
-         let tracked_output = Command::new("git")
-            .args(["diff"])
-            .current_dir(dir)
+         let tracked_output = diff_command("diff", dir, config)
             .output()
             .map_err(|e| CommitGenError::GitError(format!("Failed to run git diff: {e}")))?;
 
@@ -50,65 +356,25 @@ pub fn get_git_diff(
 
          let tracked_diff = String::from_utf8_lossy(&tracked_output.stdout).to_string();
 
-         // Get untracked files
-         let untracked_output = Command::new("git")
-            .args(["ls-files", "--others", "--exclude-standard"])
-            .current_dir(dir)
-            .output()
-            .map_err(|e| {
-               CommitGenError::GitError(format!("Failed to list untracked files: {e}"))
-            })?;
-
-         if !untracked_output.status.success() {
-            let stderr = String::from_utf8_lossy(&untracked_output.stderr);
-            return Err(CommitGenError::GitError(format!("git ls-files failed: {stderr}")));
-         }
-
-         let untracked_list = String::from_utf8_lossy(&untracked_output.stdout);
-         let untracked_files: Vec<&str> =
-            untracked_list.lines().filter(|s| !s.is_empty()).collect();
-
-         if untracked_files.is_empty() {
-            return Ok(tracked_diff);
-         }
-
-         // Generate diffs for untracked files using git diff /dev/null
          let mut combined_diff = tracked_diff;
-         for file in untracked_files {
-            let file_diff_output = Command::new("git")
-               .args(["diff", "--no-index", "/dev/null", file])
-               .current_dir(dir)
-               .output()
-               .map_err(|e| {
-                  CommitGenError::GitError(format!("Failed to diff untracked file {file}: {e}"))
-               })?;
-
-            // git diff --no-index exits with 1 when files differ (expected)
-            if file_diff_output.status.success() || file_diff_output.status.code() == Some(1) {
-               let file_diff = String::from_utf8_lossy(&file_diff_output.stdout);
-               // Rewrite the diff header to match standard git format
-               let lines: Vec<&str> = file_diff.lines().collect();
-               if lines.len() >= 2 {
-                  use std::fmt::Write;
-                  if !combined_diff.is_empty() {
-                     combined_diff.push('\n');
-                  }
-                  writeln!(combined_diff, "diff --git a/{file} b/{file}").unwrap();
-                  combined_diff.push_str("new file mode 100644\n");
-                  combined_diff.push_str("index 0000000..0000000\n");
-                  combined_diff.push_str("--- /dev/null\n");
-                  writeln!(combined_diff, "+++ b/{file}").unwrap();
-                  // Skip first 2 lines (---/+++ from --no-index) and copy rest
-                  for line in lines.iter().skip(2) {
-                     combined_diff.push_str(line);
-                     combined_diff.push('\n');
-                  }
-               }
+         for file in get_untracked_files(dir)? {
+            if !combined_diff.is_empty() && !combined_diff.ends_with('\n') {
+               combined_diff.push('\n');
             }
+            combined_diff.push_str(&untracked_file_diff(dir, &file, None));
          }
 
          return Ok(combined_diff);
       },
+      Mode::Merge => {
+         let base = merge_diff_base(dir)?;
+         diff_command("diff", dir, config)
+            .args(["--cached", base])
+            .output()
+            .map_err(|e| {
+               CommitGenError::GitError(format!("Failed to run git diff --cached {base}: {e}"))
+            })?
+      },
       Mode::Compose => unreachable!("compose mode handled separately"),
    };
 
@@ -124,6 +390,7 @@ pub fn get_git_diff(
          Mode::Staged => "staged",
          Mode::Commit => "commit",
          Mode::Unstaged => "unstaged",
+         Mode::Merge => "merge",
          Mode::Compose => "compose",
       };
       return Err(CommitGenError::NoChanges { mode: mode_str.to_string() });
@@ -140,9 +407,8 @@ pub fn get_git_stat(
    config: &CommitConfig,
 ) -> Result<String> {
    let output = match mode {
-      Mode::Staged => Command::new("git")
-         .args(["diff", "--cached", "--stat"])
-         .current_dir(dir)
+      Mode::Staged => diff_command("diff", dir, config)
+         .args(["--cached", "--stat"])
          .output()
          .map_err(|e| {
             CommitGenError::GitError(format!("Failed to run git diff --cached --stat: {e}"))
@@ -151,22 +417,27 @@ pub fn get_git_stat(
          let target = target.ok_or_else(|| {
             CommitGenError::ValidationError("--target required for commit mode".to_string())
          })?;
-         let mut cmd = Command::new("git");
-         cmd.arg("show");
-         if config.exclude_old_message {
-            cmd.arg("--format=");
+         if is_commit_range(target) {
+            diff_command("diff", dir, config)
+               .args(["--stat", target])
+               .output()
+               .map_err(|e| {
+                  CommitGenError::GitError(format!("Failed to run git diff --stat: {e}"))
+               })?
+         } else {
+            let mut cmd = diff_command("show", dir, config);
+            if config.exclude_old_message {
+               cmd.arg("--format=");
+            }
+            cmd.arg("--stat").arg(target).output().map_err(|e| {
+               CommitGenError::GitError(format!("Failed to run git show --stat: {e}"))
+            })?
          }
-         cmd.arg("--stat")
-            .arg(target)
-            .current_dir(dir)
-            .output()
-            .map_err(|e| CommitGenError::GitError(format!("Failed to run git show --stat: {e}")))?
       },
       Mode::Unstaged => {
          // Get stat for tracked files
-         let tracked_output = Command::new("git")
-            .args(["diff", "--stat"])
-            .current_dir(dir)
+         let tracked_output = diff_command("diff", dir, config)
+            .arg("--stat")
             .output()
             .map_err(|e| CommitGenError::GitError(format!("Failed to run git diff --stat: {e}")))?;
 
@@ -177,46 +448,25 @@ pub fn get_git_stat(
 
          let mut stat = String::from_utf8_lossy(&tracked_output.stdout).to_string();
 
-         // Get untracked files and append to stat
-         let untracked_output = Command::new("git")
-            .args(["ls-files", "--others", "--exclude-standard"])
-            .current_dir(dir)
-            .output()
-            .map_err(|e| {
-               CommitGenError::GitError(format!("Failed to list untracked files: {e}"))
-            })?;
-
-         if !untracked_output.status.success() {
-            let stderr = String::from_utf8_lossy(&untracked_output.stderr);
-            return Err(CommitGenError::GitError(format!("git ls-files failed: {stderr}")));
-         }
-
-         let untracked_list = String::from_utf8_lossy(&untracked_output.stdout);
-         let untracked_files: Vec<&str> =
-            untracked_list.lines().filter(|s| !s.is_empty()).collect();
-
-         if !untracked_files.is_empty() {
-            use std::fmt::Write;
-            for file in untracked_files {
-               use std::fs;
-               if let Ok(metadata) = fs::metadata(format!("{dir}/{file}")) {
-                  let lines = if metadata.is_file() {
-                     fs::read_to_string(format!("{dir}/{file}"))
-                        .map(|content| content.lines().count())
-                        .unwrap_or(0)
-                  } else {
-                     0
-                  };
-                  if !stat.is_empty() && !stat.ends_with('\n') {
-                     stat.push('\n');
-                  }
-                  writeln!(stat, " {file} | {lines} {}", "+".repeat(lines.min(50))).unwrap();
-               }
-            }
+         let untracked = get_untracked_files(dir)?;
+         if !untracked.is_empty() && !stat.is_empty() && !stat.ends_with('\n') {
+            stat.push('\n');
          }
+         stat.push_str(&untracked_stat(dir, &untracked));
 
          return Ok(stat);
       },
+      Mode::Merge => {
+         let base = merge_diff_base(dir)?;
+         diff_command("diff", dir, config)
+            .args(["--cached", "--stat", base])
+            .output()
+            .map_err(|e| {
+               CommitGenError::GitError(format!(
+                  "Failed to run git diff --cached --stat {base}: {e}"
+               ))
+            })?
+      },
       Mode::Compose => unreachable!("compose mode handled separately"),
    };
 
@@ -228,8 +478,127 @@ pub fn get_git_stat(
    Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Author/committer date overrides applied to `git commit`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitDates {
+   /// Value for `GIT_AUTHOR_DATE`
+   pub author:    Option<String>,
+   /// Value for `GIT_COMMITTER_DATE`
+   pub committer: Option<String>,
+}
+
+impl CommitDates {
+   /// Build overrides from `--commit-date` (both dates) and `--author-date`
+   /// (author only, takes precedence)
+   pub fn from_flags(commit_date: Option<&str>, author_date: Option<&str>) -> Result<Self> {
+      for date in [commit_date, author_date].into_iter().flatten() {
+         validate_commit_date(date)?;
+      }
+      Ok(Self {
+         author:    author_date.or(commit_date).map(str::to_string),
+         committer: commit_date.map(str::to_string),
+      })
+   }
+
+   fn env_vars(&self) -> Vec<(&'static str, &str)> {
+      let mut vars = Vec::new();
+      if let Some(author) = &self.author {
+         vars.push(("GIT_AUTHOR_DATE", author.as_str()));
+      }
+      if let Some(committer) = &self.committer {
+         vars.push(("GIT_COMMITTER_DATE", committer.as_str()));
+      }
+      vars
+   }
+}
+
+/// Validate an ISO 8601 date accepted by git (`2024-01-01T12:00:00`, with
+/// optional offset, or `2024-01-01 12:00:00`)
+fn validate_commit_date(date: &str) -> Result<()> {
+   use chrono::{DateTime, NaiveDateTime};
+
+   let date = date.trim();
+   let valid = DateTime::parse_from_rfc3339(date).is_ok()
+      || ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S%z", "%Y-%m-%d %H:%M:%S %z"]
+         .iter()
+         .any(|fmt| {
+            NaiveDateTime::parse_from_str(date, fmt).is_ok()
+               || DateTime::parse_from_str(date, fmt).is_ok()
+         });
+
+   if valid {
+      Ok(())
+   } else {
+      Err(CommitGenError::ValidationError(format!(
+         "Invalid date '{date}': expected ISO 8601, e.g. 2024-01-01T12:00:00 or \
+          2024-01-01T12:00:00+02:00"
+      )))
+   }
+}
+
+/// Build the `git commit` command, including any date overrides
+#[allow(clippy::fn_params_excessive_bools, reason = "commit flags are naturally boolean")]
+fn build_commit_command(
+   message: &str,
+   dir: &str,
+   sign: bool,
+   signoff: bool,
+   skip_hooks: bool,
+   allow_empty: bool,
+   dates: &CommitDates,
+) -> Command {
+   let mut cmd = Command::new("git");
+   cmd.arg("commit");
+   if allow_empty {
+      cmd.arg("--allow-empty");
+   }
+   if sign {
+      cmd.arg("-S");
+   }
+   if signoff {
+      cmd.arg("-s");
+   }
+   if skip_hooks {
+      cmd.arg("--no-verify");
+   }
+   cmd.args(["-m", message]).current_dir(dir);
+   for (key, value) in dates.env_vars() {
+      cmd.env(key, value);
+   }
+   cmd
+}
+
+/// Name of the checked-out branch (`git rev-parse --abbrev-ref HEAD`), or
+/// `None` on a detached HEAD
+///
+/// Falls back to `git symbolic-ref` on an unborn branch, where `HEAD` doesn't
+/// resolve yet.
+pub fn get_current_branch(dir: &str) -> Result<Option<String>> {
+   let output = Command::new("git")
+      .args(["rev-parse", "--abbrev-ref", "HEAD"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to get current branch: {e}")))?;
+   let output = if output.status.success() {
+      output
+   } else {
+      Command::new("git")
+         .args(["symbolic-ref", "--short", "-q", "HEAD"])
+         .current_dir(dir)
+         .output()
+         .map_err(|e| CommitGenError::GitError(format!("Failed to get current branch: {e}")))?
+   };
+
+   let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+   Ok((output.status.success() && !branch.is_empty() && branch != "HEAD").then_some(branch))
+}
+
 /// Execute git commit with the given message
+///
+/// With a merge in progress (`MERGE_HEAD`) this concludes it: git records both
+/// parents and clears the merge state, as a plain `git commit` would.
 #[allow(clippy::fn_params_excessive_bools, reason = "commit flags are naturally boolean")]
+#[allow(clippy::too_many_arguments, reason = "one parameter per git commit flag")]
 pub fn git_commit(
    message: &str,
    dry_run: bool,
@@ -237,35 +606,30 @@ pub fn git_commit(
    sign: bool,
    signoff: bool,
    skip_hooks: bool,
+   allow_empty: bool,
+   dates: &CommitDates,
 ) -> Result<()> {
    if dry_run {
+      use std::fmt::Write;
+      let mut env_prefix = String::new();
+      for (key, value) in dates.env_vars() {
+         write!(env_prefix, "{key}=\"{value}\" ").unwrap();
+      }
       let sign_flag = if sign { " -S" } else { "" };
       let signoff_flag = if signoff { " -s" } else { "" };
       let hooks_flag = if skip_hooks { " --no-verify" } else { "" };
+      let empty_flag = if allow_empty { " --allow-empty" } else { "" };
       let command = format!(
-         "git commit{sign_flag}{signoff_flag}{hooks_flag} -m \"{}\"",
+         "{env_prefix}git commit{empty_flag}{sign_flag}{signoff_flag}{hooks_flag} -m \"{}\"",
          message.replace('\n', "\\n")
       );
-      println!("\n{}", style::boxed_message("DRY RUN", &command, 60));
+      if !style::is_quiet() {
+         println!("\n{}", style::boxed_message("DRY RUN", &command, 60));
+      }
       return Ok(());
    }
 
-   let mut args = vec!["commit"];
-   if sign {
-      args.push("-S");
-   }
-   if signoff {
-      args.push("-s");
-   }
-   if skip_hooks {
-      args.push("--no-verify");
-   }
-   args.push("-m");
-   args.push(message);
-
-   let output = Command::new("git")
-      .args(&args)
-      .current_dir(dir)
+   let output = build_commit_command(message, dir, sign, signoff, skip_hooks, allow_empty, dates)
       .output()
       .map_err(|e| CommitGenError::GitError(format!("Failed to run git commit: {e}")))?;
 
@@ -277,23 +641,30 @@ pub fn git_commit(
       )));
    }
 
-   let stdout = String::from_utf8_lossy(&output.stdout);
-   println!("\n{stdout}");
-   println!(
-      "{} {}",
-      style::success(style::icons::SUCCESS),
-      style::success("Successfully committed!")
-   );
+   if !style::is_quiet() {
+      let stdout = String::from_utf8_lossy(&output.stdout);
+      println!("\n{stdout}");
+      println!(
+         "{} {}",
+         style::success(style::icons::SUCCESS),
+         style::success("Successfully committed!")
+      );
+   }
 
    Ok(())
 }
 
-/// Execute git push
-pub fn git_push(dir: &str) -> Result<()> {
+/// Execute git push; `follow_tags` also pushes annotated tags on the pushed
+/// commits, such as a release tag just created
+pub fn git_push(dir: &str, follow_tags: bool) -> Result<()> {
    println!("\n{}", style::info("Pushing changes..."));
 
+   let mut args = vec!["push"];
+   if follow_tags {
+      args.push("--follow-tags");
+   }
    let output = Command::new("git")
-      .args(["push"])
+      .args(&args)
       .current_dir(dir)
       .output()
       .map_err(|e| CommitGenError::GitError(format!("Failed to run git push: {e}")))?;
@@ -335,6 +706,279 @@ pub fn get_head_hash(dir: &str) -> Result<String> {
    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
+/// URL of the `origin` remote
+pub fn get_origin_url(dir: &str) -> Result<String> {
+   let output = Command::new("git")
+      .args(["remote", "get-url", "origin"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to get origin URL: {e}")))?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git remote get-url origin failed: {stderr}")));
+   }
+
+   Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Stage everything (`git add -A`) when the index is empty
+///
+/// With `auto_stage` disabled an empty index is reported as `NoChanges`
+/// instead, so unrelated files never sneak into the commit.
+pub fn stage_all_if_nothing_staged(dir: &str, auto_stage: bool) -> Result<()> {
+   let staged_check = Command::new("git")
+      .args(["diff", "--cached", "--quiet"])
+      .current_dir(dir)
+      .status()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to check staged changes: {e}")))?;
+
+   // exit code 1 = changes exist, 0 = no changes
+   if !staged_check.success() {
+      return Ok(());
+   }
+
+   if !auto_stage {
+      return Err(CommitGenError::NoChanges {
+         mode: "staged (auto-staging disabled; stage files with `git add` first)".to_string(),
+      });
+   }
+
+   // Check if there are any unstaged changes before staging
+   let unstaged_check = Command::new("git")
+      .args(["diff", "--quiet"])
+      .current_dir(dir)
+      .status()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to check unstaged changes: {e}")))?;
+
+   // Check for untracked files
+   let untracked_output = Command::new("git")
+      .args(["ls-files", "--others", "--exclude-standard"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to check untracked files: {e}")))?;
+
+   let has_untracked = !untracked_output.stdout.is_empty();
+
+   // If no unstaged changes AND no untracked files, working directory is clean
+   if unstaged_check.success() && !has_untracked {
+      return Err(CommitGenError::NoChanges {
+         mode: "working directory (nothing to commit)".to_string(),
+      });
+   }
+
+   if !style::is_quiet() {
+      println!("{} {}", style::info("›"), style::dim("No staged changes, staging all..."));
+   }
+   let add_output = Command::new("git")
+      .args(["add", "-A"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to stage changes: {e}")))?;
+
+   if !add_output.status.success() {
+      let stderr = String::from_utf8_lossy(&add_output.stderr);
+      return Err(CommitGenError::GitError(format!("git add -A failed: {stderr}")));
+   }
+
+   Ok(())
+}
+
+/// Human-readable byte size (`512 B`, `3.2 MiB`)
+pub fn format_size(bytes: u64) -> String {
+   const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+   if bytes < 1024 {
+      return format!("{bytes} B");
+   }
+   #[allow(clippy::cast_precision_loss, reason = "display only")]
+   let mut size = bytes as f64 / 1024.0;
+   let mut unit = 0;
+   while size >= 1024.0 && unit < UNITS.len() - 1 {
+      size /= 1024.0;
+      unit += 1;
+   }
+   format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Staged files larger than `limit` bytes, largest first.
+///
+/// Text files are sized from their staged blob; binary files (numstat `-`)
+/// from the working tree. Deletions are ignored.
+pub fn find_large_staged_files(dir: &str, limit: u64) -> Result<Vec<(String, u64)>> {
+   let output = Command::new("git")
+      .args(["diff", "--cached", "--numstat", "--no-renames"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| {
+         CommitGenError::GitError(format!("Failed to run git diff --cached --numstat: {e}"))
+      })?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!(
+         "git diff --cached --numstat failed: {stderr}"
+      )));
+   }
+
+   // numstat paths are relative to the repository root
+   let root_output = Command::new("git")
+      .args(["rev-parse", "--show-toplevel"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to run git rev-parse: {e}")))?;
+   let root = String::from_utf8_lossy(&root_output.stdout)
+      .trim()
+      .to_string();
+
+   let mut large = Vec::new();
+   for line in String::from_utf8_lossy(&output.stdout).lines() {
+      let mut parts = line.splitn(3, '\t');
+      let (Some(added), Some(_), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+         continue;
+      };
+
+      let bytes = if added == "-" {
+         std::fs::metadata(std::path::Path::new(&root).join(path))
+            .map(|m| m.len())
+            .ok()
+      } else if added == "0" {
+         None
+      } else {
+         Command::new("git")
+            .args(["cat-file", "-s", &format!(":{path}")])
+            .current_dir(&root)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse().ok())
+      };
+
+      if let Some(bytes) = bytes
+         && bytes > limit
+      {
+         large.push((path.to_string(), bytes));
+      }
+   }
+
+   large.sort_by(|a, b| b.1.cmp(&a.1));
+   Ok(large)
+}
+
+/// Warn about staged files over `warn_bytes` (0 disables), failing when any
+/// exceeds `max_bytes`. Returns the warnings to print.
+pub fn check_large_staged_files(
+   dir: &str,
+   warn_bytes: u64,
+   max_bytes: Option<u64>,
+) -> Result<Vec<String>> {
+   let threshold = match (warn_bytes, max_bytes) {
+      (0, None) => return Ok(Vec::new()),
+      (0, Some(max)) => max,
+      (warn, Some(max)) => warn.min(max),
+      (warn, None) => warn,
+   };
+
+   let large = find_large_staged_files(dir, threshold)?;
+   if let Some(max) = max_bytes
+      && let Some((path, bytes)) = large.iter().find(|(_, bytes)| *bytes > max)
+   {
+      return Err(CommitGenError::ValidationError(format!(
+         "{path} is {} (max_committed_file_bytes is {}); unstage it, add it to .gitignore, or \
+          track it with Git LFS",
+         format_size(*bytes),
+         format_size(max)
+      )));
+   }
+
+   Ok(large
+      .iter()
+      .map(|(path, bytes)| {
+         format!(
+            "Large file staged: {path} ({}). Consider adding it to .gitignore or tracking it with \
+             Git LFS.",
+            format_size(*bytes)
+         )
+      })
+      .collect())
+}
+
+/// Whether one attribute setting marks a file as generated (`Some(true)`),
+/// explicitly not generated (`Some(false)`), or says nothing about it
+fn generated_attribute(attr: &str) -> Option<bool> {
+   match attr {
+      "linguist-generated" | "linguist-generated=true" | "-diff" => Some(true),
+      "-linguist-generated" | "linguist-generated=false" | "!linguist-generated" => Some(false),
+      _ => None,
+   }
+}
+
+/// Gitignore-style lines for the files a `.gitattributes` file marks
+/// `linguist-generated` or `-diff`.
+///
+/// Lines keep their order, and an explicit `-linguist-generated` becomes a
+/// `!` re-include, so later lines override earlier ones as in git. Quoted
+/// patterns and directory patterns (which never match in `.gitattributes`)
+/// are skipped.
+pub fn parse_generated_attributes(contents: &str) -> Vec<String> {
+   contents
+      .lines()
+      .filter_map(|line| {
+         let mut tokens = line.split_whitespace();
+         let pattern = tokens.next()?;
+         if pattern.starts_with(['#', '"', '!']) || pattern.ends_with('/') {
+            return None;
+         }
+         let generated = tokens
+            .filter_map(generated_attribute)
+            .reduce(|_, last| last)?;
+         Some(if generated {
+            pattern.to_string()
+         } else {
+            format!("!{pattern}")
+         })
+      })
+      .collect()
+}
+
+/// Generated-file lines from the root `.gitattributes` of the repository at
+/// `dir`; empty when there is none
+pub fn generated_file_patterns(dir: &str) -> Vec<String> {
+   let root = Command::new("git")
+      .args(["rev-parse", "--show-toplevel"])
+      .current_dir(dir)
+      .output()
+      .ok()
+      .filter(|o| o.status.success())
+      .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+   root
+      .and_then(|root| {
+         std::fs::read_to_string(std::path::Path::new(&root).join(".gitattributes")).ok()
+      })
+      .map(|contents| parse_generated_attributes(&contents))
+      .unwrap_or_default()
+}
+
+/// Fingerprint the staged set (paths, modes, and blob hashes)
+///
+/// Cheap to compute and changes whenever a hunk is staged or unstaged, so
+/// watch mode can poll it instead of diffing full contents.
+pub fn get_staged_fingerprint(dir: &str) -> Result<String> {
+   let output = Command::new("git")
+      .args(["diff", "--cached", "--raw", "--no-abbrev"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| {
+         CommitGenError::GitError(format!("Failed to run git diff --cached --raw: {e}"))
+      })?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git diff --cached --raw failed: {stderr}")));
+   }
+
+   Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
 // === History Rewrite Operations ===
 
 /// Get list of commit hashes to rewrite (in chronological order)
@@ -363,6 +1007,67 @@ pub fn get_commit_list(start_ref: Option<&str>, dir: &str) -> Result<Vec<String>
    Ok(stdout.lines().map(|s| s.to_string()).collect())
 }
 
+/// The current branch's upstream (`origin/main`) and the commits on top of
+/// it (`@{upstream}..HEAD`), oldest first
+#[allow(
+   clippy::literal_string_with_formatting_args,
+   reason = "`@{upstream}` is git revision syntax"
+)]
+pub fn get_unpushed_commits(dir: &str) -> Result<(String, Vec<String>)> {
+   let output = Command::new("git")
+      .args(["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{upstream}"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to run git rev-parse: {e}")))?;
+
+   if !output.status.success() {
+      return Err(CommitGenError::ValidationError(
+         "The current branch has no upstream; set one with `git branch --set-upstream-to` first"
+            .to_string(),
+      ));
+   }
+
+   let upstream = String::from_utf8_lossy(&output.stdout).trim().to_string();
+   let commits = get_commit_list(Some(&upstream), dir)?;
+   Ok((upstream, commits))
+}
+
+/// All commits reachable from `reference`, parents before children
+pub fn list_commits(reference: &str, dir: &str) -> Result<Vec<String>> {
+   let output = Command::new("git")
+      .args(["rev-list", "--reverse", "--topo-order", reference])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to run git rev-list: {e}")))?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git rev-list {reference} failed: {stderr}")));
+   }
+
+   let stdout = String::from_utf8_lossy(&output.stdout);
+   Ok(stdout.lines().map(|s| s.to_string()).collect())
+}
+
+/// Whether the commit object carries a GPG/SSH signature
+pub fn has_commit_signature(hash: &str, dir: &str) -> Result<bool> {
+   let output = Command::new("git")
+      .args(["cat-file", "commit", hash])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to run git cat-file: {e}")))?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git cat-file failed for {hash}: {stderr}")));
+   }
+
+   // Signature headers precede the blank line that starts the message
+   let raw = String::from_utf8_lossy(&output.stdout);
+   let headers = raw.split("\n\n").next().unwrap_or_default();
+   Ok(headers.lines().any(|line| line.starts_with("gpgsig")))
+}
+
 /// Extract complete metadata for a commit (for rewriting)
 pub fn get_commit_metadata(hash: &str, dir: &str) -> Result<CommitMetadata> {
    // Format: author_name\0author_email\0author_date\0committer_name\
@@ -456,27 +1161,26 @@ pub fn create_backup_branch(dir: &str) -> Result<String> {
    Ok(backup_name)
 }
 
-/// Get recent commit messages for style consistency (last N commits)
-pub fn get_recent_commits(dir: &str, count: usize) -> Result<Vec<String>> {
+/// Create annotated tag `name` on HEAD
+pub fn create_tag(name: &str, message: &str, dir: &str) -> Result<()> {
    let output = Command::new("git")
-      .args(["log", &format!("-{count}"), "--pretty=format:%s"])
+      .args(["tag", "-a", name, "-m", message])
       .current_dir(dir)
       .output()
-      .map_err(|e| CommitGenError::GitError(format!("Failed to run git log: {e}")))?;
+      .map_err(|e| CommitGenError::GitError(format!("Failed to create tag {name}: {e}")))?;
 
    if !output.status.success() {
       let stderr = String::from_utf8_lossy(&output.stderr);
-      return Err(CommitGenError::GitError(format!("git log failed: {stderr}")));
+      return Err(CommitGenError::GitError(format!("git tag {name} failed: {stderr}")));
    }
 
-   let stdout = String::from_utf8_lossy(&output.stdout);
-   Ok(stdout.lines().map(|s| s.to_string()).collect())
+   Ok(())
 }
 
-/// Extract common scopes from git history by parsing commit messages
-pub fn get_common_scopes(dir: &str, limit: usize) -> Result<Vec<(String, usize)>> {
+/// Get recent commit messages for style consistency (last N commits)
+pub fn get_recent_commits(dir: &str, count: usize) -> Result<Vec<String>> {
    let output = Command::new("git")
-      .args(["log", &format!("-{limit}"), "--pretty=format:%s"])
+      .args(["log", &format!("-{count}"), "--pretty=format:%s"])
       .current_dir(dir)
       .output()
       .map_err(|e| CommitGenError::GitError(format!("Failed to run git log: {e}")))?;
@@ -487,39 +1191,151 @@ pub fn get_common_scopes(dir: &str, limit: usize) -> Result<Vec<(String, usize)>
    }
 
    let stdout = String::from_utf8_lossy(&output.stdout);
-   let mut scope_counts: HashMap<String, usize> = HashMap::new();
+   Ok(stdout.lines().map(|s| s.to_string()).collect())
+}
+
+/// Scope frequencies over recent history, after canonicalization
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScopeHistory {
+   /// Canonical scopes with their commit counts, most frequent first
+   pub scopes:  Vec<(String, usize)>,
+   /// Commits whose subject names at least one scope
+   pub scoped:  usize,
+   /// Commits looked at
+   pub commits: usize,
+}
 
-   // Parse conventional commit format: type(scope): message
-   for line in stdout.lines() {
-      if let Some(scope) = extract_scope_from_commit(line) {
-         *scope_counts.entry(scope).or_insert(0) += 1;
+impl ScopeHistory {
+   /// Share of commits naming a scope, 0.0-100.0
+   pub fn scoped_pct(&self) -> f32 {
+      if self.commits == 0 {
+         0.0
+      } else {
+         self.scoped as f32 / self.commits as f32 * 100.0
       }
    }
 
-   // Sort by frequency (descending)
-   let mut scopes: Vec<(String, usize)> = scope_counts.into_iter().collect();
-   scopes.sort_by(|a, b| b.1.cmp(&a.1));
+   /// Drop scopes seen fewer than `min_count` times
+   pub fn with_min_count(mut self, min_count: usize) -> Self {
+      self.scopes.retain(|(_, count)| *count >= min_count);
+      self
+   }
+}
 
-   Ok(scopes)
+/// Scopes named in a subject's `type(scope)!:` prefix.
+///
+/// Tolerates what older history contains: several scopes separated by commas
+/// (`feat(api, cli)`), spaces inside a scope (`fix(user auth)` reads as
+/// `user-auth`) and colons inside the parentheses (`fix(parser: lexer)` reads
+/// as `parser:lexer`). Scopes are lowercased.
+fn parse_subject_scopes(subject: &str) -> Vec<String> {
+   let subject = subject.trim_start();
+   let type_len = subject
+      .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+      .unwrap_or(subject.len());
+   let Some(rest) = subject[type_len..].strip_prefix('(') else {
+      return Vec::new();
+   };
+   let Some(close) = rest.find(')') else {
+      return Vec::new();
+   };
+   let after = rest[close + 1..].trim_start_matches('!');
+   if type_len == 0 || !after.starts_with(':') {
+      return Vec::new();
+   }
+
+   rest[..close]
+      .split(',')
+      .map(|scope| {
+         scope
+            .split(':')
+            .map(|part| part.split_whitespace().collect::<Vec<_>>().join("-"))
+            .collect::<Vec<_>>()
+            .join(":")
+            .to_lowercase()
+      })
+      .filter(|scope| !scope.is_empty())
+      .collect()
 }
 
-/// Extract scope from a conventional commit message
-fn extract_scope_from_commit(commit_msg: &str) -> Option<String> {
-   // Match pattern: type(scope): message
-   let parts: Vec<&str> = commit_msg.splitn(2, ':').collect();
-   if parts.len() < 2 {
-      return None;
+/// Canonical form of a scope: `aliases` applied (keys compared
+/// case-insensitively), then a plural folded into its singular when the
+/// singular also occurs in `seen`
+fn canonical_scope(scope: &str, aliases: &IndexMap<String, String>, seen: &[String]) -> String {
+   let aliased = |scope: &str| {
+      aliases
+         .iter()
+         .find(|(alias, _)| alias.eq_ignore_ascii_case(scope))
+         .map_or_else(|| scope.to_string(), |(_, canonical)| canonical.to_lowercase())
+   };
+   let scope = aliased(scope);
+   let singular = scope
+      .strip_suffix("ies")
+      .map(|stem| format!("{stem}y"))
+      .or_else(|| scope.strip_suffix('s').map(str::to_string));
+   match singular {
+      Some(singular) if seen.iter().any(|other| aliased(other) == singular) => singular,
+      _ => scope,
    }
+}
 
-   let prefix = parts[0];
-   if let Some(scope_start) = prefix.find('(')
-      && let Some(scope_end) = prefix.find(')')
-      && scope_start < scope_end
-   {
-      return Some(prefix[scope_start + 1..scope_end].to_string());
+/// Canonical scope table for a list of commit subjects
+pub fn parse_scope_history(
+   subjects: &[String],
+   aliases: &IndexMap<String, String>,
+) -> ScopeHistory {
+   let per_commit: Vec<Vec<String>> = subjects.iter().map(|s| parse_subject_scopes(s)).collect();
+   let seen: Vec<String> = per_commit.iter().flatten().cloned().collect();
+
+   let mut counts: IndexMap<String, usize> = IndexMap::new();
+   for scopes in &per_commit {
+      let mut canonical: Vec<String> = scopes
+         .iter()
+         .map(|scope| canonical_scope(scope, aliases, &seen))
+         .collect();
+      canonical.sort_unstable();
+      canonical.dedup();
+      for scope in canonical {
+         *counts.entry(scope).or_insert(0) += 1;
+      }
+   }
+
+   let mut scopes: Vec<(String, usize)> = counts.into_iter().collect();
+   scopes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+   ScopeHistory {
+      scopes,
+      scoped: per_commit
+         .iter()
+         .filter(|scopes| !scopes.is_empty())
+         .count(),
+      commits: subjects.len(),
+   }
+}
+
+/// Scope table for the last `depth` commits
+pub fn get_scope_history(dir: &str, depth: usize, config: &CommitConfig) -> Result<ScopeHistory> {
+   let output = Command::new("git")
+      .args(["log", &format!("-{depth}"), "--pretty=format:%s"])
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to run git log: {e}")))?;
+
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git log failed: {stderr}")));
    }
 
-   None
+   let subjects: Vec<String> = String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .map(|s| s.to_string())
+      .collect();
+   Ok(parse_scope_history(&subjects, &config.scope_aliases).with_min_count(config.scope_min_count))
+}
+
+/// Common scopes from the last `scope_history_depth` commits, as given to the
+/// analysis prompt
+pub fn get_common_scopes(dir: &str, config: &CommitConfig) -> Result<Vec<(String, usize)>> {
+   Ok(get_scope_history(dir, config.scope_history_depth, config)?.scopes)
 }
 
 /// Quantified style patterns extracted from commit history
@@ -617,7 +1433,7 @@ pub fn extract_style_patterns(commits: &[String]) -> Option<StylePatterns> {
             }
          }
 
-         lengths.push(summary.len());
+         lengths.push(summary.chars().count());
       }
    }
 
@@ -762,3 +1578,314 @@ pub fn rewrite_history(
 
    Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+   use std::ffi::OsStr;
+
+   use super::*;
+
+   fn env_of<'a>(cmd: &'a Command, key: &str) -> Option<&'a OsStr> {
+      cmd.get_envs()
+         .find(|(k, _)| *k == OsStr::new(key))
+         .and_then(|(_, v)| v)
+   }
+
+   #[test]
+   fn test_diff_command_passes_diff_algorithm() {
+      let args = |config: &CommitConfig| -> Vec<String> {
+         diff_command("diff", ".", config)
+            .arg("--cached")
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+      };
+
+      assert_eq!(args(&CommitConfig::default()), ["diff", "--cached"]);
+
+      let config: CommitConfig = toml::from_str("diff_algorithm = \"histogram\"").unwrap();
+      assert_eq!(args(&config), ["diff", "--diff-algorithm=histogram", "--cached"]);
+      assert!(toml::from_str::<CommitConfig>("diff_algorithm = \"fancy\"").is_err());
+   }
+
+   fn git(dir: &std::path::Path, args: &[&str]) {
+      let status = Command::new("git")
+         .args(args)
+         .current_dir(dir)
+         .status()
+         .unwrap();
+      assert!(status.success(), "git {args:?} failed");
+   }
+
+   #[test]
+   fn test_unpushed_commits_range() {
+      let dir = std::env::temp_dir().join(format!("lgit-unpushed-{}", std::process::id()));
+      let _ = std::fs::remove_dir_all(&dir);
+      std::fs::create_dir_all(&dir).unwrap();
+      git(&dir, &["init", "-q", "-b", "main"]);
+      git(&dir, &["config", "user.name", "Test"]);
+      git(&dir, &["config", "user.email", "test@example.com"]);
+      git(&dir, &["config", "commit.gpgsign", "false"]);
+      git(&dir, &["commit", "-q", "--allow-empty", "-m", "base"]);
+      let repo = dir.to_str().unwrap();
+
+      let err = get_unpushed_commits(repo).unwrap_err();
+      assert!(err.to_string().contains("no upstream"), "{err}");
+
+      git(&dir, &["checkout", "-q", "--track", "-b", "work", "main"]);
+      assert_eq!(get_unpushed_commits(repo).unwrap(), ("main".to_string(), vec![]));
+
+      git(&dir, &["commit", "-q", "--allow-empty", "-m", "first"]);
+      git(&dir, &["commit", "-q", "--allow-empty", "-m", "second"]);
+      let (upstream, commits) = get_unpushed_commits(repo).unwrap();
+      let subjects: Vec<String> = commits
+         .iter()
+         .map(|hash| get_commit_metadata(hash, repo).unwrap().message)
+         .collect();
+      std::fs::remove_dir_all(&dir).ok();
+
+      assert_eq!(upstream, "main");
+      assert_eq!(subjects, ["first", "second"]);
+   }
+
+   #[test]
+   fn test_parse_git_version() {
+      assert_eq!(parse_git_version("git version 2.39.2\n"), Some((2, 39, 2)));
+      assert_eq!(parse_git_version("git version 2.39.3 (Apple Git-146)"), Some((2, 39, 3)));
+      assert_eq!(parse_git_version("git version 2.45.1.windows.1"), Some((2, 45, 1)));
+      assert_eq!(parse_git_version("git version 2.23"), Some((2, 23, 0)));
+      assert_eq!(parse_git_version("git version 3.0.0-rc1"), Some((3, 0, 0)));
+      assert_eq!(parse_git_version("hub version 2.14.2"), None);
+      assert_eq!(parse_git_version(""), None);
+   }
+
+   #[test]
+   fn test_check_git_version_minimum() {
+      assert!(check_git_version("git version 2.23.0").is_ok());
+      assert!(check_git_version("git version 2.43.0").is_ok());
+      assert!(check_git_version("git version 3.0.0").is_ok());
+      let err = check_git_version("git version 2.20.1").unwrap_err();
+      assert!(err.contains("2.20.1 is too old"), "{err}");
+      assert!(check_git_version("not git").is_err());
+   }
+
+   #[test]
+   fn test_parse_generated_attributes() {
+      let attributes = "\
+# Generated code
+*.pb.go linguist-generated
+dist/** -diff
+src/schema.rs linguist-generated=true
+*.rs diff=rust
+docs/ linguist-generated
+\"quoted name\" linguist-generated
+src/api/*.gen.ts linguist-generated
+src/api/client.gen.ts -linguist-generated
+";
+      assert_eq!(parse_generated_attributes(attributes), vec![
+         "*.pb.go",
+         "dist/**",
+         "src/schema.rs",
+         "src/api/*.gen.ts",
+         "!src/api/client.gen.ts",
+      ]);
+   }
+
+   #[test]
+   fn test_scope_history_canonicalizes_messy_scopes() {
+      let subjects: Vec<String> = [
+         "feat(api): add pagination",
+         "fix(API): handle empty page",
+         "feat(api, cli): share the client",
+         "fix(user auth): expire sessions",
+         "refactor(tests): split fixtures",
+         "test(test): cover retries",
+         "chore(deps)!: bump reqwest",
+         "docs(auth-service): describe tokens",
+         "fix(parser: lexer): stray colon",
+         "Merge branch 'main'",
+         "update readme",
+      ]
+      .iter()
+      .map(|s| (*s).to_string())
+      .collect();
+      let mut aliases = IndexMap::new();
+      aliases.insert("Auth-Service".to_string(), "user-auth".to_string());
+
+      let history = parse_scope_history(&subjects, &aliases);
+      assert_eq!(history.commits, 11);
+      assert_eq!(history.scoped, 9);
+      assert_eq!(history.scopes, vec![
+         ("api".to_string(), 3),
+         ("test".to_string(), 2),
+         ("user-auth".to_string(), 2),
+         ("cli".to_string(), 1),
+         ("deps".to_string(), 1),
+         ("parser:lexer".to_string(), 1),
+      ]);
+      assert!((history.scoped_pct() - 81.8).abs() < 0.1);
+
+      let frequent = history.with_min_count(2);
+      assert_eq!(frequent.scopes.len(), 3);
+      assert_eq!(frequent.scoped, 9);
+   }
+
+   #[test]
+   fn test_scope_history_counts_repeated_scope_once_per_commit() {
+      let subjects = vec!["feat(api, cli, API): share the client".to_string()];
+      let history = parse_scope_history(&subjects, &IndexMap::new());
+      assert_eq!(history.scopes, vec![("api".to_string(), 1), ("cli".to_string(), 1)]);
+   }
+
+   #[test]
+   fn test_parse_merge_msg() {
+      let commented = "Merge branch 'feature'\n\n# Conflicts:\n#\tsrc/config.rs\n#\tREADME.md\n";
+      assert_eq!(parse_merge_msg_subject(commented).as_deref(), Some("Merge branch 'feature'"));
+      assert_eq!(parse_merge_msg_conflicts(commented), vec!["src/config.rs", "README.md"]);
+
+      // Older git wrote the section uncommented
+      let plain = "Merge branch 'x' into main\n\nConflicts:\n\tsrc/lib.rs\n\n# Please enter a \
+                   commit message\n";
+      assert_eq!(parse_merge_msg_conflicts(plain), vec!["src/lib.rs"]);
+
+      assert!(parse_merge_msg_conflicts("Merge branch 'clean'\n").is_empty());
+      assert_eq!(parse_merge_msg_subject("# only comments\n\n"), None);
+   }
+
+   #[test]
+   fn test_no_auto_stage_leaves_index_untouched() {
+      let dir = std::env::temp_dir().join(format!("lgit-no-stage-{}", std::process::id()));
+      std::fs::create_dir_all(&dir).unwrap();
+      git(&dir, &["init", "-q"]);
+      std::fs::write(dir.join("unrelated.txt"), "scratch").unwrap();
+      let repo = dir.to_str().unwrap();
+
+      let result = stage_all_if_nothing_staged(repo, false);
+      assert!(matches!(result, Err(CommitGenError::NoChanges { .. })));
+      let staged = Command::new("git")
+         .args(["diff", "--cached", "--name-only"])
+         .current_dir(&dir)
+         .output()
+         .unwrap();
+      assert!(staged.stdout.is_empty(), "nothing should have been staged");
+
+      // Default behavior still stages everything
+      stage_all_if_nothing_staged(repo, true).unwrap();
+      let staged = Command::new("git")
+         .args(["diff", "--cached", "--name-only"])
+         .current_dir(&dir)
+         .output()
+         .unwrap();
+      assert_eq!(String::from_utf8_lossy(&staged.stdout).trim(), "unrelated.txt");
+
+      std::fs::remove_dir_all(&dir).ok();
+   }
+
+   #[test]
+   fn test_large_staged_files_warn_and_block() {
+      let dir = std::env::temp_dir().join(format!("lgit-large-{}", std::process::id()));
+      std::fs::create_dir_all(&dir).unwrap();
+      git(&dir, &["init", "-q"]);
+      std::fs::write(dir.join("small.txt"), "tiny\n").unwrap();
+      std::fs::write(dir.join("notes.txt"), "line\n".repeat(40_000)).unwrap();
+      std::fs::write(dir.join("blob.bin"), vec![0u8; 300_000]).unwrap();
+      git(&dir, &["add", "-A"]);
+      let repo = dir.to_str().unwrap();
+
+      let large = find_large_staged_files(repo, 100_000).unwrap();
+      assert_eq!(large, vec![
+         ("blob.bin".to_string(), 300_000),
+         ("notes.txt".to_string(), 200_000)
+      ]);
+
+      let warnings = check_large_staged_files(repo, 250_000, None).unwrap();
+      assert_eq!(warnings.len(), 1);
+      assert!(warnings[0].contains("blob.bin (293.0 KiB)"), "{}", warnings[0]);
+      assert!(warnings[0].contains("Git LFS"));
+
+      assert!(check_large_staged_files(repo, 0, None).unwrap().is_empty());
+      let err = check_large_staged_files(repo, 0, Some(250_000)).unwrap_err();
+      assert!(matches!(err, CommitGenError::ValidationError(ref msg) if msg.contains("blob.bin")));
+
+      std::fs::remove_dir_all(&dir).ok();
+   }
+
+   #[test]
+   fn test_range_subjects_become_body_items() {
+      let dir = std::env::temp_dir().join(format!("lgit-range-{}", std::process::id()));
+      std::fs::create_dir_all(&dir).unwrap();
+      git(&dir, &["init", "-q"]);
+      git(&dir, &["config", "user.name", "Test"]);
+      git(&dir, &["config", "user.email", "test@example.com"]);
+      git(&dir, &["config", "commit.gpgsign", "false"]);
+      for (i, subject) in
+         ["chore: initial commit", "feat(api): added retry budget", "fix: handled empty diff"]
+            .iter()
+            .enumerate()
+      {
+         std::fs::write(dir.join("file.txt"), i.to_string()).unwrap();
+         git(&dir, &["add", "-A"]);
+         git(&dir, &["commit", "-q", "-m", subject]);
+      }
+
+      let repo = dir.to_str().unwrap();
+      assert!(is_commit_range("HEAD~2..HEAD"));
+      let subjects = get_range_subjects("HEAD~2..HEAD", repo).unwrap();
+      assert_eq!(subjects, vec!["feat(api): added retry budget", "fix: handled empty diff"]);
+      assert_eq!(crate::normalization::commit_subjects_to_body(&subjects), vec![
+         "added retry budget",
+         "handled empty diff"
+      ]);
+
+      // The range diff is the combined change, not one diff per commit
+      let diff =
+         get_git_diff(&Mode::Commit, Some("HEAD~2..HEAD"), repo, &CommitConfig::default()).unwrap();
+      assert_eq!(diff.matches("diff --git").count(), 1);
+
+      std::fs::remove_dir_all(&dir).ok();
+   }
+
+   #[test]
+   fn test_commit_command_sets_date_env() {
+      let dates = CommitDates::from_flags(Some("2024-01-01T12:00:00"), None).unwrap();
+      let cmd = build_commit_command("feat: x", ".", false, false, false, false, &dates);
+      assert_eq!(env_of(&cmd, "GIT_COMMITTER_DATE"), Some(OsStr::new("2024-01-01T12:00:00")));
+      assert_eq!(env_of(&cmd, "GIT_AUTHOR_DATE"), Some(OsStr::new("2024-01-01T12:00:00")));
+   }
+
+   #[test]
+   fn test_commit_command_author_date_only() {
+      let dates =
+         CommitDates::from_flags(Some("2024-01-01T12:00:00"), Some("2023-06-01 08:30:00")).unwrap();
+      let cmd = build_commit_command("feat: x", ".", false, false, false, false, &dates);
+      assert_eq!(env_of(&cmd, "GIT_AUTHOR_DATE"), Some(OsStr::new("2023-06-01 08:30:00")));
+      assert_eq!(env_of(&cmd, "GIT_COMMITTER_DATE"), Some(OsStr::new("2024-01-01T12:00:00")));
+   }
+
+   #[test]
+   fn test_commit_command_without_dates() {
+      let cmd =
+         build_commit_command("feat: x", ".", false, false, false, false, &CommitDates::default());
+      assert_eq!(cmd.get_envs().count(), 0);
+   }
+
+   #[test]
+   fn test_commit_command_allow_empty() {
+      let cmd =
+         build_commit_command("ci: x", ".", false, false, false, true, &CommitDates::default());
+      let args: Vec<_> = cmd.get_args().collect();
+      assert_eq!(args[..2], [OsStr::new("commit"), OsStr::new("--allow-empty")]);
+
+      let cmd =
+         build_commit_command("ci: x", ".", false, false, false, false, &CommitDates::default());
+      assert!(!cmd.get_args().any(|arg| arg == "--allow-empty"));
+   }
+
+   #[test]
+   fn test_commit_dates_validation() {
+      assert!(CommitDates::from_flags(Some("2024-01-01T12:00:00+02:00"), None).is_ok());
+      assert!(CommitDates::from_flags(Some("2024-01-01T12:00:00Z"), None).is_ok());
+      assert!(CommitDates::from_flags(Some("yesterday"), None).is_err());
+      assert!(CommitDates::from_flags(None, Some("2024-13-01T00:00:00")).is_err());
+   }
+}
@@ -0,0 +1,566 @@
+//! Issue references picked up by the analysis
+//!
+//! The model lists the `#123`-style references it saw in the user context as
+//! `issue_refs`. When no issue flags were passed on the command line they are
+//! inlined into the body item they relate to, or become `Refs` footers; when
+//! flags were passed they are only cross-checked against them.
+//!
+//! With a `github_token`, the labels of `--fixes` issues can also suggest a
+//! scope through `issue_scope_from_labels`, and `--context-url` folds an
+//! issue or pull request (title, body and the issues it references) into the
+//! context and footers.
+
+use indexmap::IndexMap;
+
+use crate::{
+   api,
+   config::CommitConfig,
+   error::{CommitGenError, Result},
+};
+
+/// Words too common to tie a body item to an issue mention
+const STOPWORDS: &[&str] = &[
+   "about", "after", "also", "because", "before", "closes", "close", "fixes", "fixed", "from",
+   "into", "issue", "refs", "related", "resolves", "see", "should", "that", "their", "there",
+   "this", "when", "where", "which", "while", "with",
+];
+
+/// Leading characters compared when matching words, so `regression` and
+/// `regressed` count as the same keyword
+const STEM_LEN: usize = 5;
+
+/// Issue number from `#123` or `123`; `None` for anything non-numeric
+pub fn issue_number(reference: &str) -> Option<&str> {
+   let number = reference.trim().trim_start_matches('#');
+   (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then_some(number)
+}
+
+/// Whether `text` mentions `#number` (and not a longer number sharing its
+/// prefix)
+fn mentions(text: &str, number: &str) -> bool {
+   let needle = format!("#{number}");
+   text.match_indices(&needle).any(|(pos, _)| {
+      !text[pos + needle.len()..]
+         .chars()
+         .next()
+         .is_some_and(|c| c.is_ascii_digit())
+   })
+}
+
+fn stems(text: &str) -> Vec<String> {
+   text
+      .split(|c: char| !c.is_alphanumeric())
+      .filter(|word| word.len() >= 4 && !word.chars().all(|c| c.is_ascii_digit()))
+      .map(str::to_lowercase)
+      .filter(|word| !STOPWORDS.contains(&word.as_str()))
+      .map(|word| word.chars().take(STEM_LEN).collect())
+      .collect()
+}
+
+/// Keywords from the context sentences that mention `#number`
+fn context_keywords(context: &str, number: &str) -> Vec<String> {
+   context
+      .split(['.', ';', '\n'])
+      .filter(|sentence| mentions(sentence, number))
+      .flat_map(stems)
+      .collect()
+}
+
+/// Append ` (#number)` to a body item, keeping a trailing period last
+fn append_ref(item: &mut String, number: &str) {
+   let reference = format!(" (#{number})");
+   match item.strip_suffix('.') {
+      Some(text) => *item = format!("{text}{reference}."),
+      None => item.push_str(&reference),
+   }
+}
+
+/// Inline the analysis' issue refs into the body items they relate to.
+///
+/// Each reference goes to the body item sharing the most keywords with the
+/// context sentence that mentioned it. References no item matches come back
+/// as `Refs #N` footers. References already present in the body or in
+/// `existing_footers` are skipped.
+pub fn inline_issue_refs(
+   body: &mut [String],
+   issue_refs: &[String],
+   context: Option<&str>,
+   existing_footers: &[String],
+) -> Vec<String> {
+   let mut footers = Vec::new();
+   let mut seen: Vec<&str> = Vec::new();
+
+   for number in issue_refs.iter().filter_map(|r| issue_number(r)) {
+      if seen.contains(&number)
+         || body.iter().any(|item| mentions(item, number))
+         || existing_footers
+            .iter()
+            .any(|footer| mentions(footer, number))
+      {
+         continue;
+      }
+      seen.push(number);
+
+      let keywords = context
+         .map(|ctx| context_keywords(ctx, number))
+         .unwrap_or_default();
+      let best = body
+         .iter()
+         .enumerate()
+         .map(|(idx, item)| {
+            let item_stems = stems(item);
+            let score = keywords.iter().filter(|k| item_stems.contains(k)).count();
+            (idx, score)
+         })
+         .filter(|&(_, score)| score > 0)
+         .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)));
+
+      match best {
+         Some((idx, _)) => append_ref(&mut body[idx], number),
+         None => footers.push(format!("Refs #{number}")),
+      }
+   }
+
+   footers
+}
+
+/// Issue numbers the analysis saw that none of the CLI issue flags name
+pub fn refs_missing_from_cli(issue_refs: &[String], cli_refs: &[&String]) -> Vec<String> {
+   let passed: Vec<&str> = cli_refs.iter().filter_map(|r| issue_number(r)).collect();
+   let mut missing: Vec<String> = Vec::new();
+   for number in issue_refs.iter().filter_map(|r| issue_number(r)) {
+      if !passed.contains(&number) && !missing.iter().any(|m| m == number) {
+         missing.push(number.to_string());
+      }
+   }
+   missing
+}
+
+/// `owner/repo` from a GitHub remote URL (`git@github.com:owner/repo.git`,
+/// `https://github.com/owner/repo`, ...)
+pub fn github_repo_slug(url: &str) -> Option<String> {
+   let url = url.trim().trim_end_matches('/');
+   let path = match url.split_once("://") {
+      Some((_, rest)) => rest.split_once('/')?.1,
+      None => url.split_once(':')?.1,
+   };
+   let mut segments = path.trim_end_matches(".git").rsplit('/');
+   let repo = segments.next().filter(|s| !s.is_empty())?;
+   let owner = segments.next().filter(|s| !s.is_empty())?;
+   Some(format!("{owner}/{repo}"))
+}
+
+/// Label names of a GitHub issue payload
+pub fn issue_labels(issue: &serde_json::Value) -> Vec<String> {
+   issue["labels"]
+      .as_array()
+      .into_iter()
+      .flatten()
+      .filter_map(|label| label["name"].as_str().or_else(|| label.as_str()))
+      .map(str::to_string)
+      .collect()
+}
+
+/// Scope of the first `issue_scope_from_labels` entry whose label is among
+/// `labels` (case-insensitive); map order decides between several
+pub fn scope_from_labels(labels: &[String], map: &IndexMap<String, String>) -> Option<String> {
+   map.iter()
+      .find(|(label, _)| labels.iter().any(|l| l.eq_ignore_ascii_case(label)))
+      .map(|(_, scope)| scope.clone())
+}
+
+/// Fetch issue `number` of `repo` (`owner/repo`) from the GitHub API
+pub fn fetch_issue(
+   repo: &str,
+   number: &str,
+   token: &str,
+   config: &CommitConfig,
+) -> Result<serde_json::Value> {
+   let url =
+      format!("{}/repos/{repo}/issues/{number}", config.github_api_url.trim_end_matches('/'));
+   // Report the GitHub URL, not the model endpoint, when the network is off
+   let client = api::build_client(config).map_err(|err| match err {
+      CommitGenError::NetworkDisabled(_) => CommitGenError::NetworkDisabled(url.clone()),
+      err => err,
+   })?;
+   let response = client
+      .get(&url)
+      .bearer_auth(token)
+      .header("accept", "application/vnd.github+json")
+      .header("user-agent", "llm-git")
+      .send()?;
+
+   let status = response.status();
+   let body = response.text()?;
+   if !status.is_success() {
+      return Err(CommitGenError::ApiError { status: status.as_u16(), body });
+   }
+   Ok(serde_json::from_str(&body)?)
+}
+
+/// Scope suggested by the labels of the `fixes` issues, if a token is
+/// configured and any label maps to one; lookup failures are returned for
+/// the caller to report
+pub fn scope_from_fixed_issues(
+   fixes: &[String],
+   dir: &str,
+   config: &CommitConfig,
+) -> Result<Option<String>> {
+   let Some(token) = config.github_token.as_deref() else {
+      return Ok(None);
+   };
+   let numbers: Vec<&str> = fixes.iter().filter_map(|r| issue_number(r)).collect();
+   if config.issue_scope_from_labels.is_empty() || numbers.is_empty() {
+      return Ok(None);
+   }
+
+   let origin = crate::git::get_origin_url(dir)?;
+   let repo = github_repo_slug(&origin).ok_or_else(|| {
+      CommitGenError::Other(format!("Can't tell the GitHub repository from origin `{origin}`"))
+   })?;
+   for number in numbers {
+      let issue = fetch_issue(&repo, number, token, config)?;
+      if let Some(scope) = scope_from_labels(&issue_labels(&issue), &config.issue_scope_from_labels)
+      {
+         return Ok(Some(scope));
+      }
+   }
+   Ok(None)
+}
+
+/// Longest issue/PR body passed on as context; the rest is cut off
+const URL_CONTEXT_MAX_CHARS: usize = 4000;
+
+/// A GitHub issue or pull request named by its web URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueUrl {
+   /// `owner/repo`
+   pub repo:         String,
+   pub number:       String,
+   pub pull_request: bool,
+}
+
+/// Host (with any port) of an `scheme://host/...` URL, lowercased
+fn url_host(url: &str) -> Option<String> {
+   let (_, rest) = url.trim().split_once("://")?;
+   let host = rest.split(['/', '?', '#']).next()?;
+   (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+/// Whether issue URLs on `host` belong to the configured GitHub: github.com,
+/// or the host of `github_api_url` for GitHub Enterprise (without an `api.`
+/// prefix, if it has one)
+fn is_github_host(host: &str, config: &CommitConfig) -> bool {
+   host == "github.com"
+      || url_host(&config.github_api_url)
+         .is_some_and(|api_host| host == api_host || api_host.strip_prefix("api.") == Some(host))
+}
+
+/// Parse `https://github.com/owner/repo/issues/12` or `.../pull/12` on
+/// github.com or the `github_api_url` host; anything after the number, such
+/// as `/files` or `#issuecomment-1`, is ignored
+pub fn parse_issue_url(url: &str, config: &CommitConfig) -> Option<IssueUrl> {
+   if !is_github_host(&url_host(url)?, config) {
+      return None;
+   }
+   let (_, rest) = url.trim().split_once("://")?;
+   let path = rest.split(['?', '#']).next()?;
+   let mut segments = path.split('/').skip(1);
+   let owner = segments.next().filter(|s| !s.is_empty())?;
+   let repo = segments.next().filter(|s| !s.is_empty())?;
+   let pull_request = match segments.next()? {
+      "issues" => false,
+      "pull" | "pulls" => true,
+      _ => return None,
+   };
+   let number = issue_number(segments.next()?)?;
+   Some(IssueUrl { repo: format!("{owner}/{repo}"), number: number.to_string(), pull_request })
+}
+
+/// Issue numbers referenced in an issue or PR body, grouped by the footer
+/// they become
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReferencedIssues {
+   pub fixes:    Vec<String>,
+   pub closes:   Vec<String>,
+   pub resolves: Vec<String>,
+   /// Mentions without a closing keyword
+   pub refs:     Vec<String>,
+}
+
+/// Issue numbers referenced as `#N` in `text`.
+///
+/// After a closing keyword (`fixes #1`, `Closes: #2`) a number goes to that
+/// keyword's footer, otherwise to `refs`. Cross-repo references
+/// (`owner/repo#3`) and numbers mentioned twice are skipped.
+pub fn referenced_issues(text: &str) -> ReferencedIssues {
+   let mut found: Vec<(String, &str)> = Vec::new();
+   for (pos, _) in text.match_indices('#') {
+      let before = &text[..pos];
+      if before
+         .chars()
+         .next_back()
+         .is_some_and(|c| c.is_alphanumeric() || c == '/' || c == '&')
+      {
+         continue;
+      }
+      let number: String = text[pos + 1..]
+         .chars()
+         .take_while(char::is_ascii_digit)
+         .collect();
+      if number.is_empty() || found.iter().any(|(seen, _)| *seen == number) {
+         continue;
+      }
+      let keyword = before
+         .trim_end_matches(|c: char| c.is_whitespace() || c == ':')
+         .rsplit(|c: char| !c.is_alphabetic())
+         .next()
+         .unwrap_or("")
+         .to_lowercase();
+      let kind = match keyword.as_str() {
+         "fix" | "fixes" | "fixed" => "fixes",
+         "close" | "closes" | "closed" => "closes",
+         "resolve" | "resolves" | "resolved" => "resolves",
+         _ => "refs",
+      };
+      found.push((number, kind));
+   }
+
+   let mut issues = ReferencedIssues::default();
+   for (number, kind) in found {
+      match kind {
+         "fixes" => issues.fixes.push(number),
+         "closes" => issues.closes.push(number),
+         "resolves" => issues.resolves.push(number),
+         _ => issues.refs.push(number),
+      }
+   }
+   issues
+}
+
+/// An issue or pull request read for `--context-url`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlContext {
+   /// Title and (capped) body, for the user context
+   pub text:   String,
+   /// Issues the body references, for the footers
+   pub issues: ReferencedIssues,
+}
+
+/// Context from a GitHub issue payload (pull requests are issues to the API)
+pub fn url_context_from_issue(issue: &serde_json::Value, url: &IssueUrl) -> UrlContext {
+   let kind = if url.pull_request {
+      "Pull request"
+   } else {
+      "Issue"
+   };
+   let title = issue["title"].as_str().unwrap_or("").trim();
+   let body = issue["body"].as_str().unwrap_or("").trim();
+   let mut text = format!("{kind} #{}: {title}", url.number);
+   if !body.is_empty() {
+      text.push_str("\n\n");
+      text.extend(body.chars().take(URL_CONTEXT_MAX_CHARS));
+   }
+
+   let mut issues = referenced_issues(body);
+   // The item itself is the context, not a reference
+   for numbers in [&mut issues.fixes, &mut issues.closes, &mut issues.resolves, &mut issues.refs] {
+      numbers.retain(|number| *number != url.number);
+   }
+   UrlContext { text, issues }
+}
+
+/// Read the issue or pull request at `url`; `None` without a `github_token`
+pub fn fetch_url_context(url: &IssueUrl, config: &CommitConfig) -> Result<Option<UrlContext>> {
+   let Some(token) = config.github_token.as_deref() else {
+      return Ok(None);
+   };
+   let issue = fetch_issue(&url.repo, &url.number, token, config)?;
+   Ok(Some(url_context_from_issue(&issue, url)))
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   fn strings(items: &[&str]) -> Vec<String> {
+      items.iter().map(|s| (*s).to_string()).collect()
+   }
+
+   #[test]
+   fn test_fetch_issue_offline_reports_github_url() {
+      let config = CommitConfig { offline_strict: true, ..CommitConfig::default() };
+      let err = fetch_issue("owner/repo", "12", "token", &config).unwrap_err();
+      assert!(
+         matches!(&err, CommitGenError::NetworkDisabled(url)
+            if url == "https://api.github.com/repos/owner/repo/issues/12"),
+         "{err:?}"
+      );
+   }
+
+   #[test]
+   fn test_refs_inlined_into_matching_items() {
+      let mut body = strings(&[
+         "Added retry logic to the HTTP client.",
+         "Fixed token refresh racing with logout.",
+      ]);
+      let context = "Token refresh race reported in #42. Client retries requested in #7";
+
+      let footers = inline_issue_refs(&mut body, &strings(&["#42", "7"]), Some(context), &[]);
+
+      assert!(footers.is_empty());
+      assert_eq!(
+         body,
+         strings(&[
+            "Added retry logic to the HTTP client (#7).",
+            "Fixed token refresh racing with logout (#42).",
+         ])
+      );
+   }
+
+   #[test]
+   fn test_unmatched_refs_become_footers() {
+      let mut body = strings(&["Renamed config loader."]);
+      let footers = inline_issue_refs(
+         &mut body,
+         &strings(&["#12", "#34", "#12", "not-an-issue"]),
+         Some("Part of the cleanup tracked in #12"),
+         &[],
+      );
+
+      assert_eq!(footers, strings(&["Refs #12", "Refs #34"]));
+      assert_eq!(body, strings(&["Renamed config loader."]));
+   }
+
+   #[test]
+   fn test_refs_already_present_are_skipped() {
+      let mut body = strings(&["Fixed parser crash (#5)."]);
+      let footers =
+         inline_issue_refs(&mut body, &strings(&["#5", "#6", "#60"]), None, &strings(&["Refs #6"]));
+
+      assert_eq!(footers, strings(&["Refs #60"]));
+      assert_eq!(body, strings(&["Fixed parser crash (#5)."]));
+   }
+
+   #[test]
+   fn test_cross_check_reports_refs_missing_from_cli() {
+      let fixes = "#100".to_string();
+      let refs = "200".to_string();
+      let missing =
+         refs_missing_from_cli(&strings(&["#100", "#300", "200", "#300"]), &[&fixes, &refs]);
+      assert_eq!(missing, strings(&["300"]));
+   }
+
+   #[test]
+   fn test_scope_from_issue_labels() {
+      let issue = serde_json::json!({
+         "number": 123,
+         "title": "Parser panics on empty input",
+         "labels": [
+            { "id": 1, "name": "bug", "color": "d73a4a" },
+            { "id": 2, "name": "Area: Parser", "color": "0e8a16" },
+            { "id": 3, "name": "area: cli", "color": "0e8a16" }
+         ]
+      });
+      let labels = issue_labels(&issue);
+      assert_eq!(labels, strings(&["bug", "Area: Parser", "area: cli"]));
+
+      let mut map = IndexMap::new();
+      map.insert("area: cli".to_string(), "cli".to_string());
+      map.insert("area: parser".to_string(), "parser".to_string());
+      // Config order wins over label order
+      assert_eq!(scope_from_labels(&labels, &map).as_deref(), Some("cli"));
+      map.shift_remove("area: cli");
+      assert_eq!(scope_from_labels(&labels, &map).as_deref(), Some("parser"));
+      assert_eq!(scope_from_labels(&strings(&["bug"]), &map), None);
+      assert!(issue_labels(&serde_json::json!({ "number": 1 })).is_empty());
+   }
+
+   #[test]
+   fn test_github_repo_slug() {
+      for url in [
+         "git@github.com:vowstar/llm-git.git",
+         "https://github.com/vowstar/llm-git",
+         "https://github.com/vowstar/llm-git.git/",
+         "ssh://git@github.example.com:22/vowstar/llm-git.git",
+      ] {
+         assert_eq!(github_repo_slug(url).as_deref(), Some("vowstar/llm-git"), "{url}");
+      }
+      assert_eq!(github_repo_slug("/srv/git/project"), None);
+   }
+
+   #[test]
+   fn test_parse_issue_url() {
+      let config = CommitConfig {
+         github_api_url: "https://github.example.com/api/v3".to_string(),
+         ..CommitConfig::default()
+      };
+      let parse_issue_url = |url| parse_issue_url(url, &config);
+      assert_eq!(
+         parse_issue_url("https://github.com/vowstar/llm-git/issues/42"),
+         Some(IssueUrl {
+            repo:         "vowstar/llm-git".to_string(),
+            number:       "42".to_string(),
+            pull_request: false,
+         })
+      );
+      let pr = parse_issue_url("https://github.example.com/team/app/pull/7/files?w=1").unwrap();
+      assert_eq!((pr.repo.as_str(), pr.number.as_str(), pr.pull_request), ("team/app", "7", true));
+      let comment =
+         parse_issue_url("https://github.com/vowstar/llm-git/issues/42#issuecomment-1").unwrap();
+      assert_eq!(comment.number, "42");
+
+      for url in [
+         "github.com/vowstar/llm-git/issues/42",
+         "https://github.com/vowstar/llm-git",
+         "https://github.com/vowstar/llm-git/commit/abc123",
+         "https://github.com/vowstar/llm-git/issues/new",
+         "https://gitlab.com/vowstar/llm-git/issues/42",
+         "https://github.com.evil.example/vowstar/llm-git/issues/42",
+      ] {
+         assert_eq!(parse_issue_url(url), None, "{url}");
+      }
+   }
+
+   #[test]
+   fn test_parse_issue_url_accepts_api_host_without_api_prefix() {
+      let config = CommitConfig {
+         github_api_url: "https://api.ghe.example.com".to_string(),
+         ..CommitConfig::default()
+      };
+      assert!(parse_issue_url("https://ghe.example.com/team/app/issues/3", &config).is_some());
+      // The default API root only admits github.com
+      let url = "https://ghe.example.com/team/app/issues/3";
+      assert_eq!(parse_issue_url(url, &CommitConfig::default()), None);
+   }
+
+   #[test]
+   fn test_url_context_extracts_referenced_issues() {
+      let issue = serde_json::json!({
+         "number": 50,
+         "title": "Retry flaky uploads",
+         "body": "Fixes #12 and closes: #13.\nResolves #14, see #15 and #12 again.\n\
+                  Related to other/repo#99, this PR (#50), and color #fff.",
+      });
+      let url =
+         parse_issue_url("https://github.com/vowstar/llm-git/pull/50", &CommitConfig::default())
+            .unwrap();
+      let context = url_context_from_issue(&issue, &url);
+
+      assert!(
+         context
+            .text
+            .starts_with("Pull request #50: Retry flaky uploads\n\nFixes #12")
+      );
+      assert_eq!(context.issues, ReferencedIssues {
+         fixes:    strings(&["12"]),
+         closes:   strings(&["13"]),
+         resolves: strings(&["14"]),
+         refs:     strings(&["15"]),
+      });
+
+      let empty = url_context_from_issue(&serde_json::json!({ "title": "Bug" }), &url);
+      assert_eq!(empty.text, "Pull request #50: Bug");
+      assert_eq!(empty.issues, ReferencedIssues::default());
+   }
+}
@@ -3,17 +3,27 @@
 //! This library provides functionality for analyzing git diffs and generating
 //! conventional commit messages using Claude AI via `LiteLLM`.
 pub mod analysis;
+pub mod anonymize;
 pub mod api;
 pub mod changelog;
 pub mod compose;
+pub mod confidence;
 pub mod config;
+pub mod diagnostics;
 pub mod diff;
 pub mod error;
+pub mod exclude;
 pub mod git;
+pub mod issues;
+pub mod mailmap;
 pub mod map_reduce;
+pub mod models;
 pub mod normalization;
 pub mod patch;
+pub mod rate_limit;
+pub mod release;
 pub mod repo;
+pub mod stats;
 pub mod style;
 pub mod templates;
 pub mod testing;
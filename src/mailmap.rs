@@ -0,0 +1,425 @@
+//! `.mailmap` support
+//!
+//! Maps the raw author/committer identities recorded in commits to the
+//! canonical ones listed in the repository's `.mailmap`, following git's
+//! rules (see `gitmailmap(5)`), plus any `rewrite_mailmap` pairs from the
+//! config.
+
+use std::process::Command;
+
+use crate::{
+   error::{CommitGenError, Result},
+   types::CommitMetadata,
+};
+
+/// One `.mailmap` line
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MailmapEntry {
+   proper_name:  Option<String>,
+   proper_email: Option<String>,
+   /// Only set for `Proper Name <proper@email> Commit Name <commit@email>`
+   commit_name:  Option<String>,
+   commit_email: String,
+}
+
+/// Parsed `.mailmap`
+#[derive(Debug, Clone, Default)]
+pub struct Mailmap {
+   entries: Vec<MailmapEntry>,
+}
+
+/// Split `Name <email>` off the front of `text`, returning the (possibly
+/// empty) name, the email, and the remainder after `>`
+fn split_name_email(text: &str) -> Option<(Option<String>, String, &str)> {
+   let open = text.find('<')?;
+   let close = open + text[open..].find('>')?;
+   let name = text[..open].trim();
+   let email = text[open + 1..close].trim();
+   let name = (!name.is_empty()).then(|| name.to_string());
+   Some((name, email.to_string(), &text[close + 1..]))
+}
+
+/// One side of a `rewrite_mailmap` pair: `email` or `Name <email>`
+fn parse_identity(text: &str) -> Option<(Option<String>, String)> {
+   let text = text.trim();
+   if text.contains('<') {
+      let (name, email, rest) = split_name_email(text)?;
+      (rest.trim().is_empty() && !email.is_empty()).then_some((name, email))
+   } else {
+      (!text.is_empty() && !text.contains(char::is_whitespace)).then(|| (None, text.to_string()))
+   }
+}
+
+impl Mailmap {
+   /// Parse `.mailmap` content. Handles all four line forms:
+   ///
+   /// ```text
+   /// Proper Name <commit@email>
+   /// <proper@email> <commit@email>
+   /// Proper Name <proper@email> <commit@email>
+   /// Proper Name <proper@email> Commit Name <commit@email>
+   /// ```
+   ///
+   /// Lines starting with `#` are comments; malformed lines are ignored.
+   pub fn parse(content: &str) -> Self {
+      let mut entries = Vec::new();
+
+      for line in content.lines() {
+         let line = line.trim();
+         if line.is_empty() || line.starts_with('#') {
+            continue;
+         }
+         let Some((name1, email1, rest)) = split_name_email(line) else {
+            continue;
+         };
+
+         let entry = match split_name_email(rest) {
+            Some((name2, email2, _)) => MailmapEntry {
+               proper_name:  name1,
+               proper_email: Some(email1),
+               commit_name:  name2,
+               commit_email: email2,
+            },
+            None => MailmapEntry {
+               proper_name:  name1,
+               proper_email: None,
+               commit_name:  None,
+               commit_email: email1,
+            },
+         };
+         entries.push(entry);
+      }
+
+      Self { entries }
+   }
+
+   /// Load `.mailmap` from the root of the repository containing `dir`.
+   /// A missing file yields an empty map.
+   pub fn load(dir: &str) -> Result<Self> {
+      let output = Command::new("git")
+         .args(["rev-parse", "--show-toplevel"])
+         .current_dir(dir)
+         .output()
+         .map_err(|e| CommitGenError::GitError(format!("Failed to run git rev-parse: {e}")))?;
+
+      if !output.status.success() {
+         let stderr = String::from_utf8_lossy(&output.stderr);
+         return Err(CommitGenError::GitError(format!("git rev-parse failed: {stderr}")));
+      }
+
+      let root = String::from_utf8_lossy(&output.stdout);
+      match std::fs::read_to_string(std::path::Path::new(root.trim()).join(".mailmap")) {
+         Ok(content) => Ok(Self::parse(&content)),
+         Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+         Err(e) => Err(e.into()),
+      }
+   }
+
+   /// Build a map from `rewrite_mailmap` `(from, to)` pairs, each side an
+   /// `email` or `Name <email>`. Names are only replaced when `to` gives
+   /// one; a named `from` only matches commits with that name.
+   pub fn from_pairs(pairs: &[(String, String)]) -> Result<Self> {
+      let entries = pairs
+         .iter()
+         .map(|(from, to)| {
+            let (Some((commit_name, commit_email)), Some((proper_name, proper_email))) =
+               (parse_identity(from), parse_identity(to))
+            else {
+               return Err(CommitGenError::ValidationError(format!(
+                  "rewrite_mailmap entry [\"{from}\", \"{to}\"]: expected `email` or `Name \
+                   <email>` on both sides"
+               )));
+            };
+            Ok(MailmapEntry {
+               proper_name,
+               proper_email: Some(proper_email),
+               commit_name,
+               commit_email,
+            })
+         })
+         .collect::<Result<_>>()?;
+      Ok(Self { entries })
+   }
+
+   /// Add `other`'s entries after this map's, so they take precedence
+   pub fn extend(&mut self, other: Self) {
+      self.entries.extend(other.entries);
+   }
+
+   pub const fn is_empty(&self) -> bool {
+      self.entries.is_empty()
+   }
+
+   /// Canonical `(name, email)` for a raw identity.
+   ///
+   /// An entry naming both the commit name and email wins over email-only
+   /// entries; email-only entries for the same address combine, so one line
+   /// can fix the name and another the email. Matching is case-insensitive.
+   pub fn resolve(&self, name: &str, email: &str) -> (String, String) {
+      let email_matches = |entry: &&MailmapEntry| entry.commit_email.eq_ignore_ascii_case(email);
+
+      let exact = self
+         .entries
+         .iter()
+         .filter(email_matches)
+         .rev()
+         .find(|entry| {
+            entry
+               .commit_name
+               .as_deref()
+               .is_some_and(|commit_name| commit_name.eq_ignore_ascii_case(name))
+         });
+
+      let (mut proper_name, mut proper_email) = (None, None);
+      if let Some(entry) = exact {
+         proper_name = entry.proper_name.as_deref();
+         proper_email = entry.proper_email.as_deref();
+      } else {
+         for entry in self
+            .entries
+            .iter()
+            .filter(email_matches)
+            .filter(|entry| entry.commit_name.is_none())
+         {
+            proper_name = entry.proper_name.as_deref().or(proper_name);
+            proper_email = entry.proper_email.as_deref().or(proper_email);
+         }
+      }
+
+      (proper_name.unwrap_or(name).to_string(), proper_email.unwrap_or(email).to_string())
+   }
+
+   /// Rewrite a commit's author and committer to their canonical identities
+   pub fn apply(&self, commit: &mut CommitMetadata) {
+      (commit.author_name, commit.author_email) =
+         self.resolve(&commit.author_name, &commit.author_email);
+      (commit.committer_name, commit.committer_email) =
+         self.resolve(&commit.committer_name, &commit.committer_email);
+   }
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   const FIXTURE: &str = "\
+# Form 1: fix the name only
+Jane Doe <jane@example.com>
+# Form 2: fix the email only
+<bob@example.com> <bobby@old.example>
+# Form 3: fix both, matched by email
+Carol Smith <carol@example.com> <carol@laptop.local>
+# Form 4: fix both, matched by name and email
+Dave Jones <dave@example.com> dj <shared@example.com>
+Erin Poe <erin@example.com> erin <shared@example.com>
+";
+
+   #[test]
+   fn test_parse_all_forms() {
+      let map = Mailmap::parse(FIXTURE);
+      assert_eq!(map.entries.len(), 5);
+      assert_eq!(map.entries[0], MailmapEntry {
+         proper_name:  Some("Jane Doe".to_string()),
+         proper_email: None,
+         commit_name:  None,
+         commit_email: "jane@example.com".to_string(),
+      });
+      assert_eq!(map.entries[1].proper_name, None);
+      assert_eq!(map.entries[1].proper_email.as_deref(), Some("bob@example.com"));
+      assert_eq!(map.entries[3].commit_name.as_deref(), Some("dj"));
+   }
+
+   #[test]
+   fn test_resolve_name_only() {
+      let map = Mailmap::parse(FIXTURE);
+      assert_eq!(
+         map.resolve("jdoe", "JANE@example.com"),
+         ("Jane Doe".to_string(), "JANE@example.com".to_string())
+      );
+   }
+
+   #[test]
+   fn test_resolve_email_only() {
+      let map = Mailmap::parse(FIXTURE);
+      assert_eq!(
+         map.resolve("Bob", "bobby@old.example"),
+         ("Bob".to_string(), "bob@example.com".to_string())
+      );
+   }
+
+   #[test]
+   fn test_resolve_name_and_email_by_email() {
+      let map = Mailmap::parse(FIXTURE);
+      assert_eq!(
+         map.resolve("carol", "carol@laptop.local"),
+         ("Carol Smith".to_string(), "carol@example.com".to_string())
+      );
+   }
+
+   #[test]
+   fn test_resolve_by_name_and_email() {
+      let map = Mailmap::parse(FIXTURE);
+      assert_eq!(
+         map.resolve("DJ", "shared@example.com"),
+         ("Dave Jones".to_string(), "dave@example.com".to_string())
+      );
+      assert_eq!(
+         map.resolve("erin", "shared@example.com"),
+         ("Erin Poe".to_string(), "erin@example.com".to_string())
+      );
+      // Same email, unknown name: untouched
+      assert_eq!(
+         map.resolve("someone", "shared@example.com"),
+         ("someone".to_string(), "shared@example.com".to_string())
+      );
+   }
+
+   #[test]
+   fn test_email_only_entries_combine() {
+      let map = Mailmap::parse("Frank <f@old.example>\n<frank@example.com> <f@old.example>\n");
+      assert_eq!(
+         map.resolve("f", "f@old.example"),
+         ("Frank".to_string(), "frank@example.com".to_string())
+      );
+   }
+
+   #[test]
+   fn test_parse_ignores_comments_and_malformed_lines() {
+      let map = Mailmap::parse("# comment\n\nno email here\nName <unterminated\n");
+      assert!(map.is_empty());
+   }
+
+   fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+      items
+         .iter()
+         .map(|(from, to)| ((*from).to_string(), (*to).to_string()))
+         .collect()
+   }
+
+   #[test]
+   fn test_pairs_keep_names_unless_mapped() {
+      let map = Mailmap::from_pairs(&pairs(&[
+         ("me@old.example", "me@new.example"),
+         ("Old Nick <nick@old.example>", "Nick Moss <nick@example.com>"),
+      ]))
+      .unwrap();
+
+      assert_eq!(
+         map.resolve("Me", "ME@old.example"),
+         ("Me".to_string(), "me@new.example".to_string())
+      );
+      assert_eq!(
+         map.resolve("Old Nick", "nick@old.example"),
+         ("Nick Moss".to_string(), "nick@example.com".to_string())
+      );
+      // A named `from` only matches that name
+      assert_eq!(
+         map.resolve("nick", "nick@old.example"),
+         ("nick".to_string(), "nick@old.example".to_string())
+      );
+   }
+
+   #[test]
+   fn test_pairs_take_precedence_over_mailmap_file() {
+      let mut map = Mailmap::parse("<bob@example.com> <bobby@old.example>\n");
+      map.extend(Mailmap::from_pairs(&pairs(&[("bobby@old.example", "bob@new.example")])).unwrap());
+      assert_eq!(map.resolve("Bob", "bobby@old.example").1, "bob@new.example");
+   }
+
+   #[test]
+   fn test_malformed_pairs_are_rejected() {
+      for (from, to) in [("", "me@new"), ("me@old", "Name <unterminated"), ("two words", "x@y")] {
+         let err = Mailmap::from_pairs(&pairs(&[(from, to)])).unwrap_err();
+         assert!(matches!(err, CommitGenError::ValidationError(_)), "{from} -> {to}");
+      }
+   }
+
+   #[test]
+   fn test_rewrite_merges_email_variants_from_config_pairs() {
+      use crate::git::{get_commit_list, get_commit_metadata, rewrite_history};
+
+      let git = |dir: &std::path::Path, args: &[&str]| {
+         let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+         assert!(output.status.success(), "git {args:?} failed");
+         String::from_utf8_lossy(&output.stdout).trim().to_string()
+      };
+
+      let dir = std::env::temp_dir().join(format!("lgit-mailmap-pairs-{}", std::process::id()));
+      let _ = std::fs::remove_dir_all(&dir);
+      std::fs::create_dir_all(&dir).unwrap();
+      git(&dir, &["init", "-q"]);
+      git(&dir, &["config", "user.name", "Me"]);
+      for (file, email) in [("a.txt", "me@old.example"), ("b.txt", "me@new.example")] {
+         std::fs::write(dir.join(file), file).unwrap();
+         git(&dir, &["add", "-A"]);
+         git(&dir, &["-c", &format!("user.email={email}"), "commit", "-qm", file]);
+      }
+
+      let dir_str = dir.to_str().unwrap();
+      let map = Mailmap::from_pairs(&pairs(&[("me@old.example", "me@new.example")])).unwrap();
+      let commits: Vec<CommitMetadata> = get_commit_list(None, dir_str)
+         .unwrap()
+         .iter()
+         .map(|hash| {
+            let mut commit = get_commit_metadata(hash, dir_str).unwrap();
+            map.apply(&mut commit);
+            commit
+         })
+         .collect();
+      let messages = vec!["chore: added a".to_string(), "chore: added b".to_string()];
+      rewrite_history(&commits, &messages, dir_str).unwrap();
+
+      let identities = git(&dir, &["log", "--no-mailmap", "--format=%an <%ae>|%cn <%ce>"]);
+      assert_eq!(
+         identities,
+         "Me <me@new.example>|Me <me@new.example>\n"
+            .repeat(2)
+            .trim_end()
+      );
+      let _ = std::fs::remove_dir_all(&dir);
+   }
+
+   #[test]
+   fn test_rewrite_applies_mailmap_to_author() {
+      use crate::git::{get_commit_metadata, rewrite_history};
+
+      let git = |dir: &std::path::Path, args: &[&str]| {
+         let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+         assert!(output.status.success(), "git {args:?} failed");
+         String::from_utf8_lossy(&output.stdout).trim().to_string()
+      };
+
+      let dir = std::env::temp_dir().join(format!("lgit-mailmap-{}", std::process::id()));
+      let _ = std::fs::remove_dir_all(&dir);
+      std::fs::create_dir_all(&dir).unwrap();
+      git(&dir, &["init", "-q"]);
+      git(&dir, &["config", "user.email", "carol@laptop.local"]);
+      git(&dir, &["config", "user.name", "carol"]);
+      std::fs::write(dir.join(".mailmap"), FIXTURE).unwrap();
+      git(&dir, &["add", "-A"]);
+      git(&dir, &["commit", "-qm", "add mailmap"]);
+
+      let dir_str = dir.to_str().unwrap();
+      let hash = git(&dir, &["rev-parse", "HEAD"]);
+      let mut commit = get_commit_metadata(&hash, dir_str).unwrap();
+      assert_eq!(commit.author_name, "carol");
+
+      Mailmap::load(dir_str).unwrap().apply(&mut commit);
+      rewrite_history(&[commit], &["chore: added mailmap".to_string()], dir_str).unwrap();
+
+      // Read the raw object: `git log` would apply the mailmap itself
+      let raw = git(&dir, &["cat-file", "commit", "HEAD"]);
+      assert!(raw.contains("\nauthor Carol Smith <carol@example.com> "), "{raw}");
+      assert!(raw.contains("\ncommitter Carol Smith <carol@example.com> "), "{raw}");
+      assert!(raw.ends_with("chore: added mailmap"), "{raw}");
+      let _ = std::fs::remove_dir_all(&dir);
+   }
+}
@@ -1,23 +1,35 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use analysis::extract_scope_candidates;
+use analysis::{
+   ChangeProfile, ScopeAnalyzer, classify_change_profile, extract_scope_candidates, get_numstat,
+};
 use api::{
-   AnalysisContext, fallback_summary, generate_analysis_with_map_reduce,
-   generate_summary_from_analysis,
+   AnalysisContext, GeneratedSummary, fallback_summary, generate_analysis_with_map_reduce,
+   generate_conventional_analysis, generate_fast_commit, generate_split_analysis,
+   generate_summary_from_analysis, retry_context, should_use_fast_mode,
 };
 use arboard::Clipboard;
-use clap::Parser;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, parser::ValueSource};
 use compose::run_compose_mode;
-use config::CommitConfig;
-use diff::smart_truncate_diff;
+use confidence::ConfidenceSignals;
+use config::{CliDefaults, CommitConfig, MergeSubject};
+use diff::{IGNORE_HUNK_MARKER, drop_ignored_hunks, elide_long_hunks, smart_truncate_diff};
 use error::{CommitGenError, Result};
 use git::{
-   get_common_scopes, get_git_diff, get_git_stat, get_recent_commits, git_commit, git_push,
+   CommitDates, ensure_git_available, generated_file_patterns, get_common_scopes, get_git_diff,
+   get_git_stat, get_range_subjects, get_recent_commits, git_commit, git_push, is_commit_range,
 };
 use llm_git::{style, tokens::create_token_counter, *};
-use normalization::{format_commit_message, post_process_commit_message};
-use types::{Args, ConventionalCommit, Mode, resolve_model_name};
-use validation::{check_type_scope_consistency, validate_commit_message};
+use normalization::{commit_subjects_to_body, format_commit_message, post_process_commit_message};
+use stats::RunTimer;
+use types::{
+   AnalysisDetail, Args, CommitSummary, CommitType, ConventionalAnalysis, ConventionalCommit, Mode,
+   Scope, model_aliases_for, resolve_model_name,
+};
+use validation::{
+   apply_type_scope_policy, check_scope_not_filename, find_template_placeholder,
+   is_vacuous_message, validate_commit_message,
+};
 
 /// Save debug output to the specified directory
 fn save_debug_output(dir: &Path, filename: &str, content: &str) -> Result<()> {
@@ -27,6 +39,44 @@ fn save_debug_output(dir: &Path, filename: &str, content: &str) -> Result<()> {
    Ok(())
 }
 
+/// Write the analysis a message was built from as JSON (`--save-analysis`)
+fn save_analysis(path: &Path, analysis: &ConventionalAnalysis) -> Result<()> {
+   if let Some(parent) = path
+      .parent()
+      .filter(|parent| !parent.as_os_str().is_empty())
+   {
+      std::fs::create_dir_all(parent)?;
+   }
+   std::fs::write(path, serde_json::to_string_pretty(analysis)?)?;
+   Ok(())
+}
+
+/// Apply `--prompt-var` and `--prompt-append-*` to every prompt of this run,
+/// recording them in the debug output so the run can be reproduced
+fn apply_prompt_experiment(args: &Args) -> Result<()> {
+   let mut experiment = templates::PromptExperiment {
+      append_analysis: args.prompt_append_analysis.clone(),
+      append_summary: args.prompt_append_summary.clone(),
+      ..Default::default()
+   };
+   for assignment in &args.prompt_var {
+      experiment.add_var(assignment)?;
+   }
+   if experiment.is_empty() {
+      return Ok(());
+   }
+
+   if let Some(debug_dir) = &args.debug_output {
+      save_debug_output(
+         debug_dir,
+         "prompt_experiment.json",
+         &serde_json::to_string_pretty(&experiment)?,
+      )?;
+   }
+   templates::set_prompt_experiment(experiment);
+   Ok(())
+}
+
 /// Run test mode for fixture-based testing
 fn run_test_mode(args: &Args, config: &CommitConfig) -> Result<()> {
    use llm_git::testing::{self, TestRunner, TestSummary};
@@ -160,7 +210,7 @@ fn add_fixture(
          let style_patterns = git::extract_style_patterns(&commits);
          let style_str = style_patterns.map(|p| p.format_for_prompt());
 
-         let scopes = git::get_common_scopes(repo_dir, 100)
+         let scopes = git::get_common_scopes(repo_dir, config)
             .ok()
             .filter(|s| !s.is_empty())
             .map(|scopes| {
@@ -184,11 +234,12 @@ fn add_fixture(
    let fixture = Fixture {
       name:   name.to_string(),
       meta:   FixtureMeta {
-         source_repo:   repo_dir.to_string(),
-         source_commit: commit_hash.to_string(),
-         description:   format!("Fixture from commit {commit_hash}"),
-         captured_at:   chrono::Utc::now().to_rfc3339(),
-         tags:          vec![],
+         source_repo:       repo_dir.to_string(),
+         source_commit:     commit_hash.to_string(),
+         description:       format!("Fixture from commit {commit_hash}"),
+         captured_at:       chrono::Utc::now().to_rfc3339(),
+         tags:              vec![],
+         prompt_experiment: templates::prompt_experiment(),
       },
       input:  FixtureInput {
          diff,
@@ -223,14 +274,16 @@ fn add_fixture(
 }
 
 /// Apply CLI overrides to config
-fn apply_cli_overrides(config: &mut CommitConfig, args: &Args) {
+fn apply_cli_overrides(config: &mut CommitConfig, args: &Args) -> Result<()> {
    if let Some(model) = &args.model {
       let resolved = resolve_model_name(model);
       config.model = resolved;
    }
    if let Some(temp) = args.temperature {
       if (0.0..=1.0).contains(&temp) {
+         // An explicit temperature applies to every model
          config.temperature = temp;
+         config.model_temperatures.clear();
       } else {
          eprintln!(
             "Warning: Temperature {} out of range [0.0, 1.0], using default {}",
@@ -241,6 +294,255 @@ fn apply_cli_overrides(config: &mut CommitConfig, args: &Args) {
    if args.exclude_old_message {
       config.exclude_old_message = true;
    }
+   if args.no_stage {
+      config.auto_stage = false;
+   }
+   if args.no_fast {
+      config.fast_mode_threshold_chars = 0;
+   }
+   if args.explain {
+      config.capture_rationale = true;
+   }
+   if let Some(url) = &args.base_url {
+      config.api_base_url = parse_base_url(url)?;
+   }
+   let regenerate =
+      args.regenerate || std::env::var("LLM_GIT_REGENERATE").is_ok_and(|v| v == "1" || v == "true");
+   if regenerate {
+      let unit = random_unit();
+      config.temperature =
+         jittered_temperature(config.temperature, config.temperature_jitter, unit);
+      for temperature in config.model_temperatures.values_mut() {
+         *temperature = jittered_temperature(*temperature, config.temperature_jitter, unit);
+      }
+   }
+   Ok(())
+}
+
+/// Fill flags the command line didn't pass from `[cli_defaults]`. A default
+/// that conflicts with a passed flag (or an earlier default) is dropped with a
+/// warning, so the command line wins.
+fn apply_cli_defaults(
+   args: &mut Args,
+   defaults: &CliDefaults,
+   matches: &ArgMatches,
+) -> Vec<String> {
+   let command = Args::command();
+   let passed: Vec<&str> = command
+      .get_arguments()
+      .map(|arg| arg.get_id().as_str())
+      .filter(|id| matches.value_source(id) == Some(ValueSource::CommandLine))
+      .collect();
+   let mut enabled = passed.clone();
+   let mut warnings = Vec::new();
+
+   let flags = [
+      ("copy", defaults.copy, &mut args.copy),
+      ("dry_run", defaults.dry_run, &mut args.dry_run),
+      ("push", defaults.push, &mut args.push),
+      ("quiet", defaults.quiet, &mut args.quiet),
+      ("sign", defaults.sign, &mut args.sign),
+      ("signoff", defaults.signoff, &mut args.signoff),
+      ("skip_hooks", defaults.skip_hooks, &mut args.skip_hooks),
+      ("no_stage", defaults.no_stage, &mut args.no_stage),
+      ("no_changelog", defaults.no_changelog, &mut args.no_changelog),
+      ("yes", defaults.yes, &mut args.yes),
+      ("compose", defaults.compose, &mut args.compose),
+   ];
+   for (id, value, flag) in flags {
+      let Some(value) = value else {
+         continue;
+      };
+      if passed.contains(&id) {
+         continue;
+      }
+      if value {
+         if let Some(other) = enabled
+            .iter()
+            .find(|other| args_conflict(&command, id, other))
+         {
+            let source = if passed.contains(other) {
+               let long = command
+                  .get_arguments()
+                  .find(|arg| arg.get_id() == *other)
+                  .and_then(|arg| arg.get_long())
+                  .unwrap_or(other);
+               format!("--{long}")
+            } else {
+               format!("cli_defaults.{other}")
+            };
+            warnings.push(format!("Ignoring cli_defaults.{id}: conflicts with {source}"));
+            continue;
+         }
+         enabled.push(id);
+      }
+      *flag = value;
+   }
+
+   if let Some(model) = &defaults.model
+      && !passed.contains(&"model")
+   {
+      args.model = Some(model.clone());
+   }
+   warnings
+}
+
+/// Whether clap declares the two args as conflicting, in either direction
+fn args_conflict(command: &Command, a: &str, b: &str) -> bool {
+   let conflicts_with = |id: &str, other: &str| {
+      command
+         .get_arguments()
+         .find(|arg| arg.get_id() == id)
+         .is_some_and(|arg| {
+            command
+               .get_arg_conflicts_with(arg)
+               .iter()
+               .any(|conflict| conflict.get_id() == other)
+         })
+   };
+   conflicts_with(a, b) || conflicts_with(b, a)
+}
+
+/// Random number in [0, 1), from std's randomly keyed hasher
+fn random_unit() -> f64 {
+   use std::hash::{BuildHasher, RandomState};
+   let bits = RandomState::new().hash_one(std::time::SystemTime::now());
+   (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// `base` moved by up to `jitter` either way, where `unit` in [0, 1) picks
+/// the offset; kept within the valid [0, 1] temperature range
+fn jittered_temperature(base: f32, jitter: f32, unit: f64) -> f32 {
+   let offset = (unit.mul_add(2.0, -1.0) as f32) * jitter.max(0.0);
+   (base + offset).clamp(0.0, 1.0)
+}
+
+/// Validate a `--base-url` value, assuming `https://` when it has no scheme
+fn parse_base_url(url: &str) -> Result<String> {
+   let url = url.trim();
+   let url = if url.contains("://") {
+      url.to_string()
+   } else {
+      style::warn(&format!("--base-url '{url}' has no scheme; assuming https://{url}"));
+      format!("https://{url}")
+   };
+
+   match reqwest::Url::parse(&url) {
+      Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => {
+         Ok(url.trim_end_matches('/').to_string())
+      },
+      Ok(_) => {
+         Err(CommitGenError::ValidationError(format!("--base-url '{url}' must be an http(s) URL")))
+      },
+      Err(e) => {
+         Err(CommitGenError::ValidationError(format!("--base-url '{url}' is not a valid URL: {e}")))
+      },
+   }
+}
+
+/// Put the `--context-preset` snippets ahead of the trailing context
+fn apply_context_presets(args: &mut Args, config: &CommitConfig) -> Result<()> {
+   let mut context = Vec::with_capacity(args.context_preset.len() + args.context.len());
+   for name in &args.context_preset {
+      let Some(text) = config.context_presets.get(name) else {
+         let available = if config.context_presets.is_empty() {
+            "none configured".to_string()
+         } else {
+            config
+               .context_presets
+               .keys()
+               .map(String::as_str)
+               .collect::<Vec<_>>()
+               .join(", ")
+         };
+         return Err(CommitGenError::ValidationError(format!(
+            "Unknown context preset '{name}' (available: {available})"
+         )));
+      };
+      context.push(text.clone());
+   }
+   context.append(&mut args.context);
+   args.context = context;
+   Ok(())
+}
+
+/// Fold the `--context-url` issue or pull request into the context, and the
+/// issues it references into the footer flags; a failed lookup only warns
+fn apply_context_url(args: &mut Args, config: &CommitConfig) -> Result<()> {
+   let Some(url) = args.context_url.as_deref() else {
+      return Ok(());
+   };
+   let url = issues::parse_issue_url(url, config).ok_or_else(|| {
+      CommitGenError::ValidationError(format!(
+         "--context-url {url}: not an issue or pull request URL on github.com or the \
+          github_api_url host"
+      ))
+   })?;
+
+   let context = match issues::fetch_url_context(&url, config) {
+      Ok(Some(context)) => context,
+      Ok(None) => {
+         style::warn("--context-url needs a github_token (or LLM_GIT_GITHUB_TOKEN); ignoring it");
+         return Ok(());
+      },
+      Err(err) => {
+         style::warn(&format!("Couldn't read {}#{}: {err}", url.repo, url.number));
+         return Ok(());
+      },
+   };
+
+   args.context.push(context.text);
+   // Issues already passed on the command line keep their footer
+   let passed: Vec<String> = [&args.fixes, &args.closes, &args.resolves, &args.refs]
+      .into_iter()
+      .flatten()
+      .filter_map(|reference| issues::issue_number(reference))
+      .map(str::to_string)
+      .collect();
+   let issues::ReferencedIssues { fixes, closes, resolves, refs } = context.issues;
+   for (flag, numbers) in [
+      (&mut args.fixes, fixes),
+      (&mut args.closes, closes),
+      (&mut args.resolves, resolves),
+      (&mut args.refs, refs),
+   ] {
+      flag.extend(
+         numbers
+            .into_iter()
+            .filter(|number| !passed.contains(number)),
+      );
+   }
+   Ok(())
+}
+
+/// Tell the model a `--mode merge` diff is a conflict resolution, naming the
+/// conflicted files; appended to the user context
+fn apply_merge_context(args: &mut Args) -> Result<()> {
+   let files = git::get_merge_conflicted_files(&args.dir)?;
+   let conflicts = if files.is_empty() {
+      String::new()
+   } else {
+      format!(" Conflicted files: {}.", files.join(", "))
+   };
+   args.context.push(format!(
+      "This commit concludes a merge and the diff is its conflict resolution.{conflicts} Use the \
+       type of the dominant change and describe the key resolution decisions (which side was \
+       kept, or how both were combined) in the body."
+   ));
+   Ok(())
+}
+
+/// Final `--mode merge` message: git's `Merge branch ...` subject above the
+/// generated message, or the generated message alone
+fn with_merge_subject(
+   formatted_message: &str,
+   merge_subject: Option<&str>,
+   policy: MergeSubject,
+) -> String {
+   match (policy, merge_subject) {
+      (MergeSubject::Keep, Some(subject)) => format!("{subject}\n\n{formatted_message}"),
+      _ => formatted_message.to_string(),
+   }
 }
 
 /// Load config from args or default
@@ -252,11 +554,161 @@ fn load_config_from_args(args: &Args) -> Result<CommitConfig> {
    }
 }
 
+/// Config file `--check-config` and `--init-config` act on: `--config`, then
+/// `LLM_GIT_CONFIG`, then the default location
+fn config_file_path(args: &Args) -> Result<PathBuf> {
+   match (&args.config, std::env::var("LLM_GIT_CONFIG")) {
+      (Some(path), _) => Ok(path.clone()),
+      (None, Ok(custom)) => Ok(custom.into()),
+      (None, Err(_)) => CommitConfig::default_config_path(),
+   }
+}
+
+/// Strictly check the config file and report deprecated or unknown keys
+fn run_check_config(args: &Args) -> Result<()> {
+   let path = config_file_path(args)?;
+
+   if !path.exists() {
+      println!("{} No config file at {} (using defaults)", style::info("›"), path.display());
+      return Ok(());
+   }
+
+   let check = CommitConfig::check_file(&path)?;
+   println!("{} Checked {}", style::info("›"), path.display());
+
+   for key in &check.deprecated {
+      println!(
+         "  {} `{}` is deprecated, use `{}`",
+         style::warning("deprecated:"),
+         key.old,
+         key.new
+      );
+   }
+   for key in &check.unknown {
+      println!("  {} `{key}` is not a config key", style::error("unknown:"));
+   }
+
+   if !check.unknown.is_empty() {
+      return Err(CommitGenError::ValidationError(format!(
+         "{} unknown config key(s) in {}",
+         check.unknown.len(),
+         path.display()
+      )));
+   }
+   if check.deprecated.is_empty() {
+      println!("{}", style::success("Config OK"));
+   }
+   Ok(())
+}
+
+/// Write the commented default config for `--init-config`
+fn run_init_config(args: &Args) -> Result<()> {
+   let path = config_file_path(args)?;
+   if path.exists() && !args.force {
+      return Err(CommitGenError::ValidationError(format!(
+         "Config file {} already exists; pass --force to overwrite it",
+         path.display()
+      )));
+   }
+
+   let contents = CommitConfig::default_config_toml()?;
+   if let Some(parent) = path.parent()
+      && !parent.as_os_str().is_empty()
+   {
+      std::fs::create_dir_all(parent)?;
+   }
+   std::fs::write(&path, contents)?;
+   println!("{} Wrote {}", style::info("›"), path.display());
+   Ok(())
+}
+
+/// List the endpoint's models for `--model-list`, flagging configured models
+/// it doesn't offer
+fn run_model_list(config: &CommitConfig) -> Result<()> {
+   let available = models::fetch_models(config)?;
+   println!("{} {} models at {}", style::info("›"), available.len(), models::models_url(config));
+   for id in &available {
+      let aliases = model_aliases_for(id.rsplit('/').next().unwrap_or(id));
+      if aliases.is_empty() {
+         println!("  {id}");
+      } else {
+         println!("  {id} {}", style::dim(&format!("({})", aliases.join(", "))));
+      }
+   }
+
+   let missing = models::missing_models(config, &available);
+   for (field, model) in &missing {
+      let hint = models::closest_model(model, &available)
+         .map(|id| format!(", did you mean {id}?"))
+         .unwrap_or_default();
+      println!("  {} {field} = {model} is not offered{hint}", style::error("missing:"));
+   }
+   if !missing.is_empty() {
+      return Err(CommitGenError::ValidationError(format!(
+         "{} configured model(s) not offered by the endpoint",
+         missing.len()
+      )));
+   }
+   Ok(())
+}
+
+/// Print the scope frequency table for `--scopes`
+fn run_scopes(args: &Args, config: &CommitConfig) -> Result<()> {
+   let depth = args.depth.unwrap_or(config.scope_history_depth);
+   let history = git::get_scope_history(&args.dir, depth, config)?;
+
+   if args.json {
+      let scopes: Vec<serde_json::Value> = history
+         .scopes
+         .iter()
+         .map(|(scope, count)| serde_json::json!({ "scope": scope, "count": count }))
+         .collect();
+      let json = serde_json::json!({
+         "depth": depth,
+         "commits": history.commits,
+         "scoped": history.scoped,
+         "unscoped": history.commits - history.scoped,
+         "scoped_pct": history.scoped_pct(),
+         "scopes": scopes,
+      });
+      println!("{}", serde_json::to_string_pretty(&json)?);
+      return Ok(());
+   }
+
+   println!(
+      "{} {} commits: {} scoped ({:.0}%), {} unscoped",
+      style::info("›"),
+      history.commits,
+      history.scoped,
+      history.scoped_pct(),
+      history.commits - history.scoped
+   );
+   let width = history
+      .scopes
+      .iter()
+      .map(|(scope, _)| scope.len())
+      .max()
+      .unwrap_or(0);
+   for (scope, count) in &history.scopes {
+      println!("  {scope:<width$}  {count}");
+   }
+   Ok(())
+}
+
+/// Clear every footer under `--no-footers`, including the BREAKING CHANGE
+/// one `--breaking` adds
+fn strip_footers(commit_msg: &mut ConventionalCommit, args: &Args) {
+   if args.no_footers {
+      commit_msg.footers.clear();
+   }
+}
+
 /// Build footers from CLI args
-fn build_footers(args: &Args) -> Vec<String> {
+fn build_footers(args: &Args, config: &CommitConfig) -> Vec<String> {
    let mut footers = Vec::new();
 
-   // Add issue refs from CLI (standard format: "Token #number")
+   // Add issue refs from CLI (standard format: "Token #number"; post-processing
+   // applies `footer_format`)
    for issue in &args.fixes {
       footers.push(format!("Fixes #{}", issue.trim_start_matches('#')));
    }
@@ -270,27 +722,254 @@ fn build_footers(args: &Args) -> Vec<String> {
       footers.push(format!("Refs #{}", issue.trim_start_matches('#')));
    }
 
-   // Issue refs are now inlined in body items, so we don't add them as separate
-   // footers The analysis.issue_refs field is kept for backward compatibility
-   // but not used
+   // Issue refs found by the analysis are added in `run_generation`
 
    // Add breaking change footer if requested
    if args.breaking {
       footers.push("BREAKING CHANGE: This commit introduces breaking changes".to_string());
    }
 
+   if config.add_generator_trailer {
+      footers.retain(|footer| !footer.starts_with(GENERATOR_TRAILER));
+      footers.push(format!("{GENERATOR_TRAILER} llm-git ({})", config.model));
+   }
+
    footers
 }
 
-/// Main generation pipeline: get diff/stat → truncate → analyze → summarize →
-/// build commit
-fn run_generation(
+/// Footer key naming the tool and analysis model (`add_generator_trailer`)
+const GENERATOR_TRAILER: &str = "Generated-by:";
+
+/// Scope from the labels of the `--fixes` issues (`issue_scope_from_labels`);
+/// a failed lookup only warns
+fn issue_label_scope(args: &Args, config: &CommitConfig) -> Option<String> {
+   issues::scope_from_fixed_issues(&args.fixes, &args.dir, config)
+      .map_err(|err| style::warn(&format!("Couldn't read issue labels for a scope: {err}")))
+      .ok()
+      .flatten()
+}
+
+/// Deterministic `chore` message for a diff that only changes file modes,
+/// which gives the model nothing to read
+fn mode_only_commit(
+   diff: &str,
+   config: &CommitConfig,
+) -> Option<(ConventionalAnalysis, CommitSummary)> {
+   let files = llm_git::diff::mode_only_files(diff)?;
+   let details = files
+      .iter()
+      .filter_map(|file| file.kind_observation())
+      .map(AnalysisDetail::simple)
+      .collect();
+   let mut summary = match files.as_slice() {
+      [file] => {
+         let name = Path::new(&file.filename)
+            .file_name()
+            .map_or(file.filename.as_str(), |name| name.to_str().unwrap_or(&file.filename));
+         format!("changed file mode of {name}")
+      },
+      files => format!("changed file mode of {} files", files.len()),
+   };
+   normalization::normalize_summary_verb(&mut summary, "chore", config.summary_tense);
+
+   let analysis = ConventionalAnalysis {
+      commit_type: CommitType::new("chore").ok()?,
+      scope: None,
+      details,
+      issue_refs: Vec::new(),
+      rationale: None,
+   };
+   Some((analysis, CommitSummary::for_config(summary, config).ok()?))
+}
+
+/// Deterministic `chore(release)` message for a version bump with its
+/// changelog
+fn release_commit(
+   diff: &str,
+   config: &CommitConfig,
+) -> Option<(ConventionalAnalysis, CommitSummary)> {
+   let release = release::detect_release(diff)?;
+   let mut details: Vec<AnalysisDetail> = release
+      .bumps
+      .iter()
+      .map(|bump| {
+         AnalysisDetail::simple(format!(
+            "Bumped version in {} from {} to {}.",
+            bump.path, bump.old, bump.new
+         ))
+      })
+      .collect();
+   details.extend(
+      release
+         .changelogs
+         .iter()
+         .map(|path| AnalysisDetail::simple(format!("Updated {path} for the release."))),
+   );
+   let mut summary = format!("prepared release v{}", release.version);
+   normalization::normalize_summary_verb(&mut summary, "chore", config.summary_tense);
+
+   let analysis = ConventionalAnalysis {
+      commit_type: CommitType::new("chore").ok()?,
+      scope: Scope::new("release").ok(),
+      details,
+      issue_refs: Vec::new(),
+      rationale: None,
+   };
+   Some((analysis, CommitSummary::for_config(summary, config).ok()?))
+}
+
+/// Tag for a `chore(release)` commit of a staged release, when `release_tag`
+/// is on and `--no-tag` isn't given
+fn release_tag_name(
+   commit: &ConventionalCommit,
+   args: &Args,
+   config: &CommitConfig,
+) -> Option<String> {
+   if !config.release_tag
+      || args.no_tag
+      || !matches!(args.mode, Mode::Staged)
+      || commit.commit_type.as_str() != "chore"
+      || commit
+         .scope
+         .as_ref()
+         .is_none_or(|scope| scope.as_str() != "release")
+   {
+      return None;
+   }
+   let diff = get_git_diff(&Mode::Staged, None, &args.dir, config).ok()?;
+   let release = release::detect_release(&diff)?;
+   Some(release::tag_name(&config.release_tag_format, &release.version))
+}
+
+/// Compact per-component weights for the summary prompt, when enabled
+fn summary_change_weights(args: &Args, config: &CommitConfig) -> Option<String> {
+   if !config.summary_include_weights {
+      return None;
+   }
+   let numstat = get_numstat(&args.mode, args.target.as_deref(), &args.dir).ok()?;
+   let (candidates, _) = ScopeAnalyzer::extract_scope(&numstat, config);
+   ScopeAnalyzer::format_change_weights(&candidates)
+}
+
+/// Share of each kind of change for the type checks, from the diff when it
+/// can be read again and from `stat` paths otherwise
+fn change_profile(args: &Args, config: &CommitConfig, stat: &str) -> ChangeProfile {
+   get_git_diff(&args.mode, args.target.as_deref(), &args.dir, config)
+      .map_or_else(|_| ChangeProfile::from_stat(stat), |diff| classify_change_profile(&diff))
+}
+
+/// Share of changed lines behind `scope`, for the confidence score
+fn scope_percentage(args: &Args, config: &CommitConfig, scope: &str) -> Option<f32> {
+   let numstat = get_numstat(&args.mode, args.target.as_deref(), &args.dir).ok()?;
+   let (candidates, _) = ScopeAnalyzer::extract_scope(&numstat, config);
+   Some(confidence::scope_share(&candidates, scope))
+}
+
+/// Tell the model about a `--force-type` so scope and body match it;
+/// appended to the user context
+fn forced_type_instruction(commit_type: &CommitType) -> String {
+   format!(
+      "The commit type is already decided: `{}`. Pick the scope and describe the change \
+       consistently with it.",
+      commit_type.as_str()
+   )
+}
+
+/// Correct the model's type from the changed files: keep accompanying tests
+/// from tipping it toward `test`, give tooling-only changes their
+/// `tooling_types` type, and call documentation-only changes docs wherever
+/// they live. True if the type changed.
+fn reconcile_commit_type(
+   analysis: &mut ConventionalAnalysis,
+   args: &Args,
+   config: &CommitConfig,
+) -> bool {
+   if !(config.deprioritize_test_files_for_type
+      || !config.tooling_types.is_empty()
+      || config.docs_only_as_docs)
+   {
+      return false;
+   }
+   let Ok(numstat) = get_numstat(&args.mode, args.target.as_deref(), &args.dir) else {
+      return false;
+   };
+
+   let original = analysis.commit_type.clone();
+   if let Some(reconciled) = ScopeAnalyzer::reconcile_test_type(analysis, &numstat, config) {
+      analysis.commit_type = reconciled;
+   }
+   if let Some(reconciled) =
+      ScopeAnalyzer::reconcile_tooling_type(&analysis.commit_type, &numstat, config)
+   {
+      analysis.commit_type = reconciled;
+   }
+   if let Some(reconciled) =
+      ScopeAnalyzer::reconcile_docs_type(&analysis.commit_type, &numstat, config)
+   {
+      analysis.commit_type = reconciled;
+   }
+   analysis.commit_type != original
+}
+
+/// Replace the model's type with `--force-type`; true if it differed
+fn apply_forced_type(analysis: &mut ConventionalAnalysis, forced: Option<&CommitType>) -> bool {
+   match forced {
+      Some(forced) if analysis.commit_type != *forced => {
+         analysis.commit_type = forced.clone();
+         true
+      },
+      _ => false,
+   }
+}
+
+/// What the analysis prompt is built from: the diff and the context gathered
+/// around it
+struct AnalysisInputs {
+   stat:                 String,
+   /// Truncated to `max_diff_length` unless map-reduce will split it per file
+   diff:                 String,
+   /// The diff before truncation
+   full_diff:            String,
+   use_map_reduce:       bool,
+   /// The config with `analysis_prompt_variant` tuned to the kind of files
+   /// changed, when that differs from the configured variant
+   tuned_config:         Option<CommitConfig>,
+   recent_commits_str:   Option<String>,
+   common_scopes_str:    Option<String>,
+   project_context_str:  Option<String>,
+   /// `--context` joined, plus the `--force-type` instruction
+   context:              Option<String>,
+   scope_candidates_str: String,
+   issue_scope:          Option<String>,
+   /// Share of each kind of change in `full_diff`
+   change_profile:       ChangeProfile,
+}
+
+/// Read the diff for `args.mode` and gather the history, repository and scope
+/// context the analysis prompt is rendered with
+fn gather_analysis_inputs(
    config: &CommitConfig,
    args: &Args,
+   forced_type: Option<&CommitType>,
    token_counter: &tokens::TokenCounter,
-) -> Result<ConventionalCommit> {
-   let diff = get_git_diff(&args.mode, args.target.as_deref(), &args.dir, config)?;
-   let stat = get_git_stat(&args.mode, args.target.as_deref(), &args.dir, config)?;
+   timer: &RunTimer,
+) -> Result<AnalysisInputs> {
+   let (diff, stat) = timer.stage("diff", || -> Result<_> {
+      let diff = get_git_diff(&args.mode, args.target.as_deref(), &args.dir, config)?;
+      let stat = get_git_stat(&args.mode, args.target.as_deref(), &args.dir, config)?;
+      Ok((diff, stat))
+   })?;
+
+   // Leave `llm-git:ignore` hunks out of the analysis (they're still committed)
+   let diff = drop_ignored_hunks(&diff);
+   if diff.trim().is_empty() {
+      return Err(CommitGenError::NoChanges {
+         mode: format!("analysis (every hunk is marked `{IGNORE_HUNK_MARKER}`)"),
+      });
+   }
+
+   // Collapse giant hunks (and CRLF line endings) before any token budgeting
+   let diff = elide_long_hunks(&diff, config);
 
    // Save debug outputs if requested
    if let Some(debug_dir) = &args.debug_output {
@@ -298,32 +977,48 @@ fn run_generation(
       save_debug_output(debug_dir, "stat.txt", &stat)?;
    }
 
-   println!(
-      "{} {} {} {}",
-      style::dim("›"),
-      style::dim("model:"),
-      style::model(&config.model),
-      style::dim(&format!("(temp: {})", config.temperature))
-   );
+   if !style::is_quiet() {
+      println!(
+         "{} {} {} {}",
+         style::dim("›"),
+         style::dim("model:"),
+         style::model(&config.model),
+         style::dim(&config.temperature_for(&config.model).map_or_else(
+            || "(no temp)".to_string(),
+            |temperature| format!("(temp: {temperature})")
+         ))
+      );
+   }
 
    // Check if map-reduce should be used for large diffs
    // Map-reduce handles its own per-file processing, so we pass the original diff
    // Only apply smart truncation if map-reduce is disabled or diff is below
    // threshold
+   // Type-tuned analysis prompt when the diff is dominated by one kind of file
+   let tuned_config = get_numstat(&args.mode, args.target.as_deref(), &args.dir)
+      .ok()
+      .map(|numstat| ScopeAnalyzer::select_analysis_prompt_variant(&numstat, config).to_string())
+      .filter(|variant| *variant != config.analysis_prompt_variant)
+      .map(|variant| CommitConfig { analysis_prompt_variant: variant, ..config.clone() });
+
    let use_map_reduce = llm_git::map_reduce::should_use_map_reduce(&diff, config, token_counter);
 
+   // Kept whole for a vacuous-message retry, which may escalate to map-reduce
+   let full_diff = diff.clone();
    let diff = if use_map_reduce {
       // Map-reduce will handle the full diff with per-file analysis
       diff
-   } else if diff.len() > config.max_diff_length {
-      println!(
-         "{}",
-         style::warning(&format!(
-            "Applying smart truncation (diff size: {} characters)",
-            diff.len()
-         ))
-      );
-      smart_truncate_diff(&diff, config.max_diff_length, config, token_counter)
+   } else if diff.len() > config.max_diff_length_for(&args.mode) {
+      if !style::is_quiet() {
+         println!(
+            "{}",
+            style::warning(&format!(
+               "Applying smart truncation (diff size: {} characters)",
+               diff.len()
+            ))
+         );
+      }
+      smart_truncate_diff(&diff, config.max_diff_length_for(&args.mode), config, token_counter)
    } else {
       diff
    };
@@ -335,7 +1030,7 @@ fn run_generation(
          let style_patterns = git::extract_style_patterns(&commits);
          let style_str = style_patterns.map(|p| p.format_for_prompt());
 
-         let scopes = get_common_scopes(&args.dir, 100)
+         let scopes = get_common_scopes(&args.dir, config)
             .ok()
             .filter(|s| !s.is_empty())
             .map(|scopes| {
@@ -357,32 +1052,207 @@ fn run_generation(
    let project_context_str = repo_meta.format_for_prompt();
 
    // Generate conventional commit analysis
-   let context = if args.context.is_empty() {
+   let mut context_parts = args.context.clone();
+   if let Some(forced) = forced_type {
+      context_parts.push(forced_type_instruction(forced));
+   }
+   let context = if context_parts.is_empty() {
       None
    } else {
-      Some(args.context.join(" "))
+      Some(context_parts.join(" "))
    };
-   let (scope_candidates_str, _is_wide) =
+   let (mut scope_candidates_str, _is_wide) =
       extract_scope_candidates(&args.mode, args.target.as_deref(), &args.dir, config)?;
+   let issue_scope = issue_label_scope(args, config);
+   if let Some(scope) = &issue_scope {
+      scope_candidates_str = format!(
+         "{scope_candidates_str}\nThe issue this fixes is labeled for scope `{scope}`; prefer it."
+      );
+   }
+   let change_profile = classify_change_profile(&full_diff);
+
+   Ok(AnalysisInputs {
+      stat,
+      diff,
+      full_diff,
+      use_map_reduce,
+      tuned_config,
+      recent_commits_str,
+      common_scopes_str,
+      project_context_str,
+      context,
+      scope_candidates_str,
+      issue_scope,
+      change_profile,
+   })
+}
+
+/// `--template-debug`: print the analysis and summary prompts rendered from
+/// the real diff and context, without calling the model. The summary prompt
+/// depends on the analysis, so placeholders stand in for its type, scope and
+/// details.
+fn run_template_debug(
+   args: &Args,
+   config: &CommitConfig,
+   token_counter: &tokens::TokenCounter,
+   timer: &RunTimer,
+) -> Result<()> {
+   let forced_type = args
+      .force_type
+      .as_deref()
+      .map(CommitType::new)
+      .transpose()?;
+   let inputs = gather_analysis_inputs(config, args, forced_type.as_ref(), token_counter, timer)?;
+   let config = inputs.tuned_config.as_ref().unwrap_or(config);
+   let change_profile = inputs.change_profile.format_for_prompt();
+   if inputs.use_map_reduce {
+      style::warn(
+         "This diff would be analyzed per file (map-reduce); showing the single-call prompt",
+      );
+   }
+
+   let ctx = AnalysisContext {
+      user_context:    inputs.context.as_deref(),
+      recent_commits:  inputs.recent_commits_str.as_deref(),
+      common_scopes:   inputs.common_scopes_str.as_deref(),
+      project_context: inputs.project_context_str.as_deref(),
+      change_profile:  change_profile.as_deref(),
+      debug_output:    None,
+      debug_prefix:    None,
+   };
+   let analysis = llm_git::api::render_analysis_request(
+      &inputs.stat,
+      &inputs.diff,
+      &inputs.scope_candidates_str,
+      &ctx,
+      config,
+   )?;
+   let change_weights = summary_change_weights(args, config);
+   let summary = llm_git::api::render_summary_request(
+      &inputs.stat,
+      forced_type.as_ref().map_or("<type>", CommitType::as_str),
+      Some("<scope>"),
+      &["<details from the analysis>".to_string()],
+      inputs.context.as_deref(),
+      change_weights.as_deref(),
+      config,
+   )?;
+
+   for (name, parts) in [("analysis", analysis), ("summary", summary)] {
+      println!("===== {name} prompt: system =====\n{}\n", parts.system);
+      println!("===== {name} prompt: user =====\n{}\n", parts.user);
+   }
+   Ok(())
+}
+
+/// Main generation pipeline: get diff/stat → truncate → analyze → summarize →
+/// build commit. Returns the commit and whether a fallback summary replaced
+/// the model's.
+fn run_generation(
+   config: &CommitConfig,
+   args: &Args,
+   token_counter: &tokens::TokenCounter,
+   timer: &RunTimer,
+) -> Result<(ConventionalCommit, bool)> {
+   // Check `--summary` before spending any API calls
+   let provided_summary = args
+      .summary
+      .as_deref()
+      .map(|summary| {
+         CommitSummary::for_config(summary.trim(), config).map_err(|err| {
+            CommitGenError::ValidationError(format!("--summary \"{summary}\": {err}"))
+         })
+      })
+      .transpose()?;
+   let forced_type = args
+      .force_type
+      .as_deref()
+      .map(CommitType::new)
+      .transpose()?;
+
+   let AnalysisInputs {
+      stat,
+      diff,
+      full_diff,
+      use_map_reduce,
+      tuned_config,
+      recent_commits_str,
+      common_scopes_str,
+      project_context_str,
+      context,
+      scope_candidates_str,
+      issue_scope,
+      change_profile,
+   } = gather_analysis_inputs(config, args, forced_type.as_ref(), token_counter, timer)?;
+   let config = tuned_config.as_ref().unwrap_or(config);
+   let change_profile_note = change_profile.format_for_prompt();
    let ctx = AnalysisContext {
       user_context:    context.as_deref(),
       recent_commits:  recent_commits_str.as_deref(),
       common_scopes:   common_scopes_str.as_deref(),
       project_context: project_context_str.as_deref(),
+      change_profile:  change_profile_note.as_deref(),
       debug_output:    args.debug_output.as_deref(),
       debug_prefix:    None,
    };
-   let analysis = style::with_spinner("Generating conventional commit analysis", || {
-      generate_analysis_with_map_reduce(
-         &stat,
-         &diff,
-         &config.model,
-         &scope_candidates_str,
-         &ctx,
-         config,
-         token_counter,
-      )
-   })?;
+   // Mode-only diffs and release version bumps need no model at all
+   let deterministic = mode_only_commit(&diff, config).or_else(|| release_commit(&diff, config));
+   let is_deterministic = deterministic.is_some();
+   let fast = if deterministic.is_some() {
+      deterministic
+   } else if provided_summary.is_none() && !use_map_reduce && should_use_fast_mode(&diff, config) {
+      // Small diffs: one combined call; escalate to the two-call path on
+      // failure. Skipped with `--summary`, since the combined call would
+      // generate a summary too
+      timer
+         .stage("analysis", || {
+            style::with_spinner("Generating commit message", || {
+               generate_fast_commit(&stat, &diff, &scope_candidates_str, &ctx, config)
+            })
+         })
+         .map_err(|err| {
+            eprintln!(
+               "{}",
+               style::warning(&format!("Fast mode failed ({err}); using analysis + summary"))
+            );
+         })
+         .ok()
+   } else {
+      None
+   };
+
+   let (mut analysis, mut fast_summary) = if let Some((analysis, summary)) = fast {
+      (analysis, Some(summary))
+   } else {
+      let analyze = if config.split_type_and_body {
+         generate_split_analysis
+      } else {
+         generate_analysis_with_map_reduce
+      };
+      let analysis = timer.stage("analysis", || {
+         style::with_spinner("Generating conventional commit analysis", || {
+            analyze(&stat, &diff, &config.model, &scope_candidates_str, &ctx, config, token_counter)
+         })
+      })?;
+      (analysis, None)
+   };
+
+   // A fast-mode summary was written for the model's type, not a reconciled
+   // one; deterministic messages already have the type they need
+   if !is_deterministic && reconcile_commit_type(&mut analysis, args, config) {
+      fast_summary = None;
+   }
+
+   if analysis.scope.is_none()
+      && let Some(scope) = issue_scope.and_then(|scope| Scope::new(scope).ok())
+   {
+      analysis.scope = Some(scope);
+   }
+
+   // A fast-mode summary was written for the model's type, not the forced one
+   if apply_forced_type(&mut analysis, forced_type.as_ref()) {
+      fast_summary = None;
+   }
 
    // Save analysis debug output
    if let Some(debug_dir) = &args.debug_output {
@@ -391,35 +1261,128 @@ fn run_generation(
    }
 
    // Log scope selection
-   if let Some(scope) = &analysis.scope {
-      println!("{} {} {}", style::dim("›"), style::dim("scope:"), style::scope(&scope.to_string()));
-   } else {
-      println!("{} {}", style::dim("›"), style::dim("scope: (none)"));
+   if !style::is_quiet() {
+      match &analysis.scope {
+         Some(scope) => println!(
+            "{} {} {}",
+            style::dim("›"),
+            style::dim("scope:"),
+            style::scope(&scope.to_string())
+         ),
+         None => println!("{} {}", style::dim("›"), style::dim("scope: (none)")),
+      }
    }
 
    let detail_points = analysis.body_texts();
-   let summary = style::with_spinner("Creating summary", || {
-      generate_summary_from_analysis(
-         &stat,
-         analysis.commit_type.as_str(),
-         analysis.scope.as_ref().map(|s| s.as_str()),
-         &detail_points,
+   let change_weights = summary_change_weights(args, config);
+   let summarize =
+      |analysis: &ConventionalAnalysis, detail_points: &[String], context: Option<&str>| {
+         timer
+            .stage("summary", || {
+               style::with_spinner("Creating summary", || {
+                  generate_summary_from_analysis(
+                     &stat,
+                     &change_profile,
+                     analysis.commit_type.as_str(),
+                     analysis.scope.as_ref().map(|s| s.as_str()),
+                     detail_points,
+                     context,
+                     change_weights.as_deref(),
+                     config,
+                     args.debug_output.as_deref(),
+                     None,
+                  )
+               })
+            })
+            .unwrap_or_else(|err| {
+               eprintln!(
+                  "{}",
+                  style::warning(&format!("Failed to create summary with {}: {err}", config.model))
+               );
+               GeneratedSummary {
+                  summary:       fallback_summary(
+                     &stat,
+                     detail_points,
+                     analysis.commit_type.as_str(),
+                     config,
+                  ),
+                  fallback_used: true,
+               }
+            })
+      };
+   let GeneratedSummary { summary, fallback_used } = match (provided_summary, fast_summary) {
+      (Some(summary), _) | (None, Some(summary)) => {
+         GeneratedSummary { summary, fallback_used: false }
+      },
+      (None, None) => summarize(&analysis, &detail_points, context.as_deref()),
+   };
+
+   // A generic summary with no body for a sizeable diff gets one more attempt
+   // told to be specific (through map-reduce if the unified path produced
+   // it), and fails rather than being committed if that's generic too
+   let (analysis, detail_points, summary, fallback_used) = if !args.allow_generic
+      && args.summary.is_none()
+      && is_vacuous_message(summary.as_str(), &detail_points, full_diff.len(), config)
+   {
+      style::warn(&format!(
+         "Summary \"{}\" is too generic; retrying with a request to be specific",
+         summary.as_str()
+      ));
+      let specific_context = retry_context(
          context.as_deref(),
-         config,
-         args.debug_output.as_deref(),
-         None,
-      )
-   })
-   .unwrap_or_else(|err| {
-      eprintln!(
-         "{}",
-         style::warning(&format!("Failed to create summary with {}: {err}", config.model))
+         "described this change only as \"updated files\" or similar",
+         "Be specific: name the functions, types, files and behavior that changed.",
       );
-      fallback_summary(&stat, &detail_points, analysis.commit_type.as_str(), config)
-   });
-
-   // Save summary debug output
-   if let Some(debug_dir) = &args.debug_output {
+      let retry_ctx = AnalysisContext { user_context: Some(&specific_context), ..ctx };
+      let mut analysis = timer.stage("analysis", || {
+         style::with_spinner("Regenerating a more specific analysis", || {
+            if use_map_reduce {
+               generate_analysis_with_map_reduce(
+                  &stat,
+                  &diff,
+                  &config.model,
+                  &scope_candidates_str,
+                  &retry_ctx,
+                  config,
+                  token_counter,
+               )
+            } else {
+               llm_git::map_reduce::run_map_reduce(
+                  &full_diff,
+                  &stat,
+                  &scope_candidates_str,
+                  &retry_ctx,
+                  &config.model,
+                  config,
+                  token_counter,
+               )
+            }
+         })
+      })?;
+      apply_forced_type(&mut analysis, forced_type.as_ref());
+      let detail_points = analysis.body_texts();
+      let GeneratedSummary { summary, fallback_used } =
+         summarize(&analysis, &detail_points, Some(&specific_context));
+      if is_vacuous_message(summary.as_str(), &detail_points, full_diff.len(), config) {
+         return Err(CommitGenError::VacuousMessage {
+            message:    format!("{}: {}", analysis.commit_type.as_str(), summary.as_str()),
+            diff_chars: full_diff.len(),
+         });
+      }
+      (analysis, detail_points, summary, fallback_used)
+   } else {
+      (analysis, detail_points, summary, fallback_used)
+   };
+
+   if let Some(path) = &args.save_analysis {
+      save_analysis(path, &analysis)?;
+   }
+   if let Some(rationale) = &analysis.rationale {
+      eprintln!("{} {} {rationale}", style::dim("›"), style::dim("rationale:"));
+   }
+
+   // Save summary debug output
+   if let Some(debug_dir) = &args.debug_output {
       let summary_json = serde_json::json!({
          "summary": summary.as_str(),
          "commit_type": analysis.commit_type.as_str(),
@@ -428,27 +1391,151 @@ fn run_generation(
       save_debug_output(debug_dir, "summary.json", &serde_json::to_string_pretty(&summary_json)?)?;
    }
 
-   let footers = build_footers(args);
+   let mut body = detail_points;
+   let mut footers = build_footers(args, config);
+   let cli_refs: Vec<&String> = args
+      .fixes
+      .iter()
+      .chain(&args.closes)
+      .chain(&args.resolves)
+      .chain(&args.refs)
+      .collect();
+   if cli_refs.is_empty() {
+      let ref_footers =
+         issues::inline_issue_refs(&mut body, &analysis.issue_refs, context.as_deref(), &footers);
+      // Keep BREAKING CHANGE and the generator trailer last
+      let at = footers
+         .iter()
+         .position(|footer| {
+            footer.starts_with("BREAKING CHANGE") || footer.starts_with(GENERATOR_TRAILER)
+         })
+         .unwrap_or(footers.len());
+      footers.splice(at..at, ref_footers);
+   } else {
+      for number in issues::refs_missing_from_cli(&analysis.issue_refs, &cli_refs) {
+         style::warn(&format!(
+            "Context mentions #{number}, but it wasn't passed to \
+             --fixes/--closes/--resolves/--refs"
+         ));
+      }
+   }
 
-   Ok(ConventionalCommit {
+   let commit = ConventionalCommit {
       commit_type: analysis.commit_type,
       scope: analysis.scope,
       summary,
-      body: detail_points,
+      body,
       footers,
-   })
+   };
+   Ok((commit, fallback_used))
 }
 
-/// Post-process, validate, retry with fallback. Returns validation error if any
+/// Stand-in for the diff stat when an empty commit has no changes to describe
+const EMPTY_COMMIT_STAT: &str = "(empty commit: no files changed)";
+
+/// Generate a message for an empty commit (`--allow-empty`) from the user's
+/// context text alone; like [`run_generation`], also returns whether a
+/// fallback summary was used
+fn run_empty_generation(
+   config: &CommitConfig,
+   args: &Args,
+   timer: &RunTimer,
+) -> Result<(ConventionalCommit, bool)> {
+   if args.context.is_empty() {
+      return Err(CommitGenError::ValidationError(
+         "--allow-empty with nothing to commit needs context describing the commit (e.g. `lgit \
+          --allow-empty Triggered a CI rebuild`)"
+            .to_string(),
+      ));
+   }
+   let forced_type = args
+      .force_type
+      .as_deref()
+      .map(CommitType::new)
+      .transpose()?;
+   let mut context = args.context.join(" ");
+   if let Some(forced) = &forced_type {
+      context = format!("{context} {}", forced_type_instruction(forced));
+   }
+
+   let ctx = AnalysisContext {
+      user_context:    Some(&context),
+      recent_commits:  None,
+      common_scopes:   None,
+      project_context: None,
+      change_profile:  None,
+      debug_output:    args.debug_output.as_deref(),
+      debug_prefix:    None,
+   };
+   let mut analysis = timer.stage("analysis", || {
+      style::with_spinner("Generating conventional commit analysis", || {
+         generate_conventional_analysis(EMPTY_COMMIT_STAT, "", &config.model, "", &ctx, config)
+      })
+   })?;
+   apply_forced_type(&mut analysis, forced_type.as_ref());
+
+   let detail_points = analysis.body_texts();
+   let GeneratedSummary { summary, fallback_used } = timer
+      .stage("summary", || {
+         style::with_spinner("Creating summary", || {
+            generate_summary_from_analysis(
+               EMPTY_COMMIT_STAT,
+               &ChangeProfile::default(),
+               analysis.commit_type.as_str(),
+               analysis.scope.as_ref().map(|s| s.as_str()),
+               &detail_points,
+               Some(&context),
+               None,
+               config,
+               args.debug_output.as_deref(),
+               None,
+            )
+         })
+      })
+      .unwrap_or_else(|err| {
+         eprintln!(
+            "{}",
+            style::warning(&format!("Failed to create summary with {}: {err}", config.model))
+         );
+         GeneratedSummary {
+            summary:       fallback_summary(
+               "",
+               &detail_points,
+               analysis.commit_type.as_str(),
+               config,
+            ),
+            fallback_used: true,
+         }
+      });
+
+   let commit = ConventionalCommit {
+      commit_type: analysis.commit_type,
+      scope: analysis.scope,
+      summary,
+      body: detail_points,
+      footers: build_footers(args, config),
+   };
+   Ok((commit, fallback_used))
+}
+
+/// Post-process, validate, retry with fallback. Returns validation error if
+/// any; `fallback_used` tracks whether the summary in `commit_msg` is a
+/// fallback as retries replace it
+#[allow(clippy::too_many_arguments, reason = "regeneration needs the summary inputs")]
 fn validate_and_process(
    commit_msg: &mut ConventionalCommit,
+   fallback_used: &mut bool,
    stat: &str,
+   profile: &ChangeProfile,
    detail_points: &[String],
    user_context: Option<&str>,
+   change_weights: Option<&str>,
    config: &CommitConfig,
 ) -> Option<String> {
    let mut validation_error: Option<String> = None;
    for attempt in 0..=2 {
+      // Only warnings about the message that ends up committed count
+      diagnostics::clear();
       post_process_commit_message(commit_msg, config);
 
       // Check soft limit BEFORE full validation (only on first attempt)
@@ -467,22 +1554,26 @@ fn validate_and_process(
             // Regenerate summary (call API again)
             match generate_summary_from_analysis(
                stat,
+               profile,
                commit_msg.commit_type.as_str(),
                commit_msg.scope.as_ref().map(|s| s.as_str()),
                detail_points,
                user_context,
+               change_weights,
                config,
                None,
                None,
             ) {
-               Ok(new_summary) => {
-                  commit_msg.summary = new_summary;
+               Ok(generated) => {
+                  commit_msg.summary = generated.summary;
+                  *fallback_used = generated.fallback_used;
                   continue; // Retry validation loop
                },
                Err(e) => {
                   eprintln!("Retry generation failed: {e}, using fallback");
                   commit_msg.summary =
                      fallback_summary(stat, detail_points, commit_msg.commit_type.as_str(), config);
+                  *fallback_used = true;
                   continue;
                },
             }
@@ -524,6 +1615,7 @@ fn validate_and_process(
             if attempt < 2 {
                commit_msg.summary =
                   fallback_summary(stat, detail_points, commit_msg.commit_type.as_str(), config);
+               *fallback_used = true;
                continue;
             }
             break;
@@ -533,6 +1625,182 @@ fn validate_and_process(
    validation_error
 }
 
+/// Run generation, then validate and format the result.
+///
+/// Returns the commit, its formatted message, any validation error that
+/// survived the retry loop, and the signals behind its confidence score.
+/// `empty` generates for an empty commit, from the context text alone.
+fn generate_commit_message(
+   config: &CommitConfig,
+   args: &Args,
+   token_counter: &tokens::TokenCounter,
+   empty: bool,
+   timer: &RunTimer,
+) -> Result<(ConventionalCommit, String, Option<String>, ConfidenceSignals)> {
+   let (mut commit_msg, mut fallback_used) = if empty {
+      run_empty_generation(config, args, timer)?
+   } else {
+      run_generation(config, args, token_counter, timer)?
+   };
+
+   // `--no-footers` drops issue refs, BREAKING CHANGE and trailers alike
+   strip_footers(&mut commit_msg, args);
+
+   // Get stat and detail points for validation retry
+   let stat = if empty {
+      String::new()
+   } else {
+      timer.stage("diff", || get_git_stat(&args.mode, args.target.as_deref(), &args.dir, config))?
+   };
+   let profile = if empty {
+      ChangeProfile::default()
+   } else {
+      change_profile(args, config, &stat)
+   };
+   let detail_points = commit_msg.body.clone();
+   if args.body_from_commits
+      && let Some(range) = args.target.as_deref()
+   {
+      commit_msg.body = commit_subjects_to_body(&get_range_subjects(range, &args.dir)?);
+   }
+   let context = if args.context.is_empty() {
+      None
+   } else {
+      Some(args.context.join(" "))
+   };
+
+   // Validate and process
+   let change_weights = summary_change_weights(args, config);
+   let validation_failed = timer.stage("validation", || -> Result<_> {
+      let validation_failed = if let Some(summary) = &args.summary {
+         // The user's summary is normalized and checked, never regenerated
+         diagnostics::clear();
+         post_process_commit_message(&mut commit_msg, config);
+         validate_commit_message(&commit_msg, config).map_err(|err| {
+            CommitGenError::ValidationError(format!("--summary \"{summary}\": {err}"))
+         })?;
+         None
+      } else {
+         validate_and_process(
+            &mut commit_msg,
+            &mut fallback_used,
+            &stat,
+            &profile,
+            &detail_points,
+            context.as_deref(),
+            change_weights.as_deref(),
+            config,
+         )
+      };
+
+      if let Some(err) = &validation_failed {
+         eprintln!("Warning: Generated message failed validation even after retry: {err}");
+         eprintln!("You may want to manually edit the message before committing.");
+      }
+
+      // Check type-scope consistency (a forced type is never corrected)
+      if args.force_type.is_none() {
+         apply_type_scope_policy(
+            &mut commit_msg,
+            &stat,
+            &profile,
+            config.on_type_scope_inconsistency,
+         )?;
+      }
+      check_scope_not_filename(&commit_msg, &stat);
+      Ok(validation_failed)
+   })?;
+
+   let signals = ConfidenceSignals {
+      scope_percentage: commit_msg
+         .scope
+         .as_ref()
+         .and_then(|scope| scope_percentage(args, config, scope.as_str())),
+      validation_passed: validation_failed.is_none(),
+      fallback_used,
+   };
+
+   let formatted_message = format_commit_message(&commit_msg, config.body_style);
+   Ok((commit_msg, formatted_message, validation_failed, signals))
+}
+
+/// Watch the index and print a fresh preview whenever the staged set changes.
+///
+/// Never stages or commits. A change is only picked up once the fingerprint
+/// has been stable for a full poll interval, so rapid staging is debounced.
+fn run_watch_mode(
+   args: &Args,
+   config: &CommitConfig,
+   token_counter: &tokens::TokenCounter,
+) -> Result<()> {
+   if !matches!(args.mode, Mode::Staged) {
+      return Err(CommitGenError::ValidationError("--watch only supports staged mode".to_string()));
+   }
+
+   let interval = std::time::Duration::from_millis(config.watch_poll_interval_ms.max(100));
+   let mut last_rendered: Option<String> = None;
+
+   loop {
+      let fingerprint = git::get_staged_fingerprint(&args.dir)?;
+      if last_rendered.as_ref() == Some(&fingerprint) {
+         std::thread::sleep(interval);
+         continue;
+      }
+
+      // Debounce: wait until staging settles before regenerating
+      std::thread::sleep(interval);
+      if git::get_staged_fingerprint(&args.dir)? != fingerprint {
+         continue;
+      }
+
+      style::clear_screen();
+      if fingerprint.is_empty() {
+         println!("{} {}", style::info("›"), style::dim("No staged changes, waiting..."));
+      } else {
+         match generate_commit_message(
+            config,
+            args,
+            token_counter,
+            false,
+            &RunTimer::start(&config.api_counter),
+         ) {
+            Ok((_, formatted_message, ..)) => println!(
+               "\n{}",
+               style::boxed_message(
+                  "Preview Commit Message",
+                  &formatted_message,
+                  style::term_width()
+               )
+            ),
+            Err(e) => eprintln!("{}", style::warning(&format!("Generation failed: {e}"))),
+         }
+      }
+      println!(
+         "\n{}",
+         style::dim(&format!(
+            "Watching staged changes (every {}ms), Ctrl+C to exit",
+            interval.as_millis()
+         ))
+      );
+
+      last_rendered = Some(fingerprint);
+   }
+}
+
+/// Refuse a staged-mode commit on a `protected_branches` entry unless
+/// `--allow-protected` or `--force` was passed
+fn check_protected_branch(args: &Args, config: &CommitConfig) -> Result<()> {
+   if args.allow_protected || args.force {
+      return Ok(());
+   }
+   match git::get_current_branch(&args.dir)? {
+      Some(branch) if config.protected_branches.contains(&branch) => {
+         Err(CommitGenError::ProtectedBranch { branch })
+      },
+      _ => Ok(()),
+   }
+}
+
 /// Copy text to clipboard
 fn copy_to_clipboard(text: &str) -> Result<()> {
    let mut clipboard = Clipboard::new().map_err(CommitGenError::ClipboardError)?;
@@ -543,23 +1811,107 @@ fn copy_to_clipboard(text: &str) -> Result<()> {
 }
 
 fn main() -> Result<()> {
-   let args = Args::parse();
+   let matches = Args::command().get_matches();
+   let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+   if args.check_config {
+      return run_check_config(&args);
+   }
+   if args.init_config {
+      return run_init_config(&args);
+   }
+
+   // Fail early with a clear message if git is missing or too old
+   ensure_git_available()?;
+
+   if args.rewrite_verify {
+      return rewrite::run_rewrite_verify(&args);
+   }
 
    // Load config and apply CLI overrides
    let mut config = load_config_from_args(&args)?;
-   apply_cli_overrides(&mut config, &args);
+   let timer = RunTimer::start(&config.api_counter);
+   if !args.ignore_cli_defaults {
+      for warning in apply_cli_defaults(&mut args, &config.cli_defaults, &matches) {
+         eprintln!("{}", style::warning(&format!("{} {warning}", style::icons::WARNING)));
+      }
+   }
+
+   // --subject-only is a preview for scripts: never commit, print nothing else
+   if args.subject_only {
+      args.dry_run = true;
+      args.quiet = true;
+   }
+   // --template-debug prints the prompts and nothing else
+   if args.template_debug {
+      args.quiet = true;
+   }
+   style::set_quiet(args.quiet);
+
+   apply_cli_overrides(&mut config, &args)?;
+   apply_context_presets(&mut args, &config)?;
+   apply_context_url(&mut args, &config)?;
+   apply_prompt_experiment(&args)?;
+   config.generated_files = generated_file_patterns(&args.dir);
+
+   if args.model_list {
+      return run_model_list(&config);
+   }
+   if args.scopes {
+      return run_scopes(&args, &config);
+   }
+   if matches!(args.mode, Mode::Merge) {
+      apply_merge_context(&mut args)?;
+   }
+
+   // Validate date overrides up front so a bad value fails before any API call
+   let commit_dates =
+      CommitDates::from_flags(args.commit_date.as_deref(), args.author_date.as_deref())?;
+
+   if args.body_from_commits
+      && (!matches!(args.mode, Mode::Commit)
+         || !args.target.as_deref().is_some_and(is_commit_range))
+   {
+      return Err(CommitGenError::ValidationError(
+         "--body-from-commits requires --mode=commit with a range target (e.g. --target \
+          main..HEAD)"
+            .to_string(),
+      ));
+   }
 
    // Create token counter from final config
    let token_counter = create_token_counter(&config);
 
+   // Print a shareable, anonymized diff instead of generating
+   if args.anonymize {
+      let diff = get_git_diff(&args.mode, args.target.as_deref(), &args.dir, &config)?;
+      print!("{}", anonymize::anonymize_diff(&diff));
+      return Ok(());
+   }
+
+   // Print the prompts the model would get instead of generating
+   if args.template_debug {
+      return run_template_debug(&args, &config, &token_counter, &timer);
+   }
+
+   // Catch a mistyped model before any diff is sent
+   if config.validate_models {
+      models::validate_models(&config)?;
+   }
+
    // Route to compose mode if --compose flag is present
    if args.compose {
-      return run_compose_mode(&args, &config);
+      return run_compose_mode(&args, &config, &timer);
+   }
+
+   // Route to polish mode (rewrite limited to unpushed commits)
+   if args.polish {
+      return rewrite::run_polish_mode(&args, &config, &timer);
    }
 
    // Route to rewrite mode if --rewrite flag is present
    if args.rewrite {
-      return rewrite::run_rewrite_mode(&args, &config);
+      return rewrite::run_rewrite_mode(&args, &config, &timer);
    }
 
    // Route to test mode if --test flag is present
@@ -567,101 +1919,66 @@ fn main() -> Result<()> {
       return run_test_mode(&args, &config);
    }
 
+   // Route to watch mode if --watch flag is present (staged preview, never
+   // commits)
+   if args.watch {
+      return run_watch_mode(&args, &config, &token_counter);
+   }
+
    // Auto-stage all changes if nothing staged in commit mode
+   let mut empty_commit = false;
    if matches!(args.mode, Mode::Staged) {
-      use std::process::Command;
-      let staged_check = Command::new("git")
-         .args(["diff", "--cached", "--quiet"])
-         .current_dir(&args.dir)
-         .status()
-         .map_err(|e| CommitGenError::GitError(format!("Failed to check staged changes: {e}")))?;
-
-      // exit code 1 = changes exist, 0 = no changes
-      if staged_check.success() {
-         // Check if there are any unstaged changes before staging
-         let unstaged_check = Command::new("git")
-            .args(["diff", "--quiet"])
-            .current_dir(&args.dir)
-            .status()
-            .map_err(|e| {
-               CommitGenError::GitError(format!("Failed to check unstaged changes: {e}"))
-            })?;
-
-         // Check for untracked files
-         let untracked_output = Command::new("git")
-            .args(["ls-files", "--others", "--exclude-standard"])
-            .current_dir(&args.dir)
-            .output()
-            .map_err(|e| {
-               CommitGenError::GitError(format!("Failed to check untracked files: {e}"))
-            })?;
-
-         let has_untracked = !untracked_output.stdout.is_empty();
-
-         // If no unstaged changes AND no untracked files, working directory is clean
-         if unstaged_check.success() && !has_untracked {
-            return Err(CommitGenError::NoChanges {
-               mode: "working directory (nothing to commit)".to_string(),
-            });
-         }
-
-         println!("{} {}", style::info("›"), style::dim("No staged changes, staging all..."));
-         let add_output = Command::new("git")
-            .args(["add", "-A"])
-            .current_dir(&args.dir)
-            .output()
-            .map_err(|e| CommitGenError::GitError(format!("Failed to stage changes: {e}")))?;
+      match git::stage_all_if_nothing_staged(&args.dir, config.auto_stage) {
+         Err(CommitGenError::NoChanges { .. }) if args.allow_empty => empty_commit = true,
+         result => result?,
+      }
 
-         if !add_output.status.success() {
-            let stderr = String::from_utf8_lossy(&add_output.stderr);
-            return Err(CommitGenError::GitError(format!("git add -A failed: {stderr}")));
-         }
+      // Catch stray binaries before they land in history
+      let max_bytes = config.max_committed_file_bytes.filter(|_| !args.dry_run);
+      for warning in
+         git::check_large_staged_files(&args.dir, config.large_file_warn_bytes, max_bytes)?
+      {
+         eprintln!("{}", style::warning(&format!("{} {warning}", style::icons::WARNING)));
       }
    }
 
-   // Run changelog maintenance if not disabled (check both CLI flag and config)
+   // Run changelog maintenance if not disabled (check both CLI flag and config).
+   // A merge brings in changes the other branch already recorded.
    if !args.no_changelog
+      && !matches!(args.mode, Mode::Merge)
+      && !args.subject_only
+      && !empty_commit
       && config.changelog_enabled
-      && let Err(e) = llm_git::changelog::run_changelog_flow(&args, &config)
+      && let Err(e) =
+         timer.stage("changelog", || llm_git::changelog::run_changelog_flow(&args, &config))
    {
       // Don't fail the commit, just warn
       eprintln!("Warning: Changelog update failed: {e}");
    }
 
-   println!("{} Analyzing {} changes...", style::info("›"), match args.mode {
-      Mode::Staged => style::bold("staged"),
-      Mode::Commit => style::bold("commit"),
-      Mode::Unstaged => style::bold("unstaged"),
-      Mode::Compose => unreachable!("compose mode handled separately"),
-   });
+   if !style::is_quiet() {
+      println!("{} Analyzing {} changes...", style::info("›"), match args.mode {
+         Mode::Staged => style::bold("staged"),
+         Mode::Commit => style::bold("commit"),
+         Mode::Unstaged => style::bold("unstaged"),
+         Mode::Merge => style::bold("merge"),
+         Mode::Compose => unreachable!("compose mode handled separately"),
+      });
+   }
 
    // Run generation pipeline
-   let mut commit_msg = run_generation(&config, &args, &token_counter)?;
-
-   // Get stat and detail points for validation retry
-   let stat = get_git_stat(&args.mode, args.target.as_deref(), &args.dir, &config)?;
-   let detail_points = commit_msg.body.clone();
-   let context = if args.context.is_empty() {
-      None
+   let (commit_msg, formatted_message, validation_failed, signals) =
+      generate_commit_message(&config, &args, &token_counter, empty_commit, &timer)?;
+   let formatted_message = if matches!(args.mode, Mode::Merge) {
+      with_merge_subject(
+         &formatted_message,
+         git::get_merge_subject(&args.dir).as_deref(),
+         config.merge_subject,
+      )
    } else {
-      Some(args.context.join(" "))
+      formatted_message
    };
 
-   // Validate and process
-   let validation_failed =
-      validate_and_process(&mut commit_msg, &stat, &detail_points, context.as_deref(), &config);
-
-   if let Some(err) = &validation_failed {
-      eprintln!("Warning: Generated message failed validation even after retry: {err}");
-      eprintln!("You may want to manually edit the message before committing.");
-   }
-
-   // Check type-scope consistency
-   check_type_scope_consistency(&commit_msg, &stat);
-
-   // Format and display
-   let formatted_message = format_commit_message(&commit_msg);
-
    // Save final commit message if debug output requested
    if let Some(debug_dir) = &args.debug_output {
       save_debug_output(debug_dir, "final.txt", &formatted_message)?;
@@ -669,10 +1986,27 @@ fn main() -> Result<()> {
       save_debug_output(debug_dir, "commit.json", &commit_json)?;
    }
 
-   println!(
-      "\n{}",
-      style::boxed_message("Generated Commit Message", &formatted_message, style::term_width())
-   );
+   // Subject only: body and footers were generated (and validated) but are not
+   // printed
+   if args.subject_only {
+      let subject = formatted_message.lines().next().unwrap_or_default();
+      if args.copy
+         && let Err(e) = copy_to_clipboard(subject)
+      {
+         eprintln!("Note: Failed to copy to clipboard: {e}");
+      }
+      println!("{subject}");
+      return Ok(());
+   }
+
+   if style::is_quiet() {
+      println!("{formatted_message}");
+   } else {
+      println!(
+         "\n{}",
+         style::boxed_message("Generated Commit Message", &formatted_message, style::term_width())
+      );
+   }
 
    if std::env::var("LLM_GIT_VERBOSE").is_ok() {
       println!("\nJSON Structure:");
@@ -682,14 +2016,15 @@ fn main() -> Result<()> {
    // Copy to clipboard if requested
    if args.copy {
       match copy_to_clipboard(&formatted_message) {
+         Ok(()) if style::is_quiet() => {},
          Ok(()) => println!("\n{}", style::success("Copied to clipboard")),
-         Err(e) => println!("\nNote: Failed to copy to clipboard: {e}"),
+         Err(e) => eprintln!("\nNote: Failed to copy to clipboard: {e}"),
       }
    }
 
-   // Auto-commit for staged mode (unless dry-run)
-   // Don't commit if validation failed
-   if matches!(args.mode, Mode::Staged) {
+   // Auto-commit for staged mode, and conclude the merge in merge mode (unless
+   // dry-run). Don't commit if validation failed
+   if matches!(args.mode, Mode::Staged | Mode::Merge) {
       if validation_failed.is_some() {
          eprintln!(
             "\n{}",
@@ -703,17 +2038,85 @@ fn main() -> Result<()> {
          ));
       }
 
-      println!("\n{}", style::info("Preparing to commit..."));
+      if !args.allow_placeholders
+         && let Some(placeholder) = find_template_placeholder(&formatted_message)
+      {
+         return Err(CommitGenError::TemplatePlaceholder { placeholder });
+      }
+
+      let score = signals.score();
+      if !args.dry_run && !args.yes && score < config.auto_commit_min_confidence {
+         eprintln!(
+            "\n{}",
+            style::warning(&format!(
+               "Confidence {score:.2} is below auto_commit_min_confidence {:.2} ({}). Not \
+                committing; re-run with --yes to commit anyway.",
+               config.auto_commit_min_confidence,
+               signals.reasons().join(", ")
+            ))
+         );
+         return Err(CommitGenError::ValidationError(
+            "Commit confidence below auto_commit_min_confidence".to_string(),
+         ));
+      }
+      if !args.dry_run {
+         diagnostics::check_before_commit(&config, args.yes)?;
+      }
+      if matches!(args.mode, Mode::Staged) && !args.dry_run {
+         check_protected_branch(&args, &config)?;
+      }
+
+      // Checked before committing, while the release is still staged
+      let release_tag = release_tag_name(&commit_msg, &args, &config);
+
+      if !style::is_quiet() {
+         println!("\n{}", style::info("Preparing to commit..."));
+      }
       let sign = args.sign || config.gpg_sign;
       let signoff = args.signoff || config.signoff;
-      git_commit(&formatted_message, args.dry_run, &args.dir, sign, signoff, args.skip_hooks)?;
+      timer.stage("commit", || {
+         git_commit(
+            &formatted_message,
+            args.dry_run,
+            &args.dir,
+            sign,
+            signoff,
+            args.skip_hooks,
+            empty_commit,
+            &commit_dates,
+         )
+      })?;
+
+      let tagged = release_tag.is_some();
+      if let Some(tag) = release_tag {
+         if args.dry_run {
+            println!("{}", style::info(&format!("Would tag the release {tag}")));
+         } else {
+            git::create_tag(&tag, &format!("Release {tag}"), &args.dir)?;
+            if !style::is_quiet() {
+               println!("{}", style::success(&format!("Tagged {tag}")));
+            }
+         }
+      }
 
       // Auto-push if requested (only if not dry-run)
       if args.push && !args.dry_run {
-         git_push(&args.dir)?;
+         timer.stage("push", || git_push(&args.dir, tagged))?;
       }
    }
 
+   let stats = timer.finish();
+   if !style::is_quiet() {
+      println!("\n{} {}", style::dim("›"), style::dim(&stats.to_string()));
+   }
+   if std::env::var("LLM_GIT_VERBOSE").is_ok() {
+      println!("\nRun Stats:");
+      println!("{}", serde_json::to_string_pretty(&stats.to_json())?);
+   }
+   if let Some(debug_dir) = &args.debug_output {
+      save_debug_output(debug_dir, "stats.json", &serde_json::to_string_pretty(&stats.to_json())?)?;
+   }
+
    Ok(())
 }
 
@@ -721,19 +2124,202 @@ fn main() -> Result<()> {
 mod tests {
    use super::*;
 
+   #[test]
+   fn test_merge_subject_policy() {
+      let message = "fix(retry): combined backoff\n\n- Kept both sides.";
+      assert_eq!(
+         with_merge_subject(message, Some("Merge branch 'feature'"), MergeSubject::Keep),
+         "Merge branch 'feature'\n\nfix(retry): combined backoff\n\n- Kept both sides."
+      );
+      assert_eq!(
+         with_merge_subject(message, Some("Merge branch 'feature'"), MergeSubject::Replace),
+         message
+      );
+      assert_eq!(with_merge_subject(message, None, MergeSubject::Keep), message);
+   }
+
+   #[test]
+   fn test_temperature_jitter_only_on_regenerate() {
+      let config = CommitConfig { temperature: 0.5, temperature_jitter: 0.1, ..Default::default() };
+
+      let mut plain = config.clone();
+      apply_cli_overrides(&mut plain, &Args::default()).unwrap();
+      assert!((plain.temperature - 0.5).abs() < f32::EPSILON);
+
+      let args = Args { regenerate: true, ..Args::default() };
+      for _ in 0..50 {
+         let mut jittered = config.clone();
+         apply_cli_overrides(&mut jittered, &args).unwrap();
+         assert!((0.4..=0.6).contains(&jittered.temperature), "{}", jittered.temperature);
+      }
+
+      assert!((jittered_temperature(0.5, 0.1, 0.0) - 0.4).abs() < 1e-6);
+      assert!((jittered_temperature(0.5, 0.1, 0.999_999) - 0.6).abs() < 1e-5);
+      assert!((jittered_temperature(0.5, 0.0, 0.9) - 0.5).abs() < f32::EPSILON);
+      assert!(jittered_temperature(0.95, 0.2, 0.999).abs() <= 1.0);
+      assert!(jittered_temperature(0.05, 0.2, 0.0) >= 0.0);
+   }
+
+   fn parse_args(argv: &[&str]) -> (Args, ArgMatches) {
+      let matches = Args::command().try_get_matches_from(argv).unwrap();
+      (Args::from_arg_matches(&matches).unwrap(), matches)
+   }
+
+   #[test]
+   fn test_cli_defaults_fill_unpassed_flags() {
+      let defaults = CliDefaults {
+         model: Some("opus".to_string()),
+         copy: Some(true),
+         dry_run: Some(true),
+         push: Some(false),
+         ..Default::default()
+      };
+
+      let (mut args, matches) = parse_args(&["lgit"]);
+      assert!(apply_cli_defaults(&mut args, &defaults, &matches).is_empty());
+      assert!(args.copy && args.dry_run && !args.push);
+      assert_eq!(args.model.as_deref(), Some("opus"));
+
+      // Passed flags keep their value, including ones the table turns off
+      let (mut args, matches) = parse_args(&["lgit", "--push", "-m", "haiku"]);
+      assert!(apply_cli_defaults(&mut args, &defaults, &matches).is_empty());
+      assert!(args.copy && args.dry_run && args.push);
+      assert_eq!(args.model.as_deref(), Some("haiku"));
+
+      // Unset entries leave the flag alone
+      let (mut args, matches) = parse_args(&["lgit"]);
+      apply_cli_defaults(&mut args, &CliDefaults::default(), &matches);
+      assert!(!args.copy && !args.dry_run && args.model.is_none());
+   }
+
+   #[test]
+   fn test_cli_defaults_conflicts_favor_command_line() {
+      let defaults = CliDefaults {
+         compose: Some(true),
+         dry_run: Some(true),
+         signoff: Some(true),
+         ..Default::default()
+      };
+      let (mut args, matches) = parse_args(&["lgit", "--rewrite"]);
+      let warnings = apply_cli_defaults(&mut args, &defaults, &matches);
+      assert!(args.rewrite && !args.compose && !args.dry_run && args.signoff);
+      assert_eq!(warnings, vec![
+         "Ignoring cli_defaults.dry_run: conflicts with --rewrite",
+         "Ignoring cli_defaults.compose: conflicts with --rewrite",
+      ]);
+
+      // Every dropped default is reported
+      let defaults = CliDefaults { push: Some(true), compose: Some(true), ..Default::default() };
+      let (mut args, matches) = parse_args(&["lgit", "--watch"]);
+      let warnings = apply_cli_defaults(&mut args, &defaults, &matches);
+      assert!(args.watch && !args.push && !args.compose);
+      assert_eq!(warnings.len(), 2);
+   }
+
+   #[test]
+   fn test_forced_type_replaces_model_type() {
+      let mut analysis: ConventionalAnalysis = serde_json::from_str(
+         r#"{"type":"feat","scope":"api","details":["Added retry to the client."],"issue_refs":[]}"#,
+      )
+      .unwrap();
+      assert!(!apply_forced_type(&mut analysis, None));
+      assert_eq!(analysis.commit_type.as_str(), "feat");
+
+      let forced = CommitType::new("fix").unwrap();
+      assert!(apply_forced_type(&mut analysis, Some(&forced)));
+      assert!(!apply_forced_type(&mut analysis, Some(&forced)));
+
+      let commit = ConventionalCommit {
+         commit_type: analysis.commit_type.clone(),
+         scope:       analysis.scope.clone(),
+         summary:     CommitSummary::new("added retry to the client", 72).unwrap(),
+         body:        analysis.body_texts(),
+         footers:     vec![],
+      };
+      let message = format_commit_message(&commit, config::BodyStyle::Bullets);
+      assert!(message.starts_with("fix(api): added retry"), "{message}");
+      assert!(forced_type_instruction(&forced).contains("`fix`"));
+   }
+
+   #[test]
+   fn test_base_url_override() {
+      let args =
+         Args { base_url: Some("http://localhost:4000/v1/".to_string()), ..Args::default() };
+      let mut config = CommitConfig::default();
+      apply_cli_overrides(&mut config, &args).unwrap();
+      assert_eq!(config.api_base_url, "http://localhost:4000/v1");
+
+      assert_eq!(
+         parse_base_url("gateway.example.com/v1").unwrap(),
+         "https://gateway.example.com/v1"
+      );
+      assert!(parse_base_url("ftp://gateway.example.com").is_err());
+      assert!(parse_base_url("http://").is_err());
+      assert!(parse_base_url("http://exa mple.com").is_err());
+   }
+
+   #[test]
+   fn test_context_url_requires_issue_url_and_token() {
+      let mut args = Args {
+         context_url: Some("https://github.com/vowstar/llm-git/tree/main".to_string()),
+         ..Default::default()
+      };
+      let err = apply_context_url(&mut args, &CommitConfig::default()).unwrap_err();
+      assert!(matches!(err, CommitGenError::ValidationError(ref m) if m.contains("--context-url")));
+
+      // Without a token the URL is skipped, not fetched
+      args.context_url = Some("https://github.com/vowstar/llm-git/pull/7".to_string());
+      args.context = vec!["keep".to_string()];
+      apply_context_url(&mut args, &CommitConfig::default()).unwrap();
+      assert_eq!(args.context, vec!["keep"]);
+      assert!(args.fixes.is_empty() && args.refs.is_empty());
+   }
+
+   #[test]
+   fn test_context_presets_prepend_to_context() {
+      let mut config = CommitConfig::default();
+      config
+         .context_presets
+         .insert("payments".to_string(), "Payments service, PCI-sensitive.".to_string());
+      config
+         .context_presets
+         .insert("ui".to_string(), "Web frontend.".to_string());
+
+      let mut args = Args {
+         context_preset: vec!["payments".to_string(), "ui".to_string()],
+         context: vec!["Fixed".to_string(), "rounding".to_string()],
+         ..Default::default()
+      };
+      apply_context_presets(&mut args, &config).unwrap();
+      assert_eq!(
+         args.context.join(" "),
+         "Payments service, PCI-sensitive. Web frontend. Fixed rounding"
+      );
+
+      let mut args = Args { context_preset: vec!["billing".to_string()], ..Default::default() };
+      let err = apply_context_presets(&mut args, &config).unwrap_err();
+      assert_eq!(
+         err.to_string(),
+         "Validation failed: Unknown context preset 'billing' (available: payments, ui)"
+      );
+
+      let err = apply_context_presets(&mut args, &CommitConfig::default()).unwrap_err();
+      assert!(err.to_string().ends_with("(available: none configured)"));
+   }
+
    // ========== build_footers Tests ==========
 
    #[test]
    fn test_build_footers_empty() {
       let args = Args::default();
-      let footers = build_footers(&args);
+      let footers = build_footers(&args, &CommitConfig::default());
       assert_eq!(footers, Vec::<String>::new());
    }
 
    #[test]
    fn test_build_footers_cli_fixes() {
       let args = Args { fixes: vec!["123".to_string(), "#456".to_string()], ..Default::default() };
-      let footers = build_footers(&args);
+      let footers = build_footers(&args, &CommitConfig::default());
       assert_eq!(footers, vec!["Fixes #123", "Fixes #456"]);
    }
 
@@ -747,24 +2333,48 @@ mod tests {
          ..Default::default()
       };
 
-      let footers = build_footers(&args);
+      let footers = build_footers(&args, &CommitConfig::default());
       assert_eq!(footers, vec!["Fixes #1", "Closes #2", "Resolves #3", "Refs #4"]);
    }
 
    #[test]
    fn test_build_footers_cli_only() {
       let args = Args { fixes: vec!["123".to_string()], ..Default::default() };
-      let footers = build_footers(&args);
+      let footers = build_footers(&args, &CommitConfig::default());
       assert_eq!(footers, vec!["Fixes #123"]);
    }
 
    #[test]
    fn test_build_footers_breaking_change() {
       let args = Args { breaking: true, ..Default::default() };
-      let footers = build_footers(&args);
+      let footers = build_footers(&args, &CommitConfig::default());
       assert_eq!(footers, vec!["BREAKING CHANGE: This commit introduces breaking changes"]);
    }
 
+   #[test]
+   fn test_build_footers_generator_trailer_last() {
+      let args = Args { fixes: vec!["7".to_string()], breaking: true, ..Default::default() };
+      let config = CommitConfig {
+         model: "claude-sonnet-4.5".to_string(),
+         add_generator_trailer: true,
+         ..Default::default()
+      };
+
+      let footers = build_footers(&args, &config);
+      assert_eq!(footers, vec![
+         "Fixes #7",
+         "BREAKING CHANGE: This commit introduces breaking changes",
+         "Generated-by: llm-git (claude-sonnet-4.5)"
+      ]);
+      assert_eq!(
+         footers
+            .iter()
+            .filter(|f| f.starts_with("Generated-by:"))
+            .count(),
+         1
+      );
+   }
+
    #[test]
    fn test_build_footers_combined() {
       let args = Args {
@@ -774,11 +2384,59 @@ mod tests {
          ..Default::default()
       };
 
-      let footers = build_footers(&args);
+      let footers = build_footers(&args, &CommitConfig::default());
       assert_eq!(footers, vec![
          "Fixes #100",
          "Refs #200",
          "BREAKING CHANGE: This commit introduces breaking changes"
       ]);
    }
+
+   #[test]
+   fn test_no_footers_empties_built_footers() {
+      let args = Args {
+         fixes: vec!["100".to_string()],
+         breaking: true,
+         no_footers: true,
+         ..Default::default()
+      };
+      let mut commit = ConventionalCommit {
+         commit_type: CommitType::new("feat").unwrap(),
+         scope:       None,
+         summary:     CommitSummary::new("added retry to the client", 72).unwrap(),
+         body:        vec!["Retried failed requests.".to_string()],
+         footers:     build_footers(&args, &CommitConfig::default()),
+      };
+      assert_eq!(commit.footers.len(), 2);
+
+      strip_footers(&mut commit, &args);
+      assert!(commit.footers.is_empty());
+      let message = format_commit_message(&commit, config::BodyStyle::Bullets);
+      assert_eq!(message, "feat: added retry to the client\n\n- Retried failed requests.");
+
+      // Without the flag the footers stay
+      let mut commit = ConventionalCommit { footers: vec!["Fixes #1".to_string()], ..commit };
+      strip_footers(&mut commit, &Args::default());
+      assert_eq!(commit.footers, vec!["Fixes #1"]);
+   }
+
+   #[test]
+   fn test_init_config_refuses_to_overwrite_without_force() {
+      let dir = std::env::temp_dir().join(format!("lgit-init-config-{}", std::process::id()));
+      let path = dir.join("nested/config.toml");
+      let args = Args { config: Some(path.clone()), init_config: true, ..Default::default() };
+
+      run_init_config(&args).unwrap();
+      let written = std::fs::read_to_string(&path).unwrap();
+      assert_eq!(written, CommitConfig::default_config_toml().unwrap());
+
+      std::fs::write(&path, "model = \"mine\"\n").unwrap();
+      let err = run_init_config(&args).unwrap_err();
+      assert!(err.to_string().contains("pass --force to overwrite it"), "{err}");
+      assert_eq!(std::fs::read_to_string(&path).unwrap(), "model = \"mine\"\n");
+
+      run_init_config(&Args { force: true, ..args }).unwrap();
+      assert_eq!(std::fs::read_to_string(&path).unwrap(), written);
+      std::fs::remove_dir_all(&dir).ok();
+   }
 }
@@ -3,16 +3,27 @@
 //! When diffs exceed the token threshold, this module splits analysis across
 //! files, then synthesizes results for accurate classification.
 
-use std::path::Path;
+use std::{
+   io::IsTerminal,
+   path::Path,
+   sync::{
+      Arc,
+      atomic::{AtomicUsize, Ordering},
+   },
+};
 
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-   api::retry_api_call,
+   api::{
+      AnalysisContext, add_rationale_property, build_client, non_json_error, response_content_type,
+      retry_api_call, retry_api_call_with,
+   },
    config::{CommitConfig, ResolvedApiMode},
-   diff::{FileDiff, parse_diff, reconstruct_diff},
+   diff::{FileDiff, parse_diff, reconstruct_diff, sample_note, sample_representative_files},
    error::{CommitGenError, Result},
+   rate_limit::{RateLimiter, parse_retry_after, rate_limit_error},
    templates,
    tokens::TokenCounter,
    types::ConventionalAnalysis,
@@ -46,12 +57,7 @@ pub fn should_use_map_reduce(diff: &str, config: &CommitConfig, counter: &TokenC
    let files = parse_diff(diff);
    let file_count = files
       .iter()
-      .filter(|f| {
-         !config
-            .excluded_files
-            .iter()
-            .any(|ex| f.filename.ends_with(ex))
-      })
+      .filter(|f| !config.is_excluded_file(&f.filename))
       .count();
 
    // Use map-reduce for 4+ files, or if any single file would need truncation
@@ -160,6 +166,135 @@ fn infer_file_description(filename: &str, content: &str) -> &'static str {
    "source code"
 }
 
+/// Completed-file count for the map phase, shared by the rayon workers.
+///
+/// Each finished file prints one `[k/N] analyzed <file>` line to stderr, or
+/// `[k/N] failed <file>` when its map call failed; lines are written whole,
+/// so parallel workers never interleave mid-line.
+#[derive(Debug)]
+pub struct MapProgress {
+   done:    AtomicUsize,
+   total:   usize,
+   enabled: bool,
+}
+
+impl MapProgress {
+   /// Progress over `total` files, printed per `show_progress` (default: when
+   /// stderr is a terminal), never in quiet mode
+   pub fn new(total: usize, config: &CommitConfig) -> Self {
+      let enabled = !crate::style::is_quiet()
+         && config
+            .show_progress
+            .unwrap_or_else(|| std::io::stderr().is_terminal());
+      Self { done: AtomicUsize::new(0), total, enabled }
+   }
+
+   /// Record `file` as done, `analyzed` or failed, and return how many files
+   /// are done
+   pub fn complete(&self, file: &str, analyzed: bool) -> usize {
+      let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+      if self.enabled {
+         // Clear a spinner line first, like `style::print_info`
+         let clear = if std::io::stderr().is_terminal() {
+            "\r\x1b[K"
+         } else {
+            ""
+         };
+         eprintln!("{clear}{}", self.line(done, file, analyzed));
+      }
+      done
+   }
+
+   fn line(&self, done: usize, file: &str, analyzed: bool) -> String {
+      let status = if analyzed { "analyzed" } else { "failed" };
+      format!("[{done}/{}] {status} {file}", self.total)
+   }
+
+   pub fn done(&self) -> usize {
+      self.done.load(Ordering::Relaxed)
+   }
+}
+
+/// Observations for one file of the map phase: canned for binary, LFS,
+/// mode-only, symlink, renamed and deleted files, otherwise from the model
+fn observe_file(
+   file: &FileDiff,
+   files: &[FileDiff],
+   model_name: &str,
+   config: &CommitConfig,
+   counter: &TokenCounter,
+   limiter: &Arc<RateLimiter>,
+) -> Result<FileObservation> {
+   if file.is_binary() {
+      return Ok(FileObservation {
+         file:         file.filename.clone(),
+         observations: vec!["Binary file changed.".to_string()],
+         additions:    0,
+         deletions:    0,
+      });
+   }
+
+   if let Some(note) = file.lfs_observation() {
+      return Ok(FileObservation {
+         file:         file.filename.clone(),
+         observations: vec![format!("{note}.")],
+         additions:    0,
+         deletions:    0,
+      });
+   }
+
+   if let Some(observation) = file.kind_observation() {
+      // A mode change can come with a rename (`git mv` then `chmod`)
+      let mut observations: Vec<String> = file.rename_observation().into_iter().collect();
+      observations.push(observation);
+      return Ok(FileObservation {
+         file: file.filename.clone(),
+         observations,
+         additions: file.additions,
+         deletions: file.deletions,
+      });
+   }
+
+   if let Some(observation) = file.rename_observation() {
+      return Ok(FileObservation {
+         file:         file.filename.clone(),
+         observations: vec![observation],
+         additions:    0,
+         deletions:    0,
+      });
+   }
+
+   if let Some(observation) = file.deletion_observation() {
+      return Ok(FileObservation {
+         file:         file.filename.clone(),
+         observations: vec![observation],
+         additions:    0,
+         deletions:    file.deleted_line_count(),
+      });
+   }
+
+   let context_header = generate_context_header(files, &file.filename);
+
+   // Truncate large files to fit API limits
+   let mut file_clone = file.clone();
+   let file_tokens = file_clone.token_estimate(counter);
+   if file_tokens > MAX_FILE_TOKENS {
+      let target_size = MAX_FILE_TOKENS * 4; // Convert tokens to chars
+      file_clone.truncate(target_size);
+      eprintln!(
+         "  {} truncated {} ({} → {} tokens)",
+         crate::style::icons::WARNING,
+         file.filename,
+         file_tokens,
+         file_clone.token_estimate(counter)
+      );
+   }
+
+   let file_diff = reconstruct_diff(&[file_clone]);
+
+   map_single_file(&file.filename, &file_diff, &context_header, model_name, config, limiter)
+}
+
 /// Map phase: analyze each file individually and extract observations
 fn map_phase(
    files: &[FileDiff],
@@ -167,39 +302,17 @@ fn map_phase(
    config: &CommitConfig,
    counter: &TokenCounter,
 ) -> Result<Vec<FileObservation>> {
+   // One limiter per phase so a rate limit pauses (and throttles) every worker
+   let limiter = Arc::new(RateLimiter::new(rayon::current_num_threads()));
+   let progress = MapProgress::new(files.len(), config);
+
    // Process files in parallel using rayon
    let observations: Vec<Result<FileObservation>> = files
       .par_iter()
       .map(|file| {
-         if file.is_binary {
-            return Ok(FileObservation {
-               file:         file.filename.clone(),
-               observations: vec!["Binary file changed.".to_string()],
-               additions:    0,
-               deletions:    0,
-            });
-         }
-
-         let context_header = generate_context_header(files, &file.filename);
-
-         // Truncate large files to fit API limits
-         let mut file_clone = file.clone();
-         let file_tokens = file_clone.token_estimate(counter);
-         if file_tokens > MAX_FILE_TOKENS {
-            let target_size = MAX_FILE_TOKENS * 4; // Convert tokens to chars
-            file_clone.truncate(target_size);
-            eprintln!(
-               "  {} truncated {} ({} → {} tokens)",
-               crate::style::icons::WARNING,
-               file.filename,
-               file_tokens,
-               file_clone.token_estimate(counter)
-            );
-         }
-
-         let file_diff = reconstruct_diff(&[file_clone]);
-
-         map_single_file(&file.filename, &file_diff, &context_header, model_name, config)
+         let observation = observe_file(file, files, model_name, config, counter, &limiter);
+         progress.complete(&file.filename, observation.is_ok());
+         observation
       })
       .collect();
 
@@ -214,9 +327,10 @@ fn map_single_file(
    context_header: &str,
    model_name: &str,
    config: &CommitConfig,
+   limiter: &Arc<RateLimiter>,
 ) -> Result<FileObservation> {
-   retry_api_call(config, || {
-      let client = build_client(config);
+   retry_api_call_with(config, limiter, || {
+      let client = build_client(config)?;
 
       let tool = build_observation_tool();
 
@@ -227,7 +341,7 @@ fn map_single_file(
          ResolvedApiMode::ChatCompletions => {
             let request = build_api_request(
                model_name,
-               config.temperature,
+               config.temperature_for(model_name),
                vec![tool],
                &parts.system,
                &parts.user,
@@ -248,8 +362,17 @@ fn map_single_file(
                .map_err(CommitGenError::HttpError)?;
 
             let status = response.status();
+            let retry_after = parse_retry_after(response.headers());
+            let content_type = response_content_type(response.headers());
             let response_text = response.text().map_err(CommitGenError::HttpError)?;
 
+            if let Some(err) = rate_limit_error(status, retry_after, &response_text) {
+               return Err(err);
+            }
+            if let Some(err) = non_json_error(status, &content_type, &response_text) {
+               return Err(err);
+            }
+
             if status.is_server_error() {
                eprintln!(
                   "{}",
@@ -271,7 +394,7 @@ fn map_single_file(
             let request = AnthropicRequest {
                model:       model_name.to_string(),
                max_tokens:  1500,
-               temperature: config.temperature,
+               temperature: config.temperature_for(model_name),
                system:      if parts.system.is_empty() {
                   None
                } else {
@@ -320,8 +443,17 @@ fn map_single_file(
                .map_err(CommitGenError::HttpError)?;
 
             let status = response.status();
+            let retry_after = parse_retry_after(response.headers());
+            let content_type = response_content_type(response.headers());
             let response_text = response.text().map_err(CommitGenError::HttpError)?;
 
+            if let Some(err) = rate_limit_error(status, retry_after, &response_text) {
+               return Err(err);
+            }
+            if let Some(err) = non_json_error(status, &content_type, &response_text) {
+               return Err(err);
+            }
+
             if status.is_server_error() {
                eprintln!(
                   "{}",
@@ -363,30 +495,27 @@ fn map_single_file(
 
             let message = &api_response.choices[0].message;
 
-            if !message.tool_calls.is_empty() {
-               let tool_call = &message.tool_calls[0];
-               if tool_call.function.name.ends_with("create_file_observation") {
-                  let args = &tool_call.function.arguments;
-                  if args.is_empty() {
-                     return Err(CommitGenError::Other(
-                        "Model returned empty function arguments for observation".to_string(),
-                     ));
-                  }
-
-                  let obs: FileObservationResponse = serde_json::from_str(args).map_err(|e| {
-                     CommitGenError::Other(format!("Failed to parse observation response: {e}"))
-                  })?;
-
-                  return Ok((
-                     false,
-                     Some(FileObservation {
-                        file:         filename.to_string(),
-                        observations: obs.observations,
-                        additions:    0, // Will be filled from FileDiff
-                        deletions:    0,
-                     }),
+            if let Some(tool_call) = message.find_tool_call("create_file_observation") {
+               let args = &tool_call.function.arguments;
+               if args.is_empty() {
+                  return Err(CommitGenError::Other(
+                     "Model returned empty function arguments for observation".to_string(),
                   ));
                }
+
+               let obs: FileObservationResponse = serde_json::from_str(args).map_err(|e| {
+                  CommitGenError::Other(format!("Failed to parse observation response: {e}"))
+               })?;
+
+               return Ok((
+                  false,
+                  Some(FileObservation {
+                     file:         filename.to_string(),
+                     observations: obs.observations,
+                     additions:    0, // Will be filled from FileDiff
+                     deletions:    0,
+                  }),
+               ));
             }
 
             // Fallback: try to parse content
@@ -489,20 +618,25 @@ fn map_single_file(
 }
 
 /// Reduce phase: synthesize all observations into final analysis
+///
+/// `sample_note` tells the model when the observations cover only a sample of
+/// the commit's files; `ctx` supplies the user context and change profile.
 pub fn reduce_phase(
    observations: &[FileObservation],
    stat: &str,
    scope_candidates: &str,
+   sample_note: Option<&str>,
+   ctx: &AnalysisContext<'_>,
    model_name: &str,
    config: &CommitConfig,
 ) -> Result<ConventionalAnalysis> {
    retry_api_call(config, || {
-      let client = build_client(config);
+      let client = build_client(config)?;
 
       // Build type enum from config
       let type_enum: Vec<&str> = config.types.keys().map(|s| s.as_str()).collect();
 
-      let tool = build_analysis_tool(&type_enum);
+      let tool = build_analysis_tool(&type_enum, config);
 
       let observations_json =
          serde_json::to_string_pretty(observations).unwrap_or_else(|_| "[]".to_string());
@@ -513,15 +647,25 @@ pub fn reduce_phase(
          &observations_json,
          stat,
          scope_candidates,
+         ctx.change_profile,
          Some(&types_description),
+         config.body_tense,
+         sample_note,
       )?;
+      let parts = match ctx.user_context {
+         Some(user_context) => templates::PromptParts {
+            system: parts.system,
+            user:   format!("ADDITIONAL CONTEXT FROM USER:\n{user_context}\n\n{}", parts.user),
+         },
+         None => parts,
+      };
       let mode = config.resolved_api_mode(model_name);
 
       let response_text = match mode {
          ResolvedApiMode::ChatCompletions => {
             let request = build_api_request(
                model_name,
-               config.temperature,
+               config.temperature_for(model_name),
                vec![tool],
                &parts.system,
                &parts.user,
@@ -542,8 +686,17 @@ pub fn reduce_phase(
                .map_err(CommitGenError::HttpError)?;
 
             let status = response.status();
+            let retry_after = parse_retry_after(response.headers());
+            let content_type = response_content_type(response.headers());
             let response_text = response.text().map_err(CommitGenError::HttpError)?;
 
+            if let Some(err) = rate_limit_error(status, retry_after, &response_text) {
+               return Err(err);
+            }
+            if let Some(err) = non_json_error(status, &content_type, &response_text) {
+               return Err(err);
+            }
+
             if status.is_server_error() {
                eprintln!(
                   "{}",
@@ -562,10 +715,10 @@ pub fn reduce_phase(
             response_text
          },
          ResolvedApiMode::AnthropicMessages => {
-            let request = AnthropicRequest {
+            let mut request = AnthropicRequest {
                model:       model_name.to_string(),
                max_tokens:  1500,
-               temperature: config.temperature,
+               temperature: config.temperature_for(model_name),
                system:      if parts.system.is_empty() {
                   None
                } else {
@@ -590,13 +743,13 @@ pub fn reduce_phase(
                         },
                         "details": {
                            "type": "array",
-                           "description": "Array of 0-6 detail items with changelog metadata.",
+                           "description": "Array of 0-6 detail items with changelog metadata. Any deleted or moved file over 100 lines must be mentioned by name.",
                            "items": {
                               "type": "object",
                               "properties": {
                                  "text": {
                                     "type": "string",
-                                    "description": "Detail about change, starting with past-tense verb, ending with period"
+                                    "description": format!("Detail about change, starting with {} verb, ending with period", config.body_tense.verb_form())
                                  },
                                  "changelog_category": {
                                     "type": "string",
@@ -634,6 +787,7 @@ pub fn reduce_phase(
                   }],
                }],
             };
+            add_rationale_property(&mut request.tools[0].input_schema["properties"], config);
 
             let mut request_builder = client
                .post(anthropic_messages_url(&config.api_base_url))
@@ -650,8 +804,17 @@ pub fn reduce_phase(
                .map_err(CommitGenError::HttpError)?;
 
             let status = response.status();
+            let retry_after = parse_retry_after(response.headers());
+            let content_type = response_content_type(response.headers());
             let response_text = response.text().map_err(CommitGenError::HttpError)?;
 
+            if let Some(err) = rate_limit_error(status, retry_after, &response_text) {
+               return Err(err);
+            }
+            if let Some(err) = non_json_error(status, &content_type, &response_text) {
+               return Err(err);
+            }
+
             if status.is_server_error() {
                eprintln!(
                   "{}",
@@ -693,26 +856,19 @@ pub fn reduce_phase(
 
             let message = &api_response.choices[0].message;
 
-            if !message.tool_calls.is_empty() {
-               let tool_call = &message.tool_calls[0];
-               if tool_call
-                  .function
-                  .name
-                  .ends_with("create_conventional_analysis")
-               {
-                  let args = &tool_call.function.arguments;
-                  if args.is_empty() {
-                     return Err(CommitGenError::Other(
-                        "Model returned empty function arguments for synthesis".to_string(),
-                     ));
-                  }
+            if let Some(tool_call) = message.find_tool_call("create_conventional_analysis") {
+               let args = &tool_call.function.arguments;
+               if args.is_empty() {
+                  return Err(CommitGenError::Other(
+                     "Model returned empty function arguments for synthesis".to_string(),
+                  ));
+               }
 
-                  let analysis: ConventionalAnalysis = serde_json::from_str(args).map_err(|e| {
-                     CommitGenError::Other(format!("Failed to parse synthesis response: {e}"))
-                  })?;
+               let analysis: ConventionalAnalysis = serde_json::from_str(args).map_err(|e| {
+                  CommitGenError::Other(format!("Failed to parse synthesis response: {e}"))
+               })?;
 
-                  return Ok((false, Some(analysis)));
-               }
+               return Ok((false, Some(analysis)));
             }
 
             // Fallback
@@ -771,11 +927,13 @@ pub fn reduce_phase(
    })
 }
 
-/// Run full map-reduce pipeline for large diffs
+/// Run full map-reduce pipeline for large diffs; `ctx` reaches the reduce
+/// prompt
 pub fn run_map_reduce(
    diff: &str,
    stat: &str,
    scope_candidates: &str,
+   ctx: &AnalysisContext<'_>,
    model_name: &str,
    config: &CommitConfig,
    counter: &TokenCounter,
@@ -783,12 +941,7 @@ pub fn run_map_reduce(
    let mut files = parse_diff(diff);
 
    // Filter excluded files
-   files.retain(|f| {
-      !config
-         .excluded_files
-         .iter()
-         .any(|excluded| f.filename.ends_with(excluded))
-   });
+   files.retain(|f| !config.is_excluded_file(&f.filename));
 
    if files.is_empty() {
       return Err(CommitGenError::Other(
@@ -797,29 +950,119 @@ pub fn run_map_reduce(
    }
 
    let file_count = files.len();
-   crate::style::print_info(&format!("Running map-reduce on {file_count} files..."));
+
+   // Gigantic commits (vendored imports): analyze a representative sample
+   let note = if config.max_analyzed_files > 0 && file_count > config.max_analyzed_files {
+      files = sample_representative_files(&files, config.max_analyzed_files);
+      crate::style::print_info(&format!(
+         "Commit touches {file_count} files; analyzing a representative sample of {}",
+         files.len()
+      ));
+      Some(sample_note(file_count, &files))
+   } else {
+      crate::style::print_info(&format!("Running map-reduce on {file_count} files..."));
+      None
+   };
 
    // Map phase
    let observations = map_phase(&files, model_name, config, counter)?;
 
-   // Reduce phase
-   reduce_phase(&observations, stat, scope_candidates, model_name, config)
+   // Reduce phase: observations stand in for the diff, so they get the diff's
+   // token budget; larger sets are reduced in groups first
+   reduce_hierarchically(observations, counter, config.max_diff_tokens, |group| {
+      reduce_phase(group, stat, scope_candidates, note.as_deref(), ctx, model_name, config)
+   })
 }
 
-// ============================================================================
-// API types (duplicated from api.rs to avoid circular deps)
-// ============================================================================
+/// Reduce `observations`, first in groups of at most `budget` tokens (and
+/// then groups of the group summaries) while they don't fit one call
+fn reduce_hierarchically(
+   mut observations: Vec<FileObservation>,
+   counter: &TokenCounter,
+   budget: usize,
+   reduce: impl Fn(&[FileObservation]) -> Result<ConventionalAnalysis>,
+) -> Result<ConventionalAnalysis> {
+   loop {
+      let groups = observation_groups(&observations, counter, budget);
+      // One observation per group can't shrink further by grouping
+      if groups.len() <= 1 || groups.len() == observations.len() {
+         return reduce(&observations);
+      }
+
+      crate::style::print_info(&format!(
+         "Reducing {} observations in {} groups first",
+         observations.len(),
+         groups.len()
+      ));
+      observations = groups
+         .iter()
+         .map(|group| reduce(group).map(|analysis| group_observation(group, &analysis)))
+         .collect::<Result<_>>()?;
+   }
+}
+
+/// Split observations into consecutive groups of at most `budget` tokens; an
+/// observation over budget on its own gets a group of its own
+fn observation_groups<'a>(
+   observations: &'a [FileObservation],
+   counter: &TokenCounter,
+   budget: usize,
+) -> Vec<&'a [FileObservation]> {
+   let mut groups = Vec::new();
+   let mut start = 0;
+   let mut tokens = 0;
+   for (i, observation) in observations.iter().enumerate() {
+      let json = serde_json::to_string_pretty(observation).unwrap_or_default();
+      let cost = counter.count_sync(&json);
+      if i > start && tokens + cost > budget {
+         groups.push(&observations[start..i]);
+         start = i;
+         tokens = 0;
+      }
+      tokens += cost;
+   }
+   if start < observations.len() {
+      groups.push(&observations[start..]);
+   }
+   groups
+}
 
-use std::time::Duration;
+/// Stand-in observation for a reduced group, carrying its classification,
+/// details and issue references into the next reduce
+fn group_observation(
+   group: &[FileObservation],
+   analysis: &ConventionalAnalysis,
+) -> FileObservation {
+   let scope = analysis
+      .scope
+      .as_ref()
+      .map(|scope| format!(" in scope {scope}"))
+      .unwrap_or_default();
+   let mut observations = vec![format!("Classified as {}{scope}.", analysis.commit_type)];
+   observations.extend(analysis.details.iter().map(|detail| {
+      match detail.changelog_category.filter(|_| detail.user_visible) {
+         Some(category) => format!("[{}] {}", category.as_str(), detail.text),
+         None => detail.text.clone(),
+      }
+   }));
+   if !analysis.issue_refs.is_empty() {
+      observations.push(format!("References {}.", analysis.issue_refs.join(", ")));
+   }
 
-fn build_client(config: &CommitConfig) -> reqwest::blocking::Client {
-   reqwest::blocking::Client::builder()
-      .timeout(Duration::from_secs(config.request_timeout_secs))
-      .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
-      .build()
-      .expect("Failed to build HTTP client")
+   let first = group.first().map_or("", |o| o.file.as_str());
+   let last = group.last().map_or("", |o| o.file.as_str());
+   FileObservation {
+      file: format!("{} files, {first} to {last}", group.len()),
+      observations,
+      additions: group.iter().map(|o| o.additions).sum(),
+      deletions: group.iter().map(|o| o.deletions).sum(),
+   }
 }
 
+// ============================================================================
+// API types (duplicated from api.rs to avoid circular deps)
+// ============================================================================
+
 fn response_snippet(body: &str, limit: usize) -> String {
    if body.is_empty() {
       return "<empty response body>".to_string();
@@ -943,7 +1186,8 @@ struct Tool {
 struct ApiRequest {
    model:       String,
    max_tokens:  u32,
-   temperature: f32,
+   #[serde(skip_serializing_if = "Option::is_none")]
+   temperature: Option<f32>,
    tools:       Vec<Tool>,
    #[serde(skip_serializing_if = "Option::is_none")]
    tool_choice: Option<serde_json::Value>,
@@ -954,7 +1198,8 @@ struct ApiRequest {
 struct AnthropicRequest {
    model:       String,
    max_tokens:  u32,
-   temperature: f32,
+   #[serde(skip_serializing_if = "Option::is_none")]
+   temperature: Option<f32>,
    #[serde(skip_serializing_if = "Option::is_none")]
    system:      Option<String>,
    tools:       Vec<AnthropicTool>,
@@ -1014,6 +1259,17 @@ struct ResponseMessage {
    content:    Option<String>,
 }
 
+impl ResponseMessage {
+   /// First tool call whose name matches `name`; models may emit several
+   /// calls, or put the wanted one after others
+   fn find_tool_call(&self, name: &str) -> Option<&ToolCall> {
+      self
+         .tool_calls
+         .iter()
+         .find(|call| call.function.name.ends_with(name))
+   }
+}
+
 #[derive(Debug, Deserialize)]
 struct ApiResponse {
    choices: Vec<Choice>,
@@ -1122,8 +1378,8 @@ fn build_observation_tool() -> Tool {
    }
 }
 
-fn build_analysis_tool(type_enum: &[&str]) -> Tool {
-   Tool {
+fn build_analysis_tool(type_enum: &[&str], config: &CommitConfig) -> Tool {
+   let mut tool = Tool {
       tool_type: "function".to_string(),
       function:  Function {
          name:        "create_conventional_analysis".to_string(),
@@ -1142,13 +1398,13 @@ fn build_analysis_tool(type_enum: &[&str]) -> Tool {
                },
                "details": {
                   "type": "array",
-                  "description": "Array of 0-6 detail items with changelog metadata.",
+                  "description": "Array of 0-6 detail items with changelog metadata. Any deleted or moved file over 100 lines must be mentioned by name.",
                   "items": {
                      "type": "object",
                      "properties": {
                         "text": {
                            "type": "string",
-                           "description": "Detail about change, starting with past-tense verb, ending with period"
+                           "description": format!("Detail about change, starting with {} verb, ending with period", config.body_tense.verb_form())
                         },
                         "changelog_category": {
                            "type": "string",
@@ -1174,12 +1430,14 @@ fn build_analysis_tool(type_enum: &[&str]) -> Tool {
             required:   vec!["type".to_string(), "details".to_string(), "issue_refs".to_string()],
          },
       },
-   }
+   };
+   add_rationale_property(&mut tool.function.parameters.properties, config);
+   tool
 }
 
 fn build_api_request(
    model: &str,
-   temperature: f32,
+   temperature: Option<f32>,
    tools: Vec<Tool>,
    system: &str,
    user: &str,
@@ -1206,12 +1464,161 @@ fn build_api_request(
 #[cfg(test)]
 mod tests {
    use super::*;
-   use crate::tokens::TokenCounter;
+   use crate::{diff::ChangeKind, tokens::TokenCounter};
 
    fn test_counter() -> TokenCounter {
       TokenCounter::new("http://localhost:4000", None, "claude-sonnet-4.5")
    }
 
+   #[test]
+   fn test_map_progress_counts_every_parallel_completion() {
+      let config = CommitConfig { show_progress: Some(false), ..Default::default() };
+      let files: Vec<String> = (0..200).map(|i| format!("src/file_{i}.rs")).collect();
+      let progress = MapProgress::new(files.len(), &config);
+
+      let mut seen: Vec<usize> = files
+         .par_iter()
+         .map(|file| progress.complete(file, true))
+         .collect();
+      seen.sort_unstable();
+
+      assert_eq!(progress.done(), 200);
+      assert_eq!(seen, (1..=200).collect::<Vec<_>>());
+      assert_eq!(progress.line(3, "src/a.rs", true), "[3/200] analyzed src/a.rs");
+      assert_eq!(progress.line(4, "src/b.rs", false), "[4/200] failed src/b.rs");
+   }
+
+   #[test]
+   fn test_map_phase_synthesizes_deletion_and_rename_observations() {
+      // Offline: any attempt to call the API would fail the phase
+      let config = CommitConfig { offline_strict: true, ..Default::default() };
+      let diff = r"diff --git a/src/legacy/auth.rs b/src/legacy/auth.rs
+deleted file mode 100644
+index 123..000 100644
+--- a/src/legacy/auth.rs
++++ /dev/null
+@@ -1,412 +0,0 @@
+-pub fn login() {}
+diff --git a/src/io.rs b/src/io/mod.rs
+similarity index 100%
+rename from src/io.rs
+rename to src/io/mod.rs";
+      let files = parse_diff(diff);
+
+      let observations = map_phase(&files, "test-model", &config, &test_counter()).unwrap();
+      assert_eq!(observations.len(), 2);
+      assert_eq!(observations[0].observations, vec!["Deleted src/legacy/auth.rs (412 lines)."]);
+      assert_eq!(observations[0].deletions, 412);
+      assert_eq!(observations[1].observations, vec!["Renamed src/io.rs to src/io/mod.rs."]);
+   }
+
+   #[test]
+   fn test_map_phase_synthesizes_mode_and_symlink_observations() {
+      let config = CommitConfig { offline_strict: true, ..Default::default() };
+      let diff = r"diff --git a/scripts/deploy.sh b/scripts/deploy.sh
+old mode 100644
+new mode 100755
+diff --git a/bin/run b/tools/run
+old mode 100755
+new mode 100644
+similarity index 100%
+rename from bin/run
+rename to tools/run
+diff --git a/current b/current
+index 1111111..2222222 120000
+--- a/current
++++ b/current
+@@ -1 +1 @@
+-releases/v1
+\ No newline at end of file
++releases/v2
+\ No newline at end of file";
+      let files = parse_diff(diff);
+
+      let observations = map_phase(&files, "test-model", &config, &test_counter()).unwrap();
+      assert_eq!(observations[0].observations, vec![
+         "Changed file mode of scripts/deploy.sh to 100755."
+      ]);
+      assert_eq!(observations[1].observations, vec![
+         "Renamed bin/run to tools/run.",
+         "Changed file mode of tools/run to 100644."
+      ]);
+      assert_eq!(observations[2].observations, vec![
+         "Retargeted symlink current from releases/v1 to releases/v2."
+      ]);
+   }
+
+   #[test]
+   fn test_reduce_hierarchically_groups_large_observation_sets() {
+      let observations: Vec<FileObservation> = (0..300)
+         .map(|i| FileObservation {
+            file:         format!("src/handlers/handler_{i}.rs"),
+            observations: vec![format!(
+               "Replaced the manual retry loop in handler {i} with the shared backoff helper."
+            )],
+            additions:    3,
+            deletions:    10,
+         })
+         .collect();
+      let calls = parking_lot::Mutex::new(Vec::new());
+
+      let analysis = reduce_hierarchically(observations, &test_counter(), 2_000, |group| {
+         calls.lock().push(group.to_vec());
+         Ok(ConventionalAnalysis {
+            commit_type: crate::types::CommitType::new("refactor").unwrap(),
+            scope:       None,
+            details:     vec![crate::types::AnalysisDetail::simple("Shared retry backoff.")],
+            issue_refs:  vec!["#42".to_string()],
+            rationale:   None,
+         })
+      })
+      .unwrap();
+      assert_eq!(analysis.commit_type.as_str(), "refactor");
+
+      let calls = calls.into_inner();
+      let (last, groups) = calls.split_last().unwrap();
+      assert!(groups.len() > 1, "{} calls", calls.len());
+      assert_eq!(groups.iter().map(Vec::len).sum::<usize>(), 300);
+      assert!(groups.iter().all(|group| group.len() < 300));
+
+      // The final reduce sees one summary per group
+      assert_eq!(last.len(), groups.len());
+      assert_eq!(
+         last[0].file,
+         format!(
+            "{} files, src/handlers/handler_0.rs to {}",
+            groups[0].len(),
+            groups[0].last().unwrap().file
+         )
+      );
+      assert_eq!(last[0].observations, vec![
+         "Classified as refactor.",
+         "Shared retry backoff.",
+         "References #42."
+      ]);
+      assert_eq!(last.iter().map(|o| o.deletions).sum::<usize>(), 3000);
+   }
+
+   #[test]
+   fn test_reduce_hierarchically_small_set_reduces_once() {
+      let observations = vec![FileObservation {
+         file:         "src/lib.rs".to_string(),
+         observations: vec!["Exported the parser.".to_string()],
+         additions:    1,
+         deletions:    0,
+      }];
+      let calls = AtomicUsize::new(0);
+
+      let budget = CommitConfig::default().max_diff_tokens;
+      reduce_hierarchically(observations, &test_counter(), budget, |group| {
+         calls.fetch_add(1, Ordering::Relaxed);
+         assert_eq!(group.len(), 1);
+         Err(CommitGenError::Other("stop".to_string()))
+      })
+      .unwrap_err();
+      assert_eq!(calls.load(Ordering::Relaxed), 1);
+   }
+
    #[test]
    fn test_should_use_map_reduce_disabled() {
       let config = CommitConfig { map_reduce_enabled: false, ..Default::default() };
@@ -1277,7 +1684,7 @@ diff --git a/e.rs b/e.rs
          content:   String::new(),
          additions: 10,
          deletions: 5,
-         is_binary: false,
+         kind:      ChangeKind::Content,
       }];
       let header = generate_context_header(&files, "only.rs");
       assert!(header.is_empty());
@@ -1292,7 +1699,7 @@ diff --git a/e.rs b/e.rs
             content:   "fn main() {}".to_string(),
             additions: 10,
             deletions: 5,
-            is_binary: false,
+            kind:      ChangeKind::Content,
          },
          FileDiff {
             filename:  "src/lib.rs".to_string(),
@@ -1300,7 +1707,7 @@ diff --git a/e.rs b/e.rs
             content:   "mod test;".to_string(),
             additions: 3,
             deletions: 1,
-            is_binary: false,
+            kind:      ChangeKind::Content,
          },
          FileDiff {
             filename:  "tests/test.rs".to_string(),
@@ -1308,7 +1715,7 @@ diff --git a/e.rs b/e.rs
             content:   "#[test]".to_string(),
             additions: 20,
             deletions: 0,
-            is_binary: false,
+            kind:      ChangeKind::Content,
          },
       ];
 
@@ -1380,4 +1787,18 @@ diff --git a/e.rs b/e.rs
       let result: FileObservationResponse = serde_json::from_str(json).unwrap();
       assert_eq!(result.observations, vec!["updated function", "fixed bug"]);
    }
+
+   #[test]
+   fn test_request_omits_unsupported_temperature() {
+      let config = CommitConfig::default();
+      let request =
+         build_api_request("o3-mini", config.temperature_for("o3-mini"), vec![], "", "diff");
+      let json = serde_json::to_value(&request).unwrap();
+      assert!(json.get("temperature").is_none(), "{json}");
+
+      let request =
+         build_api_request("gpt-4.1", config.temperature_for("gpt-4.1"), vec![], "", "diff");
+      let json = serde_json::to_value(&request).unwrap();
+      assert!(json.get("temperature").is_some(), "{json}");
+   }
 }
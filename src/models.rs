@@ -0,0 +1,271 @@
+//! Models offered by the configured endpoint
+//!
+//! `--model-list` prints what the endpoint's `/models` (chat-completions) or
+//! `/v1/models` (Anthropic messages) lists, with the local aliases of each,
+//! and flags configured models it doesn't offer. With `validate_models` the
+//! same check runs quietly before any diff is sent, so a typo like
+//! `claude-sonet-4.5` fails with a clear error instead of a 404 mid-run.
+
+use crate::{
+   api,
+   config::{CommitConfig, ResolvedApiMode},
+   error::{CommitGenError, Result},
+};
+
+/// Models listing URL for the configured API mode
+pub fn models_url(config: &CommitConfig) -> String {
+   let trimmed = config.api_base_url.trim_end_matches('/');
+   match config.resolved_api_mode(&config.model) {
+      ResolvedApiMode::ChatCompletions => format!("{trimmed}/models"),
+      ResolvedApiMode::AnthropicMessages if trimmed.ends_with("/v1") => {
+         format!("{trimmed}/models?limit=1000")
+      },
+      ResolvedApiMode::AnthropicMessages => format!("{trimmed}/v1/models?limit=1000"),
+   }
+}
+
+/// Model IDs in a models listing
+///
+/// Accepts the chat-completions shape (`{"object": "list", "data": [{"id":
+/// ...}]}`), the Anthropic shape (`{"data": [{"type": "model", "id": ...}],
+/// "has_more": ...}`), and the `{"models": [{"name": ...}]}` some local
+/// servers return.
+pub fn parse_model_ids(body: &str) -> Result<Vec<String>> {
+   let value: serde_json::Value = serde_json::from_str(body)
+      .map_err(|e| CommitGenError::Other(format!("Failed to parse models listing: {e}")))?;
+   let entries = value
+      .get("data")
+      .or_else(|| value.get("models"))
+      .unwrap_or(&value)
+      .as_array()
+      .ok_or_else(|| {
+         CommitGenError::Other("Models listing has no `data` or `models` array".to_string())
+      })?;
+
+   Ok(entries
+      .iter()
+      .filter_map(|entry| {
+         entry
+            .as_str()
+            .or_else(|| entry.get("id").and_then(|id| id.as_str()))
+            .or_else(|| entry.get("name").and_then(|name| name.as_str()))
+      })
+      .map(str::to_string)
+      .collect())
+}
+
+/// Fetch the model IDs the configured endpoint offers
+pub fn fetch_models(config: &CommitConfig) -> Result<Vec<String>> {
+   let client = api::build_client(config)?;
+   let url = models_url(config);
+   let mut request = client.get(&url);
+   match config.resolved_api_mode(&config.model) {
+      ResolvedApiMode::ChatCompletions => {
+         if let Some(api_key) = &config.api_key {
+            request = request.header("Authorization", format!("Bearer {api_key}"));
+         }
+      },
+      ResolvedApiMode::AnthropicMessages => {
+         request = request.header("anthropic-version", "2023-06-01");
+         if let Some(api_key) = &config.api_key {
+            request = request.header("x-api-key", api_key);
+         }
+      },
+   }
+
+   let response = request.send()?;
+   let status = response.status();
+   let content_type = api::response_content_type(response.headers());
+   let body = response.text()?;
+   if !status.is_success() {
+      return Err(CommitGenError::ApiError { status: status.as_u16(), body });
+   }
+   if let Some(err) = api::non_json_error(status, &content_type, &body) {
+      return Err(err);
+   }
+   parse_model_ids(&body)
+}
+
+/// Models the config would call, as sent to the endpoint, with the key each
+/// comes from (`model`, `classify_model`, `summary_model_cascade`)
+pub fn configured_models(config: &CommitConfig) -> Vec<(&'static str, String)> {
+   let mut models = vec![("model", config.model.clone())];
+   if let Some(classify) = &config.classify_model {
+      models.push(("classify_model", classify.clone()));
+   }
+   for model in config.summary_models() {
+      if !models.iter().any(|(_, seen)| *seen == model) {
+         models.push(("summary_model_cascade", model));
+      }
+   }
+   models
+}
+
+/// Whether `available` offers `model`, ignoring a `provider/` prefix on
+/// either side
+pub fn is_available(model: &str, available: &[String]) -> bool {
+   let bare = |name: &str| name.rsplit('/').next().unwrap_or(name).to_string();
+   available
+      .iter()
+      .any(|id| id == model || bare(id) == bare(model))
+}
+
+/// Configured models `available` doesn't offer
+pub fn missing_models(config: &CommitConfig, available: &[String]) -> Vec<(&'static str, String)> {
+   configured_models(config)
+      .into_iter()
+      .filter(|(_, model)| !is_available(model, available))
+      .collect()
+}
+
+/// Closest entry of `available` to `model`, if near enough to be a typo
+pub fn closest_model<'a>(model: &str, available: &'a [String]) -> Option<&'a str> {
+   available
+      .iter()
+      .map(|id| (edit_distance(model, id), id))
+      .filter(|(distance, _)| *distance <= 3.max(model.len() / 5))
+      .min_by_key(|(distance, _)| *distance)
+      .map(|(_, id)| id.as_str())
+}
+
+/// Levenshtein distance over chars
+fn edit_distance(a: &str, b: &str) -> usize {
+   let b: Vec<char> = b.chars().collect();
+   let mut row: Vec<usize> = (0..=b.len()).collect();
+   for (i, ca) in a.chars().enumerate() {
+      let mut diagonal = row[0];
+      row[0] = i + 1;
+      for (j, cb) in b.iter().enumerate() {
+         let substitution = diagonal + usize::from(ca != *cb);
+         diagonal = row[j + 1];
+         row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+      }
+   }
+   row[b.len()]
+}
+
+/// [`CommitGenError::ModelNotFound`] for the first configured model missing
+/// from `available`
+pub fn check_models(config: &CommitConfig, available: &[String]) -> Result<()> {
+   let Some((field, model)) = missing_models(config, available).into_iter().next() else {
+      return Ok(());
+   };
+   let suggestion = closest_model(&model, available)
+      .map(|id| format!(" (did you mean {id}?)"))
+      .unwrap_or_default();
+   Err(CommitGenError::ModelNotFound {
+      model,
+      field: field.to_string(),
+      endpoint: models_url(config),
+      suggestion,
+   })
+}
+
+/// `validate_models` startup check: fetch the listing and fail on the first
+/// configured model it doesn't offer
+pub fn validate_models(config: &CommitConfig) -> Result<()> {
+   let available = fetch_models(config)?;
+   check_models(config, &available)
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   const OPENAI_LISTING: &str = r#"{
+      "object": "list",
+      "data": [
+         {"id": "claude-sonnet-4.5", "object": "model", "created": 1, "owned_by": "anthropic"},
+         {"id": "openai/gpt-5", "object": "model", "created": 1, "owned_by": "openai"}
+      ]
+   }"#;
+
+   const ANTHROPIC_LISTING: &str = r#"{
+      "data": [
+         {"type": "model", "id": "claude-opus-4-5", "display_name": "Claude Opus 4.5",
+          "created_at": "2025-11-24T00:00:00Z"},
+         {"type": "model", "id": "claude-haiku-4-5", "display_name": "Claude Haiku 4.5",
+          "created_at": "2025-10-15T00:00:00Z"}
+      ],
+      "has_more": false,
+      "first_id": "claude-opus-4-5",
+      "last_id": "claude-haiku-4-5"
+   }"#;
+
+   fn ids(list: &[&str]) -> Vec<String> {
+      list.iter().map(|id| id.to_string()).collect()
+   }
+
+   #[test]
+   fn test_parse_openai_listing() {
+      assert_eq!(
+         parse_model_ids(OPENAI_LISTING).unwrap(),
+         ids(&["claude-sonnet-4.5", "openai/gpt-5"])
+      );
+   }
+
+   #[test]
+   fn test_parse_anthropic_listing() {
+      assert_eq!(
+         parse_model_ids(ANTHROPIC_LISTING).unwrap(),
+         ids(&["claude-opus-4-5", "claude-haiku-4-5"])
+      );
+   }
+
+   #[test]
+   fn test_parse_other_listings() {
+      let models = r#"{"models": [{"name": "qwen-3-coder-480b"}, {"name": "glm-4.6"}]}"#;
+      assert_eq!(parse_model_ids(models).unwrap(), ids(&["qwen-3-coder-480b", "glm-4.6"]));
+      assert_eq!(parse_model_ids(r#"["gpt-5"]"#).unwrap(), ids(&["gpt-5"]));
+
+      assert!(parse_model_ids(r#"{"error": "unauthorized"}"#).is_err());
+      assert!(parse_model_ids("<html>login</html>").is_err());
+   }
+
+   #[test]
+   fn test_models_url_per_api_mode() {
+      let config =
+         CommitConfig { api_base_url: "http://localhost:4000/".to_string(), ..Default::default() };
+      assert_eq!(models_url(&config), "http://localhost:4000/models");
+
+      let config = CommitConfig { api_base_url: "https://api.anthropic.com".to_string(), ..config };
+      assert_eq!(models_url(&config), "https://api.anthropic.com/v1/models?limit=1000");
+      let config =
+         CommitConfig { api_base_url: "https://api.anthropic.com/v1".to_string(), ..config };
+      assert_eq!(models_url(&config), "https://api.anthropic.com/v1/models?limit=1000");
+   }
+
+   #[test]
+   fn test_missing_models_cover_every_configured_model() {
+      let available = parse_model_ids(OPENAI_LISTING).unwrap();
+      let config = CommitConfig {
+         model: "claude-sonnet-4.5".to_string(),
+         classify_model: Some("gpt-5".to_string()),
+         summary_model_cascade: vec!["haiku".to_string(), "sonnet".to_string()],
+         ..Default::default()
+      };
+
+      // `gpt-5` matches `openai/gpt-5`; the `haiku` alias isn't listed
+      assert_eq!(missing_models(&config, &available), vec![(
+         "summary_model_cascade",
+         "claude-haiku-4-5".to_string()
+      )]);
+   }
+
+   #[test]
+   fn test_check_models_suggests_typo_fix() {
+      let available = parse_model_ids(OPENAI_LISTING).unwrap();
+      let config = CommitConfig { model: "claude-sonet-4.5".to_string(), ..Default::default() };
+
+      let err = check_models(&config, &available).unwrap_err();
+      assert_eq!(
+         err.to_string(),
+         "Model claude-sonet-4.5 (model) is not offered by http://localhost:4000/models (did you \
+          mean claude-sonnet-4.5?); run `lgit --model-list` to see the available models"
+      );
+
+      let config = CommitConfig { model: "claude-sonnet-4.5".to_string(), ..config };
+      assert!(check_models(&config, &available).is_ok());
+      assert_eq!(closest_model("llama-70b", &available), None);
+   }
+}
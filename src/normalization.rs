@@ -1,7 +1,13 @@
 /// Normalization utilities for commit messages
+use std::collections::HashSet;
+
 use unicode_normalization::UnicodeNormalization;
 
-use crate::{config::CommitConfig, types::ConventionalCommit, validation::is_past_tense_verb};
+use crate::{
+   config::{BodyStyle, CommitConfig, FooterFormat, NormalizationConfig, Tense},
+   types::ConventionalCommit,
+   validation::is_verb_in_tense,
+};
 
 /// Normalize Unicode characters to ASCII (remove AI-style formatting)
 /// Normalize Unicode characters to ASCII (remove AI-style formatting)
@@ -112,6 +118,146 @@ pub fn normalize_unicode(text: &str) -> String {
       ) // ideographic space
       // Zero-width characters (remove)
       .replace(['\u{200B}', '\u{200C}', '\u{200D}', '\u{FEFF}'], "") // zero-width no-break space (BOM)
+      // Line endings: CRLF and stray CR become LF
+      .replace("\r\n", "\n")
+      .replace('\r', "\n")
+}
+
+/// [`normalize_unicode`] leaving the categories `settings` turns off as
+/// written. The text between kept characters is normalized as usual, so NFKD
+/// never decomposes a kept character.
+pub fn normalize_unicode_with(text: &str, settings: NormalizationConfig) -> String {
+   let mut out = String::with_capacity(text.len());
+   let mut run_start = 0;
+   for (idx, c) in text.char_indices() {
+      if is_kept_char(c, settings) {
+         out.push_str(&normalize_unicode(&text[run_start..idx]));
+         out.push(c);
+         run_start = idx + c.len_utf8();
+      }
+   }
+   out.push_str(&normalize_unicode(&text[run_start..]));
+   out
+}
+
+/// Pieces of `text` in order, flagged `true` for inline-code spans (fences
+/// included). As in Markdown, a span opens with a run of backticks and closes
+/// at the next run of the same length; a run that never closes is prose.
+fn split_code_spans(text: &str) -> Vec<(bool, &str)> {
+   let bytes = text.as_bytes();
+   let mut pieces = Vec::new();
+   let mut prose_start = 0;
+   let mut i = 0;
+   while i < bytes.len() {
+      if bytes[i] != b'`' {
+         i += 1;
+         continue;
+      }
+      let fence = backtick_run(&bytes[i..]);
+      let mut j = i + fence;
+      let close = loop {
+         match bytes[j..].iter().position(|&b| b == b'`') {
+            Some(offset) => {
+               let run = backtick_run(&bytes[j + offset..]);
+               if run == fence {
+                  break Some(j + offset + run);
+               }
+               j += offset + run;
+            },
+            None => break None,
+         }
+      };
+      match close {
+         Some(end) => {
+            if prose_start < i {
+               pieces.push((false, &text[prose_start..i]));
+            }
+            pieces.push((true, &text[i..end]));
+            prose_start = end;
+            i = end;
+         },
+         None => i += fence,
+      }
+   }
+   if prose_start < text.len() {
+      pieces.push((false, &text[prose_start..]));
+   }
+   pieces
+}
+
+/// Length of the run of backticks `bytes` starts with
+fn backtick_run(bytes: &[u8]) -> usize {
+   bytes.iter().take_while(|&&b| b == b'`').count()
+}
+
+/// [`normalize_unicode_with`] on the prose of `text`; inline-code spans are
+/// kept byte-for-byte so identifiers and signatures survive as written
+fn normalize_prose(text: &str, settings: NormalizationConfig) -> String {
+   split_code_spans(text)
+      .into_iter()
+      .map(|(code, piece)| {
+         if code {
+            piece.to_string()
+         } else {
+            normalize_unicode_with(piece, settings)
+         }
+      })
+      .collect()
+}
+
+/// Whitespace-separated words of `text`, where an inline-code span stays
+/// whole, spacing included, inside the word it is part of. Line breaks in a
+/// span become spaces, as Markdown renders them.
+fn prose_words(text: &str) -> Vec<String> {
+   let mut words = Vec::new();
+   let mut current = String::new();
+   for (code, piece) in split_code_spans(text) {
+      if code {
+         current.push_str(&piece.replace(['\r', '\n'], " "));
+         continue;
+      }
+      for c in piece.chars() {
+         if !c.is_whitespace() {
+            current.push(c);
+         } else if !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+         }
+      }
+   }
+   if !current.is_empty() {
+      words.push(current);
+   }
+   words
+}
+
+/// Whether `c` belongs to a category `settings` leaves unnormalized
+fn is_kept_char(c: char, settings: NormalizationConfig) -> bool {
+   let quote = matches!(
+      c,
+      '\u{2018}'
+         | '\u{2019}'
+         | '\u{201A}'
+         | '\u{201C}'
+         | '\u{201D}'
+         | '\u{201E}'
+         | '\u{00AB}'
+         | '\u{00BB}'
+         | '\u{2039}'
+         | '\u{203A}'
+   );
+   let arrow = matches!(c, '→' | '←' | '↔' | '⇒' | '⇐' | '⇔' | '↑' | '↓');
+   let math = matches!(c, '≠' | '≤' | '≥' | '≈' | '≡' | '×' | '÷' | '¹' | '²' | '³')
+      || ('\u{00BC}'..='\u{00BE}').contains(&c) // ¼ ½ ¾
+      || ('\u{2153}'..='\u{215E}').contains(&c) // ⅓ .. ⅞
+      || ('\u{2070}'..='\u{2089}').contains(&c); // superscripts and subscripts
+   let greek = ('\u{0370}'..='\u{03FF}').contains(&c);
+   let bullet = matches!(c, '•' | '◦' | '▪' | '▫' | '◆' | '◇');
+
+   (quote && !settings.smart_quotes)
+      || (arrow && !settings.arrows)
+      || (math && !settings.math)
+      || (greek && !settings.greek)
+      || (bullet && !settings.bullets)
 }
 
 /// Estimate token count for text (rough approximation: 1 token ≈ 4 chars)
@@ -206,9 +352,56 @@ pub fn cap_details(details: &mut Vec<String>, max_tokens: usize) {
    *details = kept;
 }
 
-/// Convert present-tense verbs to past-tense and handle type-specific
-/// replacements
-pub fn normalize_summary_verb(summary: &mut String, commit_type: &str) {
+/// Base and past forms of verbs commonly used to open a summary; walked in
+/// either direction depending on the configured tense
+const VERB_FORMS: &[(&str, &str)] = &[
+   ("add", "added"),
+   ("fix", "fixed"),
+   ("update", "updated"),
+   ("refactor", "refactored"),
+   ("remove", "removed"),
+   ("replace", "replaced"),
+   ("improve", "improved"),
+   ("implement", "implemented"),
+   ("migrate", "migrated"),
+   ("rename", "renamed"),
+   ("move", "moved"),
+   ("merge", "merged"),
+   ("split", "split"),
+   ("extract", "extracted"),
+   ("restructure", "restructured"),
+   ("reorganize", "reorganized"),
+   ("consolidate", "consolidated"),
+   ("simplify", "simplified"),
+   ("optimize", "optimized"),
+   ("document", "documented"),
+   ("test", "tested"),
+   ("change", "changed"),
+   ("introduce", "introduced"),
+   ("deprecate", "deprecated"),
+   ("delete", "deleted"),
+   ("correct", "corrected"),
+   ("enhance", "enhanced"),
+   ("revert", "reverted"),
+];
+
+/// Third-person singular of a base-form verb ("fix" -> "fixes")
+fn third_person(base: &str) -> String {
+   if let Some(stem) = base.strip_suffix('y') {
+      format!("{stem}ies")
+   } else if ["s", "x", "z", "ch", "sh"]
+      .iter()
+      .any(|suffix| base.ends_with(suffix))
+   {
+      format!("{base}es")
+   } else {
+      format!("{base}s")
+   }
+}
+
+/// Convert the leading verb of a summary to the configured tense and handle
+/// type-specific replacements
+pub fn normalize_summary_verb(summary: &mut String, commit_type: &str, tense: Tense) {
    if summary.trim().is_empty() {
       return;
    }
@@ -221,62 +414,340 @@ pub fn normalize_summary_verb(summary: &mut String, commit_type: &str) {
    let rest = parts_iter.collect::<Vec<_>>().join(" ");
    let first_word_lower = first_word.to_lowercase();
 
-   // Check if already past tense
-   if is_past_tense_verb(&first_word_lower) {
-      // Special case: refactor type shouldn't use "refactored"
-      if commit_type == "refactor" && first_word_lower == "refactored" {
-         *summary = if rest.is_empty() {
-            "restructured".to_string()
+   // "refactor: refactored ..." just repeats the type
+   let (refactor_verb, restructure_verb) = match tense {
+      Tense::Past => ("refactored", "restructured"),
+      Tense::Imperative => ("refactor", "restructure"),
+   };
+
+   let converted = if is_verb_in_tense(&first_word_lower, tense) {
+      // Special case: refactor type shouldn't use "refactored"/"refactor"
+      if commit_type == "refactor" && first_word_lower == refactor_verb {
+         Some(restructure_verb)
+      } else {
+         return;
+      }
+   } else {
+      VERB_FORMS
+         .iter()
+         .find(|(base, past)| {
+            first_word_lower == third_person(base)
+               || match tense {
+                  Tense::Past => first_word_lower == *base,
+                  Tense::Imperative => first_word_lower == *past,
+               }
+         })
+         .map(|&(base, past)| {
+            let target = match tense {
+               Tense::Past => past,
+               Tense::Imperative => base,
+            };
+            if commit_type == "refactor" && target == refactor_verb {
+               restructure_verb
+            } else {
+               target
+            }
+         })
+   };
+
+   if let Some(verb) = converted {
+      *summary = if rest.is_empty() {
+         verb.to_string()
+      } else {
+         format!("{verb} {rest}")
+      };
+   }
+}
+
+/// Share of words a body item must have in common with the summary to count
+/// as restating it
+const SUMMARY_OVERLAP_THRESHOLD: f64 = 0.8;
+
+/// Words ignored when comparing a body item with the summary
+const FILLER_WORDS: &[&str] =
+   &["a", "an", "and", "as", "by", "for", "in", "of", "on", "the", "to", "with"];
+
+/// Lowercase word stems: plural and tense endings dropped, capped at five
+/// characters so `retries`/`retry` and `handled`/`handling` compare equal
+fn word_stems(text: &str) -> HashSet<String> {
+   text
+      .split(|c: char| !c.is_alphanumeric())
+      .map(str::to_lowercase)
+      .filter(|word| !word.is_empty() && !FILLER_WORDS.contains(&word.as_str()))
+      .map(|word| {
+         let stem = if let Some(base) = word.strip_suffix("ies") {
+            format!("{base}y")
          } else {
-            format!("restructured {rest}")
+            ["ing", "ed", "es", "s"]
+               .iter()
+               .find_map(|suffix| word.strip_suffix(suffix).filter(|base| base.len() >= 3))
+               .unwrap_or(&word)
+               .to_string()
          };
-      }
+         stem.chars().take(5).collect()
+      })
+      .collect()
+}
+
+/// Drop body items whose words overlap the summary's by at least
+/// [`SUMMARY_OVERLAP_THRESHOLD`] (Dice coefficient over word stems)
+pub fn drop_summary_restatements(body: &mut Vec<String>, summary: &str) {
+   let summary = word_stems(summary);
+   if summary.is_empty() {
       return;
    }
+   body.retain(|item| {
+      let item = word_stems(item);
+      let shared = item.intersection(&summary).count();
+      let overlap = 2.0 * shared as f64 / (item.len() + summary.len()) as f64;
+      overlap < SUMMARY_OVERLAP_THRESHOLD
+   });
+}
 
-   // Convert present tense to past tense
-   let converted = match first_word_lower.as_str() {
-      "add" | "adds" => Some("added"),
-      "fix" | "fixes" => Some("fixed"),
-      "update" | "updates" => Some("updated"),
-      "refactor" | "refactors" => Some(if commit_type == "refactor" {
-         "restructured"
-      } else {
-         "refactored"
-      }),
-      "remove" | "removes" => Some("removed"),
-      "replace" | "replaces" => Some("replaced"),
-      "improve" | "improves" => Some("improved"),
-      "implement" | "implements" => Some("implemented"),
-      "migrate" | "migrates" => Some("migrated"),
-      "rename" | "renames" => Some("renamed"),
-      "move" | "moves" => Some("moved"),
-      "merge" | "merges" => Some("merged"),
-      "split" | "splits" => Some("split"),
-      "extract" | "extracts" => Some("extracted"),
-      "restructure" | "restructures" => Some("restructured"),
-      "reorganize" | "reorganizes" => Some("reorganized"),
-      "consolidate" | "consolidates" => Some("consolidated"),
-      "simplify" | "simplifies" => Some("simplified"),
-      "optimize" | "optimizes" => Some("optimized"),
-      "document" | "documents" => Some("documented"),
-      "test" | "tests" => Some("tested"),
-      "change" | "changes" => Some("changed"),
-      "introduce" | "introduces" => Some("introduced"),
-      "deprecate" | "deprecates" => Some("deprecated"),
-      "delete" | "deletes" => Some("deleted"),
-      "correct" | "corrects" => Some("corrected"),
-      "enhance" | "enhances" => Some("enhanced"),
-      "revert" | "reverts" => Some("reverted"),
-      _ => None,
+/// Words dropped along with a scope word ("parser module")
+const SCOPE_FILLER_WORDS: &[&str] = &["module", "code"];
+
+/// Words after which a scope word is the head of a phrase ("added parser for
+/// TOML"), so dropping it would leave "added for TOML"
+const SCOPE_FOLLOWING_CONNECTORS: &[&str] = &[
+   "and", "as", "at", "by", "for", "from", "in", "into", "of", "on", "so", "that", "to", "when",
+   "with",
+];
+
+/// Fewest words a summary keeps after dropping a repeated scope word
+const MIN_WORDS_AFTER_SCOPE_REMOVAL: usize = 3;
+
+/// A summary word for comparison: lowercase, without surrounding punctuation
+fn bare_word(word: &str) -> String {
+   word
+      .trim_matches(|c: char| !c.is_alphanumeric())
+      .to_lowercase()
+}
+
+/// Word sequences naming `scope` in a summary: each path segment as one word
+/// (`auth-service`), and a hyphenated segment also as separate words (`auth
+/// service`). Longest first, so `rate limit` wins over a lone `rate`.
+fn scope_phrases(scope: &str) -> Vec<Vec<String>> {
+   let mut phrases: Vec<Vec<String>> = Vec::new();
+   for segment in scope
+      .split(['/', ','])
+      .map(str::trim)
+      .filter(|s| !s.is_empty())
+   {
+      let segment = segment.to_lowercase();
+      let parts: Vec<String> = segment
+         .split(['-', '_'])
+         .filter(|part| !part.is_empty())
+         .map(str::to_string)
+         .collect();
+      if parts.len() > 1 {
+         phrases.push(parts);
+      }
+      phrases.push(vec![segment]);
+   }
+   phrases.sort_by_key(|phrase| std::cmp::Reverse(phrase.len()));
+   phrases.dedup();
+   phrases
+}
+
+/// Position and length of the first occurrence of a `scope` phrase among
+/// `words` at or after `from`, matching whole words only
+fn find_scope_phrase(words: &[&str], scope: &str, from: usize) -> Option<(usize, usize)> {
+   let bare: Vec<String> = words.iter().map(|word| bare_word(word)).collect();
+   (from..bare.len()).find_map(|start| {
+      scope_phrases(scope)
+         .into_iter()
+         .find(|phrase| bare[start..].starts_with(phrase))
+         .map(|phrase| (start, phrase.len()))
+   })
+}
+
+/// Whether `summary` names `scope` anywhere, as whole words (scope `api`
+/// doesn't match `rapid`)
+pub fn summary_repeats_scope(summary: &str, scope: &str) -> bool {
+   let words: Vec<&str> = summary.split_whitespace().collect();
+   find_scope_phrase(&words, scope, 0).is_some()
+}
+
+/// Drop a `scope` word repeated among the first three words of `summary`
+/// (`fix(parser): fixed parser panic` -> `fixed panic`), along with a "module"
+/// or "code" right after it.
+///
+/// The leading verb is never dropped, nor a scope word heading a phrase
+/// ("added parser for TOML"); the summary is kept as-is when fewer than three
+/// words would remain.
+pub fn drop_scope_repetition(summary: &str, scope: &str) -> String {
+   let words: Vec<&str> = summary.split_whitespace().collect();
+   let Some((start, len)) = find_scope_phrase(&words, scope, 1).filter(|&(start, _)| start < 3)
+   else {
+      return summary.to_string();
    };
 
-   if let Some(past) = converted {
-      *summary = if rest.is_empty() {
-         past.to_string()
+   let mut end = start + len;
+   if words
+      .get(end)
+      .is_some_and(|word| SCOPE_FILLER_WORDS.contains(&bare_word(word).as_str()))
+   {
+      end += 1;
+   }
+   let heads_phrase = words
+      .get(end)
+      .is_none_or(|word| SCOPE_FOLLOWING_CONNECTORS.contains(&bare_word(word).as_str()));
+   // Punctuation attached to the dropped words belongs to the phrase
+   let trailing_punctuation = words[end - 1].ends_with([',', ':', ';']);
+   if heads_phrase
+      || trailing_punctuation
+      || words.len() - (end - start) < MIN_WORDS_AFTER_SCOPE_REMOVAL
+   {
+      return summary.to_string();
+   }
+
+   words[..start]
+      .iter()
+      .chain(&words[end..])
+      .copied()
+      .collect::<Vec<_>>()
+      .join(" ")
+}
+
+/// Write a footer in `format`: `BREAKING CHANGE` always gets `: `, and with
+/// [`FooterFormat::Colon`] a `Key #value` footer becomes `Key: #value`.
+///
+/// Footers already using `: ` are left alone in either format.
+pub fn format_footer(footer: &str, format: FooterFormat) -> String {
+   for token in ["BREAKING CHANGE", "BREAKING-CHANGE"] {
+      if let Some(rest) = footer.strip_prefix(token)
+         && (rest.is_empty() || rest.starts_with([':', ' ', '#']))
+         && !rest.starts_with(": ")
+      {
+         let value = rest.trim_start_matches(':').trim_start();
+         return format!("{token}: {value}");
+      }
+   }
+
+   let first_line = footer.lines().next().unwrap_or_default();
+   if format == FooterFormat::Colon
+      && first_line.contains(" #")
+      && let Some((key, value)) = footer.split_once(" #")
+      && !key.is_empty()
+      && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+   {
+      return format!("{key}: #{value}");
+   }
+   footer.to_string()
+}
+
+/// Token of a footer line: `Token: value` or `Token #value`, where only
+/// `BREAKING CHANGE` may contain a space
+fn footer_token(line: &str) -> Option<&str> {
+   let is_separator = |rest: &str| rest.starts_with(": ") || rest.starts_with(" #");
+   if let Some(token) = ["BREAKING CHANGE", "BREAKING-CHANGE"]
+      .into_iter()
+      .find(|token| line.strip_prefix(token).is_some_and(is_separator))
+   {
+      return Some(token);
+   }
+
+   let end = line.find([':', ' '])?;
+   let token = &line[..end];
+   (!token.is_empty()
+      && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+      && is_separator(&line[end..]))
+   .then_some(token)
+}
+
+fn is_breaking_footer(footer: &str) -> bool {
+   matches!(footer_token(footer), Some("BREAKING CHANGE" | "BREAKING-CHANGE"))
+}
+
+/// A footer as it appears in the message. `BREAKING CHANGE` continuation
+/// lines are written as they are (a line that would read as a new footer is
+/// indented by one space); other footers are folded like git trailers, with
+/// every continuation line indented and blank lines dropped.
+fn render_footer(footer: &str) -> String {
+   let breaking = is_breaking_footer(footer);
+   let mut lines = footer.lines();
+   let mut out = lines.next().unwrap_or_default().to_string();
+   for line in lines {
+      if breaking {
+         out.push('\n');
+         if footer_token(line).is_some() {
+            out.push(' ');
+         }
+         out.push_str(line);
+      } else if !line.trim().is_empty() {
+         out.push_str("\n ");
+         out.push_str(line.trim_start());
+      }
+   }
+   out
+}
+
+/// Footers of `lines`, which must start with a footer line; `None` when a
+/// line can't belong to a footer
+fn parse_footer_lines(lines: &[&str]) -> Option<Vec<String>> {
+   let mut footers: Vec<String> = Vec::new();
+   let mut after_blank = false;
+   for &line in lines {
+      if footer_token(line).is_some() {
+         if let Some(last) = footers.last_mut() {
+            last.truncate(last.trim_end().len());
+         }
+         footers.push(line.trim_end().to_string());
+         after_blank = false;
+         continue;
+      }
+
+      let current = footers.last_mut()?;
+      if is_breaking_footer(current) {
+         // Undo the indent `render_footer` gives lines that look like footers
+         let line = line
+            .strip_prefix(' ')
+            .filter(|rest| footer_token(rest).is_some())
+            .unwrap_or(line);
+         current.push('\n');
+         current.push_str(line.trim_end());
+      } else if line.trim().is_empty() {
+         after_blank = true;
+      } else if line.starts_with([' ', '\t']) && !after_blank {
+         current.push('\n');
+         current.push_str(line.trim());
       } else {
-         format!("{past} {rest}")
-      };
+         return None;
+      }
+   }
+   if let Some(last) = footers.last_mut() {
+      last.truncate(last.trim_end().len());
+   }
+   Some(footers)
+}
+
+/// Split a commit message into the text before its footers and the footers.
+///
+/// Footers are the trailing lines from a paragraph that opens with `Token: `
+/// or `Token #`. Each runs until the next footer line: `BREAKING CHANGE` takes
+/// any lines, blank ones included, while other footers continue only on
+/// folded lines starting with whitespace, as git trailers do. Continuation
+/// lines are kept in the footer string, separated by newlines.
+pub fn split_footers(message: &str) -> (String, Vec<String>) {
+   let lines: Vec<&str> = message.trim_end().lines().collect();
+   for start in 2..lines.len() {
+      if lines[start - 1].trim().is_empty()
+         && footer_token(lines[start]).is_some()
+         && let Some(footers) = parse_footer_lines(&lines[start..])
+      {
+         return (lines[..start].join("\n").trim_end().to_string(), footers);
+      }
+   }
+   (message.trim_end().to_string(), Vec::new())
+}
+
+/// `summary` without trailing periods, when `enforce_no_trailing_period` is on
+fn strip_trailing_period<'a>(summary: &'a str, config: &CommitConfig) -> &'a str {
+   if config.enforce_no_trailing_period {
+      summary.trim_end_matches('.')
+   } else {
+      summary
    }
 }
 
@@ -286,20 +757,37 @@ pub fn post_process_commit_message(msg: &mut ConventionalCommit, config: &Commit
    // constructors No need to re-normalize them here
 
    // Extract summary string for mutations, will reconstruct at end
-   let mut summary_str = normalize_unicode(msg.summary.as_str());
+   let normalize = |text: &str| normalize_unicode_with(text, config.normalization);
+   let mut summary_str = normalize(msg.summary.as_str());
 
-   // Normalize body and footers
-   msg.body = msg.body.iter().map(|s| normalize_unicode(s)).collect();
-   msg.footers = msg.footers.iter().map(|s| normalize_unicode(s)).collect();
+   // Normalize body and footers; a multi-line footer keeps its lines, and
+   // inline code in the body stays as written
+   msg.body = msg
+      .body
+      .iter()
+      .map(|s| normalize_prose(s, config.normalization))
+      .collect();
+   msg.footers = msg
+      .footers
+      .iter()
+      .map(|s| {
+         let footer = normalize(s)
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n");
+         format_footer(footer.trim_end(), config.footer_format)
+      })
+      .collect();
 
-   // Normalize summary formatting: single line, trimmed, enforce trailing period
+   // Normalize summary formatting: single line, trimmed, no trailing
+   // punctuation (a period stays when `enforce_no_trailing_period` is off)
    summary_str = summary_str
       .replace(['\r', '\n'], " ")
       .split_whitespace()
       .collect::<Vec<_>>()
-      .join(" ")
-      .trim()
-      .trim_end_matches('.')
+      .join(" ");
+   summary_str = strip_trailing_period(summary_str.trim(), config)
       .trim_end_matches(';')
       .trim_end_matches(':')
       .to_string();
@@ -314,7 +802,8 @@ pub fn post_process_commit_message(msg: &mut ConventionalCommit, config: &Commit
    };
 
    // Ensure summary starts with lowercase (unless first token is all caps)
-   if !is_first_token_all_caps(&summary_str)
+   if config.enforce_lowercase_start
+      && !is_first_token_all_caps(&summary_str)
       && let Some(first_char) = summary_str.chars().next()
       && first_char.is_uppercase()
    {
@@ -324,11 +813,24 @@ pub fn post_process_commit_message(msg: &mut ConventionalCommit, config: &Commit
 
    // Normalize verb tense (present \u{2192} past, handle type-specific
    // replacements)
-   normalize_summary_verb(&mut summary_str, msg.commit_type.as_str());
+   if let Some(tense) = config.enforced_tense() {
+      let capitalized = summary_str.starts_with(char::is_uppercase);
+      normalize_summary_verb(&mut summary_str, msg.commit_type.as_str(), tense);
+      // The converted verb comes back lowercase; keep a capital the lowercase
+      // rule would have left alone
+      if capitalized
+         && !config.enforce_lowercase_start
+         && let Some(first_char) = summary_str.chars().next()
+      {
+         let rest = &summary_str[first_char.len_utf8()..];
+         summary_str = format!("{}{rest}", first_char.to_uppercase());
+      }
+   }
    summary_str = summary_str.trim().to_string();
 
    // Ensure lowercase after normalization (unless first token is all caps)
-   if !is_first_token_all_caps(&summary_str)
+   if config.enforce_lowercase_start
+      && !is_first_token_all_caps(&summary_str)
       && let Some(first_char) = summary_str.chars().next()
       && first_char.is_uppercase()
    {
@@ -336,32 +838,50 @@ pub fn post_process_commit_message(msg: &mut ConventionalCommit, config: &Commit
       summary_str = format!("{}{}", first_char.to_lowercase(), rest);
    }
 
+   // The scope is already in the prefix; don't spend summary characters on it
+   if let Some(scope) = &msg.scope {
+      summary_str = drop_scope_repetition(&summary_str, scope.as_str());
+   }
+
    // No truncation - validation handles length checks
    // Remove any trailing period (conventional commits don't use periods)
-   summary_str = summary_str.trim_end_matches('.').to_string();
+   summary_str = strip_trailing_period(&summary_str, config).to_string();
 
    // Reconstruct CommitSummary (bypassing warnings since post-processing
    // normalizes)
    msg.summary = crate::types::CommitSummary::new_unchecked(summary_str, 128)
       .expect("post-processed summary should be valid");
 
-   // Clean and enforce punctuation for body items
+   if config.body_style == BodyStyle::Paragraphs {
+      // Paragraphs keep their own punctuation; only whitespace and width
+      // change, and never inside inline code
+      for item in &mut msg.body {
+         let words = prose_words(item);
+         *item = if config.body_wrap_width == 0 {
+            words.join(" ")
+         } else {
+            crate::style::wrap_words(words.iter().map(String::as_str), config.body_wrap_width)
+               .join("\n")
+         };
+      }
+      msg.body.retain(|item| !item.is_empty());
+      if config.dedupe_summary_body {
+         drop_summary_restatements(&mut msg.body, msg.summary.as_str());
+      }
+      cap_details(&mut msg.body, config.max_detail_tokens);
+      return;
+   }
+
+   // Clean and enforce punctuation for body items; inline code is opaque, so
+   // an item opening with a span isn't capitalized
    for item in &mut msg.body {
-      let mut cleaned = item
-         .replace(['\r', '\n'], " ")
-         .trim()
+      let joined = prose_words(item).join(" ");
+      let mut cleaned = joined
          .trim_start_matches('\u{2022}')
          .trim_start_matches('-')
          .trim_start_matches('*')
          .trim_start_matches('+')
          .trim()
-         .to_string();
-
-      cleaned = cleaned
-         .split_whitespace()
-         .collect::<Vec<_>>()
-         .join(" ")
-         .trim()
          .trim_end_matches('.')
          .trim_end_matches(';')
          .trim_end_matches(',')
@@ -390,12 +910,60 @@ pub fn post_process_commit_message(msg: &mut ConventionalCommit, config: &Commit
    // Remove empty body items
    msg.body.retain(|item| !item.trim().is_empty());
 
+   if config.dedupe_summary_body {
+      drop_summary_restatements(&mut msg.body, msg.summary.as_str());
+   }
+
    // Cap details by token budget
    cap_details(&mut msg.body, config.max_detail_tokens);
 }
 
+/// Turn the subjects of a commit range into body items for a squash message
+///
+/// Drops `type(scope):` prefixes, fixup/squash/merge commits and duplicates.
+/// Capitalization and punctuation are left to [`post_process_commit_message`].
+pub fn commit_subjects_to_body(subjects: &[String]) -> Vec<String> {
+   let mut items: Vec<String> = Vec::new();
+
+   for subject in subjects {
+      let subject = normalize_unicode(subject.trim());
+      if ["fixup!", "squash!", "amend!", "Merge "]
+         .iter()
+         .any(|marker| subject.starts_with(marker))
+      {
+         continue;
+      }
+
+      let text = match subject.split_once(": ") {
+         Some((prefix, rest)) if is_conventional_prefix(prefix) => rest.trim(),
+         _ => subject.as_str(),
+      };
+
+      if !text.is_empty() && !items.iter().any(|item| item.eq_ignore_ascii_case(text)) {
+         items.push(text.to_string());
+      }
+   }
+
+   items
+}
+
+/// Whether `prefix` looks like `type`, `type!`, `type(scope)` or `type(scope)!`
+fn is_conventional_prefix(prefix: &str) -> bool {
+   let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+   let commit_type = match prefix.split_once('(') {
+      Some((commit_type, scope)) => {
+         if !scope.ends_with(')') {
+            return false;
+         }
+         commit_type
+      },
+      None => prefix,
+   };
+   !commit_type.is_empty() && commit_type.chars().all(|c| c.is_ascii_alphabetic())
+}
+
 /// Format `ConventionalCommit` as a single string for display and commit
-pub fn format_commit_message(msg: &ConventionalCommit) -> String {
+pub fn format_commit_message(msg: &ConventionalCommit, body_style: BodyStyle) -> String {
    // Build first line: type(scope): summary
    let scope_part = msg
       .scope
@@ -404,23 +972,24 @@ pub fn format_commit_message(msg: &ConventionalCommit) -> String {
       .unwrap_or_default();
    let first_line = format!("{}{}: {}", msg.commit_type, scope_part, msg.summary);
 
-   // Build body with - bullets
-   let body_formatted = if msg.body.is_empty() {
-      String::new()
-   } else {
-      msg.body
+   // Build body: `- ` bullets, or paragraphs separated by blank lines
+   let body_formatted = match body_style {
+      BodyStyle::Bullets => msg
+         .body
          .iter()
          .map(|item| format!("- {item}"))
          .collect::<Vec<_>>()
-         .join("\n")
+         .join("\n"),
+      BodyStyle::Paragraphs => msg.body.join("\n\n"),
    };
 
-   // Build footers
-   let footers_formatted = if msg.footers.is_empty() {
-      String::new()
-   } else {
-      msg.footers.join("\n")
-   };
+   // Build footers; multi-line ones keep their continuation lines
+   let footers_formatted = msg
+      .footers
+      .iter()
+      .map(|footer| render_footer(footer))
+      .collect::<Vec<_>>()
+      .join("\n");
 
    // Combine parts
    let mut result = first_line;
@@ -440,6 +1009,23 @@ mod tests {
    use super::*;
    use crate::types::{CommitSummary, CommitType, ConventionalCommit, Scope};
 
+   #[test]
+   fn test_commit_subjects_to_body() {
+      let subjects = vec![
+         "feat(api): added retry budget".to_string(),
+         "fixup! feat(api): added retry budget".to_string(),
+         "fix!: handled empty diff".to_string(),
+         "Merge branch 'main' into topic".to_string(),
+         "Update README".to_string(),
+         "fix: handled empty diff".to_string(),
+      ];
+      assert_eq!(commit_subjects_to_body(&subjects), vec![
+         "added retry budget",
+         "handled empty diff",
+         "Update README"
+      ]);
+   }
+
    // normalize_unicode tests
    #[test]
    fn test_normalize_unicode_smart_quotes() {
@@ -523,83 +1109,463 @@ mod tests {
       assert_eq!(normalize_unicode("\u{2717}failed"), "xfailed");
    }
 
+   #[test]
+   fn test_normalize_unicode_with_disabled_categories() {
+      let all = NormalizationConfig::default();
+      let text = "\u{201C}x\u{201D} \u{2192} y \u{2260} \u{00BD}\u{00B2} \u{03BB} \u{2022} \
+                  caf\u{00E9}\u{2014}ok";
+      assert_eq!(normalize_unicode_with(text, all), normalize_unicode(text));
+
+      let none = NormalizationConfig {
+         smart_quotes: false,
+         arrows:       false,
+         math:         false,
+         greek:        false,
+         bullets:      false,
+      };
+      // Kept characters survive NFKD; everything else is still normalized
+      assert_eq!(
+         normalize_unicode_with(text, none),
+         "\u{201C}x\u{201D} \u{2192} y \u{2260} \u{00BD}\u{00B2} \u{03BB} \u{2022} cafe\u{301}--ok"
+      );
+
+      let keep_arrows = NormalizationConfig { arrows: false, ..all };
+      assert_eq!(
+         normalize_unicode_with("a \u{2192} \u{201C}b\u{201D}", keep_arrows),
+         "a \u{2192} \"b\""
+      );
+      let keep_greek = NormalizationConfig { greek: false, ..all };
+      assert_eq!(
+         normalize_unicode_with("\u{03BB} \u{03AC} x\u{2264}y", keep_greek),
+         "\u{03BB} \u{03AC} x<=y"
+      );
+      let keep_math = NormalizationConfig { math: false, ..all };
+      assert_eq!(
+         normalize_unicode_with("x\u{2264}y \u{00BC} H\u{2082}O", keep_math),
+         "x\u{2264}y \u{00BC} H\u{2082}O"
+      );
+      let keep_bullets = NormalizationConfig { bullets: false, ..all };
+      assert_eq!(
+         normalize_unicode_with("\u{2022} \u{2018}a\u{2019}", keep_bullets),
+         "\u{2022} 'a'"
+      );
+   }
+
+   #[test]
+   fn test_post_process_respects_normalization_config() {
+      let config = CommitConfig {
+         normalization: NormalizationConfig { arrows: false, ..Default::default() },
+         ..Default::default()
+      };
+      let mut msg = ConventionalCommit {
+         commit_type: CommitType::new("refactor").unwrap(),
+         scope:       None,
+         summary:     CommitSummary::new_unchecked("mapped input \u{2192} output", 128).unwrap(),
+         body:        vec!["Routed \u{201C}a\u{201D} \u{2192} b.".to_string()],
+         footers:     vec![],
+      };
+      post_process_commit_message(&mut msg, &config);
+      assert_eq!(msg.summary.as_str(), "mapped input \u{2192} output");
+      assert_eq!(msg.body, vec!["Routed \"a\" \u{2192} b."]);
+   }
+
+   #[test]
+   fn test_split_code_spans() {
+      assert_eq!(split_code_spans("Boxed `Fn() -> T` as ``a`b``."), vec![
+         (false, "Boxed "),
+         (true, "`Fn() -> T`"),
+         (false, " as "),
+         (true, "``a`b``"),
+         (false, "."),
+      ]);
+      // An unclosed fence is prose
+      assert_eq!(split_code_spans("Quoted `a and ``b``"), vec![
+         (false, "Quoted `a and "),
+         (true, "``b``"),
+      ]);
+      assert_eq!(split_code_spans(""), Vec::<(bool, &str)>::new());
+   }
+
+   #[test]
+   fn test_post_process_keeps_code_spans_verbatim() {
+      let config = CommitConfig::default();
+      let mut msg = ConventionalCommit {
+         commit_type: CommitType::new("refactor").unwrap(),
+         scope:       None,
+         summary:     CommitSummary::new_unchecked("boxed worker callbacks", 128).unwrap(),
+         body:        vec![
+            "- stored callbacks as `Vec<Box<dyn Fn() -> T>>`  so the   pool \u{2192} queue              handoff \u{2014} is cheap"
+               .to_string(),
+            "`ioWorker` now drains `a  \u{2192}  b` and `caf\u{e9}` before `shutdown()`.".to_string(),
+            "Renamed `IOWorker::\u{3bb}` to `IOWorker::run`;".to_string(),
+         ],
+         footers:     vec![],
+      };
+      post_process_commit_message(&mut msg, &config);
+      assert_eq!(msg.body, vec![
+         "Stored callbacks as `Vec<Box<dyn Fn() -> T>>` so the pool -> queue handoff -- is cheap.",
+         "`ioWorker` now drains `a  \u{2192}  b` and `caf\u{e9}` before `shutdown()`.",
+         "Renamed `IOWorker::\u{3bb}` to `IOWorker::run`.",
+      ]);
+   }
+
+   #[test]
+   fn test_paragraph_wrap_never_splits_code_spans() {
+      let config = CommitConfig {
+         body_style: BodyStyle::Paragraphs,
+         body_wrap_width: 30,
+         ..Default::default()
+      };
+      let mut msg = ConventionalCommit {
+         commit_type: CommitType::new("refactor").unwrap(),
+         scope:       None,
+         summary:     CommitSummary::new_unchecked("boxed worker callbacks", 128).unwrap(),
+         body:        vec![
+            "Stored callbacks as `Vec<Box<dyn Fn() -> T>>`, which   keeps the pool simple."
+               .to_string(),
+         ],
+         footers:     vec![],
+      };
+      post_process_commit_message(&mut msg, &config);
+      assert_eq!(msg.body, vec![
+         "Stored callbacks as\n`Vec<Box<dyn Fn() -> T>>`,\nwhich keeps the pool simple."
+      ]);
+   }
+
    // normalize_summary_verb tests
    #[test]
    fn test_normalize_summary_verb_present_to_past() {
       let mut s = "add new feature".to_string();
-      normalize_summary_verb(&mut s, "feat");
+      normalize_summary_verb(&mut s, "feat", Tense::Past);
       assert_eq!(s, "added new feature");
 
       let mut s = "fix bug".to_string();
-      normalize_summary_verb(&mut s, "fix");
+      normalize_summary_verb(&mut s, "fix", Tense::Past);
       assert_eq!(s, "fixed bug");
 
       let mut s = "update docs".to_string();
-      normalize_summary_verb(&mut s, "docs");
+      normalize_summary_verb(&mut s, "docs", Tense::Past);
       assert_eq!(s, "updated docs");
    }
 
    #[test]
    fn test_normalize_summary_verb_already_past() {
       let mut s = "added feature".to_string();
-      normalize_summary_verb(&mut s, "feat");
+      normalize_summary_verb(&mut s, "feat", Tense::Past);
       assert_eq!(s, "added feature");
 
       let mut s = "fixed bug".to_string();
-      normalize_summary_verb(&mut s, "fix");
+      normalize_summary_verb(&mut s, "fix", Tense::Past);
       assert_eq!(s, "fixed bug");
    }
 
    #[test]
    fn test_normalize_summary_verb_third_person() {
       let mut s = "adds feature".to_string();
-      normalize_summary_verb(&mut s, "feat");
+      normalize_summary_verb(&mut s, "feat", Tense::Past);
       assert_eq!(s, "added feature");
 
       let mut s = "fixes bug".to_string();
-      normalize_summary_verb(&mut s, "fix");
+      normalize_summary_verb(&mut s, "fix", Tense::Past);
       assert_eq!(s, "fixed bug");
    }
 
    #[test]
    fn test_normalize_summary_verb_non_verb_start() {
       let mut s = "123 files changed".to_string();
-      normalize_summary_verb(&mut s, "chore");
+      normalize_summary_verb(&mut s, "chore", Tense::Past);
       assert_eq!(s, "123 files changed");
    }
 
    #[test]
    fn test_normalize_summary_verb_refactor_special_case() {
       let mut s = "refactored code".to_string();
-      normalize_summary_verb(&mut s, "refactor");
+      normalize_summary_verb(&mut s, "refactor", Tense::Past);
       assert_eq!(s, "restructured code");
    }
 
    #[test]
    fn test_normalize_summary_verb_refactor_present() {
       let mut s = "refactor code".to_string();
-      normalize_summary_verb(&mut s, "refactor");
+      normalize_summary_verb(&mut s, "refactor", Tense::Past);
       assert_eq!(s, "restructured code");
 
       let mut s = "refactor logic".to_string();
-      normalize_summary_verb(&mut s, "feat");
+      normalize_summary_verb(&mut s, "feat", Tense::Past);
       assert_eq!(s, "refactored logic");
    }
 
    #[test]
    fn test_normalize_summary_verb_empty() {
       let mut s = String::new();
-      normalize_summary_verb(&mut s, "feat");
+      normalize_summary_verb(&mut s, "feat", Tense::Past);
       assert_eq!(s, "");
    }
 
    #[test]
    fn test_normalize_summary_verb_single_word() {
       let mut s = "add".to_string();
-      normalize_summary_verb(&mut s, "feat");
+      normalize_summary_verb(&mut s, "feat", Tense::Past);
       assert_eq!(s, "added");
    }
 
+   #[test]
+   fn test_normalize_summary_verb_imperative_from_past() {
+      let mut s = "added new feature".to_string();
+      normalize_summary_verb(&mut s, "feat", Tense::Imperative);
+      assert_eq!(s, "add new feature");
+
+      let mut s = "fixed bug".to_string();
+      normalize_summary_verb(&mut s, "docs", Tense::Imperative);
+      assert_eq!(s, "fix bug");
+
+      let mut s = "simplified parser".to_string();
+      normalize_summary_verb(&mut s, "refactor", Tense::Imperative);
+      assert_eq!(s, "simplify parser");
+
+      let mut s = "split module".to_string();
+      normalize_summary_verb(&mut s, "refactor", Tense::Imperative);
+      assert_eq!(s, "split module");
+   }
+
+   #[test]
+   fn test_normalize_summary_verb_imperative_already_imperative() {
+      let mut s = "add feature".to_string();
+      normalize_summary_verb(&mut s, "feat", Tense::Imperative);
+      assert_eq!(s, "add feature");
+
+      let mut s = "correct bug".to_string();
+      normalize_summary_verb(&mut s, "fix", Tense::Imperative);
+      assert_eq!(s, "correct bug");
+   }
+
+   #[test]
+   fn test_normalize_summary_verb_imperative_third_person() {
+      let mut s = "adds feature".to_string();
+      normalize_summary_verb(&mut s, "feat", Tense::Imperative);
+      assert_eq!(s, "add feature");
+
+      let mut s = "fixes bug".to_string();
+      normalize_summary_verb(&mut s, "docs", Tense::Imperative);
+      assert_eq!(s, "fix bug");
+
+      let mut s = "simplifies parser".to_string();
+      normalize_summary_verb(&mut s, "feat", Tense::Imperative);
+      assert_eq!(s, "simplify parser");
+   }
+
+   #[test]
+   fn test_normalize_summary_verb_imperative_non_verb_start() {
+      let mut s = "123 files changed".to_string();
+      normalize_summary_verb(&mut s, "chore", Tense::Imperative);
+      assert_eq!(s, "123 files changed");
+   }
+
+   #[test]
+   fn test_normalize_summary_verb_imperative_refactor_special_case() {
+      let mut s = "refactor code".to_string();
+      normalize_summary_verb(&mut s, "refactor", Tense::Imperative);
+      assert_eq!(s, "restructure code");
+
+      let mut s = "refactored code".to_string();
+      normalize_summary_verb(&mut s, "refactor", Tense::Imperative);
+      assert_eq!(s, "restructure code");
+
+      let mut s = "refactored logic".to_string();
+      normalize_summary_verb(&mut s, "feat", Tense::Imperative);
+      assert_eq!(s, "refactor logic");
+   }
+
+   #[test]
+   fn test_normalize_summary_verb_imperative_empty_and_single_word() {
+      let mut s = String::new();
+      normalize_summary_verb(&mut s, "feat", Tense::Imperative);
+      assert_eq!(s, "");
+
+      let mut s = "added".to_string();
+      normalize_summary_verb(&mut s, "feat", Tense::Imperative);
+      assert_eq!(s, "add");
+   }
+
+   #[test]
+   fn test_verb_forms_round_trip() {
+      for &(base, past) in VERB_FORMS {
+         let mut s = format!("{past} thing");
+         normalize_summary_verb(&mut s, "chore", Tense::Imperative);
+         assert_eq!(s, format!("{base} thing"));
+
+         let mut s = format!("{base} thing");
+         normalize_summary_verb(&mut s, "chore", Tense::Past);
+         assert_eq!(s, format!("{past} thing"));
+      }
+   }
+
+   #[test]
+   fn test_normalize_unicode_line_endings() {
+      assert_eq!(normalize_unicode("a\r\nb\rc\n"), "a\nb\nc\n");
+   }
+
+   fn message_with_footers(footers: &[&str]) -> ConventionalCommit {
+      ConventionalCommit {
+         commit_type: CommitType::new("feat").unwrap(),
+         scope:       Some(Scope::new("config").unwrap()),
+         summary:     CommitSummary::new_unchecked("replaced the config loader", 128).unwrap(),
+         body:        vec!["Moved parsing into one place.".to_string()],
+         footers:     footers.iter().map(ToString::to_string).collect(),
+      }
+   }
+
+   #[test]
+   fn test_multi_line_footers_round_trip() {
+      let msg = message_with_footers(&[
+         "BREAKING CHANGE: The config format changed.\nOld keys are rejected.\n\nRun `lgit \
+          --check-config` to find them.\nNote: not a footer",
+         "Co-authored-by: Jane Doe\n<jane@example.com>",
+         "Refs #12",
+      ]);
+      let formatted = format_commit_message(&msg, BodyStyle::Bullets);
+      assert_eq!(
+         formatted,
+         "feat(config): replaced the config loader\n\n- Moved parsing into one place.\n\nBREAKING \
+          CHANGE: The config format changed.\nOld keys are rejected.\n\nRun `lgit --check-config` \
+          to find them.\n Note: not a footer\nCo-authored-by: Jane Doe\n <jane@example.com>\nRefs \
+          #12"
+      );
+
+      let (rest, footers) = split_footers(&formatted);
+      assert_eq!(
+         rest,
+         "feat(config): replaced the config loader\n\n- Moved parsing into one place."
+      );
+      assert_eq!(footers, msg.footers);
+   }
+
+   #[test]
+   fn test_split_footers_folded_trailers() {
+      let message = "fix: handled empty diff\n\nNote: this paragraph is prose,\nnot a \
+                     footer.\n\nCo-authored-by: Jane Doe\n  <jane@example.com>\n\tvia \
+                     pairing\nSigned-off-by: Sam <sam@example.com>\n";
+      let (rest, footers) = split_footers(message);
+      assert_eq!(rest, "fix: handled empty diff\n\nNote: this paragraph is prose,\nnot a footer.");
+      assert_eq!(footers, vec![
+         "Co-authored-by: Jane Doe\n<jane@example.com>\nvia pairing",
+         "Signed-off-by: Sam <sam@example.com>",
+      ]);
+
+      // Unfolded text after a trailer means the paragraph is body text
+      let (rest, footers) = split_footers("fix: x\n\nSee: the docs\nfor details");
+      assert_eq!(rest, "fix: x\n\nSee: the docs\nfor details");
+      assert!(footers.is_empty());
+   }
+
+   #[test]
+   fn test_post_process_keeps_multi_line_footers() {
+      let mut msg = message_with_footers(&[
+         "BREAKING CHANGE: Dropped \u{201C}v1\u{201D} keys.  \r\n\r\nMigrate first.\r\n",
+         "Refs #7",
+      ]);
+      let config = CommitConfig { footer_format: FooterFormat::Colon, ..Default::default() };
+      post_process_commit_message(&mut msg, &config);
+      assert_eq!(msg.footers, vec![
+         "BREAKING CHANGE: Dropped \"v1\" keys.\n\nMigrate first.",
+         "Refs: #7"
+      ]);
+   }
+
+   #[test]
+   fn test_post_process_strips_carriage_returns() {
+      let config = CommitConfig::default();
+      let mut msg = ConventionalCommit {
+         commit_type: CommitType::new("fix").unwrap(),
+         scope:       None,
+         summary:     CommitSummary::new_unchecked("corrected CRLF\r\nhandling\r", 128).unwrap(),
+         body:        vec![
+            "Stripped carriage returns from diffs.\r\n".to_string(),
+            "Handled mixed\r\nline endings\r".to_string(),
+         ],
+         footers:     vec!["Fixes #12\r".to_string()],
+      };
+      post_process_commit_message(&mut msg, &config);
+
+      assert_eq!(msg.summary.as_str(), "corrected CRLF handling");
+      assert_eq!(msg.body, vec![
+         "Stripped carriage returns from diffs.",
+         "Handled mixed line endings."
+      ]);
+      let formatted = format_commit_message(&msg, BodyStyle::Bullets);
+      assert!(!formatted.contains('\r'), "{formatted:?}");
+   }
+
+   #[test]
+   fn test_post_process_imperative_summary() {
+      let config = CommitConfig { summary_tense: Tense::Imperative, ..Default::default() };
+      let mut msg = ConventionalCommit {
+         commit_type: CommitType::new("feat").unwrap(),
+         scope:       Some(Scope::new("api").unwrap()),
+         summary:     CommitSummary::new_unchecked("Added OAuth support.", 128).unwrap(),
+         body:        vec!["added OAuth client".to_string()],
+         footers:     vec![],
+      };
+      post_process_commit_message(&mut msg, &config);
+      assert_eq!(msg.summary.as_str(), "add OAuth support");
+      // Body keeps its own (past) convention
+      assert_eq!(msg.body, vec!["Added OAuth client."]);
+   }
+
+   #[test]
+   fn test_post_process_summary_rule_toggles() {
+      let summary_after = |config: &CommitConfig| {
+         let mut msg = ConventionalCommit {
+            commit_type: CommitType::new("feat").unwrap(),
+            scope:       None,
+            summary:     CommitSummary::new_unchecked("Added OAuth support.", 128).unwrap(),
+            body:        vec![],
+            footers:     vec![],
+         };
+         post_process_commit_message(&mut msg, config);
+         msg.summary.as_str().to_string()
+      };
+      let imperative = CommitConfig { summary_tense: Tense::Imperative, ..Default::default() };
+
+      assert_eq!(summary_after(&imperative), "add OAuth support");
+      assert_eq!(
+         summary_after(&CommitConfig { enforce_lowercase_start: false, ..imperative.clone() }),
+         "Add OAuth support"
+      );
+      assert_eq!(
+         summary_after(&CommitConfig { enforce_tense: false, ..imperative.clone() }),
+         "added OAuth support"
+      );
+      assert_eq!(
+         summary_after(&CommitConfig { enforce_no_trailing_period: false, ..imperative }),
+         "add OAuth support."
+      );
+   }
+
+   #[test]
+   fn test_post_process_drops_body_item_restating_summary() {
+      let commit = || ConventionalCommit {
+         commit_type: CommitType::new("fix").unwrap(),
+         scope:       Some(Scope::new("api").unwrap()),
+         summary:     CommitSummary::new_unchecked("added backoff to API client retries", 128)
+            .unwrap(),
+         body:        vec![
+            "Added retry backoff for the API client.".to_string(),
+            "Capped backoff delay at 30 seconds.".to_string(),
+         ],
+         footers:     vec![],
+      };
+
+      let mut msg = commit();
+      post_process_commit_message(&mut msg, &CommitConfig::default());
+      assert_eq!(msg.body, vec!["Capped backoff delay at 30 seconds."]);
+
+      let mut msg = commit();
+      let config = CommitConfig { dedupe_summary_body: false, ..Default::default() };
+      post_process_commit_message(&mut msg, &config);
+      assert_eq!(msg.body.len(), 2);
+   }
+
    // cap_details tests (budget-based)
    #[test]
    fn test_cap_details_under_budget() {
@@ -766,7 +1732,7 @@ mod tests {
          body:        vec![],
          footers:     vec![],
       };
-      assert_eq!(format_commit_message(&commit), "feat: added new feature");
+      assert_eq!(format_commit_message(&commit, BodyStyle::Bullets), "feat: added new feature");
    }
 
    #[test]
@@ -778,7 +1744,7 @@ mod tests {
          body:        vec![],
          footers:     vec![],
       };
-      assert_eq!(format_commit_message(&commit), "fix(api): fixed bug");
+      assert_eq!(format_commit_message(&commit, BodyStyle::Bullets), "fix(api): fixed bug");
    }
 
    #[test]
@@ -791,7 +1757,7 @@ mod tests {
          footers:     vec![],
       };
       let expected = "feat: added feature\n\n- First detail.\n- Second detail.";
-      assert_eq!(format_commit_message(&commit), expected);
+      assert_eq!(format_commit_message(&commit, BodyStyle::Bullets), expected);
    }
 
    #[test]
@@ -804,7 +1770,7 @@ mod tests {
          footers:     vec!["Closes: #123".to_string(), "Fixes: #456".to_string()],
       };
       let expected = "fix: fixed bug\n\nCloses: #123\nFixes: #456";
-      assert_eq!(format_commit_message(&commit), expected);
+      assert_eq!(format_commit_message(&commit, BodyStyle::Bullets), expected);
    }
 
    #[test]
@@ -821,7 +1787,168 @@ mod tests {
       };
       let expected = "feat(auth): added oauth support\n\n- Implemented OAuth2 flow.\n- Added \
                       token refresh.\n\nCloses: #789";
-      assert_eq!(format_commit_message(&commit), expected);
+      assert_eq!(format_commit_message(&commit, BodyStyle::Bullets), expected);
+   }
+
+   /// Same analysis body rendered in both body styles
+   fn golden_body_commit(body_style: BodyStyle) -> String {
+      let config = CommitConfig { body_style, body_wrap_width: 60, ..Default::default() };
+      let mut msg = ConventionalCommit {
+         commit_type: CommitType::new("feat").unwrap(),
+         scope:       Some(Scope::new("api").unwrap()),
+         summary:     CommitSummary::new_unchecked("added retry budget", 128).unwrap(),
+         body:        vec![
+            "added a per-request retry budget so   transient 503s no longer fail the whole batch"
+               .to_string(),
+            "Exposed the budget as `max_retries` in config".to_string(),
+         ],
+         footers:     vec!["Closes #42".to_string()],
+      };
+      post_process_commit_message(&mut msg, &config);
+      format_commit_message(&msg, config.body_style)
+   }
+
+   #[test]
+   fn test_golden_body_bullets() {
+      assert_eq!(
+         golden_body_commit(BodyStyle::Bullets),
+         "feat(api): added retry budget\n\n- Added a per-request retry budget so transient 503s \
+          no longer fail the whole batch.\n- Exposed the budget as `max_retries` in \
+          config.\n\nCloses #42"
+      );
+   }
+
+   #[test]
+   fn test_golden_body_paragraphs() {
+      assert_eq!(
+         golden_body_commit(BodyStyle::Paragraphs),
+         "feat(api): added retry budget\n\nadded a per-request retry budget so transient 503s no \
+          longer\nfail the whole batch\n\nExposed the budget as `max_retries` in config\n\nCloses \
+          #42"
+      );
+   }
+
+   #[test]
+   fn test_drop_scope_repetition() {
+      let cases = [
+         ("fixed parser panic on empty input", "parser", "fixed panic on empty input"),
+         (
+            "fixed the parser module panic on empty input",
+            "parser",
+            "fixed the panic on empty input",
+         ),
+         ("Fixed Parser, panic on empty input", "parser", "Fixed Parser, panic on empty input"),
+         // Whole words only
+         ("added rapid retry for flaky calls", "api", "added rapid retry for flaky calls"),
+         ("added api-gateway retry budget", "api", "added api-gateway retry budget"),
+         // Hyphenated scopes, as one word or spelled out
+         ("fixed auth-service token refresh race", "auth-service", "fixed token refresh race"),
+         ("fixed auth service token refresh race", "auth-service", "fixed token refresh race"),
+         ("fixed auth token refresh race", "auth-service", "fixed auth token refresh race"),
+         // Two-segment scopes: either segment
+         ("fixed client retry loop on timeouts", "api/client", "fixed retry loop on timeouts"),
+         (
+            "added api pagination to list endpoints",
+            "api/client",
+            "added pagination to list endpoints",
+         ),
+         // Never the verb, nor past the third word
+         ("cached parsed templates per variant", "cached", "cached parsed templates per variant"),
+         (
+            "fixed panic when parser input is empty",
+            "parser",
+            "fixed panic when parser input is empty",
+         ),
+         // Not when it heads a phrase or too little would remain
+         ("added parser for TOML front matter", "parser", "added parser for TOML front matter"),
+         ("fixed parser panic", "parser", "fixed parser panic"),
+         ("fixed parser code panic", "parser", "fixed parser code panic"),
+      ];
+      for (summary, scope, expected) in cases {
+         assert_eq!(drop_scope_repetition(summary, scope), expected, "{summary} ({scope})");
+      }
+
+      assert!(summary_repeats_scope("fixed panic in the parser", "parser"));
+      assert!(summary_repeats_scope("fixed API client panic", "api/client"));
+      assert!(!summary_repeats_scope("added rapid retries", "api"));
+   }
+
+   #[test]
+   fn test_post_process_drops_repeated_scope() {
+      let mut commit = ConventionalCommit {
+         commit_type: CommitType::new("fix").unwrap(),
+         scope:       Some(Scope::new("parser").unwrap()),
+         summary:     CommitSummary::new_unchecked("Fix parser panic on empty input.", 128)
+            .unwrap(),
+         body:        vec![],
+         footers:     vec![],
+      };
+      post_process_commit_message(&mut commit, &CommitConfig::default());
+      assert_eq!(commit.summary.as_str(), "fixed panic on empty input");
+   }
+
+   #[test]
+   fn test_format_footer_styles() {
+      let spaced = FooterFormat::Spaced;
+      let colon = FooterFormat::Colon;
+
+      assert_eq!(format_footer("Fixes #123", spaced), "Fixes #123");
+      assert_eq!(format_footer("Fixes #123", colon), "Fixes: #123");
+      assert_eq!(format_footer("Refs #7", colon), "Refs: #7");
+      // Already `key: value`, or not a `key #value` footer
+      assert_eq!(
+         format_footer("Reviewed-by: Jo <jo@example.com>", colon),
+         "Reviewed-by: Jo <jo@example.com>"
+      );
+      assert_eq!(format_footer("Fixes: #123", spaced), "Fixes: #123");
+      assert_eq!(format_footer("See issue #4 upstream", colon), "See issue #4 upstream");
+
+      // BREAKING CHANGE always takes a colon
+      for format in [spaced, colon] {
+         assert_eq!(
+            format_footer("BREAKING CHANGE dropped v1 API", format),
+            "BREAKING CHANGE: dropped v1 API"
+         );
+         assert_eq!(
+            format_footer("BREAKING CHANGE:dropped v1 API", format),
+            "BREAKING CHANGE: dropped v1 API"
+         );
+         assert_eq!(
+            format_footer("BREAKING-CHANGE: dropped v1 API", format),
+            "BREAKING-CHANGE: dropped v1 API"
+         );
+         // Only the whole token is rewritten
+         assert_eq!(
+            format_footer("BREAKING CHANGES: dropped v1 API", format),
+            "BREAKING CHANGES: dropped v1 API"
+         );
+         assert_eq!(format_footer("BREAKING CHANGEX", format), "BREAKING CHANGEX");
+      }
+   }
+
+   #[test]
+   fn test_post_process_applies_footer_format() {
+      let commit = |footers: &[&str]| ConventionalCommit {
+         commit_type: CommitType::new("fix").unwrap(),
+         scope:       None,
+         summary:     CommitSummary::new_unchecked("handled empty input", 128).unwrap(),
+         body:        vec![],
+         footers:     footers.iter().map(|f| (*f).to_string()).collect(),
+      };
+      let footers = ["Fixes #12", "Refs #34", "BREAKING CHANGE dropped v1 API"];
+
+      let mut spaced = commit(&footers);
+      post_process_commit_message(&mut spaced, &CommitConfig::default());
+      assert_eq!(spaced.footers, ["Fixes #12", "Refs #34", "BREAKING CHANGE: dropped v1 API"]);
+
+      let mut colon = commit(&footers);
+      let config = CommitConfig { footer_format: FooterFormat::Colon, ..Default::default() };
+      post_process_commit_message(&mut colon, &config);
+      assert_eq!(colon.footers, ["Fixes: #12", "Refs: #34", "BREAKING CHANGE: dropped v1 API"]);
+      assert_eq!(
+         format_commit_message(&colon, BodyStyle::Bullets),
+         "fix: handled empty input\n\nFixes: #12\nRefs: #34\nBREAKING CHANGE: dropped v1 API"
+      );
    }
 
    #[test]
@@ -833,6 +1960,9 @@ mod tests {
          body:        vec![],
          footers:     vec![],
       };
-      assert_eq!(format_commit_message(&commit), "refactor(api/client): restructured code");
+      assert_eq!(
+         format_commit_message(&commit, BodyStyle::Bullets),
+         "refactor(api/client): restructured code"
+      );
    }
 }
@@ -1,4 +1,4 @@
-use std::process::Command;
+use std::{path::Path, process::Command};
 
 use crate::{
    error::{CommitGenError, Result},
@@ -42,8 +42,18 @@ pub fn create_patch_for_files(files: &[String], dir: &str) -> Result<String> {
 
 /// Apply patch to staging area
 pub fn apply_patch_to_index(patch: &str, dir: &str) -> Result<()> {
-   let mut child = Command::new("git")
-      .args(["apply", "--cached"])
+   run_git_apply(patch, dir, None)
+}
+
+/// `git apply --cached` of `patch`; with `check_index`, only `--check` it
+/// against that index file instead of the repository's
+fn run_git_apply(patch: &str, dir: &str, check_index: Option<&Path>) -> Result<()> {
+   let mut command = Command::new("git");
+   command.args(["apply", "--cached"]);
+   if let Some(index) = check_index {
+      command.arg("--check").env("GIT_INDEX_FILE", index);
+   }
+   let mut child = command
       .current_dir(dir)
       .stdin(std::process::Stdio::piped())
       .stdout(std::process::Stdio::piped())
@@ -64,7 +74,12 @@ pub fn apply_patch_to_index(patch: &str, dir: &str) -> Result<()> {
 
    if !output.status.success() {
       let stderr = String::from_utf8_lossy(&output.stderr);
-      return Err(CommitGenError::GitError(format!("git apply --cached failed: {stderr}")));
+      let command = if check_index.is_some() {
+         "git apply --cached --check"
+      } else {
+         "git apply --cached"
+      };
+      return Err(CommitGenError::GitError(format!("{command} failed: {stderr}")));
    }
 
    Ok(())
@@ -447,10 +462,28 @@ pub fn create_patch_for_changes(full_diff: &str, changes: &[FileChange]) -> Resu
    Ok(patch)
 }
 
+/// Whether `selectors` claim at least one hunk of `file_path` in `full_diff`.
+/// Used to check that widening another group's selector to `All` won't steal
+/// hunks from this one.
+pub fn selectors_claim_hunks(full_diff: &str, file_path: &str, selectors: &[HunkSelector]) -> bool {
+   selectors.iter().any(|s| matches!(s, HunkSelector::All))
+      || resolve_selectors_to_headers(full_diff, file_path, selectors)
+         .is_ok_and(|headers| !headers.is_empty())
+}
+
 /// Stage changes for a specific group (hunk-aware).
 /// The `full_diff` argument must be taken before any compose commits run so the
 /// recorded hunk headers remain stable across groups.
-pub fn stage_group_changes(group: &ChangeGroup, dir: &str, full_diff: &str) -> Result<()> {
+///
+/// Partial files are applied one at a time; a file whose selectors can't be
+/// resolved or applied, or an untracked file selected partially, fails with
+/// [`CommitGenError::HunkApplyFailed`], leaving the files before it staged.
+pub fn stage_group_changes(
+   group: &ChangeGroup,
+   group_idx: usize,
+   dir: &str,
+   full_diff: &str,
+) -> Result<()> {
    let mut full_files = Vec::new();
    let mut partial_changes = Vec::new();
 
@@ -461,7 +494,7 @@ pub fn stage_group_changes(group: &ChangeGroup, dir: &str, full_diff: &str) -> R
       if is_all {
          full_files.push(change.path.clone());
       } else {
-         partial_changes.push(change.clone());
+         partial_changes.push(change);
       }
    }
 
@@ -472,10 +505,107 @@ pub fn stage_group_changes(group: &ChangeGroup, dir: &str, full_diff: &str) -> R
       stage_files(&full_files, dir)?;
    }
 
-   if partial_changes.is_empty() {
-      return Ok(());
+   let untracked = if partial_changes.is_empty() {
+      Vec::new()
+   } else {
+      crate::git::get_untracked_files(dir)?
+   };
+   for change in partial_changes {
+      reject_untracked_partial(change, &untracked)
+         .and_then(|()| create_patch_for_changes(full_diff, std::slice::from_ref(change)))
+         .and_then(|patch| apply_patch_to_index(&patch, dir))
+         .map_err(|e| apply_failed(e, change, group_idx))?;
+   }
+
+   Ok(())
+}
+
+/// Untracked files have no index entry to apply hunks against, so they only
+/// stage whole
+fn reject_untracked_partial(change: &FileChange, untracked: &[String]) -> Result<()> {
+   if untracked.contains(&change.path) {
+      return Err(CommitGenError::Other(format!(
+         "{} is untracked and can only be staged whole; select it with [\"ALL\"]",
+         change.path
+      )));
+   }
+   Ok(())
+}
+
+/// Report a failure to stage `change` as [`CommitGenError::HunkApplyFailed`];
+/// other errors pass through
+fn apply_failed(err: CommitGenError, change: &FileChange, group_idx: usize) -> CommitGenError {
+   match err {
+      CommitGenError::GitError(stderr) | CommitGenError::Other(stderr) => {
+         let selector = change
+            .hunks
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+         CommitGenError::HunkApplyFailed {
+            group: group_idx,
+            file: change.path.clone(),
+            selector,
+            stderr,
+         }
+      },
+      e => e,
+   }
+}
+
+/// Dry run of [`stage_group_changes`] that leaves the index alone.
+///
+/// Whole files must be in `full_diff`; partial files' patches go through
+/// `git apply --cached --check` against a scratch index read from HEAD. Fails
+/// the same way, with [`CommitGenError::HunkApplyFailed`] for the first file
+/// that wouldn't stage.
+pub fn check_group_changes(
+   group: &ChangeGroup,
+   group_idx: usize,
+   dir: &str,
+   full_diff: &str,
+) -> Result<()> {
+   let nanos = std::time::SystemTime::now()
+      .duration_since(std::time::UNIX_EPOCH)
+      .map_or(0, |d| d.as_nanos());
+   let index =
+      std::env::temp_dir().join(format!("lgit-check-index-{}-{nanos}", std::process::id()));
+   let result = check_group_against_index(group, group_idx, dir, full_diff, &index);
+   std::fs::remove_file(&index).ok();
+   result
+}
+
+fn check_group_against_index(
+   group: &ChangeGroup,
+   group_idx: usize,
+   dir: &str,
+   full_diff: &str,
+   index: &Path,
+) -> Result<()> {
+   let output = Command::new("git")
+      .args(["read-tree", "HEAD"])
+      .env("GIT_INDEX_FILE", index)
+      .current_dir(dir)
+      .output()
+      .map_err(|e| CommitGenError::GitError(format!("Failed to read HEAD into index: {e}")))?;
+   if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      return Err(CommitGenError::GitError(format!("git read-tree HEAD failed: {stderr}")));
+   }
+
+   let untracked = crate::git::get_untracked_files(dir)?;
+   for change in &group.changes {
+      let is_all = change.hunks.len() == 1 && matches!(change.hunks[0], HunkSelector::All);
+      let checked = if is_all {
+         extract_file_diff(full_diff, &change.path).map(drop)
+      } else {
+         reject_untracked_partial(change, &untracked)
+            .and_then(|()| create_patch_for_changes(full_diff, std::slice::from_ref(change)))
+            .and_then(|patch| run_git_apply(&patch, dir, Some(index)))
+      };
+      checked.map_err(|e| apply_failed(e, change, group_idx))?;
    }
 
-   let patch = create_patch_for_changes(full_diff, &partial_changes)?;
-   apply_patch_to_index(&patch, dir)
+   Ok(())
 }
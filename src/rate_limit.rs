@@ -0,0 +1,247 @@
+//! Shared rate limiting for parallel API workers
+//!
+//! When a provider rate-limits, every worker that backs off independently will
+//! retry at roughly the same moment and re-trigger the limit. The
+//! [`RateLimiter`] is shared between workers (via `Arc`) so that a single 429
+//! pauses everyone until the same deadline, and halves the number of requests
+//! allowed in flight for the remainder of the phase.
+
+use std::{
+   thread,
+   time::{Duration, Instant},
+};
+
+use parking_lot::{Condvar, Mutex};
+use reqwest::{StatusCode, header::HeaderMap};
+
+use crate::error::CommitGenError;
+
+/// Upper bound on any single pause, regardless of what the provider asks for
+const MAX_PAUSE: Duration = Duration::from_secs(120);
+
+#[derive(Debug)]
+struct LimiterState {
+   /// Global "do not send before" deadline shared by all workers
+   pause_until: Option<Instant>,
+   /// Maximum requests allowed in flight
+   concurrency: usize,
+   /// Requests currently in flight
+   in_flight:   usize,
+}
+
+/// Rate limiter shared across workers of a single phase
+#[derive(Debug)]
+pub struct RateLimiter {
+   state:    Mutex<LimiterState>,
+   released: Condvar,
+}
+
+/// Guard for an in-flight request slot; releases the slot on drop
+pub struct Permit<'a> {
+   limiter: &'a RateLimiter,
+}
+
+impl Drop for Permit<'_> {
+   fn drop(&mut self) {
+      let mut state = self.limiter.state.lock();
+      state.in_flight = state.in_flight.saturating_sub(1);
+      drop(state);
+      self.limiter.released.notify_all();
+   }
+}
+
+impl RateLimiter {
+   /// Create a limiter allowing `concurrency` requests in flight
+   pub fn new(concurrency: usize) -> Self {
+      Self {
+         state:    Mutex::new(LimiterState {
+            pause_until: None,
+            concurrency: concurrency.max(1),
+            in_flight:   0,
+         }),
+         released: Condvar::new(),
+      }
+   }
+
+   /// Create a limiter that never restricts concurrency (single-call use)
+   pub fn unbounded() -> Self {
+      Self::new(usize::MAX)
+   }
+
+   /// Current effective concurrency
+   pub fn concurrency(&self) -> usize {
+      self.state.lock().concurrency
+   }
+
+   /// Wait for any global pause to elapse, then claim an in-flight slot
+   pub fn acquire(&self) -> Permit<'_> {
+      loop {
+         let mut state = self.state.lock();
+
+         if let Some(until) = state.pause_until {
+            let now = Instant::now();
+            if until > now {
+               drop(state);
+               thread::sleep(until - now);
+               continue;
+            }
+            state.pause_until = None;
+         }
+
+         if state.in_flight < state.concurrency {
+            state.in_flight += 1;
+            return Permit { limiter: self };
+         }
+
+         self.released.wait(&mut state);
+      }
+   }
+
+   /// Record a rate-limit response and pause all workers
+   ///
+   /// Honors `retry_after` when the provider sent one; otherwise backs off
+   /// exponentially from `initial_backoff_ms`. Concurrency is halved once per
+   /// pause window, so a burst of simultaneous 429s only halves it once.
+   pub fn record_rate_limit(
+      &self,
+      retry_after: Option<Duration>,
+      attempt: u32,
+      initial_backoff_ms: u64,
+   ) -> Duration {
+      let delay = retry_after
+         .unwrap_or_else(|| {
+            Duration::from_millis(initial_backoff_ms.saturating_mul(1 << attempt.min(16)))
+         })
+         .min(MAX_PAUSE);
+
+      let now = Instant::now();
+      let deadline = now + delay;
+
+      let mut state = self.state.lock();
+      let already_paused = state.pause_until.is_some_and(|until| until > now);
+      if !already_paused {
+         state.concurrency = (state.concurrency / 2).max(1);
+      }
+      if state.pause_until.is_none_or(|until| until < deadline) {
+         state.pause_until = Some(deadline);
+      }
+
+      delay
+   }
+}
+
+/// Parse a `Retry-After` header given in seconds
+pub fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+   let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+   let secs: f64 = value.trim().parse().ok()?;
+   (secs.is_finite() && secs >= 0.0).then(|| Duration::from_secs_f64(secs))
+}
+
+/// Detect provider rate limiting or overload responses
+///
+/// Returns `CommitGenError::RateLimited` for HTTP 429/529, or any status whose
+/// body carries a provider overload marker.
+pub fn rate_limit_error(
+   status: StatusCode,
+   retry_after: Option<Duration>,
+   body: &str,
+) -> Option<CommitGenError> {
+   const OVERLOAD_MARKERS: &[&str] =
+      &["overloaded_error", "rate_limit_error", "rate_limit_exceeded"];
+
+   let is_limited = matches!(status.as_u16(), 429 | 529)
+      || (!status.is_success() && OVERLOAD_MARKERS.iter().any(|m| body.contains(m)));
+
+   is_limited.then(|| CommitGenError::RateLimited {
+      status: status.as_u16(),
+      retry_after,
+      body: body.to_string(),
+   })
+}
+
+#[cfg(test)]
+mod tests {
+   use std::sync::{
+      Arc,
+      atomic::{AtomicUsize, Ordering},
+   };
+
+   use super::*;
+   use crate::{api::retry_api_call_with, config::CommitConfig};
+
+   #[test]
+   fn test_rate_limit_error_detection() {
+      assert!(rate_limit_error(StatusCode::TOO_MANY_REQUESTS, None, "").is_some());
+      assert!(rate_limit_error(StatusCode::from_u16(529).unwrap(), None, "").is_some());
+      assert!(
+         rate_limit_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            None,
+            r#"{"type":"error","error":{"type":"overloaded_error"}}"#
+         )
+         .is_some()
+      );
+      assert!(rate_limit_error(StatusCode::BAD_REQUEST, None, "bad input").is_none());
+      assert!(rate_limit_error(StatusCode::INTERNAL_SERVER_ERROR, None, "boom").is_none());
+   }
+
+   #[test]
+   fn test_parse_retry_after() {
+      let mut headers = HeaderMap::new();
+      assert_eq!(parse_retry_after(&headers), None);
+      headers.insert(reqwest::header::RETRY_AFTER, "2".parse().unwrap());
+      assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(2)));
+      headers.insert(reqwest::header::RETRY_AFTER, "soon".parse().unwrap());
+      assert_eq!(parse_retry_after(&headers), None);
+   }
+
+   #[test]
+   fn test_burst_halves_concurrency_once() {
+      let limiter = RateLimiter::new(8);
+      limiter.record_rate_limit(Some(Duration::from_millis(50)), 0, 1);
+      limiter.record_rate_limit(Some(Duration::from_millis(50)), 0, 1);
+      limiter.record_rate_limit(None, 1, 1);
+      assert_eq!(limiter.concurrency(), 4);
+   }
+
+   #[test]
+   fn test_workers_back_off_until_concurrency_drops() {
+      let config = CommitConfig { max_retries: 20, initial_backoff_ms: 1, ..Default::default() };
+      let limiter = Arc::new(RateLimiter::new(8));
+      let in_flight = Arc::new(AtomicUsize::new(0));
+      let rejected = Arc::new(AtomicUsize::new(0));
+
+      // Mock API: rejects with 429 whenever more than 2 requests are in flight
+      let handles: Vec<_> = (0..8)
+         .map(|_| {
+            let config = config.clone();
+            let limiter = Arc::clone(&limiter);
+            let in_flight = Arc::clone(&in_flight);
+            let rejected = Arc::clone(&rejected);
+            thread::spawn(move || {
+               retry_api_call_with(&config, &limiter, || {
+                  let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                  thread::sleep(Duration::from_millis(20));
+                  in_flight.fetch_sub(1, Ordering::SeqCst);
+                  if current > 2 {
+                     rejected.fetch_add(1, Ordering::SeqCst);
+                     return Err(CommitGenError::RateLimited {
+                        status:      429,
+                        retry_after: Some(Duration::from_millis(5)),
+                        body:        "rate limited".to_string(),
+                     });
+                  }
+                  Ok((false, Some(())))
+               })
+            })
+         })
+         .collect();
+
+      for handle in handles {
+         handle.join().unwrap().unwrap();
+      }
+
+      assert!(rejected.load(Ordering::SeqCst) > 0);
+      assert!(limiter.concurrency() <= 2);
+   }
+}
@@ -0,0 +1,243 @@
+//! Release commits: a version bump with its regenerated changelog
+//!
+//! A diff that only touches manifests, changelogs and lockfiles, and changes
+//! the version field of at least one manifest (`Cargo.toml`, `package.json`,
+//! `pyproject.toml`), is a release. It gets a deterministic
+//! `chore(release): prepared release vX.Y.Z` message instead of a model call,
+//! and optionally the matching annotated tag (`release_tag`).
+
+use std::path::Path;
+
+use crate::{
+   analysis::{is_changelog, is_lockfile},
+   diff::{FileDiff, parse_diff},
+};
+
+/// Manifest formats with a version field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Manifest {
+   /// `Cargo.toml`, `pyproject.toml`: `version = "1.4.0"`
+   Toml,
+   /// `package.json`: `"version": "1.4.0",`
+   Json,
+}
+
+/// A manifest whose version changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionBump {
+   pub path: String,
+   pub old:  String,
+   pub new:  String,
+}
+
+/// A detected release
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+   /// New version of the first bumped manifest, without a `v` prefix
+   pub version:    String,
+   pub bumps:      Vec<VersionBump>,
+   /// Changelog files the release updated
+   pub changelogs: Vec<String>,
+}
+
+fn file_name(path: &str) -> &str {
+   Path::new(path)
+      .file_name()
+      .and_then(|name| name.to_str())
+      .unwrap_or(path)
+}
+
+fn manifest_kind(path: &str) -> Option<Manifest> {
+   match file_name(path) {
+      "Cargo.toml" | "pyproject.toml" => Some(Manifest::Toml),
+      "package.json" => Some(Manifest::Json),
+      _ => None,
+   }
+}
+
+/// Version from a manifest line declaring one
+fn version_value(manifest: Manifest, line: &str) -> Option<&str> {
+   let line = line.trim();
+   let value = match manifest {
+      Manifest::Toml => line
+         .strip_prefix("version")?
+         .trim_start()
+         .strip_prefix('=')?,
+      Manifest::Json => line
+         .strip_prefix("\"version\"")?
+         .trim_start()
+         .strip_prefix(':')?,
+   };
+   let value = value
+      .trim()
+      .trim_end_matches(',')
+      .strip_prefix('"')?
+      .strip_suffix('"')?;
+   (!value.is_empty()).then_some(value)
+}
+
+/// The version bump in a manifest's diff; `None` when it changes anything but
+/// the version line
+fn version_bump(file: &FileDiff, manifest: Manifest) -> Option<VersionBump> {
+   let (mut old, mut new) = (None, None);
+   for line in file.content.lines() {
+      let (marker, text) = line.split_at_checked(1)?;
+      if !matches!(marker, "+" | "-") || text.trim().is_empty() {
+         continue;
+      }
+      let version = version_value(manifest, text)?;
+      let side = if marker == "+" { &mut new } else { &mut old };
+      if side.replace(version).is_some() {
+         return None;
+      }
+   }
+
+   match (old, new) {
+      (Some(old), Some(new)) if old != new => Some(VersionBump {
+         path: file.filename.clone(),
+         old:  old.to_string(),
+         new:  new.to_string(),
+      }),
+      _ => None,
+   }
+}
+
+/// Release in `diff`, if every file is a manifest whose only change is its
+/// version, a changelog or a lockfile, and some manifest's version changed
+pub fn detect_release(diff: &str) -> Option<Release> {
+   let mut bumps = Vec::new();
+   let mut changelogs = Vec::new();
+   for file in parse_diff(diff) {
+      if let Some(manifest) = manifest_kind(&file.filename) {
+         bumps.push(version_bump(&file, manifest)?);
+      } else if is_changelog(&file.filename) {
+         changelogs.push(file.filename);
+      } else if !is_lockfile(&file.filename) {
+         return None;
+      }
+   }
+
+   let version = bumps.first()?.new.trim_start_matches('v').to_string();
+   Some(Release { version, bumps, changelogs })
+}
+
+/// Tag name for `version` from `release_tag_format` (`{version}` is replaced)
+pub fn tag_name(format: &str, release_version: &str) -> String {
+   format.replace("{version}", release_version.trim_start_matches('v'))
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   const CARGO_RELEASE: &str = r#"diff --git a/Cargo.toml b/Cargo.toml
+index 1111111..2222222 100644
+--- a/Cargo.toml
++++ b/Cargo.toml
+@@ -1,6 +1,6 @@
+ [package]
+ name = "llm-git"
+-version = "1.3.2"
++version = "1.4.0"
+ edition = "2024"
+diff --git a/Cargo.lock b/Cargo.lock
+index 3333333..4444444 100644
+--- a/Cargo.lock
++++ b/Cargo.lock
+@@ -10,7 +10,7 @@
+ [[package]]
+ name = "llm-git"
+-version = "1.3.2"
++version = "1.4.0"
+diff --git a/CHANGELOG.md b/CHANGELOG.md
+index 5555555..6666666 100644
+--- a/CHANGELOG.md
++++ b/CHANGELOG.md
+@@ -1,3 +1,7 @@
+ # Changelog
++
++## [1.4.0] - 2026-10-16
++
++- Added release detection.
+"#;
+
+   const PACKAGE_RELEASE: &str = r#"diff --git a/package.json b/package.json
+index 1111111..2222222 100644
+--- a/package.json
++++ b/package.json
+@@ -1,5 +1,5 @@
+ {
+   "name": "web",
+-  "version": "0.9.1",
++  "version": "1.0.0",
+   "private": true,
+diff --git a/package-lock.json b/package-lock.json
+index 3333333..4444444 100644
+--- a/package-lock.json
++++ b/package-lock.json
+@@ -1,6 +1,6 @@
+ {
+   "name": "web",
+-  "version": "0.9.1",
++  "version": "1.0.0",
+"#;
+
+   const PYPROJECT_RELEASE: &str = r#"diff --git a/pyproject.toml b/pyproject.toml
+index 1111111..2222222 100644
+--- a/pyproject.toml
++++ b/pyproject.toml
+@@ -1,4 +1,4 @@
+ [project]
+ name = "tool"
+-version = "2.0.0rc1"
++version = "2.0.0"
+"#;
+
+   #[test]
+   fn test_detect_release_across_manifest_formats() {
+      let release = detect_release(CARGO_RELEASE).unwrap();
+      assert_eq!(release.version, "1.4.0");
+      assert_eq!(release.bumps, vec![VersionBump {
+         path: "Cargo.toml".to_string(),
+         old:  "1.3.2".to_string(),
+         new:  "1.4.0".to_string(),
+      }]);
+      assert_eq!(release.changelogs, vec!["CHANGELOG.md".to_string()]);
+
+      let release = detect_release(PACKAGE_RELEASE).unwrap();
+      assert_eq!(release.version, "1.0.0");
+      assert!(release.changelogs.is_empty());
+
+      assert_eq!(detect_release(PYPROJECT_RELEASE).unwrap().version, "2.0.0");
+   }
+
+   #[test]
+   fn test_detect_release_rejects_other_changes() {
+      // A dependency change alongside the bump isn't just a release
+      let with_dependency = CARGO_RELEASE.replace(
+         " edition = \"2024\"\n",
+         " edition = \"2024\"\n-serde = \"1.0.1\"\n+serde = \"1.0.2\"\n",
+      );
+      assert_eq!(detect_release(&with_dependency), None);
+
+      // Nor is one touching source files
+      let with_source = format!(
+         "{PYPROJECT_RELEASE}diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ \
+          b/src/lib.rs\n@@ -1 +1 @@\n-old\n+new\n"
+      );
+      assert_eq!(detect_release(&with_source), None);
+
+      // A changelog alone has no version to release
+      let changelog_only =
+         &CARGO_RELEASE[CARGO_RELEASE.find("diff --git a/CHANGELOG.md").unwrap()..];
+      assert_eq!(detect_release(changelog_only), None);
+   }
+
+   #[test]
+   fn test_tag_name_templating() {
+      assert_eq!(tag_name("v{version}", "1.4.0"), "v1.4.0");
+      assert_eq!(tag_name("v{version}", "v1.4.0"), "v1.4.0");
+      assert_eq!(tag_name("release-{version}", "2.0.0"), "release-2.0.0");
+      assert_eq!(tag_name("{version}", "0.1.0"), "0.1.0");
+   }
+}
@@ -1,27 +1,35 @@
-use std::{fmt, sync::Arc};
+use std::{collections::HashMap, fmt, sync::Arc};
 
 use parking_lot::Mutex;
 use rayon::prelude::*;
 
 use crate::{
-   analysis::extract_scope_candidates,
+   analysis::{classify_change_profile, extract_scope_candidates},
    api::{AnalysisContext, generate_conventional_analysis, generate_summary_from_analysis},
    config::CommitConfig,
    diff::smart_truncate_diff,
    error::{CommitGenError, Result},
    git::{
       check_working_tree_clean, create_backup_branch, get_commit_list, get_commit_metadata,
-      get_git_diff, get_git_stat, rewrite_history,
+      get_git_diff, get_git_stat, get_unpushed_commits, has_commit_signature, list_commits,
+      rewrite_history,
    },
-   normalization::{format_commit_message, post_process_commit_message},
+   mailmap::Mailmap,
+   normalization::{format_commit_message, post_process_commit_message, split_footers},
+   stats::RunTimer,
    style,
    tokens::create_token_counter,
    types::{Args, CommitMetadata, ConventionalCommit, Mode},
    validation::validate_commit_message,
 };
 
+/// Print the run's stats line (stage times are summed over commits)
+fn print_stats(timer: &RunTimer) {
+   println!("\n{} {}", style::dim("›"), style::dim(&timer.finish().to_string()));
+}
+
 /// Run rewrite mode - regenerate all commit messages in history
-pub fn run_rewrite_mode(args: &Args, config: &CommitConfig) -> Result<()> {
+pub fn run_rewrite_mode(args: &Args, config: &CommitConfig, timer: &RunTimer) -> Result<()> {
    // 1. Validate preconditions
    if !args.rewrite_dry_run
       && args.rewrite_preview.is_none()
@@ -44,7 +52,7 @@ pub fn run_rewrite_mode(args: &Args, config: &CommitConfig) -> Result<()> {
 
    // 3. Extract metadata
    println!("{} Extracting commit metadata...", style::info("🔍"));
-   let commits: Vec<CommitMetadata> = commit_hashes
+   let mut commits: Vec<CommitMetadata> = commit_hashes
       .iter()
       .enumerate()
       .map(|(i, hash)| {
@@ -55,6 +63,19 @@ pub fn run_rewrite_mode(args: &Args, config: &CommitConfig) -> Result<()> {
       })
       .collect::<Result<Vec<_>>>()?;
 
+   let mut mailmap = if config.rewrite_apply_mailmap {
+      Mailmap::load(&args.dir)?
+   } else {
+      Mailmap::default()
+   };
+   mailmap.extend(Mailmap::from_pairs(&config.rewrite_mailmap)?);
+   if !mailmap.is_empty() {
+      println!("{} Applying .mailmap identities", style::info("👤"));
+      for commit in &mut commits {
+         mailmap.apply(commit);
+      }
+   }
+
    // 4. Preview mode (no API calls)
    if args.rewrite_dry_run && args.rewrite_preview.is_some() {
       print_preview_list(&commits);
@@ -72,7 +93,7 @@ pub fn run_rewrite_mode(args: &Args, config: &CommitConfig) -> Result<()> {
    let mut rewrite_config = config.clone();
    rewrite_config.exclude_old_message = true;
 
-   let new_messages = generate_messages_parallel(&commits, &rewrite_config, args)?;
+   let new_messages = generate_messages_parallel(&commits, &rewrite_config, args, timer)?;
 
    // 6. Show results
    print_conversion_results(&commits, &new_messages);
@@ -81,11 +102,13 @@ pub fn run_rewrite_mode(args: &Args, config: &CommitConfig) -> Result<()> {
    if args.rewrite_dry_run {
       println!("\n{}", style::section_header("DRY RUN - No changes made", 50));
       println!("Run without --rewrite-dry-run to apply changes");
+      print_stats(timer);
       return Ok(());
    }
 
    if args.rewrite_preview.is_some() {
       println!("\nRun without --rewrite-preview to rewrite all history");
+      print_stats(timer);
       return Ok(());
    }
 
@@ -96,7 +119,7 @@ pub fn run_rewrite_mode(args: &Args, config: &CommitConfig) -> Result<()> {
 
    // 9. Rewrite history
    println!("\n{} Rewriting history...", style::warning("⚠️"));
-   rewrite_history(&commits, &new_messages, &args.dir)?;
+   timer.stage("rewrite", || rewrite_history(&commits, &new_messages, &args.dir))?;
 
    println!(
       "\n{} Done! Rewrote {} commits",
@@ -104,7 +127,58 @@ pub fn run_rewrite_mode(args: &Args, config: &CommitConfig) -> Result<()> {
       style::bold(&commits.len().to_string())
    );
    println!("Restore with: {}", style::dim(&format!("git reset --hard {backup}")));
+   print_stats(timer);
+
+   Ok(())
+}
+
+/// Regenerate the messages of the commits not yet pushed
+/// (`@{upstream}..HEAD`), then replay them with the new messages.
+///
+/// Trees, authors and dates are kept, as in rewrite mode; `--dry-run` only
+/// prints the proposed messages.
+pub fn run_polish_mode(args: &Args, config: &CommitConfig, timer: &RunTimer) -> Result<()> {
+   let (upstream, commit_hashes) = get_unpushed_commits(&args.dir)?;
+   if commit_hashes.is_empty() {
+      println!("{} Nothing to polish: no commits ahead of {upstream}", style::info("›"));
+      return Ok(());
+   }
+   if !args.dry_run && !check_working_tree_clean(&args.dir)? {
+      return Err(CommitGenError::Other(
+         "Working directory not clean. Commit or stash changes first.".to_string(),
+      ));
+   }
+
+   println!(
+      "{} Polishing {} unpushed commits (ahead of {upstream})...\n",
+      style::info("›"),
+      style::bold(&commit_hashes.len().to_string())
+   );
+   let commits: Vec<CommitMetadata> = commit_hashes
+      .iter()
+      .map(|hash| get_commit_metadata(hash, &args.dir))
+      .collect::<Result<Vec<_>>>()?;
+
+   let mut polish_config = config.clone();
+   polish_config.exclude_old_message = true;
+   let new_messages = generate_messages_parallel(&commits, &polish_config, args, timer)?;
+   print_conversion_results(&commits, &new_messages);
+
+   if args.dry_run {
+      println!("\n{}", style::section_header("DRY RUN - No changes made", 50));
+      print_stats(timer);
+      return Ok(());
+   }
 
+   let backup = create_backup_branch(&args.dir)?;
+   timer.stage("rewrite", || rewrite_history(&commits, &new_messages, &args.dir))?;
+   println!(
+      "\n{} Polished {} commits",
+      style::success("✓"),
+      style::bold(&commits.len().to_string())
+   );
+   println!("Restore with: {}", style::dim(&format!("git reset --hard {backup}")));
+   print_stats(timer);
    Ok(())
 }
 
@@ -113,6 +187,7 @@ fn generate_messages_parallel(
    commits: &[CommitMetadata],
    config: &CommitConfig,
    args: &Args,
+   timer: &RunTimer,
 ) -> Result<Vec<String>> {
    let new_messages = Arc::new(Mutex::new(vec![String::new(); commits.len()]));
    let errors = Arc::new(Mutex::new(Vec::new()));
@@ -123,7 +198,7 @@ fn generate_messages_parallel(
       .map_err(|e| CommitGenError::Other(format!("Failed to create thread pool: {e}")))?
       .install(|| {
          commits.par_iter().enumerate().for_each(|(idx, commit)| {
-            match generate_for_commit(commit, config, &args.dir) {
+            match generate_for_commit(commit, config, &args.dir, timer) {
                Ok(new_msg) => {
                   new_messages.lock()[idx].clone_from(&new_msg);
 
@@ -176,6 +251,7 @@ fn generate_for_commit(
    commit: &CommitMetadata,
    config: &CommitConfig,
    dir: &str,
+   timer: &RunTimer,
 ) -> Result<String> {
    let token_counter = create_token_counter(config);
 
@@ -184,6 +260,8 @@ fn generate_for_commit(
    let diff = get_git_diff(&Mode::Commit, Some(&commit.hash), dir, config)?;
    let stat = get_git_stat(&Mode::Commit, Some(&commit.hash), dir, config)?;
 
+   let profile = classify_change_profile(&diff);
+
    // Truncate if needed
    let diff = if diff.len() > config.max_diff_length {
       smart_truncate_diff(&diff, config.max_diff_length, config, &token_counter)
@@ -201,40 +279,49 @@ fn generate_for_commit(
       recent_commits:  None, // No recent commits for rewrite mode
       common_scopes:   None, // No common scopes for rewrite mode
       project_context: None, // No project context for rewrite mode
+      change_profile:  None,
       debug_output:    None,
       debug_prefix:    None,
    };
-   let analysis = generate_conventional_analysis(
-      &stat,
-      &diff,
-      &config.model,
-      &scope_candidates_str,
-      &ctx,
-      config,
-   )?;
+   let analysis = timer.stage("analysis", || {
+      generate_conventional_analysis(
+         &stat,
+         &diff,
+         &config.model,
+         &scope_candidates_str,
+         &ctx,
+         config,
+      )
+   })?;
 
    // Phase 2: Summary
    let body_texts = analysis.body_texts();
-   let summary = generate_summary_from_analysis(
-      &stat,
-      analysis.commit_type.as_str(),
-      analysis.scope.as_ref().map(|s| s.as_str()),
-      &body_texts,
-      None, // No user context in rewrite mode
-      config,
-      None,
-      None,
-   )?;
-
-   // Build ConventionalCommit
-   // Issue refs are now inlined in body items, so footers are empty (unless added
-   // by CLI)
+   let summary = timer
+      .stage("summary", || {
+         generate_summary_from_analysis(
+            &stat,
+            &profile,
+            analysis.commit_type.as_str(),
+            analysis.scope.as_ref().map(|s| s.as_str()),
+            &body_texts,
+            None, // No user context in rewrite mode
+            None,
+            config,
+            None,
+            None,
+         )
+      })?
+      .summary;
+
+   // Build ConventionalCommit; the original footers (trailers, BREAKING CHANGE)
+   // carry over, while issue refs are inlined in body items
+   let (_, footers) = split_footers(&commit.message);
    let mut commit_msg = ConventionalCommit {
       commit_type: analysis.commit_type,
       scope: analysis.scope,
       summary,
       body: body_texts,
-      footers: vec![], // Issue refs are inlined in body items now
+      footers,
    };
 
    // Post-process and validate
@@ -242,7 +329,7 @@ fn generate_for_commit(
    validate_commit_message(&commit_msg, config)?;
 
    // Format final message
-   Ok(format_commit_message(&commit_msg))
+   Ok(format_commit_message(&commit_msg, config.body_style))
 }
 
 /// Print preview list of commits (no API calls)
@@ -295,6 +382,142 @@ fn print_conversion_results(commits: &[CommitMetadata], new_messages: &[String])
    }
 }
 
+/// A commit whose message changed in a rewrite
+#[derive(Debug, Clone)]
+pub struct MessageChange {
+   pub old_hash:    String,
+   pub new_hash:    String,
+   pub old_message: String,
+   pub new_message: String,
+}
+
+/// Result of comparing a history with its rewrite
+#[derive(Debug, Default)]
+pub struct RewriteVerification {
+   /// Commits compared (same on both sides)
+   pub commits:            usize,
+   pub message_changes:    Vec<MessageChange>,
+   /// Commits signed in the original but not in the rewrite; expected, since
+   /// rewriting cannot re-sign
+   pub dropped_signatures: usize,
+}
+
+/// Walk `old_ref` and `new_ref` side by side and check that only messages
+/// differ.
+///
+/// Commit counts, trees, author identities and dates, and parents (mapped
+/// through the rewrite) must match; the first divergent pair is a
+/// `ValidationError`.
+pub fn verify_rewrite(old_ref: &str, new_ref: &str, dir: &str) -> Result<RewriteVerification> {
+   let old_hashes = list_commits(old_ref, dir)?;
+   let new_hashes = list_commits(new_ref, dir)?;
+   if old_hashes.len() != new_hashes.len() {
+      return Err(CommitGenError::ValidationError(format!(
+         "Commit counts differ: {old_ref} has {}, {new_ref} has {}",
+         old_hashes.len(),
+         new_hashes.len()
+      )));
+   }
+
+   let mut report = RewriteVerification { commits: old_hashes.len(), ..Default::default() };
+   let mut rewritten: HashMap<String, String> = HashMap::new();
+
+   for (idx, (old_hash, new_hash)) in old_hashes.iter().zip(&new_hashes).enumerate() {
+      let old = get_commit_metadata(old_hash, dir)?;
+      let new = get_commit_metadata(new_hash, dir)?;
+
+      let expected_parents: Vec<&String> = old
+         .parent_hashes
+         .iter()
+         .map(|parent| rewritten.get(parent).unwrap_or(parent))
+         .collect();
+      let new_parents: Vec<&String> = new.parent_hashes.iter().collect();
+
+      let mismatch = if old.tree_hash != new.tree_hash {
+         Some(format!("tree {} vs {}", old.tree_hash, new.tree_hash))
+      } else if (&old.author_name, &old.author_email) != (&new.author_name, &new.author_email) {
+         Some(format!(
+            "author {} <{}> vs {} <{}>",
+            old.author_name, old.author_email, new.author_name, new.author_email
+         ))
+      } else if old.author_date != new.author_date {
+         Some(format!("author date {} vs {}", old.author_date, new.author_date))
+      } else if expected_parents != new_parents {
+         Some("parent structure".to_string())
+      } else {
+         None
+      };
+
+      if let Some(what) = mismatch {
+         return Err(CommitGenError::ValidationError(format!(
+            "Rewrite diverges at commit {} ({} vs {}): {what} differs",
+            idx + 1,
+            &old_hash[..8],
+            &new_hash[..8]
+         )));
+      }
+
+      if old.message.trim_end() != new.message.trim_end() {
+         report.message_changes.push(MessageChange {
+            old_hash:    old_hash.clone(),
+            new_hash:    new_hash.clone(),
+            old_message: old.message,
+            new_message: new.message,
+         });
+      }
+      if has_commit_signature(old_hash, dir)? && !has_commit_signature(new_hash, dir)? {
+         report.dropped_signatures += 1;
+      }
+
+      rewritten.insert(old_hash.clone(), new_hash.clone());
+   }
+
+   Ok(report)
+}
+
+/// Run `--rewrite-verify`: fail on any non-message difference, else print the
+/// message diffs
+pub fn run_rewrite_verify(args: &Args) -> Result<()> {
+   let (Some(old_ref), Some(new_ref)) = (&args.verify_old, &args.verify_new) else {
+      return Err(CommitGenError::ValidationError(
+         "--rewrite-verify requires --old <ref> and --new <ref>".to_string(),
+      ));
+   };
+
+   let report = verify_rewrite(old_ref, new_ref, &args.dir)?;
+
+   for change in &report.message_changes {
+      println!(
+         "{} {} -> {}",
+         style::info("~"),
+         style::dim(&change.old_hash[..8]),
+         style::dim(&change.new_hash[..8])
+      );
+      for line in change.old_message.trim_end().lines() {
+         println!("  {} {}", style::error("-"), style::dim(line));
+      }
+      for line in change.new_message.trim_end().lines() {
+         println!("  {} {line}", style::success("+"));
+      }
+      println!();
+   }
+
+   if report.dropped_signatures > 0 {
+      println!(
+         "{} {} commit signature(s) not carried over (rewritten commits are unsigned)",
+         style::info("ℹ"),
+         report.dropped_signatures
+      );
+   }
+   println!(
+      "{} {} commits match; {} message(s) changed, nothing else",
+      style::success("✓"),
+      style::bold(&report.commits.to_string()),
+      report.message_changes.len()
+   );
+   Ok(())
+}
+
 struct TruncStr<'a>(&'a str, usize);
 
 impl fmt::Display for TruncStr<'_> {
@@ -308,3 +531,67 @@ impl fmt::Display for TruncStr<'_> {
       }
    }
 }
+
+#[cfg(test)]
+mod tests {
+   use std::{path::Path, process::Command};
+
+   use super::*;
+
+   fn git(dir: &Path, args: &[&str]) -> String {
+      let output = Command::new("git")
+         .args(args)
+         .current_dir(dir)
+         .output()
+         .unwrap();
+      assert!(output.status.success(), "git {args:?} failed");
+      String::from_utf8_lossy(&output.stdout).trim().to_string()
+   }
+
+   #[test]
+   fn test_verify_rewrite_faithful_and_dropped_commit() {
+      let dir = std::env::temp_dir().join(format!("lgit-rewrite-verify-{}", std::process::id()));
+      let _ = std::fs::remove_dir_all(&dir);
+      std::fs::create_dir_all(&dir).unwrap();
+      git(&dir, &["init", "-q", "-b", "main"]);
+      git(&dir, &["config", "user.name", "Test"]);
+      git(&dir, &["config", "user.email", "test@example.com"]);
+      git(&dir, &["config", "commit.gpgsign", "false"]);
+      for (idx, file) in ["a.txt", "b.txt", "c.txt"].iter().enumerate() {
+         std::fs::write(dir.join(file), format!("{idx}\n")).unwrap();
+         git(&dir, &["add", file]);
+         git(&dir, &["commit", "-qm", &format!("wip {file}")]);
+      }
+      git(&dir, &["branch", "orig"]);
+      let dir_str = dir.to_str().unwrap();
+
+      // Faithful: only messages change
+      let commits: Vec<CommitMetadata> = get_commit_list(None, dir_str)
+         .unwrap()
+         .iter()
+         .map(|hash| get_commit_metadata(hash, dir_str).unwrap())
+         .collect();
+      let messages =
+         vec!["chore: added a".to_string(), "wip b.txt".to_string(), "chore: added c".to_string()];
+      rewrite_history(&commits, &messages, dir_str).unwrap();
+
+      let report = verify_rewrite("orig", "main", dir_str).unwrap();
+      assert_eq!(report.commits, 3);
+      assert_eq!(report.message_changes.len(), 2);
+      assert_eq!(report.message_changes[0].new_message.trim(), "chore: added a");
+      assert_eq!(report.dropped_signatures, 0);
+
+      // Unfaithful: the middle commit is dropped
+      git(&dir, &["checkout", "-q", "-b", "dropped", "orig~2"]);
+      git(&dir, &["cherry-pick", "orig"]);
+      let err = verify_rewrite("orig", "dropped", dir_str).unwrap_err();
+      assert!(err.to_string().contains("Commit counts differ"), "{err}");
+
+      // Same count but a changed tree is named at the first divergent pair
+      git(&dir, &["commit", "-q", "--allow-empty", "-m", "pad"]);
+      let err = verify_rewrite("orig", "dropped", dir_str).unwrap_err();
+      assert!(err.to_string().contains("diverges at commit 2"), "{err}");
+
+      let _ = std::fs::remove_dir_all(&dir);
+   }
+}
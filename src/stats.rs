@@ -0,0 +1,255 @@
+//! Local run statistics for the end-of-run summary line
+//!
+//! Stage timings are accumulated by a [`RunTimer`] created at the start of a
+//! run; API calls and retries are counted into the config's [`ApiCounter`] by
+//! [`retry_api_call_with`](crate::api::retry_api_call_with), and fallbacks by
+//! [`confidence`](crate::confidence). Nothing is sent anywhere: the stats are
+//! only printed and included in JSON/debug output.
+
+use std::{
+   fmt,
+   sync::{
+      Arc,
+      atomic::{AtomicUsize, Ordering},
+   },
+   time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::confidence;
+
+/// API request attempts made with one loaded config. Clones share the counts,
+/// so per-call config overrides and parallel workers add to the same totals.
+#[derive(Debug, Clone, Default)]
+pub struct ApiCounter(Arc<ApiCounts>);
+
+#[derive(Debug, Default)]
+struct ApiCounts {
+   /// Requests attempted, including retries
+   calls:   AtomicUsize,
+   /// Attempts that were retries of an earlier failed one
+   retries: AtomicUsize,
+}
+
+impl ApiCounter {
+   /// Note one request attempt; `attempt` starts at 1
+   pub fn record_attempt(&self, attempt: u32) {
+      self.0.calls.fetch_add(1, Ordering::Relaxed);
+      if attempt > 1 {
+         self.0.retries.fetch_add(1, Ordering::Relaxed);
+      }
+   }
+
+   /// Current counts, with the process-wide fallback count
+   pub fn counters(&self) -> Counters {
+      Counters {
+         api_calls: self.0.calls.load(Ordering::Relaxed),
+         retries:   self.0.retries.load(Ordering::Relaxed),
+         fallbacks: confidence::fallback_count(),
+      }
+   }
+}
+
+/// Snapshot of an [`ApiCounter`] and the fallback count
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counters {
+   pub api_calls: usize,
+   pub retries:   usize,
+   pub fallbacks: usize,
+}
+
+impl Counters {
+   /// Counts since an earlier snapshot
+   pub const fn since(self, start: Self) -> Self {
+      Self {
+         api_calls: self.api_calls.saturating_sub(start.api_calls),
+         retries:   self.retries.saturating_sub(start.retries),
+         fallbacks: self.fallbacks.saturating_sub(start.fallbacks),
+      }
+   }
+}
+
+/// Timings and counts for one run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunStats {
+   pub elapsed:  Duration,
+   /// Time per stage, in the order stages first ran; a stage that runs more
+   /// than once (per compose group, per rewritten commit) is summed
+   pub stages:   Vec<(&'static str, Duration)>,
+   pub counters: Counters,
+}
+
+impl RunStats {
+   /// Add `duration` to `stage`
+   pub fn add_stage(&mut self, stage: &'static str, duration: Duration) {
+      match self.stages.iter_mut().find(|(name, _)| *name == stage) {
+         Some((_, total)) => *total += duration,
+         None => self.stages.push((stage, duration)),
+      }
+   }
+
+   /// Total time recorded for `stage`
+   pub fn stage(&self, stage: &str) -> Duration {
+      self
+         .stages
+         .iter()
+         .find(|(name, _)| *name == stage)
+         .map_or(Duration::ZERO, |(_, total)| *total)
+   }
+
+   pub fn to_json(&self) -> serde_json::Value {
+      let stages: serde_json::Map<String, serde_json::Value> = self
+         .stages
+         .iter()
+         .map(|(name, duration)| ((*name).to_string(), duration.as_secs_f64().into()))
+         .collect();
+      serde_json::json!({
+         "elapsed_secs": self.elapsed.as_secs_f64(),
+         "stage_secs": stages,
+         "api_calls": self.counters.api_calls,
+         "retries": self.counters.retries,
+         "fallbacks": self.counters.fallbacks,
+      })
+   }
+}
+
+fn count_of(count: usize, singular: &str, plural: &str) -> String {
+   format!("{count} {}", if count == 1 { singular } else { plural })
+}
+
+/// `done in 8.4s (analysis 5.1s, summary 1.2s) — 2 calls, 1 retry`; stages
+/// that would show as 0.0s are left out
+impl fmt::Display for RunStats {
+   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      write!(f, "done in {:.1}s", self.elapsed.as_secs_f64())?;
+
+      let stages: Vec<String> = self
+         .stages
+         .iter()
+         .filter(|(_, duration)| duration.as_secs_f64() >= 0.05)
+         .map(|(name, duration)| format!("{name} {:.1}s", duration.as_secs_f64()))
+         .collect();
+      if !stages.is_empty() {
+         write!(f, " ({})", stages.join(", "))?;
+      }
+
+      write!(f, " — {}", count_of(self.counters.api_calls, "call", "calls"))?;
+      if self.counters.retries > 0 {
+         write!(f, ", {}", count_of(self.counters.retries, "retry", "retries"))?;
+      }
+      if self.counters.fallbacks > 0 {
+         write!(f, ", {}", count_of(self.counters.fallbacks, "fallback", "fallbacks"))?;
+      }
+      Ok(())
+   }
+}
+
+/// Accumulates stage timings for one run. Shared by reference, so parallel
+/// workers (rewrite) can time their stages into the same totals.
+#[derive(Debug)]
+pub struct RunTimer {
+   started:  Instant,
+   api:      ApiCounter,
+   counters: Counters,
+   stages:   Mutex<RunStats>,
+}
+
+impl RunTimer {
+   /// Start timing; API calls are counted from `api`
+   pub fn start(api: &ApiCounter) -> Self {
+      Self {
+         started:  Instant::now(),
+         api:      api.clone(),
+         counters: api.counters(),
+         stages:   Mutex::default(),
+      }
+   }
+
+   /// Run `f`, adding its wall time to `stage`
+   pub fn stage<T>(&self, stage: &'static str, f: impl FnOnce() -> T) -> T {
+      let started = Instant::now();
+      let result = f();
+      self.stages.lock().add_stage(stage, started.elapsed());
+      result
+   }
+
+   /// Stats so far: elapsed time, stage totals, and counter changes since
+   /// [`RunTimer::start`]
+   pub fn finish(&self) -> RunStats {
+      RunStats {
+         elapsed: self.started.elapsed(),
+         counters: self.api.counters().since(self.counters),
+         ..self.stages.lock().clone()
+      }
+   }
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   fn secs(s: f64) -> Duration {
+      Duration::from_secs_f64(s)
+   }
+
+   #[test]
+   fn test_repeated_stages_are_summed_in_first_run_order() {
+      let mut stats = RunStats::default();
+      stats.add_stage("analysis", secs(2.0));
+      stats.add_stage("summary", secs(0.5));
+      stats.add_stage("analysis", secs(3.1));
+      stats.add_stage("summary", secs(0.7));
+
+      assert_eq!(stats.stages.len(), 2);
+      assert_eq!(stats.stages[0].0, "analysis");
+      assert_eq!(stats.stage("analysis"), secs(5.1));
+      assert_eq!(stats.stage("summary"), secs(1.2));
+      assert_eq!(stats.stage("commit"), Duration::ZERO);
+   }
+
+   #[test]
+   fn test_counters_since_snapshot() {
+      let start = Counters { api_calls: 4, retries: 1, fallbacks: 0 };
+      let now = Counters { api_calls: 7, retries: 2, fallbacks: 1 };
+      assert_eq!(now.since(start), Counters { api_calls: 3, retries: 1, fallbacks: 1 });
+      assert_eq!(start.since(now), Counters::default());
+   }
+
+   #[test]
+   fn test_timer_aggregates_across_threads() {
+      let timer = RunTimer::start(&ApiCounter::default());
+      std::thread::scope(|scope| {
+         for _ in 0..4 {
+            scope.spawn(|| timer.stage("analysis", || std::thread::sleep(secs(0.01))));
+         }
+      });
+      let stats = timer.finish();
+      assert_eq!(stats.stages.len(), 1);
+      assert!(stats.stage("analysis") >= secs(0.04));
+   }
+
+   #[test]
+   fn test_display_summary_line() {
+      let mut stats = RunStats {
+         elapsed: secs(8.43),
+         counters: Counters { api_calls: 2, retries: 1, fallbacks: 0 },
+         ..RunStats::default()
+      };
+      stats.add_stage("diff", secs(0.02));
+      stats.add_stage("analysis", secs(5.1));
+      stats.add_stage("summary", secs(1.2));
+      assert_eq!(
+         stats.to_string(),
+         "done in 8.4s (analysis 5.1s, summary 1.2s) — 2 calls, 1 retry"
+      );
+
+      stats.counters = Counters { api_calls: 1, retries: 2, fallbacks: 1 };
+      stats.stages.clear();
+      assert_eq!(stats.to_string(), "done in 8.4s — 1 call, 2 retries, 1 fallback");
+
+      let json = stats.to_json();
+      assert_eq!(json["api_calls"], 1);
+      assert_eq!(json["fallbacks"], 1);
+   }
+}
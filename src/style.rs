@@ -4,7 +4,10 @@
 
 use std::{
    io::{self, Write},
-   sync::OnceLock,
+   sync::{
+      OnceLock,
+      atomic::{AtomicBool, Ordering},
+   },
    thread,
    time::Duration,
 };
@@ -14,6 +17,19 @@ use owo_colors::OwoColorize;
 /// Whether color output is enabled (cached on first call).
 static COLOR_ENABLED: OnceLock<bool> = OnceLock::new();
 
+/// Whether status output (spinners, progress lines) is suppressed.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Suppress status output for the rest of the process (`--quiet`).
+pub fn set_quiet(quiet: bool) {
+   QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Check if status output is suppressed.
+pub fn is_quiet() -> bool {
+   QUIET.load(Ordering::Relaxed)
+}
+
 /// Check if colors should be used.
 pub fn colors_enabled() -> bool {
    *COLOR_ENABLED.get_or_init(|| {
@@ -71,8 +87,10 @@ pub fn info(s: &str) -> String {
 /// message.
 pub fn warn(msg: &str) {
    // Clear current line in case spinner is active (stdout, not stderr)
-   print!("\r\x1b[K");
-   io::stdout().flush().ok();
+   if !is_quiet() {
+      print!("\r\x1b[K");
+      io::stdout().flush().ok();
+   }
    eprintln!("{} {}", warning(icons::WARNING), warning(msg));
 }
 
@@ -141,15 +159,21 @@ pub mod box_chars {
 }
 
 /// Wrap text to fit within a given width, preserving words.
-fn wrap_line(line: &str, max_width: usize) -> Vec<String> {
+pub fn wrap_line(line: &str, max_width: usize) -> Vec<String> {
    if line.is_empty() {
       return vec![String::new()];
    }
 
+   wrap_words(line.split_whitespace(), max_width)
+}
+
+/// Wrap `words` to lines of at most `max_width` chars, joining them with
+/// single spaces. A word is never split, so callers decide what a word is.
+pub fn wrap_words<'a>(words: impl IntoIterator<Item = &'a str>, max_width: usize) -> Vec<String> {
    let mut lines = Vec::new();
    let mut current = String::new();
 
-   for word in line.split_whitespace() {
+   for word in words {
       let word_len = word.chars().count();
       let current_len = current.chars().count();
 
@@ -236,6 +260,17 @@ pub fn print_info(msg: &str) {
    }
 }
 
+/// Clear the terminal so a refreshed view replaces the previous output.
+///
+/// No-op when stdout is not a terminal, so piped output stays append-only.
+pub fn clear_screen() {
+   use std::io::IsTerminal;
+   if io::stdout().is_terminal() {
+      print!("\x1b[2J\x1b[H");
+      io::stdout().flush().ok();
+   }
+}
+
 /// Horizontal separator line.
 pub fn separator(width: usize) -> String {
    let line = box_chars::HORIZONTAL.to_string().repeat(width);
@@ -283,6 +318,10 @@ pub fn with_spinner<F, T>(message: &str, f: F) -> T
 where
    F: FnOnce() -> T,
 {
+   if is_quiet() {
+      return f();
+   }
+
    // No spinner if not a TTY or colors disabled
    if !colors_enabled() {
       println!("{message}");
@@ -319,6 +358,10 @@ pub fn with_spinner_result<F, T, E>(message: &str, f: F) -> Result<T, E>
 where
    F: FnOnce() -> Result<T, E>,
 {
+   if is_quiet() {
+      return f();
+   }
+
    if !colors_enabled() {
       println!("{message}");
       return f();
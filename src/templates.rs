@@ -1,13 +1,20 @@
 use std::{
+   collections::{HashMap, HashSet},
    path::{Path, PathBuf},
    sync::LazyLock,
 };
 
+use indexmap::IndexMap;
 use parking_lot::Mutex;
 use rust_embed::RustEmbed;
+use serde::{Deserialize, Serialize};
 use tera::{Context, Tera};
 
-use crate::error::{CommitGenError, Result};
+use crate::{
+   config::{SummaryRules, TemplateConfig, Tense},
+   error::{CommitGenError, Result},
+   style,
+};
 
 /// Rendered prompt split into system and user parts.
 pub struct PromptParts {
@@ -40,6 +47,12 @@ pub struct AnalysisParams<'a> {
    pub common_scopes:     Option<&'a str>,
    pub types_description: Option<&'a str>,
    pub project_context:   Option<&'a str>,
+   /// Share of each kind of change (code/tests/docs/config/deps)
+   pub change_profile:    Option<&'a str>,
+   /// Verb form required for detail items
+   pub body_tense:        Tense,
+   /// Ask for prose paragraphs instead of bullet-style detail items
+   pub body_paragraphs:   bool,
 }
 
 /// Embedded prompts folder (compiled into binary)
@@ -81,6 +94,14 @@ static TERA: LazyLock<Mutex<Tera>> = LazyLock::new(|| {
       {
          eprintln!("Warning: {e}");
       }
+      if let Err(e) = register_directory_templates(&mut tera, &prompts_dir.join("fast"), "fast") {
+         eprintln!("Warning: {e}");
+      }
+      if let Err(e) =
+         register_directory_templates(&mut tera, &prompts_dir.join("classify"), "classify")
+      {
+         eprintln!("Warning: {e}");
+      }
    }
 
    // Register embedded templates that aren't overridden by user-provided files.
@@ -113,6 +134,263 @@ static TERA: LazyLock<Mutex<Tera>> = LazyLock::new(|| {
    Mutex::new(tera)
 });
 
+/// Variants registered at runtime, keyed by `<category>/<variant>`; these
+/// take precedence over prompt files and embedded defaults
+static CUSTOM_TEMPLATES: LazyLock<Mutex<HashMap<String, String>>> =
+   LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Per-run prompt tweaks for A/B testing prompts without editing config or
+/// template files (`--prompt-var`, `--prompt-append-analysis`,
+/// `--prompt-append-summary`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptExperiment {
+   /// Values for `{key}` placeholders in templates
+   #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+   pub vars:            IndexMap<String, String>,
+   /// Lines appended to the analysis prompts (analysis, reduce, fast)
+   #[serde(default, skip_serializing_if = "Vec::is_empty")]
+   pub append_analysis: Vec<String>,
+   /// Lines appended to the summary prompts (summary, fast)
+   #[serde(default, skip_serializing_if = "Vec::is_empty")]
+   pub append_summary:  Vec<String>,
+}
+
+impl PromptExperiment {
+   pub fn is_empty(&self) -> bool {
+      self.vars.is_empty() && self.append_analysis.is_empty() && self.append_summary.is_empty()
+   }
+
+   /// Add a `--prompt-var` value given as `KEY=VALUE`
+   pub fn add_var(&mut self, assignment: &str) -> Result<()> {
+      let (key, value) = assignment
+         .split_once('=')
+         .filter(|(key, _)| !key.is_empty() && key.chars().all(is_placeholder_char))
+         .ok_or_else(|| {
+            CommitGenError::ValidationError(format!(
+               "--prompt-var expects KEY=VALUE with a key of letters, digits, `_`, `-` or `.`, \
+                got '{assignment}'"
+            ))
+         })?;
+      self.vars.insert(key.to_string(), value.to_string());
+      Ok(())
+   }
+
+   /// Lines to append to prompts of `category`
+   fn appends_for(&self, category: &str) -> impl Iterator<Item = &String> {
+      let analysis = matches!(category, "analysis" | "reduce" | "fast");
+      let summary = matches!(category, "summary" | "fast");
+      self
+         .append_analysis
+         .iter()
+         .filter(move |_| analysis)
+         .chain(self.append_summary.iter().filter(move |_| summary))
+   }
+}
+
+/// Experiment applied to every prompt rendered in this process
+static PROMPT_EXPERIMENT: LazyLock<Mutex<PromptExperiment>> =
+   LazyLock::new(|| Mutex::new(PromptExperiment::default()));
+
+/// Templates already warned about for unresolved placeholders, so per-file
+/// map prompts warn once
+static WARNED_UNRESOLVED: LazyLock<Mutex<HashSet<String>>> =
+   LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Use `experiment` for all prompts rendered from now on
+pub fn set_prompt_experiment(experiment: PromptExperiment) {
+   *PROMPT_EXPERIMENT.lock() = experiment;
+}
+
+/// The experiment currently applied to prompts
+pub fn prompt_experiment() -> PromptExperiment {
+   PROMPT_EXPERIMENT.lock().clone()
+}
+
+const fn is_placeholder_char(c: char) -> bool {
+   c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+/// Replace single-brace `{key}` placeholders with their `vars` value. Tera's
+/// `{{ ... }}`, `{% ... %}` and `{# ... #}` are left alone. Returns the
+/// result and the placeholders that had no value, in order of appearance.
+fn substitute_prompt_vars(content: &str, vars: &IndexMap<String, String>) -> (String, Vec<String>) {
+   let mut out = String::with_capacity(content.len());
+   let mut unresolved: Vec<String> = Vec::new();
+   let mut rest = content;
+
+   while let Some(open) = rest.find('{') {
+      let (before, after) = rest.split_at(open);
+      out.push_str(before);
+      let key_len = after[1..]
+         .find(|c: char| !is_placeholder_char(c))
+         .unwrap_or(after.len() - 1);
+      let key = &after[1..=key_len];
+      let is_placeholder = key_len > 0
+         && after[1 + key_len..].starts_with('}')
+         && !after[2 + key_len..].starts_with('}')
+         && !before.ends_with('{');
+
+      if !is_placeholder {
+         // Skip a whole `{{`/`{%` opener so its second brace isn't rescanned
+         let skip = if after[1..].starts_with(['{', '%', '#']) {
+            2
+         } else {
+            1
+         };
+         out.push_str(&after[..skip]);
+         rest = &after[skip..];
+         continue;
+      }
+
+      if let Some(value) = vars.get(key) {
+         out.push_str(value);
+      } else {
+         out.push_str(&after[..key_len + 2]);
+         if !unresolved.iter().any(|k| k == key) {
+            unresolved.push(key.to_string());
+         }
+      }
+      rest = &after[key_len + 2..];
+   }
+   out.push_str(rest);
+   (out, unresolved)
+}
+
+/// Load, substitute and render a prompt template, then append the
+/// experiment's lines for `category` to the user part
+fn render_prompt(
+   category: &str,
+   variant: &str,
+   context: &Context,
+   experiment: &PromptExperiment,
+) -> Result<PromptParts> {
+   let template_content = load_template_file(category, variant)?;
+   let (template_content, unresolved) = substitute_prompt_vars(&template_content, &experiment.vars);
+   if !unresolved.is_empty()
+      && WARNED_UNRESOLVED
+         .lock()
+         .insert(format!("{category}/{variant}"))
+   {
+      style::warn(&format!(
+         "Unresolved placeholders in {category} prompt '{variant}' (set with --prompt-var): {}",
+         unresolved
+            .iter()
+            .map(|key| format!("{{{key}}}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+      ));
+   }
+
+   let rendered = TERA
+      .lock()
+      .render_str(&template_content, context)
+      .map_err(|e| {
+         CommitGenError::Other(format!(
+            "Failed to render {category} prompt template '{variant}': {e}"
+         ))
+      })?;
+
+   let mut parts = split_prompt_parts(&rendered);
+   let appended: Vec<&str> = experiment
+      .appends_for(category)
+      .map(|line| line.trim())
+      .filter(|line| !line.is_empty())
+      .collect();
+   if !appended.is_empty() {
+      if !parts.user.is_empty() {
+         parts.user.push_str("\n\n");
+      }
+      parts.user.push_str(&appended.join("\n"));
+   }
+   Ok(parts)
+}
+
+/// Variables a custom template must output (`{{ name }}`) for its category
+fn required_placeholders(category: &str) -> Option<&'static [&'static str]> {
+   match category {
+      "analysis" => Some(&["stat", "diff", "scope_candidates"]),
+      "summary" => Some(&["details", "stat"]),
+      "changelog" => Some(&["diff"]),
+      "map" => Some(&["filename", "diff"]),
+      "reduce" => Some(&["observations", "stat", "scope_candidates"]),
+      "fast" => Some(&["stat", "diff", "scope_candidates"]),
+      "classify" => Some(&["stat", "scope_candidates"]),
+      _ => None,
+   }
+}
+
+/// Whether `content` outputs `name` in some `{{ ... }}` expression
+fn outputs_variable(content: &str, name: &str) -> bool {
+   let mut rest = content;
+   while let Some(start) = rest.find("{{") {
+      let after = &rest[start + 2..];
+      let Some(end) = after.find("}}") else {
+         return false;
+      };
+      if after[..end]
+         .split(|c: char| !c.is_alphanumeric() && c != '_')
+         .any(|token| token == name)
+      {
+         return true;
+      }
+      rest = &after[end + 2..];
+   }
+   false
+}
+
+/// Register a prompt variant from template text, so `variant` can be selected
+/// like a built-in one (e.g. `analysis_prompt_variant = "<variant>"`).
+///
+/// Fails if the category is unknown, the template doesn't parse, or it never
+/// outputs one of the category's required variables.
+pub fn register_template(category: &str, variant: &str, content: &str) -> Result<()> {
+   let required = required_placeholders(category).ok_or_else(|| {
+      CommitGenError::Other(format!(
+         "Unknown template category '{category}' (expected analysis, summary, changelog, map, \
+          reduce, fast or classify)"
+      ))
+   })?;
+
+   let missing: Vec<&str> = required
+      .iter()
+      .copied()
+      .filter(|name| !outputs_variable(content, name))
+      .collect();
+   if !missing.is_empty() {
+      return Err(CommitGenError::Other(format!(
+         "Template '{category}.{variant}' is missing required placeholders: {}",
+         missing
+            .iter()
+            .map(|name| format!("{{{{ {name} }}}}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+      )));
+   }
+
+   Tera::default()
+      .add_raw_template(variant, content)
+      .map_err(|e| {
+         CommitGenError::Other(format!("Template '{category}.{variant}' is invalid: {e}"))
+      })?;
+
+   CUSTOM_TEMPLATES
+      .lock()
+      .insert(format!("{category}/{variant}"), content.to_string());
+   Ok(())
+}
+
+/// Register every `[templates.<category>.<variant>]` entry from config
+pub fn register_config_templates(
+   templates: &IndexMap<String, IndexMap<String, TemplateConfig>>,
+) -> Result<()> {
+   for (category, variants) in templates {
+      for (variant, template) in variants {
+         register_template(category, variant, &template.text)?;
+      }
+   }
+   Ok(())
+}
+
 /// Determine user prompts directory (~/.llm-git/prompts/) if a home dir exists.
 fn get_user_prompts_dir() -> Option<PathBuf> {
    std::env::var("HOME")
@@ -240,8 +518,16 @@ fn register_directory_templates(tera: &mut Tera, directory: &Path, category: &st
    Ok(())
 }
 
-/// Load template content from file (for dynamic user templates)
+/// Load template content: runtime-registered variant, user file, or embedded
+/// default, in that order
 fn load_template_file(category: &str, variant: &str) -> Result<String> {
+   if let Some(content) = CUSTOM_TEMPLATES
+      .lock()
+      .get(&format!("{category}/{variant}"))
+   {
+      return Ok(content.clone());
+   }
+
    // Prefer user-provided template if available.
    if let Some(prompts_dir) = get_user_prompts_dir() {
       let template_path = prompts_dir.join(category).join(format!("{variant}.md"));
@@ -274,11 +560,25 @@ fn load_template_file(category: &str, variant: &str) -> Result<String> {
    )))
 }
 
+/// Expose a tense as `<prefix>_tense` ("past-tense"/"imperative") and
+/// `<prefix>_imperative` (bool) template variables
+fn insert_tense(context: &mut Context, prefix: &str, tense: Tense) {
+   context.insert(format!("{prefix}_tense"), tense.verb_form());
+   context.insert(format!("{prefix}_imperative"), &(tense == Tense::Imperative));
+}
+
+/// Expose the summary tense plus the `summary_enforce_tense`,
+/// `summary_lowercase` and `summary_no_period` flags, so prompts only ask for
+/// what validation enforces
+fn insert_summary_rules(context: &mut Context, rules: SummaryRules) {
+   insert_tense(context, "summary", rules.tense);
+   context.insert("summary_enforce_tense", &rules.enforce_tense);
+   context.insert("summary_lowercase", &rules.lowercase_start);
+   context.insert("summary_no_period", &rules.no_trailing_period);
+}
+
 /// Render analysis prompt template
 pub fn render_analysis_prompt(p: &AnalysisParams<'_>) -> Result<PromptParts> {
-   // Try to load template dynamically (supports user-added templates)
-   let template_content = load_template_file("analysis", p.variant)?;
-
    // Create context with all the data
    let mut context = Context::new();
    context.insert("stat", p.stat);
@@ -296,20 +596,73 @@ pub fn render_analysis_prompt(p: &AnalysisParams<'_>) -> Result<PromptParts> {
    if let Some(ctx) = p.project_context {
       context.insert("project_context", ctx);
    }
+   if let Some(profile) = p.change_profile {
+      context.insert("change_profile", profile);
+   }
+   insert_tense(&mut context, "body", p.body_tense);
+   context.insert("body_paragraphs", &p.body_paragraphs);
 
-   // Render using render_str for dynamic templates
-   let mut tera = TERA.lock();
+   render_prompt("analysis", p.variant, &context, &prompt_experiment())
+}
 
-   let rendered = tera.render_str(&template_content, &context).map_err(|e| {
-      CommitGenError::Other(format!(
-         "Failed to render analysis prompt template '{}': {e}",
-         p.variant
-      ))
-   })?;
-   Ok(split_prompt_parts(&rendered))
+/// Render the combined single-call prompt used by fast mode (analysis and
+/// summary at once)
+pub fn render_fast_prompt(
+   p: &AnalysisParams<'_>,
+   summary_chars: &str,
+   summary_rules: SummaryRules,
+) -> Result<PromptParts> {
+   let mut context = Context::new();
+   context.insert("stat", p.stat);
+   context.insert("diff", p.diff);
+   context.insert("scope_candidates", p.scope_candidates);
+   if let Some(commits) = p.recent_commits {
+      context.insert("recent_commits", commits);
+   }
+   if let Some(scopes) = p.common_scopes {
+      context.insert("common_scopes", scopes);
+   }
+   if let Some(types) = p.types_description {
+      context.insert("types_description", types);
+   }
+   if let Some(ctx) = p.project_context {
+      context.insert("project_context", ctx);
+   }
+   if let Some(profile) = p.change_profile {
+      context.insert("change_profile", profile);
+   }
+   context.insert("chars", summary_chars);
+   insert_summary_rules(&mut context, summary_rules);
+   insert_tense(&mut context, "body", p.body_tense);
+   context.insert("body_paragraphs", &p.body_paragraphs);
+
+   render_prompt("fast", p.variant, &context, &prompt_experiment())
+}
+
+/// Render the stat-only type/scope classification prompt used when
+/// `split_type_and_body` is enabled (`p.diff` is ignored)
+pub fn render_classify_prompt(p: &AnalysisParams<'_>) -> Result<PromptParts> {
+   let mut context = Context::new();
+   context.insert("stat", p.stat);
+   context.insert("scope_candidates", p.scope_candidates);
+   if let Some(scopes) = p.common_scopes {
+      context.insert("common_scopes", scopes);
+   }
+   if let Some(types) = p.types_description {
+      context.insert("types_description", types);
+   }
+   if let Some(ctx) = p.project_context {
+      context.insert("project_context", ctx);
+   }
+   if let Some(profile) = p.change_profile {
+      context.insert("change_profile", profile);
+   }
+
+   render_prompt("classify", p.variant, &context, &prompt_experiment())
 }
 
 /// Render summary prompt template
+#[allow(clippy::too_many_arguments, reason = "mirrors the summary template variables")]
 pub fn render_summary_prompt(
    variant: &str,
    commit_type: &str,
@@ -318,10 +671,9 @@ pub fn render_summary_prompt(
    details: &str,
    stat: &str,
    user_context: Option<&str>,
+   change_weights: Option<&str>,
+   rules: SummaryRules,
 ) -> Result<PromptParts> {
-   // Try to load template dynamically (supports user-added templates)
-   let template_content = load_template_file("summary", variant)?;
-
    // Create context with all the data
    let mut context = Context::new();
    context.insert("commit_type", commit_type);
@@ -332,13 +684,12 @@ pub fn render_summary_prompt(
    if let Some(ctx) = user_context {
       context.insert("user_context", ctx);
    }
+   if let Some(weights) = change_weights {
+      context.insert("change_weights", weights);
+   }
+   insert_summary_rules(&mut context, rules);
 
-   // Render using render_str for dynamic templates
-   let mut tera = TERA.lock();
-   let rendered = tera.render_str(&template_content, &context).map_err(|e| {
-      CommitGenError::Other(format!("Failed to render summary prompt template '{variant}': {e}"))
-   })?;
-   Ok(split_prompt_parts(&rendered))
+   render_prompt("summary", variant, &context, &prompt_experiment())
 }
 
 /// Render changelog prompt template
@@ -350,9 +701,6 @@ pub fn render_changelog_prompt(
    diff: &str,
    existing_entries: Option<&str>,
 ) -> Result<PromptParts> {
-   // Try to load template dynamically (supports user-added templates)
-   let template_content = load_template_file("changelog", variant)?;
-
    // Create context with all the data
    let mut context = Context::new();
    context.insert("changelog_path", changelog_path);
@@ -363,12 +711,7 @@ pub fn render_changelog_prompt(
       context.insert("existing_entries", entries);
    }
 
-   // Render using render_str for dynamic templates
-   let mut tera = TERA.lock();
-   let rendered = tera.render_str(&template_content, &context).map_err(|e| {
-      CommitGenError::Other(format!("Failed to render changelog prompt template '{variant}': {e}"))
-   })?;
-   Ok(split_prompt_parts(&rendered))
+   render_prompt("changelog", variant, &context, &prompt_experiment())
 }
 
 /// Render map prompt template (per-file observation extraction)
@@ -378,8 +721,6 @@ pub fn render_map_prompt(
    diff: &str,
    context_header: &str,
 ) -> Result<PromptParts> {
-   let template_content = load_template_file("map", variant)?;
-
    let mut context = Context::new();
    context.insert("filename", filename);
    context.insert("diff", diff);
@@ -387,34 +728,215 @@ pub fn render_map_prompt(
       context.insert("context_header", context_header);
    }
 
-   let mut tera = TERA.lock();
-   let rendered = tera.render_str(&template_content, &context).map_err(|e| {
-      CommitGenError::Other(format!("Failed to render map prompt template '{variant}': {e}"))
-   })?;
-   Ok(split_prompt_parts(&rendered))
+   render_prompt("map", variant, &context, &prompt_experiment())
 }
 
 /// Render reduce prompt template (synthesis from observations)
+#[allow(clippy::too_many_arguments, reason = "mirrors the reduce template variables")]
 pub fn render_reduce_prompt(
    variant: &str,
    observations: &str,
    stat: &str,
    scope_candidates: &str,
+   change_profile: Option<&str>,
    types_description: Option<&str>,
+   body_tense: Tense,
+   sample_note: Option<&str>,
 ) -> Result<PromptParts> {
-   let template_content = load_template_file("reduce", variant)?;
-
    let mut context = Context::new();
    context.insert("observations", observations);
    context.insert("stat", stat);
    context.insert("scope_candidates", scope_candidates);
+   if let Some(profile) = change_profile {
+      context.insert("change_profile", profile);
+   }
    if let Some(types_desc) = types_description {
       context.insert("types_description", types_desc);
    }
+   insert_tense(&mut context, "body", body_tense);
+   if let Some(note) = sample_note {
+      context.insert("sample_note", note);
+   }
 
-   let mut tera = TERA.lock();
-   let rendered = tera.render_str(&template_content, &context).map_err(|e| {
-      CommitGenError::Other(format!("Failed to render reduce prompt template '{variant}': {e}"))
-   })?;
-   Ok(split_prompt_parts(&rendered))
+   render_prompt("reduce", variant, &context, &prompt_experiment())
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   const TERSE: &str = "Classify this diff.\n======USER=======\n{{ stat }}\nScopes: {{ \
+                        scope_candidates | default(value=\"none\") }}\n{{ diff }}";
+
+   #[test]
+   fn test_register_and_render_custom_analysis_variant() {
+      register_template("analysis", "test-terse", TERSE).unwrap();
+
+      let parts = render_analysis_prompt(&AnalysisParams {
+         variant: "test-terse",
+         stat: " lib.rs | 2 +-",
+         diff: "+fn a() {}",
+         scope_candidates: "lib (100%)",
+         ..Default::default()
+      })
+      .unwrap();
+      assert_eq!(parts.system, "Classify this diff.");
+      assert_eq!(parts.user, "lib.rs | 2 +-\nScopes: lib (100%)\n+fn a() {}");
+   }
+
+   #[test]
+   fn test_register_config_templates_rejects_missing_placeholders() {
+      let config: crate::config::CommitConfig = toml::from_str(
+         "[templates.analysis.test-nodiff]\ntext = \"{{ stat }} {% if diff %}has diff{% endif \
+          %}\"\n",
+      )
+      .unwrap();
+      let err = register_config_templates(&config.templates).unwrap_err();
+      assert!(
+         err.to_string()
+            .contains("{{ diff }}, {{ scope_candidates }}"),
+         "{err}"
+      );
+      assert!(load_template_file("analysis", "test-nodiff").is_err());
+   }
+
+   #[test]
+   fn test_register_template_rejects_unknown_category_and_bad_syntax() {
+      assert!(register_template("commit", "x", "{{ diff }}").is_err());
+      assert!(
+         register_template("map", "test-broken", "{{ filename }} {{ diff }} {% if %}").is_err()
+      );
+   }
+
+   fn experiment(vars: &[(&str, &str)], analysis: &[&str], summary: &[&str]) -> PromptExperiment {
+      PromptExperiment {
+         vars:            vars
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect(),
+         append_analysis: analysis.iter().map(|s| (*s).to_string()).collect(),
+         append_summary:  summary.iter().map(|s| (*s).to_string()).collect(),
+      }
+   }
+
+   #[test]
+   fn test_prompt_vars_substituted_before_rendering() {
+      register_template(
+         "analysis",
+         "test-vars",
+         "Be {tone}. Cap at {max_items} items.\n======USER=======\n{{ stat }} {{ scope_candidates \
+          }}\n{% if diff %}{{ diff }}{% endif %}\n{json}",
+      )
+      .unwrap();
+      let mut context = Context::new();
+      context.insert("stat", "a.rs | 1 +");
+      context.insert("scope_candidates", "core");
+      context.insert("diff", "+fn a() {}");
+
+      let exp = experiment(&[("tone", "terse"), ("max_items", "3"), ("json", "{}")], &[], &[]);
+      let parts = render_prompt("analysis", "test-vars", &context, &exp).unwrap();
+      assert_eq!(parts.system, "Be terse. Cap at 3 items.");
+      // Tera syntax is untouched, and substituted values aren't rescanned
+      assert_eq!(parts.user, "a.rs | 1 + core\n+fn a() {}\n{}");
+   }
+
+   #[test]
+   fn test_unresolved_prompt_vars_are_reported() {
+      let vars = experiment(&[("tone", "terse")], &[], &[]).vars;
+      let (out, unresolved) = substitute_prompt_vars(
+         "{tone} {missing} {{ stat }} {%raw%}{x}{%endraw%} {missing} {not a key} {}",
+         &vars,
+      );
+      assert_eq!(out, "terse {missing} {{ stat }} {%raw%}{x}{%endraw%} {missing} {not a key} {}");
+      assert_eq!(unresolved, vec!["missing".to_string(), "x".to_string()]);
+
+      // Rendering still succeeds, leaving the placeholder in place
+      register_template("summary", "test-unresolved", "{{ details }} {{ stat }} {oops}").unwrap();
+      let mut context = Context::new();
+      context.insert("details", "d");
+      context.insert("stat", "s");
+      let parts =
+         render_prompt("summary", "test-unresolved", &context, &PromptExperiment::default())
+            .unwrap();
+      assert_eq!(parts.user, "d s {oops}");
+   }
+
+   #[test]
+   fn test_prompt_appends_go_to_their_prompts() {
+      register_template(
+         "summary",
+         "test-append",
+         "System\n======USER=======\n{{ details }} {{ stat }}",
+      )
+      .unwrap();
+      let mut context = Context::new();
+      context.insert("details", "d");
+      context.insert("stat", "s");
+      let exp = experiment(&[], &["Prefer feat."], &["Keep it short.", "  ", "No scope."]);
+
+      let parts = render_prompt("summary", "test-append", &context, &exp).unwrap();
+      assert_eq!(parts.system, "System");
+      assert_eq!(parts.user, "d s\n\nKeep it short.\nNo scope.");
+
+      assert_eq!(exp.appends_for("analysis").collect::<Vec<_>>(), ["Prefer feat."]);
+      assert_eq!(exp.appends_for("fast").count(), 4);
+      assert_eq!(exp.appends_for("map").count(), 0);
+   }
+
+   #[test]
+   fn test_prompt_var_parsing() {
+      let mut exp = PromptExperiment::default();
+      exp.add_var("tone=terse = very").unwrap();
+      exp.add_var("empty=").unwrap();
+      assert_eq!(exp.vars["tone"], "terse = very");
+      assert_eq!(exp.vars["empty"], "");
+      assert!(exp.add_var("novalue").is_err());
+      assert!(exp.add_var("=x").is_err());
+      assert!(exp.add_var("bad key=x").is_err());
+   }
+
+   #[test]
+   fn test_outputs_variable() {
+      assert!(outputs_variable("a {{diff}} b", "diff"));
+      assert!(outputs_variable("{{ stat | trim }}", "stat"));
+      assert!(!outputs_variable("{{ diffstat }}", "diff"));
+      assert!(!outputs_variable("{% if diff %}{% endif %}", "diff"));
+   }
+
+   #[test]
+   fn test_summary_prompt_follows_rules() {
+      let render = |rules| {
+         let parts = render_summary_prompt(
+            "default",
+            "feat",
+            "",
+            "72",
+            "- Added x.",
+            "a.rs | 1 +",
+            None,
+            None,
+            rules,
+         )
+         .unwrap();
+         format!("{}\n{}", parts.system, parts.user)
+      };
+      let enforced = SummaryRules {
+         tense:              Tense::Past,
+         enforce_tense:      true,
+         lowercase_start:    true,
+         no_trailing_period: true,
+      };
+      let prompt = render(enforced);
+      assert!(prompt.contains("Start with lowercase past-tense verb"), "{prompt}");
+      assert!(prompt.contains("no trailing period"), "{prompt}");
+
+      let prompt =
+         render(SummaryRules { enforce_tense: false, lowercase_start: false, ..enforced });
+      assert!(prompt.contains("Start with verb (must differ"), "{prompt}");
+      assert!(prompt.contains("no trailing period"), "{prompt}");
+
+      let prompt = render(SummaryRules { no_trailing_period: false, ..enforced });
+      assert!(!prompt.contains("no trailing period"), "{prompt}");
+      assert!(prompt.contains("lowercase past-tense verb"), "{prompt}");
+   }
 }
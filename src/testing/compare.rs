@@ -113,6 +113,7 @@ mod tests {
          scope:       Some(Scope::new("api").unwrap()),
          details:     vec![],
          issue_refs:  vec![],
+         rationale:   None,
       };
       let actual = golden.clone();
 
@@ -129,12 +130,14 @@ mod tests {
          scope:       None,
          details:     vec![],
          issue_refs:  vec![],
+         rationale:   None,
       };
       let actual = ConventionalAnalysis {
          commit_type: CommitType::new("fix").unwrap(),
          scope:       None,
          details:     vec![],
          issue_refs:  vec![],
+         rationale:   None,
       };
 
       let result = compare_analysis(&golden, &actual);
@@ -149,12 +152,14 @@ mod tests {
          scope:       Some(Scope::new("api").unwrap()),
          details:     vec![],
          issue_refs:  vec![],
+         rationale:   None,
       };
       let actual = ConventionalAnalysis {
          commit_type: CommitType::new("feat").unwrap(),
          scope:       Some(Scope::new("api/client").unwrap()),
          details:     vec![],
          issue_refs:  vec![],
+         rationale:   None,
       };
 
       let result = compare_analysis(&golden, &actual);
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
    error::{CommitGenError, Result},
+   templates::PromptExperiment,
    types::ConventionalAnalysis,
 };
 
@@ -58,16 +59,19 @@ impl Manifest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FixtureMeta {
    /// Source repository (e.g., "tetra")
-   pub source_repo:   String,
+   pub source_repo:       String,
    /// Original commit hash
-   pub source_commit: String,
+   pub source_commit:     String,
    /// Why this fixture is interesting
-   pub description:   String,
+   pub description:       String,
    /// When this fixture was captured
-   pub captured_at:   String,
+   pub captured_at:       String,
    /// Tags for categorization
    #[serde(default)]
-   pub tags:          Vec<String>,
+   pub tags:              Vec<String>,
+   /// Prompt experiment the golden output was generated under
+   #[serde(default, skip_serializing_if = "PromptExperiment::is_empty")]
+   pub prompt_experiment: PromptExperiment,
 }
 
 /// Context captured for analysis (replaces live git queries)
@@ -82,6 +82,7 @@ impl TestRunner {
                scope:       None,
                details:     vec![],
                issue_refs:  vec![],
+               rationale:   None,
             },
             final_message: String::new(),
             error:         Some(e.to_string()),
@@ -99,6 +100,7 @@ impl TestRunner {
          recent_commits:  fixture.input.context.recent_commits.as_deref(),
          common_scopes:   fixture.input.context.common_scopes.as_deref(),
          project_context: fixture.input.context.project_context.as_deref(),
+         change_profile:  None,
          debug_output:    None,
          debug_prefix:    None,
       };
@@ -118,22 +120,27 @@ impl TestRunner {
       let detail_points = analysis.body_texts();
       let summary = crate::api::generate_summary_from_analysis(
          &fixture.input.stat,
+         &crate::analysis::classify_change_profile(&fixture.input.diff),
          analysis.commit_type.as_str(),
          analysis.scope.as_ref().map(|s| s.as_str()),
          &detail_points,
          fixture.input.context.user_context.as_deref(),
+         None,
          &self.config,
          None,
          None,
       )
-      .unwrap_or_else(|_| {
-         crate::api::fallback_summary(
-            &fixture.input.stat,
-            &detail_points,
-            analysis.commit_type.as_str(),
-            &self.config,
-         )
-      });
+      .map_or_else(
+         |_| {
+            crate::api::fallback_summary(
+               &fixture.input.stat,
+               &detail_points,
+               analysis.commit_type.as_str(),
+               &self.config,
+            )
+         },
+         |generated| generated.summary,
+      );
 
       let final_commit = ConventionalCommit {
          commit_type: analysis.commit_type.clone(),
@@ -142,7 +149,7 @@ impl TestRunner {
          body: detail_points,
          footers: vec![],
       };
-      let final_message = format_commit_message(&final_commit);
+      let final_message = format_commit_message(&final_commit, self.config.body_style);
 
       // Compare to golden if exists
       let comparison = fixture
@@ -184,6 +191,7 @@ impl TestRunner {
 
       let mut fixture = Fixture::load(&self.fixtures_dir, name)?;
       fixture.update_golden(result.analysis, result.final_message);
+      fixture.meta.prompt_experiment = crate::templates::prompt_experiment();
       fixture.save(&self.fixtures_dir)?;
 
       Ok(())
@@ -5,7 +5,10 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::error::{CommitGenError, Result};
+use crate::{
+   config::{CommitConfig, LengthMetric},
+   error::{CommitGenError, Result},
+};
 
 // === Commit type configuration ===
 
@@ -30,6 +33,12 @@ pub struct TypeConfig {
    /// Per-type hint for classification guidance
    #[serde(default)]
    pub hint: String,
+
+   /// Summary opening verbs accepted for this type even when the verb-form or
+   /// type-repetition check wouldn't recognize them (`ci` → "configured");
+   /// the first one in the configured tense opens fallback summaries
+   #[serde(default)]
+   pub leading_verbs: Vec<String>,
 }
 
 /// Match rules for mapping commits to changelog categories
@@ -156,11 +165,17 @@ pub fn default_types() -> IndexMap<String, TypeConfig> {
             "package.json".to_string(),
             "Makefile".to_string(),
          ],
+         leading_verbs: ["bumped", "pinned", "bump", "pin"]
+            .map(String::from)
+            .to_vec(),
          ..Default::default()
       }),
       ("ci".to_string(), TypeConfig {
          description: "CI/CD configuration".to_string(),
          file_patterns: vec![".github/workflows/".to_string(), ".gitlab-ci.yml".to_string()],
+         leading_verbs: ["configured", "updated", "configure", "update"]
+            .map(String::from)
+            .to_vec(),
          ..Default::default()
       }),
       ("revert".to_string(), TypeConfig {
@@ -347,51 +362,67 @@ pub enum Mode {
    Commit,
    /// Analyze unstaged changes
    Unstaged,
+   /// Analyze the conflict resolution of an in-progress merge, then complete
+   /// it
+   Merge,
    /// Compose changes into multiple commits
    Compose,
 }
 
+/// Short model aliases and the full `LiteLLM` model names they resolve to
+pub const MODEL_ALIASES: &[(&str, &str)] = &[
+   // Claude short names
+   ("sonnet", "claude-sonnet-4.5"),
+   ("s", "claude-sonnet-4.5"),
+   ("opus", "claude-opus-4.5"),
+   ("o", "claude-opus-4.5"),
+   ("o4.5", "claude-opus-4.5"),
+   ("haiku", "claude-haiku-4-5"),
+   ("h", "claude-haiku-4-5"),
+   ("3.5", "claude-3.5-sonnet"),
+   ("sonnet-3.5", "claude-3.5-sonnet"),
+   ("3.7", "claude-3.7-sonnet"),
+   ("sonnet-3.7", "claude-3.7-sonnet"),
+   // GPT short names
+   ("gpt5", "gpt-5"),
+   ("g5", "gpt-5"),
+   ("gpt5-pro", "gpt-5-pro"),
+   ("gpt5-mini", "gpt-5-mini"),
+   ("gpt5-codex", "gpt-5-codex"),
+   // Gemini short names
+   ("gemini", "gemini-2.5-pro"),
+   ("g2.5", "gemini-2.5-pro"),
+   ("flash", "gemini-2.5-flash"),
+   ("g2.5-flash", "gemini-2.5-flash"),
+   ("flash-lite", "gemini-2.5-flash-lite"),
+   // Cerebras
+   ("qwen", "qwen-3-coder-480b"),
+   ("q480b", "qwen-3-coder-480b"),
+   // GLM models
+   ("glm4.6", "glm-4.6"),
+   ("glm4.5", "glm-4.5"),
+   ("glm-air", "glm-4.5-air"),
+];
+
 /// Resolve model name from short aliases to full `LiteLLM` model names
+///
+/// Names that aren't an alias (full model names, o-series names like `o3`)
+/// pass through as-is.
 pub fn resolve_model_name(name: &str) -> String {
-   match name {
-      // Claude short names
-      "sonnet" | "s" => "claude-sonnet-4.5",
-      "opus" | "o" | "o4.5" => "claude-opus-4.5",
-      "haiku" | "h" => "claude-haiku-4-5",
-      "3.5" | "sonnet-3.5" => "claude-3.5-sonnet",
-      "3.7" | "sonnet-3.7" => "claude-3.7-sonnet",
-
-      // GPT short names
-      "gpt5" | "g5" => "gpt-5",
-      "gpt5-pro" => "gpt-5-pro",
-      "gpt5-mini" => "gpt-5-mini",
-      "gpt5-codex" => "gpt-5-codex",
-
-      // o-series short names
-      "o3" => "o3",
-      "o3-pro" => "o3-pro",
-      "o3-mini" => "o3-mini",
-      "o1" => "o1",
-      "o1-pro" => "o1-pro",
-      "o1-mini" => "o1-mini",
-
-      // Gemini short names
-      "gemini" | "g2.5" => "gemini-2.5-pro",
-      "flash" | "g2.5-flash" => "gemini-2.5-flash",
-      "flash-lite" => "gemini-2.5-flash-lite",
-
-      // Cerebras
-      "qwen" | "q480b" => "qwen-3-coder-480b",
-
-      // GLM models
-      "glm4.6" => "glm-4.6",
-      "glm4.5" => "glm-4.5",
-      "glm-air" => "glm-4.5-air",
+   MODEL_ALIASES
+      .iter()
+      .find(|(alias, _)| *alias == name)
+      .map_or(name, |(_, model)| model)
+      .to_string()
+}
 
-      // Otherwise pass through as-is (allows full model names)
-      _ => name,
-   }
-   .to_string()
+/// Aliases in [`MODEL_ALIASES`] that resolve to `model`
+pub fn model_aliases_for(model: &str) -> Vec<&'static str> {
+   MODEL_ALIASES
+      .iter()
+      .filter(|(_, target)| *target == model)
+      .map(|(alias, _)| *alias)
+      .collect()
 }
 
 /// Scope candidate with metadata for inference
@@ -483,16 +514,34 @@ impl CommitSummary {
    /// Creates new `CommitSummary` with strict length validation and format
    /// warnings
    pub fn new(s: impl Into<String>, max_len: usize) -> Result<Self> {
-      Self::new_impl(s, max_len, true)
+      Self::new_impl(s, max_len, LengthMetric::default(), true, true)
+   }
+
+   /// Like [`CommitSummary::new`] with `summary_hard_limit`, warning only
+   /// about the style rules `config` enforces
+   pub fn for_config(s: impl Into<String>, config: &CommitConfig) -> Result<Self> {
+      Self::new_impl(
+         s,
+         config.summary_hard_limit,
+         config.summary_length_metric,
+         config.enforce_lowercase_start,
+         config.enforce_no_trailing_period,
+      )
    }
 
    /// Internal constructor allowing warning suppression (used by
    /// post-processing)
    pub(crate) fn new_unchecked(s: impl Into<String>, max_len: usize) -> Result<Self> {
-      Self::new_impl(s, max_len, false)
+      Self::new_impl(s, max_len, LengthMetric::default(), false, false)
    }
 
-   fn new_impl(s: impl Into<String>, max_len: usize, emit_warnings: bool) -> Result<Self> {
+   fn new_impl(
+      s: impl Into<String>,
+      max_len: usize,
+      metric: LengthMetric,
+      warn_uppercase: bool,
+      warn_period: bool,
+   ) -> Result<Self> {
       let s = s.into();
 
       // Strict validation: must not be empty
@@ -500,25 +549,25 @@ impl CommitSummary {
          return Err(CommitGenError::ValidationError("commit summary cannot be empty".to_string()));
       }
 
-      // Strict validation: must be ≤ max_len characters (hard limit from config)
-      if s.len() > max_len {
-         return Err(CommitGenError::SummaryTooLong { len: s.len(), max: max_len });
+      // Strict validation: must be ≤ max_len (hard limit from config)
+      let len = metric.measure(&s);
+      if len > max_len {
+         return Err(CommitGenError::SummaryTooLong { len, max: max_len });
       }
 
-      if emit_warnings {
-         // Warning-only: should start with lowercase
-         if let Some(first_char) = s.chars().next()
-            && first_char.is_uppercase()
-         {
-            crate::style::warn(&format!("commit summary should start with lowercase: {s}"));
-         }
+      // Warning-only: should start with lowercase
+      if warn_uppercase
+         && let Some(first_char) = s.chars().next()
+         && first_char.is_uppercase()
+      {
+         crate::style::warn(&format!("commit summary should start with lowercase: {s}"));
+      }
 
-         // Warning-only: should NOT end with period (conventional commits style)
-         if s.trim_end().ends_with('.') {
-            crate::style::warn(&format!(
-               "commit summary should NOT end with period (conventional commits style): {s}"
-            ));
-         }
+      // Warning-only: should NOT end with period (conventional commits style)
+      if warn_period && s.trim_end().ends_with('.') {
+         crate::style::warn(&format!(
+            "commit summary should NOT end with period (conventional commits style): {s}"
+         ));
       }
 
       Ok(Self(s))
@@ -529,9 +578,9 @@ impl CommitSummary {
       &self.0
    }
 
-   /// Returns length of summary
-   pub const fn len(&self) -> usize {
-      self.0.len()
+   /// Returns length of summary in characters
+   pub fn len(&self) -> usize {
+      self.0.chars().count()
    }
 
    /// Checks if summary is empty
@@ -572,10 +621,10 @@ impl<'de> Deserialize<'de> for CommitSummary {
       if s.trim().is_empty() {
          return Err(serde::de::Error::custom("commit summary cannot be empty"));
       }
-      if s.len() > 128 {
+      let len = s.chars().count();
+      if len > 128 {
          return Err(serde::de::Error::custom(format!(
-            "commit summary must be ≤128 characters, got {}",
-            s.len()
+            "commit summary must be ≤128 characters, got {len}"
          )));
       }
       Ok(Self(s))
@@ -710,6 +759,10 @@ pub struct ConventionalAnalysis {
    pub details:     Vec<AnalysisDetail>,
    #[serde(default, deserialize_with = "deserialize_string_vec")]
    pub issue_refs:  Vec<String>,
+   /// Why this type and scope fit, only requested with `--explain`; never
+   /// part of the message
+   #[serde(default, skip_serializing_if = "Option::is_none")]
+   pub rationale:   Option<String>,
 }
 
 impl ConventionalAnalysis {
@@ -767,6 +820,16 @@ pub enum HunkSelector {
    Search { pattern: String },
 }
 
+impl fmt::Display for HunkSelector {
+   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      match self {
+         Self::All => write!(f, "all"),
+         Self::Lines { start, end } => write!(f, "lines {start}-{end}"),
+         Self::Search { pattern } => write!(f, "search '{pattern}'"),
+      }
+   }
+}
+
 impl Serialize for HunkSelector {
    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
    where
@@ -911,10 +974,16 @@ pub struct Args {
    #[arg(long, value_enum, default_value = "staged")]
    pub mode: Mode,
 
-   /// Commit hash/ref when using --mode=commit
+   /// Commit hash/ref when using --mode=commit (a range such as `main..HEAD`
+   /// analyzes the combined diff)
    #[arg(long)]
    pub target: Option<String>,
 
+   /// Build the body from the subjects of the commits in a `--target` range
+   /// instead of the analysis (summary still comes from the combined diff)
+   #[arg(long, requires = "target")]
+   pub body_from_commits: bool,
+
    /// Copy the message to clipboard
    #[arg(long)]
    pub copy: bool,
@@ -923,7 +992,17 @@ pub struct Args {
    #[arg(long)]
    pub dry_run: bool,
 
-   /// Push changes after committing
+   /// Suppress status output (spinners, progress lines); print only the
+   /// message
+   #[arg(long, short = 'q')]
+   pub quiet: bool,
+
+   /// Print only the subject line (`type(scope): summary`); implies --dry-run
+   /// and --quiet
+   #[arg(long, conflicts_with_all = ["push", "rewrite", "compose", "test", "watch"])]
+   pub subject_only: bool,
+
+   /// Push changes after committing (with the release tag, if one was created)
    #[arg(long, short = 'p')]
    pub push: bool,
 
@@ -940,6 +1019,10 @@ pub struct Args {
    #[arg(long, short = 't')]
    pub temperature: Option<f32>,
 
+   /// API base URL, overriding `api_base_url` and `LLM_GIT_API_URL`
+   #[arg(long, value_name = "URL")]
+   pub base_url: Option<String>,
+
    /// Issue numbers this commit fixes (e.g., --fixes 123 456)
    #[arg(long)]
    pub fixes: Vec<String>,
@@ -960,6 +1043,11 @@ pub struct Args {
    #[arg(long)]
    pub breaking: bool,
 
+   /// Strip all footers (issue refs, BREAKING CHANGE, trailers) from the
+   /// message
+   #[arg(long)]
+   pub no_footers: bool,
+
    /// GPG sign the commit (equivalent to git commit -S)
    #[arg(long, short = 'S')]
    pub sign: bool,
@@ -973,15 +1061,149 @@ pub struct Args {
    #[arg(long, short = 'n')]
    pub skip_hooks: bool,
 
+   /// Commit date override, sets both `GIT_COMMITTER_DATE` and
+   /// `GIT_AUTHOR_DATE` (ISO 8601, e.g. 2024-01-01T12:00:00)
+   #[arg(long, conflicts_with = "rewrite")]
+   pub commit_date: Option<String>,
+
+   /// Author date override, sets `GIT_AUTHOR_DATE` (takes precedence over
+   /// --commit-date for the author)
+   #[arg(long, conflicts_with = "rewrite")]
+   pub author_date: Option<String>,
+
+   /// Never run `git add -A` when nothing is staged; fail instead
+   #[arg(long)]
+   pub no_stage: bool,
+
+   /// Don't tag a detected release commit, even with `release_tag = true`
+   #[arg(long)]
+   pub no_tag: bool,
+
+   /// Print the model's reasoning for the chosen type and scope to stderr
+   /// (never added to the message)
+   #[arg(long)]
+   pub explain: bool,
+
+   /// Always use the two-call analysis + summary pipeline, even for small
+   /// diffs
+   #[arg(long)]
+   pub no_fast: bool,
+
+   /// Re-run with a randomly jittered temperature (within
+   /// `temperature_jitter`) to get a different message; also enabled by
+   /// `LLM_GIT_REGENERATE=1`
+   #[arg(long)]
+   pub regenerate: bool,
+
+   /// With nothing to commit, generate the message from the context text
+   /// alone and commit with `git commit --allow-empty`
+   #[arg(long, conflicts_with_all = ["compose", "rewrite", "watch"])]
+   pub allow_empty: bool,
+
+   /// Accept a generic summary with no body (e.g. "updated files") instead of
+   /// failing after one more specific attempt
+   #[arg(long)]
+   pub allow_generic: bool,
+
+   /// Commit even if the final message still contains template placeholders
+   /// (`<scope>`, `{summary}`, an item starting with `TODO:` or `XXX:`)
+   #[arg(long)]
+   pub allow_placeholders: bool,
+
+   /// Print the diff with identifiers and string literals replaced by
+   /// placeholders, for sharing in bug reports (no API calls, never commits)
+   #[arg(long, conflicts_with_all = ["compose", "rewrite", "watch"])]
+   pub anonymize: bool,
+
+   /// Print the analysis and summary prompts rendered from the real diff and
+   /// context, then exit (no API calls, never commits)
+   #[arg(long, conflicts_with_all = ["compose", "rewrite", "watch", "anonymize"])]
+   pub template_debug: bool,
+
+   /// Commit even when the message's confidence is below
+   /// `auto_commit_min_confidence`, and run a compose plan without asking
+   #[arg(long, short = 'y')]
+   pub yes: bool,
+
+   /// Commit in staged mode even on a branch listed in `protected_branches`
+   #[arg(long)]
+   pub allow_protected: bool,
+
+   /// Watch the index and regenerate a preview whenever the staged set changes
+   /// (never commits)
+   #[arg(long, conflicts_with_all = ["target", "rewrite", "compose", "push"])]
+   pub watch: bool,
+
    /// Path to config file (default: ~/.config/llm-git/config.toml)
    #[arg(long)]
    pub config: Option<PathBuf>,
 
+   /// Don't apply the config's `[cli_defaults]` flag values
+   #[arg(long)]
+   pub ignore_cli_defaults: bool,
+
+   /// Strictly check the config file (deprecated and unknown keys) and exit
+   #[arg(long)]
+   pub check_config: bool,
+
+   /// Write a commented config with every key at its default to the config
+   /// path and exit
+   #[arg(long, conflicts_with = "check_config")]
+   pub init_config: bool,
+
+   /// List the models the endpoint offers (with their local aliases), flag
+   /// configured models it doesn't, and exit
+   #[arg(long)]
+   pub model_list: bool,
+
+   /// Let `--init-config` overwrite an existing config file, and staged mode
+   /// commit on a protected branch
+   #[arg(long)]
+   pub force: bool,
+
+   /// Print how often each scope was used in recent history (canonicalized
+   /// as for the analysis prompt) and exit
+   #[arg(long)]
+   pub scopes: bool,
+
+   /// Commits of history `--scopes` looks at (default: `scope_history_depth`)
+   #[arg(long, value_name = "N", requires = "scopes")]
+   pub depth: Option<usize>,
+
+   /// Print the `--scopes` table as JSON
+   #[arg(long, requires = "scopes")]
+   pub json: bool,
+
+   /// Add a named snippet from the config's `context_presets` to the context
+   /// (repeatable)
+   #[arg(long = "context-preset", value_name = "NAME")]
+   pub context_preset: Vec<String>,
+
+   /// GitHub issue or pull request whose title and body join the context, and
+   /// whose referenced issues become footers (needs `github_token`)
+   #[arg(long = "context-url", value_name = "URL")]
+   pub context_url: Option<String>,
+
+   /// Use this commit type instead of the model's classification; scope,
+   /// body and summary are still generated to match it
+   #[arg(long, value_name = "TYPE", conflicts_with_all = ["rewrite", "compose"])]
+   pub force_type: Option<String>,
+
+   /// Use this summary as written (after normalization and length checks)
+   /// and only generate the body
+   #[arg(long, value_name = "TEXT", conflicts_with_all = ["rewrite", "compose"])]
+   pub summary: Option<String>,
+
    /// Additional context to provide to the analysis model (all trailing
    /// non-flag text)
    #[arg(trailing_var_arg = true)]
    pub context: Vec<String>,
 
+   /// Regenerate the messages of unpushed commits (`@{upstream}..HEAD`) and
+   /// rewrite them in place; with --dry-run only prints the new messages
+   #[arg(long, conflicts_with_all = ["target", "rewrite", "compose", "watch", "push"])]
+   pub polish: bool,
+
    // === Rewrite mode args ===
    /// Rewrite git history to conventional commits
    #[arg(long, conflicts_with_all = ["target", "copy", "dry_run"])]
@@ -1007,6 +1229,19 @@ pub struct Args {
    #[arg(long, requires = "rewrite")]
    pub rewrite_hide_old_types: bool,
 
+   /// Check that a rewritten branch (--new) differs from the original (--old)
+   /// only in commit messages, then list the message changes
+   #[arg(long, requires_all = ["verify_old", "verify_new"], conflicts_with_all = ["rewrite", "compose"])]
+   pub rewrite_verify: bool,
+
+   /// Original ref for --rewrite-verify
+   #[arg(long = "old", value_name = "REF", requires = "rewrite_verify")]
+   pub verify_old: Option<String>,
+
+   /// Rewritten ref for --rewrite-verify
+   #[arg(long = "new", value_name = "REF", requires = "rewrite_verify")]
+   pub verify_new: Option<String>,
+
    /// Exclude old commit message from context when analyzing commits (prevents
    /// contamination)
    #[arg(long)]
@@ -1021,6 +1256,11 @@ pub struct Args {
    #[arg(long, requires = "compose")]
    pub compose_preview: bool,
 
+   /// Preview proposed splits and dry-run staging them (`git apply --check`),
+   /// without committing
+   #[arg(long, requires = "compose")]
+   pub compose_validate: bool,
+
    /// Maximum number of commits to create
    #[arg(long, requires = "compose")]
    pub compose_max_commits: Option<usize>,
@@ -1034,12 +1274,31 @@ pub struct Args {
    #[arg(long)]
    pub no_changelog: bool,
 
+   // === Prompt experiment args ===
+   /// Value for a `{KEY}` placeholder in the prompt templates, for trying
+   /// prompt changes without editing them (repeatable)
+   #[arg(long = "prompt-var", value_name = "KEY=VALUE")]
+   pub prompt_var: Vec<String>,
+
+   /// Line to append to the analysis prompt for this run (repeatable)
+   #[arg(long, value_name = "TEXT")]
+   pub prompt_append_analysis: Vec<String>,
+
+   /// Line to append to the summary prompt for this run (repeatable)
+   #[arg(long, value_name = "TEXT")]
+   pub prompt_append_summary: Vec<String>,
+
    // === Debug args ===
    /// Save intermediate outputs (diff, analysis, summary, changelog) to
    /// directory
    #[arg(long)]
    pub debug_output: Option<PathBuf>,
 
+   /// Write the analysis (type, scope, details, issue refs) as JSON to this
+   /// file on every run, for auditing or assembling changelogs later
+   #[arg(long, value_name = "PATH")]
+   pub save_analysis: Option<PathBuf>,
+
    // === Test mode args ===
    /// Run fixture-based tests
    #[arg(long, conflicts_with_all = ["target", "rewrite", "compose"])]
@@ -1079,35 +1338,76 @@ impl Default for Args {
       Self {
          mode:                    Mode::Staged,
          target:                  None,
+         body_from_commits:       false,
+         no_stage:                false,
+         no_tag:                  false,
+         no_fast:                 false,
+         explain:                 false,
+         regenerate:              false,
+         yes:                     false,
+         allow_protected:         false,
          copy:                    false,
          dry_run:                 false,
+         quiet:                   false,
+         subject_only:            false,
          push:                    false,
          dir:                     ".".to_string(),
          model:                   None,
          temperature:             None,
+         base_url:                None,
          fixes:                   vec![],
          closes:                  vec![],
          resolves:                vec![],
          refs:                    vec![],
          breaking:                false,
+         no_footers:              false,
          sign:                    false,
          signoff:                 false,
          skip_hooks:              false,
+         allow_empty:             false,
+         allow_generic:           false,
+         allow_placeholders:      false,
+         anonymize:               false,
+         template_debug:          false,
+         commit_date:             None,
+         author_date:             None,
+         watch:                   false,
          config:                  None,
+         ignore_cli_defaults:     false,
+         check_config:            false,
+         init_config:             false,
+         model_list:              false,
+         force:                   false,
+         scopes:                  false,
+         depth:                   None,
+         json:                    false,
+         context_preset:          vec![],
+         context_url:             None,
+         force_type:              None,
+         summary:                 None,
          context:                 vec![],
+         polish:                  false,
          rewrite:                 false,
          rewrite_preview:         None,
          rewrite_start:           None,
          rewrite_parallel:        10,
          rewrite_dry_run:         false,
          rewrite_hide_old_types:  false,
+         rewrite_verify:          false,
+         verify_old:              None,
+         verify_new:              None,
          exclude_old_message:     false,
          compose:                 false,
          compose_preview:         false,
+         compose_validate:        false,
          compose_max_commits:     None,
          compose_test_after_each: false,
          no_changelog:            false,
+         prompt_var:              vec![],
+         prompt_append_analysis:  vec![],
+         prompt_append_summary:   vec![],
          debug_output:            None,
+         save_analysis:           None,
          test:                    false,
          test_update:             false,
          test_add:                None,
@@ -1346,6 +1646,10 @@ mod tests {
       // Pass-through for full names
       assert_eq!(resolve_model_name("claude-sonnet-4.5"), "claude-sonnet-4.5");
       assert_eq!(resolve_model_name("custom-model"), "custom-model");
+      assert_eq!(resolve_model_name("o3-mini"), "o3-mini");
+
+      assert_eq!(model_aliases_for("claude-sonnet-4.5"), vec!["sonnet", "s"]);
+      assert!(model_aliases_for("custom-model").is_empty());
    }
 
    // ========== CommitType Tests ==========
@@ -1528,6 +1832,30 @@ mod tests {
       }
    }
 
+   #[test]
+   fn test_commit_summary_multibyte_length() {
+      // 128 accented characters are 256 bytes but within a 128-character limit
+      assert!(CommitSummary::new("é".repeat(128), 128).is_ok());
+      assert!(matches!(
+         CommitSummary::new("é".repeat(129), 128),
+         Err(CommitGenError::SummaryTooLong { len: 129, max: 128 })
+      ));
+      assert_eq!(CommitSummary::new("añadido soporte", 128).unwrap().len(), 15);
+
+      // CJK counts one character but two display columns
+      let config = CommitConfig::default();
+      let cjk = "中".repeat(65);
+      assert!(CommitSummary::for_config(&cjk, &config).is_ok());
+      let config = CommitConfig { summary_length_metric: LengthMetric::Width, ..config };
+      assert!(CommitSummary::for_config("中".repeat(64), &config).is_ok());
+      assert!(matches!(
+         CommitSummary::for_config(&cjk, &config),
+         Err(CommitGenError::SummaryTooLong { len: 130, max: 128 })
+      ));
+      let config = CommitConfig { summary_length_metric: LengthMetric::Bytes, ..config };
+      assert!(CommitSummary::for_config("é".repeat(65), &config).is_err());
+   }
+
    #[test]
    fn test_commit_summary_empty() {
       let empty_cases = ["", "   ", "\t", "\n"];
@@ -1647,6 +1975,21 @@ mod tests {
       }
    }
 
+   #[test]
+   fn test_analysis_rationale() {
+      let analysis: ConventionalAnalysis = serde_json::from_str(
+         r#"{"type":"fix","details":[],"issue_refs":[],"rationale":"Restores the old retry behavior."}"#,
+      )
+      .unwrap();
+      assert_eq!(analysis.rationale.as_deref(), Some("Restores the old retry behavior."));
+
+      let analysis: ConventionalAnalysis =
+         serde_json::from_str(r#"{"type":"fix","details":[],"issue_refs":[]}"#).unwrap();
+      assert!(analysis.rationale.is_none());
+      let json = serde_json::to_value(&analysis).unwrap();
+      assert!(json.get("rationale").is_none());
+   }
+
    #[test]
    fn test_analysis_detail_with_changelog() {
       // Test structured detail with changelog metadata
@@ -1,10 +1,12 @@
 use std::process::Command;
 
 use crate::{
-   config::CommitConfig,
+   analysis::{ChangeProfile, ScopeAnalyzer, is_changelog},
+   config::{BodyStyle, CommitConfig, Tense, TypeScopePolicy},
+   diagnostics::{self, WarningCode},
    error::{CommitGenError, Result},
    style::{self, icons},
-   types::ConventionalCommit,
+   types::{CommitType, ConventionalCommit},
 };
 
 /// Common code file extensions for validation checks
@@ -48,7 +50,7 @@ fn is_code_extension(ext: &str) -> bool {
 }
 
 /// Get repository name from git working directory
-fn get_repository_name() -> Result<String> {
+pub fn get_repository_name() -> Result<String> {
    let output = Command::new("git")
       .args(["rev-parse", "--show-toplevel"])
       .output()
@@ -154,6 +156,171 @@ pub fn is_past_tense_verb(word: &str) -> bool {
    IRREGULAR.contains(&word)
 }
 
+/// Check if word is a base-form (imperative) verb
+///
+/// Morphology can't tell "add" from "addition", so this relies on a list of
+/// common base forms; anything ending in -ed or -ing is rejected outright.
+pub fn is_imperative_verb(word: &str) -> bool {
+   if word.ends_with("ed") || word.ends_with("ing") {
+      return false;
+   }
+
+   const BASE_FORMS: &[&str] = &[
+      "add",
+      "adjust",
+      "allow",
+      "align",
+      "apply",
+      "avoid",
+      "batch",
+      "bump",
+      "cache",
+      "clarify",
+      "clean",
+      "configure",
+      "consolidate",
+      "convert",
+      "correct",
+      "cover",
+      "create",
+      "deduplicate",
+      "defer",
+      "delete",
+      "deprecate",
+      "detect",
+      "disable",
+      "document",
+      "drop",
+      "eliminate",
+      "emit",
+      "enable",
+      "enforce",
+      "ensure",
+      "expand",
+      "expose",
+      "extend",
+      "extract",
+      "fix",
+      "format",
+      "guard",
+      "handle",
+      "harden",
+      "hide",
+      "ignore",
+      "implement",
+      "improve",
+      "include",
+      "inline",
+      "introduce",
+      "isolate",
+      "limit",
+      "log",
+      "make",
+      "merge",
+      "migrate",
+      "move",
+      "normalize",
+      "optimize",
+      "organize",
+      "parse",
+      "patch",
+      "pin",
+      "prevent",
+      "propagate",
+      "reduce",
+      "refactor",
+      "register",
+      "reject",
+      "release",
+      "remove",
+      "rename",
+      "reorganize",
+      "replace",
+      "report",
+      "require",
+      "resolve",
+      "restore",
+      "restructure",
+      "retry",
+      "return",
+      "revert",
+      "rewrite",
+      "route",
+      "run",
+      "separate",
+      "set",
+      "show",
+      "simplify",
+      "skip",
+      "sort",
+      "split",
+      "store",
+      "support",
+      "switch",
+      "test",
+      "track",
+      "trim",
+      "undo",
+      "unify",
+      "update",
+      "upgrade",
+      "use",
+      "validate",
+      "wrap",
+   ];
+
+   BASE_FORMS.contains(&word)
+}
+
+/// Check if word has the verb form required by `tense`
+pub fn is_verb_in_tense(word: &str, tense: Tense) -> bool {
+   match tense {
+      Tense::Past => is_past_tense_verb(word),
+      Tense::Imperative => is_imperative_verb(word),
+   }
+}
+
+/// Whether `word` is one of the type's configured `leading_verbs` and not
+/// recognizably in the other tense, so a list covering both tenses only
+/// accepts the forms that fit `config.summary_tense`
+pub fn is_accepted_leading_verb(word: &str, commit_type: &str, config: &CommitConfig) -> bool {
+   let wrong_tense = match config.summary_tense {
+      Tense::Past => is_imperative_verb(word) && !is_past_tense_verb(word),
+      Tense::Imperative => is_past_tense_verb(word),
+   };
+   !wrong_tense
+      && config.types.get(commit_type).is_some_and(|ty| {
+         ty.leading_verbs
+            .iter()
+            .any(|verb| verb.eq_ignore_ascii_case(word))
+      })
+}
+
+/// Length of the `type(scope): summary` line under `summary_length_metric`
+fn measure_first_line(msg: &ConventionalCommit, config: &CommitConfig) -> usize {
+   let scope_part = msg
+      .scope
+      .as_ref()
+      .map(|s| format!("({s})"))
+      .unwrap_or_default();
+   let first_line = format!("{}{scope_part}: {}", msg.commit_type, msg.summary);
+   config.summary_length_metric.measure(&first_line)
+}
+
+/// First lines of the footers longer than `summary_soft_limit`, with their
+/// length. Only a footer's first line counts: `BREAKING CHANGE` paragraphs and
+/// folded trailers continue on lines of their own.
+fn long_footers<'a>(
+   footers: &'a [String],
+   config: &CommitConfig,
+) -> impl Iterator<Item = (&'a str, usize)> {
+   footers.iter().filter_map(|footer| {
+      let first_line = footer.lines().next().unwrap_or_default();
+      let len = config.summary_length_metric.measure(first_line);
+      (len > config.summary_soft_limit).then_some((first_line, len))
+   })
+}
+
 /// Validate conventional commit message
 pub fn validate_commit_message(msg: &ConventionalCommit, config: &CommitConfig) -> Result<()> {
    // Validate commit type
@@ -181,15 +348,11 @@ pub fn validate_commit_message(msg: &ConventionalCommit, config: &CommitConfig)
    // Reject scope if it's just the project/repo name
    if let Some(scope) = &msg.scope
       && let Ok(repo_name) = get_repository_name()
+      && is_project_name_scope(scope.as_str(), &repo_name)
    {
-      let normalized_scope = normalize_name(scope.as_str());
-      let normalized_repo = normalize_name(&repo_name);
-
-      if normalized_scope == normalized_repo {
-         return Err(CommitGenError::InvalidScope(format!(
-            "Scope '{scope}' is the project name - omit scope for project-wide changes"
-         )));
-      }
+      return Err(CommitGenError::InvalidScope(format!(
+         "Scope '{scope}' is the project name - omit scope for project-wide changes"
+      )));
    }
 
    // Check summary not empty
@@ -199,19 +362,14 @@ pub fn validate_commit_message(msg: &ConventionalCommit, config: &CommitConfig)
 
    // Check summary does NOT end with period (conventional commits don't use
    // periods)
-   if msg.summary.as_str().trim_end().ends_with('.') {
+   if config.enforce_no_trailing_period && msg.summary.as_str().trim_end().ends_with('.') {
       return Err(CommitGenError::ValidationError(
          "Summary must NOT end with a period (conventional commits style)".to_string(),
       ));
    }
 
    // Check first line length: type(scope): summary
-   let scope_part = msg
-      .scope
-      .as_ref()
-      .map(|s| format!("({s})"))
-      .unwrap_or_default();
-   let first_line_len = msg.commit_type.len() + scope_part.len() + 2 + msg.summary.len();
+   let first_line_len = measure_first_line(msg, config);
 
    // Hard limit check (absolute maximum) - REJECT
    if first_line_len > config.summary_hard_limit {
@@ -223,10 +381,13 @@ pub fn validate_commit_message(msg: &ConventionalCommit, config: &CommitConfig)
 
    // Soft limit warning (triggers retry in main.rs) - WARN but pass
    if first_line_len > config.summary_soft_limit {
-      style::warn(&format!(
-         "Summary exceeds soft limit: {} > {} chars (retry recommended)",
-         first_line_len, config.summary_soft_limit
-      ));
+      diagnostics::warn(
+         WarningCode::SoftLimit,
+         &format!(
+            "Summary exceeds soft limit: {} > {} chars (retry recommended)",
+            first_line_len, config.summary_soft_limit
+         ),
+      );
    }
 
    // Guideline warning (72-96 range) - INFO
@@ -241,9 +402,19 @@ pub fn validate_commit_message(msg: &ConventionalCommit, config: &CommitConfig)
       );
    }
 
+   for (first_line, len) in long_footers(&msg.footers, config) {
+      diagnostics::warn(
+         WarningCode::LongFooter,
+         &format!(
+            "Footer exceeds soft limit: {len} > {} chars: {first_line}",
+            config.summary_soft_limit
+         ),
+      );
+   }
+
    // Note: lowercase check is done in CommitSummary::new() to avoid duplication
 
-   // Check first word is past-tense verb (morphology-based)
+   // Check first word has the configured verb form
    let first_word = msg.summary.as_str().split_whitespace().next().unwrap_or("");
 
    if first_word.is_empty() {
@@ -253,16 +424,27 @@ pub fn validate_commit_message(msg: &ConventionalCommit, config: &CommitConfig)
    }
 
    let first_word_lower = first_word.to_lowercase();
-   if !is_past_tense_verb(&first_word_lower) {
-      return Err(CommitGenError::ValidationError(format!(
-         "Summary must start with a past-tense verb (ending in -ed/-d or irregular). Got \
-          '{first_word}'"
-      )));
+   let type_word = msg.commit_type.as_str();
+   let accepted = is_accepted_leading_verb(&first_word_lower, type_word, config);
+   match config.enforced_tense() {
+      _ if accepted => {},
+      Some(Tense::Past) if !is_past_tense_verb(&first_word_lower) => {
+         return Err(CommitGenError::ValidationError(format!(
+            "Summary must start with a past-tense verb (ending in -ed/-d or irregular). Got \
+             '{first_word}'"
+         )));
+      },
+      Some(Tense::Imperative) if !is_imperative_verb(&first_word_lower) => {
+         return Err(CommitGenError::ValidationError(format!(
+            "Summary must start with an imperative verb (base form, not -ed/-ing). Got \
+             '{first_word}'"
+         )));
+      },
+      _ => {},
    }
 
    // Check for type-word repetition
-   let type_word = msg.commit_type.as_str();
-   if first_word_lower == type_word {
+   if first_word_lower == type_word && !accepted {
       return Err(CommitGenError::ValidationError(format!(
          "Summary repeats commit type '{type_word}': first word is '{first_word}'"
       )));
@@ -273,10 +455,23 @@ pub fn validate_commit_message(msg: &ConventionalCommit, config: &CommitConfig)
    const FILLER_WORDS: &[&str] = &["comprehensive", "better", "various", "several"];
    for filler in FILLER_WORDS {
       if msg.summary.as_str().to_lowercase().contains(filler) {
-         style::warn(&format!("Summary contains filler word '{}': {}", filler, msg.summary));
+         diagnostics::warn(
+            WarningCode::FillerWord,
+            &format!("Summary contains filler word '{}': {}", filler, msg.summary),
+         );
       }
    }
 
+   // The scope is already in the prefix
+   if let Some(scope) = &msg.scope
+      && crate::normalization::summary_repeats_scope(msg.summary.as_str(), scope.as_str())
+   {
+      diagnostics::warn(
+         WarningCode::ScopeInSummary,
+         &format!("Summary repeats scope '{scope}': {}", msg.summary),
+      );
+   }
+
    // Check for meta-phrases that add no information
    const META_PHRASES: &[&str] = &[
       "this commit",
@@ -291,20 +486,17 @@ pub fn validate_commit_message(msg: &ConventionalCommit, config: &CommitConfig)
    ];
    for phrase in META_PHRASES {
       if msg.summary.as_str().to_lowercase().contains(phrase) {
-         style::warn(&format!(
-            "Summary contains meta-phrase '{phrase}' - be more specific about what changed"
-         ));
+         diagnostics::warn(
+            WarningCode::MetaPhrase,
+            &format!(
+               "Summary contains meta-phrase '{phrase}' - be more specific about what changed"
+            ),
+         );
       }
    }
 
    // Final length check after all potential mutations
-   let final_scope_part = msg
-      .scope
-      .as_ref()
-      .map(|s| format!("({s})"))
-      .unwrap_or_default();
-   let final_first_line_len =
-      msg.commit_type.len() + final_scope_part.len() + 2 + msg.summary.len();
+   let final_first_line_len = measure_first_line(msg, config);
 
    if final_first_line_len > config.summary_hard_limit {
       return Err(CommitGenError::SummaryTooLong {
@@ -337,137 +529,387 @@ pub fn validate_commit_message(msg: &ConventionalCommit, config: &CommitConfig)
          "reorganizes",
          "consolidates",
       ];
-      if present_tense
-         .iter()
-         .any(|&word| first_word.to_lowercase() == word)
-      {
-         style::warn(&format!("Body item uses present tense: '{item}'"));
+      let first_word_lower = first_word.to_lowercase();
+      match config.body_tense {
+         Tense::Past if present_tense.contains(&first_word_lower.as_str()) => {
+            diagnostics::warn(
+               WarningCode::BodyStyle,
+               &format!("Body item uses present tense: '{item}'"),
+            );
+         },
+         Tense::Imperative if is_past_tense_verb(&first_word_lower) => {
+            diagnostics::warn(
+               WarningCode::BodyStyle,
+               &format!("Body item uses past tense: '{item}'"),
+            );
+         },
+         _ => {},
       }
-      if !item.trim_end().ends_with('.') {
-         style::warn(&format!("Body item missing period: '{item}'"));
+      // Paragraphs may end in any sentence punctuation
+      if config.body_style == BodyStyle::Bullets && !item.trim_end().ends_with('.') {
+         diagnostics::warn(WarningCode::BodyStyle, &format!("Body item missing period: '{item}'"));
       }
    }
 
    Ok(())
 }
 
-/// Check type-scope consistency (warn if mismatched)
-pub fn check_type_scope_consistency(msg: &ConventionalCommit, stat: &str) {
-   let commit_type = msg.commit_type.as_str();
+/// Whether `scope` is just the project/repo name
+pub fn is_project_name_scope(scope: &str, repo_name: &str) -> bool {
+   normalize_name(scope) == normalize_name(repo_name)
+}
 
-   // Check for docs type
-   if commit_type == "docs" {
-      let has_docs = stat.lines().any(|line| {
-         let path = line.split('|').next().unwrap_or("").trim();
-         let is_doc_file = std::path::Path::new(&path)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .is_some_and(|ext| {
-               matches!(
-                  ext.to_ascii_lowercase().as_str(),
-                  "md" | "mdx" | "adoc" | "asciidoc" | "rst" | "txt" | "org" | "tex" | "pod"
-               )
-            });
-         is_doc_file
-            || path.to_lowercase().contains("/docs/")
-            || path.to_lowercase().contains("readme")
-      });
-      if !has_docs {
-         style::warn("Commit type 'docs' but no documentation files changed");
-      }
+/// Paths from `git diff --stat` lines (everything left of the `|`)
+fn stat_paths(stat: &str) -> impl Iterator<Item = &str> {
+   stat
+      .lines()
+      .map(|line| line.split('|').next().unwrap_or("").trim())
+}
+
+fn is_test_file_path(path: &str) -> bool {
+   let path = path.to_lowercase();
+   path.contains("/test") || path.contains("_test.") || path.contains(".test.")
+}
+
+fn is_ci_path(path: &str) -> bool {
+   let path = path.to_lowercase();
+   path.contains(".github/workflows") || path.contains(".gitlab-ci") || path.contains("jenkinsfile")
+}
+
+fn is_build_path(path: &str) -> bool {
+   let path = path.to_lowercase();
+   path.contains("cargo.toml")
+      || path.contains("package.json")
+      || path.contains("makefile")
+      || path.contains("build.")
+}
+
+/// Type every changed file agrees on (docs, test, ci or build), other than
+/// `current`
+fn type_from_paths(stat: &str, current: &str) -> Option<&'static str> {
+   let paths: Vec<&str> = stat
+      .lines()
+      .filter(|line| line.contains('|'))
+      .map(|line| line.split('|').next().unwrap_or("").trim())
+      .collect();
+   if paths.is_empty() {
+      return None;
    }
 
-   // Check for test type
-   if commit_type == "test" {
-      let has_test = stat.lines().any(|line| {
-         let path = line.split('|').next().unwrap_or("").trim().to_lowercase();
-         path.contains("/test") || path.contains("_test.") || path.contains(".test.")
+   type PathCheck = fn(&str) -> bool;
+   let kinds: [(&str, PathCheck); 4] = [
+      ("docs", |path| ScopeAnalyzer::is_doc_path(path) && !is_changelog(path)),
+      ("test", is_test_file_path),
+      ("ci", is_ci_path),
+      ("build", is_build_path),
+   ];
+   kinds
+      .into_iter()
+      .find(|&(kind, matches)| kind != current && paths.iter().all(|path| matches(path)))
+      .map(|(kind, _)| kind)
+}
+
+/// A commit type the changed files contradict
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeMismatch {
+   pub warning:   &'static str,
+   /// Type the changed files point to instead, when they point to one
+   pub suggested: Option<&'static str>,
+}
+
+/// Check whether the commit type fits the changed files in `stat`; `profile`
+/// also credits docs for `examples/` and comment-only source edits
+pub fn type_scope_mismatch(
+   msg: &ConventionalCommit,
+   stat: &str,
+   profile: &ChangeProfile,
+) -> Option<TypeMismatch> {
+   let commit_type = msg.commit_type.as_str();
+   let mismatch = |warning| {
+      let suggested = type_from_paths(stat, commit_type).or_else(|| {
+         (commit_type != "docs" && profile.docs >= 1.0 && !stat_paths(stat).all(is_changelog))
+            .then_some("docs")
       });
-      if !has_test {
-         style::warn("Commit type 'test' but no test files changed");
-      }
+      Some(TypeMismatch { warning, suggested })
+   };
+
+   match commit_type {
+      "docs" if profile.docs == 0.0 && !stat_paths(stat).any(ScopeAnalyzer::is_doc_path) => {
+         mismatch("Commit type 'docs' but no documentation files changed")
+      },
+      "test" if !stat_paths(stat).any(is_test_file_path) => {
+         mismatch("Commit type 'test' but no test files changed")
+      },
+      // Should be mostly whitespace/formatting
+      "style"
+         if stat_paths(stat).any(|path| {
+            std::path::Path::new(path)
+               .extension()
+               .is_some_and(|ext| is_code_extension(ext.to_str().unwrap_or("")))
+         }) =>
+      {
+         Some(TypeMismatch {
+            warning:   "Commit type 'style' but code files changed (verify no logic changes)",
+            suggested: None,
+         })
+      },
+      "ci" if !stat_paths(stat).any(is_ci_path) => {
+         mismatch("Commit type 'ci' but no CI configuration files changed")
+      },
+      "build" if !stat_paths(stat).any(is_build_path) => {
+         mismatch("Commit type 'build' but no build files (Cargo.toml, package.json) changed")
+      },
+      // New files might mean new capabilities
+      "refactor"
+         if stat
+            .lines()
+            .any(|line| line.trim().starts_with("create mode") || line.contains("new file")) =>
+      {
+         Some(TypeMismatch {
+            warning:   "Commit type 'refactor' but new files were created - verify no new \
+                        capabilities added (might be 'feat')",
+            suggested: Some("feat"),
+         })
+      },
+      "perf" => {
+         let has_perf_files = stat_paths(stat).any(|path| {
+            let path = path.to_lowercase();
+            path.contains("bench") || path.contains("perf") || path.contains("profile")
+         });
+
+         // Check if details mention performance
+         let details_text = msg.body.join(" ").to_lowercase();
+         let has_perf_details = details_text.contains("faster")
+            || details_text.contains("optimization")
+            || details_text.contains("performance")
+            || details_text.contains("optimized");
+
+         (!has_perf_files && !has_perf_details).then_some(TypeMismatch {
+            warning:   "Commit type 'perf' but no performance-related files or optimization \
+                        keywords found",
+            suggested: None,
+         })
+      },
+      _ => None,
    }
+}
 
-   // Check for style type (should be mostly whitespace/formatting)
-   if commit_type == "style" {
-      let has_code = stat.lines().any(|line| {
-         let path = line.split('|').next().unwrap_or("").trim();
-         let path_obj = std::path::Path::new(&path);
-         path_obj
-            .extension()
-            .is_some_and(|ext| is_code_extension(ext.to_str().unwrap_or("")))
-      });
-      if has_code {
-         style::warn("Commit type 'style' but code files changed (verify no logic changes)");
-      }
+/// Check type-scope consistency (warn if mismatched); `profile` is the
+/// share of each kind of change, as from [`classify_change_profile`]
+///
+/// [`classify_change_profile`]: crate::analysis::classify_change_profile
+pub fn check_type_scope_consistency(msg: &ConventionalCommit, stat: &str, profile: &ChangeProfile) {
+   if let Some(mismatch) = type_scope_mismatch(msg, stat, profile) {
+      diagnostics::warn(WarningCode::TypeScopeMismatch, mismatch.warning);
    }
+}
 
-   // Check for ci type
-   if commit_type == "ci" {
-      let has_ci = stat.lines().any(|line| {
-         let path = line.split('|').next().unwrap_or("").trim().to_lowercase();
-         path.contains(".github/workflows")
-            || path.contains(".gitlab-ci")
-            || path.contains("jenkinsfile")
-      });
-      if !has_ci {
-         style::warn("Commit type 'ci' but no CI configuration files changed");
-      }
+/// Handle a type/file mismatch per `on_type_scope_inconsistency`: warn,
+/// switch to the suggested type, or fail; mismatches without a suggested
+/// type only warn
+pub fn apply_type_scope_policy(
+   msg: &mut ConventionalCommit,
+   stat: &str,
+   profile: &ChangeProfile,
+   policy: TypeScopePolicy,
+) -> Result<()> {
+   let Some(mismatch) = type_scope_mismatch(msg, stat, profile) else {
+      return Ok(());
+   };
+
+   match (policy, mismatch.suggested) {
+      (TypeScopePolicy::Reject, Some(_)) => Err(CommitGenError::ValidationError(format!(
+         "{} (on_type_scope_inconsistency = \"reject\")",
+         mismatch.warning
+      ))),
+      (TypeScopePolicy::Correct, Some(suggested)) => {
+         style::warn(&format!(
+            "{}; changed type '{}' to '{suggested}'",
+            mismatch.warning,
+            msg.commit_type.as_str()
+         ));
+         msg.commit_type = CommitType::new(suggested)?;
+         Ok(())
+      },
+      _ => {
+         diagnostics::warn(WarningCode::TypeScopeMismatch, mismatch.warning);
+         Ok(())
+      },
    }
+}
 
-   // Check for build type
-   if commit_type == "build" {
-      let has_build = stat.lines().any(|line| {
-         let path = line.split('|').next().unwrap_or("").trim().to_lowercase();
-         path.contains("cargo.toml")
-            || path.contains("package.json")
-            || path.contains("makefile")
-            || path.contains("build.")
-      });
-      if !has_build {
-         style::warn("Commit type 'build' but no build files (Cargo.toml, package.json) changed");
-      }
+/// Warning when the share of each kind of change contradicts the commit type:
+/// mostly documentation but not `docs`, or `feat`/`fix` without code changes
+pub fn profile_type_warning(commit_type: &str, profile: &ChangeProfile) -> Option<String> {
+   if profile.is_empty() {
+      return None;
+   }
+   if profile.docs > 0.8 && commit_type != "docs" {
+      return Some(format!(
+         "Type mismatch: {:.0}% of changed lines are documentation but type is '{commit_type}' \
+          (consider docs type)",
+         profile.docs * 100.0
+      ));
    }
+   (profile.code == 0.0 && matches!(commit_type, "feat" | "fix"))
+      .then(|| format!("Type mismatch: no code changed but type is '{commit_type}'"))
+}
 
-   // Check for refactor with new files (might actually be feat)
-   if commit_type == "refactor" {
-      let has_new_files = stat
-         .lines()
-         .any(|line| line.trim().starts_with("create mode") || line.contains("new file"));
-      if has_new_files {
-         style::warn(
-            "Commit type 'refactor' but new files were created - verify no new capabilities added \
-             (might be 'feat')",
-         );
+/// Whether a message says nothing about a sizeable change.
+///
+/// That is: no body, and a summary that is one of `vacuous_summary_patterns`
+/// (case, whitespace and trailing punctuation aside), for a diff of at least
+/// `vacuous_min_diff_chars`.
+pub fn is_vacuous_message(
+   summary: &str,
+   body: &[String],
+   diff_chars: usize,
+   config: &CommitConfig,
+) -> bool {
+   let normalize = |text: &str| {
+      text
+         .split_whitespace()
+         .collect::<Vec<_>>()
+         .join(" ")
+         .trim_end_matches(['.', '!'])
+         .to_lowercase()
+   };
+   let summary = normalize(summary);
+   body.iter().all(|item| item.trim().is_empty())
+      && diff_chars >= config.vacuous_min_diff_chars
+      && config
+         .vacuous_summary_patterns
+         .iter()
+         .any(|pattern| normalize(pattern) == summary)
+}
+
+/// Words that mark an unfinished item when one starts with them, followed by
+/// a colon
+const PLACEHOLDER_WORDS: &[&str] = &["TODO", "XXX"];
+
+/// First commit-template placeholder left in a message: `<type>`- or
+/// `{summary}`-style fields, or an item (the summary or a body line) that
+/// starts with `TODO:`/`XXX:`.
+///
+/// Text in backticks is skipped, and `<...>` directly after an identifier
+/// (`Vec<String>`) is a generic, not a placeholder. `TODO` elsewhere
+/// ("removed stale TODO comments") is prose.
+pub fn find_template_placeholder(message: &str) -> Option<String> {
+   let is_word = |c: char| c.is_alphanumeric() || c == '_';
+   for (i, prose) in message.split('`').enumerate() {
+      if i % 2 == 1 {
+         continue;
+      }
+
+      for (open, close) in [('<', '>'), ('{', '}')] {
+         for (start, _) in prose.match_indices(open) {
+            let after_word = prose[..start]
+               .chars()
+               .next_back()
+               .is_some_and(|c| is_word(c) || c == ':');
+            let Some(len) = prose[start + 1..].find(close) else {
+               continue;
+            };
+            let inner = &prose[start + 1..start + 1 + len];
+            if !after_word
+               && inner.len() <= 40
+               && inner.trim() == inner
+               && inner.chars().any(char::is_alphabetic)
+               && inner
+                  .chars()
+                  .all(|c| is_word(c) || matches!(c, ' ' | '-' | '|' | '.'))
+            {
+               return Some(format!("{open}{inner}{close}"));
+            }
+         }
       }
    }
 
-   // Check for perf type without performance evidence
-   if commit_type == "perf" {
-      let has_perf_files = stat.lines().any(|line| {
-         let path = line.split('|').next().unwrap_or("").trim().to_lowercase();
-         path.contains("bench") || path.contains("perf") || path.contains("profile")
-      });
+   message.lines().enumerate().find_map(|(i, line)| {
+      let mut item = line.trim_start();
+      item = item
+         .strip_prefix("- ")
+         .or_else(|| item.strip_prefix("* "))
+         .unwrap_or(item);
+      // The summary starts after the `type(scope): ` header
+      if i == 0 {
+         item = item.split_once(": ").map_or(item, |(_, summary)| summary);
+      }
+      PLACEHOLDER_WORDS
+         .iter()
+         .find(|word| {
+            item
+               .strip_prefix(**word)
+               .is_some_and(|rest| rest.starts_with(':'))
+         })
+         .map(|word| (*word).to_string())
+   })
+}
+
+/// Changed file whose stem `scope` names, when no changed file has a
+/// directory by that name (e.g. scope `client` for `src/api/client.rs`)
+pub fn filename_stem_scope<'a>(scope: &str, stat: &'a str) -> Option<&'a str> {
+   let name = scope.rsplit('/').next().unwrap_or(scope);
+   let paths: Vec<&str> = stat
+      .lines()
+      .filter(|line| line.contains('|'))
+      .map(|line| line.split('|').next().unwrap_or("").trim())
+      .collect();
+
+   let is_directory = paths.iter().any(|path| {
+      let mut components: Vec<&str> = path.split('/').collect();
+      components.pop();
+      components.iter().any(|dir| dir.eq_ignore_ascii_case(name))
+   });
+   if is_directory {
+      return None;
+   }
 
-      // Check if details mention performance
-      let details_text = msg.body.join(" ").to_lowercase();
-      let has_perf_details = details_text.contains("faster")
-         || details_text.contains("optimization")
-         || details_text.contains("performance")
-         || details_text.contains("optimized");
+   paths.into_iter().find(|path| {
+      std::path::Path::new(path)
+         .file_stem()
+         .and_then(|stem| stem.to_str())
+         .is_some_and(|stem| stem.eq_ignore_ascii_case(name))
+   })
+}
 
-      if !has_perf_files && !has_perf_details {
-         style::warn(
-            "Commit type 'perf' but no performance-related files or optimization keywords found",
-         );
-      }
+/// Warn when the scope is a file name rather than a module or directory
+pub fn check_scope_not_filename(msg: &ConventionalCommit, stat: &str) {
+   let Some(scope) = &msg.scope else {
+      return;
+   };
+   let Some(path) = filename_stem_scope(scope.as_str(), stat) else {
+      return;
+   };
+
+   let parent = std::path::Path::new(path)
+      .parent()
+      .and_then(|dir| dir.file_name())
+      .and_then(|dir| dir.to_str());
+   match parent {
+      Some(dir) => diagnostics::warn(
+         WarningCode::FilenameScope,
+         &format!(
+            "Scope '{scope}' names the file {path}, not a module; consider the directory scope \
+             '{dir}'"
+         ),
+      ),
+      None => diagnostics::warn(
+         WarningCode::FilenameScope,
+         &format!(
+            "Scope '{scope}' names the file {path}, not a module; consider a directory scope"
+         ),
+      ),
    }
 }
 
 #[cfg(test)]
 mod tests {
    use super::*;
-   use crate::types::{CommitSummary, CommitType, ConventionalCommit, Scope};
+   use crate::{
+      config::LengthMetric,
+      types::{CommitSummary, CommitType, ConventionalCommit, Scope},
+   };
 
    fn create_commit(
       type_str: &str,
@@ -643,6 +1085,142 @@ mod tests {
       }
    }
 
+   #[test]
+   fn test_validate_summary_rule_toggles() {
+      let config = CommitConfig::default();
+      let capitalized = create_commit("feat", None, "Add support for proxies", vec![]);
+      let with_period = create_commit("feat", None, "added support for proxies.", vec![]);
+      assert!(validate_commit_message(&capitalized, &config).is_err());
+      assert!(validate_commit_message(&with_period, &config).is_err());
+
+      // Tense off: any opener passes, the period rule still applies
+      let config = CommitConfig { enforce_tense: false, ..Default::default() };
+      assert!(validate_commit_message(&capitalized, &config).is_ok());
+      assert!(validate_commit_message(&with_period, &config).is_err());
+      let repeated = create_commit("fix", None, "fix proxy handling", vec![]);
+      assert!(validate_commit_message(&repeated, &config).is_err());
+
+      // Period off: the trailing period passes, the tense rule still applies
+      let config = CommitConfig { enforce_no_trailing_period: false, ..Default::default() };
+      assert!(validate_commit_message(&with_period, &config).is_ok());
+      assert!(validate_commit_message(&capitalized, &config).is_err());
+   }
+
+   fn imperative_config() -> CommitConfig {
+      CommitConfig {
+         summary_tense: Tense::Imperative,
+         body_tense: Tense::Imperative,
+         ..Default::default()
+      }
+   }
+
+   #[test]
+   fn test_validate_imperative_valid_commit() {
+      let config = imperative_config();
+      let msg = create_commit("feat", Some("api"), "add new endpoint", vec![]);
+      assert!(validate_commit_message(&msg, &config).is_ok());
+      let msg = create_commit("fix", None, "correct race condition", vec![]);
+      assert!(validate_commit_message(&msg, &config).is_ok());
+   }
+
+   #[test]
+   fn test_validate_imperative_rejects_past_tense() {
+      let config = imperative_config();
+      let msg = create_commit("feat", None, "added new feature", vec![]);
+      let err = validate_commit_message(&msg, &config).unwrap_err();
+      assert!(
+         err.to_string()
+            .contains("must start with an imperative verb")
+      );
+   }
+
+   #[test]
+   fn test_validate_imperative_wrong_verb() {
+      let config = imperative_config();
+      for summary in ["adding new feature", "adds new feature", "new feature for api"] {
+         let msg = create_commit("feat", None, summary, vec![]);
+         assert!(
+            validate_commit_message(&msg, &config).is_err(),
+            "'{summary}' should be rejected in imperative mode"
+         );
+      }
+   }
+
+   #[test]
+   fn test_validate_imperative_no_type_verb_overlap() {
+      let config = imperative_config();
+      let msg = create_commit("docs", Some("api"), "document new api", vec![]);
+      assert!(validate_commit_message(&msg, &config).is_ok());
+
+      // Imperative "fix" for a fix commit just repeats the type
+      let msg = create_commit("fix", None, "fix parser crash", vec![]);
+      let err = validate_commit_message(&msg, &config).unwrap_err();
+      assert!(err.to_string().contains("repeats commit type"));
+   }
+
+   #[test]
+   fn test_validate_type_leading_verbs() {
+      let mut config = CommitConfig::default();
+      config.types.get_mut("ci").unwrap().leading_verbs =
+         ["reran", "rerun", "ci", "wired"].map(String::from).to_vec();
+
+      // "rerun" isn't a recognized past-tense verb and "ci" repeats the type,
+      // but both are listed for ci
+      for summary in ["rerun flaky jobs nightly", "ci matrix split by target", "reran jobs"] {
+         let msg = create_commit("ci", None, summary, vec![]);
+         assert!(validate_commit_message(&msg, &config).is_ok(), "{summary}");
+      }
+      // The list only applies to its own type
+      let msg = create_commit("build", None, "rerun codegen on install", vec![]);
+      assert!(validate_commit_message(&msg, &config).is_err());
+
+      // Listed verbs in the other tense still fail
+      let mut config = imperative_config();
+      config.types.get_mut("ci").unwrap().leading_verbs =
+         ["wired", "wire"].map(String::from).to_vec();
+      let msg = create_commit("ci", None, "wired cache into workflow", vec![]);
+      assert!(validate_commit_message(&msg, &config).is_err());
+      let msg = create_commit("ci", None, "wire cache into workflow", vec![]);
+      assert!(validate_commit_message(&msg, &config).is_ok());
+   }
+
+   #[test]
+   fn test_is_imperative_verb() {
+      for verb in ["add", "fix", "remove", "simplify", "split", "use", "undo"] {
+         assert!(is_imperative_verb(verb), "'{verb}' should be imperative");
+      }
+      for word in ["added", "adding", "adds", "fixes", "feature", "hundred", "red"] {
+         assert!(!is_imperative_verb(word), "'{word}' should not be imperative");
+      }
+   }
+
+   #[test]
+   fn test_is_verb_in_tense() {
+      assert!(is_verb_in_tense("added", Tense::Past));
+      assert!(!is_verb_in_tense("add", Tense::Past));
+      assert!(is_verb_in_tense("add", Tense::Imperative));
+      assert!(!is_verb_in_tense("added", Tense::Imperative));
+   }
+
+   #[test]
+   fn test_validate_imperative_summary_keeps_past_body() {
+      // body_tense stays past unless set explicitly
+      let config = CommitConfig { summary_tense: Tense::Imperative, ..Default::default() };
+      let msg = create_commit("feat", None, "add oauth support", vec!["Added OAuth client."]);
+      assert!(validate_commit_message(&msg, &config).is_ok());
+   }
+
+   #[test]
+   fn test_validate_body_past_tense_warning_in_imperative_mode() {
+      let config = imperative_config();
+      let msg = create_commit("feat", None, "add new feature", vec![
+         "Added support for TLS.",
+         "Update configuration.",
+      ]);
+      // Should succeed but print warnings
+      assert!(validate_commit_message(&msg, &config).is_ok());
+   }
+
    #[test]
    fn test_validate_scope_empty_string() {
       let result = Scope::new("");
@@ -733,12 +1311,31 @@ mod tests {
       assert!(matches!(result.unwrap_err(), CommitGenError::SummaryTooLong { .. }));
    }
 
+   #[test]
+   fn test_long_footers_measure_first_line_only() {
+      let config = CommitConfig::default();
+      let long = "x".repeat(120);
+      let footers = vec![
+         format!("BREAKING CHANGE: dropped the v1 API\n\n{long}\n{long}"),
+         format!("Co-authored-by: A <a@example.com>\n {long}"),
+         format!("Refs: {long}"),
+      ];
+      let flagged: Vec<_> = long_footers(&footers, &config).collect();
+      assert_eq!(flagged, vec![(format!("Refs: {long}").as_str(), 126)]);
+
+      let msg = ConventionalCommit {
+         footers: footers[..2].to_vec(),
+         ..create_commit("feat", None, "added v2 API", vec![])
+      };
+      assert!(validate_commit_message(&msg, &config).is_ok());
+   }
+
    #[test]
    fn test_check_type_scope_docs_with_md() {
       let msg = create_commit("docs", Some("readme"), "updated installation guide", vec![]);
       let stat = " README.md | 10 +++++++---\n 1 file changed, 7 insertions(+), 3 deletions(-)";
       // Should not print warning
-      check_type_scope_consistency(&msg, stat);
+      check_type_scope_consistency(&msg, stat, &ChangeProfile::from_stat(stat));
    }
 
    #[test]
@@ -746,42 +1343,42 @@ mod tests {
       let msg = create_commit("docs", None, "updated documentation", vec![]);
       let stat = " src/main.rs | 10 +++++++---\n 1 file changed, 7 insertions(+), 3 deletions(-)";
       // Should print warning (but we can't test stderr easily)
-      check_type_scope_consistency(&msg, stat);
+      check_type_scope_consistency(&msg, stat, &ChangeProfile::from_stat(stat));
    }
 
    #[test]
    fn test_check_type_scope_test_with_test_files() {
       let msg = create_commit("test", Some("api"), "added integration tests", vec![]);
       let stat = " tests/integration_test.rs | 50 ++++++++++++++++++++++++++++++++\n";
-      check_type_scope_consistency(&msg, stat);
+      check_type_scope_consistency(&msg, stat, &ChangeProfile::from_stat(stat));
    }
 
    #[test]
    fn test_check_type_scope_test_without_test_files() {
       let msg = create_commit("test", None, "added tests", vec![]);
       let stat = " src/lib.rs | 10 +++++++---\n";
-      check_type_scope_consistency(&msg, stat);
+      check_type_scope_consistency(&msg, stat, &ChangeProfile::from_stat(stat));
    }
 
    #[test]
    fn test_check_type_scope_refactor_new_files() {
       let msg = create_commit("refactor", Some("core"), "restructured modules", vec![]);
       let stat = " create mode 100644 src/new_module.rs\n src/lib.rs | 10 +++++++---\n";
-      check_type_scope_consistency(&msg, stat);
+      check_type_scope_consistency(&msg, stat, &ChangeProfile::from_stat(stat));
    }
 
    #[test]
    fn test_check_type_scope_ci_with_workflow() {
       let msg = create_commit("ci", None, "updated github actions", vec![]);
       let stat = " .github/workflows/ci.yml | 20 ++++++++++++++++++++\n";
-      check_type_scope_consistency(&msg, stat);
+      check_type_scope_consistency(&msg, stat, &ChangeProfile::from_stat(stat));
    }
 
    #[test]
    fn test_check_type_scope_build_with_cargo() {
       let msg = create_commit("build", Some("deps"), "updated dependencies", vec![]);
       let stat = " Cargo.toml | 5 +++--\n Cargo.lock | 150 +++++++++++++++++++\n";
-      check_type_scope_consistency(&msg, stat);
+      check_type_scope_consistency(&msg, stat, &ChangeProfile::from_stat(stat));
    }
 
    #[test]
@@ -790,14 +1387,194 @@ mod tests {
          "reduced allocations by 50% for faster throughput.",
       ]);
       let stat = " src/core.rs | 30 +++++++++++++-----------------\n";
-      check_type_scope_consistency(&msg, stat);
+      check_type_scope_consistency(&msg, stat, &ChangeProfile::from_stat(stat));
    }
 
    #[test]
    fn test_check_type_scope_perf_without_evidence() {
       let msg = create_commit("perf", None, "changed algorithm", vec![]);
       let stat = " src/lib.rs | 10 +++++++---\n";
-      check_type_scope_consistency(&msg, stat);
+      check_type_scope_consistency(&msg, stat, &ChangeProfile::from_stat(stat));
+   }
+
+   #[test]
+   fn test_type_scope_mismatch_suggests_type_from_paths() {
+      let docs_only = " README.md | 4 ++--\n docs/guide.md | 2 +-\n";
+      let msg = create_commit("feat", None, "added guide", vec![]);
+      assert!(type_scope_mismatch(&msg, docs_only, &ChangeProfile::from_stat(docs_only)).is_none());
+
+      let msg = create_commit("test", None, "added guide", vec![]);
+      let mismatch =
+         type_scope_mismatch(&msg, docs_only, &ChangeProfile::from_stat(docs_only)).unwrap();
+      assert_eq!(mismatch.suggested, Some("docs"));
+
+      // A changelog update alone doesn't point to `docs`
+      let changelog = " CHANGELOG.md | 8 ++++++++\n";
+      let msg = create_commit("ci", None, "updated changelog", vec![]);
+      let mismatch =
+         type_scope_mismatch(&msg, changelog, &ChangeProfile::from_stat(changelog)).unwrap();
+      assert_eq!(mismatch.suggested, None);
+
+      // Mixed files point to no single type
+      let msg = create_commit("docs", None, "updated parser", vec![]);
+      let mixed = " src/parser.rs | 10 +++++++---\n tests/parser_test.rs | 5 +++++\n";
+      assert_eq!(
+         type_scope_mismatch(&msg, mixed, &ChangeProfile::from_stat(mixed))
+            .unwrap()
+            .suggested,
+         None
+      );
+   }
+
+   #[test]
+   fn test_type_scope_mismatch_credits_doc_comments() {
+      let stat = " src/lib.rs | 3 ++-\n";
+      let diff = "diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,1 +1,2 @@
+-/// Parse the input
++/// Parse the input into tokens
++/// (see the grammar in the crate docs)
+";
+      let profile = crate::analysis::classify_change_profile(diff);
+      let msg = create_commit("docs", Some("parser"), "documented token parsing", vec![]);
+      assert!(type_scope_mismatch(&msg, stat, &profile).is_none());
+      assert!(type_scope_mismatch(&msg, stat, &ChangeProfile::from_stat(stat)).is_some());
+
+      // A `test` type over doc comments points to docs
+      let msg = create_commit("test", None, "documented token parsing", vec![]);
+      assert_eq!(type_scope_mismatch(&msg, stat, &profile).unwrap().suggested, Some("docs"));
+   }
+
+   #[test]
+   fn test_profile_type_warning() {
+      let docs = ChangeProfile { docs: 0.9, code: 0.1, ..Default::default() };
+      assert!(profile_type_warning("feat", &docs).unwrap().contains("90%"));
+      assert!(profile_type_warning("docs", &docs).is_none());
+
+      let config = ChangeProfile { config: 0.7, docs: 0.3, ..Default::default() };
+      assert!(
+         profile_type_warning("fix", &config)
+            .unwrap()
+            .contains("no code changed")
+      );
+      assert!(profile_type_warning("chore", &config).is_none());
+      assert!(profile_type_warning("feat", &ChangeProfile::default()).is_none());
+   }
+
+   #[test]
+   fn test_type_scope_policy_warn_keeps_type() {
+      let mut msg = create_commit("docs", Some("ci"), "updated pipeline", vec![]);
+      let stat = " .github/workflows/ci.yml | 20 ++++++++++++++++++++\n";
+      apply_type_scope_policy(
+         &mut msg,
+         stat,
+         &ChangeProfile::from_stat(stat),
+         TypeScopePolicy::Warn,
+      )
+      .unwrap();
+      assert_eq!(msg.commit_type.as_str(), "docs");
+   }
+
+   #[test]
+   fn test_type_scope_policy_correct_applies_suggestion() {
+      let stat = " .github/workflows/ci.yml | 20 ++++++++++++++++++++\n";
+      let mut msg = create_commit("docs", Some("ci"), "updated pipeline", vec![]);
+      apply_type_scope_policy(
+         &mut msg,
+         stat,
+         &ChangeProfile::from_stat(stat),
+         TypeScopePolicy::Correct,
+      )
+      .unwrap();
+      assert_eq!(msg.commit_type.as_str(), "ci");
+
+      let stat = " create mode 100644 src/new_module.rs\n src/lib.rs | 10 +++++++---\n";
+      let mut msg = create_commit("refactor", Some("core"), "split modules", vec![]);
+      apply_type_scope_policy(
+         &mut msg,
+         stat,
+         &ChangeProfile::from_stat(stat),
+         TypeScopePolicy::Correct,
+      )
+      .unwrap();
+      assert_eq!(msg.commit_type.as_str(), "feat");
+
+      // Without a suggestion the type is kept
+      let stat = " src/lib.rs | 10 +++++++---\n";
+      let mut msg = create_commit("perf", None, "changed algorithm", vec![]);
+      apply_type_scope_policy(
+         &mut msg,
+         stat,
+         &ChangeProfile::from_stat(stat),
+         TypeScopePolicy::Correct,
+      )
+      .unwrap();
+      assert_eq!(msg.commit_type.as_str(), "perf");
+   }
+
+   #[test]
+   fn test_type_scope_policy_reject_fails() {
+      let stat = " .github/workflows/ci.yml | 20 ++++++++++++++++++++\n";
+      let mut msg = create_commit("test", None, "added tests", vec![]);
+      let err = apply_type_scope_policy(
+         &mut msg,
+         stat,
+         &ChangeProfile::from_stat(stat),
+         TypeScopePolicy::Reject,
+      )
+      .unwrap_err();
+      assert!(matches!(err, CommitGenError::ValidationError(ref m) if m.contains("'test'")));
+
+      // Consistent messages pass under any policy
+      let stat = " tests/api_test.rs | 50 ++++++++++++++++++++++++++++++++\n";
+      apply_type_scope_policy(
+         &mut msg,
+         stat,
+         &ChangeProfile::from_stat(stat),
+         TypeScopePolicy::Reject,
+      )
+      .unwrap();
+   }
+
+   #[test]
+   fn test_type_scope_policy_reject_warns_without_suggestion() {
+      let stat = " src/lib.rs | 10 +++++++---\n";
+      let mut msg = create_commit("perf", None, "changed algorithm", vec![]);
+      apply_type_scope_policy(
+         &mut msg,
+         stat,
+         &ChangeProfile::from_stat(stat),
+         TypeScopePolicy::Reject,
+      )
+      .unwrap();
+      assert_eq!(msg.commit_type.as_str(), "perf");
+   }
+
+   #[test]
+   fn test_filename_stem_scope_detected() {
+      let stat = " src/api/client.rs | 10 +++++++---\n src/api/mod.rs | 2 +-\n";
+      assert_eq!(filename_stem_scope("client", stat), Some("src/api/client.rs"));
+      assert_eq!(filename_stem_scope("Client", stat), Some("src/api/client.rs"));
+      assert_eq!(filename_stem_scope("api/client", stat), Some("src/api/client.rs"));
+   }
+
+   #[test]
+   fn test_directory_scope_not_flagged() {
+      let stat = " src/api/client.rs | 10 +++++++---\n";
+      assert_eq!(filename_stem_scope("api", stat), None);
+      assert_eq!(filename_stem_scope("parser", stat), None);
+   }
+
+   #[test]
+   fn test_stem_that_is_also_a_directory_not_flagged() {
+      // `client.rs` alongside `client/`: the module has a directory
+      let stat = " src/client.rs | 4 ++--\n src/client/pool.rs | 12 ++++++------\n";
+      assert_eq!(filename_stem_scope("client", stat), None);
+
+      let msg = create_commit("fix", Some("client"), "fixed pool leak", vec![]);
+      check_scope_not_filename(&msg, stat);
    }
 
    #[test]
@@ -867,4 +1644,93 @@ mod tests {
          _ => panic!("Expected SummaryTooLong error"),
       }
    }
+
+   #[test]
+   fn test_first_line_length_multibyte() {
+      // "feat: " is 6, leaving 122 for the summary under a 128 limit
+      let config = CommitConfig::default();
+      let msg = create_commit("feat", None, &format!("added {}", "é".repeat(116)), vec![]);
+      assert!(validate_commit_message(&msg, &config).is_ok());
+      let msg = create_commit("feat", None, &format!("added {}", "é".repeat(117)), vec![]);
+      assert!(matches!(
+         validate_commit_message(&msg, &config),
+         Err(CommitGenError::SummaryTooLong { len: 129, max: 128 })
+      ));
+
+      // Each CJK character takes two columns: 58 after "feat: added " fill 128
+      let config = CommitConfig { summary_length_metric: LengthMetric::Width, ..config };
+      let msg = create_commit("feat", None, &format!("added {}", "中".repeat(58)), vec![]);
+      assert!(validate_commit_message(&msg, &config).is_ok());
+      let msg = create_commit("feat", None, &format!("added {}", "中".repeat(59)), vec![]);
+      assert!(matches!(
+         validate_commit_message(&msg, &config),
+         Err(CommitGenError::SummaryTooLong { len: 130, max: 128 })
+      ));
+
+      // The same line is 71 characters by count
+      let config = CommitConfig { summary_length_metric: LengthMetric::Chars, ..config };
+      assert!(validate_commit_message(&msg, &config).is_ok());
+   }
+
+   #[test]
+   fn test_template_placeholders_are_found() {
+      assert_eq!(
+         find_template_placeholder("feat(<scope>): added retry logic").as_deref(),
+         Some("<scope>")
+      );
+      assert_eq!(find_template_placeholder("<type>: <short summary>").as_deref(), Some("<type>"));
+      assert_eq!(
+         find_template_placeholder("fix: handled timeouts\n\n- TODO: explain why.").as_deref(),
+         Some("TODO")
+      );
+      assert_eq!(find_template_placeholder("fix(api): XXX: fill in").as_deref(), Some("XXX"));
+      assert_eq!(
+         find_template_placeholder("feat(api): {summary}\n\n- {detail}").as_deref(),
+         Some("{summary}")
+      );
+
+      // `TODO` as a word in the middle of an item is prose
+      assert_eq!(
+         find_template_placeholder(
+            "chore: removed stale TODO comments\n\n- Dropped TODO and XXX markers from the parser."
+         ),
+         None
+      );
+
+      // Generics, code spans and ordinary words are not placeholders
+      assert_eq!(
+         find_template_placeholder(
+            "refactor(api): returned Vec<String> from parse\n\n- Replaced `<T>` with `Xxx` and \
+             removed todos; a < b > c."
+         ),
+         None
+      );
+   }
+
+   #[test]
+   fn test_is_vacuous_message() {
+      let config = CommitConfig::default();
+      let none: Vec<String> = vec![];
+
+      assert!(is_vacuous_message("updated files", &none, 5000, &config));
+      assert!(is_vacuous_message("  Updated   files. ", &none, 5000, &config));
+      assert!(is_vacuous_message("Made changes", &["  ".to_string()], 2000, &config));
+
+      // Specific summary, a body, or a small diff are all fine
+      assert!(!is_vacuous_message("updated files list sorting", &none, 5000, &config));
+      assert!(!is_vacuous_message(
+         "updated files",
+         &["Sorted by mtime.".to_string()],
+         5000,
+         &config
+      ));
+      assert!(!is_vacuous_message("updated files", &none, 1999, &config));
+
+      let config = CommitConfig {
+         vacuous_summary_patterns: vec!["tweaked stuff".to_string()],
+         ..CommitConfig::default()
+      };
+      assert!(is_vacuous_message("tweaked stuff", &none, 5000, &config));
+      assert!(!is_vacuous_message("updated files", &none, 5000, &config));
+   }
 }
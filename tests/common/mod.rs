@@ -0,0 +1,211 @@
+//! Scaffolding shared by the integration tests: a mock OpenAI-compatible
+//! endpoint, throwaway repositories, and runners for `git` and `lgit`.
+
+#![allow(dead_code, reason = "each test crate uses a different subset")]
+
+use std::{
+   io::{BufRead, BufReader, Read, Write},
+   net::{TcpListener, TcpStream},
+   path::{Path, PathBuf},
+   process::Command,
+   sync::Arc,
+   thread,
+   time::{SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+
+/// A request the mock endpoint received
+#[derive(Debug, Clone)]
+pub struct Request {
+   /// Request line, e.g. `POST /chat/completions HTTP/1.1`
+   pub line: String,
+   pub body: String,
+}
+
+impl Request {
+   /// Name of the first tool the request offers, empty when it offers none
+   pub fn tool(&self) -> String {
+      let body: serde_json::Value = serde_json::from_str(&self.body).unwrap_or_default();
+      body["tools"][0]["function"]["name"]
+         .as_str()
+         .unwrap_or_default()
+         .to_string()
+   }
+
+   /// Model the request is addressed to
+   pub fn model(&self) -> String {
+      let body: serde_json::Value = serde_json::from_str(&self.body).unwrap_or_default();
+      body["model"].as_str().unwrap_or_default().to_string()
+   }
+}
+
+/// Requests a mock endpoint from [`spawn_recording_mock_api`] has served,
+/// oldest first
+pub type Requests = Arc<Mutex<Vec<Request>>>;
+
+/// Chat completion whose only tool call is `name` with `arguments`
+pub fn tool_call_response(name: &str, arguments: &str) -> String {
+   serde_json::json!({
+      "choices": [{
+         "message": {
+            "role": "assistant",
+            "content": null,
+            "tool_calls": [{
+               "id": "call_0",
+               "type": "function",
+               "function": { "name": name, "arguments": arguments }
+            }]
+         }
+      }]
+   })
+   .to_string()
+}
+
+/// Answer `200 OK` with the JSON `respond` returns, or `500` when it returns
+/// `None`
+fn handle_connection(stream: TcpStream, respond: &dyn Fn(&Request) -> Option<String>) {
+   let mut reader = BufReader::new(stream);
+   let mut request_line = String::new();
+   if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+      return;
+   }
+   let mut content_length = 0;
+   loop {
+      let mut line = String::new();
+      if reader.read_line(&mut line).unwrap_or(0) == 0 {
+         return;
+      }
+      let line = line.trim_end();
+      if line.is_empty() {
+         break;
+      }
+      if let Some((key, value)) = line.split_once(':')
+         && key.eq_ignore_ascii_case("content-length")
+      {
+         content_length = value.trim().parse().unwrap_or(0);
+      }
+   }
+
+   let mut body = vec![0; content_length];
+   reader.read_exact(&mut body).ok();
+   let request = Request {
+      line: request_line.trim_end().to_string(),
+      body: String::from_utf8_lossy(&body).into_owned(),
+   };
+
+   let mut stream = reader.into_inner();
+   match respond(&request) {
+      Some(payload) => write!(
+         stream,
+         "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: \
+          close\r\n\r\n{payload}",
+         payload.len()
+      ),
+      None => write!(
+         stream,
+         "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+      ),
+   }
+   .ok();
+}
+
+/// Serve each request with `respond` (see [`handle_connection`]); returns the
+/// endpoint URL
+pub fn spawn_mock_api(
+   respond: impl Fn(&Request) -> Option<String> + Send + Sync + 'static,
+) -> String {
+   let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+   let addr = listener.local_addr().unwrap();
+   let respond = Arc::new(respond);
+   thread::spawn(move || {
+      for stream in listener.incoming().flatten() {
+         let respond = Arc::clone(&respond);
+         thread::spawn(move || handle_connection(stream, &*respond));
+      }
+   });
+   format!("http://{addr}")
+}
+
+/// Like [`spawn_mock_api`], also recording every request; `respond` gets the
+/// requests served before this one. Returns the endpoint URL and the record.
+pub fn spawn_recording_mock_api(
+   respond: impl Fn(&Request, &[Request]) -> Option<String> + Send + Sync + 'static,
+) -> (String, Requests) {
+   let requests = Requests::default();
+   let recorded = Arc::clone(&requests);
+   let url = spawn_mock_api(move |request| {
+      // Held while responding, so concurrent requests are recorded in order
+      let mut recorded = recorded.lock();
+      let payload = respond(request, &recorded);
+      recorded.push(request.clone());
+      payload
+   });
+   (url, requests)
+}
+
+/// Tool of each recorded request, oldest first
+pub fn tools(requests: &Requests) -> Vec<String> {
+   requests.lock().iter().map(Request::tool).collect()
+}
+
+/// Fresh, empty directory under the system temp dir
+pub fn scratch_dir(name: &str) -> PathBuf {
+   let nanos = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .unwrap()
+      .as_nanos();
+   let dir = std::env::temp_dir().join(format!("lgit-{name}-{}-{nanos}", std::process::id()));
+   std::fs::create_dir_all(&dir).unwrap();
+   dir
+}
+
+/// Run git in `dir`, asserting success; returns trimmed stdout
+pub fn git(dir: &Path, args: &[&str]) -> String {
+   let output = Command::new("git")
+      .args(args)
+      .current_dir(dir)
+      .output()
+      .unwrap();
+   assert!(output.status.success(), "git {args:?} failed");
+   String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Create `repo` and initialize it with a test identity and unsigned commits
+pub fn init_repo(repo: &Path) {
+   std::fs::create_dir_all(repo).unwrap();
+   git(repo, &["init", "-q"]);
+   git(repo, &["config", "user.name", "Test"]);
+   git(repo, &["config", "user.email", "test@example.com"]);
+   git(repo, &["config", "commit.gpgsign", "false"]);
+}
+
+/// Write `root/config.toml` pointing at the mock endpoint `url`, with
+/// `extra` appended; returns its path
+pub fn write_config(root: &Path, url: &str, extra: &str) -> PathBuf {
+   let config_path = root.join("config.toml");
+   std::fs::write(
+      &config_path,
+      format!(
+         "api_base_url = \"{url}\"\napi_mode = \"chat-completions\"\nchangelog_enabled = \
+          false\nmax_retries = 1\n{extra}"
+      ),
+   )
+   .unwrap();
+   config_path
+}
+
+/// The `lgit` binary with `home` as `HOME`, no colors, and none of the
+/// environment overrides that would redirect it away from the test config
+pub fn lgit(home: &Path) -> Command {
+   let mut command = Command::new(env!("CARGO_BIN_EXE_lgit"));
+   command
+      .env("HOME", home)
+      .env("NO_COLOR", "1")
+      .env_remove("LLM_GIT_API_URL")
+      .env_remove("LLM_GIT_API_KEY")
+      .env_remove("LLM_GIT_API_MODE")
+      .env_remove("LLM_GIT_VERBOSE")
+      .env_remove("LLM_GIT_NO_NETWORK");
+   command
+}
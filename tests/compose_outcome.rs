@@ -0,0 +1,58 @@
+//! Library-level check that `compose()` returns the proposed plan in preview
+//! mode without committing.
+//!
+//! Uses a throwaway repository and a mock OpenAI-compatible endpoint, so no
+//! network access or API key is needed.
+
+mod common;
+
+use common::{git, init_repo, scratch_dir, spawn_mock_api, tool_call_response};
+use llm_git::{config::CommitConfig, types::Args};
+
+const COMPOSE_ARGS: &str = r#"{"groups":[{"changes":[{"path":"lib.rs","hunks":["ALL"]}],"type":"feat","scope":"greeting","rationale":"Add a greeting helper","dependencies":[]}]}"#;
+
+#[test]
+fn test_compose_preview_returns_plan() {
+   let repo = scratch_dir("compose-outcome");
+   init_repo(&repo);
+   std::fs::write(repo.join("lib.rs"), "pub fn one() -> u32 {\n   1\n}\n").unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(
+      repo.join("lib.rs"),
+      "pub fn one() -> u32 {\n   1\n}\n\npub fn greet() -> &'static str {\n   \"hello\"\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let config = CommitConfig {
+      api_base_url: spawn_mock_api(|_| {
+         Some(tool_call_response("create_compose_analysis", COMPOSE_ARGS))
+      }),
+      changelog_enabled: false,
+      max_retries: 1,
+      ..CommitConfig::default()
+   };
+   let args = Args {
+      compose: true,
+      compose_preview: true,
+      dir: repo.to_str().unwrap().to_string(),
+      ..Args::default()
+   };
+
+   let outcome = llm_git::compose::compose(&args, &config).unwrap();
+
+   assert_eq!(outcome.rounds.len(), 1);
+   let round = &outcome.rounds[0];
+   assert_eq!(round.analysis.groups.len(), 1);
+   assert_eq!(round.analysis.groups[0].commit_type.as_str(), "feat");
+   assert_eq!(round.analysis.groups[0].changes[0].path, "lib.rs");
+   assert_eq!(round.analysis.dependency_order, vec![0]);
+   assert!(round.commit_hashes.is_empty());
+   assert!(outcome.uncovered_files.is_empty());
+
+   // Preview never commits
+   assert_eq!(git(&repo, &["rev-list", "--count", "HEAD"]), "1");
+
+   std::fs::remove_dir_all(&repo).ok();
+}
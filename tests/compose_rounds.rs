@@ -0,0 +1,111 @@
+//! Two-round compose: the second round's prompts carry the commits the first
+//! round created.
+//!
+//! Uses a throwaway repository and a mock OpenAI-compatible endpoint, so no
+//! network access or API key is needed.
+
+mod common;
+
+use common::{Requests, git, init_repo, scratch_dir, tool_call_response};
+use llm_git::{
+   config::{ApplyErrorStrategy, CommitConfig},
+   types::Args,
+};
+
+/// Round 1 only takes the first hunk of lib.rs, leaving the second for round 2
+const ROUND_ONE_ARGS: &str = r#"{"groups":[{"changes":[{"path":"lib.rs","hunks":[{"start":1,"end":5}]}],"type":"feat","scope":"greeting","rationale":"Add a greeting helper","dependencies":[]}]}"#;
+const ROUND_TWO_ARGS: &str = r#"{"groups":[{"changes":[{"path":"lib.rs","hunks":["ALL"]}],"type":"fix","scope":"math","rationale":"Fix the sum helper","dependencies":[]}]}"#;
+const ANALYSIS_ARGS: &str = r#"{"type":"feat","details":[{"text":"Added a greeting helper to the library.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"added greeting helper"}"#;
+
+fn spawn_mock_api() -> (String, Requests) {
+   common::spawn_recording_mock_api(|request, served| {
+      let tool = request.tool();
+      let payload = match tool.as_str() {
+         "create_compose_analysis" => {
+            let earlier = served.iter().any(|r| r.tool() == tool);
+            tool_call_response(
+               &tool,
+               if earlier {
+                  ROUND_TWO_ARGS
+               } else {
+                  ROUND_ONE_ARGS
+               },
+            )
+         },
+         "create_commit_summary" => tool_call_response(&tool, SUMMARY_ARGS),
+         _ => tool_call_response("create_conventional_analysis", ANALYSIS_ARGS),
+      };
+      Some(payload)
+   })
+}
+
+/// 30 numbered lines, with `first` and `last` replacing lines 2 and 29
+fn source(first: &str, last: &str) -> String {
+   (1..=30)
+      .map(|n| match n {
+         2 => format!("{first}\n"),
+         29 => format!("{last}\n"),
+         n => format!("// line {n}\n"),
+      })
+      .collect()
+}
+
+#[test]
+fn test_second_round_sees_first_round_commits() {
+   let repo = scratch_dir("compose-rounds");
+   init_repo(&repo);
+   std::fs::write(repo.join("lib.rs"), source("// line 2", "// line 29")).unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(
+      repo.join("lib.rs"),
+      source(
+         "pub fn greet() -> &'static str { \"hello\" }",
+         "pub fn sum(a: u32, b: u32) -> u32 { a + b }",
+      ),
+   )
+   .unwrap();
+
+   let (api_base_url, requests) = spawn_mock_api();
+   let config = CommitConfig {
+      api_base_url,
+      changelog_enabled: false,
+      max_retries: 1,
+      compose_on_apply_error: Some(ApplyErrorStrategy::Abort),
+      ..CommitConfig::default()
+   };
+   let args =
+      Args { compose: true, yes: true, dir: repo.to_str().unwrap().to_string(), ..Args::default() };
+
+   let outcome = llm_git::compose::compose(&args, &config).unwrap();
+
+   assert_eq!(outcome.rounds.len(), 2);
+   assert!(outcome.uncovered_files.is_empty(), "{:?}", outcome.uncovered_files);
+   assert_eq!(git(&repo, &["rev-list", "--count", "HEAD"]), "3");
+   let first_subject = git(&repo, &["log", "-1", "--format=%s", "HEAD~1"]);
+   assert_eq!(first_subject, "feat(greeting): added greeting helper");
+
+   let requests = requests.lock();
+   let compose_prompts: Vec<&String> = requests
+      .iter()
+      .filter(|r| r.tool() == "create_compose_analysis")
+      .map(|r| &r.body)
+      .collect();
+   assert_eq!(compose_prompts.len(), 2);
+   assert!(!compose_prompts[0].contains("COMMITS ALREADY CREATED THIS SESSION"));
+   assert!(compose_prompts[1].contains("COMMITS ALREADY CREATED THIS SESSION"));
+   assert!(compose_prompts[1].contains(&first_subject));
+
+   // The second round's message generation sees the first round's commit too
+   let analysis_prompts: Vec<&String> = requests
+      .iter()
+      .filter(|r| r.tool() == "create_conventional_analysis")
+      .map(|r| &r.body)
+      .collect();
+   assert_eq!(analysis_prompts.len(), 2);
+   assert!(!analysis_prompts[0].contains(&first_subject));
+   assert!(analysis_prompts[1].contains(&first_subject));
+
+   std::fs::remove_dir_all(&repo).ok();
+}
@@ -0,0 +1,80 @@
+//! Compose with untracked files: they're planned alongside modified files,
+//! staged whole, and none are left over after the round.
+//!
+//! Uses a throwaway repository and a mock OpenAI-compatible endpoint, so no
+//! network access or API key is needed.
+
+mod common;
+
+use common::{Requests, git, init_repo, scratch_dir, tool_call_response};
+use llm_git::{
+   config::{ApplyErrorStrategy, CommitConfig},
+   types::Args,
+};
+
+/// The modified file goes with a new module; the new docs and log go alone
+const COMPOSE_ARGS: &str = r#"{"groups":[{"changes":[{"path":"lib.rs","hunks":["ALL"]},{"path":"helper.rs","hunks":["ALL"]}],"type":"feat","scope":"helper","rationale":"Add a helper module","dependencies":[]},{"changes":[{"path":"NOTES.md","hunks":["ALL"]},{"path":"trace.log","hunks":["ALL"]}],"type":"docs","rationale":"Add notes","dependencies":[0]}]}"#;
+const ANALYSIS_ARGS: &str = r#"{"type":"feat","details":[{"text":"Added a helper module to the library.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"added helper module"}"#;
+
+fn spawn_mock_api() -> (String, Requests) {
+   common::spawn_recording_mock_api(|request, _| {
+      let payload = match request.tool().as_str() {
+         "create_compose_analysis" => tool_call_response("create_compose_analysis", COMPOSE_ARGS),
+         "create_commit_summary" => tool_call_response("create_commit_summary", SUMMARY_ARGS),
+         _ => tool_call_response("create_conventional_analysis", ANALYSIS_ARGS),
+      };
+      Some(payload)
+   })
+}
+
+#[test]
+fn test_compose_commits_untracked_files() {
+   let repo = scratch_dir("compose-untracked");
+   init_repo(&repo);
+   std::fs::write(repo.join("lib.rs"), "pub fn answer() -> u32 { 41 }\n").unwrap();
+   std::fs::write(repo.join(".gitignore"), "*.tmp\n").unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+
+   std::fs::write(repo.join("lib.rs"), "mod helper;\npub fn answer() -> u32 { 42 }\n").unwrap();
+   std::fs::write(repo.join("helper.rs"), "pub fn helper_body() {}\n").unwrap();
+   std::fs::write(repo.join("NOTES.md"), "# Notes\n").unwrap();
+   std::fs::write(repo.join("trace.log"), "trace_line_content\n".repeat(20)).unwrap();
+   std::fs::write(repo.join("scratch.tmp"), "ignored\n").unwrap();
+
+   let (api_base_url, requests) = spawn_mock_api();
+   let config = CommitConfig {
+      api_base_url,
+      changelog_enabled: false,
+      max_retries: 1,
+      compose_on_apply_error: Some(ApplyErrorStrategy::Abort),
+      compose_untracked_max_bytes: 100,
+      ..CommitConfig::default()
+   };
+   let args =
+      Args { compose: true, yes: true, dir: repo.to_str().unwrap().to_string(), ..Args::default() };
+
+   let outcome = llm_git::compose::compose(&args, &config).unwrap();
+
+   assert_eq!(outcome.rounds.len(), 1);
+   assert!(outcome.uncovered_files.is_empty(), "{:?}", outcome.uncovered_files);
+   assert_eq!(git(&repo, &["rev-list", "--count", "HEAD"]), "3");
+   assert_eq!(git(&repo, &["show", "--format=", "--name-only", "HEAD~1"]), "helper.rs\nlib.rs");
+   assert_eq!(git(&repo, &["show", "--format=", "--name-only", "HEAD"]), "NOTES.md\ntrace.log");
+   assert_eq!(git(&repo, &["status", "--porcelain"]), "");
+
+   // The planner saw the new files' contents, with the oversized one stubbed
+   let requests = requests.lock();
+   let prompt = &requests
+      .iter()
+      .find(|r| r.tool() == "create_compose_analysis")
+      .unwrap()
+      .body;
+   assert!(prompt.contains("+pub fn helper_body() {}"));
+   assert!(prompt.contains("diff --git a/trace.log b/trace.log"));
+   assert!(!prompt.contains("trace_line_content"));
+   assert!(!prompt.contains("scratch.tmp"));
+
+   std::fs::remove_dir_all(&repo).ok();
+}
@@ -0,0 +1,112 @@
+//! `--context-preset`: named snippets from `context_presets` join the user
+//! context that the analysis prompt sees.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint that records each request.
+
+mod common;
+
+use std::{
+   path::{Path, PathBuf},
+   process::Output,
+};
+
+use common::{Requests, git, init_repo, lgit, scratch_dir, tool_call_response, write_config};
+
+const ANALYSIS_ARGS: &str = r#"{"type":"fix","scope":"billing","details":[{"text":"Rounded refunds to whole cents.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"rounded refunds to whole cents"}"#;
+
+const PRESET_TEXT: &str = "This is the payments service, PCI-sensitive.";
+
+fn spawn_mock_api() -> (String, Requests) {
+   common::spawn_recording_mock_api(|request, _| {
+      Some(if request.tool() == "create_commit_summary" {
+         tool_call_response("create_commit_summary", SUMMARY_ARGS)
+      } else {
+         tool_call_response("create_conventional_analysis", ANALYSIS_ARGS)
+      })
+   })
+}
+
+/// Repository with one staged change, plus a config pointing at `url`
+fn setup(name: &str, url: &str) -> (PathBuf, PathBuf) {
+   let root = scratch_dir(&format!("preset-{name}"));
+   let repo = root.join("repo");
+   std::fs::create_dir_all(repo.join("billing")).unwrap();
+
+   init_repo(&repo);
+   std::fs::write(repo.join("billing/refund.rs"), "pub fn refund(x: f64) -> f64 {\n   x\n}\n")
+      .unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(
+      repo.join("billing/refund.rs"),
+      "pub fn refund(x: f64) -> f64 {\n   (x * 100.0).round() / 100.0\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let config_path = write_config(
+      &root,
+      url,
+      &format!(
+         "fast_mode_threshold_chars = 0\n\n[context_presets]\npayments = \
+          \"{PRESET_TEXT}\"\nfrontend = \"Web frontend.\"\n"
+      ),
+   );
+   (root, config_path)
+}
+
+fn run_lgit(root: &Path, config_path: &Path, args: &[&str]) -> Output {
+   lgit(root)
+      .args(["--dry-run", "--quiet", "--dir"])
+      .arg(root.join("repo"))
+      .arg("--config")
+      .arg(config_path)
+      .args(args)
+      .output()
+      .unwrap()
+}
+
+#[test]
+fn test_preset_text_reaches_analysis_prompt() {
+   let (url, requests) = spawn_mock_api();
+   let (root, config_path) = setup("known", &url);
+
+   let output =
+      run_lgit(&root, &config_path, &["--context-preset", "payments", "Refunds", "were", "off"]);
+   let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+   assert!(
+      output.status.success(),
+      "lgit failed: {}\nstdout: {stdout}",
+      String::from_utf8_lossy(&output.stderr)
+   );
+   std::fs::remove_dir_all(&root).ok();
+   assert!(stdout.starts_with("fix(billing): rounded refunds to whole cents\n"), "{stdout}");
+
+   let requests = requests.lock();
+   let analysis_body = &requests
+      .iter()
+      .find(|r| r.tool() == "create_conventional_analysis")
+      .unwrap()
+      .body;
+   assert!(analysis_body.contains(&format!("{PRESET_TEXT} Refunds were off")), "{analysis_body}");
+   assert!(!analysis_body.contains("Web frontend."));
+}
+
+#[test]
+fn test_unknown_preset_fails_before_any_request() {
+   let (url, requests) = spawn_mock_api();
+   let (root, config_path) = setup("unknown", &url);
+
+   let output = run_lgit(&root, &config_path, &["--context-preset", "billing"]);
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(!output.status.success());
+   let stderr = String::from_utf8_lossy(&output.stderr);
+   assert!(
+      stderr.contains("Unknown context preset 'billing' (available: payments, frontend)"),
+      "{stderr}"
+   );
+   assert!(requests.lock().is_empty());
+}
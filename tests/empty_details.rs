@@ -0,0 +1,122 @@
+//! Empty-details retry: an analysis with no detail items for a diff of at
+//! least `min_diff_for_body_lines` changed lines is requested once more,
+//! asking for 2-4 items; smaller diffs keep their empty body.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint that answers with empty details as scripted.
+
+mod common;
+
+use std::{
+   path::{Path, PathBuf},
+   process::Output,
+};
+
+use common::{
+   Requests, git, init_repo, lgit, scratch_dir, tool_call_response, tools, write_config,
+};
+
+const EMPTY_ANALYSIS: &str = r#"{"type":"feat","scope":"billing","details":[],"issue_refs":[]}"#;
+const ANALYSIS_ARGS: &str = r#"{"type":"feat","scope":"billing","details":[{"text":"Added tiered refund rules.","user_visible":false},{"text":"Rounded refunds to whole cents.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"added tiered refund rules"}"#;
+
+/// Answer the first analysis with empty details, or every one with
+/// `always_empty`
+fn spawn_mock_api(always_empty: bool) -> (String, Requests) {
+   common::spawn_recording_mock_api(move |request, served| {
+      let tool = request.tool();
+      let repeat = served.iter().any(|seen| seen.tool() == tool);
+      let arguments = match tool.as_str() {
+         "create_commit_summary" => SUMMARY_ARGS,
+         _ if always_empty || !repeat => EMPTY_ANALYSIS,
+         _ => ANALYSIS_ARGS,
+      };
+      Some(tool_call_response(&tool, arguments))
+   })
+}
+
+/// Repository with `lines` changed lines staged, plus a config pointing at
+/// `url`
+fn setup(name: &str, url: &str, lines: usize) -> (PathBuf, PathBuf) {
+   let root = scratch_dir(&format!("empty-details-{name}"));
+   let repo = root.join("repo");
+   std::fs::create_dir_all(repo.join("billing")).unwrap();
+
+   init_repo(&repo);
+   std::fs::write(repo.join("billing/refund.rs"), "pub fn refund(x: f64) -> f64 {\n   x\n}\n")
+      .unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   let rules = (0..lines)
+      .map(|i| format!("pub const TIER_{i}: f64 = {i}.0;"))
+      .collect::<Vec<_>>()
+      .join("\n");
+   std::fs::write(repo.join("billing/tiers.rs"), rules).unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let config_path =
+      write_config(&root, url, "fast_mode_threshold_chars = 0\nmin_diff_for_body_lines = 10\n");
+   (root, config_path)
+}
+
+fn run_lgit(root: &Path, config_path: &Path, args: &[&str]) -> Output {
+   lgit(root)
+      .args(["--dry-run", "--quiet", "--dir"])
+      .arg(root.join("repo"))
+      .arg("--config")
+      .arg(config_path)
+      .args(args)
+      .output()
+      .unwrap()
+}
+#[test]
+fn test_empty_details_for_sizeable_diff_are_requested_again() {
+   let (url, requests) = spawn_mock_api(false);
+   let (root, config_path) = setup("retry", &url, 12);
+
+   let output = run_lgit(&root, &config_path, &[]);
+   let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+   let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+   std::fs::remove_dir_all(&root).ok();
+   assert!(output.status.success(), "lgit failed: {stderr}\nstdout: {stdout}");
+
+   assert!(stdout.starts_with("feat(billing): added tiered refund rules\n"), "{stdout}");
+   assert!(stdout.contains("- Rounded refunds to whole cents."), "{stdout}");
+   assert!(stderr.contains("no details"), "{stderr}");
+   assert_eq!(tools(&requests), [
+      "create_conventional_analysis",
+      "create_conventional_analysis",
+      "create_commit_summary",
+   ]);
+   let requests = requests.lock();
+   assert!(requests[1].body.contains("Return 2-4 detail items"));
+   assert!(!requests[0].body.contains("Return 2-4 detail items"));
+}
+
+#[test]
+fn test_empty_details_for_small_diff_are_kept() {
+   let (url, requests) = spawn_mock_api(true);
+   let (root, config_path) = setup("small", &url, 3);
+
+   let output = run_lgit(&root, &config_path, &[]);
+   let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+   assert_eq!(stdout.trim_end(), "feat(billing): added tiered refund rules");
+   assert_eq!(tools(&requests), ["create_conventional_analysis", "create_commit_summary"]);
+}
+
+#[test]
+fn test_still_empty_details_keep_the_first_analysis() {
+   let (url, requests) = spawn_mock_api(true);
+   let (root, config_path) = setup("still-empty", &url, 12);
+
+   let output = run_lgit(&root, &config_path, &[]);
+   let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+   assert_eq!(stdout.trim_end(), "feat(billing): added tiered refund rules");
+   assert_eq!(tools(&requests).len(), 3);
+}
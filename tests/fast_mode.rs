@@ -0,0 +1,115 @@
+//! Fast mode routing: small diffs use one `create_commit_message` call, and a
+//! summary that fails the quality check escalates to analysis + summary.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint that records which tool each request asked for.
+
+mod common;
+
+use common::{
+   Requests, git, init_repo, lgit, scratch_dir, tool_call_response, tools, write_config,
+};
+
+const ANALYSIS_ARGS: &str = r#"{"type":"feat","details":[{"text":"Added a greeting helper to the library.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"added greeting helper"}"#;
+
+/// Mock API whose `create_commit_message` calls return `fast_summary`
+fn spawn_mock_api(fast_summary: &'static str) -> (String, Requests) {
+   common::spawn_recording_mock_api(move |request, _| {
+      let tool = request.tool();
+      let arguments = match tool.as_str() {
+         "create_commit_message" => serde_json::json!({
+            "type": "feat",
+            "summary": fast_summary,
+            "details": [{ "text": "Added a greeting helper to the library.", "user_visible": false }],
+            "issue_refs": []
+         })
+         .to_string(),
+         "create_commit_summary" => SUMMARY_ARGS.to_string(),
+         _ => ANALYSIS_ARGS.to_string(),
+      };
+      Some(tool_call_response(&tool, &arguments))
+   })
+}
+
+/// Stage a small change to `file` and run `lgit --subject-only`, returning
+/// stdout and the tools requested, in order
+fn run_subject_only(
+   name: &str,
+   fast_summary: &'static str,
+   file: &str,
+   extra_args: &[&str],
+) -> (String, Vec<String>) {
+   let root = scratch_dir(name);
+   let repo = root.join("repo");
+   init_repo(&repo);
+   std::fs::write(repo.join(file), "pub fn one() -> u32 {\n   1\n}\n").unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(
+      repo.join(file),
+      "pub fn one() -> u32 {\n   1\n}\n\npub fn greet() -> &'static str {\n   \"hello\"\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let (url, requests) = spawn_mock_api(fast_summary);
+   let config_path = write_config(&root, &url, "");
+
+   let output = lgit(&root)
+      .arg("--subject-only")
+      .args(extra_args)
+      .arg("--dir")
+      .arg(&repo)
+      .arg("--config")
+      .arg(&config_path)
+      .output()
+      .unwrap();
+
+   let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+   assert!(
+      output.status.success(),
+      "lgit failed: {}\nstdout: {stdout}",
+      String::from_utf8_lossy(&output.stderr)
+   );
+   std::fs::remove_dir_all(&root).ok();
+
+   (stdout, tools(&requests))
+}
+
+#[test]
+fn test_small_diff_uses_single_call() {
+   let (stdout, calls) = run_subject_only("single", "added greeting helper", "lib.rs", &[]);
+   assert_eq!(stdout, "feat: added greeting helper\n");
+   assert_eq!(calls, vec!["create_commit_message"]);
+}
+
+#[test]
+fn test_invalid_fast_summary_escalates_to_two_calls() {
+   // Not a past-tense verb: fails the summary quality check
+   let (stdout, calls) =
+      run_subject_only("escalate", "greeting helper for the library", "lib.rs", &[]);
+   assert_eq!(stdout, "feat: added greeting helper\n");
+   assert_eq!(calls, vec![
+      "create_commit_message",
+      "create_conventional_analysis",
+      "create_commit_summary"
+   ]);
+}
+
+#[test]
+fn test_no_fast_skips_combined_call() {
+   let (stdout, calls) =
+      run_subject_only("disabled", "added greeting helper", "lib.rs", &["--no-fast"]);
+   assert_eq!(stdout, "feat: added greeting helper\n");
+   assert_eq!(calls, vec!["create_conventional_analysis", "create_commit_summary"]);
+}
+
+#[test]
+fn test_reconciled_type_regenerates_fast_summary() {
+   // The model calls a docs-only change `feat`; `docs_only_as_docs` retypes it,
+   // so the fast summary written for `feat` is replaced
+   let (stdout, calls) = run_subject_only("retyped", "added greeting helper", "GUIDE.md", &[]);
+   assert_eq!(stdout, "docs: added greeting helper\n");
+   assert_eq!(calls, vec!["create_commit_message", "create_commit_summary"]);
+}
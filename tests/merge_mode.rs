@@ -0,0 +1,159 @@
+//! `--mode merge`: generating the message for a resolved merge conflict and
+//! concluding the merge.
+//!
+//! Runs the real binary against a throwaway repository with a scripted
+//! conflict and a mock OpenAI-compatible endpoint.
+
+mod common;
+
+use std::{
+   path::{Path, PathBuf},
+   process::{Command, Output},
+};
+
+use common::{Requests, git, init_repo, lgit, scratch_dir, tool_call_response, write_config};
+
+const ANALYSIS_ARGS: &str = r#"{"type":"fix","scope":"retry","details":[{"text":"Kept the feature branch's exponential backoff and main's retry cap.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"combined backoff with the retry cap"}"#;
+
+fn spawn_mock_api() -> (String, Requests) {
+   common::spawn_recording_mock_api(|request, _| {
+      Some(if request.tool() == "create_commit_summary" {
+         tool_call_response("create_commit_summary", SUMMARY_ARGS)
+      } else {
+         tool_call_response("create_conventional_analysis", ANALYSIS_ARGS)
+      })
+   })
+}
+
+/// Repository stopped in a conflicting merge of `feature` into the default
+/// branch, plus a config pointing at `url`
+fn setup(name: &str, url: &str, extra_config: &str) -> (PathBuf, PathBuf) {
+   let root = scratch_dir(&format!("merge-{name}"));
+   let repo = root.join("repo");
+   init_repo(&repo);
+   std::fs::write(repo.join("retry.rs"), "pub fn delay(attempt: u32) -> u32 {\n   100\n}\n")
+      .unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "feat: add retry delay"]);
+
+   git(&repo, &["checkout", "-q", "-b", "feature"]);
+   std::fs::write(
+      repo.join("retry.rs"),
+      "pub fn delay(attempt: u32) -> u32 {\n   100 << attempt\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["commit", "-q", "-am", "feat: exponential backoff"]);
+
+   git(&repo, &["checkout", "-q", "-"]);
+   std::fs::write(
+      repo.join("retry.rs"),
+      "pub fn delay(attempt: u32) -> u32 {\n   100.min(attempt * 1000)\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["commit", "-q", "-am", "fix: cap retry delay"]);
+
+   let status = Command::new("git")
+      .args(["merge", "-q", "feature"])
+      .current_dir(&repo)
+      .output()
+      .unwrap()
+      .status;
+   assert!(!status.success(), "merge should conflict");
+
+   let config_path =
+      write_config(&root, url, &format!("fast_mode_threshold_chars = 0\n{extra_config}"));
+   (root, config_path)
+}
+
+/// Resolve the conflict by combining both sides and stage it
+fn resolve(repo: &Path) {
+   std::fs::write(
+      repo.join("retry.rs"),
+      "pub fn delay(attempt: u32) -> u32 {\n   (100 << attempt).min(1000)\n}\n",
+   )
+   .unwrap();
+   git(repo, &["add", "retry.rs"]);
+}
+
+fn run_lgit(root: &Path, config_path: &Path, args: &[&str]) -> Output {
+   lgit(root)
+      .args(["--quiet", "--mode", "merge", "--dir"])
+      .arg(root.join("repo"))
+      .arg("--config")
+      .arg(config_path)
+      .args(args)
+      .output()
+      .unwrap()
+}
+
+#[test]
+fn test_merge_mode_concludes_merge_keeping_subject() {
+   let (url, requests) = spawn_mock_api();
+   let (root, config_path) = setup("keep", &url, "");
+   let repo = root.join("repo");
+   resolve(&repo);
+
+   let output = run_lgit(&root, &config_path, &[]);
+   let message = git(&repo, &["log", "-1", "--format=%B"]);
+   let parents = git(&repo, &["log", "-1", "--format=%P"]);
+   let merge_head = repo.join(".git/MERGE_HEAD").exists();
+   std::fs::remove_dir_all(&root).ok();
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+
+   // A real merge commit, and the merge state is gone
+   assert_eq!(parents.split_whitespace().count(), 2, "{parents}");
+   assert!(!merge_head);
+   assert!(
+      message.starts_with(
+         "Merge branch 'feature'\n\nfix(retry): combined backoff with the retry cap\n\n- Kept"
+      ),
+      "{message}"
+   );
+
+   // The prompt says it's a conflict resolution and names the file; the diff
+   // is the resolution against git's conflicted merge result
+   let requests = requests.lock();
+   let analysis = &requests[0].body;
+   assert!(analysis.contains("conflict resolution"), "{analysis}");
+   assert!(analysis.contains("Conflicted files: retry.rs."), "{analysis}");
+   assert!(analysis.contains("<<<<<<<"), "{analysis}");
+}
+
+#[test]
+fn test_merge_subject_replace() {
+   let (url, _requests) = spawn_mock_api();
+   let (root, config_path) = setup("replace", &url, "merge_subject = \"replace\"\n");
+   let repo = root.join("repo");
+   resolve(&repo);
+
+   let output = run_lgit(&root, &config_path, &["--dry-run"]);
+   let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+   let merge_head = repo.join(".git/MERGE_HEAD").exists();
+   std::fs::remove_dir_all(&root).ok();
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+
+   assert!(stdout.starts_with("fix(retry): combined backoff with the retry cap\n"), "{stdout}");
+   assert!(merge_head, "dry run must leave the merge in progress");
+}
+
+#[test]
+fn test_merge_mode_requires_resolved_merge() {
+   let (url, requests) = spawn_mock_api();
+   let (root, config_path) = setup("unresolved", &url, "");
+   let repo = root.join("repo");
+
+   let unresolved = run_lgit(&root, &config_path, &["--dry-run"]);
+   git(&repo, &["merge", "--abort"]);
+   let no_merge = run_lgit(&root, &config_path, &["--dry-run"]);
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(!unresolved.status.success());
+   let stderr = String::from_utf8_lossy(&unresolved.stderr);
+   assert!(stderr.contains("remaining conflicts first: retry.rs"), "{stderr}");
+
+   assert!(!no_merge.status.success());
+   let stderr = String::from_utf8_lossy(&no_merge.stderr);
+   assert!(stderr.contains("requires a merge in progress"), "{stderr}");
+   assert!(requests.lock().is_empty());
+}
@@ -0,0 +1,91 @@
+//! End-to-end check that a diff changing only file modes gets a deterministic
+//! `chore` message without calling the model.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint that counts the requests it gets.
+
+mod common;
+
+use std::{
+   path::PathBuf,
+   process::Output,
+   sync::{
+      Arc,
+      atomic::{AtomicUsize, Ordering},
+   },
+};
+
+use common::{git, init_repo, lgit, scratch_dir, write_config};
+
+/// Mock endpoint URL and the number of requests it has served; every request
+/// fails
+fn spawn_mock_api() -> (String, Arc<AtomicUsize>) {
+   let calls = Arc::new(AtomicUsize::new(0));
+   let counter = Arc::clone(&calls);
+   let url = common::spawn_mock_api(move |_| {
+      counter.fetch_add(1, Ordering::SeqCst);
+      None
+   });
+   (url, calls)
+}
+
+/// Run `lgit --dry-run` on a fresh repository where `files` were made
+/// executable; returns the scratch root and the output
+fn run_lgit(api_base_url: &str, files: &[&str]) -> (PathBuf, Output) {
+   let root = scratch_dir("mode-only");
+   let repo = root.join("repo");
+   init_repo(&repo);
+   git(&repo, &["config", "core.fileMode", "true"]);
+   for file in files {
+      let path = repo.join(file);
+      std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+      std::fs::write(&path, "#!/bin/sh\necho deploy\n").unwrap();
+   }
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   for file in files {
+      git(&repo, &["update-index", "--chmod=+x", file]);
+   }
+
+   let config_path = write_config(&root, api_base_url, "");
+
+   let output = lgit(&root)
+      .arg("--dry-run")
+      .arg("--dir")
+      .arg(&repo)
+      .arg("--config")
+      .arg(&config_path)
+      .output()
+      .unwrap();
+   (root, output)
+}
+
+#[test]
+fn test_mode_only_change_is_deterministic() {
+   let (base_url, calls) = spawn_mock_api();
+   let (root, output) = run_lgit(&base_url, &["scripts/deploy.sh"]);
+   std::fs::remove_dir_all(&root).ok();
+
+   let stdout = String::from_utf8_lossy(&output.stdout);
+   assert!(
+      output.status.success(),
+      "lgit failed: {}\nstdout: {stdout}",
+      String::from_utf8_lossy(&output.stderr)
+   );
+   assert!(stdout.contains("chore: changed file mode of deploy.sh\""), "{stdout}");
+   assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_mode_only_change_of_several_files() {
+   let (base_url, calls) = spawn_mock_api();
+   let (root, output) = run_lgit(&base_url, &["a.sh", "b.sh"]);
+   std::fs::remove_dir_all(&root).ok();
+
+   let stdout = String::from_utf8_lossy(&output.stdout);
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+   assert!(stdout.contains("chore: changed file mode of 2 files"), "{stdout}");
+   assert!(stdout.contains("Changed file mode of a.sh to 100755."), "{stdout}");
+   assert!(stdout.contains("Changed file mode of b.sh to 100755."), "{stdout}");
+   assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
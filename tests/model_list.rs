@@ -0,0 +1,132 @@
+//! `--model-list` and `validate_models`: the endpoint's model listing is
+//! checked against the configured models.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint that serves `/models` and records each request.
+
+mod common;
+
+use std::{
+   path::{Path, PathBuf},
+   process::Output,
+};
+
+use common::{Requests, git, init_repo, lgit, scratch_dir, tool_call_response, write_config};
+
+const MODELS: &str = r#"{"object":"list","data":[{"id":"claude-sonnet-4.5","object":"model"},{"id":"claude-haiku-4-5","object":"model"},{"id":"local-model","object":"model"}]}"#;
+const ANALYSIS_ARGS: &str = r#"{"type":"fix","scope":"billing","details":[{"text":"Rounded refunds to whole cents.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"rounded refunds to whole cents"}"#;
+
+fn spawn_mock_api() -> (String, Requests) {
+   common::spawn_recording_mock_api(|request, _| {
+      Some(if request.line.starts_with("GET /models") {
+         MODELS.to_string()
+      } else if request.tool() == "create_commit_summary" {
+         tool_call_response("create_commit_summary", SUMMARY_ARGS)
+      } else {
+         tool_call_response("create_conventional_analysis", ANALYSIS_ARGS)
+      })
+   })
+}
+
+/// Repository with one staged change, plus a config pointing at `url`
+fn setup(name: &str, url: &str, extra_config: &str) -> (PathBuf, PathBuf) {
+   let root = scratch_dir(&format!("models-{name}"));
+   let repo = root.join("repo");
+   std::fs::create_dir_all(repo.join("billing")).unwrap();
+
+   init_repo(&repo);
+   std::fs::write(repo.join("billing/refund.rs"), "pub fn refund(x: f64) -> f64 {\n   x\n}\n")
+      .unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(
+      repo.join("billing/refund.rs"),
+      "pub fn refund(x: f64) -> f64 {\n   (x * 100.0).round() / 100.0\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let config_path =
+      write_config(&root, url, &format!("fast_mode_threshold_chars = 0\n{extra_config}"));
+   (root, config_path)
+}
+
+fn run_lgit(root: &Path, config_path: &Path, args: &[&str]) -> Output {
+   lgit(root)
+      .args(["--dry-run", "--quiet", "--dir"])
+      .arg(root.join("repo"))
+      .arg("--config")
+      .arg(config_path)
+      .args(args)
+      .output()
+      .unwrap()
+}
+
+#[test]
+fn test_model_list_shows_aliases_and_flags_missing_models() {
+   let (url, _) = spawn_mock_api();
+   let (root, config_path) = setup(
+      "list",
+      &url,
+      "model = \"claude-sonet-4.5\"\nsummary_model_cascade = [\"haiku\", \"local-model\"]\n",
+   );
+
+   let output = run_lgit(&root, &config_path, &["--model-list"]);
+   std::fs::remove_dir_all(&root).ok();
+   let stdout = String::from_utf8_lossy(&output.stdout);
+
+   assert!(!output.status.success(), "{stdout}");
+   assert!(stdout.contains(&format!("3 models at {url}/models")), "{stdout}");
+   assert!(stdout.contains("  claude-sonnet-4.5 (sonnet, s)\n"), "{stdout}");
+   assert!(stdout.contains("  claude-haiku-4-5 (haiku, h)\n"), "{stdout}");
+   assert!(stdout.contains("  local-model\n"), "{stdout}");
+   assert!(
+      stdout.contains(
+         "missing: model = claude-sonet-4.5 is not offered, did you mean claude-sonnet-4.5?"
+      ),
+      "{stdout}"
+   );
+   assert_eq!(stdout.matches("missing:").count(), 1, "{stdout}");
+   assert!(String::from_utf8_lossy(&output.stderr).contains("1 configured model(s)"));
+}
+
+#[test]
+fn test_validate_models_fails_before_any_diff_is_sent() {
+   let (url, requests) = spawn_mock_api();
+   let (root, config_path) =
+      setup("missing", &url, "model = \"claude-sonet-4.5\"\nvalidate_models = true\n");
+
+   let output = run_lgit(&root, &config_path, &[]);
+   std::fs::remove_dir_all(&root).ok();
+   let stderr = String::from_utf8_lossy(&output.stderr);
+
+   assert!(!output.status.success());
+   assert!(stderr.contains("ModelNotFound"), "{stderr}");
+   assert!(stderr.contains("model: \"claude-sonet-4.5\", field: \"model\""), "{stderr}");
+   assert!(stderr.contains("did you mean claude-sonnet-4.5?"), "{stderr}");
+   let requests = requests.lock();
+   assert_eq!(requests.len(), 1);
+   assert_eq!(requests[0].line, "GET /models HTTP/1.1");
+}
+
+#[test]
+fn test_validate_models_passes_for_offered_models() {
+   let (url, requests) = spawn_mock_api();
+   let (root, config_path) = setup("offered", &url, "validate_models = true\n");
+
+   // `--model` aliases resolve before the check
+   let output = run_lgit(&root, &config_path, &["--model", "sonnet"]);
+   std::fs::remove_dir_all(&root).ok();
+   let stdout = String::from_utf8_lossy(&output.stdout);
+
+   assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+   assert!(stdout.starts_with("fix(billing): rounded refunds to whole cents\n"), "{stdout}");
+   let requests = requests.lock();
+   assert_eq!(requests[0].line, "GET /models HTTP/1.1");
+   assert!(
+      requests[1..]
+         .iter()
+         .all(|request| request.line.starts_with("POST "))
+   );
+}
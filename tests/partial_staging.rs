@@ -0,0 +1,123 @@
+//! End-to-end checks that a partially staged file (`git add -p`) is analyzed
+//! from its staged hunks only, on the direct and the map-reduce paths.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint, so no network access or API key is needed.
+
+mod common;
+
+use common::{Requests, git, init_repo, lgit, scratch_dir, tool_call_response, write_config};
+
+const OBSERVATION_ARGS: &str = r#"{"observations":["Changed a line."]}"#;
+const ANALYSIS_ARGS: &str = r#"{"type":"refactor","details":[{"text":"Reworded the staged lines.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"reworded staged lines"}"#;
+
+const STAGED_MARKER: &str = "staged change";
+const UNSTAGED_MARKER: &str = "unstaged change";
+
+fn spawn_mock_api() -> (String, Requests) {
+   common::spawn_recording_mock_api(|request, _| {
+      let tool = request.tool();
+      let arguments = match tool.as_str() {
+         "create_file_observation" => OBSERVATION_ARGS,
+         "create_commit_summary" => SUMMARY_ARGS,
+         _ => ANALYSIS_ARGS,
+      };
+      Some(tool_call_response(&tool, arguments))
+   })
+}
+
+/// Twelve numbered lines, with lines 2 and 11 optionally replaced
+fn file_contents(name: &str, line_2: Option<&str>, line_11: Option<&str>) -> String {
+   (1..=12)
+      .map(|n| match (n, line_2, line_11) {
+         (2, Some(text), _) | (11, _, Some(text)) => format!("{text} in {name}\n"),
+         _ => format!("line {n} of {name}\n"),
+      })
+      .collect()
+}
+
+/// Stage one of two hunks in each of `files` (as `git add -p` would), run
+/// `lgit --subject-only --debug-output`, and check the analysis saw the staged
+/// hunks only; returns the request bodies the model received
+fn assert_analyzes_staged_hunks_only(files: &[&str], extra_config: &str) -> Vec<String> {
+   let (base_url, requests) = spawn_mock_api();
+   let root = scratch_dir("partial-staging");
+   let repo = root.join("repo");
+   init_repo(&repo);
+   for name in files {
+      std::fs::write(repo.join(name), file_contents(name, None, None)).unwrap();
+   }
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+
+   // Index: the line 2 hunk only; working tree: both hunks
+   for name in files {
+      std::fs::write(repo.join(name), file_contents(name, Some(STAGED_MARKER), None)).unwrap();
+   }
+   git(&repo, &["add", "-A"]);
+   for name in files {
+      std::fs::write(
+         repo.join(name),
+         file_contents(name, Some(STAGED_MARKER), Some(UNSTAGED_MARKER)),
+      )
+      .unwrap();
+   }
+
+   let config_path =
+      write_config(&root, &base_url, &format!("fast_mode_threshold_chars = 0\n{extra_config}"));
+   let debug_dir = root.join("debug");
+
+   let output = lgit(&root)
+      .arg("--subject-only")
+      .arg("--dir")
+      .arg(&repo)
+      .arg("--config")
+      .arg(&config_path)
+      .arg("--debug-output")
+      .arg(&debug_dir)
+      .output()
+      .unwrap();
+
+   let staged_diff = git(&repo, &["diff", "--cached"]);
+   let staged_stat = git(&repo, &["diff", "--cached", "--stat"]);
+   let analyzed_diff = std::fs::read_to_string(debug_dir.join("diff.patch")).unwrap_or_default();
+   let analyzed_stat = std::fs::read_to_string(debug_dir.join("stat.txt")).unwrap_or_default();
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+   assert_eq!(String::from_utf8_lossy(&output.stdout), "refactor: reworded staged lines\n");
+   assert_eq!(analyzed_diff.trim(), staged_diff);
+   assert_eq!(analyzed_stat.trim(), staged_stat);
+
+   let requests: Vec<String> = requests.lock().iter().map(|r| r.body.clone()).collect();
+   assert!(requests.iter().any(|body| body.contains(STAGED_MARKER)));
+   assert!(
+      requests.iter().all(|body| !body.contains(UNSTAGED_MARKER)),
+      "an unstaged hunk reached the model"
+   );
+   requests
+}
+
+#[test]
+fn test_partially_staged_file_analyzes_staged_hunks() {
+   let requests = assert_analyzes_staged_hunks_only(&["lib.rs"], "map_reduce_enabled = false\n");
+   assert!(
+      requests
+         .iter()
+         .all(|body| !body.contains("create_file_observation"))
+   );
+}
+
+#[test]
+fn test_partially_staged_files_map_reduce_analyzes_staged_hunks() {
+   let requests = assert_analyzes_staged_hunks_only(
+      &["a.rs", "b.rs", "c.rs", "d.rs"],
+      "map_reduce_enabled = true\n",
+   );
+   let observations = requests
+      .iter()
+      .filter(|body| body.contains("create_file_observation"))
+      .count();
+   assert_eq!(observations, 4, "every staged file goes through the map phase");
+}
@@ -0,0 +1,105 @@
+//! `protected_branches`: staged mode generates and prints the message on a
+//! protected branch but doesn't commit it without `--allow-protected`.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint.
+
+mod common;
+
+use std::{
+   path::{Path, PathBuf},
+   process::Output,
+};
+
+use common::{git, init_repo, lgit, scratch_dir, tool_call_response, write_config};
+
+const ANALYSIS_ARGS: &str = r#"{"type":"fix","scope":"billing","details":[{"text":"Rounded refunds to whole cents.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"rounded refunds to whole cents"}"#;
+
+fn spawn_mock_api() -> String {
+   common::spawn_mock_api(|request| {
+      Some(if request.body.contains("create_commit_summary") {
+         tool_call_response("create_commit_summary", SUMMARY_ARGS)
+      } else {
+         tool_call_response("create_conventional_analysis", ANALYSIS_ARGS)
+      })
+   })
+}
+
+/// Repository on `branch` with one staged change, plus a config pointing at
+/// `url`
+fn setup(name: &str, url: &str, branch: &str) -> (PathBuf, PathBuf) {
+   let root = scratch_dir(&format!("protected-{name}"));
+   let repo = root.join("repo");
+   std::fs::create_dir_all(repo.join("billing")).unwrap();
+
+   init_repo(&repo);
+   git(&repo, &["symbolic-ref", "HEAD", &format!("refs/heads/{branch}")]);
+   std::fs::write(repo.join("billing/refund.rs"), "pub fn refund(x: f64) -> f64 {\n   x\n}\n")
+      .unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(
+      repo.join("billing/refund.rs"),
+      "pub fn refund(x: f64) -> f64 {\n   (x * 100.0).round() / 100.0\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let config_path = write_config(&root, url, "fast_mode_threshold_chars = 0\n");
+   (root, config_path)
+}
+
+fn run_lgit(root: &Path, config_path: &Path, args: &[&str]) -> Output {
+   lgit(root)
+      .args(["--quiet", "--dir"])
+      .arg(root.join("repo"))
+      .arg("--config")
+      .arg(config_path)
+      .args(args)
+      .output()
+      .unwrap()
+}
+
+#[test]
+fn test_protected_branch_blocks_commit() {
+   let url = spawn_mock_api();
+   let (root, config_path) = setup("blocked", &url, "main");
+
+   let output = run_lgit(&root, &config_path, &[]);
+   let subject = git(&root.join("repo"), &["log", "-1", "--format=%s"]);
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(!output.status.success());
+   let stdout = String::from_utf8_lossy(&output.stdout);
+   assert!(stdout.starts_with("fix(billing): rounded refunds to whole cents\n"), "{stdout}");
+   let stderr = String::from_utf8_lossy(&output.stderr);
+   assert!(stderr.contains("ProtectedBranch { branch: \"main\" }"), "{stderr}");
+   assert_eq!(subject, "chore: initial commit");
+}
+
+#[test]
+fn test_allow_protected_commits() {
+   let url = spawn_mock_api();
+   let (root, config_path) = setup("allowed", &url, "main");
+
+   let output = run_lgit(&root, &config_path, &["--allow-protected"]);
+   let subject = git(&root.join("repo"), &["log", "-1", "--format=%s"]);
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+   assert_eq!(subject, "fix(billing): rounded refunds to whole cents");
+}
+
+#[test]
+fn test_unprotected_branch_commits() {
+   let url = spawn_mock_api();
+   let (root, config_path) = setup("feature", &url, "feature/refunds");
+
+   let output = run_lgit(&root, &config_path, &[]);
+   let subject = git(&root.join("repo"), &["log", "-1", "--format=%s"]);
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+   assert_eq!(subject, "fix(billing): rounded refunds to whole cents");
+}
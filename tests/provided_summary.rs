@@ -0,0 +1,109 @@
+//! End-to-end checks that `--summary` keeps the given subject and only
+//! generates the body.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint, so no network access or API key is needed.
+
+mod common;
+
+use std::{
+   path::PathBuf,
+   process::Output,
+   sync::{
+      Arc,
+      atomic::{AtomicUsize, Ordering},
+   },
+};
+
+use common::{git, init_repo, lgit, scratch_dir, tool_call_response, write_config};
+
+const ANALYSIS_ARGS: &str = r#"{"type":"feat","details":[{"text":"Added a greeting helper to the library.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"added greeting helper"}"#;
+
+/// Mock endpoint URL and the number of summary requests it has served
+fn spawn_mock_api() -> (String, Arc<AtomicUsize>) {
+   let summary_calls = Arc::new(AtomicUsize::new(0));
+   let counter = Arc::clone(&summary_calls);
+   let url = common::spawn_mock_api(move |request| {
+      Some(if request.body.contains("create_commit_summary") {
+         counter.fetch_add(1, Ordering::SeqCst);
+         tool_call_response("create_commit_summary", SUMMARY_ARGS)
+      } else {
+         tool_call_response("create_conventional_analysis", ANALYSIS_ARGS)
+      })
+   });
+   (url, summary_calls)
+}
+
+/// Run `lgit --subject-only` on a fresh repository with one staged change;
+/// returns the scratch root, the repository and the output
+/// Run `lgit --subject-only` on a fresh repository with one staged change;
+/// returns the scratch root and the output
+fn run_lgit(api_base_url: &str, extra_args: &[&str]) -> (PathBuf, Output) {
+   let root = scratch_dir("provided-summary");
+   let repo = root.join("repo");
+   init_repo(&repo);
+   std::fs::write(repo.join("lib.rs"), "pub fn one() -> u32 {\n   1\n}\n").unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(
+      repo.join("lib.rs"),
+      "pub fn one() -> u32 {\n   1\n}\n\npub fn greet() -> &'static str {\n   \"hello\"\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let config_path = write_config(&root, api_base_url, "fast_mode_threshold_chars = 0\n");
+
+   let output = lgit(&root)
+      .arg("--subject-only")
+      .arg("--dir")
+      .arg(&repo)
+      .arg("--config")
+      .arg(&config_path)
+      .args(extra_args)
+      .output()
+      .unwrap();
+   (root, output)
+}
+
+#[test]
+fn test_provided_summary_skips_summary_call() {
+   let (base_url, summary_calls) = spawn_mock_api();
+   let (root, output) = run_lgit(&base_url, &["--summary", "Added a friendly greeting."]);
+   std::fs::remove_dir_all(&root).ok();
+
+   let stdout = String::from_utf8_lossy(&output.stdout);
+   assert!(
+      output.status.success(),
+      "lgit failed: {}\nstdout: {stdout}",
+      String::from_utf8_lossy(&output.stderr)
+   );
+   // Normalized (lowercase, no period), type still from the analysis
+   assert_eq!(stdout, "feat: added a friendly greeting\n");
+   assert_eq!(summary_calls.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_generated_summary_calls_summary_model() {
+   let (base_url, summary_calls) = spawn_mock_api();
+   let (root, output) = run_lgit(&base_url, &[]);
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+   assert_eq!(String::from_utf8_lossy(&output.stdout), "feat: added greeting helper\n");
+   assert_eq!(summary_calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_provided_summary_over_hard_limit_fails() {
+   let (base_url, summary_calls) = spawn_mock_api();
+   let long = format!("added {}", "greeting ".repeat(20));
+   let (root, output) = run_lgit(&base_url, &["--summary", &long]);
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(!output.status.success());
+   let stderr = String::from_utf8_lossy(&output.stderr);
+   assert!(stderr.contains("Summary too long"), "{stderr}");
+   assert_eq!(summary_calls.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,124 @@
+//! End-to-end checks that a version bump with its changelog is committed as
+//! `chore(release)` without calling the model, and tagged with `release_tag`.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint that counts the requests it gets.
+
+mod common;
+
+use std::{
+   path::PathBuf,
+   process::Output,
+   sync::{
+      Arc,
+      atomic::{AtomicUsize, Ordering},
+   },
+};
+
+use common::{git, init_repo, lgit, scratch_dir, write_config};
+
+/// Mock endpoint URL and the number of requests it has served; every request
+/// fails
+fn spawn_mock_api() -> (String, Arc<AtomicUsize>) {
+   let calls = Arc::new(AtomicUsize::new(0));
+   let counter = Arc::clone(&calls);
+   let url = common::spawn_mock_api(move |_| {
+      counter.fetch_add(1, Ordering::SeqCst);
+      None
+   });
+   (url, calls)
+}
+
+fn manifest(version: &str) -> String {
+   format!("[package]\nname = \"demo\"\nversion = \"{version}\"\nedition = \"2024\"\n")
+}
+
+/// Run `lgit` with `release_tag = true` on a fresh repository with a staged
+/// 1.3.2 -> 1.4.0 bump on a branch tracking the bare `remote.git`, and
+/// `extra_config` appended to the config; returns the scratch root, the
+/// repository and the output
+fn run_lgit(
+   api_base_url: &str,
+   extra_config: &str,
+   extra_args: &[&str],
+) -> (PathBuf, PathBuf, Output) {
+   let root = scratch_dir("release-commit");
+   let repo = root.join("repo");
+   init_repo(&repo);
+   git(&repo, &["config", "tag.gpgsign", "false"]);
+   std::fs::write(repo.join("Cargo.toml"), manifest("1.3.2")).unwrap();
+   std::fs::write(repo.join("CHANGELOG.md"), "# Changelog\n").unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   git(&repo, &["checkout", "-q", "-b", "release-1.4"]);
+   let remote = root.join("remote.git");
+   git(&root, &["init", "-q", "--bare", remote.to_str().unwrap()]);
+   git(&repo, &["remote", "add", "origin", remote.to_str().unwrap()]);
+   git(&repo, &["push", "-q", "-u", "origin", "release-1.4"]);
+   std::fs::write(repo.join("Cargo.toml"), manifest("1.4.0")).unwrap();
+   std::fs::write(repo.join("CHANGELOG.md"), "# Changelog\n\n## [1.4.0]\n\n- Added demo.\n")
+      .unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let config_path =
+      write_config(&root, api_base_url, &format!("release_tag = true\n{extra_config}"));
+
+   let output = lgit(&root)
+      .arg("--dir")
+      .arg(&repo)
+      .arg("--config")
+      .arg(&config_path)
+      .args(extra_args)
+      .output()
+      .unwrap();
+   (root, repo, output)
+}
+
+#[test]
+fn test_release_commit_is_deterministic_and_tagged() {
+   let (base_url, calls) = spawn_mock_api();
+   let (root, repo, output) = run_lgit(&base_url, "", &[]);
+
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+   assert_eq!(git(&repo, &["log", "-1", "--format=%s"]), "chore(release): prepared release v1.4.0");
+   let body = git(&repo, &["log", "-1", "--format=%b"]);
+   assert!(body.contains("Bumped version in Cargo.toml from 1.3.2 to 1.4.0."), "{body}");
+   assert_eq!(git(&repo, &["tag", "--points-at", "HEAD"]), "v1.4.0");
+   assert_eq!(git(&repo, &["cat-file", "-t", "v1.4.0"]), "tag");
+   assert_eq!(calls.load(Ordering::SeqCst), 0);
+   std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn test_push_sends_release_tag() {
+   let (base_url, _) = spawn_mock_api();
+   let (root, repo, output) = run_lgit(&base_url, "", &["--push"]);
+
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+   let remote = root.join("remote.git");
+   assert_eq!(git(&remote, &["rev-parse", "release-1.4"]), git(&repo, &["rev-parse", "HEAD"]));
+   assert_eq!(git(&remote, &["tag"]), "v1.4.0");
+   std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn test_no_tag_skips_release_tag() {
+   let (base_url, _) = spawn_mock_api();
+   let (root, repo, output) = run_lgit(&base_url, "", &["--no-tag"]);
+
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+   assert_eq!(git(&repo, &["log", "-1", "--format=%s"]), "chore(release): prepared release v1.4.0");
+   assert_eq!(git(&repo, &["tag"]), "");
+   std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn test_release_commit_is_not_retyped() {
+   let (base_url, _) = spawn_mock_api();
+   let tooling = "tooling_dominance_percent = 1\n[tooling_types]\nconfig = \"build\"\n";
+   let (root, repo, output) = run_lgit(&base_url, tooling, &["--no-tag"]);
+
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+   assert_eq!(git(&repo, &["log", "-1", "--format=%s"]), "chore(release): prepared release v1.4.0");
+   std::fs::remove_dir_all(&root).ok();
+}
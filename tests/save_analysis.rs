@@ -0,0 +1,65 @@
+//! End-to-end check that `--save-analysis` writes the analysis the message
+//! was built from.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint, so no network access or API key is needed.
+
+mod common;
+
+use common::{git, init_repo, lgit, scratch_dir, tool_call_response, write_config};
+
+const ANALYSIS_ARGS: &str = r##"{"type":"feat","scope":"greeting","details":[{"text":"Added a greeting helper to the library.","changelog_category":"Added","user_visible":true}],"issue_refs":["#12"]}"##;
+const SUMMARY_ARGS: &str = r#"{"summary":"added greeting helper"}"#;
+
+fn spawn_mock_api() -> String {
+   common::spawn_mock_api(|request| {
+      Some(if request.body.contains("create_commit_summary") {
+         tool_call_response("create_commit_summary", SUMMARY_ARGS)
+      } else {
+         tool_call_response("create_conventional_analysis", ANALYSIS_ARGS)
+      })
+   })
+}
+
+#[test]
+fn test_save_analysis_writes_analysis_json() {
+   let api_base_url = spawn_mock_api();
+   let root = scratch_dir("save-analysis");
+   let repo = root.join("repo");
+   init_repo(&repo);
+   std::fs::write(repo.join("lib.rs"), "pub fn one() -> u32 {\n   1\n}\n").unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(
+      repo.join("lib.rs"),
+      "pub fn one() -> u32 {\n   1\n}\n\npub fn greet() -> &'static str {\n   \"hello\"\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let config_path = write_config(&root, &api_base_url, "fast_mode_threshold_chars = 0\n");
+   // Parent directories are created as needed
+   let analysis_path = root.join("audit").join("analysis.json");
+
+   let output = lgit(&root)
+      .arg("--dry-run")
+      .arg("--dir")
+      .arg(&repo)
+      .arg("--config")
+      .arg(&config_path)
+      .arg("--save-analysis")
+      .arg(&analysis_path)
+      .output()
+      .unwrap();
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+
+   let saved: serde_json::Value =
+      serde_json::from_str(&std::fs::read_to_string(&analysis_path).unwrap()).unwrap();
+   std::fs::remove_dir_all(&root).ok();
+
+   assert_eq!(saved["type"], "feat");
+   assert_eq!(saved["scope"], "greeting");
+   assert_eq!(saved["details"][0]["text"], "Added a greeting helper to the library.");
+   assert_eq!(saved["details"][0]["user_visible"], true);
+   assert_eq!(saved["issue_refs"], serde_json::json!(["#12"]));
+}
@@ -0,0 +1,97 @@
+//! `split_type_and_body`: a stat-only classification call decides type and
+//! scope, the diff analysis supplies the body, and the two merge.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint that records each request.
+
+mod common;
+
+use common::{
+   Requests, git, init_repo, lgit, scratch_dir, tool_call_response, tools, write_config,
+};
+
+const CLASSIFY_ARGS: &str = r#"{"type":"fix","scope":"greeting"}"#;
+const ANALYSIS_ARGS: &str = r#"{"type":"feat","details":[{"text":"Returned a friendlier greeting from the helper.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"corrected greeting text"}"#;
+
+fn spawn_mock_api() -> (String, Requests) {
+   common::spawn_recording_mock_api(|request, _| {
+      let tool = request.tool();
+      let arguments = match tool.as_str() {
+         "create_type_classification" => CLASSIFY_ARGS,
+         "create_commit_summary" => SUMMARY_ARGS,
+         _ => ANALYSIS_ARGS,
+      };
+      Some(tool_call_response(&tool, arguments))
+   })
+}
+
+#[test]
+fn test_split_mode_merges_classification_and_body() {
+   let root = scratch_dir("split");
+   let repo = root.join("repo");
+   std::fs::create_dir_all(repo.join("greeting")).unwrap();
+
+   init_repo(&repo);
+   std::fs::write(repo.join("greeting/lib.rs"), "pub fn greet() -> &'static str {\n   \"hi\"\n}\n")
+      .unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(
+      repo.join("greeting/lib.rs"),
+      "pub fn greet() -> &'static str {\n   \"hello there\"\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let (url, requests) = spawn_mock_api();
+   let config_path = write_config(
+      &root,
+      &url,
+      "fast_mode_threshold_chars = 0\nsplit_type_and_body = true\nclassify_model = \
+       \"cheap-model\"\n",
+   );
+
+   let output = lgit(&root)
+      .args(["--dry-run", "--quiet", "--dir"])
+      .arg(&repo)
+      .arg("--config")
+      .arg(&config_path)
+      .output()
+      .unwrap();
+   let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+   assert!(
+      output.status.success(),
+      "lgit failed: {}\nstdout: {stdout}",
+      String::from_utf8_lossy(&output.stderr)
+   );
+   std::fs::remove_dir_all(&root).ok();
+
+   // Type and scope from the classification, body from the diff analysis
+   assert!(stdout.starts_with("fix(greeting): corrected greeting text\n"), "{stdout}");
+   assert!(stdout.contains("- Returned a friendlier greeting from the helper."), "{stdout}");
+
+   let mut tools = tools(&requests);
+   tools.sort_unstable();
+   assert_eq!(tools, vec![
+      "create_commit_summary",
+      "create_conventional_analysis",
+      "create_type_classification"
+   ]);
+
+   // The classification runs on its own model and never sees the diff
+   let requests = requests.lock();
+   let classify_body = &requests
+      .iter()
+      .find(|request| request.tool() == "create_type_classification")
+      .unwrap()
+      .body;
+   assert!(classify_body.contains("\"model\":\"cheap-model\""), "{classify_body}");
+   assert!(!classify_body.contains("hello there"), "{classify_body}");
+   let analysis_body = &requests
+      .iter()
+      .find(|request| request.tool() == "create_conventional_analysis")
+      .unwrap()
+      .body;
+   assert!(analysis_body.contains("hello there"));
+}
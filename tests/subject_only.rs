@@ -0,0 +1,82 @@
+//! End-to-end check that `--subject-only` prints exactly the subject line.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint, so no network access or API key is needed.
+
+mod common;
+
+use std::{path::PathBuf, process::Output};
+
+use common::{git, init_repo, lgit, scratch_dir, tool_call_response, write_config};
+
+const ANALYSIS_ARGS: &str = r#"{"type":"feat","details":[{"text":"Added a greeting helper to the library.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"added greeting helper"}"#;
+
+fn spawn_mock_api() -> String {
+   common::spawn_mock_api(|request| {
+      Some(if request.body.contains("create_commit_summary") {
+         tool_call_response("create_commit_summary", SUMMARY_ARGS)
+      } else {
+         tool_call_response("create_conventional_analysis", ANALYSIS_ARGS)
+      })
+   })
+}
+
+/// Run `lgit --subject-only` on a fresh repository with one staged change;
+/// returns the scratch root, the repository and the output
+fn run_subject_only(api_base_url: &str, extra_args: &[&str]) -> (PathBuf, PathBuf, Output) {
+   let root = scratch_dir("subject-only");
+   let repo = root.join("repo");
+   init_repo(&repo);
+   std::fs::write(repo.join("lib.rs"), "pub fn one() -> u32 {\n   1\n}\n").unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(
+      repo.join("lib.rs"),
+      "pub fn one() -> u32 {\n   1\n}\n\npub fn greet() -> &'static str {\n   \"hello\"\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let config_path = write_config(&root, api_base_url, "");
+
+   let output = lgit(&root)
+      .arg("--subject-only")
+      .arg("--dir")
+      .arg(&repo)
+      .arg("--config")
+      .arg(&config_path)
+      .args(extra_args)
+      .output()
+      .unwrap();
+   (root, repo, output)
+}
+
+#[test]
+fn test_subject_only_prints_single_line() {
+   let (root, repo, output) = run_subject_only(&spawn_mock_api(), &[]);
+
+   let stdout = String::from_utf8_lossy(&output.stdout);
+   assert!(
+      output.status.success(),
+      "lgit failed: {}\nstdout: {stdout}",
+      String::from_utf8_lossy(&output.stderr)
+   );
+   assert_eq!(stdout, "feat: added greeting helper\n");
+
+   // Never commits
+   assert_eq!(git(&repo, &["rev-list", "--count", "HEAD"]), "1");
+
+   std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn test_base_url_flag_overrides_config() {
+   // The config points at a closed port; only the flag reaches the mock
+   let base_url = format!("{}/", spawn_mock_api());
+   let (root, _, output) = run_subject_only("http://127.0.0.1:9", &["--base-url", &base_url]);
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+   assert_eq!(String::from_utf8_lossy(&output.stdout), "feat: added greeting helper\n");
+}
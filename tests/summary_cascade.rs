@@ -0,0 +1,134 @@
+//! End-to-end checks that `summary_model_cascade` escalates to the next model
+//! when a cheaper one keeps failing the summary quality check.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint, so no network access or API key is needed.
+
+mod common;
+
+use std::{path::PathBuf, process::Output};
+
+use common::{Requests, git, init_repo, lgit, scratch_dir, tool_call_response, write_config};
+
+const ANALYSIS_ARGS: &str = r#"{"type":"feat","details":[{"text":"Added a greeting helper to the library.","user_visible":false}],"issue_refs":[]}"#;
+
+/// What the mock's `tiny-model` answers summary requests with
+#[derive(Clone, Copy)]
+enum TinyModel {
+   /// Present tense, rejected while past tense is required
+   PresentTense,
+   /// No tool call arguments at all
+   NoToolCalls,
+}
+
+fn spawn_mock_api(tiny: TinyModel) -> (String, Requests) {
+   common::spawn_recording_mock_api(move |request, _| {
+      Some(if request.tool() == "create_commit_summary" {
+         match (request.model().as_str(), tiny) {
+            ("tiny-model", TinyModel::PresentTense) => {
+               tool_call_response("create_commit_summary", r#"{"summary":"add greeting helper"}"#)
+            },
+            ("tiny-model", TinyModel::NoToolCalls) => {
+               tool_call_response("create_commit_summary", "")
+            },
+            _ => {
+               tool_call_response("create_commit_summary", r#"{"summary":"added greeting helper"}"#)
+            },
+         }
+      } else {
+         tool_call_response("create_conventional_analysis", ANALYSIS_ARGS)
+      })
+   })
+}
+
+/// Models of the summary requests served, oldest first
+fn summary_models(requests: &Requests) -> Vec<String> {
+   requests
+      .lock()
+      .iter()
+      .filter(|request| request.tool() == "create_commit_summary")
+      .map(common::Request::model)
+      .collect()
+}
+
+/// `tiny-model` first, escalating to `big-model`
+const CASCADE: &str = "summary_model_cascade = [\"tiny-model\", \"big-model\"]\n";
+
+/// Run `lgit` with `extra_config` appended to the config on a fresh repository
+/// with one staged change; returns the scratch root and the output
+fn run_lgit(api_base_url: &str, extra_config: &str, extra_args: &[&str]) -> (PathBuf, Output) {
+   let root = scratch_dir("summary-cascade");
+   let repo = root.join("repo");
+   init_repo(&repo);
+   std::fs::write(repo.join("lib.rs"), "pub fn one() -> u32 {\n   1\n}\n").unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(
+      repo.join("lib.rs"),
+      "pub fn one() -> u32 {\n   1\n}\n\npub fn greet() -> &'static str {\n   \"hello\"\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let config_path =
+      write_config(&root, api_base_url, &format!("fast_mode_threshold_chars = 0\n{extra_config}"));
+
+   let output = lgit(&root)
+      .args(extra_args)
+      .arg("--dir")
+      .arg(&repo)
+      .arg("--config")
+      .arg(&config_path)
+      .output()
+      .unwrap();
+   (root, output)
+}
+
+#[test]
+fn test_cascade_escalates_after_rejected_summaries() {
+   let (base_url, requests) = spawn_mock_api(TinyModel::PresentTense);
+   let (root, output) = run_lgit(&base_url, CASCADE, &["--dry-run"]);
+   std::fs::remove_dir_all(&root).ok();
+
+   let stdout = String::from_utf8_lossy(&output.stdout);
+   let stderr = String::from_utf8_lossy(&output.stderr);
+   assert!(output.status.success(), "lgit failed: {stderr}\nstdout: {stdout}");
+   assert!(stdout.contains("feat: added greeting helper"), "{stdout}");
+   assert!(stderr.contains("escalating to big-model"), "{stderr}");
+   assert!(stderr.contains("summary model: big-model"), "{stderr}");
+   assert!(!stderr.contains("Using fallback"), "{stderr}");
+   assert_eq!(summary_models(&requests), ["tiny-model", "tiny-model", "big-model"]);
+}
+
+#[test]
+fn test_cascade_escalates_when_model_cannot_call_tools() {
+   let (base_url, requests) = spawn_mock_api(TinyModel::NoToolCalls);
+   let (root, output) = run_lgit(&base_url, CASCADE, &["--dry-run"]);
+   std::fs::remove_dir_all(&root).ok();
+
+   let stdout = String::from_utf8_lossy(&output.stdout);
+   let stderr = String::from_utf8_lossy(&output.stderr);
+   assert!(output.status.success(), "lgit failed: {stderr}\nstdout: {stdout}");
+   assert!(stdout.contains("feat: added greeting helper"), "{stdout}");
+   assert!(stderr.contains("may not support function calling"), "{stderr}");
+   // Not retried on the same model
+   assert_eq!(summary_models(&requests), ["tiny-model", "big-model"]);
+}
+
+#[test]
+fn test_fallback_summary_lowers_commit_confidence() {
+   // Only the tiny model, whose summaries are always rejected
+   let (base_url, _) = spawn_mock_api(TinyModel::PresentTense);
+   let extra_config =
+      "summary_model_cascade = [\"tiny-model\"]\nauto_commit_min_confidence = 0.9\n";
+   let (root, output) = run_lgit(&base_url, extra_config, &[]);
+   let commits = git(&root.join("repo"), &["rev-list", "--count", "HEAD"]);
+   std::fs::remove_dir_all(&root).ok();
+
+   let stderr = String::from_utf8_lossy(&output.stderr);
+   assert!(!output.status.success(), "{stderr}");
+   assert!(stderr.contains("Using fallback"), "{stderr}");
+   assert!(stderr.contains("Confidence 0.50"), "{stderr}");
+   assert!(stderr.contains("fallback summary used"), "{stderr}");
+   assert_eq!(commits, "1", "nothing was committed");
+}
@@ -0,0 +1,46 @@
+//! End-to-end check that `--template-debug` prints the rendered prompts with
+//! the real diff and exits without calling the model.
+//!
+//! Runs the real binary against a throwaway repository with `offline_strict`
+//! set, so any API call would fail the run.
+
+mod common;
+
+use common::{git, init_repo, lgit, scratch_dir};
+
+#[test]
+fn test_template_debug_prints_prompts_with_diff() {
+   let root = scratch_dir("template-debug");
+   let repo = root.join("repo");
+   init_repo(&repo);
+   std::fs::write(repo.join("lib.rs"), "pub fn answer() -> u32 { 41 }\n").unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(repo.join("lib.rs"), "pub fn answer() -> u32 { 42 }\n").unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let config_path = root.join("config.toml");
+   std::fs::write(&config_path, "offline_strict = true\nchangelog_enabled = false\n").unwrap();
+
+   let output = lgit(&root)
+      .arg("--template-debug")
+      .arg("--dir")
+      .arg(&repo)
+      .arg("--config")
+      .arg(&config_path)
+      .args(["Answer", "off", "by", "one"])
+      .output()
+      .unwrap();
+   let head = git(&repo, &["rev-list", "--count", "HEAD"]);
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+   let stdout = String::from_utf8_lossy(&output.stdout);
+   assert!(stdout.contains("===== analysis prompt: user ====="), "{stdout}");
+   assert!(stdout.contains("===== summary prompt: user ====="), "{stdout}");
+   assert!(stdout.contains("-pub fn answer() -> u32 { 41 }"), "{stdout}");
+   assert!(stdout.contains("+pub fn answer() -> u32 { 42 }"), "{stdout}");
+   assert!(stdout.contains("lib.rs | 2"), "{stdout}");
+   assert!(stdout.contains("Answer off by one"), "{stdout}");
+   assert_eq!(head, "1", "nothing was committed");
+}
@@ -0,0 +1,129 @@
+//! Vacuous-message gate: a body-less "updated files" summary for a sizeable
+//! diff is regenerated once through map-reduce, then rejected unless
+//! `--allow-generic` is passed.
+//!
+//! Runs the real binary against a throwaway repository and a mock
+//! OpenAI-compatible endpoint that answers vacuously as scripted.
+
+mod common;
+
+use std::{
+   path::{Path, PathBuf},
+   process::Output,
+};
+
+use common::{
+   Requests, git, init_repo, lgit, scratch_dir, tool_call_response, tools, write_config,
+};
+
+const VACUOUS_ANALYSIS: &str = r#"{"type":"chore","details":[],"issue_refs":[]}"#;
+const VACUOUS_SUMMARY: &str = r#"{"summary":"updated files"}"#;
+const ANALYSIS_ARGS: &str = r#"{"type":"fix","scope":"billing","details":[{"text":"Rounded refunds to whole cents.","user_visible":false}],"issue_refs":[]}"#;
+const SUMMARY_ARGS: &str = r#"{"summary":"rounded refunds to whole cents"}"#;
+const OBSERVATION_ARGS: &str = r#"{"observations":["Rounded refund amounts to whole cents."]}"#;
+
+/// Answer vacuously on the first analysis and summary, or on every one with
+/// `always_vacuous`
+fn spawn_mock_api(always_vacuous: bool) -> (String, Requests) {
+   common::spawn_recording_mock_api(move |request, served| {
+      let tool = request.tool();
+      let repeat = served.iter().any(|seen| seen.tool() == tool);
+      let arguments = match tool.as_str() {
+         "create_file_observation" => OBSERVATION_ARGS,
+         "create_commit_summary" if always_vacuous || !repeat => VACUOUS_SUMMARY,
+         "create_commit_summary" => SUMMARY_ARGS,
+         _ if always_vacuous || !repeat => VACUOUS_ANALYSIS,
+         _ => ANALYSIS_ARGS,
+      };
+      Some(tool_call_response(&tool, arguments))
+   })
+}
+
+/// Repository with one staged change, plus a config pointing at `url`
+fn setup(name: &str, url: &str) -> (PathBuf, PathBuf) {
+   let root = scratch_dir(&format!("vacuous-{name}"));
+   let repo = root.join("repo");
+   std::fs::create_dir_all(repo.join("billing")).unwrap();
+
+   init_repo(&repo);
+   std::fs::write(repo.join("billing/refund.rs"), "pub fn refund(x: f64) -> f64 {\n   x\n}\n")
+      .unwrap();
+   git(&repo, &["add", "-A"]);
+   git(&repo, &["commit", "-q", "-m", "chore: initial commit"]);
+   std::fs::write(
+      repo.join("billing/refund.rs"),
+      "pub fn refund(x: f64) -> f64 {\n   (x * 100.0).round() / 100.0\n}\n",
+   )
+   .unwrap();
+   git(&repo, &["add", "-A"]);
+
+   let config_path =
+      write_config(&root, url, "fast_mode_threshold_chars = 0\nvacuous_min_diff_chars = 10\n");
+   (root, config_path)
+}
+
+fn run_lgit(root: &Path, config_path: &Path, args: &[&str]) -> Output {
+   lgit(root)
+      .args(["--dry-run", "--quiet", "--dir"])
+      .arg(root.join("repo"))
+      .arg("--config")
+      .arg(config_path)
+      .args(args)
+      .output()
+      .unwrap()
+}
+
+#[test]
+fn test_vacuous_message_escalates_to_map_reduce() {
+   let (url, requests) = spawn_mock_api(false);
+   let (root, config_path) = setup("escalate", &url);
+
+   let output = run_lgit(&root, &config_path, &[]);
+   let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+   let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+   std::fs::remove_dir_all(&root).ok();
+   assert!(output.status.success(), "lgit failed: {stderr}\nstdout: {stdout}");
+
+   assert!(stdout.starts_with("fix(billing): rounded refunds to whole cents\n"), "{stdout}");
+   assert!(stderr.contains("\"updated files\" is too generic"), "{stderr}");
+   assert_eq!(tools(&requests), [
+      "create_conventional_analysis",
+      "create_commit_summary",
+      "create_file_observation",
+      "create_conventional_analysis",
+      "create_commit_summary",
+   ]);
+   let requests = requests.lock();
+   assert!(requests[3].body.contains("Be specific"));
+   assert!(requests[4].body.contains("Be specific"));
+   assert!(!requests[1].body.contains("Be specific"));
+}
+
+#[test]
+fn test_still_vacuous_message_is_rejected() {
+   let (url, requests) = spawn_mock_api(true);
+   let (root, config_path) = setup("reject", &url);
+
+   let output = run_lgit(&root, &config_path, &[]);
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(!output.status.success());
+   let stderr = String::from_utf8_lossy(&output.stderr);
+   assert!(stderr.contains("VacuousMessage"), "{stderr}");
+   assert!(stderr.contains("chore: updated files"), "{stderr}");
+   assert_eq!(tools(&requests).len(), 5);
+}
+
+#[test]
+fn test_allow_generic_accepts_vacuous_message() {
+   let (url, requests) = spawn_mock_api(true);
+   let (root, config_path) = setup("allow", &url);
+
+   let output = run_lgit(&root, &config_path, &["--allow-generic"]);
+   let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+   std::fs::remove_dir_all(&root).ok();
+
+   assert!(output.status.success(), "lgit failed: {}", String::from_utf8_lossy(&output.stderr));
+   assert_eq!(stdout.lines().next(), Some("chore: updated files"));
+   assert_eq!(tools(&requests), ["create_conventional_analysis", "create_commit_summary"]);
+}